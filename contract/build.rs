@@ -0,0 +1,207 @@
+//! Embeds build info into the contract wasm, which is exposed via the `version` view:
+//! - `GIT_COMMIT` - the git commit the contract was built from
+//! - `STATE_SCHEMA_HASH` - hash of the source files that define the persisted contract state, i.e.,
+//!   the [Contract](src/lib.rs) struct, the domain model, and the [Config](src/config.rs)
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+fn main() {
+    println!("cargo:rustc-env=GIT_COMMIT={}", git_commit());
+    println!("cargo:rustc-env=STATE_SCHEMA_HASH={}", state_schema_hash());
+}
+
+fn git_commit() -> String {
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/refs/heads");
+    // allows reproducible builds from source archives that are not git repos
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+    if let Ok(commit) = env::var("GIT_COMMIT") {
+        return commit;
+    }
+    Command::new("git")
+        .args(&["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// 64-bit FNV-1a hash over the state schema source files, which are visited in sorted path order
+/// - FNV-1a is used because it is stable across Rust releases, unlike the std hashers
+/// - only the code tokens are hashed, i.e., comments, whitespace, and `#[cfg(test)]` items are
+///   stripped, which means doc and formatting changes do not change the hash
+fn state_schema_hash() -> String {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut files = vec![
+        PathBuf::from("src/config.rs"),
+        PathBuf::from("src/domain.rs"),
+        PathBuf::from("src/lib.rs"),
+    ];
+    collect_rs_files(Path::new("src/domain"), &mut files);
+    files.sort();
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for file in files {
+        println!("cargo:rerun-if-changed={}", file.display());
+        let content = fs::read_to_string(&file).expect("failed to read state schema source file");
+        let tokens = strip_test_items(tokenize(&content)).join(" ");
+        for byte in file.to_string_lossy().bytes().chain(tokens.bytes()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+/// splits the source into identifier, literal, and punctuation tokens - comments and whitespace
+/// are dropped
+/// - string and char literals are kept as single tokens, which means comment markers and braces
+///   within them are not mistaken for code
+fn tokenize(src: &str) -> Vec<String> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        } else if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        } else if c == '/' && next == Some('*') {
+            // block comments nest
+            let mut depth = 0;
+            while i < chars.len() {
+                if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                    depth += 1;
+                    i += 2;
+                } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+            continue;
+        } else if let Some(end) = raw_string_end(&chars, i) {
+            i = end;
+        } else if c == '"' {
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+            i += 1;
+        } else if c == '\'' && next == Some('\\') {
+            // escaped char literal, e.g., '\'' or '\u{7f}'
+            i += 3;
+            while i < chars.len() && chars[i] != '\'' {
+                i += 1;
+            }
+            i += 1;
+        } else if c == '\'' && chars.get(i + 2) == Some(&'\'') {
+            i += 3;
+        } else if c.is_alphanumeric() || c == '_' || c == '\'' {
+            // identifiers, numbers, and lifetimes
+            i += 1;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+        } else {
+            i += 1;
+        }
+        tokens.push(chars[start..i.min(chars.len())].iter().collect());
+    }
+    tokens
+}
+
+/// Returns the index after the raw string literal that starts at `start`, e.g., `r#"{}"#`
+fn raw_string_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start;
+    if chars.get(i) == Some(&'b') {
+        i += 1;
+    }
+    if chars.get(i) != Some(&'r') {
+        return None;
+    }
+    i += 1;
+    let hashes = chars[i..].iter().take_while(|c| **c == '#').count();
+    i += hashes;
+    if chars.get(i) != Some(&'"') {
+        return None;
+    }
+    i += 1;
+    while i < chars.len() {
+        if chars[i] == '"' && chars[i + 1..].iter().take_while(|c| **c == '#').count() >= hashes {
+            return Some(i + 1 + hashes);
+        }
+        i += 1;
+    }
+    Some(chars.len())
+}
+
+/// drops each item that is annotated with `#[cfg(test)]`, i.e., the attribute up to the end of
+/// the item's block, or up to the `;` for items without a block
+fn strip_test_items(tokens: Vec<String>) -> Vec<String> {
+    const CFG_TEST: [&str; 7] = ["#", "[", "cfg", "(", "test", ")", "]"];
+
+    let mut stripped = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let is_cfg_test = tokens.len() - i >= CFG_TEST.len()
+            && tokens[i..i + CFG_TEST.len()]
+                .iter()
+                .zip(CFG_TEST.iter())
+                .all(|(token, expected)| token == expected);
+        if !is_cfg_test {
+            stripped.push(tokens[i].clone());
+            i += 1;
+            continue;
+        }
+        i += CFG_TEST.len();
+        let mut depth = 0;
+        while i < tokens.len() {
+            let token = tokens[i].as_str();
+            i += 1;
+            match token {
+                "{" => depth += 1,
+                "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                ";" if depth == 0 => break,
+                _ => {}
+            }
+        }
+    }
+    stripped
+}
+
+fn collect_rs_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    println!("cargo:rerun-if-changed={}", dir.display());
+    for entry in fs::read_dir(dir).expect("failed to read source dir") {
+        let path = entry.expect("failed to read source dir entry").path();
+        if path.is_dir() {
+            collect_rs_files(&path, files);
+        } else if path.extension().map_or(false, |ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+}
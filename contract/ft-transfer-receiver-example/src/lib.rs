@@ -0,0 +1,223 @@
+//! Reference `ft_on_transfer` receiver for STAKE integrators - see the README.
+
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    collections::LookupMap,
+    env,
+    json_types::{ValidAccountId, U128},
+    log, near_bindgen,
+    serde::{Deserialize, Serialize},
+    serde_json::{self, json},
+    wee_alloc, AccountId, PanicOnDefault, Promise, PromiseOrValue, PromiseResult,
+};
+
+#[global_allocator]
+static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
+
+const TGAS: u64 = 1_000_000_000_000;
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct StakeVault {
+    /// the only token contract whose transfers are accepted
+    token_contract: AccountId,
+    /// max amount of STAKE the vault holds
+    capacity: u128,
+    total_deposits: u128,
+    deposits: LookupMap<AccountId, u128>,
+}
+
+/// `ft_on_transfer` message
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Message {
+    /// deposits the transferred STAKE up to the remaining vault capacity
+    Deposit,
+    /// simulates a misbehaving receiver that returns more than the transferred amount as unused
+    OverReturn { extra: U128 },
+    /// simulates a receiver that fails
+    Panic,
+}
+
+#[near_bindgen]
+impl StakeVault {
+    #[init]
+    pub fn new(token_contract: ValidAccountId, capacity: U128) -> Self {
+        assert!(!env::state_exists(), "contract is already initialized");
+        Self {
+            token_contract: token_contract.into(),
+            capacity: capacity.0,
+            total_deposits: 0,
+            deposits: LookupMap::new(b"d".to_vec()),
+        }
+    }
+
+    /// The vault must be registered with the token contract in order to receive STAKE. The
+    /// attached deposit pays for the vault's account storage on the token contract.
+    #[payable]
+    pub fn register_with_token_contract(&mut self) -> Promise {
+        Promise::new(self.token_contract.clone()).function_call(
+            b"register_account".to_vec(),
+            vec![],
+            env::attached_deposit(),
+            5 * TGAS,
+        )
+    }
+
+    /// Returns the amount of STAKE that was not used, which the token contract refunds to the
+    /// sender. Returning the full amount is the non-panicking way to reject a transfer.
+    pub fn ft_on_transfer(
+        &mut self,
+        sender_id: ValidAccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        if env::predecessor_account_id() != self.token_contract {
+            log!("transfers are only accepted from {}", self.token_contract);
+            return PromiseOrValue::Value(amount);
+        }
+        let msg: Message = serde_json::from_str(&msg).expect("invalid msg");
+        match msg {
+            Message::Deposit => {
+                let used = amount.0.min(self.capacity - self.total_deposits);
+                if used > 0 {
+                    let deposit = self.deposit_of(sender_id.clone()).0 + used;
+                    self.deposits.insert(sender_id.as_ref(), &deposit);
+                    self.total_deposits += used;
+                }
+                PromiseOrValue::Value((amount.0 - used).into())
+            }
+            Message::OverReturn { extra } => PromiseOrValue::Value((amount.0 + extra.0).into()),
+            Message::Panic => panic!("BOOM!"),
+        }
+    }
+
+    /// transfers the STAKE back to the depositor - the deposit is debited up front and restored if
+    /// the transfer fails
+    ///
+    /// #\[payable\]
+    /// exactly 1 yoctoNEAR must be attached, which is forwarded to `ft_transfer`
+    #[payable]
+    pub fn withdraw(&mut self, amount: U128) -> Promise {
+        assert_eq!(
+            env::attached_deposit(),
+            1,
+            "exactly 1 yoctoNEAR must be attached"
+        );
+        let account_id = env::predecessor_account_id();
+        let deposit = self.deposits.get(&account_id).unwrap_or(0);
+        assert!(amount.0 <= deposit, "deposit is insufficient");
+        self.deposits.insert(&account_id, &(deposit - amount.0));
+        self.total_deposits -= amount.0;
+
+        Promise::new(self.token_contract.clone())
+            .function_call(
+                b"ft_transfer".to_vec(),
+                json!({ "receiver_id": account_id, "amount": amount })
+                    .to_string()
+                    .into_bytes(),
+                1,
+                10 * TGAS,
+            )
+            .then(
+                Promise::new(env::current_account_id()).function_call(
+                    b"on_withdraw".to_vec(),
+                    json!({ "account_id": account_id, "amount": amount })
+                        .to_string()
+                        .into_bytes(),
+                    0,
+                    5 * TGAS,
+                ),
+            )
+    }
+
+    #[private]
+    pub fn on_withdraw(&mut self, account_id: AccountId, amount: U128) {
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            return;
+        }
+        log!("withdrawal failed - deposit is restored");
+        let deposit = self.deposits.get(&account_id).unwrap_or(0) + amount.0;
+        self.deposits.insert(&account_id, &deposit);
+        self.total_deposits += amount.0;
+    }
+
+    pub fn deposit_of(&self, account_id: ValidAccountId) -> U128 {
+        self.deposits.get(account_id.as_ref()).unwrap_or(0).into()
+    }
+
+    pub fn total_deposits(&self) -> U128 {
+        self.total_deposits.into()
+    }
+
+    pub fn capacity(&self) -> U128 {
+        self.capacity.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::{test_utils::VMContextBuilder, testing_env, MockedBlockchain};
+    use std::convert::TryFrom;
+
+    const TOKEN_CONTRACT: &str = "stake.near";
+
+    fn account_id(account_id: &str) -> ValidAccountId {
+        ValidAccountId::try_from(account_id).unwrap()
+    }
+
+    fn setup(predecessor_account_id: &str) -> StakeVault {
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(account_id(predecessor_account_id))
+            .build();
+        testing_env!(context);
+        StakeVault::new(account_id(TOKEN_CONTRACT), 100.into())
+    }
+
+    fn unused(result: PromiseOrValue<U128>) -> u128 {
+        match result {
+            PromiseOrValue::Value(amount) => amount.0,
+            PromiseOrValue::Promise(_) => panic!("expected value"),
+        }
+    }
+
+    #[test]
+    fn deposit_up_to_capacity() {
+        let mut vault = setup(TOKEN_CONTRACT);
+        let msg = serde_json::to_string(&Message::Deposit).unwrap();
+        assert_eq!(
+            unused(vault.ft_on_transfer(account_id("alice.near"), 60.into(), msg.clone())),
+            0
+        );
+        // partial use
+        assert_eq!(
+            unused(vault.ft_on_transfer(account_id("bob.near"), 60.into(), msg)),
+            20
+        );
+        assert_eq!(vault.deposit_of(account_id("alice.near")), 60.into());
+        assert_eq!(vault.deposit_of(account_id("bob.near")), 40.into());
+        assert_eq!(vault.total_deposits(), 100.into());
+    }
+
+    #[test]
+    fn reject_other_token_contracts() {
+        let mut vault = setup("other-token.near");
+        let msg = serde_json::to_string(&Message::Deposit).unwrap();
+        assert_eq!(
+            unused(vault.ft_on_transfer(account_id("alice.near"), 60.into(), msg)),
+            60
+        );
+        assert_eq!(vault.total_deposits(), 0.into());
+    }
+
+    #[test]
+    fn over_return() {
+        let mut vault = setup(TOKEN_CONTRACT);
+        let msg = serde_json::to_string(&Message::OverReturn { extra: 10.into() }).unwrap();
+        assert_eq!(
+            unused(vault.ft_on_transfer(account_id("alice.near"), 60.into(), msg)),
+            70
+        );
+    }
+}
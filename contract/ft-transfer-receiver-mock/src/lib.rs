@@ -77,7 +77,7 @@ impl TransferReceiverMock {
     pub fn unregister_account(&self, contract_id: ValidAccountId) -> Promise {
         Promise::new(contract_id.as_ref().to_string()).function_call(
             b"unregister_account".to_vec(),
-            vec![],
+            b"{}".to_vec(),
             YOCTO,
             10 * TGAS,
         )
@@ -0,0 +1,1025 @@
+//! Typed helpers that construct the JSON args for the contract's public methods.
+//!
+//! The helpers mirror the [interface](crate::interface) method signatures, which ensures that the
+//! args are serialized exactly as the contract expects them. They are meant to be used by integration
+//! tests and off-chain Rust clients instead of hand writing JSON strings.
+//!
+//! Methods that take no args are invoked using [no_args].
+//!
+//! ```ignore
+//! use oysterpack_near_stake_token::client;
+//!
+//! let args = client::staking_service::redeem(YOCTO.into());
+//! let args = client::fungible_token::TransferCallArgs::new(receiver_id, amount, "pay".into())
+//!     .memo("invoice #1")
+//!     .to_json();
+//! ```
+
+use near_sdk::serde_json::{json, Value};
+
+/// args for methods that take no args
+pub fn no_args() -> Value {
+    json!({})
+}
+
+/// [Contract::new](crate::Contract::new)
+pub fn new(
+    staking_pool_id: near_sdk::json_types::ValidAccountId,
+    owner_id: near_sdk::json_types::ValidAccountId,
+    operator_id: near_sdk::json_types::ValidAccountId,
+    config_preset: Option<crate::interface::ConfigPreset>,
+    config: Option<crate::interface::Config>,
+) -> Value {
+    json!({
+        "staking_pool_id": staking_pool_id,
+        "owner_id": owner_id,
+        "operator_id": operator_id,
+        "config_preset": config_preset,
+        "config": config,
+    })
+}
+
+/// [AccessControl](crate::interface::AccessControl)
+pub mod access_control {
+    use crate::interface::OperatorAction;
+    use near_sdk::{
+        json_types::{ValidAccountId, U64},
+        serde_json::{json, Value},
+    };
+
+    pub fn add_operator(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn remove_operator(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn set_required_confirmations(count: u8) -> Value {
+        json!({ "count": count })
+    }
+
+    pub fn propose_operator_action(action: OperatorAction) -> Value {
+        json!({ "action": action })
+    }
+
+    /// args for `confirm_operator_action`, `execute_operator_action`, `cancel_operator_action`, and
+    /// `operator_proposal`
+    pub fn operator_proposal(proposal_id: U64) -> Value {
+        json!({ "proposal_id": proposal_id })
+    }
+}
+
+/// [AccountActivityLogs](crate::interface::AccountActivityLogs)
+pub mod account_activity_logs {
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn account_activity(account_id: ValidAccountId, from_index: u32, limit: u32) -> Value {
+        json!({ "account_id": account_id, "from_index": from_index, "limit": limit })
+    }
+}
+
+/// [AccountManagement](crate::interface::AccountManagement)
+pub mod account_management {
+    use crate::interface::{AccountIdHash, AccountPreferences, YoctoStake};
+    use near_sdk::{
+        json_types::{ValidAccountId, U64},
+        serde_json::{json, Value},
+    };
+
+    pub fn unregister_account(stake_beneficiary_id: Option<ValidAccountId>) -> Value {
+        json!({ "stake_beneficiary_id": stake_beneficiary_id })
+    }
+
+    pub fn account_registered(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn lookup_account(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn lookup_account_by_hash(account_id_hash: AccountIdHash) -> Value {
+        json!({ "account_id_hash": account_id_hash })
+    }
+
+    pub fn account_hibernated(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn prove_min_balance(account_id: ValidAccountId, min_amount: YoctoStake) -> Value {
+        json!({ "account_id": account_id, "min_amount": min_amount })
+    }
+
+    pub fn account_nonce(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn get_accounts(from_index: U64, limit: U64) -> Value {
+        json!({ "from_index": from_index, "limit": limit })
+    }
+
+    pub fn index_account(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn set_account_preferences(preferences: AccountPreferences) -> Value {
+        json!({ "preferences": preferences })
+    }
+
+    pub fn account_preferences(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+}
+
+/// [BatchHistory](crate::interface::BatchHistory)
+pub mod batch_history {
+    use near_sdk::serde_json::{json, Value};
+
+    pub fn stake_batch_history(from: u64, limit: u32) -> Value {
+        json!({ "from": from, "limit": limit })
+    }
+
+    pub fn redeem_batch_history(from: u64, limit: u32) -> Value {
+        json!({ "from": from, "limit": limit })
+    }
+}
+
+/// [ContractOwner](crate::interface::ContractOwner)
+pub mod batch_reservations {
+    use crate::interface::YoctoNear;
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn reserve_batch_slot(amount: YoctoNear) -> Value {
+        json!({ "amount": amount })
+    }
+
+    pub fn batch_reservation(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+}
+
+/// [BatchTotals](crate::interface::BatchTotals)
+pub mod batch_totals {
+    use crate::interface::BatchId;
+    use near_sdk::serde_json::{json, Value};
+
+    pub fn rebuild_batch_totals(batch_id: BatchId, from: u64, limit: u32, dry_run: bool) -> Value {
+        json!({ "batch_id": batch_id, "from": from, "limit": limit, "dry_run": dry_run })
+    }
+
+    pub fn prune_batch_contributors(batch_id: BatchId, limit: u32) -> Value {
+        json!({ "batch_id": batch_id, "limit": limit })
+    }
+
+    pub fn batch_contributors_len(batch_id: BatchId) -> Value {
+        json!({ "batch_id": batch_id })
+    }
+
+    pub fn process_claims(batch_id: BatchId, from: u64, limit: u32) -> Value {
+        json!({ "batch_id": batch_id, "from": from, "limit": limit })
+    }
+}
+
+pub mod contract_owner {
+    use crate::interface::{YoctoNear, YoctoStake};
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn transfer_ownership(new_owner: ValidAccountId) -> Value {
+        json!({ "new_owner": new_owner })
+    }
+
+    pub fn set_operator_id(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn stake_owner_balance(amount: YoctoNear) -> Value {
+        json!({ "amount": amount })
+    }
+
+    pub fn redeem_owner_stake(amount: YoctoStake) -> Value {
+        json!({ "amount": amount })
+    }
+
+    pub fn withdraw_owner_balance(amount: YoctoNear) -> Value {
+        json!({ "amount": amount })
+    }
+}
+
+/// [ExitPositions](crate::interface::ExitPositions)
+pub mod exit_positions {
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn exit_position(beneficiary: ValidAccountId) -> Value {
+        json!({ "beneficiary": beneficiary })
+    }
+
+    pub fn process_exit(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn exit_position_status(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+}
+
+/// [ContractFinancials](crate::interface::ContractFinancials)
+/// [FeatureFlags](crate::interface::FeatureFlags)
+pub mod feature_flags {
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn set_feature_rollout(name: &str, percentage: u8) -> Value {
+        json!({ "name": name, "percentage": percentage })
+    }
+
+    pub fn allow_feature(name: &str, account_id: ValidAccountId) -> Value {
+        json!({ "name": name, "account_id": account_id })
+    }
+
+    pub fn disallow_feature(name: &str, account_id: ValidAccountId) -> Value {
+        json!({ "name": name, "account_id": account_id })
+    }
+
+    pub fn remove_feature_rollout(name: &str) -> Value {
+        json!({ "name": name })
+    }
+
+    pub fn feature_enabled(name: &str, account_id: ValidAccountId) -> Value {
+        json!({ "name": name, "account_id": account_id })
+    }
+
+    pub fn feature_rollout(name: &str) -> Value {
+        json!({ "name": name })
+    }
+}
+
+pub mod financials {
+    use near_sdk::serde_json::{json, Value};
+
+    pub fn financials_history(from: u64, limit: u32) -> Value {
+        json!({ "from": from, "limit": limit })
+    }
+}
+
+/// [FungibleToken](crate::interface::FungibleToken)
+pub mod fungible_token {
+    use crate::interface::{Gas, Memo, TokenAmount, TransferCallMessage};
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde::Serialize,
+        serde_json::{self, json, Value},
+    };
+
+    pub fn ft_transfer(
+        receiver_id: ValidAccountId,
+        amount: TokenAmount,
+        memo: Option<Memo>,
+    ) -> Value {
+        json!({
+            "receiver_id": receiver_id,
+            "amount": amount,
+            "memo": memo,
+        })
+    }
+
+    pub fn ft_balance_of(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    /// [FungibleToken::ft_transfer_call](crate::interface::FungibleToken::ft_transfer_call) args
+    /// - the `msg` is passed as is to the receiver's `ft_on_transfer` - the STAKE token contract
+    ///   does not interpret it. Receivers that expect a JSON message can construct the message via
+    ///   [json_msg](TransferCallArgs::json_msg)
+    #[derive(Serialize)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct TransferCallArgs {
+        receiver_id: ValidAccountId,
+        amount: TokenAmount,
+        msg: TransferCallMessage,
+        memo: Option<Memo>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        gas_for_receiver: Option<Gas>,
+    }
+
+    impl TransferCallArgs {
+        pub fn new(
+            receiver_id: ValidAccountId,
+            amount: TokenAmount,
+            msg: TransferCallMessage,
+        ) -> Self {
+            Self {
+                receiver_id,
+                amount,
+                msg,
+                memo: None,
+                gas_for_receiver: None,
+            }
+        }
+
+        /// serializes the message as a JSON string
+        pub fn json_msg<T: Serialize>(
+            receiver_id: ValidAccountId,
+            amount: TokenAmount,
+            msg: &T,
+        ) -> Self {
+            let msg = serde_json::to_string(msg).expect("msg failed to serialize to JSON");
+            Self::new(receiver_id, amount, TransferCallMessage(msg))
+        }
+
+        pub fn memo(mut self, memo: &str) -> Self {
+            self.memo = Some(memo.into());
+            self
+        }
+
+        /// gas that is forwarded to the receiver's `ft_on_transfer` - if not specified, then the
+        /// contract configured default applies
+        pub fn gas_for_receiver(mut self, gas: Gas) -> Self {
+            self.gas_for_receiver = Some(gas);
+            self
+        }
+
+        pub fn to_json(&self) -> Value {
+            serde_json::to_value(self).unwrap()
+        }
+    }
+}
+
+/// [Governance](crate::interface::Governance)
+pub mod governance {
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn set_governance_id(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+}
+
+/// [IdleNearSharing](crate::interface::IdleNearSharing)
+pub mod idle_near_sharing {
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn set_idle_near_sharing_opt_out(opt_out: bool) -> Value {
+        json!({ "opt_out": opt_out })
+    }
+
+    pub fn idle_near_sharing_opted_out(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+}
+
+/// [MetaData](crate::interface::MetaData)
+pub mod metadata {
+    use near_sdk::serde_json::{json, Value};
+
+    pub fn metadata(uri: &str) -> Value {
+        json!({ "uri": uri })
+    }
+}
+
+/// [Operator](crate::interface::Operator)
+/// [Migrations](crate::interface::Migrations)
+pub mod migration {
+    use near_sdk::serde_json::{json, Value};
+
+    pub fn migrate_chunk(task_id: &str, limit: u32) -> Value {
+        json!({ "task_id": task_id, "limit": limit })
+    }
+
+    pub fn reset_migration(task_id: &str) -> Value {
+        json!({ "task_id": task_id })
+    }
+
+    pub fn migration_progress(task_id: &str) -> Value {
+        json!({ "task_id": task_id })
+    }
+}
+
+pub mod operator {
+    use crate::domain::Workflow;
+    use crate::interface::{Config, Gas, GasConfig};
+    use near_sdk::{
+        json_types::{Base64VecU8, ValidAccountId},
+        serde_json::{json, Value},
+    };
+
+    pub fn validate_config(config: Config) -> Value {
+        json!({ "config": config })
+    }
+
+    /// only the config fields that are set are updated - the validation hash is returned by
+    /// [validate_config], e.g.,
+    /// ```ignore
+    /// client::operator::update_config(
+    ///     Config {
+    ///         max_withdrawal_per_epoch: Some(YOCTO.into()),
+    ///         ..Config::default()
+    ///     },
+    ///     validation.validation_hash.unwrap(),
+    /// )
+    /// ```
+    pub fn update_config(config: Config, validation_hash: Base64VecU8) -> Value {
+        json!({ "config": config, "validation_hash": validation_hash })
+    }
+
+    pub fn force_update_config(config: Config) -> Value {
+        json!({ "config": config })
+    }
+
+    /// only the gas config fields that are set are updated - the validation hash is returned by
+    /// [validate_config] for a [Config] that only sets the gas config
+    pub fn update_gas_config(gas_config: GasConfig, validation_hash: Base64VecU8) -> Value {
+        json!({ "gas_config": gas_config, "validation_hash": validation_hash })
+    }
+
+    /// the validation hash is returned by [validate_config] for the default config, e.g.,
+    /// `validate_config(config::Config::default().into())`
+    pub fn reset_config_default(validation_hash: Base64VecU8) -> Value {
+        json!({ "validation_hash": validation_hash })
+    }
+
+    pub fn pause(reason: &str) -> Value {
+        json!({ "reason": reason })
+    }
+
+    pub fn lock_history(limit: u32) -> Value {
+        json!({ "limit": limit })
+    }
+
+    pub fn reset_workflow_backoff(workflow: Workflow) -> Value {
+        json!({ "workflow": workflow })
+    }
+
+    /// the data and hash are returned by [export_workflow_state](crate::interface::Operator::export_workflow_state)
+    pub fn import_workflow_state(data: Base64VecU8) -> Value {
+        json!({ "data": data })
+    }
+
+    pub fn release_queued_withdrawal(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn whitelist_auto_registration(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn remove_auto_registration(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn auto_registration_whitelisted(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn whitelist_redeem_forwarding_target(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn remove_redeem_forwarding_target(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn redeem_forwarding_target_whitelisted(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn register_swap_deployment(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn unregister_swap_deployment(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn swap_deployment_registered(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn debug_last_storage_delta(method_name: &str) -> Value {
+        json!({ "method_name": method_name })
+    }
+
+    pub fn record_staking_pool_gas_usage(method_name: &str, gas_burnt: Gas) -> Value {
+        json!({ "method_name": method_name, "gas_burnt": gas_burnt })
+    }
+}
+
+/// [PendingTransfers](crate::interface::PendingTransfers)
+pub mod pending_transfers {
+    use near_sdk::{
+        json_types::U64,
+        serde_json::{json, Value},
+    };
+
+    pub fn ft_transfer_call_status(transfer_id: U64) -> Value {
+        json!({ "transfer_id": transfer_id })
+    }
+
+    pub fn resolve_stalled_transfer(transfer_id: U64, refund: bool) -> Value {
+        json!({ "transfer_id": transfer_id, "refund": refund })
+    }
+}
+
+/// [PoolPreferences](crate::interface::PoolPreferences)
+pub mod pool_preferences {
+    use crate::interface::EpochHeight;
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn signal_pool_preference(pool_id: ValidAccountId) -> Value {
+        json!({ "pool_id": pool_id })
+    }
+
+    pub fn account_pool_preference(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn pool_preference_tallies(epoch_height: Option<EpochHeight>) -> Value {
+        json!({ "epoch_height": epoch_height })
+    }
+}
+
+/// [FeeRebates](crate::interface::FeeRebates)
+pub mod rebates {
+    use crate::interface::RebateTier;
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn set_rebate_tiers(tiers: Vec<RebateTier>) -> Value {
+        json!({ "tiers": tiers })
+    }
+
+    pub fn rebate_round(round_id: u64) -> Value {
+        json!({ "round_id": round_id })
+    }
+
+    pub fn account_rebate(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+}
+
+/// [ReceiverAuthorization](crate::interface::ReceiverAuthorization)
+pub mod receiver_authorization {
+    use near_sdk::{
+        json_types::{Base58PublicKey, ValidAccountId},
+        serde_json::{json, Value},
+    };
+
+    pub fn add_delegate_key(public_key: Base58PublicKey) -> Value {
+        json!({ "public_key": public_key })
+    }
+
+    pub fn remove_delegate_key(public_key: Base58PublicKey) -> Value {
+        json!({ "public_key": public_key })
+    }
+
+    pub fn authorize_receiver(receiver_id: ValidAccountId) -> Value {
+        json!({ "receiver_id": receiver_id })
+    }
+
+    pub fn revoke_receiver(receiver_id: ValidAccountId) -> Value {
+        json!({ "receiver_id": receiver_id })
+    }
+
+    pub fn receiver_authorizations(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+}
+
+/// [RedeemSplits](crate::interface::RedeemSplits)
+pub mod redeem_splits {
+    use crate::interface::YoctoStake;
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn redeem_split(amount: YoctoStake, max_batches: u8) -> Value {
+        json!({ "amount": amount, "max_batches": max_batches })
+    }
+
+    pub fn redeem_split_schedule(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+}
+
+/// [Referrals](crate::interface::Referrals)
+pub mod referrals {
+    use near_sdk::serde_json::{json, Value};
+
+    pub fn register_referral_code(code: String) -> Value {
+        json!({ "code": code })
+    }
+
+    pub fn unregister_referral_code(code: String) -> Value {
+        json!({ "code": code })
+    }
+
+    pub fn referral_code(code: String) -> Value {
+        json!({ "code": code })
+    }
+
+    pub fn referral_codes(from_index: u32, limit: u32) -> Value {
+        json!({ "from_index": from_index, "limit": limit })
+    }
+}
+
+/// [StakingService](crate::interface::StakingService)
+pub mod scheduled_actions {
+    use crate::interface::ScheduledActionId;
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn scheduled_actions(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn cancel_scheduled_action(id: ScheduledActionId) -> Value {
+        json!({ "id": id })
+    }
+}
+
+pub mod staking_service {
+    use crate::interface::{BatchId, YoctoNear, YoctoStake};
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn stake_batch_receipt(batch_id: BatchId) -> Value {
+        json!({ "batch_id": batch_id })
+    }
+
+    pub fn redeem_stake_batch_receipt(batch_id: BatchId) -> Value {
+        json!({ "batch_id": batch_id })
+    }
+
+    pub fn batch_audit(batch_id: BatchId) -> Value {
+        json!({ "batch_id": batch_id })
+    }
+
+    /// the referral code is optional - see [Referrals](crate::interface::Referrals)
+    pub fn deposit(referral_code: Option<String>) -> Value {
+        json!({ "referral_code": referral_code })
+    }
+
+    /// also used for [deposit_and_stake_for](crate::interface::StakingService::deposit_and_stake_for)
+    pub fn deposit_for(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn deposit_for_stake_target(target_stake_amount: YoctoStake) -> Value {
+        json!({ "target_stake_amount": target_stake_amount })
+    }
+
+    pub fn entry_quote(near_amount: YoctoNear) -> Value {
+        json!({ "near_amount": near_amount })
+    }
+
+    pub fn exit_quote(stake_amount: YoctoStake) -> Value {
+        json!({ "stake_amount": stake_amount })
+    }
+
+    pub fn simulate_deposit(amount: YoctoNear) -> Value {
+        json!({ "amount": amount })
+    }
+
+    pub fn simulate_redeem(amount: YoctoStake) -> Value {
+        json!({ "amount": amount })
+    }
+
+    pub fn withdraw_from_stake_batch(amount: YoctoNear) -> Value {
+        json!({ "amount": amount })
+    }
+
+    /// also used for [redeem_for_near_instant](crate::interface::StakingService::redeem_for_near_instant)
+    pub fn redeem(amount: YoctoStake) -> Value {
+        json!({ "amount": amount })
+    }
+
+    pub fn remove_from_redeem_stake_batch(amount: YoctoStake) -> Value {
+        json!({ "amount": amount })
+    }
+
+    /// None removes the limit
+    pub fn set_max_redeem_per_epoch(max_redeem_per_epoch: Option<YoctoStake>) -> Value {
+        json!({ "max_redeem_per_epoch": max_redeem_per_epoch })
+    }
+
+    pub fn redeem_limit(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn redeem_and_unstake(amount: YoctoStake) -> Value {
+        json!({ "amount": amount })
+    }
+
+    pub fn account_receipts(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn redeem_participation(batch_id: BatchId, account_id: ValidAccountId) -> Value {
+        json!({ "batch_id": batch_id, "account_id": account_id })
+    }
+
+    /// None opts the account out of redeem forwarding
+    pub fn claim_receipts_for(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn set_redeem_forwarding(target_stake_contract: Option<ValidAccountId>) -> Value {
+        json!({ "target_stake_contract": target_stake_contract })
+    }
+
+    pub fn redeem_forwarding(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn deposit_redeem_forwarding(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn withdraw(amount: YoctoNear) -> Value {
+        json!({ "amount": amount })
+    }
+
+    pub fn transfer_near(recipient: ValidAccountId, amount: YoctoNear) -> Value {
+        json!({ "recipient": recipient, "amount": amount })
+    }
+
+    pub fn transfer_all_near(recipient: ValidAccountId) -> Value {
+        json!({ "recipient": recipient })
+    }
+
+    pub fn queued_withdrawal(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn process_queued_withdrawal(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    pub fn near_to_stake(amount: YoctoNear) -> Value {
+        json!({ "amount": amount })
+    }
+
+    pub fn stake_to_near(amount: YoctoStake) -> Value {
+        json!({ "amount": amount })
+    }
+
+    pub fn stake_token_value_twap(window_epochs: u32) -> Value {
+        json!({ "window_epochs": window_epochs })
+    }
+
+    pub fn stake_token_value_history(limit: u32) -> Value {
+        json!({ "limit": limit })
+    }
+
+    pub fn redeem_timeline(batch_id: BatchId) -> Value {
+        json!({ "batch_id": batch_id })
+    }
+}
+
+/// [StorageRefunds](crate::interface::StorageRefunds)
+pub mod storage_refunds {
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn storage_refund_balance(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+}
+
+/// [SwapIntents](crate::interface::SwapIntents)
+pub mod swap_intents {
+    use crate::interface::{FillSwapIntentMessage, TransferCallMessage, YoctoStake};
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{self, json, Value},
+    };
+
+    pub fn post_swap_intent(target_stake_contract: ValidAccountId, amount: YoctoStake) -> Value {
+        json!({ "target_stake_contract": target_stake_contract, "amount": amount })
+    }
+
+    pub fn swap_intent(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+
+    /// `ft_transfer_call` message that is sent to this contract via the target STAKE contract to fill
+    /// the account's swap intent
+    pub fn fill_swap_intent_message(intent_owner_id: ValidAccountId) -> TransferCallMessage {
+        TransferCallMessage(
+            serde_json::to_string(&FillSwapIntentMessage {
+                intent_owner_id: intent_owner_id.into(),
+            })
+            .unwrap(),
+        )
+    }
+}
+
+/// [Treasury](crate::interface::Treasury)
+pub mod treasury {
+    use crate::interface::YoctoStake;
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn treasury_transfer(recipient: ValidAccountId, amount: YoctoStake) -> Value {
+        json!({ "recipient": recipient, "amount": amount })
+    }
+}
+
+pub mod invoices {
+    use crate::interface::{BlockTimestamp, YoctoStake};
+    use near_sdk::{
+        json_types::U64,
+        serde_json::{json, Value},
+    };
+
+    pub fn create_invoice(amount: YoctoStake, memo: &str, expiry: BlockTimestamp) -> Value {
+        json!({ "amount": amount, "memo": memo, "expiry": expiry })
+    }
+
+    pub fn pay_invoice(invoice_id: U64) -> Value {
+        json!({ "invoice_id": invoice_id })
+    }
+
+    pub fn close_invoice(invoice_id: U64) -> Value {
+        json!({ "invoice_id": invoice_id })
+    }
+
+    pub fn invoice(invoice_id: U64) -> Value {
+        json!({ "invoice_id": invoice_id })
+    }
+}
+
+/// [Lockups](crate::interface::Lockups)
+pub mod liquidity_providers {
+    use crate::interface::{YoctoNear, YoctoStake};
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn remove_liquidity(amount: YoctoNear) -> Value {
+        json!({ "amount": amount })
+    }
+
+    pub fn swap_stake_for_near(amount: YoctoStake, min_near_out: YoctoNear) -> Value {
+        json!({
+            "amount": amount,
+            "min_near_out": min_near_out
+        })
+    }
+
+    pub fn quote_swap_stake_for_near(amount: YoctoStake) -> Value {
+        json!({ "amount": amount })
+    }
+
+    pub fn liquidity_shares_of(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+}
+
+pub mod lockups {
+    use crate::interface::{BlockTimestamp, YoctoStake};
+    use near_sdk::{
+        json_types::ValidAccountId,
+        serde_json::{json, Value},
+    };
+
+    pub fn create_lockup(
+        beneficiary_id: ValidAccountId,
+        amount: YoctoStake,
+        vesting_start: BlockTimestamp,
+        vesting_end: BlockTimestamp,
+    ) -> Value {
+        json!({
+            "beneficiary_id": beneficiary_id,
+            "amount": amount,
+            "vesting_start": vesting_start,
+            "vesting_end": vesting_end
+        })
+    }
+
+    pub fn lockup_of(account_id: ValidAccountId) -> Value {
+        json!({ "account_id": account_id })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::{Config, Gas, TransferCallMessage};
+    use crate::near::YOCTO;
+    use near_sdk::{
+        json_types::{Base64VecU8, ValidAccountId},
+        serde::Serialize,
+        serde_json::{self, json},
+    };
+    use std::convert::TryFrom;
+
+    fn account_id(account_id: &str) -> ValidAccountId {
+        ValidAccountId::try_from(account_id).unwrap()
+    }
+
+    #[test]
+    fn amounts_are_serialized_as_strings() {
+        assert_eq!(
+            staking_service::transfer_near(account_id("bob.near"), YOCTO.into()),
+            json!({ "recipient": "bob.near", "amount": YOCTO.to_string() })
+        );
+        assert_eq!(
+            staking_service::set_max_redeem_per_epoch(None),
+            json!({ "max_redeem_per_epoch": null })
+        );
+    }
+
+    #[test]
+    fn transfer_call_args() {
+        let args = fungible_token::TransferCallArgs::new(
+            account_id("receiver.near"),
+            YOCTO.into(),
+            TransferCallMessage::from("pay"),
+        )
+        .memo("invoice #1")
+        .to_json();
+        assert_eq!(
+            args,
+            json!({
+                "receiver_id": "receiver.near",
+                "amount": YOCTO.to_string(),
+                "msg": "pay",
+                "memo": "invoice #1",
+            })
+        );
+
+        #[derive(Serialize)]
+        #[serde(crate = "near_sdk::serde")]
+        struct Order {
+            order_id: u64,
+        }
+        let args = fungible_token::TransferCallArgs::json_msg(
+            account_id("receiver.near"),
+            YOCTO.into(),
+            &Order { order_id: 1 },
+        )
+        .to_json();
+        assert_eq!(args["msg"], json!(r#"{"order_id":1}"#));
+        assert_eq!(args["memo"], json!(null));
+        assert!(args.get("gas_for_receiver").is_none());
+
+        let args = fungible_token::TransferCallArgs::new(
+            account_id("receiver.near"),
+            YOCTO.into(),
+            TransferCallMessage::from("pay"),
+        )
+        .gas_for_receiver(Gas::from(50_000_000_000_000))
+        .to_json();
+        assert_eq!(args["gas_for_receiver"], json!("50000000000000"));
+    }
+
+    #[test]
+    fn update_config_args_deserialize_as_config() {
+        let args = operator::update_config(
+            Config {
+                max_stake_token_value_increase_percentage: Some(5),
+                ..Config::default()
+            },
+            Base64VecU8(vec![1, 2, 3]),
+        );
+        let config: Config = serde_json::from_value(args["config"].clone()).unwrap();
+        assert_eq!(config.max_stake_token_value_increase_percentage, Some(5));
+        assert!(config.storage_cost_per_byte.is_none());
+        assert_eq!(args["validation_hash"], json!("AQID"));
+    }
+}
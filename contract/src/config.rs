@@ -1,23 +1,157 @@
 use crate::near::YOCTO;
 use crate::{
-    domain::{Gas, YoctoNear, TGAS},
-    interface,
+    domain::{Gas, YoctoNear, MAX_ACCOUNT_ACTIVITY_LOG_LEN, MAX_BATCH_HISTORY_CAPACITY, TGAS},
+    interface::{self, ConfigFinding},
+};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
 };
-use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 
 /// min contract balance required above the contract's locked balance used for storage staking to
 /// ensure the contract is operational
 pub const CONTRACT_MIN_OPERATIONAL_BALANCE: YoctoNear = YoctoNear(YOCTO);
 
-#[derive(Debug, BorshSerialize, BorshDeserialize, Clone, Copy)]
+/// max protocol fee that can be skimmed from the distributed earnings, i.e., 50%
+pub const MAX_PROTOCOL_FEE_BASIS_POINTS: u16 = 5_000;
+
+/// max fee that liquidity providers can charge for serving claims pending withdrawal and for STAKE to
+/// NEAR swaps, i.e., 10%
+pub const MAX_LIQUIDITY_PROVIDER_FEE_BASIS_POINTS: u16 = 1_000;
+
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone)]
 pub struct Config {
     storage_cost_per_byte: YoctoNear,
     gas_config: GasConfig,
 
     /// percentage of contract gas rewards that are distributed to the contract owner
     /// - the rest of the contract earnings are staked to boost the staking rewards for user accounts
+    /// - the protocol fee is skimmed before the owner share is applied
     /// - must be a number between 0-100
     contract_owner_earnings_percentage: u8,
+
+    /// number of epochs to back off running stake batches after the staking pool was detected to
+    /// be unavailable
+    staking_pool_unavailable_backoff_epochs: u64,
+
+    /// number of epochs before an increase to an account's redeem limit takes effect
+    redeem_limit_increase_delay_epochs: u64,
+
+    /// max percentage of collected earnings that are distributed per stake batch run
+    /// - large windfalls are spread across multiple batch runs to smooth the STAKE token value curve
+    /// - must be a number between 1-100
+    max_earnings_distribution_percentage: u8,
+    /// max amount of collected earnings that are distributed per stake batch run
+    /// - zero means there is no limit
+    max_earnings_distribution_amount: YoctoNear,
+    /// max amount of NEAR that can leave the contract per epoch via account withdrawals and NEAR
+    /// transfers - any overflow is queued and released in the next epoch
+    /// - zero means there is no limit
+    max_withdrawal_per_epoch: YoctoNear,
+    /// max percentage that the STAKE token value can increase per update - larger jumps are held
+    /// pending until the operator confirms them
+    /// - guards against a corrupted staking pool response inflating the STAKE token value
+    /// - zero means there is no limit
+    max_stake_token_value_increase_percentage: u8,
+    /// number of blocks that must pass since the stake batch balance last changed before the batch
+    /// can be run - gives accounts a window to withdraw deposits from the batch
+    /// - zero means there is no cooldown
+    deposit_cooldown_blocks: u64,
+    /// when enabled, the storage usage delta of the last call is recorded per instrumented method
+    /// - see [debug_last_storage_delta](crate::interface::Operator::debug_last_storage_delta)
+    /// - recording costs extra gas and storage, thus it should only be enabled while profiling
+    storage_delta_diagnostics: bool,
+    /// when enabled, a partial stake batch withdrawal that would leave a batch balance below the
+    /// min required NEAR deposit withdraws the full batch balance instead of failing
+    withdraw_full_batch_balance_below_min_deposit: bool,
+    /// fee that is escrowed with a batch reservation - forfeited if the reservation is not used
+    batch_reservation_fee: YoctoNear,
+    /// number of blocks a batch reservation is valid for
+    batch_reservation_expiry_blocks: u64,
+    /// when enabled, the gas attached to staking pool calls is nudged toward the reported gas usage
+    /// - see [record_staking_pool_gas_usage](crate::interface::Operator::record_staking_pool_gas_usage)
+    staking_pool_gas_auto_tune: bool,
+    /// headroom that is added on top of the max observed gas burned when auto-tuning
+    /// - must be a number between 0-100
+    staking_pool_gas_auto_tune_headroom_percentage: u8,
+    /// percentage of the sweepable idle NEAR that is swept into a staking cycle - 0 disables sweeping
+    /// - idle NEAR is the NEAR in account balances awaiting withdrawal
+    /// - must be a number between 0-100
+    idle_near_sweep_percentage: u8,
+    /// percentage of the idle NEAR that is always kept liquid to fulfill withdrawals
+    /// - must be a number between 1-100
+    idle_near_liquidity_buffer_percentage: u8,
+    /// number of epochs that swept NEAR stays staked before it is redeemed
+    idle_near_sweep_cycle_epochs: u64,
+    /// safety margin that is added on top of the protocol storage byte cost when computing the
+    /// account storage fee - see [account_storage_fee](crate::interface::AccountManagement::account_storage_fee)
+    /// - must be a number between 0-100
+    account_storage_fee_margin_percentage: u8,
+    /// public goods beneficiary that receives a share of the user account earnings when earnings
+    /// are distributed
+    public_goods_beneficiary_id: Option<AccountId>,
+    /// percentage of the user account earnings that is donated to the public goods beneficiary
+    /// - 0 disables donations
+    /// - must be a number between 0-100
+    public_goods_percentage: u8,
+    /// max percentage that the STAKE supply, total NEAR, or NEAR liquidity pool can change within a
+    /// single contract call before an [AggregateAlarm](crate::interface::operator::events::AggregateAlarm)
+    /// is emitted
+    /// - 0 disables the alarm
+    aggregate_alarm_txn_threshold_percentage: u8,
+    /// max percentage that the STAKE supply, total NEAR, or NEAR liquidity pool can change within an
+    /// epoch before an [AggregateAlarm](crate::interface::operator::events::AggregateAlarm) is emitted
+    /// - 0 disables the alarm
+    aggregate_alarm_epoch_threshold_percentage: u8,
+    /// cooldown that is enforced before a workflow can be retried after it failed - the cooldown
+    /// doubles with each consecutive failure
+    /// - see [workflow_backoff_status](crate::interface::Operator::workflow_backoff_status)
+    /// - 0 disables the backoff
+    workflow_backoff_base_blocks: u64,
+    /// caps the workflow backoff cooldown
+    workflow_backoff_max_blocks: u64,
+    /// number of blocks after which a pending `ft_transfer_call` whose resolve callback never ran
+    /// can be resolved - see [resolve_stalled_transfer](crate::interface::PendingTransfers::resolve_stalled_transfer)
+    stalled_transfer_timeout_blocks: u64,
+    /// wNEAR contract whose `ft_transfer_call` transfers are unwrapped and deposited into the
+    /// sender's stake batch - see [TransferReceiver](crate::interface::TransferReceiver)
+    /// - None disables wNEAR deposits
+    wrap_near_contract_id: Option<AccountId>,
+    /// when enabled, the contract only stakes with a staking pool once the staking pool whitelist
+    /// contract reports it as whitelisted - see [StakingPools](crate::interface::StakingPools)
+    /// - should be disabled on networks without a staking pool whitelist contract, e.g., testnets
+    staking_pool_whitelist_check: bool,
+    /// NEAR Foundation staking pool whitelist contract
+    staking_pool_whitelist_contract_id: AccountId,
+    /// number of activity entries that are retained per account - see [AccountActivityLogs](crate::interface::AccountActivityLogs)
+    /// - 0 disables activity logging
+    account_activity_log_len: u8,
+    /// number of batch history entries that are retained per batch kind - see [BatchHistory](crate::interface::BatchHistory)
+    /// - 0 disables the batch history
+    batch_history_capacity: u32,
+    /// protocol fee in basis points that is skimmed from the distributed earnings before they are
+    /// split between the contract owner and the user accounts - the fee is staked as STAKE that is
+    /// owned by the [Treasury](crate::interface::Treasury)
+    /// - must be <= [MAX_PROTOCOL_FEE_BASIS_POINTS]
+    /// - 0 disables the protocol fee
+    protocol_fee_basis_points: u16,
+    /// number of epochs that a workflow lock must be held before it can be released as stale via
+    /// [release_stale_lock](crate::interface::Operator::release_stale_lock), i.e., the
+    /// workflow callback never fired
+    /// - must be > 0
+    stale_lock_timeout_epochs: u64,
+    /// fee in basis points that is earned by the liquidity providers when claims against a redeem
+    /// stake batch that is pending withdrawal are served from the liquidity provider pool - see
+    /// [LiquidityProviders](crate::interface::LiquidityProviders)
+    /// - must be <= [MAX_LIQUIDITY_PROVIDER_FEE_BASIS_POINTS]
+    liquidity_provider_fee_basis_points: u16,
+    /// STAKE to NEAR swap fee in basis points when the liquidity provider pool utilization is zero -
+    /// see [swap_stake_for_near](crate::interface::LiquidityProviders::swap_stake_for_near)
+    /// - must be <= [swap_fee_max_basis_points](Config::swap_fee_max_basis_points)
+    swap_fee_min_basis_points: u16,
+    /// STAKE to NEAR swap fee in basis points when the liquidity provider pool is fully utilized
+    /// - must be <= [MAX_LIQUIDITY_PROVIDER_FEE_BASIS_POINTS]
+    swap_fee_max_basis_points: u16,
 }
 
 impl Default for Config {
@@ -28,11 +162,74 @@ impl Default for Config {
             storage_cost_per_byte: 100_000_000_000_000_000_000.into(),
             gas_config: GasConfig::default(),
             contract_owner_earnings_percentage: 50,
+            staking_pool_unavailable_backoff_epochs: 1,
+            redeem_limit_increase_delay_epochs: 4,
+            max_earnings_distribution_percentage: 100,
+            max_earnings_distribution_amount: 0.into(),
+            max_withdrawal_per_epoch: 0.into(),
+            max_stake_token_value_increase_percentage: 0,
+            deposit_cooldown_blocks: 0,
+            storage_delta_diagnostics: false,
+            withdraw_full_batch_balance_below_min_deposit: false,
+            // 0.1 NEAR
+            batch_reservation_fee: (YOCTO / 10).into(),
+            // ~1 epoch
+            batch_reservation_expiry_blocks: 43_200,
+            staking_pool_gas_auto_tune: false,
+            staking_pool_gas_auto_tune_headroom_percentage: 25,
+            idle_near_sweep_percentage: 0,
+            idle_near_liquidity_buffer_percentage: 50,
+            idle_near_sweep_cycle_epochs: 1,
+            account_storage_fee_margin_percentage: 10,
+            public_goods_beneficiary_id: None,
+            public_goods_percentage: 0,
+            aggregate_alarm_txn_threshold_percentage: 0,
+            aggregate_alarm_epoch_threshold_percentage: 0,
+            workflow_backoff_base_blocks: 0,
+            // ~1 epoch
+            workflow_backoff_max_blocks: 43_200,
+            // ~10 minutes
+            stalled_transfer_timeout_blocks: 600,
+            wrap_near_contract_id: None,
+            staking_pool_whitelist_check: false,
+            staking_pool_whitelist_contract_id: "lockup-whitelist.near".to_string(),
+            account_activity_log_len: MAX_ACCOUNT_ACTIVITY_LOG_LEN,
+            batch_history_capacity: 0,
+            protocol_fee_basis_points: 0,
+            stale_lock_timeout_epochs: 2,
+            liquidity_provider_fee_basis_points: 0,
+            swap_fee_min_basis_points: 10,
+            swap_fee_max_basis_points: 100,
         }
     }
 }
 
 impl Config {
+    /// expands the preset into a full config - settings that are not specified by the preset use
+    /// the default values
+    pub fn preset(preset: interface::ConfigPreset) -> Self {
+        let config = Self::default();
+        match preset {
+            interface::ConfigPreset::MainnetConservative => Self {
+                staking_pool_unavailable_backoff_epochs: 2,
+                redeem_limit_increase_delay_epochs: 8,
+                max_earnings_distribution_percentage: 25,
+                max_stake_token_value_increase_percentage: 5,
+                // ~10 minutes
+                workflow_backoff_base_blocks: 600,
+                staking_pool_whitelist_check: true,
+                ..config
+            },
+            interface::ConfigPreset::TestnetFast => Self {
+                staking_pool_unavailable_backoff_epochs: 1,
+                redeem_limit_increase_delay_epochs: 1,
+                max_earnings_distribution_percentage: 100,
+                max_stake_token_value_increase_percentage: 0,
+                ..config
+            },
+        }
+    }
+
     pub fn storage_cost_per_byte(&self) -> YoctoNear {
         self.storage_cost_per_byte
     }
@@ -48,19 +245,401 @@ impl Config {
         self.contract_owner_earnings_percentage
     }
 
+    /// number of epochs to back off running stake batches after the staking pool was detected to
+    /// be unavailable
+    pub fn staking_pool_unavailable_backoff_epochs(&self) -> u64 {
+        self.staking_pool_unavailable_backoff_epochs
+    }
+
+    /// number of epochs before an increase to an account's redeem limit takes effect
+    pub fn redeem_limit_increase_delay_epochs(&self) -> u64 {
+        self.redeem_limit_increase_delay_epochs
+    }
+
+    /// max percentage of collected earnings that are distributed per stake batch run
+    pub fn max_earnings_distribution_percentage(&self) -> u8 {
+        self.max_earnings_distribution_percentage
+    }
+
+    /// max amount of collected earnings that are distributed per stake batch run
+    /// - zero means there is no limit
+    pub fn max_earnings_distribution_amount(&self) -> YoctoNear {
+        self.max_earnings_distribution_amount
+    }
+
+    /// max amount of NEAR that can leave the contract per epoch via account withdrawals and NEAR
+    /// transfers
+    /// - zero means there is no limit
+    pub fn max_withdrawal_per_epoch(&self) -> YoctoNear {
+        self.max_withdrawal_per_epoch
+    }
+
+    /// max percentage that the STAKE token value can increase per update
+    /// - zero means there is no limit
+    pub fn max_stake_token_value_increase_percentage(&self) -> u8 {
+        self.max_stake_token_value_increase_percentage
+    }
+
+    /// number of blocks that must pass since the stake batch balance last changed before the batch
+    /// can be run
+    /// - zero means there is no cooldown
+    pub fn deposit_cooldown_blocks(&self) -> u64 {
+        self.deposit_cooldown_blocks
+    }
+
+    pub fn storage_delta_diagnostics(&self) -> bool {
+        self.storage_delta_diagnostics
+    }
+
+    /// when enabled, a partial stake batch withdrawal that would leave a batch balance below the
+    /// min required NEAR deposit withdraws the full batch balance instead of failing
+    pub fn withdraw_full_batch_balance_below_min_deposit(&self) -> bool {
+        self.withdraw_full_batch_balance_below_min_deposit
+    }
+
+    /// fee that is escrowed with a batch reservation - forfeited if the reservation is not used
+    pub fn batch_reservation_fee(&self) -> YoctoNear {
+        self.batch_reservation_fee
+    }
+
+    /// number of blocks a batch reservation is valid for
+    pub fn batch_reservation_expiry_blocks(&self) -> u64 {
+        self.batch_reservation_expiry_blocks
+    }
+
+    /// when enabled, the gas attached to staking pool calls is nudged toward the observed gas usage
+    pub fn staking_pool_gas_auto_tune(&self) -> bool {
+        self.staking_pool_gas_auto_tune
+    }
+
+    /// headroom that is added on top of the max observed gas burned when auto-tuning
+    pub fn staking_pool_gas_auto_tune_headroom_percentage(&self) -> u8 {
+        self.staking_pool_gas_auto_tune_headroom_percentage
+    }
+
+    /// percentage of the sweepable idle NEAR that is swept into a staking cycle - 0 means disabled
+    pub fn idle_near_sweep_percentage(&self) -> u8 {
+        self.idle_near_sweep_percentage
+    }
+
+    /// percentage of the idle NEAR that is always kept liquid to fulfill withdrawals
+    pub fn idle_near_liquidity_buffer_percentage(&self) -> u8 {
+        self.idle_near_liquidity_buffer_percentage
+    }
+
+    /// number of epochs that swept NEAR stays staked before it is redeemed
+    pub fn idle_near_sweep_cycle_epochs(&self) -> u64 {
+        self.idle_near_sweep_cycle_epochs
+    }
+
+    /// safety margin that is added on top of the protocol storage byte cost when computing the
+    /// account storage fee
+    pub fn account_storage_fee_margin_percentage(&self) -> u8 {
+        self.account_storage_fee_margin_percentage
+    }
+
+    pub fn staking_pool_whitelist_check(&self) -> bool {
+        self.staking_pool_whitelist_check
+    }
+
+    pub fn staking_pool_whitelist_contract_id(&self) -> &str {
+        &self.staking_pool_whitelist_contract_id
+    }
+
+    pub fn account_activity_log_len(&self) -> u8 {
+        self.account_activity_log_len
+    }
+
+    pub fn batch_history_capacity(&self) -> u32 {
+        self.batch_history_capacity
+    }
+
+    pub fn protocol_fee_basis_points(&self) -> u16 {
+        self.protocol_fee_basis_points
+    }
+
+    pub fn stale_lock_timeout_epochs(&self) -> u64 {
+        self.stale_lock_timeout_epochs
+    }
+
+    pub fn liquidity_provider_fee_basis_points(&self) -> u16 {
+        self.liquidity_provider_fee_basis_points
+    }
+
+    pub fn swap_fee_min_basis_points(&self) -> u16 {
+        self.swap_fee_min_basis_points
+    }
+
+    pub fn swap_fee_max_basis_points(&self) -> u16 {
+        self.swap_fee_max_basis_points
+    }
+
+    pub fn wrap_near_contract_id(&self) -> Option<&str> {
+        self.wrap_near_contract_id.as_deref()
+    }
+
+    pub fn public_goods_beneficiary_id(&self) -> Option<&str> {
+        self.public_goods_beneficiary_id.as_deref()
+    }
+
+    /// percentage of the user account earnings that is donated to the public goods beneficiary
+    pub fn public_goods_percentage(&self) -> u8 {
+        self.public_goods_percentage
+    }
+
+    /// 0 means the per contract call aggregate alarm is disabled
+    pub fn aggregate_alarm_txn_threshold_percentage(&self) -> u8 {
+        self.aggregate_alarm_txn_threshold_percentage
+    }
+
+    /// 0 means the per epoch aggregate alarm is disabled
+    pub fn aggregate_alarm_epoch_threshold_percentage(&self) -> u8 {
+        self.aggregate_alarm_epoch_threshold_percentage
+    }
+
+    /// 0 means the workflow backoff is disabled
+    pub fn workflow_backoff_base_blocks(&self) -> u64 {
+        self.workflow_backoff_base_blocks
+    }
+
+    pub fn workflow_backoff_max_blocks(&self) -> u64 {
+        self.workflow_backoff_max_blocks
+    }
+
+    pub fn stalled_transfer_timeout_blocks(&self) -> u64 {
+        self.stalled_transfer_timeout_blocks
+    }
+
+    /// if auto-tuning is enabled, then the gas attached to the staking pool method is nudged toward
+    /// the max observed gas burned plus headroom
+    ///
+    /// Returns the updated gas, or None if the gas was not changed
+    pub(crate) fn auto_tune_staking_pool_gas(
+        &mut self,
+        method_name: &str,
+        max_burnt: Gas,
+    ) -> Option<Gas> {
+        if !self.staking_pool_gas_auto_tune {
+            return None;
+        }
+        self.gas_config.auto_tune_staking_pool_gas(
+            method_name,
+            max_burnt,
+            self.staking_pool_gas_auto_tune_headroom_percentage,
+        )
+    }
+
     /// ## Panics
-    /// if validation fails
+    /// if validation fails - see [validate](Config::validate)
     pub fn merge(&mut self, config: interface::Config) {
-        if let Some(storage_cost_per_byte) = config.storage_cost_per_byte {
-            assert!(
+        assert_no_findings(self.validate(&config));
+        self.force_merge(config);
+    }
+
+    /// validates the proposed config changes
+    /// - fields that are set on the proposed config are checked against their bounds
+    /// - cross field constraints are checked against the full config that results from merging in
+    ///   the proposed changes, e.g., the gas sums for the cross-contract workflows
+    ///
+    /// Returns the findings - an empty list means the config changes are valid
+    pub fn validate(&self, config: &interface::Config) -> Vec<ConfigFinding> {
+        let mut findings = vec![];
+        if let Some(storage_cost_per_byte) = config.storage_cost_per_byte.as_ref() {
+            check(
+                &mut findings,
                 storage_cost_per_byte.value() > 0,
-                "storage_cost_per_byte must be > 0"
+                "storage_cost_per_byte",
+                "storage_cost_per_byte must be > 0",
             );
-            self.storage_cost_per_byte = storage_cost_per_byte.value().into();
         }
-        if let Some(gas_config) = config.gas_config {
-            self.gas_config.merge(gas_config, true);
+        let mut merged = self.clone();
+        merged.force_merge(config.clone());
+        self.gas_config.validate(
+            config.gas_config.as_ref(),
+            &merged.gas_config,
+            &mut findings,
+        );
+        if let Some(epochs) = config.staking_pool_unavailable_backoff_epochs {
+            check(
+                &mut findings,
+                epochs > 0,
+                "staking_pool_unavailable_backoff_epochs",
+                "staking_pool_unavailable_backoff_epochs must be > 0",
+            );
+        }
+        if let Some(epochs) = config.redeem_limit_increase_delay_epochs {
+            check(
+                &mut findings,
+                epochs > 0,
+                "redeem_limit_increase_delay_epochs",
+                "redeem_limit_increase_delay_epochs must be > 0",
+            );
+        }
+        if let Some(epochs) = config.stale_lock_timeout_epochs {
+            check(
+                &mut findings,
+                epochs > 0,
+                "stale_lock_timeout_epochs",
+                "stale_lock_timeout_epochs must be > 0",
+            );
+        }
+        if let Some(percentage) = config.max_earnings_distribution_percentage {
+            check(
+                &mut findings,
+                percentage > 0 && percentage <= 100,
+                "max_earnings_distribution_percentage",
+                "max_earnings_distribution_percentage must be within 1 - 100",
+            );
+        }
+        if let Some(percentage) = config.max_stake_token_value_increase_percentage {
+            check_percentage(
+                &mut findings,
+                percentage,
+                "max_stake_token_value_increase_percentage",
+            );
+        }
+        if let Some(blocks) = config.batch_reservation_expiry_blocks {
+            check(
+                &mut findings,
+                blocks > 0,
+                "batch_reservation_expiry_blocks",
+                "batch_reservation_expiry_blocks must be > 0",
+            );
         }
+        if let Some(percentage) = config.staking_pool_gas_auto_tune_headroom_percentage {
+            check_percentage(
+                &mut findings,
+                percentage,
+                "staking_pool_gas_auto_tune_headroom_percentage",
+            );
+        }
+        if let Some(percentage) = config.idle_near_sweep_percentage {
+            check_percentage(&mut findings, percentage, "idle_near_sweep_percentage");
+        }
+        if let Some(percentage) = config.idle_near_liquidity_buffer_percentage {
+            check(
+                &mut findings,
+                percentage > 0 && percentage <= 100,
+                "idle_near_liquidity_buffer_percentage",
+                "idle_near_liquidity_buffer_percentage must be within 1 - 100",
+            );
+        }
+        if let Some(epochs) = config.idle_near_sweep_cycle_epochs {
+            check(
+                &mut findings,
+                epochs > 0,
+                "idle_near_sweep_cycle_epochs",
+                "idle_near_sweep_cycle_epochs must be > 0",
+            );
+        }
+        if let Some(percentage) = config.account_storage_fee_margin_percentage {
+            check_percentage(
+                &mut findings,
+                percentage,
+                "account_storage_fee_margin_percentage",
+            );
+        }
+        if let Some(percentage) = config.public_goods_percentage {
+            check_percentage(&mut findings, percentage, "public_goods_percentage");
+        }
+        check(
+            &mut findings,
+            merged.public_goods_percentage == 0 || merged.public_goods_beneficiary_id.is_some(),
+            "public_goods_beneficiary_id",
+            "public_goods_beneficiary_id must be set when public_goods_percentage > 0",
+        );
+        if let Some(percentage) = config.aggregate_alarm_txn_threshold_percentage {
+            check_percentage(
+                &mut findings,
+                percentage,
+                "aggregate_alarm_txn_threshold_percentage",
+            );
+        }
+        if let Some(percentage) = config.aggregate_alarm_epoch_threshold_percentage {
+            check_percentage(
+                &mut findings,
+                percentage,
+                "aggregate_alarm_epoch_threshold_percentage",
+            );
+        }
+        check(
+            &mut findings,
+            merged.workflow_backoff_base_blocks <= merged.workflow_backoff_max_blocks,
+            "workflow_backoff_base_blocks",
+            "workflow_backoff_base_blocks must be <= workflow_backoff_max_blocks",
+        );
+        // a zero timeout would enable in-flight transfers to be resolved before the receiver responds
+        check(
+            &mut findings,
+            merged.stalled_transfer_timeout_blocks > 0,
+            "stalled_transfer_timeout_blocks",
+            "stalled_transfer_timeout_blocks must be > 0",
+        );
+        // the account storage fee only covers a fully allocated activity log
+        check(
+            &mut findings,
+            merged.account_activity_log_len <= MAX_ACCOUNT_ACTIVITY_LOG_LEN,
+            "account_activity_log_len",
+            &format!(
+                "account_activity_log_len must be <= {}",
+                MAX_ACCOUNT_ACTIVITY_LOG_LEN
+            ),
+        );
+        // the batch history storage is paid for by the contract
+        check(
+            &mut findings,
+            merged.batch_history_capacity <= MAX_BATCH_HISTORY_CAPACITY,
+            "batch_history_capacity",
+            &format!(
+                "batch_history_capacity must be <= {}",
+                MAX_BATCH_HISTORY_CAPACITY
+            ),
+        );
+        check(
+            &mut findings,
+            merged.protocol_fee_basis_points <= MAX_PROTOCOL_FEE_BASIS_POINTS,
+            "protocol_fee_basis_points",
+            &format!(
+                "protocol_fee_basis_points must be <= {}",
+                MAX_PROTOCOL_FEE_BASIS_POINTS
+            ),
+        );
+        check(
+            &mut findings,
+            merged.liquidity_provider_fee_basis_points <= MAX_LIQUIDITY_PROVIDER_FEE_BASIS_POINTS,
+            "liquidity_provider_fee_basis_points",
+            &format!(
+                "liquidity_provider_fee_basis_points must be <= {}",
+                MAX_LIQUIDITY_PROVIDER_FEE_BASIS_POINTS
+            ),
+        );
+        check(
+            &mut findings,
+            merged.swap_fee_max_basis_points <= MAX_LIQUIDITY_PROVIDER_FEE_BASIS_POINTS,
+            "swap_fee_max_basis_points",
+            &format!(
+                "swap_fee_max_basis_points must be <= {}",
+                MAX_LIQUIDITY_PROVIDER_FEE_BASIS_POINTS
+            ),
+        );
+        check(
+            &mut findings,
+            merged.swap_fee_min_basis_points <= merged.swap_fee_max_basis_points,
+            "swap_fee_min_basis_points",
+            "swap_fee_min_basis_points must be <= swap_fee_max_basis_points",
+        );
+        // changes within a single transaction also count toward the epoch window
+        check(
+            &mut findings,
+            merged.aggregate_alarm_txn_threshold_percentage == 0
+                || merged.aggregate_alarm_epoch_threshold_percentage == 0
+                || merged.aggregate_alarm_txn_threshold_percentage
+                    <= merged.aggregate_alarm_epoch_threshold_percentage,
+            "aggregate_alarm_txn_threshold_percentage",
+            "aggregate_alarm_txn_threshold_percentage must be <= \
+            aggregate_alarm_epoch_threshold_percentage",
+        );
+        findings
     }
 
     /// performas no validation
@@ -71,19 +650,152 @@ impl Config {
         if let Some(gas_config) = config.gas_config {
             self.gas_config.merge(gas_config, false);
         }
+        if let Some(epochs) = config.staking_pool_unavailable_backoff_epochs {
+            self.staking_pool_unavailable_backoff_epochs = epochs;
+        }
+        if let Some(epochs) = config.redeem_limit_increase_delay_epochs {
+            self.redeem_limit_increase_delay_epochs = epochs;
+        }
+        if let Some(percentage) = config.max_earnings_distribution_percentage {
+            self.max_earnings_distribution_percentage = percentage;
+        }
+        if let Some(amount) = config.max_earnings_distribution_amount {
+            self.max_earnings_distribution_amount = amount.value().into();
+        }
+        if let Some(amount) = config.max_withdrawal_per_epoch {
+            self.max_withdrawal_per_epoch = amount.value().into();
+        }
+        if let Some(percentage) = config.max_stake_token_value_increase_percentage {
+            self.max_stake_token_value_increase_percentage = percentage;
+        }
+        if let Some(blocks) = config.deposit_cooldown_blocks {
+            self.deposit_cooldown_blocks = blocks;
+        }
+        if let Some(enabled) = config.storage_delta_diagnostics {
+            self.storage_delta_diagnostics = enabled;
+        }
+        if let Some(enabled) = config.withdraw_full_batch_balance_below_min_deposit {
+            self.withdraw_full_batch_balance_below_min_deposit = enabled;
+        }
+        if let Some(fee) = config.batch_reservation_fee {
+            self.batch_reservation_fee = fee.value().into();
+        }
+        if let Some(blocks) = config.batch_reservation_expiry_blocks {
+            self.batch_reservation_expiry_blocks = blocks;
+        }
+        if let Some(enabled) = config.staking_pool_gas_auto_tune {
+            self.staking_pool_gas_auto_tune = enabled;
+        }
+        if let Some(percentage) = config.staking_pool_gas_auto_tune_headroom_percentage {
+            self.staking_pool_gas_auto_tune_headroom_percentage = percentage;
+        }
+        if let Some(percentage) = config.idle_near_sweep_percentage {
+            self.idle_near_sweep_percentage = percentage;
+        }
+        if let Some(percentage) = config.idle_near_liquidity_buffer_percentage {
+            self.idle_near_liquidity_buffer_percentage = percentage;
+        }
+        if let Some(epochs) = config.idle_near_sweep_cycle_epochs {
+            self.idle_near_sweep_cycle_epochs = epochs;
+        }
+        if let Some(percentage) = config.account_storage_fee_margin_percentage {
+            self.account_storage_fee_margin_percentage = percentage;
+        }
+        if let Some(account_id) = config.public_goods_beneficiary_id {
+            self.public_goods_beneficiary_id = Some(account_id.into());
+        }
+        if let Some(percentage) = config.public_goods_percentage {
+            self.public_goods_percentage = percentage;
+        }
+        if let Some(percentage) = config.aggregate_alarm_txn_threshold_percentage {
+            self.aggregate_alarm_txn_threshold_percentage = percentage;
+        }
+        if let Some(percentage) = config.aggregate_alarm_epoch_threshold_percentage {
+            self.aggregate_alarm_epoch_threshold_percentage = percentage;
+        }
+        if let Some(blocks) = config.workflow_backoff_base_blocks {
+            self.workflow_backoff_base_blocks = blocks;
+        }
+        if let Some(blocks) = config.workflow_backoff_max_blocks {
+            self.workflow_backoff_max_blocks = blocks;
+        }
+        if let Some(blocks) = config.stalled_transfer_timeout_blocks {
+            self.stalled_transfer_timeout_blocks = blocks;
+        }
+        if let Some(account_id) = config.wrap_near_contract_id {
+            self.wrap_near_contract_id = Some(account_id.into());
+        }
+        if let Some(enabled) = config.staking_pool_whitelist_check {
+            self.staking_pool_whitelist_check = enabled;
+        }
+        if let Some(account_id) = config.staking_pool_whitelist_contract_id {
+            self.staking_pool_whitelist_contract_id = account_id.into();
+        }
+        if let Some(len) = config.account_activity_log_len {
+            self.account_activity_log_len = len;
+        }
+        if let Some(capacity) = config.batch_history_capacity {
+            self.batch_history_capacity = capacity;
+        }
+        if let Some(basis_points) = config.protocol_fee_basis_points {
+            self.protocol_fee_basis_points = basis_points;
+        }
+        if let Some(epochs) = config.stale_lock_timeout_epochs {
+            self.stale_lock_timeout_epochs = epochs;
+        }
+        if let Some(basis_points) = config.liquidity_provider_fee_basis_points {
+            self.liquidity_provider_fee_basis_points = basis_points;
+        }
+        if let Some(basis_points) = config.swap_fee_min_basis_points {
+            self.swap_fee_min_basis_points = basis_points;
+        }
+        if let Some(basis_points) = config.swap_fee_max_basis_points {
+            self.swap_fee_max_basis_points = basis_points;
+        }
     }
 }
 
-fn assert_gas_range(gas: Gas, min: u8, max: u8, field: &str) {
-    assert!(
+fn check(findings: &mut Vec<ConfigFinding>, valid: bool, field: &str, message: &str) {
+    if !valid {
+        findings.push(ConfigFinding {
+            field: field.to_string(),
+            message: message.to_string(),
+        });
+    }
+}
+
+fn check_percentage(findings: &mut Vec<ConfigFinding>, percentage: u8, field: &str) {
+    check(
+        findings,
+        percentage <= 100,
+        field,
+        &format!("{} must be within 0 - 100", field),
+    );
+}
+
+fn check_gas_range(findings: &mut Vec<ConfigFinding>, gas: Gas, min: u8, max: u8, field: &str) {
+    check(
+        findings,
         gas >= TGAS * min as u64 && gas <= TGAS * max as u64,
-        "{} must be within {} - {} TGas",
         field,
-        min,
-        max
+        &format!("{} must be within {} - {} TGas", field, min, max),
     );
 }
 
+/// ## Panics
+/// with the first finding's message
+fn assert_no_findings(findings: Vec<ConfigFinding>) {
+    if let Some(finding) = findings.first() {
+        panic!("{}", finding.message);
+    }
+}
+
+/// max gas that can be attached to a transaction per the NEAR protocol
+pub const MAX_TRANSACTION_GAS: Gas = Gas(300_000_000_000_000);
+
+/// max amount the gas attached to a staking pool method is moved per auto-tune adjustment
+pub const GAS_AUTO_TUNE_MAX_STEP: Gas = Gas(5_000_000_000_000);
+
 #[derive(Debug, BorshSerialize, BorshDeserialize, Clone, Copy)]
 pub struct GasConfig {
     staking_pool: StakingPoolGasConfig,
@@ -91,6 +803,12 @@ pub struct GasConfig {
 
     function_call_promise: Gas,
     function_call_promise_data_dependency: Gas,
+
+    /// default gas that is forwarded to the receiver's `ft_on_transfer` by `ft_transfer_call`
+    /// when the caller does not specify `gas_for_receiver`
+    /// - zero means all remaining prepaid gas is forwarded, after reserving gas for
+    ///   `ft_resolve_transfer_call`
+    ft_on_transfer: Gas,
 }
 
 impl GasConfig {
@@ -110,17 +828,29 @@ impl GasConfig {
         self.function_call_promise_data_dependency
     }
 
+    pub fn ft_on_transfer(&self) -> Gas {
+        self.ft_on_transfer
+    }
+
     /// if validate is true, then merge performs some sanity checks on the config to
-    /// catch mis-configurations.
+    /// catch mis-configurations - see [validate](GasConfig::validate)
     ///
     /// ## Panics
     /// if validation fails
     pub fn merge(&mut self, config: interface::GasConfig, validate: bool) {
+        if validate {
+            let mut merged = self.clone();
+            merged.merge(config.clone(), false);
+            let mut findings = vec![];
+            self.validate(Some(&config), &merged, &mut findings);
+            assert_no_findings(findings);
+        }
+
         if let Some(config) = config.callbacks {
-            self.callbacks.merge(config, validate);
+            self.callbacks.merge(config, false);
         }
         if let Some(config) = config.staking_pool {
-            self.staking_pool.merge(config, validate);
+            self.staking_pool.merge(config, false);
         }
 
         if let Some(gas) = config.function_call_promise {
@@ -129,33 +859,154 @@ impl GasConfig {
         if let Some(gas) = config.function_call_promise_data_dependency {
             self.function_call_promise_data_dependency = gas.into();
         }
+        if let Some(gas) = config.ft_on_transfer {
+            self.ft_on_transfer = gas.into();
+        }
+    }
 
-        if validate {
-            // check that the numbers add up for cross-contract workflows
-            assert!(
-                self.callbacks.on_run_stake_batch
-                    >= (self.staking_pool.deposit_and_stake
-                        + self.callbacks.on_deposit_and_stake
-                        + (TGAS * 5)),
-                "callbacks.on_run_stake_batch must be >= \
-            staking_pool.deposit_and_stake + callbacks.on_deposit_and_stake + 5 TGas"
-            );
-            assert!(
-                self.callbacks.on_run_redeem_stake_batch
-                    >= (self.staking_pool.unstake + self.callbacks.on_unstake + (TGAS * 5)),
-                "callbacks.on_run_redeem_stake_batch must be >= \
-            staking_pool.unstake + callbacks.on_unstake + 5 TGas"
-            );
-            assert!(
-                self.callbacks.on_redeeming_stake_pending_withdrawal
-                    >= (self.staking_pool.withdraw
-                        + self.callbacks.on_redeeming_stake_post_withdrawal
-                        + (TGAS * 5)),
-                "callbacks.on_redeeming_stake_pending_withdrawal must be >= \
-            staking_pool.withdraw + callbacks.on_redeeming_stake_post_withdrawal + 5 TGas"
+    /// - the proposed gas values are checked against their bounds
+    /// - the merged gas config is checked to ensure that the numbers add up for the cross-contract
+    ///   workflows, and that the gas for each workflow fits within a single transaction
+    fn validate(
+        &self,
+        config: Option<&interface::GasConfig>,
+        merged: &GasConfig,
+        findings: &mut Vec<ConfigFinding>,
+    ) {
+        if let Some(config) = config {
+            if let Some(config) = config.callbacks.as_ref() {
+                CallBacksGasConfig::validate(config, findings);
+            }
+            if let Some(config) = config.staking_pool.as_ref() {
+                StakingPoolGasConfig::validate(config, findings);
+            }
+            if let Some(gas) = config.ft_on_transfer.as_ref() {
+                let gas: Gas = gas.clone().into();
+                if gas.value() > 0 {
+                    check_gas_range(findings, gas, 5, 250, "ft_on_transfer");
+                }
+            }
+        }
+
+        let callbacks = merged.callbacks;
+        let staking_pool = merged.staking_pool;
+        check(
+            findings,
+            callbacks.on_run_stake_batch
+                >= (staking_pool.deposit_and_stake + callbacks.on_deposit_and_stake + (TGAS * 5)),
+            "callbacks::on_run_stake_batch",
+            "callbacks.on_run_stake_batch must be >= \
+            staking_pool.deposit_and_stake + callbacks.on_deposit_and_stake + 5 TGas",
+        );
+        check(
+            findings,
+            callbacks.on_run_redeem_stake_batch
+                >= (staking_pool.unstake + callbacks.on_unstake + (TGAS * 5)),
+            "callbacks::on_run_redeem_stake_batch",
+            "callbacks.on_run_redeem_stake_batch must be >= \
+            staking_pool.unstake + callbacks.on_unstake + 5 TGas",
+        );
+        check(
+            findings,
+            callbacks.on_redeeming_stake_pending_withdrawal
+                >= (staking_pool.withdraw
+                    + callbacks.on_redeeming_stake_post_withdrawal
+                    + (TGAS * 5)),
+            "callbacks::on_redeeming_stake_pending_withdrawal",
+            "callbacks.on_redeeming_stake_pending_withdrawal must be >= \
+            staking_pool.withdraw + callbacks.on_redeeming_stake_post_withdrawal + 5 TGas",
+        );
+
+        // gas that is attached by the batch run workflows, which are submitted as a single
+        // transaction: staking_pool::get_account -> callback -> unlock
+        let workflows = [
+            (
+                "stake",
+                staking_pool.get_account + callbacks.on_run_stake_batch + callbacks.unlock,
+            ),
+            (
+                "unstake",
+                staking_pool.get_account + callbacks.on_run_redeem_stake_batch + callbacks.unlock,
+            ),
+            (
+                "withdraw",
+                staking_pool.get_account + callbacks.on_redeeming_stake_pending_withdrawal,
+            ),
+            (
+                "refresh_stake_token_value",
+                staking_pool.get_account
+                    + callbacks.on_refresh_stake_token_value
+                    + callbacks.unlock,
+            ),
+        ];
+        for (workflow, gas) in workflows.iter() {
+            check(
+                findings,
+                *gas <= MAX_TRANSACTION_GAS,
+                workflow,
+                &format!("{} workflow gas must be <= 300 TGas", workflow),
             );
         }
     }
+
+    /// nudges the gas attached to the staking pool method toward the max observed gas burned plus
+    /// headroom
+    /// - the gas moves by at most [GAS_AUTO_TUNE_MAX_STEP] per adjustment
+    /// - the gas is kept within the bounds that are validated by [merge](GasConfig::merge), which
+    ///   includes leaving enough gas for the workflow callbacks
+    ///
+    /// Returns the updated gas, or None if the gas was not changed or the method is not auto-tuned
+    pub(crate) fn auto_tune_staking_pool_gas(
+        &mut self,
+        method_name: &str,
+        max_burnt: Gas,
+        headroom_percentage: u8,
+    ) -> Option<Gas> {
+        let (min, max) = match method_name {
+            "get_account" | "get_reward_fee_fraction" => (TGAS * 5, TGAS * 10),
+            "deposit" => (TGAS * 5, TGAS * 20),
+            "deposit_and_stake" | "stake" | "unstake" | "withdraw" => (TGAS * 40, TGAS * 75),
+            _ => return None,
+        };
+        let callbacks_budget = |callback: Gas, post_callback: Gas| {
+            Gas(callback
+                .value()
+                .saturating_sub((post_callback + TGAS * 5).value()))
+        };
+        let max = match method_name {
+            "deposit_and_stake" => max.min(callbacks_budget(
+                self.callbacks.on_run_stake_batch,
+                self.callbacks.on_deposit_and_stake,
+            )),
+            "unstake" => max.min(callbacks_budget(
+                self.callbacks.on_run_redeem_stake_batch,
+                self.callbacks.on_unstake,
+            )),
+            "withdraw" => max.min(callbacks_budget(
+                self.callbacks.on_redeeming_stake_pending_withdrawal,
+                self.callbacks.on_redeeming_stake_post_withdrawal,
+            )),
+            _ => max,
+        };
+
+        let target = max_burnt.value() + max_burnt.value() / 100 * headroom_percentage as u64;
+        let current = self.staking_pool.gas(method_name)?.value();
+        let gas = if target > current {
+            current
+                .saturating_add(GAS_AUTO_TUNE_MAX_STEP.value())
+                .min(target)
+        } else {
+            current
+                .saturating_sub(GAS_AUTO_TUNE_MAX_STEP.value())
+                .max(target)
+        };
+        let gas = Gas(gas).min(max).max(min);
+        if gas.value() == current {
+            return None;
+        }
+        self.staking_pool.set_gas(method_name, gas);
+        Some(gas)
+    }
 }
 
 impl Default for GasConfig {
@@ -165,6 +1016,7 @@ impl Default for GasConfig {
             callbacks: Default::default(),
             function_call_promise: TGAS * 5,
             function_call_promise_data_dependency: TGAS * 10,
+            ft_on_transfer: Gas(0),
         }
     }
 }
@@ -177,6 +1029,7 @@ pub struct StakingPoolGasConfig {
     unstake: Gas,
     withdraw: Gas,
     get_account: Gas,
+    get_reward_fee_fraction: Gas,
     ping: Gas,
 }
 
@@ -184,6 +1037,7 @@ impl Default for StakingPoolGasConfig {
     fn default() -> Self {
         Self {
             get_account: TGAS * 5,
+            get_reward_fee_fraction: TGAS * 5,
             deposit_and_stake: TGAS * 45,
             deposit: TGAS * 45,
             stake: TGAS * 45,
@@ -219,52 +1073,126 @@ impl StakingPoolGasConfig {
         self.get_account
     }
 
+    pub fn get_reward_fee_fraction(&self) -> Gas {
+        self.get_reward_fee_fraction
+    }
+
     pub fn ping(&self) -> Gas {
         self.ping
     }
 
+    /// returns the gas attached to the specified staking pool method
+    pub fn gas(&self, method_name: &str) -> Option<Gas> {
+        match method_name {
+            "deposit_and_stake" => Some(self.deposit_and_stake),
+            "deposit" => Some(self.deposit),
+            "stake" => Some(self.stake),
+            "unstake" => Some(self.unstake),
+            "withdraw" => Some(self.withdraw),
+            "get_account" => Some(self.get_account),
+            "get_reward_fee_fraction" => Some(self.get_reward_fee_fraction),
+            "ping" => Some(self.ping),
+            _ => None,
+        }
+    }
+
+    fn set_gas(&mut self, method_name: &str, gas: Gas) {
+        match method_name {
+            "deposit_and_stake" => self.deposit_and_stake = gas,
+            "deposit" => self.deposit = gas,
+            "stake" => self.stake = gas,
+            "unstake" => self.unstake = gas,
+            "withdraw" => self.withdraw = gas,
+            "get_account" => self.get_account = gas,
+            "get_reward_fee_fraction" => self.get_reward_fee_fraction = gas,
+            "ping" => self.ping = gas,
+            _ => {}
+        }
+    }
+
+    /// ## Panics
+    /// if validate is true and validation fails
     pub fn merge(&mut self, config: interface::StakingPoolGasConfig, validate: bool) {
+        if validate {
+            let mut findings = vec![];
+            Self::validate(&config, &mut findings);
+            assert_no_findings(findings);
+        }
         if let Some(gas) = config.get_account {
-            let gas = gas.into();
-            if validate {
-                assert_gas_range(gas, 5, 10, "staking_pool::get_account");
-            }
-            self.get_account = gas;
+            self.get_account = gas.into();
+        }
+        if let Some(gas) = config.get_reward_fee_fraction {
+            self.get_reward_fee_fraction = gas.into();
         }
         if let Some(gas) = config.deposit_and_stake {
-            let gas = gas.into();
-            if validate {
-                assert_gas_range(gas, 40, 75, "staking_pool::deposit_and_stake");
-            }
-            self.deposit_and_stake = gas;
+            self.deposit_and_stake = gas.into();
         }
         if let Some(gas) = config.deposit {
-            let gas = gas.into();
-            if validate {
-                assert_gas_range(gas, 5, 20, "staking_pool::deposit");
-            }
-            self.deposit = gas;
+            self.deposit = gas.into();
         }
         if let Some(gas) = config.stake {
-            let gas = gas.into();
-            if validate {
-                assert_gas_range(gas, 40, 75, "staking_pool::stake");
-            }
-            self.stake = gas;
+            self.stake = gas.into();
         }
         if let Some(gas) = config.unstake {
-            let gas = gas.into();
-            if validate {
-                assert_gas_range(gas, 40, 75, "staking_pool::unstake");
-            }
-            self.unstake = gas;
+            self.unstake = gas.into();
         }
         if let Some(gas) = config.withdraw {
-            let gas = gas.into();
-            if validate {
-                assert_gas_range(gas, 40, 75, "staking_pool::withdraw");
-            }
-            self.withdraw = gas;
+            self.withdraw = gas.into();
+        }
+    }
+
+    /// checks the proposed gas values against their bounds
+    fn validate(config: &interface::StakingPoolGasConfig, findings: &mut Vec<ConfigFinding>) {
+        if let Some(gas) = config.get_account.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                5,
+                10,
+                "staking_pool::get_account",
+            );
+        }
+        if let Some(gas) = config.get_reward_fee_fraction.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                5,
+                10,
+                "staking_pool::get_reward_fee_fraction",
+            );
+        }
+        if let Some(gas) = config.deposit_and_stake.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                40,
+                75,
+                "staking_pool::deposit_and_stake",
+            );
+        }
+        if let Some(gas) = config.deposit.as_ref() {
+            check_gas_range(findings, gas.clone().into(), 5, 20, "staking_pool::deposit");
+        }
+        if let Some(gas) = config.stake.as_ref() {
+            check_gas_range(findings, gas.clone().into(), 40, 75, "staking_pool::stake");
+        }
+        if let Some(gas) = config.unstake.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                40,
+                75,
+                "staking_pool::unstake",
+            );
+        }
+        if let Some(gas) = config.withdraw.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                40,
+                75,
+                "staking_pool::withdraw",
+            );
         }
     }
 }
@@ -291,70 +1219,291 @@ pub struct CallBacksGasConfig {
     resolve_transfer_gas: Gas,
 
     on_refresh_stake_token_value: Gas,
+    on_refresh_reward_fee_fraction: Gas,
+
+    /// used by the redeem forwarding workflow
+    /// - gas attached to the `deposit_redeem_forwarding` call on the target STAKE contract
+    deposit_redeem_forwarding: Gas,
+    on_redeem_forwarding: Gas,
+
+    /// used by the swap intent workflow
+    /// - gas attached to the `stake_token_value` call on the target STAKE contract
+    swap_stake_token_value: Gas,
+    on_swap_intent_fill: Gas,
+    /// gas attached to the `ft_transfer` call on the target STAKE contract
+    swap_settlement_transfer: Gas,
+    on_swap_settlement: Gas,
+
+    /// used by the wNEAR deposit workflow
+    /// - gas attached to the `near_withdraw` call on the wNEAR contract
+    wrap_near_withdraw: Gas,
+    on_wrap_near_withdraw: Gas,
+
+    /// used by the staking pool whitelist check
+    /// - gas attached to the `is_whitelisted` call on the staking pool whitelist contract
+    staking_pool_whitelist_check: Gas,
+    on_staking_pool_whitelist_check: Gas,
+
+    /// used by [release_stale_lock](crate::interface::Operator::release_stale_lock)
+    on_release_stale_lock: Gas,
 }
 
 impl CallBacksGasConfig {
+    /// ## Panics
+    /// if validate is true and validation fails
     pub fn merge(&mut self, config: interface::CallBacksGasConfig, validate: bool) {
+        if validate {
+            let mut findings = vec![];
+            Self::validate(&config, &mut findings);
+            assert_no_findings(findings);
+        }
         if let Some(gas) = config.on_run_stake_batch {
-            let gas = gas.into();
-            if validate {
-                assert_gas_range(gas, 70, 150, "callbacks::on_run_stake_batch");
-            }
-            self.on_run_stake_batch = gas;
+            self.on_run_stake_batch = gas.into();
         }
         if let Some(gas) = config.on_deposit_and_stake {
-            let gas = gas.into();
-            if validate {
-                assert_gas_range(gas, 5, 10, "callbacks::on_deposit_and_stake");
-            }
-            self.on_deposit_and_stake = gas;
+            self.on_deposit_and_stake = gas.into();
         }
         if let Some(gas) = config.on_unstake {
-            let gas = gas.into();
-            if validate {
-                assert_gas_range(gas, 5, 10, "callbacks::on_unstake");
-            }
-            self.on_unstake = gas;
+            self.on_unstake = gas.into();
         }
         if let Some(gas) = config.unlock {
-            let gas = gas.into();
-            if validate {
-                assert_gas_range(gas, 5, 10, "callbacks::unlock");
-            }
-            self.unlock = gas;
+            self.unlock = gas.into();
         }
         if let Some(gas) = config.on_run_redeem_stake_batch {
-            let gas = gas.into();
-            if validate {
-                assert_gas_range(gas, 70, 100, "callbacks::on_run_redeem_stake_batch");
-            }
-            self.on_run_redeem_stake_batch = gas;
+            self.on_run_redeem_stake_batch = gas.into();
         }
         if let Some(gas) = config.on_redeeming_stake_pending_withdrawal {
-            let gas = gas.into();
-            if validate {
-                assert_gas_range(
-                    gas,
-                    70,
-                    100,
-                    "callbacks::on_redeeming_stake_pending_withdrawal",
-                );
-            }
-            self.on_redeeming_stake_pending_withdrawal = gas;
+            self.on_redeeming_stake_pending_withdrawal = gas.into();
         }
         if let Some(gas) = config.on_redeeming_stake_post_withdrawal {
-            let gas = gas.into();
-            if validate {
-                assert_gas_range(gas, 5, 10, "callbacks::on_redeeming_stake_post_withdrawal");
-            }
-            self.on_redeeming_stake_post_withdrawal = gas;
+            self.on_redeeming_stake_post_withdrawal = gas.into();
         }
         if let Some(gas) = config.resolve_transfer_gas {
-            let gas = gas.into();
-            if validate {
-                assert_gas_range(gas, 5, 20, "callbacks::resolve_transfer_gas");
+            self.resolve_transfer_gas = gas.into();
+        }
+        if let Some(gas) = config.on_refresh_reward_fee_fraction {
+            self.on_refresh_reward_fee_fraction = gas.into();
+        }
+        if let Some(gas) = config.deposit_redeem_forwarding {
+            self.deposit_redeem_forwarding = gas.into();
+        }
+        if let Some(gas) = config.on_redeem_forwarding {
+            self.on_redeem_forwarding = gas.into();
+        }
+        if let Some(gas) = config.swap_stake_token_value {
+            self.swap_stake_token_value = gas.into();
+        }
+        if let Some(gas) = config.on_swap_intent_fill {
+            self.on_swap_intent_fill = gas.into();
+        }
+        if let Some(gas) = config.swap_settlement_transfer {
+            self.swap_settlement_transfer = gas.into();
+        }
+        if let Some(gas) = config.on_swap_settlement {
+            self.on_swap_settlement = gas.into();
+        }
+        if let Some(gas) = config.wrap_near_withdraw {
+            self.wrap_near_withdraw = gas.into();
+        }
+        if let Some(gas) = config.on_wrap_near_withdraw {
+            self.on_wrap_near_withdraw = gas.into();
+        }
+        if let Some(gas) = config.staking_pool_whitelist_check {
+            self.staking_pool_whitelist_check = gas.into();
+        }
+        if let Some(gas) = config.on_staking_pool_whitelist_check {
+            self.on_staking_pool_whitelist_check = gas.into();
+        }
+        if let Some(gas) = config.on_release_stale_lock {
+            self.on_release_stale_lock = gas.into();
+        }
+    }
+
+    /// checks the proposed gas values against their bounds
+    fn validate(config: &interface::CallBacksGasConfig, findings: &mut Vec<ConfigFinding>) {
+        if let Some(gas) = config.on_run_stake_batch.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                70,
+                150,
+                "callbacks::on_run_stake_batch",
+            );
+        }
+        if let Some(gas) = config.on_deposit_and_stake.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                5,
+                10,
+                "callbacks::on_deposit_and_stake",
+            );
+        }
+        if let Some(gas) = config.on_unstake.as_ref() {
+            check_gas_range(findings, gas.clone().into(), 5, 10, "callbacks::on_unstake");
+        }
+        if let Some(gas) = config.unlock.as_ref() {
+            check_gas_range(findings, gas.clone().into(), 5, 10, "callbacks::unlock");
+        }
+        if let Some(gas) = config.on_run_redeem_stake_batch.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                70,
+                100,
+                "callbacks::on_run_redeem_stake_batch",
+            );
+        }
+        if let Some(gas) = config.on_redeeming_stake_pending_withdrawal.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                70,
+                100,
+                "callbacks::on_redeeming_stake_pending_withdrawal",
+            );
+        }
+        if let Some(gas) = config.on_redeeming_stake_post_withdrawal.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                5,
+                10,
+                "callbacks::on_redeeming_stake_post_withdrawal",
+            );
+        }
+        if let Some(gas) = config.resolve_transfer_gas.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                5,
+                20,
+                "callbacks::resolve_transfer_gas",
+            );
+        }
+        if let Some(gas) = config.on_refresh_reward_fee_fraction.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                5,
+                10,
+                "callbacks::on_refresh_reward_fee_fraction",
+            );
+        }
+        if let Some(gas) = config.deposit_redeem_forwarding.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                10,
+                50,
+                "callbacks::deposit_redeem_forwarding",
+            );
+        }
+        if let Some(gas) = config.on_redeem_forwarding.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                5,
+                20,
+                "callbacks::on_redeem_forwarding",
+            );
+        }
+        if let Some(gas) = config.swap_stake_token_value.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                5,
+                10,
+                "callbacks::swap_stake_token_value",
+            );
+        }
+        if let Some(gas) = config.on_swap_intent_fill.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                30,
+                60,
+                "callbacks::on_swap_intent_fill",
+            );
+        }
+        if let Some(gas) = config.swap_settlement_transfer.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                10,
+                30,
+                "callbacks::swap_settlement_transfer",
+            );
+        }
+        if let Some(gas) = config.on_swap_settlement.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                5,
+                20,
+                "callbacks::on_swap_settlement",
+            );
+        }
+        if let Some(gas) = config.wrap_near_withdraw.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                5,
+                20,
+                "callbacks::wrap_near_withdraw",
+            );
+        }
+        if let Some(gas) = config.on_wrap_near_withdraw.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                10,
+                40,
+                "callbacks::on_wrap_near_withdraw",
+            );
+        }
+        if let Some(gas) = config.staking_pool_whitelist_check.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                5,
+                20,
+                "callbacks::staking_pool_whitelist_check",
+            );
+        }
+        if let Some(gas) = config.on_staking_pool_whitelist_check.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                5,
+                20,
+                "callbacks::on_staking_pool_whitelist_check",
+            );
+        }
+        if let Some(gas) = config.on_release_stale_lock.as_ref() {
+            check_gas_range(
+                findings,
+                gas.clone().into(),
+                5,
+                20,
+                "callbacks::on_release_stale_lock",
+            );
+        }
+    }
+
+    /// returns the gas attached to the specified staking pool workflow callback
+    /// - `on_stake_batch_deposited` is attached the `on_deposit_and_stake` gas
+    pub fn gas(&self, callback: &str) -> Option<Gas> {
+        match callback {
+            "on_run_stake_batch" => Some(self.on_run_stake_batch),
+            "on_deposit_and_stake" | "on_stake_batch_deposited" => Some(self.on_deposit_and_stake),
+            "on_run_redeem_stake_batch" => Some(self.on_run_redeem_stake_batch),
+            "on_unstake" => Some(self.on_unstake),
+            "on_redeeming_stake_pending_withdrawal" => {
+                Some(self.on_redeeming_stake_pending_withdrawal)
             }
-            self.resolve_transfer_gas = gas;
+            "on_redeeming_stake_post_withdrawal" => Some(self.on_redeeming_stake_post_withdrawal),
+            _ => None,
         }
     }
 
@@ -393,6 +1542,54 @@ impl CallBacksGasConfig {
     pub fn on_refresh_stake_token_value(&self) -> Gas {
         self.on_refresh_stake_token_value
     }
+
+    pub fn on_refresh_reward_fee_fraction(&self) -> Gas {
+        self.on_refresh_reward_fee_fraction
+    }
+
+    pub fn deposit_redeem_forwarding(&self) -> Gas {
+        self.deposit_redeem_forwarding
+    }
+
+    pub fn on_redeem_forwarding(&self) -> Gas {
+        self.on_redeem_forwarding
+    }
+
+    pub fn swap_stake_token_value(&self) -> Gas {
+        self.swap_stake_token_value
+    }
+
+    pub fn on_swap_intent_fill(&self) -> Gas {
+        self.on_swap_intent_fill
+    }
+
+    pub fn wrap_near_withdraw(&self) -> Gas {
+        self.wrap_near_withdraw
+    }
+
+    pub fn on_wrap_near_withdraw(&self) -> Gas {
+        self.on_wrap_near_withdraw
+    }
+
+    pub fn staking_pool_whitelist_check(&self) -> Gas {
+        self.staking_pool_whitelist_check
+    }
+
+    pub fn on_staking_pool_whitelist_check(&self) -> Gas {
+        self.on_staking_pool_whitelist_check
+    }
+
+    pub fn on_release_stale_lock(&self) -> Gas {
+        self.on_release_stale_lock
+    }
+
+    pub fn swap_settlement_transfer(&self) -> Gas {
+        self.swap_settlement_transfer
+    }
+
+    pub fn on_swap_settlement(&self) -> Gas {
+        self.on_swap_settlement
+    }
 }
 
 impl Default for CallBacksGasConfig {
@@ -411,6 +1608,122 @@ impl Default for CallBacksGasConfig {
             resolve_transfer_gas: TGAS * 10,
 
             on_refresh_stake_token_value: TGAS * 15,
+            on_refresh_reward_fee_fraction: TGAS * 5,
+
+            deposit_redeem_forwarding: TGAS * 25,
+            on_redeem_forwarding: TGAS * 10,
+
+            swap_stake_token_value: TGAS * 5,
+            on_swap_intent_fill: TGAS * 40,
+            swap_settlement_transfer: TGAS * 15,
+            on_swap_settlement: TGAS * 10,
+
+            wrap_near_withdraw: TGAS * 10,
+            on_wrap_near_withdraw: TGAS * 20,
+
+            staking_pool_whitelist_check: TGAS * 5,
+            on_staking_pool_whitelist_check: TGAS * 10,
+
+            on_release_stale_lock: TGAS * 10,
+        }
+    }
+}
+
+/// [Config] layout that was persisted by state version 1
+/// - config that was introduced after state version 1 is initialized to the defaults
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Default)]
+pub struct ConfigV1 {
+    pub storage_cost_per_byte: YoctoNear,
+    pub gas_config: GasConfigV1,
+    pub contract_owner_earnings_percentage: u8,
+}
+
+impl From<ConfigV1> for Config {
+    fn from(config: ConfigV1) -> Self {
+        Self {
+            storage_cost_per_byte: config.storage_cost_per_byte,
+            gas_config: config.gas_config.into(),
+            contract_owner_earnings_percentage: config.contract_owner_earnings_percentage,
+            ..Self::default()
+        }
+    }
+}
+
+/// [GasConfig] layout that was persisted by state version 1
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Default)]
+pub struct GasConfigV1 {
+    pub staking_pool: StakingPoolGasConfigV1,
+    pub callbacks: CallBacksGasConfigV1,
+    pub function_call_promise: Gas,
+    pub function_call_promise_data_dependency: Gas,
+}
+
+impl From<GasConfigV1> for GasConfig {
+    fn from(config: GasConfigV1) -> Self {
+        Self {
+            staking_pool: config.staking_pool.into(),
+            callbacks: config.callbacks.into(),
+            function_call_promise: config.function_call_promise,
+            function_call_promise_data_dependency: config.function_call_promise_data_dependency,
+            ..Self::default()
+        }
+    }
+}
+
+/// [StakingPoolGasConfig] layout that was persisted by state version 1
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Default)]
+pub struct StakingPoolGasConfigV1 {
+    pub deposit_and_stake: Gas,
+    pub deposit: Gas,
+    pub stake: Gas,
+    pub unstake: Gas,
+    pub withdraw: Gas,
+    pub get_account: Gas,
+    pub ping: Gas,
+}
+
+impl From<StakingPoolGasConfigV1> for StakingPoolGasConfig {
+    fn from(config: StakingPoolGasConfigV1) -> Self {
+        Self {
+            deposit_and_stake: config.deposit_and_stake,
+            deposit: config.deposit,
+            stake: config.stake,
+            unstake: config.unstake,
+            withdraw: config.withdraw,
+            get_account: config.get_account,
+            ping: config.ping,
+            ..Self::default()
+        }
+    }
+}
+
+/// [CallBacksGasConfig] layout that was persisted by state version 1
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Default)]
+pub struct CallBacksGasConfigV1 {
+    pub on_run_stake_batch: Gas,
+    pub on_deposit_and_stake: Gas,
+    pub on_unstake: Gas,
+    pub unlock: Gas,
+    pub on_run_redeem_stake_batch: Gas,
+    pub on_redeeming_stake_pending_withdrawal: Gas,
+    pub on_redeeming_stake_post_withdrawal: Gas,
+    pub resolve_transfer_gas: Gas,
+    pub on_refresh_stake_token_value: Gas,
+}
+
+impl From<CallBacksGasConfigV1> for CallBacksGasConfig {
+    fn from(config: CallBacksGasConfigV1) -> Self {
+        Self {
+            on_run_stake_batch: config.on_run_stake_batch,
+            on_deposit_and_stake: config.on_deposit_and_stake,
+            on_unstake: config.on_unstake,
+            unlock: config.unlock,
+            on_run_redeem_stake_batch: config.on_run_redeem_stake_batch,
+            on_redeeming_stake_pending_withdrawal: config.on_redeeming_stake_pending_withdrawal,
+            on_redeeming_stake_post_withdrawal: config.on_redeeming_stake_post_withdrawal,
+            resolve_transfer_gas: config.resolve_transfer_gas,
+            on_refresh_stake_token_value: config.on_refresh_stake_token_value,
+            ..Self::default()
         }
     }
 }
@@ -434,6 +1747,18 @@ mod test {
                 on_redeeming_stake_post_withdrawal: Some((TGAS * 9).into()),
                 resolve_transfer_gas: Some((TGAS * 10).into()),
                 refresh_stake_token_value: Some((TGAS * 15).into()),
+                on_refresh_reward_fee_fraction: Some((TGAS * 6).into()),
+                deposit_redeem_forwarding: Some((TGAS * 30).into()),
+                on_redeem_forwarding: Some((TGAS * 11).into()),
+                swap_stake_token_value: Some((TGAS * 6).into()),
+                on_swap_intent_fill: Some((TGAS * 45).into()),
+                swap_settlement_transfer: Some((TGAS * 20).into()),
+                on_swap_settlement: Some((TGAS * 12).into()),
+                wrap_near_withdraw: Some((TGAS * 11).into()),
+                on_wrap_near_withdraw: Some((TGAS * 21).into()),
+                staking_pool_whitelist_check: Some((TGAS * 6).into()),
+                on_staking_pool_whitelist_check: Some((TGAS * 11).into()),
+                on_release_stale_lock: Some((TGAS * 12).into()),
             },
             true,
         );
@@ -444,6 +1769,18 @@ mod test {
         assert_eq!(config.on_run_redeem_stake_batch, TGAS * 72);
         assert_eq!(config.on_redeeming_stake_pending_withdrawal, TGAS * 73);
         assert_eq!(config.on_redeeming_stake_post_withdrawal, TGAS * 9);
+        assert_eq!(config.on_refresh_reward_fee_fraction, TGAS * 6);
+        assert_eq!(config.deposit_redeem_forwarding, TGAS * 30);
+        assert_eq!(config.on_redeem_forwarding, TGAS * 11);
+        assert_eq!(config.swap_stake_token_value, TGAS * 6);
+        assert_eq!(config.on_swap_intent_fill, TGAS * 45);
+        assert_eq!(config.swap_settlement_transfer, TGAS * 20);
+        assert_eq!(config.on_swap_settlement, TGAS * 12);
+        assert_eq!(config.wrap_near_withdraw, TGAS * 11);
+        assert_eq!(config.on_wrap_near_withdraw, TGAS * 21);
+        assert_eq!(config.staking_pool_whitelist_check, TGAS * 6);
+        assert_eq!(config.on_staking_pool_whitelist_check, TGAS * 11);
+        assert_eq!(config.on_release_stale_lock, TGAS * 12);
     }
 
     #[test]
@@ -457,6 +1794,7 @@ mod test {
                 unstake: Some((TGAS * 72).into()),
                 withdraw: Some((TGAS * 73).into()),
                 get_account: Some((TGAS * 7).into()),
+                get_reward_fee_fraction: Some((TGAS * 8).into()),
                 ping: Some((TGAS * 74).into()),
             },
             true,
@@ -465,5 +1803,62 @@ mod test {
         assert_eq!(config.unstake, TGAS * 72);
         assert_eq!(config.withdraw, TGAS * 73);
         assert_eq!(config.get_account, TGAS * 7);
+        assert_eq!(config.get_reward_fee_fraction, TGAS * 8);
+    }
+
+    #[test]
+    fn auto_tune_staking_pool_gas() {
+        let mut config = GasConfig::default();
+        assert_eq!(config.staking_pool.deposit_and_stake, TGAS * 45);
+
+        // moves toward the target by at most the max step
+        assert_eq!(
+            config.auto_tune_staking_pool_gas("deposit_and_stake", TGAS * 60, 25),
+            Some(TGAS * 50)
+        );
+        assert_eq!(config.staking_pool.deposit_and_stake, TGAS * 50);
+        // the target is max burned + 25% = 75 TGas
+        for _ in 0..10 {
+            config.auto_tune_staking_pool_gas("deposit_and_stake", TGAS * 60, 25);
+        }
+        assert_eq!(config.staking_pool.deposit_and_stake, TGAS * 75);
+
+        // tuned down, but never below the validation min
+        for _ in 0..10 {
+            config.auto_tune_staking_pool_gas("deposit_and_stake", TGAS * 10, 25);
+        }
+        assert_eq!(config.staking_pool.deposit_and_stake, TGAS * 40);
+        assert_eq!(
+            config.auto_tune_staking_pool_gas("deposit_and_stake", TGAS * 10, 25),
+            None
+        );
+
+        // ping gas is not validated, thus it is not auto-tuned
+        assert_eq!(config.auto_tune_staking_pool_gas("ping", TGAS, 25), None);
+        assert_eq!(config.auto_tune_staking_pool_gas("unknown", TGAS, 25), None);
+    }
+
+    #[test]
+    fn auto_tune_staking_pool_gas_leaves_gas_for_callbacks() {
+        let mut config = GasConfig::default();
+        config.callbacks.on_run_redeem_stake_batch = TGAS * 70;
+        // 70 - on_unstake (5) - 5 = 60 TGas
+        for _ in 0..10 {
+            config.auto_tune_staking_pool_gas("unstake", TGAS * 100, 25);
+        }
+        assert_eq!(config.staking_pool.unstake, TGAS * 60);
+    }
+
+    #[test]
+    fn auto_tune_disabled_by_default() {
+        let mut config = Config::default();
+        assert_eq!(
+            config.auto_tune_staking_pool_gas("deposit_and_stake", TGAS * 60),
+            None
+        );
+        assert_eq!(
+            config.gas_config().staking_pool().deposit_and_stake(),
+            TGAS * 45
+        );
     }
 }
@@ -1,18 +1,45 @@
+pub mod access_control;
+pub mod account_activity_logs;
 pub mod account_management;
+pub mod batch_history;
+pub mod batch_reservations;
+pub mod batch_totals;
 pub mod contract_owner;
+pub mod exit_positions;
+pub mod feature_flags;
 pub mod financials;
 mod fungible_token;
+pub mod governance;
+pub mod idle_near_sharing;
+pub mod invoices;
+pub mod liquidity_providers;
+pub mod lockups;
 pub mod metadata;
+pub mod migration;
 pub mod operator;
+pub mod pending_transfers;
+pub mod pool_preferences;
+pub mod rebates;
+pub mod receiver_authorization;
+pub mod redeem_splits;
 pub mod redeeming_workflow_callbacks;
+pub mod referrals;
+pub mod scheduled_actions;
 pub(crate) mod staking_pool;
+pub mod staking_pools;
 pub mod staking_service;
 pub mod staking_workflow_callbacks;
+pub mod storage_refunds;
+pub mod swap_intents;
+pub mod treasury;
+pub mod wrap_near;
 
 pub use staking_service::*;
 
+use crate::errors::access_control::OPERATOR_ACTION_REQUIRES_CONFIRMATIONS;
 use crate::errors::asserts::{
-    PREDECESSOR_MUST_BE_OPERATOR, PREDECESSOR_MUST_BE_OWNER, PREDECESSOR_MUST_NE_SELF_OR_OPERATOR,
+    CONTRACT_PAUSED, PREDECESSOR_MUST_BE_GOVERNANCE, PREDECESSOR_MUST_BE_OPERATOR,
+    PREDECESSOR_MUST_BE_OWNER, PREDECESSOR_MUST_NE_SELF_OR_OPERATOR,
 };
 use crate::Contract;
 use near_sdk::{env, PromiseResult};
@@ -22,20 +49,34 @@ impl Contract {
         let predecessor_account_id = env::predecessor_account_id();
         assert!(
             predecessor_account_id == env::current_account_id()
-                || predecessor_account_id == self.operator_id,
+                || self.is_operator(&predecessor_account_id),
             PREDECESSOR_MUST_NE_SELF_OR_OPERATOR
         );
     }
 
     pub fn assert_predecessor_is_operator(&self) {
-        assert_eq!(
-            env::predecessor_account_id(),
-            self.operator_id,
-            "{}",
+        assert!(
+            self.is_operator(&env::predecessor_account_id()),
             PREDECESSOR_MUST_BE_OPERATOR
         );
     }
 
+    /// sensitive operator actions, i.e., config changes, changing the staking pool, and pausing
+    /// the contract, must be proposed and confirmed via [AccessControl](crate::interface::AccessControl)
+    /// once more than 1 operator confirmation is required
+    pub fn assert_predecessor_can_run_sensitive_operator_action(&self) {
+        self.assert_predecessor_is_operator();
+        assert!(
+            self.operator_required_confirmations <= 1,
+            OPERATOR_ACTION_REQUIRES_CONFIRMATIONS
+        );
+    }
+
+    /// returns true if the account is the primary operator account or was granted the operator role
+    pub fn is_operator(&self, account_id: &str) -> bool {
+        self.operator_id == account_id || self.operators.iter().any(|id| id == account_id)
+    }
+
     pub fn assert_predecessor_is_owner(&self) {
         assert_eq!(
             env::predecessor_account_id(),
@@ -45,6 +86,33 @@ impl Contract {
         );
     }
 
+    pub fn assert_predecessor_is_governance(&self) {
+        assert_eq!(
+            Some(env::predecessor_account_id()),
+            self.governance_id,
+            "{}",
+            PREDECESSOR_MUST_BE_GOVERNANCE
+        );
+    }
+
+    /// when a governance DAO account is set, then config changes are only accepted from the DAO -
+    /// otherwise the config is managed by the operator
+    pub fn assert_predecessor_can_update_config(&self) {
+        if self.governance_id.is_some() {
+            self.assert_predecessor_is_governance();
+        } else {
+            self.assert_predecessor_can_run_sensitive_operator_action();
+        }
+    }
+
+    /// guards the methods that move funds, i.e., deposit, stake, redeem, unstake, transfers, and
+    /// withdrawals - see [pause](crate::interface::Operator::pause)
+    pub fn assert_not_paused(&self) {
+        if let Some(pause) = self.pause.as_ref() {
+            panic!("{}: {}", CONTRACT_PAUSED, pause.reason());
+        }
+    }
+
     pub fn stake_batch_locked(&self) -> bool {
         self.stake_batch_lock.is_some()
     }
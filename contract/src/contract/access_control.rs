@@ -0,0 +1,431 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::domain::{self, MAX_OPERATORS, MAX_PENDING_OPERATOR_PROPOSALS};
+use crate::errors::access_control::{
+    CONFIG_MANAGED_BY_GOVERNANCE, INVALID_REQUIRED_CONFIRMATIONS, MAX_OPERATORS_EXCEEDED,
+    MAX_PENDING_OPERATOR_PROPOSALS_EXCEEDED, OPERATOR_ALREADY_ADDED, OPERATOR_MUST_BE_REGISTERED,
+    OPERATOR_NOT_ADDED, OPERATOR_PROPOSAL_ALREADY_CONFIRMED,
+    OPERATOR_PROPOSAL_CANCEL_ACCESS_DENIED, OPERATOR_PROPOSAL_NOT_CONFIRMED,
+    OPERATOR_PROPOSAL_NOT_FOUND,
+};
+use crate::errors::asserts::OPERATOR_ID_MUST_NOT_BE_CONTRACT_ID;
+use crate::interface::access_control::events::{
+    OperatorActionCancelled, OperatorActionConfirmed, OperatorActionExecuted,
+    OperatorActionProposed, OperatorAdded, OperatorRemoved, RequiredConfirmationsChanged,
+};
+use crate::interface::{AccessControl, AccountManagement, OperatorAction, OperatorProposal};
+use crate::near::log;
+use crate::*;
+use near_sdk::{
+    json_types::{ValidAccountId, U64},
+    near_bindgen, serde_json,
+};
+
+#[near_bindgen]
+impl AccessControl for Contract {
+    fn operators(&self) -> Vec<AccountId> {
+        std::iter::once(self.operator_id.clone())
+            .chain(
+                self.operators
+                    .iter()
+                    .filter(|account_id| **account_id != self.operator_id)
+                    .cloned(),
+            )
+            .collect()
+    }
+
+    fn add_operator(&mut self, account_id: ValidAccountId) {
+        self.assert_predecessor_is_owner();
+        assert!(
+            !self.is_operator(account_id.as_ref()),
+            OPERATOR_ALREADY_ADDED
+        );
+        assert!(
+            account_id.as_ref() != &env::current_account_id(),
+            OPERATOR_ID_MUST_NOT_BE_CONTRACT_ID
+        );
+        assert!(
+            self.account_registered(account_id.clone()),
+            OPERATOR_MUST_BE_REGISTERED
+        );
+        assert!(self.operators.len() < MAX_OPERATORS, MAX_OPERATORS_EXCEEDED);
+
+        self.operators.push(account_id.as_ref().to_string());
+        log(OperatorAdded {
+            account_id: account_id.as_ref(),
+        });
+    }
+
+    fn remove_operator(&mut self, account_id: ValidAccountId) {
+        self.assert_predecessor_is_owner();
+        let index = self
+            .operators
+            .iter()
+            .position(|id| id == account_id.as_ref())
+            .expect(OPERATOR_NOT_ADDED);
+        self.operators.remove(index);
+        assert!(
+            self.operator_required_confirmations as usize <= self.operators().len(),
+            INVALID_REQUIRED_CONFIRMATIONS
+        );
+
+        log(OperatorRemoved {
+            account_id: account_id.as_ref(),
+        });
+    }
+
+    fn required_confirmations(&self) -> u8 {
+        self.operator_required_confirmations
+    }
+
+    fn set_required_confirmations(&mut self, count: u8) {
+        self.assert_predecessor_is_owner();
+        assert!(
+            count > 0 && count as usize <= self.operators().len(),
+            INVALID_REQUIRED_CONFIRMATIONS
+        );
+
+        log(RequiredConfirmationsChanged {
+            from: self.operator_required_confirmations,
+            to: count,
+        });
+        self.operator_required_confirmations = count;
+    }
+
+    fn propose_operator_action(&mut self, action: OperatorAction) -> OperatorProposal {
+        self.assert_predecessor_is_operator();
+        assert!(
+            self.operator_proposal_ids.len() < MAX_PENDING_OPERATOR_PROPOSALS,
+            MAX_PENDING_OPERATOR_PROPOSALS_EXCEEDED
+        );
+        let action = self.check_operator_action(action);
+
+        self.operator_proposal_id_sequence += 1;
+        let proposal_id = self.operator_proposal_id_sequence;
+        let proposer = env::predecessor_account_id();
+        let proposal = domain::OperatorProposal::new(action, &proposer);
+        self.operator_proposals.insert(&proposal_id, &proposal);
+        self.operator_proposal_ids.push(proposal_id);
+
+        log(OperatorActionProposed {
+            proposal_id,
+            proposer: &proposer,
+        });
+        OperatorProposal::from(proposal_id, &proposal, self.operator_required_confirmations)
+    }
+
+    fn confirm_operator_action(&mut self, proposal_id: U64) -> OperatorProposal {
+        self.assert_predecessor_is_operator();
+        let mut proposal = self
+            .operator_proposals
+            .get(&proposal_id.0)
+            .expect(OPERATOR_PROPOSAL_NOT_FOUND);
+        let operator = env::predecessor_account_id();
+        assert!(
+            !proposal.confirmed_by(&operator),
+            OPERATOR_PROPOSAL_ALREADY_CONFIRMED
+        );
+
+        proposal.confirm(&operator);
+        self.operator_proposals.insert(&proposal_id.0, &proposal);
+        log(OperatorActionConfirmed {
+            proposal_id: proposal_id.0,
+            operator: &operator,
+            confirmations: proposal.confirmation_count(|account_id| self.is_operator(account_id)),
+        });
+        OperatorProposal::from(
+            proposal_id.0,
+            &proposal,
+            self.operator_required_confirmations,
+        )
+    }
+
+    fn execute_operator_action(&mut self, proposal_id: U64) -> OperatorProposal {
+        self.assert_predecessor_is_operator();
+        let proposal = self
+            .operator_proposals
+            .get(&proposal_id.0)
+            .expect(OPERATOR_PROPOSAL_NOT_FOUND);
+        assert!(
+            proposal.confirmation_count(|account_id| self.is_operator(account_id))
+                >= self.operator_required_confirmations as usize,
+            OPERATOR_PROPOSAL_NOT_CONFIRMED
+        );
+
+        self.remove_operator_proposal(proposal_id.0);
+        self.run_operator_action(proposal.action().clone());
+        log(OperatorActionExecuted {
+            proposal_id: proposal_id.0,
+            executor: &env::predecessor_account_id(),
+        });
+        OperatorProposal::from(
+            proposal_id.0,
+            &proposal,
+            self.operator_required_confirmations,
+        )
+    }
+
+    fn cancel_operator_action(&mut self, proposal_id: U64) -> OperatorProposal {
+        let proposal = self
+            .operator_proposals
+            .get(&proposal_id.0)
+            .expect(OPERATOR_PROPOSAL_NOT_FOUND);
+        let predecessor_account_id = env::predecessor_account_id();
+        assert!(
+            predecessor_account_id == proposal.proposer()
+                || predecessor_account_id == self.owner_id,
+            OPERATOR_PROPOSAL_CANCEL_ACCESS_DENIED
+        );
+
+        self.remove_operator_proposal(proposal_id.0);
+        log(OperatorActionCancelled {
+            proposal_id: proposal_id.0,
+            cancelled_by: &predecessor_account_id,
+        });
+        OperatorProposal::from(
+            proposal_id.0,
+            &proposal,
+            self.operator_required_confirmations,
+        )
+    }
+
+    fn operator_proposal(&self, proposal_id: U64) -> Option<OperatorProposal> {
+        self.operator_proposals.get(&proposal_id.0).map(|proposal| {
+            OperatorProposal::from(
+                proposal_id.0,
+                &proposal,
+                self.operator_required_confirmations,
+            )
+        })
+    }
+
+    fn pending_operator_proposals(&self) -> Vec<OperatorProposal> {
+        self.operator_proposal_ids
+            .iter()
+            .filter_map(|proposal_id| self.operator_proposal((*proposal_id).into()))
+            .collect()
+    }
+}
+
+impl Contract {
+    /// checks the action before it is proposed in order to fail fast
+    fn check_operator_action(&self, action: OperatorAction) -> domain::OperatorAction {
+        match action {
+            OperatorAction::UpdateConfig {
+                config,
+                validation_hash,
+            } => {
+                self.assert_config_managed_by_operators();
+                self.assert_config_validation_hash(&config, &validation_hash);
+                domain::OperatorAction::UpdateConfig(
+                    serde_json::to_string(&config).unwrap(),
+                    validation_hash.0,
+                )
+            }
+            OperatorAction::ForceUpdateConfig { config } => {
+                self.assert_config_managed_by_operators();
+                domain::OperatorAction::ForceUpdateConfig(serde_json::to_string(&config).unwrap())
+            }
+            OperatorAction::ChangeStakingPool { account_id } => {
+                self.check_staking_pool_change(account_id.as_ref());
+                domain::OperatorAction::ChangeStakingPool(account_id.into())
+            }
+            OperatorAction::Pause { reason } => {
+                domain::OperatorAction::Pause(Self::check_pause_reason(reason))
+            }
+            OperatorAction::Resume => domain::OperatorAction::Resume,
+        }
+    }
+
+    fn run_operator_action(&mut self, action: domain::OperatorAction) {
+        match OperatorAction::from(&action) {
+            OperatorAction::UpdateConfig {
+                config,
+                validation_hash,
+            } => {
+                self.assert_config_managed_by_operators();
+                // the config may have changed since the action was proposed
+                self.assert_config_validation_hash(&config, &validation_hash);
+                self.apply_config_change(config, true);
+            }
+            OperatorAction::ForceUpdateConfig { config } => {
+                self.assert_config_managed_by_operators();
+                self.apply_config_change(config, false);
+            }
+            OperatorAction::ChangeStakingPool { account_id } => {
+                // if the staking pool whitelist is checked, then the staking pool is changed via
+                // the whitelist check callback
+                let _ = self.change_staking_pool_account(account_id.into());
+            }
+            OperatorAction::Pause { reason } => self.pause_contract(reason),
+            OperatorAction::Resume => self.resume_contract(),
+        }
+    }
+
+    fn assert_config_managed_by_operators(&self) {
+        assert!(self.governance_id.is_none(), CONFIG_MANAGED_BY_GOVERNANCE);
+    }
+
+    fn remove_operator_proposal(&mut self, proposal_id: u64) {
+        self.operator_proposals.remove(&proposal_id);
+        self.operator_proposal_ids.retain(|id| *id != proposal_id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::Operator;
+    use crate::test_utils::*;
+    use near_sdk::{serde_json, testing_env, MockedBlockchain};
+
+    const ALICE: &str = "alice.near";
+    const BOB: &str = "bob.near";
+
+    /// registers 2 additional operators, i.e., 3 operators in total, and requires 2 confirmations
+    fn setup() -> TestContext<'static> {
+        let mut ctx = TestContext::new();
+        ctx.register_account(ALICE);
+        ctx.register_account(BOB);
+        let context = ctx.set_predecessor_account_id(TEST_OWNER_ID);
+        testing_env!(context);
+        ctx.add_operator(to_valid_account_id(ALICE));
+        ctx.add_operator(to_valid_account_id(BOB));
+        ctx.set_required_confirmations(2);
+        ctx
+    }
+
+    fn set_predecessor(ctx: &mut TestContext, account_id: &str) {
+        let context = ctx.set_predecessor_account_id(account_id);
+        testing_env!(context);
+    }
+
+    #[test]
+    fn operators() {
+        let mut ctx = setup();
+        assert_eq!(ctx.operators(), vec![TEST_OPERATOR_ID, ALICE, BOB]);
+        assert_eq!(ctx.required_confirmations(), 2);
+
+        // additional operators can perform operator actions that do not require confirmations
+        set_predecessor(&mut ctx, ALICE);
+        ctx.whitelist_auto_registration(to_valid_account_id(BOB));
+
+        set_predecessor(&mut ctx, TEST_OWNER_ID);
+        ctx.remove_operator(to_valid_account_id(BOB));
+        assert_eq!(ctx.operators(), vec![TEST_OPERATOR_ID, ALICE]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "required confirmations must be at least 1 and must not exceed the number of operators"
+    )]
+    fn remove_operator_below_required_confirmations() {
+        let mut ctx = setup();
+        ctx.set_required_confirmations(3);
+        ctx.remove_operator(to_valid_account_id(BOB));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "operator action requires multiple operator confirmations - it must be proposed"
+    )]
+    fn sensitive_action_requires_confirmations() {
+        let mut ctx = setup();
+        set_predecessor(&mut ctx, TEST_OPERATOR_ID);
+        ctx.pause("incident".to_string());
+    }
+
+    #[test]
+    fn propose_confirm_execute() {
+        let mut ctx = setup();
+        set_predecessor(&mut ctx, ALICE);
+        let proposal = ctx.propose_operator_action(OperatorAction::Pause {
+            reason: "incident".to_string(),
+        });
+        assert_eq!(proposal.id, 1.into());
+        assert_eq!(proposal.confirmations, vec![ALICE]);
+        assert_eq!(proposal.required_confirmations, 2);
+        assert_eq!(ctx.pending_operator_proposals().len(), 1);
+
+        set_predecessor(&mut ctx, BOB);
+        let proposal = ctx.confirm_operator_action(proposal.id);
+        assert_eq!(proposal.confirmations, vec![ALICE, BOB]);
+
+        ctx.execute_operator_action(proposal.id);
+        assert_eq!(
+            ctx.contract_state().pause.unwrap().reason,
+            "incident".to_string()
+        );
+        assert!(ctx.pending_operator_proposals().is_empty());
+        assert!(ctx.operator_proposal(proposal.id).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "operator proposal does not have the required confirmations")]
+    fn confirmations_from_removed_operators_do_not_count() {
+        let mut ctx = setup();
+        set_predecessor(&mut ctx, ALICE);
+        let proposal = ctx.propose_operator_action(OperatorAction::Resume);
+        set_predecessor(&mut ctx, BOB);
+        ctx.confirm_operator_action(proposal.id);
+
+        set_predecessor(&mut ctx, TEST_OWNER_ID);
+        ctx.remove_operator(to_valid_account_id(BOB));
+
+        set_predecessor(&mut ctx, ALICE);
+        ctx.execute_operator_action(proposal.id);
+    }
+
+    #[test]
+    #[should_panic(expected = "config validation hash does not match")]
+    fn proposed_config_change_is_rejected_if_the_config_changed_before_execution() {
+        let mut ctx = setup();
+        set_predecessor(&mut ctx, ALICE);
+        let config: interface::Config =
+            serde_json::from_str(r#"{"deposit_cooldown_blocks": 10}"#).unwrap();
+        let validation_hash = ctx.validate_config(config.clone()).validation_hash.unwrap();
+        let proposal = ctx.propose_operator_action(OperatorAction::UpdateConfig {
+            config,
+            validation_hash,
+        });
+        set_predecessor(&mut ctx, BOB);
+        ctx.confirm_operator_action(proposal.id);
+
+        ctx.config.force_merge(
+            serde_json::from_str(r#"{"redeem_limit_increase_delay_epochs": 10}"#).unwrap(),
+        );
+        ctx.execute_operator_action(proposal.id);
+    }
+
+    #[test]
+    fn execute_staking_pool_change() {
+        let mut ctx = setup();
+        set_predecessor(&mut ctx, ALICE);
+        let proposal = ctx.propose_operator_action(OperatorAction::ChangeStakingPool {
+            account_id: to_valid_account_id("pool-2.near"),
+        });
+        set_predecessor(&mut ctx, TEST_OPERATOR_ID);
+        ctx.confirm_operator_action(proposal.id);
+        ctx.execute_operator_action(proposal.id);
+        assert_eq!(ctx.staking_pool_id, "pool-2.near");
+    }
+
+    #[test]
+    #[should_panic(expected = "account is already the staking pool")]
+    fn propose_unchanged_staking_pool() {
+        let mut ctx = setup();
+        set_predecessor(&mut ctx, ALICE);
+        ctx.propose_operator_action(OperatorAction::ChangeStakingPool {
+            account_id: to_valid_account_id(TEST_STAKING_POOL_ID),
+        });
+    }
+
+    #[test]
+    fn cancel_proposal() {
+        let mut ctx = setup();
+        set_predecessor(&mut ctx, ALICE);
+        let proposal = ctx.propose_operator_action(OperatorAction::Resume);
+        ctx.cancel_operator_action(proposal.id);
+        assert!(ctx.operator_proposal(proposal.id).is_none());
+        assert!(ctx.pending_operator_proposals().is_empty());
+    }
+}
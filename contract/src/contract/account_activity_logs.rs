@@ -0,0 +1,164 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::core::Hash;
+use crate::domain::{self, Account, AccountActivityKind, BatchId};
+use crate::interface::{AccountActivity, AccountActivityLogs};
+use crate::near::{storage_keys::ACCOUNT_ACTIVITY_LOGS_KEY_PREFIX, STORAGE_RECORD_OVERHEAD};
+use crate::*;
+use near_sdk::{json_types::ValidAccountId, near_bindgen};
+
+#[near_bindgen]
+impl AccountActivityLogs for Contract {
+    fn account_activity(
+        &self,
+        account_id: ValidAccountId,
+        from_index: u32,
+        limit: u32,
+    ) -> Vec<AccountActivity> {
+        self.account_activity_logs
+            .get(&Hash::from(account_id))
+            .map(|log| {
+                log.entries()
+                    .iter()
+                    .skip(from_index as usize)
+                    .take(limit as usize)
+                    .map(|activity| (*activity).into())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl Contract {
+    /// appends the activity to the account's activity log
+    /// - activity is not logged if activity logging is disabled or if the account's storage escrow
+    ///   does not cover a fully allocated activity log
+    pub(crate) fn record_account_activity(
+        &mut self,
+        account_id_hash: &Hash,
+        account: &Account,
+        kind: AccountActivityKind,
+        amount: u128,
+        batch_id: Option<BatchId>,
+    ) {
+        let max_len = self.config.account_activity_log_len();
+        if max_len == 0 || amount == 0 {
+            return;
+        }
+        let required_storage_escrow =
+            self.account_storage_cost(account) + self.account_activity_log_storage_cost();
+        if account.storage_escrow.amount() < required_storage_escrow {
+            return;
+        }
+
+        let mut log = self
+            .account_activity_logs
+            .get(account_id_hash)
+            .unwrap_or_default();
+        log.record(
+            domain::AccountActivity::new(kind, amount, batch_id),
+            max_len,
+        );
+        self.account_activity_logs.insert(account_id_hash, &log);
+    }
+
+    /// frees the account's activity log storage, which is covered by the account storage escrow
+    pub(crate) fn delete_account_activity_log(&mut self, account_id_hash: &Hash) {
+        self.account_activity_logs.remove(account_id_hash);
+    }
+
+    /// storage cost for a fully allocated activity log
+    fn account_activity_log_storage_cost(&self) -> domain::YoctoNear {
+        let record_len = domain::AccountActivityLog::template_to_measure_storage_usage()
+            .try_to_vec()
+            .unwrap()
+            .len();
+        let storage_usage = (ACCOUNT_ACTIVITY_LOGS_KEY_PREFIX.len() + Hash::LENGTH + record_len)
+            as u64
+            + STORAGE_RECORD_OVERHEAD;
+        self.storage_fee(storage_usage)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::{FungibleToken, StakingService};
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    const RECEIVER_ID: &str = "receiver.near";
+
+    fn deposit(test_ctx: &mut TestContext, amount: u128) -> interface::BatchId {
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = amount;
+        testing_env!(context);
+        test_ctx.deposit(None)
+    }
+
+    #[test]
+    fn deposits_and_transfers_are_logged_oldest_first() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let account_id = test_ctx.account_id;
+        let batch_id = deposit(&mut test_ctx, YOCTO);
+        deposit(&mut test_ctx, 2 * YOCTO);
+
+        test_ctx.register_account(RECEIVER_ID);
+        test_ctx.credit_stake(account_id, 100 * YOCTO);
+        let mut context = test_ctx.set_predecessor_account_id(account_id);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_ctx.ft_transfer(to_valid_account_id(RECEIVER_ID), (10 * YOCTO).into(), None);
+
+        let activity = test_ctx.account_activity(to_valid_account_id(account_id), 0, 10);
+        assert_eq!(activity.len(), 3);
+        assert_eq!(activity[0].kind, AccountActivityKind::Deposit);
+        assert_eq!(activity[0].amount, YOCTO.into());
+        assert_eq!(activity[0].batch_id, Some(batch_id));
+        assert_eq!(activity[1].amount, (2 * YOCTO).into());
+        assert_eq!(activity[2].kind, AccountActivityKind::TransferOut);
+        assert_eq!(activity[2].amount, (10 * YOCTO).into());
+
+        // paginated
+        let page = test_ctx.account_activity(to_valid_account_id(account_id), 1, 1);
+        assert_eq!(page, vec![activity[1].clone()]);
+        assert!(test_ctx
+            .account_activity(to_valid_account_id(account_id), 3, 10)
+            .is_empty());
+
+        let activity = test_ctx.account_activity(to_valid_account_id(RECEIVER_ID), 0, 10);
+        assert_eq!(activity.len(), 1);
+        assert_eq!(activity[0].kind, AccountActivityKind::TransferIn);
+    }
+
+    #[test]
+    fn activity_is_not_logged_when_storage_escrow_does_not_cover_log() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let account_id = test_ctx.account_id;
+        // simulates an account that was registered before the activity log was introduced, i.e.,
+        // the storage escrow covers the account record growth but not the activity log
+        let mut account = test_ctx.registered_account(account_id);
+        let storage_escrow = test_ctx.account_storage_cost(&account).value() * 2;
+        account.storage_escrow = domain::TimestampedNearBalance::new(storage_escrow.into());
+        test_ctx.save_registered_account(&account);
+
+        deposit(&mut test_ctx, YOCTO);
+        assert!(test_ctx
+            .account_activity(to_valid_account_id(account_id), 0, 10)
+            .is_empty());
+    }
+
+    #[test]
+    fn activity_logging_disabled_via_config() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let account_id = test_ctx.account_id;
+        test_ctx
+            .config
+            .merge(near_sdk::serde_json::from_str(r#"{"account_activity_log_len": 0}"#).unwrap());
+
+        deposit(&mut test_ctx, YOCTO);
+        assert!(test_ctx
+            .account_activity(to_valid_account_id(account_id), 0, 10)
+            .is_empty());
+    }
+}
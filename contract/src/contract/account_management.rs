@@ -1,20 +1,39 @@
 //required in order for near_bindgen macro to work outside of lib.rs
-use crate::domain::RegisteredAccount;
-use crate::errors::account_management::ACCOUNT_NOT_REGISTERED;
+use crate::domain::{AccountActivityKind, BlockTimeHeight, RegisteredAccount};
+use crate::errors::account_management::{
+    ACCOUNT_HIBERNATION_NOT_ALLOWED, ACCOUNT_NOT_REGISTERED, ACCOUNT_REHYDRATION_FEE_INSUFFICIENT,
+    ACCOUNT_STORAGE_ESCROW_INSUFFICIENT, REGISTRATION_POOL_INSUFFICIENT,
+};
+use crate::errors::liquidity_providers::UNREGISTER_REQUIRES_NO_LIQUIDITY_SHARES;
+use crate::errors::lockups::UNREGISTER_REQUIRES_NO_LOCKUP;
+use crate::errors::swap_intents::UNREGISTER_REQUIRES_NO_SWAP_INTENT;
+use crate::interface::account_management::events::{
+    AccountAutoRegistered, AccountHibernated, AccountRehydrated, AccountStorageEscrowBilled,
+};
+use crate::near::{
+    events, log,
+    storage_keys::{ACCOUNTS_KEY_PREFIX, ACCOUNT_IDS_KEY_PREFIX, ACCOUNT_ID_INDEX_KEY_PREFIX},
+    STORAGE_RECORD_OVERHEAD,
+};
 use crate::*;
 use crate::{
     core::Hash,
-    domain::{Account, YoctoNear},
+    domain::{Account, VersionedAccount, YoctoNear},
     errors::account_management::{
-        ACCOUNT_ALREADY_REGISTERED, INSUFFICIENT_STORAGE_FEE, UNREGISTER_REQUIRES_ZERO_BALANCES,
+        ACCOUNT_ALREADY_REGISTERED, INSUFFICIENT_STORAGE_FEE,
+        UNREGISTER_REQUIRES_STAKE_BENEFICIARY, UNREGISTER_REQUIRES_ZERO_BALANCES,
+        UNREGISTER_STAKE_BENEFICIARY_INVALID,
+    },
+    interface::{
+        self, AccountIdHash, AccountManagement, StakeAccount, StakeAccountEntry, StakingService,
     },
-    interface::{self, AccountManagement, StakeAccount, StakingService},
 };
 use near_sdk::{
     env,
-    json_types::{ValidAccountId, U128},
+    json_types::{ValidAccountId, U128, U64},
     near_bindgen, Promise,
 };
+use std::cell::Cell;
 
 #[near_bindgen]
 impl AccountManagement for Contract {
@@ -29,6 +48,19 @@ impl AccountManagement for Contract {
     /// - if account is already registered
     #[payable]
     fn register_account(&mut self) {
+        let account_id_hash = Hash::from(&env::predecessor_account_id());
+        if self.is_account_hibernated(&account_id_hash) {
+            let mut account = self.predecessor_registered_account();
+            let storage_fee =
+                self.pay_account_rehydration_fee(&mut account, env::attached_deposit().into());
+            self.save_registered_account(&account);
+            let refund = env::attached_deposit() - storage_fee.value();
+            if refund > 0 {
+                Promise::new(env::predecessor_account_id()).transfer(refund);
+            }
+            return;
+        }
+
         assert!(
             env::attached_deposit() >= self.account_storage_fee().value(),
             INSUFFICIENT_STORAGE_FEE,
@@ -38,7 +70,7 @@ impl AccountManagement for Contract {
         self.total_account_storage_escrow += account_storage_fee;
         let account = Account::new(account_storage_fee);
         assert!(
-            self.save_account(&Hash::from(&env::predecessor_account_id()), &account),
+            self.save_account(&env::predecessor_account_id(), &account),
             ACCOUNT_ALREADY_REGISTERED
         );
 
@@ -49,17 +81,38 @@ impl AccountManagement for Contract {
         }
     }
 
-    fn unregister_account(&mut self) {
+    fn unregister_account(&mut self, stake_beneficiary_id: Option<ValidAccountId>) {
+        self.assert_not_paused();
         let account_id = env::predecessor_account_id();
         let account_id_hash = Hash::from(&env::predecessor_account_id());
+        assert!(
+            !self.swap_intents.contains_key(&account_id_hash),
+            UNREGISTER_REQUIRES_NO_SWAP_INTENT
+        );
+        assert!(
+            !self.lockups.contains_key(&account_id_hash),
+            UNREGISTER_REQUIRES_NO_LOCKUP
+        );
+        assert!(
+            !self
+                .liquidity_provider_shares
+                .contains_key(&account_id_hash),
+            UNREGISTER_REQUIRES_NO_LIQUIDITY_SHARES
+        );
+        if let Some(mut account) = self.lookup_registered_account(&account_id) {
+            self.sweep_account_funds(&account_id, &mut account, stake_beneficiary_id);
+        }
 
         match self.delete_account(&account_id_hash) {
             None => panic!(ACCOUNT_NOT_REGISTERED),
             Some(account) => {
                 assert!(!account.has_funds(), UNREGISTER_REQUIRES_ZERO_BALANCES);
                 self.total_account_storage_escrow -= account.storage_escrow.amount();
-                // refund the escrowed storage fee
-                Promise::new(account_id).transfer(account.storage_escrow.amount().value());
+                // refund the escrowed storage fees along with any storage refunds owed to the account
+                let storage_escrow = account.storage_escrow.amount()
+                    + self.delete_receiver_authorizations(&account_id_hash)
+                    + self.take_storage_refund(&account_id_hash);
+                Promise::new(account_id).transfer(storage_escrow.value());
             }
         };
     }
@@ -67,11 +120,10 @@ impl AccountManagement for Contract {
     /// returns the required account storage fee that needs to be attached to the account registration
     /// contract function call in yoctoNEAR
     ///
-    /// NOTE: this is dynamic based on the storage cost per byte specified in the config
+    /// NOTE: this is dynamic based on the protocol storage byte cost plus the account storage fee
+    ///       margin specified in the config
     fn account_storage_fee(&self) -> interface::YoctoNear {
-        let fee = self.config.storage_cost_per_byte().value()
-            * self.account_storage_usage.value() as u128;
-        fee.into()
+        self.storage_fee(self.account_storage_usage.value()).into()
     }
 
     fn account_registered(&self, account_id: ValidAccountId) -> bool {
@@ -83,93 +135,290 @@ impl AccountManagement for Contract {
     }
 
     fn lookup_account(&self, account_id: ValidAccountId) -> Option<StakeAccount> {
+        self.lookup_account_by_hash(Hash::from(account_id).into())
+    }
+
+    fn lookup_account_by_hash(&self, account_id_hash: AccountIdHash) -> Option<StakeAccount> {
+        self.accounts
+            .get(&account_id_hash.into())
+            .map(|account| self.stake_account_view(&account))
+    }
+
+    fn hibernate_account(&mut self) -> interface::YoctoNear {
+        let account = self.predecessor_registered_account();
+        if self.is_account_hibernated(&account.id) {
+            return 0.into();
+        }
+        assert!(account.can_hibernate(), ACCOUNT_HIBERNATION_NOT_ALLOWED);
+
+        let storage_escrow = account.storage_escrow.amount();
+        // the account ID index entry is retained while the account is hibernated
+        let tombstone_storage_escrow = (self.hibernated_account_storage_cost()
+            + self.account_index_storage_cost(&env::predecessor_account_id()))
+        .min(storage_escrow);
+        self.accounts.insert_record(
+            &account.id,
+            &VersionedAccount::Hibernated(tombstone_storage_escrow),
+        );
+        // the activity log storage is covered by the storage escrow that is refunded
+        self.delete_account_activity_log(&account.id);
+        self.mutation_nonce += 1;
+        let refund = storage_escrow - tombstone_storage_escrow;
+        self.total_account_storage_escrow -= refund;
+        if refund.value() > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(refund.value());
+        }
+        log(AccountHibernated {
+            account_id: &env::predecessor_account_id(),
+            refund: refund.value(),
+            storage_escrow: tombstone_storage_escrow.value(),
+        });
+        refund.into()
+    }
+
+    fn account_hibernated(&self, account_id: ValidAccountId) -> bool {
+        self.is_account_hibernated(&Hash::from(account_id))
+    }
+
+    fn prove_min_balance(
+        &self,
+        account_id: ValidAccountId,
+        min_amount: interface::YoctoStake,
+    ) -> interface::BalanceProof {
+        let balance = self
+            .accounts
+            .get(&Hash::from(account_id.as_ref()))
+            .and_then(|account| self.apply_receipt_funds_for_view(&account).stake)
+            .map_or(0, |balance| balance.amount().value());
+        interface::BalanceProof {
+            contract_id: env::current_account_id(),
+            account_id: account_id.into(),
+            proven: balance >= min_amount.value(),
+            min_amount,
+            balance: balance.into(),
+            block_time_height: BlockTimeHeight::from_env().into(),
+        }
+    }
+
+    fn account_nonce(&self, account_id: ValidAccountId) -> Option<U64> {
         self.accounts
             .get(&Hash::from(account_id))
-            .map(|account| self.apply_receipt_funds_for_view(&account))
-            .map(|account| {
-                let redeem_stake_batch = account.redeem_stake_batch.map(|batch| {
-                    interface::RedeemStakeBatch::from(
-                        batch,
-                        self.redeem_stake_batch_receipt(batch.id().into()),
-                    )
-                });
-
-                let next_redeem_stake_batch = account.next_redeem_stake_batch.map(|batch| {
-                    interface::RedeemStakeBatch::from(
-                        batch,
-                        self.redeem_stake_batch_receipt(batch.id().into()),
-                    )
-                });
-
-                let contract_near_liquidity = if self.near_liquidity_pool.value() == 0 {
-                    None
-                } else {
-                    let mut total_unstaked_near = YoctoNear(0);
-
-                    let mut update_total_unstaked_near = |batch: &interface::RedeemStakeBatch| {
-                        if let Some(receipt) = batch.receipt.as_ref() {
-                            let stake_token_value: domain::StakeTokenValue =
-                                receipt.stake_token_value.clone().into();
-                            total_unstaked_near +=
-                                stake_token_value.stake_to_near(receipt.redeemed_stake.0 .0.into());
-                        }
-                    };
-
-                    if let Some(batch) = redeem_stake_batch.as_ref() {
-                        update_total_unstaked_near(batch);
-                    }
-
-                    if let Some(batch) = next_redeem_stake_batch.as_ref() {
-                        update_total_unstaked_near(batch);
-                    }
-
-                    if total_unstaked_near.value() > 0 {
-                        if self.near_liquidity_pool.value() >= total_unstaked_near.value() {
-                            Some(total_unstaked_near.into())
-                        } else {
-                            Some(self.near_liquidity_pool.into())
-                        }
-                    } else {
-                        None
-                    }
-                };
-
-                StakeAccount {
-                    storage_escrow: account.storage_escrow.into(),
-                    near: account.near.map(Into::into),
-                    stake: account.stake.map(Into::into),
-                    stake_batch: account.stake_batch.map(Into::into),
-                    next_stake_batch: account.next_stake_batch.map(Into::into),
-                    redeem_stake_batch,
-                    next_redeem_stake_batch,
-                    contract_near_liquidity,
-                }
+            .map(|account| account.nonce.into())
+    }
+
+    fn mutation_nonce(&self) -> U64 {
+        self.mutation_nonce.into()
+    }
+
+    fn get_accounts(&self, from_index: U64, limit: U64) -> Vec<StakeAccountEntry> {
+        let end = from_index
+            .0
+            .saturating_add(limit.0)
+            .min(self.account_ids_len);
+        (from_index.0..end)
+            .filter_map(|index| self.account_ids.get(&index))
+            .filter_map(|account_id| {
+                self.accounts
+                    .get(&Hash::from(&account_id))
+                    .map(|account| StakeAccountEntry {
+                        account: self.stake_account_view(&account),
+                        account_id,
+                    })
             })
+            .collect()
+    }
+
+    fn get_account_count(&self) -> U64 {
+        self.account_ids_len.into()
+    }
+
+    fn index_account(&mut self, account_id: ValidAccountId) -> bool {
+        let account_id: AccountId = account_id.into();
+        assert!(
+            self.accounts.contains_key(&Hash::from(&account_id)),
+            "{}: {}",
+            ACCOUNT_NOT_REGISTERED,
+            account_id
+        );
+        self.index_account_id(&account_id)
+    }
+
+    fn set_account_preferences(
+        &mut self,
+        preferences: interface::AccountPreferences,
+    ) -> interface::AccountPreferences {
+        let mut account = self.predecessor_registered_account();
+        account.preferences = preferences.into();
+        self.save_registered_account(&account);
+        self.apply_account_preferences(&mut account);
+        preferences
+    }
+
+    fn account_preferences(
+        &self,
+        account_id: ValidAccountId,
+    ) -> Option<interface::AccountPreferences> {
+        self.accounts
+            .get(&Hash::from(account_id))
+            .map(|account| account.preferences.into())
     }
 }
 
 impl Contract {
+    /// sweeps the account funds in preparation for unregistering the account
+    /// - unclaimed batch receipt funds are claimed
+    /// - the STAKE balance is transferred to the beneficiary
+    /// - the NEAR balance is withdrawn to the account, subject to the per epoch withdrawal limit
+    ///
+    /// Funds in batches that have not yet been run cannot be swept.
+    ///
+    /// ## Panics
+    /// - if the account has a STAKE balance and no beneficiary is specified
+    /// - if the beneficiary is the account itself or is not registered
+    fn sweep_account_funds(
+        &mut self,
+        account_id: &str,
+        account: &mut RegisteredAccount,
+        stake_beneficiary_id: Option<ValidAccountId>,
+    ) {
+        self.claim_receipt_funds(account);
+
+        let stake = account.stake.map_or(0.into(), |balance| balance.amount());
+        if stake.value() > 0 {
+            let beneficiary_id = stake_beneficiary_id.expect(UNREGISTER_REQUIRES_STAKE_BENEFICIARY);
+            assert_ne!(
+                beneficiary_id.as_ref(),
+                account_id,
+                "{}",
+                UNREGISTER_STAKE_BENEFICIARY_INVALID
+            );
+            account.apply_stake_debit(stake);
+            self.save_registered_account(account);
+
+            let mut beneficiary = self.registered_account(beneficiary_id.as_ref());
+            beneficiary.apply_stake_credit(stake);
+            self.save_registered_account(&beneficiary);
+            self.record_account_activity(
+                &beneficiary.id,
+                &beneficiary,
+                AccountActivityKind::TransferIn,
+                stake.value(),
+                None,
+            );
+            events::ft_transfer(
+                account_id,
+                beneficiary_id.as_ref(),
+                stake,
+                Some("unregister"),
+            );
+        }
+
+        let near = account.near.map_or(0.into(), |balance| balance.amount());
+        if near.value() > 0 {
+            self.debit_near_funds(account_id, account, near, account_id.to_string());
+        }
+    }
+
+    fn stake_account_view(&self, account: &Account) -> StakeAccount {
+        let account = self.apply_receipt_funds_for_view(account);
+        let redeem_stake_batch = account.redeem_stake_batch.map(|batch| {
+            interface::RedeemStakeBatch::from(
+                batch,
+                self.redeem_stake_batch_receipt(batch.id().into()),
+            )
+        });
+
+        let next_redeem_stake_batch = account.next_redeem_stake_batch.map(|batch| {
+            interface::RedeemStakeBatch::from(
+                batch,
+                self.redeem_stake_batch_receipt(batch.id().into()),
+            )
+        });
+
+        let contract_near_liquidity = if self.near_liquidity_pool.value() == 0 {
+            None
+        } else {
+            let mut total_unstaked_near = YoctoNear(0);
+
+            let mut update_total_unstaked_near = |batch: &interface::RedeemStakeBatch| {
+                if let Some(receipt) = batch.receipt.as_ref() {
+                    let stake_token_value: domain::StakeTokenValue =
+                        receipt.stake_token_value.clone().into();
+                    total_unstaked_near +=
+                        stake_token_value.stake_to_near(receipt.redeemed_stake.0 .0.into());
+                }
+            };
+
+            if let Some(batch) = redeem_stake_batch.as_ref() {
+                update_total_unstaked_near(batch);
+            }
+
+            if let Some(batch) = next_redeem_stake_batch.as_ref() {
+                update_total_unstaked_near(batch);
+            }
+
+            if total_unstaked_near.value() > 0 {
+                if self.near_liquidity_pool.value() >= total_unstaked_near.value() {
+                    Some(total_unstaked_near.into())
+                } else {
+                    Some(self.near_liquidity_pool.into())
+                }
+            } else {
+                None
+            }
+        };
+
+        StakeAccount {
+            storage_escrow: account.storage_escrow.into(),
+            near: account.near.map(Into::into),
+            stake: account.stake.map(Into::into),
+            stake_batch: account.stake_batch.map(Into::into),
+            next_stake_batch: account.next_stake_batch.map(Into::into),
+            redeem_stake_batch,
+            next_redeem_stake_batch,
+            contract_near_liquidity,
+        }
+    }
+
+    /// NOTE: the account's rebate accumulator is brought up to date when the account is loaded,
+    ///       i.e., before any account STAKE balance changes are applied
+    ///
     /// ## Panics
     /// if account is not registered
     pub(crate) fn registered_account(&self, account_id: &str) -> RegisteredAccount {
-        let account_id_hash = Hash::from(account_id);
-        match self.accounts.get(&Hash::from(account_id)) {
-            Some(account) => RegisteredAccount {
-                account,
-                id: account_id_hash,
-            },
+        match self.lookup_registered_account(account_id) {
+            Some(account) => account,
             None => panic!("{}: {}", ACCOUNT_NOT_REGISTERED, account_id),
         }
     }
 
     pub(crate) fn lookup_registered_account(&self, account_id: &str) -> Option<RegisteredAccount> {
         let account_id_hash = Hash::from(account_id);
-        self.accounts
-            .get(&Hash::from(account_id))
-            .map(|account| RegisteredAccount {
+        self.accounts.get(&account_id_hash).map(|account| {
+            let (account, idle_near_yield) =
+                self.accrue_idle_near_yield(self.accumulate_account_rebate(account));
+            RegisteredAccount {
                 account,
                 id: account_id_hash,
-            })
+                account_id: account_id.to_string(),
+                idle_near_yield: Cell::new(idle_near_yield),
+            }
+        })
+    }
+
+    /// the account ID is resolved via the account ID index
+    ///
+    /// Returns None if the account is not registered or if the account ID is not indexed - see
+    /// [index_account](crate::interface::AccountManagement::index_account)
+    pub(crate) fn lookup_registered_account_by_hash(
+        &self,
+        account_id_hash: Hash,
+    ) -> Option<RegisteredAccount> {
+        let account_id = self
+            .account_id_index
+            .get(&account_id_hash)
+            .and_then(|index| self.account_ids.get(&index))?;
+        self.lookup_registered_account(&account_id)
     }
 
     pub(crate) fn predecessor_registered_account(&self) -> RegisteredAccount {
@@ -177,26 +426,250 @@ impl Contract {
     }
 
     /// returns true if this was a new account
-    fn save_account(&mut self, account_id: &Hash, account: &Account) -> bool {
-        if self.accounts.insert(account_id, account).is_none() {
+    /// - new accounts are added to the account index
+    pub(crate) fn save_account(&mut self, account_id: &str, account: &Account) -> bool {
+        let account = self.stamp_mutation_nonce(account);
+        if self
+            .accounts
+            .insert(&Hash::from(account_id), &account)
+            .is_none()
+        {
             // new account was added
             self.accounts_len += 1;
+            self.index_account_id(account_id);
             return true;
         }
         false
     }
 
+    /// appends the account ID to the account index
+    ///
+    /// Returns false if the account ID is already indexed.
+    fn index_account_id(&mut self, account_id: &str) -> bool {
+        let account_id_hash = Hash::from(account_id);
+        if self.account_id_index.contains_key(&account_id_hash) {
+            return false;
+        }
+        self.account_ids
+            .insert(&self.account_ids_len, &account_id.to_string());
+        self.account_id_index
+            .insert(&account_id_hash, &self.account_ids_len);
+        self.account_ids_len += 1;
+        true
+    }
+
+    /// removes the account ID from the account index - the last indexed account ID is moved into
+    /// the freed slot
+    fn unindex_account_id(&mut self, account_id_hash: &Hash) {
+        if let Some(index) = self.account_id_index.remove(account_id_hash) {
+            let last_index = self.account_ids_len - 1;
+            if index != last_index {
+                let last_account_id = self
+                    .account_ids
+                    .get(&last_index)
+                    .expect("indexed account ID should exist");
+                self.account_ids.insert(&index, &last_account_id);
+                self.account_id_index
+                    .insert(&Hash::from(&last_account_id), &index);
+            }
+            self.account_ids.remove(&last_index);
+            self.account_ids_len = last_index;
+        }
+    }
+
+    /// NOTE: idle NEAR yield that was credited when the account was loaded is settled against the
+    ///       idle NEAR yield pool
     pub(crate) fn save_registered_account(&mut self, account: &RegisteredAccount) {
-        self.save_account(&account.id, &account.account);
+        let record = self.stamp_mutation_nonce(&account.account);
+        let previous = self.accounts.insert(&account.id, &record);
+        if previous.is_none() {
+            self.accounts_len += 1;
+        }
+        self.record_idle_near_account_changes(previous.as_ref(), account);
+        self.index_batch_contributors(previous.as_ref(), account);
+    }
+
+    /// storage cost for the account record based on its current state, i.e., the more batches and
+    /// metadata the account holds, the more storage it uses
+    pub(crate) fn account_storage_cost(&self, account: &Account) -> YoctoNear {
+        self.account_record_storage_cost(account.try_to_vec().unwrap().len())
+    }
+
+    /// storage cost for the hibernated account tombstone
+    fn hibernated_account_storage_cost(&self) -> YoctoNear {
+        let tombstone = VersionedAccount::Hibernated(0.into());
+        self.account_record_storage_cost(tombstone.try_to_vec().unwrap().len())
+    }
+
+    /// storage cost for the account ID index entry, or zero if the account is not indexed
+    fn account_index_storage_cost(&self, account_id: &str) -> YoctoNear {
+        if !self.account_id_index.contains_key(&Hash::from(account_id)) {
+            return 0.into();
+        }
+        let account_ids_record_len =
+            ACCOUNT_IDS_KEY_PREFIX.len() + 8 + account_id.to_string().try_to_vec().unwrap().len();
+        let account_id_index_record_len = ACCOUNT_ID_INDEX_KEY_PREFIX.len() + Hash::LENGTH + 8;
+        let storage_usage = (account_ids_record_len + account_id_index_record_len) as u64
+            + 2 * STORAGE_RECORD_OVERHEAD;
+        self.storage_fee(storage_usage)
+    }
+
+    fn account_record_storage_cost(&self, record_len: usize) -> YoctoNear {
+        let storage_usage = (ACCOUNTS_KEY_PREFIX.len() + Hash::LENGTH + record_len) as u64
+            + STORAGE_RECORD_OVERHEAD;
+        self.storage_fee(storage_usage)
+    }
+
+    /// returns true if the account record is stored as a hibernated tombstone
+    /// - hibernated accounts are transparently rehydrated when they are loaded
+    pub(crate) fn is_account_hibernated(&self, account_id_hash: &Hash) -> bool {
+        match self.accounts.get_record(account_id_hash) {
+            Some(VersionedAccount::Hibernated(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// restores the storage escrow for the rehydrated account back up to the account storage fee
+    ///
+    /// Returns the storage fee that was paid from the payment.
+    ///
+    /// ## Panics
+    /// if the payment does not cover the storage fee
+    pub(crate) fn pay_account_rehydration_fee(
+        &mut self,
+        account: &mut RegisteredAccount,
+        payment: YoctoNear,
+    ) -> YoctoNear {
+        let storage_fee: YoctoNear = self
+            .account_storage_fee()
+            .value()
+            .saturating_sub(account.storage_escrow.amount().value())
+            .into();
+        assert!(
+            payment >= storage_fee,
+            "{}: storage fee = {} yoctoNEAR",
+            ACCOUNT_REHYDRATION_FEE_INSUFFICIENT,
+            storage_fee
+        );
+        account.storage_escrow.credit(storage_fee);
+        self.total_account_storage_escrow += storage_fee;
+        log(AccountRehydrated {
+            account_id: &env::predecessor_account_id(),
+            storage_fee: storage_fee.value(),
+            storage_escrow: account.storage_escrow.amount().value(),
+        });
+        storage_fee
+    }
+
+    /// storage fee for the specified number of bytes
+    /// - every storage fee that is charged or refunded must be priced through this, i.e., storage is
+    ///   refunded at the same rate that it is charged
+    pub(crate) fn storage_fee(&self, storage_usage: u64) -> YoctoNear {
+        (storage_usage as u128 * self.account_storage_byte_cost()).into()
+    }
+
+    /// the live protocol storage byte cost plus the configured safety margin
+    /// - the storage byte cost is looked up at call time, thus protocol storage price changes are
+    ///   reflected in the account storage fee
+    fn account_storage_byte_cost(&self) -> u128 {
+        env::storage_byte_cost()
+            * (100 + self.config.account_storage_fee_margin_percentage() as u128)
+            / 100
+    }
+
+    /// The storage escrow paid at registration covers a fully allocated account. However, the
+    /// escrow may fall short of the account's actual storage cost, e.g., if the storage cost per
+    /// byte is raised. This should be invoked before the account storage grows, i.e., when batches
+    /// or metadata are added to the account - any shortfall is billed from the account's available
+    /// NEAR balance to prevent the contract from subsidizing account storage.
+    ///
+    /// ## Panics
+    /// if the account NEAR balance is too low to cover the shortfall
+    /// applies the account preferences when the account is touched by a transaction that does not
+    /// otherwise claim the account's receipts, e.g., when the account receives a STAKE transfer
+    /// - see [set_account_preferences](crate::interface::AccountManagement::set_account_preferences)
+    ///
+    /// NOTE: the account is saved if receipts were claimed
+    pub(crate) fn apply_account_preferences(&mut self, account: &mut RegisteredAccount) {
+        if account.preferences.auto_claim_receipts {
+            self.claim_receipt_funds(account);
+        }
+    }
+
+    pub(crate) fn bill_account_storage_growth(&mut self, account: &mut RegisteredAccount) {
+        let shortfall = self
+            .account_storage_cost(&account)
+            .value()
+            .saturating_sub(account.storage_escrow.amount().value());
+        if shortfall == 0 {
+            return;
+        }
+        let shortfall: YoctoNear = shortfall.into();
+        let near_balance = account.near.map_or(0.into(), |balance| balance.amount());
+        assert!(
+            near_balance >= shortfall,
+            "{}: shortfall = {} yoctoNEAR",
+            ACCOUNT_STORAGE_ESCROW_INSUFFICIENT,
+            shortfall
+        );
+
+        account.apply_near_debit(shortfall);
+        self.debit_total_near(shortfall);
+        account.storage_escrow.credit(shortfall);
+        self.total_account_storage_escrow += shortfall;
+        log(AccountStorageEscrowBilled {
+            account_id: &env::predecessor_account_id(),
+            amount: shortfall.value(),
+            storage_escrow: account.storage_escrow.amount().value(),
+        });
+    }
+
+    /// If the account is not registered and is whitelisted for auto-registration, then the account
+    /// is registered with the account storage fee paid from the registration pool.
+    ///
+    /// ## Panics
+    /// if the registration pool balance is too low to pay for the account storage fee
+    pub(crate) fn auto_register_account(&mut self, account_id: &str) {
+        let account_id_hash = Hash::from(account_id);
+        if self.accounts.contains_key(&account_id_hash)
+            || !self.auto_registration_whitelist.contains(&account_id_hash)
+        {
+            return;
+        }
+
+        let account_storage_fee: YoctoNear = self.account_storage_fee().into();
+        assert!(
+            self.registration_pool >= account_storage_fee,
+            REGISTRATION_POOL_INSUFFICIENT
+        );
+        self.registration_pool -= account_storage_fee;
+        self.total_account_storage_escrow += account_storage_fee;
+        self.save_account(account_id, &Account::new(account_storage_fee));
+        log(AccountAutoRegistered {
+            account_id,
+            storage_fee: account_storage_fee.value(),
+            registration_pool: self.registration_pool.value(),
+        });
     }
 
     /// returns the account that was deleted, or None if no account exists for specified account ID
     fn delete_account(&mut self, account_id: &Hash) -> Option<Account> {
         self.accounts.remove(account_id).map(|account| {
             self.accounts_len -= 1;
+            self.unindex_account_id(account_id);
+            self.delete_account_activity_log(account_id);
+            self.mutation_nonce += 1;
             account
         })
     }
+
+    /// advances the global mutation nonce and returns a copy of the account that is stamped with it
+    fn stamp_mutation_nonce(&mut self, account: &Account) -> Account {
+        self.mutation_nonce += 1;
+        let mut account = *account;
+        account.nonce = self.mutation_nonce;
+        account
+    }
 }
 
 #[cfg(test)]
@@ -254,7 +727,7 @@ mod test_register_account {
 
         let account_storage_usage = env::storage_usage() - storage_before_registering_account;
         assert_eq!(
-            account_storage_usage, 119,
+            account_storage_usage, 282,
             "account storage usage changed !!! If the change is expected, then update the assert"
         );
 
@@ -331,7 +804,7 @@ mod test_unregister_account {
             contract.total_account_storage_escrow,
             contract.account_storage_fee().into()
         );
-        contract.unregister_account();
+        contract.unregister_account(None);
         assert!(!contract.account_registered(test_context.account_id.try_into().unwrap()));
         let receipts = deserialize_receipts();
         // account storage fee should have been refunded
@@ -349,7 +822,7 @@ mod test_unregister_account {
 
     #[test]
     #[should_panic(
-        expected = "all funds must be withdrawn from the account in order to unregister"
+        expected = "a STAKE beneficiary is required to unregister an account that has a STAKE balance"
     )]
     fn unregister_account_with_stake_funds() {
         let mut test_context = TestContext::with_registered_account();
@@ -361,24 +834,50 @@ mod test_unregister_account {
         contract.save_registered_account(&registered_account);
 
         // then unregister will fail
-        contract.unregister_account();
+        contract.unregister_account(None);
     }
 
     #[test]
-    #[should_panic(
-        expected = "all funds must be withdrawn from the account in order to unregister"
-    )]
-    fn unregister_account_with_near_funds() {
+    fn unregister_account_transfers_stake_to_beneficiary() {
+        let mut test_context = TestContext::with_registered_account();
+        let beneficiary_id = "beneficiary.near";
+        test_context.register_account(beneficiary_id);
+        let account_id = test_context.account_id;
+        let context = test_context.set_predecessor_account_id(account_id);
+        testing_env!(context);
+        let contract = &mut test_context.contract;
+
+        let mut registered_account = contract.registered_account(account_id);
+        registered_account.account.apply_stake_credit(YOCTO.into());
+        contract.save_registered_account(&registered_account);
+
+        contract.unregister_account(Some(beneficiary_id.try_into().unwrap()));
+        assert!(!contract.account_registered(account_id.try_into().unwrap()));
+        let beneficiary = contract.registered_account(beneficiary_id);
+        assert_eq!(beneficiary.stake.unwrap().amount(), YOCTO.into());
+    }
+
+    #[test]
+    fn unregister_account_withdraws_near_funds() {
         let mut test_context = TestContext::with_registered_account();
         let contract = &mut test_context.contract;
 
         // credit some NEAR
         let mut account = contract.registered_account(test_context.account_id);
-        account.deref_mut().apply_near_credit(1.into());
+        account.deref_mut().apply_near_credit(YOCTO.into());
         contract.save_registered_account(&account);
+        contract.total_near.credit(YOCTO.into());
 
-        // unregister should fail
-        contract.unregister_account();
+        contract.unregister_account(None);
+        assert!(!contract.account_registered(test_context.account_id.try_into().unwrap()));
+        // the NEAR balance is withdrawn and the account storage fee is refunded
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 2);
+        match &receipts[0].actions[0] {
+            Action::Transfer { deposit } => assert_eq!(*deposit, YOCTO),
+            _ => panic!("expected NEAR balance to be withdrawn"),
+        }
+        assert_eq!(contract.total_near.amount(), 0.into());
     }
 
     #[test]
@@ -393,10 +892,10 @@ mod test_unregister_account {
         // credit some NEAR
         context.attached_deposit = YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         // unregister should fail
-        contract.unregister_account();
+        contract.unregister_account(None);
     }
 
     #[test]
@@ -413,13 +912,13 @@ mod test_unregister_account {
         testing_env!(context.clone());
         // setting the lock to true should cause the deposit to be put in the next stake batch
         contract.stake_batch_lock = Some(StakeLock::Staking);
-        contract.deposit();
+        contract.deposit(None);
         // confirm that account has funds in next stake batch
         let registered_account = contract.registered_account(test_context.account_id);
         assert!(registered_account.account.next_stake_batch.is_some());
 
         // unregister should fail
-        contract.unregister_account();
+        contract.unregister_account(None);
     }
 
     #[test]
@@ -438,7 +937,7 @@ mod test_unregister_account {
         contract.redeem_all();
 
         // unregister should fail
-        contract.unregister_account();
+        contract.unregister_account(None);
     }
 
     #[test]
@@ -467,14 +966,14 @@ mod test_unregister_account {
         assert!(registered_account.account.next_redeem_stake_batch.is_some());
 
         // unregister should fail
-        contract.unregister_account();
+        contract.unregister_account(None);
     }
 
     #[test]
     #[should_panic(expected = "account is not registered")]
     fn unregister_unknown_account() {
         let mut test_context = TestContext::new();
-        test_context.contract.unregister_account();
+        test_context.contract.unregister_account(None);
     }
 }
 
@@ -484,7 +983,7 @@ mod test_lookup_account {
     use crate::interface::{AccountManagement, StakingService};
     use crate::near::YOCTO;
     use crate::test_utils::*;
-    use near_sdk::{testing_env, MockedBlockchain};
+    use near_sdk::{json_types::Base64VecU8, serde_json, testing_env, MockedBlockchain};
     use std::convert::TryInto;
 
     #[test]
@@ -507,6 +1006,39 @@ mod test_lookup_account {
             .is_none());
     }
 
+    #[test]
+    fn lookup_account_by_hash() {
+        let test_context = TestContext::with_registered_account();
+
+        let account_id_hash = AccountIdHash::from_account_id(test_context.account_id);
+        let account = test_context
+            .contract
+            .lookup_account_by_hash(account_id_hash)
+            .expect("account should be registered");
+        assert_eq!(
+            serde_json::to_string(&account).unwrap(),
+            serde_json::to_string(
+                &test_context
+                    .contract
+                    .lookup_account(test_context.account_id.try_into().unwrap())
+            )
+            .unwrap()
+        );
+        assert!(test_context
+            .contract
+            .lookup_account_by_hash(AccountIdHash::from_account_id("unregistered.near"))
+            .is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "account ID hash must be 32 bytes: 3")]
+    fn lookup_account_by_invalid_hash() {
+        let test_context = TestContext::with_registered_account();
+        test_context
+            .contract
+            .lookup_account_by_hash(AccountIdHash(Base64VecU8(vec![1, 2, 3])));
+    }
+
     /// when an account has unclaimed receipts, the receipts are applied to the account balances
     /// for display purposes - so as not to confuse the end user
     #[test]
@@ -520,7 +1052,7 @@ mod test_lookup_account {
             // deposit funds into a stake batch
             context.attached_deposit = 10_u128 * YOCTO;
             testing_env!(context.clone());
-            contract.deposit();
+            contract.deposit(None);
 
             // simulate that the batch was processed and create a batch receipt for it
             let batch = contract.stake_batch.unwrap();
@@ -528,6 +1060,7 @@ mod test_lookup_account {
             let receipt = domain::StakeBatchReceipt::new(
                 batch.balance().amount(),
                 contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
             );
             contract.stake_batch_receipts.insert(&batch.id(), &receipt);
             contract.stake_batch = None;
@@ -541,13 +1074,12 @@ mod test_lookup_account {
             contract.redeem((YOCTO * 2).into());
 
             // create a receipt for the batch
-            let redeem_stake_batch_receipt = contract
-                .redeem_stake_batch
-                .unwrap()
-                .create_receipt(contract.stake_token_value);
+            let redeem_stake_batch = contract.redeem_stake_batch.unwrap();
+            let redeem_stake_batch_receipt = redeem_stake_batch
+                .create_receipt(contract.stake_token_value, TEST_STAKING_POOL_ID.to_string());
             contract
                 .redeem_stake_batch_receipts
-                .insert(&contract.batch_id_sequence, &redeem_stake_batch_receipt);
+                .insert(&redeem_stake_batch.id(), &redeem_stake_batch_receipt);
         }
 
         context.is_view = true;
@@ -579,7 +1111,8 @@ mod test_lookup_account {
             contract.redeem_all();
 
             let batch = contract.redeem_stake_batch.unwrap();
-            let receipt = batch.create_receipt(contract.stake_token_value);
+            let receipt =
+                batch.create_receipt(contract.stake_token_value, TEST_STAKING_POOL_ID.to_string());
             contract
                 .redeem_stake_batch_receipts
                 .insert(&batch.id(), &receipt);
@@ -600,6 +1133,145 @@ mod test_lookup_account {
             .receipt
             .expect("receipt for pending withdrawal should be present");
     }
+
+    #[test]
+    fn prove_min_balance_with_unclaimed_receipts() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = ctx.context.clone();
+        let account_id = ctx.account_id;
+
+        let proof = ctx.prove_min_balance(to_valid_account_id(account_id), YOCTO.into());
+        assert!(!proof.proven);
+        assert_eq!(proof.balance, 0.into());
+
+        // deposit funds into a stake batch and simulate that the batch was run
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        ctx.deposit(None);
+        let batch = ctx.stake_batch.unwrap();
+        let receipt = domain::StakeBatchReceipt::new(
+            batch.balance().amount(),
+            ctx.stake_token_value,
+            TEST_STAKING_POOL_ID.to_string(),
+        );
+        ctx.stake_batch_receipts.insert(&batch.id(), &receipt);
+        ctx.stake_batch = None;
+
+        // the account qualifies before the receipt is claimed
+        context.is_view = true;
+        testing_env!(context);
+        let proof = ctx.prove_min_balance(to_valid_account_id(account_id), (10 * YOCTO).into());
+        assert!(proof.proven);
+        assert_eq!(proof.balance, (10 * YOCTO).into());
+        assert_eq!(proof.contract_id, ctx.context.current_account_id);
+        assert_eq!(proof.account_id, account_id);
+        assert_eq!(
+            u64::from(proof.block_time_height.block_height.0),
+            ctx.context.block_index
+        );
+
+        let proof = ctx.prove_min_balance(to_valid_account_id(account_id), (10 * YOCTO + 1).into());
+        assert!(!proof.proven);
+
+        // unregistered accounts have a zero balance
+        let proof = ctx.prove_min_balance(to_valid_account_id("alice.near"), 0.into());
+        assert!(proof.proven);
+        assert_eq!(proof.balance, 0.into());
+    }
+}
+
+#[cfg(test)]
+mod test_account_storage_growth {
+    use super::*;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{serde_json, testing_env, MockedBlockchain};
+
+    /// raises the account storage fee margin, which means the account storage escrow paid at
+    /// registration will fall short
+    fn raise_storage_cost(ctx: &mut TestContext) {
+        ctx.config.merge(
+            serde_json::from_str(r#"{"account_storage_fee_margin_percentage": 100}"#).unwrap(),
+        );
+    }
+
+    #[test]
+    fn account_storage_fee_is_based_on_protocol_storage_byte_cost_plus_margin() {
+        let mut ctx = TestContext::new();
+        testing_env!(ctx.context.clone());
+
+        let account_storage_usage = ctx.account_storage_usage.value() as u128;
+        assert_eq!(
+            ctx.account_storage_fee().value(),
+            account_storage_usage * env::storage_byte_cost() * 110 / 100
+        );
+
+        ctx.config.merge(
+            serde_json::from_str(r#"{"account_storage_fee_margin_percentage": 0}"#).unwrap(),
+        );
+        assert_eq!(
+            ctx.account_storage_fee().value(),
+            account_storage_usage * env::storage_byte_cost()
+        );
+    }
+
+    #[test]
+    fn storage_escrow_covers_account() {
+        let mut ctx = TestContext::with_registered_account();
+        testing_env!(ctx.context.clone());
+
+        let mut account = ctx.registered_account(ctx.account_id);
+        let storage_escrow = account.storage_escrow.amount();
+        assert!(storage_escrow >= ctx.account_storage_cost(&account));
+        let total_account_storage_escrow = ctx.total_account_storage_escrow;
+
+        ctx.bill_account_storage_growth(&mut account);
+        assert_eq!(account.storage_escrow.amount(), storage_escrow);
+        assert_eq!(
+            ctx.total_account_storage_escrow,
+            total_account_storage_escrow
+        );
+    }
+
+    #[test]
+    fn storage_escrow_shortfall_is_billed_from_near_balance() {
+        let mut ctx = TestContext::with_registered_account();
+        testing_env!(ctx.context.clone());
+
+        let mut account = ctx.registered_account(ctx.account_id);
+        account.apply_near_credit(YOCTO.into());
+        ctx.total_near.credit(YOCTO.into());
+        let storage_escrow = account.storage_escrow.amount();
+        let total_account_storage_escrow = ctx.total_account_storage_escrow;
+
+        raise_storage_cost(&mut ctx);
+        let storage_cost = ctx.account_storage_cost(&account);
+        assert!(storage_cost > storage_escrow);
+        let shortfall = storage_cost - storage_escrow;
+
+        ctx.bill_account_storage_growth(&mut account);
+        assert_eq!(account.storage_escrow.amount(), storage_cost);
+        assert_eq!(
+            account.near.unwrap().amount(),
+            (YOCTO - shortfall.value()).into()
+        );
+        assert_eq!(ctx.total_near.amount(), (YOCTO - shortfall.value()).into());
+        assert_eq!(
+            ctx.total_account_storage_escrow,
+            total_account_storage_escrow + shortfall
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "account storage escrow is insufficient")]
+    fn storage_escrow_shortfall_with_insufficient_near_balance() {
+        let mut ctx = TestContext::with_registered_account();
+        testing_env!(ctx.context.clone());
+
+        let mut account = ctx.registered_account(ctx.account_id);
+        raise_storage_cost(&mut ctx);
+        ctx.bill_account_storage_growth(&mut account);
+    }
 }
 
 #[cfg(test)]
@@ -628,3 +1300,207 @@ mod test {
             .lookup_account(ValidAccountId::try_from(ctx.account_id).unwrap());
     }
 }
+
+#[cfg(test)]
+mod test_account_nonce {
+    use super::*;
+    use crate::interface::AccountManagement;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn account_nonce_advances_on_account_mutations() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = to_valid_account_id(ctx.account_id);
+        let nonce = ctx.account_nonce(account_id.clone()).unwrap();
+        assert_eq!(nonce, ctx.mutation_nonce());
+        assert!(ctx
+            .account_nonce(to_valid_account_id("alice.near"))
+            .is_none());
+
+        let mut context = ctx.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        ctx.deposit(None);
+        let deposit_nonce = ctx.account_nonce(account_id.clone()).unwrap();
+        assert!(deposit_nonce.0 > nonce.0);
+        assert_eq!(deposit_nonce, ctx.mutation_nonce());
+
+        // views do not mutate the account
+        testing_env!(ctx.context.clone());
+        ctx.lookup_account(account_id.clone());
+        assert_eq!(ctx.account_nonce(account_id).unwrap(), deposit_nonce);
+    }
+
+    #[test]
+    fn mutation_nonce_advances_when_account_is_unregistered() {
+        let mut ctx = TestContext::with_registered_account();
+        let mutation_nonce = ctx.mutation_nonce();
+        ctx.unregister_account(None);
+        assert_eq!(ctx.mutation_nonce().0, mutation_nonce.0 + 1);
+        assert!(ctx
+            .account_nonce(to_valid_account_id(ctx.account_id))
+            .is_none());
+    }
+}
+
+#[cfg(test)]
+mod test_account_hibernation {
+    use super::*;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    fn hibernate(ctx: &mut TestContext) -> interface::YoctoNear {
+        testing_env!(ctx.context.clone());
+        ctx.hibernate_account()
+    }
+
+    #[test]
+    fn hibernate_then_rehydrate_on_deposit() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = to_valid_account_id(ctx.account_id);
+        let account_storage_fee = ctx.account_storage_fee();
+        let total_account_storage_escrow = ctx.total_account_storage_escrow;
+
+        let refund = hibernate(&mut ctx);
+        assert!(refund.value() > 0);
+        assert!(ctx.account_hibernated(account_id.clone()));
+        assert!(ctx.account_registered(account_id.clone()));
+        assert_eq!(ctx.total_registered_accounts().0, 1);
+        assert_eq!(
+            ctx.total_account_storage_escrow,
+            total_account_storage_escrow - refund.into()
+        );
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 1);
+        match &receipts[0].actions[0] {
+            Action::Transfer { deposit } => assert_eq!(*deposit, refund.value()),
+            _ => panic!("expected storage fee refund transfer"),
+        }
+        let storage_escrow = ctx
+            .lookup_account(account_id.clone())
+            .unwrap()
+            .storage_escrow
+            .amount;
+        assert_eq!(
+            storage_escrow.value(),
+            account_storage_fee.value() - refund.value()
+        );
+
+        // hibernating again is a no-op
+        assert_eq!(hibernate(&mut ctx).value(), 0);
+
+        let mut context = ctx.context.clone();
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context);
+        ctx.deposit(None);
+        assert!(!ctx.account_hibernated(account_id.clone()));
+        let account = ctx.registered_account(ctx.account_id);
+        assert_eq!(account.storage_escrow.amount(), account_storage_fee.into());
+        assert_eq!(
+            account.stake_batch.unwrap().balance().amount().value(),
+            10 * YOCTO - refund.value()
+        );
+        assert_eq!(
+            ctx.total_account_storage_escrow,
+            total_account_storage_escrow
+        );
+    }
+
+    #[test]
+    fn rehydrate_by_registering() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = to_valid_account_id(ctx.account_id);
+        let account_storage_fee = ctx.account_storage_fee();
+        let refund = hibernate(&mut ctx);
+
+        let mut context = ctx.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        ctx.register_account();
+        assert!(!ctx.account_hibernated(account_id.clone()));
+        assert_eq!(ctx.total_registered_accounts().0, 1);
+        assert_eq!(
+            ctx.registered_account(ctx.account_id)
+                .storage_escrow
+                .amount(),
+            account_storage_fee.into()
+        );
+        // the attached deposit minus the storage fee is refunded
+        let receipts = deserialize_receipts();
+        match &receipts[0].actions[0] {
+            Action::Transfer { deposit } => assert_eq!(*deposit, YOCTO - refund.value()),
+            _ => panic!("expected refund transfer"),
+        }
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "only accounts with zero balances, no batches, and no account settings can hibernate"
+    )]
+    fn account_with_redeem_limit_cannot_hibernate() {
+        let mut ctx = TestContext::with_registered_account();
+        testing_env!(ctx.context.clone());
+        ctx.set_max_redeem_per_epoch(Some(YOCTO.into()));
+        ctx.hibernate_account();
+    }
+}
+
+#[cfg(test)]
+mod test_account_enumeration {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    fn account_ids(ctx: &TestContext) -> Vec<AccountId> {
+        ctx.get_accounts(0.into(), 10.into())
+            .into_iter()
+            .map(|entry| entry.account_id)
+            .collect()
+    }
+
+    #[test]
+    fn registered_accounts_are_enumerable() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.register_account("alice.near");
+        ctx.register_account("bob.near");
+        assert_eq!(ctx.get_account_count(), 3.into());
+        assert_eq!(
+            account_ids(&ctx),
+            vec![ctx.account_id, "alice.near", "bob.near"]
+        );
+        let page = ctx.get_accounts(1.into(), 1.into());
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].account_id, "alice.near");
+        assert!(ctx.get_accounts(3.into(), 10.into()).is_empty());
+
+        // the last indexed account is moved into the unregistered account's slot
+        testing_env!(ctx.context.clone());
+        ctx.unregister_account(None);
+        assert_eq!(ctx.get_account_count(), 2.into());
+        assert_eq!(account_ids(&ctx), vec!["bob.near", "alice.near"]);
+    }
+
+    #[test]
+    fn backfill_account_index() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        assert!(!ctx.index_account(to_valid_account_id(account_id)));
+
+        // simulate an account that was registered before the account index was introduced
+        ctx.unindex_account_id(&Hash::from(account_id));
+        assert_eq!(ctx.get_account_count(), 0.into());
+
+        assert!(ctx.index_account(to_valid_account_id(account_id)));
+        assert_eq!(account_ids(&ctx), vec![account_id]);
+    }
+
+    #[test]
+    #[should_panic(expected = "account is not registered")]
+    fn index_unregistered_account() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.index_account(to_valid_account_id("alice.near"));
+    }
+}
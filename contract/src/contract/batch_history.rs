@@ -0,0 +1,150 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::domain::{self, BatchHistoryWindow};
+use crate::interface::{BatchHistory, BatchHistoryEntry};
+use crate::*;
+use near_sdk::{collections::LookupMap, near_bindgen};
+
+#[near_bindgen]
+impl BatchHistory for Contract {
+    fn stake_batch_history(&self, from: u64, limit: u32) -> Vec<BatchHistoryEntry> {
+        self.batch_history(
+            &self.stake_batch_history,
+            self.stake_batch_history_window,
+            from,
+            limit,
+        )
+    }
+
+    fn redeem_batch_history(&self, from: u64, limit: u32) -> Vec<BatchHistoryEntry> {
+        self.batch_history(
+            &self.redeem_batch_history,
+            self.redeem_batch_history_window,
+            from,
+            limit,
+        )
+    }
+
+    fn stake_batch_history_len(&self) -> u64 {
+        self.stake_batch_history_window.len()
+    }
+
+    fn redeem_batch_history_len(&self) -> u64 {
+        self.redeem_batch_history_window.len()
+    }
+}
+
+impl Contract {
+    /// records the stake batch that was run - no-op if the batch history is disabled
+    pub(crate) fn record_stake_batch_history(&mut self, entry: domain::BatchHistoryEntry) {
+        let capacity = self.config.batch_history_capacity();
+        record_batch_history(
+            &mut self.stake_batch_history,
+            &mut self.stake_batch_history_window,
+            capacity,
+            entry,
+        );
+    }
+
+    /// records the redeem stake batch that was run - no-op if the batch history is disabled
+    pub(crate) fn record_redeem_batch_history(&mut self, entry: domain::BatchHistoryEntry) {
+        let capacity = self.config.batch_history_capacity();
+        record_batch_history(
+            &mut self.redeem_batch_history,
+            &mut self.redeem_batch_history_window,
+            capacity,
+            entry,
+        );
+    }
+
+    fn batch_history(
+        &self,
+        history: &LookupMap<u64, domain::BatchHistoryEntry>,
+        window: BatchHistoryWindow,
+        from: u64,
+        limit: u32,
+    ) -> Vec<BatchHistoryEntry> {
+        let from = from.max(window.first(self.config.batch_history_capacity()));
+        let to = window.len().min(from.saturating_add(limit as u64));
+        (from..to)
+            .filter_map(|index| {
+                history
+                    .get(&index)
+                    .map(|entry| BatchHistoryEntry::new(index, entry))
+            })
+            .collect()
+    }
+}
+
+fn record_batch_history(
+    history: &mut LookupMap<u64, domain::BatchHistoryEntry>,
+    window: &mut BatchHistoryWindow,
+    capacity: u32,
+    entry: domain::BatchHistoryEntry,
+) {
+    if capacity == 0 {
+        return;
+    }
+    let (index, pruned) = window.push(capacity);
+    for index in pruned {
+        history.remove(&index);
+    }
+    history.insert(&index, &entry);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::{BatchId, StakeTokenValue};
+    use crate::test_utils::*;
+    use near_sdk::{serde_json, testing_env, MockedBlockchain};
+
+    fn entry(batch_id: u128) -> domain::BatchHistoryEntry {
+        domain::BatchHistoryEntry::new(
+            BatchId(batch_id),
+            batch_id.into(),
+            batch_id.into(),
+            StakeTokenValue::default(),
+            0.into(),
+        )
+    }
+
+    #[test]
+    fn batch_history_is_disabled_by_default() {
+        let mut test_ctx = TestContext::new();
+        testing_env!(test_ctx.context.clone());
+
+        test_ctx.record_stake_batch_history(entry(1));
+        assert_eq!(test_ctx.stake_batch_history_len(), 0);
+        assert!(test_ctx.stake_batch_history(0, 10).is_empty());
+    }
+
+    #[test]
+    fn batch_history_retains_configured_capacity() {
+        let mut test_ctx = TestContext::new();
+        testing_env!(test_ctx.context.clone());
+        test_ctx
+            .config
+            .merge(serde_json::from_str(r#"{"batch_history_capacity": 3}"#).unwrap());
+
+        for batch_id in 1..=5 {
+            test_ctx.record_stake_batch_history(entry(batch_id));
+        }
+        test_ctx.record_redeem_batch_history(entry(6));
+
+        assert_eq!(test_ctx.stake_batch_history_len(), 5);
+        let history = test_ctx.stake_batch_history(0, 10);
+        let batch_ids: Vec<u128> = history.iter().map(|entry| entry.batch_id.0 .0).collect();
+        assert_eq!(batch_ids, vec![3, 4, 5]);
+        assert_eq!(history[0].index, 2.into());
+        assert!(test_ctx.stake_batch_history.get(&1).is_none());
+
+        // paging
+        let history = test_ctx.stake_batch_history(3, 1);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].batch_id.0 .0, 4);
+
+        let history = test_ctx.redeem_batch_history(0, 10);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].batch_id.0 .0, 6);
+    }
+}
@@ -0,0 +1,279 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::core::Hash;
+use crate::domain::MAX_BATCH_RESERVATIONS;
+use crate::errors::batch_reservations::{
+    BATCH_RESERVATIONS_FULL, BATCH_RESERVATION_ALREADY_EXISTS,
+    BATCH_RESERVATION_DEPOSIT_INSUFFICIENT, NO_BATCH_RESERVATION,
+};
+use crate::interface::batch_reservations::events::{
+    BatchReservationCancelled, BatchReservationUsed, BatchSlotReserved,
+};
+use crate::interface::{BatchReservation, BatchReservations};
+use crate::near::log;
+use crate::*;
+use near_sdk::{json_types::ValidAccountId, near_bindgen, Promise};
+
+#[near_bindgen]
+impl BatchReservations for Contract {
+    #[payable]
+    fn reserve_batch_slot(&mut self, amount: interface::YoctoNear) -> BatchReservation {
+        self.assert_not_paused();
+        let account = self.predecessor_registered_account();
+        assert!(
+            !self.batch_reservations.contains_key(&account.id),
+            BATCH_RESERVATION_ALREADY_EXISTS
+        );
+        assert!(
+            self.batch_reservation_queue.len() < MAX_BATCH_RESERVATIONS,
+            BATCH_RESERVATIONS_FULL
+        );
+        let amount: YoctoNear = amount.into();
+        let min_required_near_deposit = self.min_required_near_deposit();
+        assert!(
+            amount >= min_required_near_deposit,
+            "minimum required NEAR deposit is: {}",
+            min_required_near_deposit
+        );
+        let fee = self.config.batch_reservation_fee();
+        let escrow = amount + fee;
+        assert!(
+            env::attached_deposit() >= escrow.value(),
+            "{}: {}",
+            BATCH_RESERVATION_DEPOSIT_INSUFFICIENT,
+            escrow
+        );
+
+        let block_height = env::block_index();
+        let reservation = domain::BatchReservation::new(
+            env::predecessor_account_id(),
+            amount,
+            fee,
+            domain::BlockTimeHeight::from_env(),
+            (block_height + self.config.batch_reservation_expiry_blocks()).into(),
+        );
+        self.batch_reservations.insert(&account.id, &reservation);
+        self.batch_reservation_queue.push(account.id);
+        self.total_batch_reservation_escrow += escrow;
+
+        let refund = env::attached_deposit() - escrow.value();
+        if refund > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(refund);
+        }
+
+        log(BatchSlotReserved {
+            account_id: reservation.account_id(),
+            amount: amount.value(),
+            fee: fee.value(),
+            expires_at: reservation.expires_at().value(),
+        });
+        BatchReservation::from(reservation, block_height.into())
+    }
+
+    fn cancel_batch_reservation(&mut self) -> interface::YoctoNear {
+        let account_id_hash = Hash::from(env::predecessor_account_id().as_str());
+        let reservation = self
+            .batch_reservations
+            .remove(&account_id_hash)
+            .expect(NO_BATCH_RESERVATION);
+        self.batch_reservation_queue
+            .retain(|queued| *queued != account_id_hash);
+        self.total_batch_reservation_escrow -= reservation.escrow();
+        // forfeited fees are distributed to the stakers
+        self.collected_earnings += reservation.fee();
+
+        Promise::new(env::predecessor_account_id()).transfer(reservation.amount().value());
+
+        log(BatchReservationCancelled {
+            account_id: reservation.account_id(),
+            refund: reservation.amount().value(),
+            forfeited_fee: reservation.fee().value(),
+            expired: reservation.is_expired(env::block_index().into()),
+        });
+        reservation.amount().into()
+    }
+
+    fn batch_reservation(&self, account_id: ValidAccountId) -> Option<BatchReservation> {
+        self.batch_reservations
+            .get(&Hash::from(account_id.as_ref()))
+            .map(|reservation| BatchReservation::from(reservation, env::block_index().into()))
+    }
+
+    fn batch_reservations(&self) -> Vec<BatchReservation> {
+        self.batch_reservation_queue
+            .iter()
+            .filter_map(|account_id_hash| self.batch_reservations.get(account_id_hash))
+            .map(|reservation| BatchReservation::from(reservation, env::block_index().into()))
+            .collect()
+    }
+}
+
+impl Contract {
+    /// deposits the reserved funds into the stake batch and refunds the reservation fees to the
+    /// account NEAR balances
+    /// - must be invoked when the stake batch run starts, i.e., before the stake batch is locked
+    /// - expired reservations remain queued until they are cancelled by the account
+    /// - reservations for accounts that are no longer registered remain queued until they are
+    ///   cancelled
+    /// - reservations for accounts whose stake batches are pending verification remain queued
+    pub(crate) fn apply_batch_reservations(&mut self) {
+        if self.batch_reservation_queue.is_empty() {
+            return;
+        }
+        let block_height: domain::BlockHeight = env::block_index().into();
+        let mut batch_id = None;
+        let queue = std::mem::take(&mut self.batch_reservation_queue);
+        for account_id_hash in queue {
+            let reservation = match self.batch_reservations.get(&account_id_hash) {
+                Some(reservation) => reservation,
+                None => continue,
+            };
+            if reservation.is_expired(block_height) {
+                self.batch_reservation_queue.push(account_id_hash);
+                continue;
+            }
+            let mut account = match self.lookup_registered_account(reservation.account_id()) {
+                Some(account) => account,
+                None => {
+                    self.batch_reservation_queue.push(account_id_hash);
+                    continue;
+                }
+            };
+            if !self.stake_batches_verified(&account) {
+                self.batch_reservation_queue.push(account_id_hash);
+                continue;
+            }
+
+            self.batch_reservations.remove(&account_id_hash);
+            self.total_batch_reservation_escrow -= reservation.escrow();
+            batch_id =
+                Some(self.deposit_near_for_account_to_stake(&mut account, reservation.amount()));
+            account.apply_near_credit(reservation.fee());
+            self.total_near.credit(reservation.fee());
+            self.save_registered_account(&account);
+
+            log(BatchReservationUsed {
+                account_id: reservation.account_id(),
+                batch_id: batch_id.unwrap().value(),
+                amount: reservation.amount().value(),
+                fee_refund: reservation.fee().value(),
+            });
+        }
+        if let Some(batch_id) = batch_id {
+            self.log_stake_batch(batch_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::StakingService;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+    use std::convert::TryFrom;
+
+    const FEE: u128 = YOCTO / 10;
+
+    #[test]
+    fn reservation_is_included_in_next_batch_run() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = ctx.context.clone();
+        let account_id = ValidAccountId::try_from(ctx.account_id).unwrap();
+
+        // reservations made while the stake batch is running are held until the next batch run
+        ctx.stake_batch_lock = Some(StakeLock::Staking);
+        context.attached_deposit = 10 * YOCTO + FEE;
+        testing_env!(context.clone());
+        let reservation = ctx.reserve_batch_slot((10 * YOCTO).into());
+        assert_eq!(reservation.amount, (10 * YOCTO).into());
+        assert_eq!(reservation.fee, FEE.into());
+        assert!(!reservation.expired);
+        assert_eq!(ctx.batch_reservations(), vec![reservation.clone()]);
+        assert_eq!(ctx.batch_reservation(account_id.clone()), Some(reservation));
+        assert_eq!(
+            ctx.total_batch_reservation_escrow,
+            (10 * YOCTO + FEE).into()
+        );
+        assert!(ctx.stake_batch.is_none());
+        assert!(ctx.next_stake_batch.is_none());
+
+        // the stake batch workflow completed
+        ctx.stake_batch_lock = None;
+        context.attached_deposit = 0;
+        testing_env!(context);
+        ctx.stake();
+        assert_eq!(ctx.stake_batch_lock, Some(StakeLock::Staking));
+        assert_eq!(
+            ctx.stake_batch.unwrap().balance().amount(),
+            (10 * YOCTO).into()
+        );
+        let account = ctx.registered_account(ctx.account_id);
+        assert_eq!(
+            account.stake_batch.unwrap().balance().amount(),
+            (10 * YOCTO).into()
+        );
+        // the fee is refunded when the reservation is used
+        assert_eq!(account.near.unwrap().amount(), FEE.into());
+        assert!(ctx.batch_reservation(account_id).is_none());
+        assert!(ctx.batch_reservations().is_empty());
+        assert_eq!(ctx.total_batch_reservation_escrow, 0.into());
+    }
+
+    #[test]
+    fn expired_reservation_is_not_used() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = ctx.context.clone();
+        let account_id = ValidAccountId::try_from(ctx.account_id).unwrap();
+
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+        ctx.deposit(None);
+        context.attached_deposit = 10 * YOCTO + FEE;
+        testing_env!(context.clone());
+        ctx.reserve_batch_slot((10 * YOCTO).into());
+
+        context.attached_deposit = 0;
+        context.block_index += ctx.config.batch_reservation_expiry_blocks();
+        testing_env!(context);
+        assert!(ctx.batch_reservation(account_id.clone()).unwrap().expired);
+        ctx.stake();
+        assert_eq!(ctx.stake_batch.unwrap().balance().amount(), YOCTO.into());
+        assert!(ctx.batch_reservation(account_id.clone()).is_some());
+
+        // the fee is forfeited when an unused reservation is cancelled
+        let collected_earnings = ctx.collected_earnings;
+        assert_eq!(ctx.cancel_batch_reservation(), (10 * YOCTO).into());
+        assert_eq!(ctx.collected_earnings, collected_earnings + FEE.into());
+        assert!(ctx.batch_reservation(account_id).is_none());
+        assert_eq!(ctx.total_batch_reservation_escrow, 0.into());
+        let receipts = deserialize_receipts();
+        match receipts.last().unwrap().actions[0] {
+            Action::Transfer { deposit } => assert_eq!(deposit, 10 * YOCTO),
+            _ => panic!("expected refund transfer"),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "account already has a batch reservation")]
+    fn reserve_batch_slot_twice() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = ctx.context.clone();
+        context.attached_deposit = 10 * YOCTO + FEE;
+        testing_env!(context);
+        ctx.reserve_batch_slot((10 * YOCTO).into());
+        ctx.reserve_batch_slot((10 * YOCTO).into());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "attached deposit must cover the reserved amount plus the reservation fee"
+    )]
+    fn reserve_batch_slot_without_fee() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = ctx.context.clone();
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context);
+        ctx.reserve_batch_slot((10 * YOCTO).into());
+    }
+}
@@ -0,0 +1,485 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::domain::{BatchKind, BlockHeight, RegisteredAccount};
+use crate::errors::{
+    batch_totals::{
+        BATCH_CHANGED_DURING_REBUILD, BATCH_IS_CURRENT, BATCH_NOT_COMPLETED, BATCH_NOT_CURRENT,
+        BATCH_TOTALS_REBUILD_OUT_OF_SEQUENCE,
+    },
+    staking_errors::BLOCKED_BY_BATCH_RUNNING,
+};
+use crate::interface::batch_totals::events::{BatchClaimsProcessed, BatchTotalsRepaired};
+use crate::interface::{self, BatchId, BatchTotals};
+use crate::near::log;
+use crate::*;
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+impl BatchTotals for Contract {
+    fn rebuild_batch_totals(
+        &mut self,
+        batch_id: BatchId,
+        from: u64,
+        limit: u32,
+        dry_run: bool,
+    ) -> interface::BatchTotalsRebuild {
+        self.assert_predecessor_is_operator();
+
+        let batch_id: domain::BatchId = batch_id.into();
+        let (recorded_balance, recorded_block_height) = self.rebuildable_batch_balance(batch_id);
+        let mut rebuild = if from == 0 {
+            // the idle NEAR sweep position and the liquidity provider pool position are not
+            // registered accounts, thus they are not indexed
+            let sweep_contribution = self.idle_near_sweep.position().batch_contribution(batch_id);
+            let liquidity_provider_pool_contribution = self
+                .liquidity_provider_pool
+                .position()
+                .batch_contribution(batch_id);
+            BatchTotalsRebuild::new(
+                batch_id,
+                sweep_contribution + liquidity_provider_pool_contribution,
+                recorded_balance,
+                recorded_block_height,
+            )
+        } else {
+            let rebuild = self
+                .batch_totals_rebuild
+                .filter(|rebuild| rebuild.batch_id() == batch_id && rebuild.next_index() == from)
+                .expect(BATCH_TOTALS_REBUILD_OUT_OF_SEQUENCE);
+            assert!(
+                rebuild.matches(recorded_balance, recorded_block_height),
+                BATCH_CHANGED_DURING_REBUILD
+            );
+            rebuild
+        };
+
+        let contributors = self.batch_contributors_len.get(&batch_id).unwrap_or(0);
+        let to = contributors.min(from + limit as u64);
+        for index in from..to {
+            let contribution = self
+                .batch_contributors
+                .get(&(batch_id, index))
+                .and_then(|account_id| self.accounts.get(&account_id))
+                .map_or(0, |account| account.batch_contribution(batch_id));
+            rebuild.add_contribution(contribution);
+        }
+
+        let complete = rebuild.next_index() >= contributors;
+        let mut repaired = false;
+        if complete {
+            self.batch_totals_rebuild = None;
+            if !dry_run && rebuild.rebuilt_balance() != recorded_balance {
+                self.repair_batch_balance(batch_id, rebuild.rebuilt_balance());
+                log(BatchTotalsRepaired {
+                    batch_id: batch_id.value(),
+                    recorded_balance,
+                    rebuilt_balance: rebuild.rebuilt_balance(),
+                });
+                repaired = true;
+            }
+        } else {
+            self.batch_totals_rebuild = Some(rebuild);
+        }
+
+        interface::BatchTotalsRebuild {
+            batch_id: batch_id.into(),
+            next_index: rebuild.next_index(),
+            contributors,
+            rebuilt_balance: rebuild.rebuilt_balance().into(),
+            recorded_balance: recorded_balance.into(),
+            complete,
+            repaired,
+        }
+    }
+
+    fn prune_batch_contributors(&mut self, batch_id: BatchId, limit: u32) -> u64 {
+        self.assert_predecessor_is_operator();
+
+        let batch_id: domain::BatchId = batch_id.into();
+        assert!(!self.is_current_batch(batch_id), BATCH_IS_CURRENT);
+
+        let len = self.batch_contributors_len.get(&batch_id).unwrap_or(0);
+        let remaining = len.saturating_sub(limit as u64);
+        for index in remaining..len {
+            if let Some(account_id) = self.batch_contributors.remove(&(batch_id, index)) {
+                self.batch_contributor_set.remove(&(batch_id, account_id));
+            }
+        }
+        if remaining == 0 {
+            self.batch_contributors_len.remove(&batch_id);
+        } else {
+            self.batch_contributors_len.insert(&batch_id, &remaining);
+        }
+        remaining
+    }
+
+    fn batch_contributors_len(&self, batch_id: BatchId) -> u64 {
+        self.batch_contributors_len
+            .get(&batch_id.into())
+            .unwrap_or(0)
+    }
+
+    fn process_claims(
+        &mut self,
+        batch_id: BatchId,
+        from: u64,
+        limit: u32,
+    ) -> interface::BatchClaims {
+        let batch_id: domain::BatchId = batch_id.into();
+        assert!(!self.is_current_batch(batch_id), BATCH_NOT_COMPLETED);
+
+        let contributors = self.batch_contributors_len.get(&batch_id).unwrap_or(0);
+        let to = contributors.min(from.saturating_add(limit as u64));
+        let mut claimed = 0;
+        for index in from..to {
+            let mut account = match self
+                .batch_contributors
+                .get(&(batch_id, index))
+                .and_then(|account_id| self.lookup_registered_account_by_hash(account_id))
+            {
+                Some(account) => account,
+                None => continue,
+            };
+            if !account.batch_ids().contains(&batch_id) {
+                continue;
+            }
+            self.claim_receipt_funds(&mut account);
+            if !account.batch_ids().contains(&batch_id) {
+                claimed += 1;
+            }
+        }
+
+        let next_index = to.max(from);
+        log(BatchClaimsProcessed {
+            batch_id: batch_id.value(),
+            from,
+            next_index,
+            claimed,
+        });
+        interface::BatchClaims {
+            batch_id: batch_id.into(),
+            next_index,
+            contributors,
+            claimed,
+            complete: next_index >= contributors,
+        }
+    }
+}
+
+impl Contract {
+    /// adds the account to the contributor index for any batches that the account joined
+    pub(crate) fn index_batch_contributors(
+        &mut self,
+        previous: Option<&Account>,
+        account: &RegisteredAccount,
+    ) {
+        let previous_batch_ids = previous.map_or_else(Vec::new, |account| account.batch_ids());
+        for batch_id in account.batch_ids() {
+            if previous_batch_ids.contains(&batch_id) {
+                continue;
+            }
+            let key = (batch_id, account.id);
+            if self.batch_contributor_set.contains(&key) {
+                continue;
+            }
+            self.batch_contributor_set.insert(&key);
+            let index = self.batch_contributors_len.get(&batch_id).unwrap_or(0);
+            self.batch_contributors
+                .insert(&(batch_id, index), &account.id);
+            self.batch_contributors_len.insert(&batch_id, &(index + 1));
+        }
+    }
+
+    fn is_current_batch(&self, batch_id: domain::BatchId) -> bool {
+        match batch_id.kind() {
+            BatchKind::Stake => self
+                .stake_batch
+                .iter()
+                .chain(self.next_stake_batch.iter())
+                .any(|batch| batch.id() == batch_id),
+            BatchKind::Redeem => self
+                .redeem_stake_batch
+                .iter()
+                .chain(self.next_redeem_stake_batch.iter())
+                .any(|batch| batch.id() == batch_id),
+        }
+    }
+
+    /// returns the contract level batch balance along with the block height when it last changed
+    ///
+    /// ## Panics
+    /// - if the batch is not a current batch
+    /// - if the batch is being run
+    fn rebuildable_batch_balance(&self, batch_id: domain::BatchId) -> (u128, BlockHeight) {
+        match batch_id.kind() {
+            BatchKind::Stake => {
+                if let Some(batch) = self.next_stake_batch.filter(|batch| batch.id() == batch_id) {
+                    return (
+                        batch.balance().amount().value(),
+                        batch.balance().block_height(),
+                    );
+                }
+                if let Some(batch) = self.stake_batch.filter(|batch| batch.id() == batch_id) {
+                    assert!(self.stake_batch_lock.is_none(), BLOCKED_BY_BATCH_RUNNING);
+                    return (
+                        batch.balance().amount().value(),
+                        batch.balance().block_height(),
+                    );
+                }
+            }
+            BatchKind::Redeem => {
+                if let Some(batch) = self
+                    .next_redeem_stake_batch
+                    .filter(|batch| batch.id() == batch_id)
+                {
+                    return (
+                        batch.balance().amount().value(),
+                        batch.balance().block_height(),
+                    );
+                }
+                if let Some(batch) = self
+                    .redeem_stake_batch
+                    .filter(|batch| batch.id() == batch_id)
+                {
+                    assert!(
+                        self.redeem_stake_batch_lock.is_none(),
+                        BLOCKED_BY_BATCH_RUNNING
+                    );
+                    return (
+                        batch.balance().amount().value(),
+                        batch.balance().block_height(),
+                    );
+                }
+            }
+        }
+        panic!(BATCH_NOT_CURRENT)
+    }
+
+    /// aligns the contract level batch balance with the rebuilt balance
+    fn repair_batch_balance(&mut self, batch_id: domain::BatchId, rebuilt_balance: u128) {
+        fn repair_stake_batch(batch: &mut Option<StakeBatch>, rebuilt_balance: u128) {
+            if let Some(mut stake_batch) = batch.take() {
+                let recorded_balance = stake_batch.balance().amount().value();
+                if rebuilt_balance > recorded_balance {
+                    stake_batch.add((rebuilt_balance - recorded_balance).into());
+                } else {
+                    stake_batch.remove((recorded_balance - rebuilt_balance).into());
+                }
+                if rebuilt_balance > 0 {
+                    *batch = Some(stake_batch);
+                }
+            }
+        }
+
+        fn repair_redeem_stake_batch(batch: &mut Option<RedeemStakeBatch>, rebuilt_balance: u128) {
+            if let Some(mut redeem_stake_batch) = batch.take() {
+                let recorded_balance = redeem_stake_batch.balance().amount().value();
+                if rebuilt_balance > recorded_balance {
+                    redeem_stake_batch.add((rebuilt_balance - recorded_balance).into());
+                } else {
+                    redeem_stake_batch.remove((recorded_balance - rebuilt_balance).into());
+                }
+                if rebuilt_balance > 0 {
+                    *batch = Some(redeem_stake_batch);
+                }
+            }
+        }
+
+        match batch_id.kind() {
+            BatchKind::Stake => {
+                if self
+                    .next_stake_batch
+                    .map_or(false, |batch| batch.id() == batch_id)
+                {
+                    repair_stake_batch(&mut self.next_stake_batch, rebuilt_balance);
+                } else {
+                    repair_stake_batch(&mut self.stake_batch, rebuilt_balance);
+                }
+            }
+            BatchKind::Redeem => {
+                if self
+                    .next_redeem_stake_batch
+                    .map_or(false, |batch| batch.id() == batch_id)
+                {
+                    repair_redeem_stake_batch(&mut self.next_redeem_stake_batch, rebuilt_balance);
+                } else {
+                    repair_redeem_stake_batch(&mut self.redeem_stake_batch, rebuilt_balance);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::StakingService;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    fn deposit(ctx: &mut TestContext, account_id: &str, amount: u128) -> BatchId {
+        let mut context = ctx.set_predecessor_account_id(account_id);
+        context.attached_deposit = amount;
+        testing_env!(context);
+        ctx.deposit(None)
+    }
+
+    fn set_operator_as_predecessor(ctx: &mut TestContext) {
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.contract.operator_id.clone();
+        testing_env!(context);
+    }
+
+    #[test]
+    fn deposits_are_indexed_once_per_batch_contributor() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        ctx.register_account("bob.near");
+
+        let batch_id = deposit(&mut ctx, account_id, YOCTO);
+        deposit(&mut ctx, account_id, YOCTO);
+        deposit(&mut ctx, "bob.near", YOCTO);
+        assert_eq!(ctx.batch_contributors_len(batch_id), 2);
+    }
+
+    #[test]
+    fn rebuild_repairs_drift() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        ctx.register_account("bob.near");
+        let batch_id = deposit(&mut ctx, account_id, YOCTO);
+        deposit(&mut ctx, "bob.near", 2 * YOCTO);
+
+        let mut stake_batch = ctx.stake_batch.unwrap();
+        stake_batch.add(YOCTO.into());
+        ctx.stake_batch = Some(stake_batch);
+
+        set_operator_as_predecessor(&mut ctx);
+        let rebuild = ctx.rebuild_batch_totals(batch_id.clone(), 0, 10, false);
+        assert!(rebuild.complete);
+        assert!(rebuild.repaired);
+        assert_eq!(rebuild.contributors, 2);
+        assert_eq!(rebuild.recorded_balance, (4 * YOCTO).into());
+        assert_eq!(rebuild.rebuilt_balance, (3 * YOCTO).into());
+        assert_eq!(
+            ctx.stake_batch.unwrap().balance().amount(),
+            (3 * YOCTO).into()
+        );
+
+        // once repaired, there is no more drift
+        let rebuild = ctx.rebuild_batch_totals(batch_id, 0, 10, false);
+        assert!(rebuild.complete);
+        assert!(!rebuild.repaired);
+    }
+
+    #[test]
+    fn dry_run_does_not_repair_drift() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        let batch_id = deposit(&mut ctx, account_id, YOCTO);
+
+        let mut stake_batch = ctx.stake_batch.unwrap();
+        stake_batch.add(YOCTO.into());
+        ctx.stake_batch = Some(stake_batch);
+
+        set_operator_as_predecessor(&mut ctx);
+        let rebuild = ctx.rebuild_batch_totals(batch_id, 0, 10, true);
+        assert!(rebuild.complete);
+        assert!(!rebuild.repaired);
+        assert_eq!(rebuild.rebuilt_balance, YOCTO.into());
+        assert_eq!(
+            ctx.stake_batch.unwrap().balance().amount(),
+            (2 * YOCTO).into()
+        );
+    }
+
+    #[test]
+    fn rebuild_in_chunks() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        ctx.register_account("bob.near");
+        ctx.register_account("alice.near");
+        let batch_id = deposit(&mut ctx, account_id, YOCTO);
+        deposit(&mut ctx, "bob.near", YOCTO);
+        deposit(&mut ctx, "alice.near", YOCTO);
+
+        let mut stake_batch = ctx.stake_batch.unwrap();
+        stake_batch.remove(YOCTO.into());
+        ctx.stake_batch = Some(stake_batch);
+
+        set_operator_as_predecessor(&mut ctx);
+        let rebuild = ctx.rebuild_batch_totals(batch_id.clone(), 0, 2, false);
+        assert!(!rebuild.complete);
+        assert_eq!(rebuild.next_index, 2);
+        assert_eq!(rebuild.rebuilt_balance, (2 * YOCTO).into());
+        assert!(ctx.batch_totals_rebuild.is_some());
+
+        let rebuild = ctx.rebuild_batch_totals(batch_id, 2, 2, false);
+        assert!(rebuild.complete);
+        assert!(rebuild.repaired);
+        assert_eq!(rebuild.next_index, 3);
+        assert_eq!(rebuild.rebuilt_balance, (3 * YOCTO).into());
+        assert!(ctx.batch_totals_rebuild.is_none());
+        assert_eq!(
+            ctx.stake_batch.unwrap().balance().amount(),
+            (3 * YOCTO).into()
+        );
+    }
+
+    #[test]
+    fn prune_batch_contributors_once_batch_is_no_longer_current() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        ctx.register_account("bob.near");
+        let batch_id = deposit(&mut ctx, account_id, YOCTO);
+        deposit(&mut ctx, "bob.near", YOCTO);
+        ctx.stake_batch = None;
+
+        set_operator_as_predecessor(&mut ctx);
+        assert_eq!(ctx.prune_batch_contributors(batch_id.clone(), 1), 1);
+        assert_eq!(ctx.batch_contributors_len(batch_id.clone()), 1);
+        assert_eq!(ctx.prune_batch_contributors(batch_id.clone(), 10), 0);
+        assert_eq!(ctx.batch_contributors_len(batch_id), 0);
+    }
+
+    #[test]
+    fn process_claims_in_chunks() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        ctx.register_account("bob.near");
+        let batch_id = deposit(&mut ctx, account_id, YOCTO);
+        deposit(&mut ctx, "bob.near", 2 * YOCTO);
+
+        // simulate that the batch was run
+        let batch = ctx.stake_batch.take().unwrap();
+        let receipt = domain::StakeBatchReceipt::new(
+            batch.balance().amount(),
+            ctx.stake_token_value,
+            TEST_STAKING_POOL_ID.to_string(),
+        );
+        ctx.stake_batch_receipts.insert(&batch.id(), &receipt);
+
+        let claims = ctx.process_claims(batch_id.clone(), 0, 1);
+        assert_eq!(claims.claimed, 1);
+        assert_eq!(claims.next_index, 1);
+        assert_eq!(claims.contributors, 2);
+        assert!(!claims.complete);
+        assert!(ctx.registered_account("bob.near").stake_batch.is_some());
+
+        let claims = ctx.process_claims(batch_id.clone(), 1, 10);
+        assert_eq!(claims.claimed, 1);
+        assert!(claims.complete);
+        let bob = ctx.registered_account("bob.near");
+        assert!(bob.stake_batch.is_none());
+        assert_eq!(bob.stake.unwrap().amount(), (2 * YOCTO).into());
+        assert_eq!(
+            ctx.registered_account(account_id).stake.unwrap().amount(),
+            YOCTO.into()
+        );
+
+        // claiming is idempotent
+        let claims = ctx.process_claims(batch_id, 0, 10);
+        assert_eq!(claims.claimed, 0);
+        assert!(claims.complete);
+    }
+}
@@ -1,10 +1,15 @@
-use crate::interface::{AccountManagement, ContractFinancials, ContractOwner, YoctoNear};
+use crate::interface::{
+    AccountManagement, BatchId, ContractFinancials, ContractOwner, YoctoNear, YoctoStake,
+};
 //required in order for near_bindgen macro to work outside of lib.rs
+use crate::errors::asserts::OPERATOR_ID_MUST_NOT_BE_CONTRACT_ID;
 use crate::errors::contract_owner::{
     INSUFFICIENT_FUNDS_FOR_OWNER_STAKING, INSUFFICIENT_FUNDS_FOR_OWNER_WITHDRAWAL,
     TRANSFER_TO_NON_REGISTERED_ACCOUNT,
 };
-use crate::interface::contract_owner::events::OwnershipTransferred;
+use crate::interface::contract_owner::events::{
+    OwnerBalanceStaked, OwnerStakeRedeemed, OwnershipTransferred,
+};
 use crate::near::log;
 use crate::*;
 use near_sdk::{json_types::ValidAccountId, near_bindgen, Promise};
@@ -34,6 +39,12 @@ impl ContractOwner for Contract {
 
     fn set_operator_id(&mut self, account_id: ValidAccountId) {
         self.assert_predecessor_is_owner();
+        assert_ne!(
+            env::current_account_id().as_str(),
+            account_id.as_ref(),
+            "{}",
+            OPERATOR_ID_MUST_NOT_BE_CONTRACT_ID
+        );
         assert!(
             self.account_registered(account_id.clone()),
             TRANSFER_TO_NON_REGISTERED_ACCOUNT,
@@ -44,32 +55,40 @@ impl ContractOwner for Contract {
 
     fn stake_all_owner_balance(&mut self) -> YoctoNear {
         self.assert_predecessor_is_owner();
-        let mut account = self.registered_account(&self.owner_id);
-        let balances = self.balances();
-        let owner_available_balance = balances.contract_owner_available_balance;
+        self.assert_not_paused();
+        let owner_available_balance = self.owner_available_balance();
         assert!(owner_available_balance.value() > 0, "owner balance is zero");
-        self.deposit_near_for_account_to_stake(
-            &mut account,
-            owner_available_balance.value().into(),
-        );
-        self.save_registered_account(&account);
-        owner_available_balance
+        self.stake_owner_funds(owner_available_balance);
+        owner_available_balance.into()
     }
 
-    fn stake_owner_balance(&mut self, amount: YoctoNear) {
+    fn stake_owner_balance(&mut self, amount: YoctoNear) -> BatchId {
         self.assert_predecessor_is_owner();
-        let mut account = self.registered_account(&self.owner_id);
-        let owner_available_balance = self.balances().contract_owner_available_balance;
+        self.assert_not_paused();
         assert!(
-            owner_available_balance.value() >= amount.value(),
+            self.owner_available_balance().value() >= amount.value(),
             INSUFFICIENT_FUNDS_FOR_OWNER_STAKING
         );
-        self.deposit_near_for_account_to_stake(&mut account, amount.into());
+        self.stake_owner_funds(amount.into()).into()
+    }
+
+    fn redeem_owner_stake(&mut self, amount: YoctoStake) -> BatchId {
+        self.assert_predecessor_is_owner();
+        self.assert_not_paused();
+        let mut account = self.registered_account(&self.owner_id);
+        let batch_id = self.redeem_stake_for_account(&mut account, amount.into());
         self.save_registered_account(&account);
+        self.log_redeem_stake_batch(batch_id.clone().into());
+        log(OwnerStakeRedeemed {
+            batch_id: batch_id.clone().into(),
+            amount: amount.value(),
+        });
+        batch_id
     }
 
     fn withdraw_all_owner_balance(&mut self) -> YoctoNear {
         self.assert_predecessor_is_owner();
+        self.assert_not_paused();
         let owner_available_balance = self.balances().contract_owner_available_balance;
         Promise::new(self.owner_id.clone()).transfer(owner_available_balance.value());
         owner_available_balance
@@ -77,6 +96,7 @@ impl ContractOwner for Contract {
 
     fn withdraw_owner_balance(&mut self, amount: YoctoNear) {
         self.assert_predecessor_is_owner();
+        self.assert_not_paused();
         let owner_available_balance = self.balances().contract_owner_available_balance;
         assert!(
             owner_available_balance.value() >= amount.value(),
@@ -86,13 +106,31 @@ impl ContractOwner for Contract {
     }
 }
 
+impl Contract {
+    /// moves the NEAR from the contract owner balance into the owner's registered account stake batch
+    fn stake_owner_funds(&mut self, amount: domain::YoctoNear) -> domain::BatchId {
+        let mut account = self.registered_account(&self.owner_id);
+        self.assert_stake_batches_verified(&account);
+        self.contract_owner_balance -= amount;
+        let batch_id = self.deposit_near_for_account_to_stake(&mut account, amount);
+        self.save_registered_account(&account);
+        self.log_stake_batch(batch_id);
+        log(OwnerBalanceStaked {
+            batch_id: batch_id.value(),
+            amount: amount.value(),
+            contract_owner_balance: self.contract_owner_balance.value(),
+        });
+        batch_id
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::interface::ContractFinancials;
     use crate::near::YOCTO;
     use crate::test_utils::*;
-    use near_sdk::{testing_env, MockedBlockchain};
+    use near_sdk::{test_utils::get_logs, testing_env, MockedBlockchain};
     use std::convert::TryFrom;
 
     #[test]
@@ -263,10 +301,66 @@ mod test {
         context.register_owner();
         let contract = &mut context.contract;
 
-        contract.stake_owner_balance(YOCTO.into());
+        let contract_owner_balance = contract.contract_owner_balance;
+        let batch_id = contract.stake_owner_balance(YOCTO.into());
         let account = contract
             .lookup_account(ValidAccountId::try_from(contract.owner_id.as_str()).unwrap())
             .unwrap();
-        assert!(account.stake_batch.is_some());
+        let stake_batch = account.stake_batch.unwrap();
+        assert_eq!(stake_batch.id, batch_id);
+        assert_eq!(stake_batch.balance.amount, YOCTO.into());
+        assert_eq!(
+            contract.stake_batch.unwrap().balance().amount(),
+            YOCTO.into()
+        );
+        // the NEAR is moved out of the contract owner balance
+        assert_eq!(
+            contract.contract_owner_balance,
+            contract_owner_balance - YOCTO.into()
+        );
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("OwnerBalanceStaked")));
+    }
+
+    #[test]
+    #[should_panic(expected = "owner balance is too low to fulfill stake request")]
+    fn stake_owner_balance_insufficient_funds() {
+        let mut context = TestContext::with_registered_account();
+        context.register_owner();
+        let contract = &mut context.contract;
+
+        let owner_available_balance = contract.owner_available_balance();
+        contract.stake_owner_balance((owner_available_balance.value() + 1).into());
+    }
+
+    #[test]
+    fn redeem_owner_stake_success() {
+        let mut context = TestContext::with_registered_account();
+        context.register_owner();
+        let contract = &mut context.contract;
+
+        let mut account = contract.registered_account(TEST_OWNER_ID);
+        account.apply_stake_credit((10 * YOCTO).into());
+        contract.save_registered_account(&account);
+
+        let batch_id = contract.redeem_owner_stake((4 * YOCTO).into());
+        let account = contract
+            .lookup_account(ValidAccountId::try_from(TEST_OWNER_ID).unwrap())
+            .unwrap();
+        assert_eq!(account.stake.unwrap().amount, (6 * YOCTO).into());
+        let redeem_stake_batch = account.redeem_stake_batch.unwrap();
+        assert_eq!(redeem_stake_batch.id, batch_id);
+        assert_eq!(redeem_stake_batch.balance.amount, (4 * YOCTO).into());
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("OwnerStakeRedeemed")));
+    }
+
+    #[test]
+    #[should_panic(expected = "contract call is only allowed by the contract owner")]
+    fn redeem_owner_stake_called_by_non_owner() {
+        let mut context = TestContext::with_registered_account();
+        context.contract.redeem_owner_stake(YOCTO.into());
     }
 }
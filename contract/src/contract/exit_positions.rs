@@ -0,0 +1,277 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::core::Hash;
+use crate::errors::exit_positions::{
+    EXIT_POSITION_ALREADY_EXISTS, EXIT_POSITION_NOT_FOUND, EXIT_POSITION_STORAGE_FEE_REQUIRED,
+    NOTHING_TO_EXIT,
+};
+use crate::interface::exit_positions::events::{
+    ExitPositionCancelled, ExitPositionCompleted, ExitPositionPending, ExitPositionRequested,
+};
+use crate::interface::{ExitPosition, ExitPositions};
+use crate::near::log;
+use crate::*;
+use near_sdk::{json_types::ValidAccountId, near_bindgen, Promise};
+
+#[near_bindgen]
+impl ExitPositions for Contract {
+    #[payable]
+    fn exit_position(&mut self, beneficiary: ValidAccountId) -> ExitPosition {
+        self.assert_not_paused();
+        let mut account = self.predecessor_registered_account();
+        let account_id = env::predecessor_account_id();
+        let account_id_hash = Hash::from(&account_id);
+        assert!(
+            self.exit_positions.get(&account_id_hash).is_none(),
+            EXIT_POSITION_ALREADY_EXISTS
+        );
+
+        self.claim_receipt_funds(&mut account);
+        let (redeemed, batch_id) = match account.stake {
+            Some(stake) => {
+                let amount = stake.amount();
+                let batch_id: domain::BatchId =
+                    self.redeem_stake_for_account(&mut account, amount).into();
+                self.bill_account_storage_growth(&mut account);
+                self.save_registered_account(&account);
+                self.log_redeem_stake_batch(batch_id);
+                (amount, Some(batch_id))
+            }
+            None => {
+                assert!(
+                    account.redeem_stake_batch.is_some()
+                        || account.next_redeem_stake_batch.is_some(),
+                    NOTHING_TO_EXIT
+                );
+                (0.into(), None)
+            }
+        };
+
+        let position = domain::ExitPosition::new(
+            beneficiary.into(),
+            redeemed,
+            batch_id,
+            domain::BlockTimeHeight::from_env(),
+        );
+        let storage_fee = self.insert_exit_position(&account_id_hash, position);
+        let refund = env::attached_deposit() - storage_fee.value();
+        if refund > 0 {
+            Promise::new(account_id.clone()).transfer(refund);
+        }
+
+        let position = self.exit_positions.get(&account_id_hash).unwrap();
+        log(ExitPositionRequested {
+            account_id: &account_id,
+            beneficiary: position.beneficiary(),
+            redeemed: redeemed.value(),
+            batch_id: batch_id.map(|batch_id| batch_id.value()),
+        });
+        position.into()
+    }
+
+    fn process_exit(&mut self, account_id: ValidAccountId) -> Option<interface::YoctoNear> {
+        self.assert_not_paused();
+        let account_id: AccountId = account_id.into();
+        let account_id_hash = Hash::from(&account_id);
+        let position = self
+            .exit_positions
+            .get(&account_id_hash)
+            .expect(EXIT_POSITION_NOT_FOUND);
+
+        let mut account = self.registered_account(&account_id);
+        // the exit position pays out the redeem proceeds to the beneficiary instead of forwarding them
+        let redeem_forwarding = account.redeem_forwarding.take();
+        self.claim_receipt_funds(&mut account);
+        account.redeem_forwarding = redeem_forwarding;
+        if account.redeem_stake_batch.is_some() || account.next_redeem_stake_batch.is_some() {
+            log(ExitPositionPending {
+                account_id: &account_id,
+                redeem_stake_batch_id: account.redeem_stake_batch.map(|batch| batch.id().value()),
+                next_redeem_stake_batch_id: account
+                    .next_redeem_stake_batch
+                    .map(|batch| batch.id().value()),
+            });
+            return None;
+        }
+
+        self.exit_positions.remove(&account_id_hash);
+        let storage_fee = position.storage_fee();
+        self.total_account_storage_escrow -= storage_fee;
+        if storage_fee.value() > 0 {
+            Promise::new(position.beneficiary().to_string()).transfer(storage_fee.value());
+        }
+
+        let balance = account.near.map_or(0.into(), |balance| balance.amount());
+        if balance.value() > 0 {
+            self.watch_aggregates(|contract| {
+                contract.debit_near_funds(
+                    &account_id,
+                    &mut account,
+                    balance,
+                    position.beneficiary().to_string(),
+                )
+            });
+        }
+
+        let amount = balance + storage_fee;
+        log(ExitPositionCompleted {
+            account_id: &account_id,
+            beneficiary: position.beneficiary(),
+            amount: amount.value(),
+        });
+        Some(amount.into())
+    }
+
+    fn cancel_exit_position(&mut self) -> ExitPosition {
+        // the account must be registered
+        self.predecessor_registered_account();
+        let account_id = env::predecessor_account_id();
+        let account_id_hash = Hash::from(&account_id);
+        let position = self
+            .exit_positions
+            .get(&account_id_hash)
+            .expect(EXIT_POSITION_NOT_FOUND);
+
+        self.exit_positions.remove(&account_id_hash);
+        let storage_fee = position.storage_fee();
+        self.total_account_storage_escrow -= storage_fee;
+        if storage_fee.value() > 0 {
+            Promise::new(account_id.clone()).transfer(storage_fee.value());
+        }
+
+        log(ExitPositionCancelled {
+            account_id: &account_id,
+            beneficiary: position.beneficiary(),
+        });
+        position.into()
+    }
+
+    fn exit_position_status(&self, account_id: ValidAccountId) -> Option<ExitPosition> {
+        self.exit_positions
+            .get(&Hash::from(account_id))
+            .map(ExitPosition::from)
+    }
+}
+
+impl Contract {
+    /// inserts the exit position record - the storage fee is escrowed
+    /// - returns the storage fee, which is paid from the attached deposit
+    fn insert_exit_position(
+        &mut self,
+        account_id_hash: &Hash,
+        mut position: domain::ExitPosition,
+    ) -> domain::YoctoNear {
+        let initial_storage_usage = env::storage_usage();
+        self.exit_positions.insert(account_id_hash, &position);
+        let storage_fee = self.storage_fee(env::storage_usage() - initial_storage_usage);
+        assert!(
+            env::attached_deposit() >= storage_fee.value(),
+            "{}: storage fee = {} yoctoNEAR",
+            EXIT_POSITION_STORAGE_FEE_REQUIRED,
+            storage_fee
+        );
+        position.set_storage_fee(storage_fee);
+        self.exit_positions.insert(account_id_hash, &position);
+        self.total_account_storage_escrow += storage_fee;
+        storage_fee
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{test_utils::get_logs, testing_env, MockedBlockchain};
+
+    const BENEFICIARY_ID: &str = "beneficiary.near";
+
+    fn exit(ctx: &mut TestContext) -> ExitPosition {
+        let account_id = ctx.account_id;
+        let mut context = ctx.set_predecessor_account_id(account_id);
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        ctx.exit_position(to_valid_account_id(BENEFICIARY_ID))
+    }
+
+    /// simulates the redeem stake batch workflow running to completion
+    /// - returns the NEAR value of the redeemed STAKE
+    fn complete_redeem_stake_batch(ctx: &mut TestContext) -> domain::YoctoNear {
+        let batch = ctx.redeem_stake_batch.take().unwrap();
+        let receipt = batch.create_receipt(ctx.stake_token_value, ctx.staking_pool_id.clone());
+        ctx.redeem_stake_batch_receipts
+            .insert(&batch.id(), &receipt);
+        ctx.total_near.credit(receipt.stake_near_value());
+        receipt.stake_near_value()
+    }
+
+    #[test]
+    fn exit_position_transfers_near_to_beneficiary_once_redeemed() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+
+        let position = exit(&mut ctx);
+        assert_eq!(position.beneficiary, BENEFICIARY_ID);
+        assert_eq!(position.redeemed, (10 * YOCTO).into());
+        assert!(position.batch_id.is_some());
+        assert!(position.storage_fee.value() > 0);
+        assert!(ctx.registered_account(account_id).stake.is_none());
+        assert_eq!(
+            ctx.exit_position_status(to_valid_account_id(account_id)),
+            Some(position.clone())
+        );
+
+        // the exit is pending until the redeem stake batch completes
+        assert!(ctx.process_exit(to_valid_account_id(account_id)).is_none());
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("ExitPositionPending")));
+
+        let redeemed_near = complete_redeem_stake_batch(&mut ctx);
+        let amount = ctx.process_exit(to_valid_account_id(account_id)).unwrap();
+        assert_eq!(
+            amount.value(),
+            redeemed_near.value() + position.storage_fee.value()
+        );
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("ExitPositionCompleted")));
+        assert!(ctx
+            .exit_position_status(to_valid_account_id(account_id))
+            .is_none());
+        assert!(ctx.registered_account(account_id).near.is_none());
+
+        let transferred: u128 = deserialize_receipts()
+            .iter()
+            .filter(|receipt| receipt.receiver_id == BENEFICIARY_ID)
+            .map(|receipt| match receipt.actions[0] {
+                Action::Transfer { deposit } => deposit,
+                _ => panic!("expected transfer"),
+            })
+            .sum();
+        assert_eq!(transferred, amount.value());
+    }
+
+    #[test]
+    fn cancel_exit_position() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+        let position = exit(&mut ctx);
+        let total_account_storage_escrow = ctx.total_account_storage_escrow;
+
+        assert_eq!(ctx.cancel_exit_position(), position);
+        assert_eq!(
+            ctx.total_account_storage_escrow,
+            total_account_storage_escrow - position.storage_fee.into()
+        );
+        assert!(ctx
+            .exit_position_status(to_valid_account_id(account_id))
+            .is_none());
+        // the redeemed STAKE is not restored
+        assert!(ctx
+            .registered_account(account_id)
+            .redeem_stake_batch
+            .is_some());
+    }
+}
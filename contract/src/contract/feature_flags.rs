@@ -0,0 +1,245 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::core::Hash;
+use crate::domain::{
+    BlockTimeHeight, FeatureRollout, MAX_FEATURE_ALLOWLIST_LEN, MAX_FEATURE_NAME_LEN,
+    MAX_FEATURE_ROLLOUTS,
+};
+use crate::errors::feature_flags::{
+    FEATURE_ALLOWLIST_FULL, FEATURE_NAME_INVALID, FEATURE_NOT_FOUND,
+    FEATURE_ROLLOUT_PERCENTAGE_INVALID, MAX_FEATURE_ROLLOUTS_REACHED,
+};
+use crate::interface::feature_flags::events::{
+    FeatureAllowlistUpdated, FeatureRolloutRemoved, FeatureRolloutUpdated,
+};
+use crate::interface::{self, FeatureFlags};
+use crate::near::log;
+use crate::*;
+use near_sdk::{json_types::ValidAccountId, near_bindgen};
+
+#[near_bindgen]
+impl FeatureFlags for Contract {
+    fn set_feature_rollout(&mut self, name: String, percentage: u8) -> interface::FeatureRollout {
+        self.assert_predecessor_is_operator();
+        assert!(
+            !name.trim().is_empty() && name.len() <= MAX_FEATURE_NAME_LEN,
+            FEATURE_NAME_INVALID
+        );
+        assert!(percentage <= 100, FEATURE_ROLLOUT_PERCENTAGE_INVALID);
+
+        let rollout = match self.feature_rollouts.get(&Hash::from(&name)) {
+            Some(mut rollout) => {
+                rollout.set_percentage(percentage, BlockTimeHeight::from_env());
+                rollout
+            }
+            None => {
+                assert!(
+                    self.feature_names.len() < MAX_FEATURE_ROLLOUTS,
+                    MAX_FEATURE_ROLLOUTS_REACHED
+                );
+                self.feature_names.push(name.clone());
+                FeatureRollout::new(name.clone(), percentage, BlockTimeHeight::from_env())
+            }
+        };
+        self.feature_rollouts.insert(&Hash::from(&name), &rollout);
+        log(FeatureRolloutUpdated {
+            name: &name,
+            percentage,
+        });
+        rollout.into()
+    }
+
+    fn allow_feature(
+        &mut self,
+        name: String,
+        account_id: ValidAccountId,
+    ) -> interface::FeatureRollout {
+        self.assert_predecessor_is_operator();
+        let mut rollout = self.expect_feature_rollout(&name);
+        assert!(
+            rollout.is_allowlisted(account_id.as_ref())
+                || rollout.allowlist().len() < MAX_FEATURE_ALLOWLIST_LEN,
+            FEATURE_ALLOWLIST_FULL
+        );
+        if rollout.allow(account_id.as_ref(), BlockTimeHeight::from_env()) {
+            self.feature_rollouts.insert(&Hash::from(&name), &rollout);
+            log(FeatureAllowlistUpdated {
+                name: &name,
+                account_id: account_id.as_ref(),
+                allowed: true,
+            });
+        }
+        rollout.into()
+    }
+
+    fn disallow_feature(
+        &mut self,
+        name: String,
+        account_id: ValidAccountId,
+    ) -> interface::FeatureRollout {
+        self.assert_predecessor_is_operator();
+        let mut rollout = self.expect_feature_rollout(&name);
+        if rollout.disallow(account_id.as_ref(), BlockTimeHeight::from_env()) {
+            self.feature_rollouts.insert(&Hash::from(&name), &rollout);
+            log(FeatureAllowlistUpdated {
+                name: &name,
+                account_id: account_id.as_ref(),
+                allowed: false,
+            });
+        }
+        rollout.into()
+    }
+
+    fn remove_feature_rollout(&mut self, name: String) -> bool {
+        self.assert_predecessor_is_operator();
+        if name.is_empty() || self.feature_rollouts.remove(&Hash::from(&name)).is_none() {
+            return false;
+        }
+        self.feature_names
+            .retain(|feature_name| *feature_name != name);
+        log(FeatureRolloutRemoved { name: &name });
+        true
+    }
+
+    fn feature_enabled(&self, name: String, account_id: ValidAccountId) -> bool {
+        self.is_feature_enabled(&name, account_id.as_ref())
+    }
+
+    fn feature_rollout(&self, name: String) -> Option<interface::FeatureRollout> {
+        if name.is_empty() {
+            return None;
+        }
+        self.feature_rollouts
+            .get(&Hash::from(&name))
+            .map(interface::FeatureRollout::from)
+    }
+
+    fn feature_rollouts(&self) -> Vec<interface::FeatureRollout> {
+        self.feature_names
+            .iter()
+            .filter_map(|name| self.feature_rollouts.get(&Hash::from(name)))
+            .map(interface::FeatureRollout::from)
+            .collect()
+    }
+}
+
+impl Contract {
+    /// used to gate new behaviors while they are being rolled out
+    /// - returns false if the feature has no rollout
+    pub(crate) fn is_feature_enabled(&self, name: &str, account_id: &str) -> bool {
+        if name.is_empty() {
+            return false;
+        }
+        self.feature_rollouts
+            .get(&Hash::from(name))
+            .map_or(false, |rollout| rollout.is_enabled(account_id))
+    }
+
+    fn expect_feature_rollout(&self, name: &str) -> FeatureRollout {
+        if name.is_empty() {
+            panic!(FEATURE_NOT_FOUND);
+        }
+        self.feature_rollouts
+            .get(&Hash::from(name))
+            .expect(FEATURE_NOT_FOUND)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    fn set_operator_as_predecessor(ctx: &mut TestContext) {
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.contract.operator_id.clone();
+        testing_env!(context);
+    }
+
+    fn account_ids() -> Vec<String> {
+        (0..200).map(|i| format!("account-{}.near", i)).collect()
+    }
+
+    #[test]
+    fn features_without_rollout_are_disabled() {
+        let ctx = TestContext::new();
+        assert!(!ctx.feature_enabled(
+            "instant_redeem".to_string(),
+            to_valid_account_id("bob.near")
+        ));
+        assert!(ctx.feature_rollout("instant_redeem".to_string()).is_none());
+        assert!(ctx.feature_rollouts().is_empty());
+    }
+
+    #[test]
+    fn rollout_percentage_cohort_grows_as_percentage_is_raised() {
+        let mut ctx = TestContext::new();
+        set_operator_as_predecessor(&mut ctx);
+        let name = "instant_redeem";
+
+        let enabled = |ctx: &TestContext| -> Vec<String> {
+            account_ids()
+                .into_iter()
+                .filter(|account_id| ctx.is_feature_enabled(name, account_id))
+                .collect()
+        };
+
+        ctx.set_feature_rollout(name.to_string(), 0);
+        assert!(enabled(&ctx).is_empty());
+
+        ctx.set_feature_rollout(name.to_string(), 25);
+        let cohort_25 = enabled(&ctx);
+        assert!(!cohort_25.is_empty() && cohort_25.len() < 100);
+
+        ctx.set_feature_rollout(name.to_string(), 50);
+        let cohort_50 = enabled(&ctx);
+        assert!(cohort_50.len() > cohort_25.len());
+        assert!(cohort_25
+            .iter()
+            .all(|account_id| cohort_50.contains(account_id)));
+
+        let rollout = ctx.set_feature_rollout(name.to_string(), 100);
+        assert_eq!(rollout.percentage, 100);
+        assert_eq!(enabled(&ctx).len(), 200);
+        assert_eq!(ctx.feature_rollouts(), vec![rollout]);
+    }
+
+    #[test]
+    fn allowlisted_accounts_are_enabled_regardless_of_percentage() {
+        let mut ctx = TestContext::new();
+        set_operator_as_predecessor(&mut ctx);
+        let name = "auto_stake".to_string();
+        ctx.set_feature_rollout(name.clone(), 0);
+
+        let rollout = ctx.allow_feature(name.clone(), to_valid_account_id("bob.near"));
+        assert_eq!(rollout.allowlist, vec!["bob.near".to_string()]);
+        assert!(ctx.feature_enabled(name.clone(), to_valid_account_id("bob.near")));
+        assert!(!ctx.feature_enabled(name.clone(), to_valid_account_id("alice.near")));
+
+        // allowlisting is idempotent
+        let rollout = ctx.allow_feature(name.clone(), to_valid_account_id("bob.near"));
+        assert_eq!(rollout.allowlist.len(), 1);
+
+        let rollout = ctx.disallow_feature(name.clone(), to_valid_account_id("bob.near"));
+        assert!(rollout.allowlist.is_empty());
+        assert!(!ctx.feature_enabled(name, to_valid_account_id("bob.near")));
+    }
+
+    #[test]
+    fn remove_feature_rollout() {
+        let mut ctx = TestContext::new();
+        set_operator_as_predecessor(&mut ctx);
+        ctx.set_feature_rollout("instant_redeem".to_string(), 100);
+        ctx.set_feature_rollout("auto_stake".to_string(), 100);
+
+        assert!(ctx.remove_feature_rollout("instant_redeem".to_string()));
+        assert!(!ctx.remove_feature_rollout("instant_redeem".to_string()));
+        assert!(!ctx.feature_enabled(
+            "instant_redeem".to_string(),
+            to_valid_account_id("bob.near")
+        ));
+        let rollouts = ctx.feature_rollouts();
+        assert_eq!(rollouts.len(), 1);
+        assert_eq!(rollouts[0].name, "auto_stake");
+    }
+}
@@ -1,12 +1,15 @@
 use crate::interface::{
     BlockHeight, BlockTimestamp, ContractBalances, ContractFinancials, EarningsDistribution,
+    FinancialsSnapshot, LiquidityReport, PublicGoodsDonation, PublicGoodsDonations,
 };
 
 //required in order for near_bindgen macro to work outside of lib.rs
 use crate::config::CONTRACT_MIN_OPERATIONAL_BALANCE;
+use crate::core::U256;
+use crate::domain::NearLiquidityInflow;
 use crate::near::log;
 use crate::*;
-use near_sdk::near_bindgen;
+use near_sdk::{near_bindgen, Promise};
 
 #[near_bindgen]
 impl ContractFinancials for Contract {
@@ -21,14 +24,22 @@ impl ContractFinancials for Contract {
             total_available_unstaked_near: self.total_near.amount().into(),
             near_liquidity_pool: self.near_liquidity_pool.into(),
             total_account_storage_escrow: self.total_account_storage_escrow.into(),
+            total_storage_refunds: self.total_storage_refunds.into(),
+            rebate_funds: self.rebate_funds().into(),
+            registration_pool: self.registration_pool.into(),
+            batch_reservation_escrow: self.total_batch_reservation_escrow.into(),
+            idle_near_swept: self.idle_near_sweep.swept().into(),
+            idle_near_yield_pool: self.idle_near_sweep.yield_pool().into(),
 
             contract_owner_storage_usage_cost: self.contract_owner_storage_usage_cost().into(),
             contract_owner_available_balance: self.owner_available_balance().into(),
 
             contract_owner_balance: self.contract_owner_balance.into(),
             contract_earnings: self.contract_earnings().into(),
+            protocol_fee_earnings: self.protocol_fee_earnings().into(),
             contract_owner_earnings: self.contract_owner_earnings().into(),
             user_accounts_earnings: self.user_accounts_earnings().into(),
+            public_goods_earnings: self.public_goods_earnings().into(),
             collected_earnings: self.collected_earnings.into(),
 
             contract_required_operational_balance: CONTRACT_MIN_OPERATIONAL_BALANCE.into(),
@@ -43,11 +54,50 @@ impl ContractFinancials for Contract {
         *self.collected_earnings += env::account_balance();
         self.collected_earnings.into()
     }
+
+    fn financials_history(&self, from: u64, limit: u32) -> Vec<FinancialsSnapshot> {
+        let to = self
+            .financials_history_len
+            .min(from.saturating_add(limit as u64));
+        (from..to)
+            .filter_map(|index| self.financials_history.get(&index))
+            .map(FinancialsSnapshot::from)
+            .collect()
+    }
+
+    fn financials_history_len(&self) -> u64 {
+        self.financials_history_len
+    }
+
+    fn liquidity_report(&self) -> LiquidityReport {
+        LiquidityReport::new(
+            self.near_liquidity_pool,
+            self.near_liquidity_stats,
+            env::block_index().into(),
+        )
+    }
+
+    fn public_goods_donations(&self) -> PublicGoodsDonations {
+        let last_donation = self.public_goods_donations.last_donation();
+        PublicGoodsDonations {
+            beneficiary_id: self
+                .config
+                .public_goods_beneficiary_id()
+                .map(|account_id| account_id.to_string()),
+            percentage: self.config.public_goods_percentage(),
+            pending_donation: self.public_goods_earnings().into(),
+            total_donated: self.public_goods_donations.total().into(),
+            donations: self.public_goods_donations.count(),
+            last_donation: last_donation.map(|(amount, _)| amount.into()),
+            last_donation_block_time_height: last_donation
+                .map(|(_, block_time_height)| block_time_height.into()),
+        }
+    }
 }
 
 impl Contract {
     pub fn total_contract_storage_usage_cost(&self) -> YoctoNear {
-        (env::storage_usage() as u128 * self.config.storage_cost_per_byte().value()).into()
+        self.storage_fee(env::storage_usage())
     }
 
     pub fn total_available_balance(&self) -> YoctoNear {
@@ -68,7 +118,16 @@ impl Contract {
         (self.customer_batched_stake_deposits().value()
             + self.total_near.amount().value()
             + self.near_liquidity_pool.value()
-            + self.total_account_storage_escrow.value())
+            + self.liquidity_provider_pool.balance().value()
+            + self.total_account_storage_escrow.value()
+            + self.total_storage_refunds.value()
+            + self.rebate_funds().value()
+            + self.registration_pool.value()
+            + self.total_batch_reservation_escrow.value()
+            + self.idle_near_sweep.yield_pool().value()
+            // swept NEAR is accounted for in both the account NEAR balances and the stake batch or
+            // the staked NEAR balance
+            - self.idle_near_sweep.swept().value())
         .into()
     }
 
@@ -85,10 +144,22 @@ impl Contract {
         self.contract_earnings() + self.collected_earnings
     }
 
-    /// percentage of earnings from contract gas rewards and collected earnings that are allotted to
-    /// the contract owner
+    /// share of the earnings that is skimmed as the protocol fee and staked on behalf of the treasury
+    pub fn protocol_fee_earnings(&self) -> YoctoNear {
+        self.protocol_fee_share(self.total_earnings())
+    }
+
+    fn protocol_fee_share(&self, amount: YoctoNear) -> YoctoNear {
+        let basis_points = self.config.protocol_fee_basis_points();
+        (U256::from(amount.value()) * U256::from(basis_points) / U256::from(10_000))
+            .as_u128()
+            .into()
+    }
+
+    /// percentage of earnings from contract gas rewards and collected earnings, net of the protocol
+    /// fee, that are allotted to the contract owner
     pub fn contract_owner_earnings(&self) -> YoctoNear {
-        self.contract_owner_share(self.total_earnings())
+        self.contract_owner_share(self.total_earnings() - self.protocol_fee_earnings())
     }
 
     fn contract_owner_share(&self, amount: YoctoNear) -> YoctoNear {
@@ -97,14 +168,33 @@ impl Contract {
         (amount.value() / 100 * contract_owner_earnings_percentage).into()
     }
 
+    /// share of the user account earnings that will be donated to the public goods beneficiary
+    pub fn public_goods_earnings(&self) -> YoctoNear {
+        self.public_goods_share(
+            self.total_earnings() - self.protocol_fee_earnings() - self.contract_owner_earnings(),
+        )
+    }
+
+    /// returns 0 if public goods donations are not configured
+    fn public_goods_share(&self, user_accounts_earnings: YoctoNear) -> YoctoNear {
+        if self.config.public_goods_beneficiary_id().is_none() {
+            return 0.into();
+        }
+        let percentage = self.config.public_goods_percentage();
+        (U256::from(user_accounts_earnings.value()) * U256::from(percentage) / U256::from(100))
+            .as_u128()
+            .into()
+    }
+
     pub fn user_accounts_earnings(&self) -> YoctoNear {
-        self.total_earnings() - self.contract_owner_earnings()
+        self.total_earnings()
+            - self.protocol_fee_earnings()
+            - self.contract_owner_earnings()
+            - self.public_goods_earnings()
     }
 
     pub fn contract_owner_storage_usage_cost(&self) -> YoctoNear {
-        (self.contract_initial_storage_usage.value() as u128
-            * self.config.storage_cost_per_byte().value())
-        .into()
+        self.storage_fee(self.contract_initial_storage_usage.value())
     }
 
     pub fn owner_available_balance(&self) -> YoctoNear {
@@ -116,9 +206,32 @@ impl Contract {
         }
     }
 
+    /// returns how much of the collected earnings will be distributed in the next stake batch run
+    /// - large windfalls are spread across multiple batch runs, i.e., the amount is capped by
+    ///   [max_earnings_distribution_percentage](crate::config::Config::max_earnings_distribution_percentage)
+    ///   and [max_earnings_distribution_amount](crate::config::Config::max_earnings_distribution_amount)
+    pub fn collected_earnings_distribution_amount(&self) -> YoctoNear {
+        let percentage = self.config.max_earnings_distribution_percentage();
+        let amount = (U256::from(self.collected_earnings.value()) * U256::from(percentage)
+            / U256::from(100))
+        .as_u128();
+        let max_amount = self.config.max_earnings_distribution_amount().value();
+        if max_amount > 0 {
+            amount.min(max_amount).into()
+        } else {
+            amount.into()
+        }
+    }
+
     pub fn distribute_earnings(&mut self) {
-        let contract_owner_earnings = self.contract_owner_earnings();
-        let user_accounts_earnings = self.user_accounts_earnings();
+        let collected_earnings = self.collected_earnings_distribution_amount();
+        let earnings = self.contract_earnings() + collected_earnings;
+        let protocol_fee = self.protocol_fee_share(earnings);
+        let contract_owner_earnings = self.contract_owner_share(earnings - protocol_fee);
+        let public_goods_donation =
+            self.public_goods_share(earnings - protocol_fee - contract_owner_earnings);
+        let user_accounts_earnings =
+            earnings - protocol_fee - contract_owner_earnings - public_goods_donation;
 
         self.contract_owner_balance = self
             .contract_owner_balance
@@ -126,17 +239,280 @@ impl Contract {
             .into();
 
         // funds added to liquidity pool distributes earnings to the user
-        self.near_liquidity_pool = self
-            .near_liquidity_pool
-            .saturating_add(user_accounts_earnings.value())
-            .into();
+        self.add_near_liquidity(user_accounts_earnings, NearLiquidityInflow::Earnings);
 
-        // collected earnings have been distributed
-        self.collected_earnings = 0.into();
+        if protocol_fee.value() > 0 {
+            self.stake_protocol_fee(protocol_fee);
+        }
+
+        if public_goods_donation.value() > 0 {
+            if let Some(beneficiary_id) = self.config.public_goods_beneficiary_id() {
+                let beneficiary_id = beneficiary_id.to_string();
+                self.donate_to_public_goods(beneficiary_id, public_goods_donation);
+            }
+        }
+
+        // any remaining collected earnings will be distributed in subsequent stake batch runs
+        self.collected_earnings -= collected_earnings;
+
+        if (protocol_fee + contract_owner_earnings + user_accounts_earnings + public_goods_donation)
+            .value()
+            > 0
+        {
+            self.record_financials_snapshot(domain::FinancialsSnapshot::new(
+                self.contract_owner_balance,
+                contract_owner_earnings,
+                user_accounts_earnings,
+                collected_earnings,
+                self.total_user_accounts_balance(),
+            ));
+        }
 
         log(EarningsDistribution {
+            protocol_fee: protocol_fee.into(),
             contract_owner_earnings: contract_owner_earnings.into(),
             user_accounts_earnings: user_accounts_earnings.into(),
+            public_goods_donation: public_goods_donation.into(),
         })
     }
+
+    /// transfers the donation to the public goods beneficiary
+    /// - if the transfer fails, e.g., because the beneficiary account does not exist, then the NEAR
+    ///   is refunded back to the contract and will be picked up as contract earnings
+    fn donate_to_public_goods(&mut self, beneficiary_id: AccountId, amount: YoctoNear) {
+        Promise::new(beneficiary_id.clone()).transfer(amount.value());
+        self.public_goods_donations
+            .record(amount, domain::BlockTimeHeight::from_env());
+        log(PublicGoodsDonation {
+            beneficiary_id: &beneficiary_id,
+            amount: amount.value(),
+        });
+    }
+
+    fn record_financials_snapshot(&mut self, snapshot: domain::FinancialsSnapshot) {
+        self.financials_history
+            .insert(&self.financials_history_len, &snapshot);
+        self.financials_history_len += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::NearLiquidityOutflow;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{serde_json, testing_env, MockedBlockchain};
+
+    #[test]
+    fn financials_history() {
+        let mut test_ctx = TestContext::new();
+        let contract = &mut test_ctx.contract;
+        let mut context = test_ctx.context.clone();
+
+        // no earnings to distribute - no snapshot is recorded
+        contract.distribute_earnings();
+        assert_eq!(contract.financials_history_len(), 0);
+        assert!(contract.financials_history(0, 10).is_empty());
+
+        for i in 1..=3 {
+            context.epoch_height = i;
+            testing_env!(context.clone());
+            contract.collected_earnings = (i as u128 * YOCTO).into();
+            let owner_balance = contract.contract_owner_balance;
+            let contract_owner_earnings = contract.contract_owner_earnings();
+            let user_accounts_earnings = contract.user_accounts_earnings();
+
+            contract.distribute_earnings();
+
+            assert_eq!(contract.financials_history_len(), i);
+            let snapshot = contract.financials_history(i - 1, 1).pop().unwrap();
+            assert_eq!(snapshot.block_time_height.epoch_height.0 .0, i);
+            assert_eq!(
+                snapshot.collected_earnings.value(),
+                i as u128 * YOCTO,
+                "collected earnings should be recorded before they are cleared"
+            );
+            assert_eq!(
+                snapshot.contract_owner_earnings,
+                contract_owner_earnings.into()
+            );
+            assert_eq!(
+                snapshot.user_accounts_earnings,
+                user_accounts_earnings.into()
+            );
+            assert_eq!(
+                snapshot.contract_owner_balance,
+                (owner_balance + contract_owner_earnings).into()
+            );
+            assert_eq!(
+                snapshot.total_user_accounts_balance,
+                contract.total_user_accounts_balance().into()
+            );
+        }
+
+        let history = contract.financials_history(0, 10);
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].block_time_height.epoch_height.0 .0, 1);
+        assert_eq!(history[2].block_time_height.epoch_height.0 .0, 3);
+
+        let history = contract.financials_history(1, 1);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].block_time_height.epoch_height.0 .0, 2);
+
+        assert!(contract.financials_history(3, 10).is_empty());
+        assert!(contract.financials_history(0, 0).is_empty());
+    }
+
+    #[test]
+    fn partial_earnings_distribution() {
+        let mut test_ctx = TestContext::new();
+        let contract = &mut test_ctx.contract;
+
+        let config: interface::Config =
+            serde_json::from_str(r#"{"max_earnings_distribution_percentage": 10}"#).unwrap();
+        contract.config.merge(config);
+        contract.collected_earnings = (100 * YOCTO).into();
+
+        contract.distribute_earnings();
+        assert_eq!(contract.collected_earnings, (90 * YOCTO).into());
+        let snapshot = contract.financials_history(0, 1).pop().unwrap();
+        assert_eq!(snapshot.collected_earnings.value(), 10 * YOCTO);
+
+        // the absolute limit applies when it is lower than the percentage limit
+        let config: interface::Config = serde_json::from_str(&format!(
+            r#"{{"max_earnings_distribution_amount": "{}"}}"#,
+            5 * YOCTO
+        ))
+        .unwrap();
+        contract.config.merge(config);
+
+        contract.distribute_earnings();
+        assert_eq!(contract.collected_earnings, (85 * YOCTO).into());
+        let snapshot = contract.financials_history(1, 1).pop().unwrap();
+        assert_eq!(snapshot.collected_earnings.value(), 5 * YOCTO);
+    }
+
+    #[test]
+    fn liquidity_report() {
+        let mut test_ctx = TestContext::new();
+        let contract = &mut test_ctx.contract;
+
+        let report = contract.liquidity_report();
+        assert_eq!(report.near_liquidity_pool, 0.into());
+        assert_eq!(report.total_inflows, 0.into());
+        assert_eq!(report.total_outflows, 0.into());
+
+        contract.collected_earnings = (100 * YOCTO).into();
+        let user_accounts_earnings = contract.user_accounts_earnings();
+        contract.distribute_earnings();
+
+        contract.add_near_liquidity((10 * YOCTO).into(), NearLiquidityInflow::StakeBatch);
+        contract.consume_near_liquidity((3 * YOCTO).into(), NearLiquidityOutflow::Withdrawal);
+        contract.consume_near_liquidity(YOCTO.into(), NearLiquidityOutflow::Restake);
+
+        let report = contract.liquidity_report();
+        assert_eq!(report.added_from_earnings, user_accounts_earnings.into());
+        assert_eq!(report.added_from_stake_batches, (10 * YOCTO).into());
+        assert_eq!(report.consumed_by_withdrawals, (3 * YOCTO).into());
+        assert_eq!(report.restaked, YOCTO.into());
+        assert_eq!(
+            report.total_inflows,
+            (user_accounts_earnings + (10 * YOCTO).into()).into()
+        );
+        assert_eq!(report.total_outflows, (4 * YOCTO).into());
+        assert_eq!(
+            report.near_liquidity_pool.value(),
+            report.total_inflows.value() - report.total_outflows.value()
+        );
+    }
+
+    #[test]
+    fn public_goods_donations_are_disabled_by_default() {
+        let mut test_ctx = TestContext::new();
+        let contract = &mut test_ctx.contract;
+        contract.collected_earnings = (100 * YOCTO).into();
+        assert_eq!(contract.public_goods_earnings(), 0.into());
+
+        contract.distribute_earnings();
+        assert!(deserialize_receipts().is_empty());
+        let donations = contract.public_goods_donations();
+        assert!(donations.beneficiary_id.is_none());
+        assert_eq!(donations.total_donated, 0.into());
+        assert_eq!(donations.donations, 0);
+    }
+
+    #[test]
+    fn public_goods_donation_is_taken_from_user_accounts_earnings() {
+        let mut test_ctx = TestContext::new();
+        let contract = &mut test_ctx.contract;
+        let config: interface::Config = serde_json::from_str(
+            r#"{"public_goods_beneficiary_id": "public-goods.near", "public_goods_percentage": 10}"#,
+        )
+        .unwrap();
+        contract.config.merge(config);
+        contract.collected_earnings = (100 * YOCTO).into();
+
+        let contract_owner_earnings = contract.contract_owner_earnings();
+        let public_goods_earnings = contract.public_goods_earnings();
+        let user_accounts_earnings = contract.user_accounts_earnings();
+        assert_eq!(
+            public_goods_earnings,
+            ((contract.total_earnings() - contract_owner_earnings).value() / 10).into()
+        );
+        assert_eq!(
+            contract_owner_earnings + public_goods_earnings + user_accounts_earnings,
+            contract.total_earnings()
+        );
+        let donations = contract.public_goods_donations();
+        assert_eq!(donations.pending_donation, public_goods_earnings.into());
+
+        contract.distribute_earnings();
+
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 1);
+        let receipt = &receipts[0];
+        assert_eq!(receipt.receiver_id, "public-goods.near");
+        match receipt.actions.first().unwrap() {
+            Action::Transfer { deposit } => assert_eq!(*deposit, public_goods_earnings.value()),
+            _ => panic!("expected Transfer action"),
+        }
+
+        let snapshot = contract.financials_history(0, 1).pop().unwrap();
+        assert_eq!(
+            snapshot.user_accounts_earnings,
+            user_accounts_earnings.into()
+        );
+
+        let donations = contract.public_goods_donations();
+        assert_eq!(
+            donations.beneficiary_id,
+            Some("public-goods.near".to_string())
+        );
+        assert_eq!(donations.percentage, 10);
+        assert_eq!(donations.total_donated, public_goods_earnings.into());
+        assert_eq!(donations.donations, 1);
+        assert_eq!(donations.last_donation, Some(public_goods_earnings.into()));
+        assert!(donations.last_donation_block_time_height.is_some());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "public_goods_beneficiary_id must be set when public_goods_percentage > 0"
+    )]
+    fn public_goods_percentage_requires_beneficiary() {
+        let mut test_ctx = TestContext::new();
+        let config: interface::Config =
+            serde_json::from_str(r#"{"public_goods_percentage": 10}"#).unwrap();
+        test_ctx.config.merge(config);
+    }
+
+    #[test]
+    #[should_panic(expected = "max_earnings_distribution_percentage must be within 1 - 100")]
+    fn max_earnings_distribution_percentage_zero() {
+        let mut test_ctx = TestContext::new();
+        let config: interface::Config =
+            serde_json::from_str(r#"{"max_earnings_distribution_percentage": 0}"#).unwrap();
+        test_ctx.config.merge(config);
+    }
 }
@@ -1,13 +1,18 @@
 use crate::*;
 use crate::{
     core::Hash,
-    domain::YoctoStake,
-    interface::{FungibleToken, Memo, ResolveTransferCall, TokenAmount, TransferCallMessage},
-    near::NO_DEPOSIT,
+    domain::{AccountActivityKind, YoctoStake},
+    errors::fungible_token::INSUFFICIENT_GAS_FOR_RECEIVER,
+    interface::{
+        self, fungible_token::events::TransferRefundBurned, FungibleToken, Memo,
+        ResolveTransferCall, TokenAmount, TransferCallMessage,
+    },
+    near::{events, NO_DEPOSIT},
 };
 use near_sdk::{
-    env, ext_contract, json_types::ValidAccountId, log, near_bindgen, serde_json, Promise,
-    PromiseResult,
+    env, ext_contract,
+    json_types::{ValidAccountId, U64},
+    log, near_bindgen, serde_json, Promise, PromiseResult,
 };
 #[allow(unused_imports)]
 use near_sdk::{AccountId, PromiseOrValue};
@@ -19,24 +24,12 @@ impl FungibleToken for Contract {
         &mut self,
         receiver_id: ValidAccountId,
         amount: TokenAmount,
-        _memo: Option<Memo>,
+        memo: Option<Memo>,
     ) {
-        assert_yocto_near_attached();
-        assert_token_amount_not_zero(&amount);
-
-        let stake_amount: YoctoStake = amount.value().into();
-
-        let mut sender = self.predecessor_registered_account();
-        self.claim_receipt_funds(&mut sender);
-        sender.apply_stake_debit(stake_amount);
-        // apply the 1 yoctoNEAR that was attached to the sender account's NEAR balance
-        sender.apply_near_credit(1.into());
-
-        let mut receiver = self.registered_account(receiver_id.as_ref());
-        receiver.apply_stake_credit(stake_amount);
-
-        self.save_registered_account(&sender);
-        self.save_registered_account(&receiver);
+        self.assert_not_paused();
+        self.measure_storage_delta("ft_transfer", |contract| {
+            contract.transfer_stake(receiver_id, amount, memo.as_deref())
+        });
     }
 
     #[payable]
@@ -45,9 +38,20 @@ impl FungibleToken for Contract {
         receiver_id: ValidAccountId,
         amount: TokenAmount,
         msg: TransferCallMessage,
-        _memo: Option<Memo>,
+        memo: Option<Memo>,
+        gas_for_receiver: Option<interface::Gas>,
     ) -> Promise {
-        self.ft_transfer(receiver_id.clone(), amount.clone(), _memo);
+        self.assert_not_paused();
+        self.assert_transfer_call_receiver_authorized(receiver_id.as_ref());
+        let transfer_id = self.measure_storage_delta("ft_transfer_call", |contract| {
+            contract.auto_register_account(receiver_id.as_ref());
+            contract.transfer_stake(receiver_id.clone(), amount.clone(), memo.as_deref());
+            contract.register_pending_transfer(
+                &env::predecessor_account_id(),
+                receiver_id.as_ref(),
+                amount.value().into(),
+            )
+        });
 
         ext_transfer_receiver::ft_on_transfer(
             env::predecessor_account_id(),
@@ -55,12 +59,13 @@ impl FungibleToken for Contract {
             msg,
             receiver_id.as_ref(),
             NO_DEPOSIT.value(),
-            self.ft_on_transfer_gas(),
+            self.ft_on_transfer_gas(gas_for_receiver),
         )
         .then(ext_resolve_transfer_call::ft_resolve_transfer_call(
             env::predecessor_account_id(),
             receiver_id.as_ref().to_string(),
             amount,
+            Some(transfer_id.into()),
             &env::current_account_id(),
             NO_DEPOSIT.value(),
             self.resolve_transfer_gas(),
@@ -84,6 +89,54 @@ impl FungibleToken for Contract {
 }
 
 impl Contract {
+    /// transfers STAKE from the predecessor account to the receiver account
+    pub(crate) fn transfer_stake(
+        &mut self,
+        receiver_id: ValidAccountId,
+        amount: TokenAmount,
+        memo: Option<&str>,
+    ) {
+        assert_yocto_near_attached();
+        assert_token_amount_not_zero(&amount);
+
+        let stake_amount: YoctoStake = amount.value().into();
+
+        let mut sender = self.predecessor_registered_account();
+        // STAKE may only exist as an unclaimed stake batch receipt - claim it before the STAKE
+        // balance is debited
+        self.claim_receipt_funds(&mut sender);
+        sender.apply_stake_debit(stake_amount);
+        // apply the 1 yoctoNEAR that was attached to the sender account's NEAR balance
+        sender.apply_near_credit(1.into());
+
+        let mut receiver = self.registered_account(receiver_id.as_ref());
+        self.apply_account_preferences(&mut receiver);
+        receiver.apply_stake_credit(stake_amount);
+
+        self.save_registered_account(&sender);
+        self.save_registered_account(&receiver);
+        self.record_account_activity(
+            &sender.id,
+            &sender,
+            AccountActivityKind::TransferOut,
+            stake_amount.value(),
+            None,
+        );
+        self.record_account_activity(
+            &receiver.id,
+            &receiver,
+            AccountActivityKind::TransferIn,
+            stake_amount.value(),
+            None,
+        );
+        events::ft_transfer(
+            &env::predecessor_account_id(),
+            receiver_id.as_ref(),
+            stake_amount,
+            memo,
+        );
+    }
+
     fn resolve_transfer_gas(&self) -> u64 {
         self.config
             .gas_config()
@@ -92,8 +145,29 @@ impl Contract {
             .value()
     }
 
-    // pass along remainder of prepaid  gas to receiver contract
-    fn ft_on_transfer_gas(&self) -> u64 {
+    /// gas that is forwarded to the receiver's `ft_on_transfer`
+    /// - if `gas_for_receiver` is not specified, then the configured default is used
+    /// - if neither is specified, then all remaining gas is forwarded after reserving the gas for
+    ///   `ft_resolve_transfer_call`
+    ///
+    /// ## Panics
+    /// if the remaining gas does not cover the requested gas for the receiver
+    fn ft_on_transfer_gas(&self, gas_for_receiver: Option<interface::Gas>) -> u64 {
+        let remaining_gas = self.ft_on_transfer_remaining_gas();
+        let gas_for_receiver = gas_for_receiver.map(|gas| gas.0.into()).or_else(|| {
+            Some(self.config.gas_config().ft_on_transfer().value()).filter(|gas| *gas > 0)
+        });
+        match gas_for_receiver {
+            Some(gas) => {
+                assert!(gas <= remaining_gas, INSUFFICIENT_GAS_FOR_RECEIVER);
+                gas
+            }
+            None => remaining_gas,
+        }
+    }
+
+    // remainder of prepaid gas that can be passed along to the receiver contract
+    fn ft_on_transfer_remaining_gas(&self) -> u64 {
         env::prepaid_gas()
             - env::used_gas()
             - self.resolve_transfer_gas()
@@ -144,54 +218,112 @@ impl ResolveTransferCall for Contract {
         sender_id: ValidAccountId,
         receiver_id: ValidAccountId,
         amount: TokenAmount,
+        transfer_id: Option<U64>,
     ) -> PromiseOrValue<TokenAmount> {
+        if let Some(transfer_id) = transfer_id {
+            if self.pending_transfers.remove(&transfer_id.0).is_none() {
+                log!(
+                    "transfer {} was already resolved as a stalled transfer",
+                    transfer_id.0
+                );
+                return PromiseOrValue::Value(0.into());
+            }
+        }
+
         let unused_amount = self.transfer_call_receiver_unused_amount(amount);
 
         let refund_amount = if unused_amount.value() > 0 {
             log!("unused amount: {}", unused_amount);
+            self.refund_transfer(sender_id.as_ref(), receiver_id.as_ref(), unused_amount)
+        } else {
+            unused_amount
+        };
 
-            match self.lookup_registered_account(receiver_id.as_ref()) {
-                Some(mut receiver) => match receiver.stake.as_mut() {
-                    Some(balance) => {
-                        let refund_amount = if balance.amount().value() < unused_amount.value() {
-                            log!("ERR: partial amount will be refunded because receiver STAKE balance is insufficient");
-                            balance.amount()
-                        } else {
-                            unused_amount.value().into()
-                        };
-                        receiver.apply_stake_debit(refund_amount);
-
-                        self.save_registered_account(&receiver);
-                        match self.lookup_registered_account(sender_id.as_ref()) {
-                            Some(mut sender) => {
-                                sender.apply_stake_credit(refund_amount);
-                                self.save_registered_account(&sender);
-                                log!("sender refunded: {}", refund_amount.value());
-                            }
-                            None => {
-                                log!("ERR: sender account is not registered - refund amount will be burned: {}", refund_amount);
-                                // NOTE: this has the effect of transferring the burned value to the STAKE token,
-                                // i.e., STAKE token value will increase when STAKE is burned
-                                self.total_stake.debit(refund_amount);
-                            }
-                        }
+        PromiseOrValue::Value(refund_amount)
+    }
+}
+
+impl Contract {
+    /// refunds the amount from the receiver account back to the sender account
+    /// - if the receiver STAKE balance is insufficient, then the receiver STAKE balance is refunded
+    /// - if the sender account is not registered, then the refund is burned
+    ///
+    /// Returns the amount that was refunded.
+    pub(crate) fn refund_transfer(
+        &mut self,
+        sender_id: &str,
+        receiver_id: &str,
+        refund_amount: TokenAmount,
+    ) -> TokenAmount {
+        match self.lookup_registered_account(receiver_id) {
+            Some(mut receiver) => match receiver.stake.as_mut() {
+                Some(balance) => {
+                    let refund_amount = if balance.amount().value() < refund_amount.value() {
+                        log!("ERR: partial amount will be refunded because receiver STAKE balance is insufficient");
+                        balance.amount()
+                    } else {
                         refund_amount.value().into()
+                    };
+                    receiver.apply_stake_debit(refund_amount);
+
+                    self.save_registered_account(&receiver);
+                    self.record_account_activity(
+                        &receiver.id,
+                        &receiver,
+                        AccountActivityKind::TransferOut,
+                        refund_amount.value(),
+                        None,
+                    );
+                    match self.lookup_registered_account(sender_id) {
+                        Some(mut sender) => {
+                            sender.apply_stake_credit(refund_amount);
+                            self.save_registered_account(&sender);
+                            self.record_account_activity(
+                                &sender.id,
+                                &sender,
+                                AccountActivityKind::TransferIn,
+                                refund_amount.value(),
+                                None,
+                            );
+                            log!("sender refunded: {}", refund_amount.value());
+                            events::ft_transfer(
+                                receiver_id,
+                                sender_id,
+                                refund_amount,
+                                Some("refund"),
+                            );
+                        }
+                        None => {
+                            log!("ERR: sender account is not registered - refund amount will be burned: {}", refund_amount);
+                            // NOTE: this has the effect of transferring the burned value to the STAKE token,
+                            // i.e., STAKE token value will increase when STAKE is burned
+                            self.accumulate_total_stake_rebate();
+                            self.total_stake.debit(refund_amount);
+                            self.stake_supply_stats
+                                .record_burn(refund_amount, env::epoch_height().into());
+                            crate::near::log(TransferRefundBurned {
+                                sender_id: sender_id,
+                                amount: refund_amount.value(),
+                            });
+                            events::ft_burn(
+                                receiver_id,
+                                refund_amount,
+                                Some("refund burned because sender is not registered"),
+                            );
+                        }
                     }
-                    None => {
-                        log!("ERR: refund is not possible because receiver STAKE balance is zero");
-                        0.into()
-                    }
-                },
+                    refund_amount.value().into()
+                }
                 None => {
-                    log!("ERR: refund is not possible because receiver account is not registered");
+                    log!("ERR: refund is not possible because receiver STAKE balance is zero");
                     0.into()
                 }
+            },
+            None => {
+                log!("ERR: refund is not possible because receiver account is not registered");
+                0.into()
             }
-        } else {
-            unused_amount
-        };
-
-        PromiseOrValue::Value(refund_amount)
+        }
     }
 }
 
@@ -224,6 +356,7 @@ pub trait ExtResolveTransferCall {
         sender_id: AccountId,
         receiver_id: AccountId,
         amount: TokenAmount,
+        transfer_id: Option<U64>,
     ) -> PromiseOrValue<TokenAmount>;
 }
 
@@ -384,6 +517,79 @@ mod test_transfer {
         );
     }
 
+    /// regression test: STAKE that only exists as an unclaimed stake batch receipt can be transferred
+    /// as soon as the stake batch completes, i.e., without the sender explicitly claiming receipts first
+    #[test]
+    fn transfer_right_after_stake_batch_receipt_lands() {
+        // Arrange
+        let mut test_ctx = TestContext::with_registered_account();
+
+        let sender_id = test_ctx.account_id;
+        let receiver_id = "receiver.near";
+        test_ctx.register_account(receiver_id);
+
+        {
+            let mut context = test_ctx.context.clone();
+            context.predecessor_account_id = sender_id.to_string();
+            context.attached_deposit = 2 * YOCTO;
+            testing_env!(context);
+            test_ctx.deposit_and_stake();
+        }
+        let batch_id = test_ctx
+            .registered_account(sender_id)
+            .stake_batch
+            .unwrap()
+            .id();
+        // progress the stake batch to completion
+        {
+            let mut context = test_ctx.context.clone();
+            context.predecessor_account_id = env::current_account_id();
+            testing_env!(context);
+            test_ctx.on_deposit_and_stake(
+                None,
+                StakingPoolAccount {
+                    account_id: env::current_account_id(),
+                    unstaked_balance: 0.into(),
+                    staked_balance: (2 * YOCTO).into(),
+                    can_withdraw: false,
+                },
+            );
+            test_ctx.process_staked_batch();
+        }
+        assert!(test_ctx.stake_batch_receipts.get(&batch_id).is_some());
+        assert!(test_ctx.registered_account(sender_id).stake_batch.is_some());
+
+        // Act
+        let mut context = test_ctx.context.clone();
+        context.predecessor_account_id = sender_id.to_string();
+        context.attached_deposit = 1; // 1 yoctoNEAR is required to transfer
+        testing_env!(context.clone());
+        let transfer_amount = YOCTO;
+        test_ctx.ft_transfer(
+            to_valid_account_id(receiver_id),
+            transfer_amount.into(),
+            None,
+        );
+
+        // Assert - the receipt was claimed by the transfer
+        let sender = test_ctx.registered_account(sender_id);
+        assert!(sender.stake_batch.is_none());
+        assert!(test_ctx.stake_batch_receipts.get(&batch_id).is_none());
+        assert_eq!(
+            test_ctx
+                .ft_balance_of(to_valid_account_id(sender_id))
+                .value(),
+            YOCTO
+        );
+        assert_eq!(
+            test_ctx
+                .ft_balance_of(to_valid_account_id(receiver_id))
+                .value(),
+            transfer_amount
+        );
+        assert_eq!(test_ctx.ft_total_supply().value(), 2 * YOCTO);
+    }
+
     #[test]
     #[should_panic(expected = "account is not registered: sender.near")]
     fn sender_not_registered() {
@@ -602,6 +808,7 @@ mod test_transfer_call {
             transfer_amount.into(),
             msg.clone(),
             None,
+            None,
         );
 
         // Assert
@@ -659,6 +866,8 @@ mod test_transfer_call {
                     assert_eq!(args.sender_id, to_valid_account_id(sender_id));
                     assert_eq!(args.receiver_id, to_valid_account_id(receiver_id));
                     assert_eq!(args.amount, transfer_amount.into());
+                    assert_eq!(args.transfer_id, Some(1.into()));
+                    assert!(test_ctx.pending_transfers.get(&1).is_some());
                     assert_eq!(
                         *gas,
                         test_ctx
@@ -680,6 +889,7 @@ mod test_transfer_call {
             transfer_amount.into(),
             "pay".into(),
             Some("memo".into()),
+            None,
         );
         let sender = test_ctx.predecessor_registered_account();
         assert_eq!(sender.near.unwrap().amount().value(), 2,
@@ -746,6 +956,7 @@ mod test_transfer_call {
             transfer_amount.into(),
             "msg".into(),
             None,
+            None,
         );
 
         // Assert
@@ -783,6 +994,7 @@ mod test_transfer_call {
             transfer_amount.into(),
             "pay".into(),
             None,
+            None,
         );
     }
 
@@ -813,9 +1025,63 @@ mod test_transfer_call {
             transfer_amount.into(),
             "pay".into(),
             None,
+            None,
         );
     }
 
+    #[test]
+    fn gas_for_receiver() {
+        // Arrange
+        let mut test_ctx = TestContext::with_registered_account();
+        let sender_id = test_ctx.account_id;
+        let receiver_id = "receiver.near";
+        test_ctx.register_account(receiver_id);
+
+        let mut sender = test_ctx.registered_account(sender_id);
+        sender.apply_stake_credit(YoctoStake(100 * YOCTO));
+        test_ctx.total_stake.credit(YoctoStake(100 * YOCTO));
+        test_ctx.save_registered_account(&sender);
+
+        let ft_on_transfer_gas =
+            |test_ctx: &mut TestContext, gas_for_receiver: Option<interface::Gas>| -> u64 {
+                let mut context = test_ctx.set_predecessor_account_id(sender_id);
+                context.attached_deposit = 1;
+                testing_env!(context);
+                test_ctx.ft_transfer_call(
+                    to_valid_account_id(receiver_id),
+                    YOCTO.into(),
+                    "pay".into(),
+                    None,
+                    gas_for_receiver,
+                );
+                match &deserialize_receipts()[0].actions[0] {
+                    Action::FunctionCall {
+                        method_name, gas, ..
+                    } => {
+                        assert_eq!(method_name, "ft_on_transfer");
+                        *gas
+                    }
+                    _ => panic!("expected `ft_on_transfer` function call"),
+                }
+            };
+
+        // Act - the caller specifies the gas for the receiver
+        let gas = ft_on_transfer_gas(&mut test_ctx, Some((TGAS * 50).into()));
+        assert_eq!(gas, (TGAS * 50).value());
+
+        // Act - the configured default is used when the caller does not specify the gas
+        test_ctx.config.merge(
+            serde_json::from_str(r#"{"gas_config": {"ft_on_transfer": "60000000000000"}}"#)
+                .unwrap(),
+        );
+        let gas = ft_on_transfer_gas(&mut test_ctx, None);
+        assert_eq!(gas, (TGAS * 60).value());
+
+        // Act - the caller overrides the configured default
+        let gas = ft_on_transfer_gas(&mut test_ctx, Some((TGAS * 20).into()));
+        assert_eq!(gas, (TGAS * 20).value());
+    }
+
     #[test]
     #[should_panic(expected = "exactly 1 yoctoNEAR must be attached")]
     pub fn zero_yocto_near_attached() {
@@ -843,6 +1109,7 @@ mod test_transfer_call {
             transfer_amount.into(),
             "pay".into(),
             None,
+            None,
         );
     }
 
@@ -874,6 +1141,7 @@ mod test_transfer_call {
             transfer_amount.into(),
             "pay".into(),
             None,
+            None,
         );
     }
 
@@ -905,6 +1173,7 @@ mod test_transfer_call {
             transfer_amount.into(),
             "pay".into(),
             None,
+            None,
         );
     }
 
@@ -936,6 +1205,7 @@ mod test_transfer_call {
             transfer_amount.into(),
             "pay".into(),
             None,
+            None,
         );
     }
 
@@ -953,6 +1223,139 @@ mod test_transfer_call {
         sender_id: ValidAccountId,
         receiver_id: ValidAccountId,
         amount: TokenAmount,
+        transfer_id: Option<U64>,
+    }
+}
+
+#[cfg(test)]
+mod test_auto_registration {
+    use super::*;
+    use crate::interface::{AccountManagement, Operator};
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain, VMContext};
+
+    const RECEIVER_ID: &str = "dex.near";
+
+    /// the sender is credited with STAKE and the operator whitelists the receiver
+    fn setup(test_ctx: &mut TestContext, registration_pool: u128) -> VMContext {
+        let mut sender = test_ctx.registered_account(test_ctx.account_id);
+        sender.apply_stake_credit((100 * YOCTO).into());
+        test_ctx.total_stake.credit((100 * YOCTO).into());
+        test_ctx.save_registered_account(&sender);
+
+        let mut context = test_ctx.context.clone();
+        context.predecessor_account_id = test_ctx.operator_id.clone();
+        testing_env!(context.clone());
+        test_ctx.whitelist_auto_registration(to_valid_account_id(RECEIVER_ID));
+        if registration_pool > 0 {
+            context.attached_deposit = registration_pool;
+            testing_env!(context.clone());
+            test_ctx.fund_registration_pool();
+        }
+
+        context.predecessor_account_id = test_ctx.account_id.to_string();
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        context
+    }
+
+    #[test]
+    fn whitelisted_receiver_is_auto_registered() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let registration_pool = test_ctx.account_storage_fee().value() * 2;
+        setup(&mut test_ctx, registration_pool);
+        assert!(test_ctx.auto_registration_whitelisted(to_valid_account_id(RECEIVER_ID)));
+        let total_account_storage_escrow = test_ctx.total_account_storage_escrow;
+
+        test_ctx.ft_transfer_call(
+            to_valid_account_id(RECEIVER_ID),
+            (10 * YOCTO).into(),
+            "pay".into(),
+            None,
+            None,
+        );
+
+        let account_storage_fee = test_ctx.account_storage_fee().value();
+        assert!(test_ctx.account_registered(to_valid_account_id(RECEIVER_ID)));
+        assert_eq!(
+            test_ctx.ft_balance_of(to_valid_account_id(RECEIVER_ID)),
+            (10 * YOCTO).into()
+        );
+        let receiver = test_ctx.registered_account(RECEIVER_ID);
+        assert_eq!(
+            receiver.storage_escrow.amount().value(),
+            account_storage_fee
+        );
+        assert_eq!(
+            test_ctx.registration_pool().value(),
+            registration_pool - account_storage_fee
+        );
+        assert_eq!(
+            test_ctx.total_account_storage_escrow.value(),
+            total_account_storage_escrow.value() + account_storage_fee
+        );
+
+        // the registration pool is only charged once
+        test_ctx.ft_transfer_call(
+            to_valid_account_id(RECEIVER_ID),
+            (10 * YOCTO).into(),
+            "pay".into(),
+            None,
+            None,
+        );
+        assert_eq!(
+            test_ctx.registration_pool().value(),
+            registration_pool - account_storage_fee
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "registration pool balance is too low to pay for the account storage fee"
+    )]
+    fn registration_pool_insufficient() {
+        let mut test_ctx = TestContext::with_registered_account();
+        setup(&mut test_ctx, 0);
+        test_ctx.ft_transfer_call(
+            to_valid_account_id(RECEIVER_ID),
+            (10 * YOCTO).into(),
+            "pay".into(),
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "account is not registered")]
+    fn receiver_not_whitelisted() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let registration_pool = test_ctx.account_storage_fee().value();
+        let mut context = setup(&mut test_ctx, registration_pool);
+
+        context.predecessor_account_id = test_ctx.operator_id.clone();
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        test_ctx.remove_auto_registration(to_valid_account_id(RECEIVER_ID));
+
+        context.predecessor_account_id = test_ctx.account_id.to_string();
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_ctx.ft_transfer_call(
+            to_valid_account_id(RECEIVER_ID),
+            (10 * YOCTO).into(),
+            "pay".into(),
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "contract call is only allowed by an operator account")]
+    fn whitelist_auto_registration_not_operator() {
+        let mut test_ctx = TestContext::with_registered_account();
+        testing_env!(test_ctx.context.clone());
+        test_ctx.whitelist_auto_registration(to_valid_account_id(RECEIVER_ID));
     }
 }
 
@@ -987,6 +1390,7 @@ mod test_resolve_transfer_call {
             to_valid_account_id(sender_id),
             to_valid_account_id(receiver_id),
             YOCTO.into(),
+            None,
         );
 
         // Assert - full amount is refunded
@@ -1024,6 +1428,7 @@ mod test_resolve_transfer_call {
             to_valid_account_id(sender_id),
             to_valid_account_id(receiver_id),
             (2 * YOCTO).into(),
+            None,
         );
 
         // Assert - partial amount is refunded
@@ -1055,6 +1460,7 @@ mod test_resolve_transfer_call {
             to_valid_account_id(sender_id),
             to_valid_account_id(receiver_id),
             (2 * YOCTO).into(),
+            None,
         );
 
         // Assert - full amount is refunded
@@ -1082,6 +1488,7 @@ mod test_resolve_transfer_call {
             to_valid_account_id(sender_id),
             to_valid_account_id(receiver_id),
             YOCTO.into(),
+            None,
         );
 
         // Assert
@@ -1109,6 +1516,7 @@ mod test_resolve_transfer_call {
             to_valid_account_id(sender_id),
             to_valid_account_id(receiver_id),
             YOCTO.into(),
+            None,
         );
 
         // Assert
@@ -1137,6 +1545,7 @@ mod test_resolve_transfer_call {
             to_valid_account_id(sender_id),
             to_valid_account_id(receiver_id),
             YOCTO.into(),
+            None,
         );
 
         // Assert
@@ -1164,6 +1573,7 @@ mod test_resolve_transfer_call {
             to_valid_account_id(sender_id),
             to_valid_account_id(receiver_id),
             YOCTO.into(),
+            None,
         );
 
         // Assert
@@ -1197,6 +1607,7 @@ mod test_resolve_transfer_call {
             to_valid_account_id(sender_id),
             to_valid_account_id(receiver_id),
             YOCTO.into(),
+            None,
         );
 
         // Assert
@@ -1224,9 +1635,12 @@ mod test_resolve_transfer_call {
 
         let logs = get_logs();
         println!("{:?}", logs);
-        assert_eq!(logs.len(), 2);
+        assert_eq!(logs.len(), 3);
         assert_eq!(&logs[0], &format!("unused amount: {}", YOCTO));
         assert_eq!(&logs[1], &format!("sender refunded: {}", YOCTO));
+        assert!(logs[2].starts_with(
+            r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_transfer""#
+        ));
     }
 
     #[test]
@@ -1251,6 +1665,7 @@ mod test_resolve_transfer_call {
             to_valid_account_id(sender_id),
             to_valid_account_id(receiver_id),
             YOCTO.into(),
+            None,
         );
 
         // Assert
@@ -1275,7 +1690,7 @@ mod test_resolve_transfer_call {
 
         let logs = get_logs();
         println!("{:?}", logs);
-        assert_eq!(logs.len(), 2);
+        assert_eq!(logs.len(), 4);
         assert_eq!(&logs[0], &format!("unused amount: {}", YOCTO));
         assert_eq!(
             &logs[1],
@@ -1284,6 +1699,9 @@ mod test_resolve_transfer_call {
                 YOCTO
             )
         );
+        assert!(logs[2].starts_with("TransferRefundBurned {"));
+        assert!(logs[3]
+            .starts_with(r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_burn""#));
     }
 
     #[test]
@@ -1302,6 +1720,7 @@ mod test_resolve_transfer_call {
             to_valid_account_id(sender_id),
             to_valid_account_id(receiver_id),
             YOCTO.into(),
+            None,
         );
 
         // Assert
@@ -1342,6 +1761,7 @@ mod test_resolve_transfer_call {
             to_valid_account_id(sender_id),
             to_valid_account_id(receiver_id),
             YOCTO.into(),
+            None,
         );
 
         // Assert
@@ -1382,6 +1802,7 @@ mod test_resolve_transfer_call {
             to_valid_account_id(sender_id),
             to_valid_account_id(receiver_id),
             YOCTO.into(),
+            None,
         );
 
         // Assert
@@ -1412,6 +1833,7 @@ mod test_resolve_transfer_call {
             to_valid_account_id(sender_id),
             to_valid_account_id(receiver_id),
             YOCTO.into(),
+            None,
         );
 
         // Assert
@@ -1442,6 +1864,7 @@ mod test_resolve_transfer_call {
             to_valid_account_id(sender_id),
             to_valid_account_id(receiver_id),
             YOCTO.into(),
+            None,
         );
 
         // Assert
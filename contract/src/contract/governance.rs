@@ -0,0 +1,145 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::errors::governance::{GOVERNANCE_ALREADY_SET, GOVERNANCE_ID_MUST_NOT_BE_CONTRACT_ID};
+use crate::interface::governance::events::{GovernanceRelinquished, GovernanceSet};
+use crate::interface::Governance;
+use crate::near::log;
+use crate::*;
+use near_sdk::{json_types::ValidAccountId, near_bindgen};
+
+#[near_bindgen]
+impl Governance for Contract {
+    fn governance_id(&self) -> Option<AccountId> {
+        self.governance_id.clone()
+    }
+
+    fn set_governance_id(&mut self, account_id: ValidAccountId) {
+        self.assert_predecessor_is_owner();
+        assert!(self.governance_id.is_none(), GOVERNANCE_ALREADY_SET);
+        assert_ne!(
+            env::current_account_id().as_str(),
+            account_id.as_ref(),
+            "{}",
+            GOVERNANCE_ID_MUST_NOT_BE_CONTRACT_ID
+        );
+
+        self.governance_id = Some(account_id.into());
+        log(GovernanceSet {
+            governance_id: self.governance_id.as_ref().unwrap(),
+        });
+    }
+
+    fn relinquish_governance(&mut self) {
+        self.assert_predecessor_is_governance();
+
+        if let Some(governance_id) = self.governance_id.take() {
+            log(GovernanceRelinquished {
+                governance_id: &governance_id,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::{Config, Operator};
+    use crate::test_utils::*;
+    use near_sdk::{json_types::Base64VecU8, serde_json, testing_env, MockedBlockchain};
+    use std::convert::TryFrom;
+
+    const DAO_ID: &str = "dao.near";
+
+    fn set_governance(ctx: &mut TestContext) {
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.owner_id.clone();
+        testing_env!(context);
+        ctx.set_governance_id(ValidAccountId::try_from(DAO_ID).unwrap());
+    }
+
+    #[test]
+    fn set_governance_id() {
+        let mut ctx = TestContext::new();
+        assert!(ctx.governance_id().is_none());
+
+        set_governance(&mut ctx);
+        assert_eq!(ctx.governance_id().unwrap(), DAO_ID);
+    }
+
+    #[test]
+    #[should_panic(expected = "contract call is only allowed by the contract owner")]
+    fn set_governance_id_invoked_by_non_owner() {
+        let mut ctx = TestContext::new();
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.operator_id.clone();
+        testing_env!(context);
+        ctx.set_governance_id(ValidAccountId::try_from(DAO_ID).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "governance DAO account is already set")]
+    fn set_governance_id_when_already_set() {
+        let mut ctx = TestContext::new();
+        set_governance(&mut ctx);
+        set_governance(&mut ctx);
+    }
+
+    #[test]
+    fn config_updates_are_only_accepted_from_governance_dao() {
+        let mut ctx = TestContext::new();
+        set_governance(&mut ctx);
+
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = DAO_ID.to_string();
+        testing_env!(context);
+        let config: Config =
+            serde_json::from_str(r#"{"redeem_limit_increase_delay_epochs": 10}"#).unwrap();
+        let validation_hash = ctx.validate_config(config.clone()).validation_hash.unwrap();
+        let config = ctx.update_config(config, validation_hash);
+        assert_eq!(config.redeem_limit_increase_delay_epochs, Some(10));
+    }
+
+    #[test]
+    #[should_panic(expected = "contract call is only allowed by the governance DAO account")]
+    fn config_updates_from_operator_are_rejected_while_governed() {
+        let mut ctx = TestContext::new();
+        set_governance(&mut ctx);
+
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.operator_id.clone();
+        testing_env!(context);
+        ctx.reset_config_default(Base64VecU8(vec![]));
+    }
+
+    #[test]
+    fn relinquish_governance() {
+        let mut ctx = TestContext::new();
+        set_governance(&mut ctx);
+
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = DAO_ID.to_string();
+        testing_env!(context.clone());
+        ctx.relinquish_governance();
+        assert!(ctx.governance_id().is_none());
+
+        // config is managed by the operator again
+        context.predecessor_account_id = ctx.operator_id.clone();
+        testing_env!(context);
+        let validation_hash = ctx
+            .validate_config(crate::config::Config::default().into())
+            .validation_hash
+            .unwrap();
+        ctx.reset_config_default(validation_hash);
+    }
+
+    #[test]
+    #[should_panic(expected = "contract call is only allowed by the governance DAO account")]
+    fn relinquish_governance_invoked_by_non_governance_account() {
+        let mut ctx = TestContext::new();
+        set_governance(&mut ctx);
+
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.owner_id.clone();
+        testing_env!(context);
+        ctx.relinquish_governance();
+    }
+}
@@ -0,0 +1,380 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::domain::{idle_near_sweep_amount, Account, IdleNearShare, RegisteredAccount};
+use crate::interface::idle_near_sharing::events::{
+    IdleNearSharingOptOut, IdleNearSweepRedeemed, IdleNearSweepSettled, IdleNearSwept,
+};
+use crate::interface::{IdleNearSharing, IdleNearSweepStats};
+use crate::near::log;
+use crate::*;
+use near_sdk::{json_types::ValidAccountId, near_bindgen};
+
+#[near_bindgen]
+impl IdleNearSharing for Contract {
+    fn set_idle_near_sharing_opt_out(&mut self, opt_out: bool) {
+        let mut account = self.predecessor_registered_account();
+        account
+            .idle_near_share
+            .get_or_insert_with(|| IdleNearShare::new(self.idle_near_sweep.yield_index()))
+            .set_opted_out(opt_out);
+        self.save_registered_account(&account);
+
+        log(IdleNearSharingOptOut {
+            account_id: &env::predecessor_account_id(),
+            opt_out,
+        });
+    }
+
+    fn idle_near_sharing_opted_out(&self, account_id: ValidAccountId) -> Option<bool> {
+        self.lookup_registered_account(account_id.as_ref())
+            .map(|account| {
+                account
+                    .idle_near_share
+                    .map_or(false, |share| share.opted_out())
+            })
+    }
+
+    fn idle_near_sweep_stats(&self) -> IdleNearSweepStats {
+        let sweep = &self.idle_near_sweep;
+        let total_idle_near = self.total_idle_near();
+        let liquidity_buffer = total_idle_near.value()
+            - idle_near_sweep_amount(
+                total_idle_near,
+                0.into(),
+                self.config.idle_near_liquidity_buffer_percentage(),
+                100,
+            )
+            .value();
+        IdleNearSweepStats {
+            total_idle_near: total_idle_near.into(),
+            total_opted_out: sweep.total_opted_out().into(),
+            swept: sweep.swept().into(),
+            swept_epoch: if sweep.cycle_in_progress() {
+                Some(sweep.swept_epoch().into())
+            } else {
+                None
+            },
+            liquid: self.liquid_near().into(),
+            liquidity_buffer: domain::YoctoNear(liquidity_buffer).into(),
+            swept_stake: sweep
+                .position()
+                .stake
+                .map_or(0.into(), |balance| balance.amount())
+                .into(),
+            yield_index: sweep.yield_index().into(),
+            yield_pool: sweep.yield_pool().into(),
+            total_yield: sweep.total_yield().into(),
+        }
+    }
+}
+
+impl Contract {
+    /// idle NEAR that shares in the sweep, i.e., excludes the NEAR held by accounts that opted out
+    /// and the NEAR that is queued for withdrawal
+    fn total_idle_near(&self) -> domain::YoctoNear {
+        self.total_near
+            .amount()
+            .value()
+            .saturating_sub(self.idle_near_sweep.total_opted_out().value())
+            .saturating_sub(self.total_queued_withdrawals.value())
+            .into()
+    }
+
+    /// NEAR that is available for withdrawal, i.e., excludes the NEAR that is swept into staking
+    pub(crate) fn liquid_near(&self) -> domain::YoctoNear {
+        self.total_near
+            .amount()
+            .value()
+            .saturating_sub(self.idle_near_sweep.swept().value())
+            .into()
+    }
+
+    /// caps the NEAR that can be transferred out of the contract while idle NEAR is swept into
+    /// staking - None if no idle NEAR is swept
+    pub(crate) fn swept_liquidity_limit(&self) -> Option<domain::YoctoNear> {
+        if self.idle_near_sweep.swept().value() == 0 {
+            None
+        } else {
+            Some(self.liquid_near())
+        }
+    }
+
+    /// credits the idle NEAR yield that has accrued on the account NEAR balance
+    /// - returns the account along with the yield that was credited, which is moved out of the
+    ///   yield pool when the account is saved
+    pub(crate) fn accrue_idle_near_yield(
+        &self,
+        mut account: Account,
+    ) -> (Account, domain::YoctoNear) {
+        let yield_index = self.idle_near_sweep.yield_index();
+        let balance = account.near.map_or(0.into(), |balance| balance.amount());
+        let idle_near_yield = account
+            .idle_near_share
+            .get_or_insert_with(|| IdleNearShare::new(yield_index))
+            .accrue(balance, yield_index);
+        if idle_near_yield.value() > 0 {
+            account.apply_near_credit(idle_near_yield);
+        }
+        (account, idle_near_yield)
+    }
+
+    /// - moves the yield that was credited to the account out of the yield pool into total NEAR
+    /// - tracks the NEAR balance of accounts that opted out of idle NEAR sharing
+    pub(crate) fn record_idle_near_account_changes(
+        &mut self,
+        previous: Option<&Account>,
+        account: &RegisteredAccount,
+    ) {
+        let idle_near_yield = account.idle_near_yield.replace(0.into());
+        if idle_near_yield.value() > 0 {
+            self.idle_near_sweep.record_yield_credited(idle_near_yield);
+            self.total_near.credit(idle_near_yield);
+        }
+
+        fn opted_out_balance(account: &Account) -> domain::YoctoNear {
+            match account.idle_near_share {
+                Some(share) if share.opted_out() => {
+                    account.near.map_or(0.into(), |balance| balance.amount())
+                }
+                _ => 0.into(),
+            }
+        }
+        let before = previous.map_or(0.into(), opted_out_balance);
+        let after = opted_out_balance(account);
+        if before != after {
+            self.idle_near_sweep.update_opted_out(before, after);
+        }
+    }
+
+    /// sweeps idle NEAR above the liquidity buffer into the stake batch
+    /// - only 1 sweep cycle runs at a time
+    /// - the sweep is skipped if the amount is below the min required NEAR deposit
+    pub(crate) fn sweep_idle_near(&mut self) {
+        let sweep_percentage = self.config.idle_near_sweep_percentage();
+        if sweep_percentage == 0 || self.idle_near_sweep.cycle_in_progress() {
+            return;
+        }
+        let amount = idle_near_sweep_amount(
+            self.total_idle_near(),
+            self.idle_near_sweep.swept(),
+            self.config.idle_near_liquidity_buffer_percentage(),
+            sweep_percentage,
+        );
+        if amount.value() == 0 || amount < self.min_required_near_deposit() {
+            return;
+        }
+
+        let mut position = self.idle_near_sweep.position();
+        if !self.stake_batches_verified(&position) {
+            return;
+        }
+        let batch_id = self.add_near_to_stake_batch(&mut position, amount);
+        self.idle_near_sweep.set_position(position);
+        self.idle_near_sweep
+            .record_sweep(amount, env::epoch_height().into());
+        self.log_stake_batch(batch_id);
+
+        log(IdleNearSwept {
+            batch_id: batch_id.value(),
+            amount: amount.value(),
+            liquid: self.liquid_near().value(),
+        });
+    }
+
+    /// advances the sweep cycle:
+    /// 1. claims the sweep position's batch receipts
+    /// 2. redeems the swept STAKE once the cycle epochs have passed
+    /// 3. settles the cycle once all of the redeemed NEAR has been returned
+    pub(crate) fn cycle_idle_near_sweep(&mut self) {
+        if !self.idle_near_sweep.cycle_in_progress() {
+            return;
+        }
+        let mut position = self.idle_near_sweep.position();
+        self.claim_stake_batch_receipts(&mut position);
+        self.claim_redeem_stake_batch_receipts(&mut position);
+        self.idle_near_sweep.set_position(position);
+
+        let has_batches = position.stake_batch.is_some()
+            || position.next_stake_batch.is_some()
+            || position.redeem_stake_batch.is_some()
+            || position.next_redeem_stake_batch.is_some();
+        if has_batches {
+            return;
+        }
+
+        if let Some(stake) = position.stake {
+            let redeem_epoch = self.idle_near_sweep.swept_epoch().value()
+                + self.config.idle_near_sweep_cycle_epochs();
+            if env::epoch_height() >= redeem_epoch {
+                position.stake = None;
+                let batch_id = self.add_stake_to_redeem_stake_batch(&mut position, stake.amount());
+                self.idle_near_sweep.set_position(position);
+                self.log_redeem_stake_batch(batch_id.clone().into());
+                log(IdleNearSweepRedeemed {
+                    batch_id: batch_id.0.into(),
+                    stake: stake.amount().value(),
+                });
+            }
+        } else if let Some(near) = position.near {
+            self.settle_idle_near_sweep(near.amount());
+        }
+    }
+
+    /// the returned NEAR was credited to total NEAR when the swept STAKE was redeemed - it is moved
+    /// back out of total NEAR because the account NEAR balances were never debited for the sweep
+    ///
+    /// NOTE: any yield that cannot be distributed, i.e., rounding dust, becomes contract earnings
+    fn settle_idle_near_sweep(&mut self, returned: domain::YoctoNear) {
+        let swept = self.idle_near_sweep.swept();
+        self.total_near.debit(returned);
+        let distributed_yield = self
+            .idle_near_sweep
+            .settle(returned, self.total_idle_near());
+
+        log(IdleNearSweepSettled {
+            swept: swept.value(),
+            returned: returned.value(),
+            distributed_yield: distributed_yield.value(),
+            yield_index: self.idle_near_sweep.yield_index(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::{ContractFinancials, StakingService};
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{serde_json, test_utils::get_logs, testing_env, MockedBlockchain};
+    use std::convert::TryFrom;
+
+    /// credits the registered account with 100 NEAR and enables sweeping 100% of the idle NEAR above
+    /// a 50% liquidity buffer
+    fn setup(ctx: &mut TestContext) {
+        ctx.config.merge(
+            serde_json::from_str(
+                r#"{"idle_near_sweep_percentage": 100, "idle_near_liquidity_buffer_percentage": 50}"#,
+            )
+            .unwrap(),
+        );
+
+        let mut account = ctx.registered_account(ctx.account_id);
+        account.apply_near_credit((100 * YOCTO).into());
+        ctx.save_registered_account(&account);
+        ctx.total_near.credit((100 * YOCTO).into());
+    }
+
+    /// simulates the swept STAKE being redeemed and the NEAR returned to the sweep position
+    fn return_swept_near(ctx: &mut TestContext, returned: domain::YoctoNear) {
+        let mut position = Account::new(0.into());
+        position.apply_near_credit(returned);
+        ctx.idle_near_sweep.set_position(position);
+        ctx.total_near.credit(returned);
+    }
+
+    #[test]
+    fn sweep_keeps_liquidity_buffer() {
+        let mut ctx = TestContext::with_registered_account();
+        setup(&mut ctx);
+
+        let total_user_accounts_balance = ctx.balances().total_user_accounts_balance;
+        ctx.sweep_idle_near();
+        let stats = ctx.idle_near_sweep_stats();
+        assert_eq!(stats.swept, (50 * YOCTO).into());
+        assert_eq!(stats.liquid, (50 * YOCTO).into());
+        assert_eq!(stats.liquidity_buffer, (50 * YOCTO).into());
+        assert!(stats.swept_epoch.is_some());
+        // swept NEAR is added to the stake batch
+        assert_eq!(
+            ctx.stake_batch.unwrap().balance().amount(),
+            (50 * YOCTO).into()
+        );
+        // swept NEAR is not double counted
+        assert_eq!(
+            ctx.balances().total_user_accounts_balance,
+            total_user_accounts_balance
+        );
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("IdleNearSwept")));
+
+        // only 1 sweep cycle runs at a time
+        ctx.sweep_idle_near();
+        assert_eq!(ctx.idle_near_sweep.swept(), (50 * YOCTO).into());
+    }
+
+    #[test]
+    fn withdrawal_beyond_liquidity_buffer_is_queued() {
+        let mut ctx = TestContext::with_registered_account();
+        setup(&mut ctx);
+        ctx.sweep_idle_near();
+
+        ctx.withdraw((51 * YOCTO).into());
+        let account_id = ValidAccountId::try_from(ctx.account_id).unwrap();
+        let queued_withdrawal = ctx.queued_withdrawal(account_id.clone()).unwrap();
+        assert_eq!(queued_withdrawal.amount, YOCTO.into());
+        assert_eq!(ctx.total_queued_withdrawals, YOCTO.into());
+        // the queued NEAR is not swept
+        let stats = ctx.idle_near_sweep_stats();
+        assert_eq!(stats.liquid, 0.into());
+        assert_eq!(stats.total_idle_near, (49 * YOCTO).into());
+
+        // the queued withdrawal is not released until liquidity is available
+        let mut context = ctx.context.clone();
+        context.epoch_height += 1;
+        testing_env!(context);
+        assert_eq!(ctx.process_queued_withdrawal(account_id.clone()), 0.into());
+        assert_eq!(
+            ctx.queued_withdrawal(account_id).unwrap().amount,
+            YOCTO.into()
+        );
+    }
+
+    #[test]
+    fn settled_yield_is_credited_pro_rata() {
+        let mut ctx = TestContext::with_registered_account();
+        setup(&mut ctx);
+        ctx.sweep_idle_near();
+        return_swept_near(&mut ctx, (55 * YOCTO).into());
+
+        ctx.cycle_idle_near_sweep();
+        let stats = ctx.idle_near_sweep_stats();
+        assert_eq!(stats.swept, 0.into());
+        assert!(stats.swept_epoch.is_none());
+        assert_eq!(stats.total_yield, (5 * YOCTO).into());
+        assert_eq!(stats.yield_pool, (5 * YOCTO).into());
+        assert_eq!(ctx.total_near.amount(), (100 * YOCTO).into());
+
+        // the yield is credited when the account is loaded and moved out of the pool when saved
+        let account = ctx.registered_account(ctx.account_id);
+        assert_eq!(account.near.unwrap().amount(), (105 * YOCTO).into());
+        ctx.save_registered_account(&account);
+        // saving the account again does not credit the yield twice
+        ctx.save_registered_account(&account);
+        assert_eq!(ctx.idle_near_sweep.yield_pool(), 0.into());
+        assert_eq!(ctx.total_near.amount(), (105 * YOCTO).into());
+    }
+
+    #[test]
+    fn opted_out_accounts_earn_no_yield() {
+        let mut ctx = TestContext::with_registered_account();
+        setup(&mut ctx);
+        let account_id = ValidAccountId::try_from(ctx.account_id).unwrap();
+        assert_eq!(
+            ctx.idle_near_sharing_opted_out(account_id.clone()),
+            Some(false)
+        );
+
+        ctx.set_idle_near_sharing_opt_out(true);
+        assert_eq!(ctx.idle_near_sharing_opted_out(account_id), Some(true));
+        let stats = ctx.idle_near_sweep_stats();
+        assert_eq!(stats.total_opted_out, (100 * YOCTO).into());
+        assert_eq!(stats.total_idle_near, 0.into());
+        // there is no idle NEAR to sweep
+        ctx.sweep_idle_near();
+        assert_eq!(ctx.idle_near_sweep.swept(), 0.into());
+
+        ctx.set_idle_near_sharing_opt_out(false);
+        assert_eq!(ctx.idle_near_sweep.total_opted_out(), 0.into());
+    }
+}
@@ -0,0 +1,262 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::domain::MAX_INVOICE_MEMO_LEN;
+use crate::errors::invoices::{
+    INVOICE_EXPIRY_MUST_BE_IN_FUTURE, INVOICE_MEMO_TOO_LONG, INVOICE_NOT_FOUND,
+    INVOICE_NOT_PAYABLE, INVOICE_PAYEE_ONLY, INVOICE_SELF_PAYMENT, INVOICE_STORAGE_FEE_REQUIRED,
+    ZERO_INVOICE_AMOUNT,
+};
+use crate::interface::invoices::events::{InvoiceClosed, InvoiceCreated, InvoicePaid};
+use crate::interface::{BlockTimestamp, Invoice, Invoices, YoctoStake};
+use crate::near::log;
+use crate::*;
+use near_sdk::{
+    json_types::{ValidAccountId, U64},
+    near_bindgen, Promise,
+};
+use std::convert::TryFrom;
+
+#[near_bindgen]
+impl Invoices for Contract {
+    #[payable]
+    fn create_invoice(
+        &mut self,
+        amount: YoctoStake,
+        memo: String,
+        expiry: BlockTimestamp,
+    ) -> Invoice {
+        let amount: domain::YoctoStake = amount.into();
+        assert!(amount.value() > 0, ZERO_INVOICE_AMOUNT);
+        assert!(memo.len() <= MAX_INVOICE_MEMO_LEN, INVOICE_MEMO_TOO_LONG);
+        let expires_at: domain::BlockTimestamp = expiry.into();
+        assert!(
+            expires_at.value() > env::block_timestamp(),
+            INVOICE_EXPIRY_MUST_BE_IN_FUTURE
+        );
+        // the payee must be registered in order to receive the STAKE payment
+        self.predecessor_registered_account();
+        let payee_id = env::predecessor_account_id();
+
+        self.invoice_id_sequence += 1;
+        let invoice_id = self.invoice_id_sequence;
+        let mut invoice = domain::Invoice::new(
+            &payee_id,
+            amount,
+            memo,
+            expires_at,
+            domain::BlockTimeHeight::from_env(),
+        );
+        let initial_storage_usage = env::storage_usage();
+        self.invoices.insert(&invoice_id, &invoice);
+        let storage_fee = self.storage_fee(env::storage_usage() - initial_storage_usage);
+        assert!(
+            env::attached_deposit() >= storage_fee.value(),
+            "{}: storage fee = {} yoctoNEAR",
+            INVOICE_STORAGE_FEE_REQUIRED,
+            storage_fee
+        );
+        invoice.set_storage_fee(storage_fee);
+        self.invoices.insert(&invoice_id, &invoice);
+        self.total_account_storage_escrow += storage_fee;
+
+        let refund = env::attached_deposit() - storage_fee.value();
+        if refund > 0 {
+            Promise::new(payee_id.clone()).transfer(refund);
+        }
+
+        log(InvoiceCreated {
+            invoice_id,
+            payee_id: &payee_id,
+            amount: amount.value(),
+            expires_at: expires_at.value(),
+        });
+        Invoice::from(invoice_id, invoice, env::block_timestamp().into())
+    }
+
+    #[payable]
+    fn pay_invoice(&mut self, invoice_id: U64) -> Invoice {
+        self.assert_not_paused();
+        let invoice_id = invoice_id.0;
+        let mut invoice = self.invoices.get(&invoice_id).expect(INVOICE_NOT_FOUND);
+        let now = domain::BlockTimeHeight::from_env();
+        assert!(
+            invoice.is_payable(now.block_timestamp()),
+            INVOICE_NOT_PAYABLE
+        );
+        let payer_id = env::predecessor_account_id();
+        assert_ne!(payer_id, invoice.payee_id(), "{}", INVOICE_SELF_PAYMENT);
+
+        self.transfer_stake(
+            ValidAccountId::try_from(invoice.payee_id()).unwrap(),
+            invoice.amount().value().into(),
+            Some(&format!("invoice {}", invoice_id)),
+        );
+        invoice.record_payment(&payer_id, now);
+        self.invoices.insert(&invoice_id, &invoice);
+
+        log(InvoicePaid {
+            invoice_id,
+            payee_id: invoice.payee_id(),
+            payer_id: &payer_id,
+            amount: invoice.amount().value(),
+            memo: invoice.memo(),
+        });
+        Invoice::from(invoice_id, invoice, now.block_timestamp())
+    }
+
+    fn close_invoice(&mut self, invoice_id: U64) -> interface::YoctoNear {
+        let invoice_id = invoice_id.0;
+        let invoice = self.invoices.get(&invoice_id).expect(INVOICE_NOT_FOUND);
+        assert_eq!(
+            env::predecessor_account_id(),
+            invoice.payee_id(),
+            "{}",
+            INVOICE_PAYEE_ONLY
+        );
+
+        self.invoices.remove(&invoice_id);
+        let storage_fee = invoice.storage_fee();
+        self.total_account_storage_escrow -= storage_fee;
+        if storage_fee.value() > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(storage_fee.value());
+        }
+
+        log(InvoiceClosed {
+            invoice_id,
+            payee_id: invoice.payee_id(),
+            paid: invoice.payment().is_some(),
+            storage_fee_refund: storage_fee.value(),
+        });
+        storage_fee.into()
+    }
+
+    fn invoice(&self, invoice_id: U64) -> Option<Invoice> {
+        self.invoices
+            .get(&invoice_id.0)
+            .map(|invoice| Invoice::from(invoice_id.0, invoice, env::block_timestamp().into()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::InvoiceStatus;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain, VMContext};
+
+    const PAYEE_ID: &str = "payee.near";
+
+    /// registers the payee, credits the registered account with STAKE, and sets the predecessor
+    /// to the payee
+    fn setup(ctx: &mut TestContext) -> VMContext {
+        ctx.credit_stake(ctx.account_id, 100 * YOCTO);
+
+        ctx.register_account(PAYEE_ID);
+        let mut context = ctx.set_predecessor_account_id(PAYEE_ID);
+        context.block_timestamp = 100;
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+        context
+    }
+
+    fn expiry() -> BlockTimestamp {
+        BlockTimestamp(1000.into())
+    }
+
+    #[test]
+    fn create_pay_and_close_invoice() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = setup(&mut ctx);
+
+        let invoice = ctx.create_invoice((10 * YOCTO).into(), "order #1".to_string(), expiry());
+        assert_eq!(invoice.id, 1.into());
+        assert_eq!(invoice.payee_id, PAYEE_ID);
+        assert_eq!(invoice.status, InvoiceStatus::Open);
+        assert!(invoice.storage_fee.value() > 0);
+        assert_eq!(
+            ctx.total_account_storage_escrow,
+            invoice.storage_fee.clone().into()
+        );
+        // excess deposit is refunded
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 1);
+        match receipts[0].actions[0] {
+            Action::Transfer { deposit } => {
+                assert_eq!(deposit, YOCTO - invoice.storage_fee.value())
+            }
+            _ => panic!("expected refund transfer"),
+        }
+
+        context.predecessor_account_id = ctx.account_id.to_string();
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let paid_invoice = ctx.pay_invoice(invoice.id);
+        assert_eq!(paid_invoice.status, InvoiceStatus::Paid);
+        assert_eq!(paid_invoice.paid_by, Some(ctx.account_id.to_string()));
+        assert_eq!(ctx.invoice(invoice.id), Some(paid_invoice));
+        let payer = ctx.registered_account(ctx.account_id);
+        assert_eq!(payer.stake.unwrap().amount(), (90 * YOCTO).into());
+        let payee = ctx.registered_account(PAYEE_ID);
+        assert_eq!(payee.stake.unwrap().amount(), (10 * YOCTO).into());
+
+        context.predecessor_account_id = PAYEE_ID.to_string();
+        context.attached_deposit = 0;
+        testing_env!(context);
+        assert_eq!(ctx.close_invoice(invoice.id), invoice.storage_fee);
+        assert!(ctx.invoice(invoice.id).is_none());
+        assert_eq!(ctx.total_account_storage_escrow, 0.into());
+    }
+
+    #[test]
+    #[should_panic(expected = "invoice has already been paid or has expired")]
+    fn pay_expired_invoice() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = setup(&mut ctx);
+        let invoice = ctx.create_invoice(YOCTO.into(), "order #1".to_string(), expiry());
+
+        context.predecessor_account_id = ctx.account_id.to_string();
+        context.attached_deposit = 1;
+        context.block_timestamp = 1000;
+        testing_env!(context);
+        assert_eq!(
+            ctx.invoice(invoice.id).unwrap().status,
+            InvoiceStatus::Expired
+        );
+        ctx.pay_invoice(invoice.id);
+    }
+
+    #[test]
+    #[should_panic(expected = "invoice cannot be paid by the payee")]
+    fn pay_own_invoice() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = setup(&mut ctx);
+        let invoice = ctx.create_invoice(YOCTO.into(), "order #1".to_string(), expiry());
+
+        context.attached_deposit = 1;
+        testing_env!(context);
+        ctx.pay_invoice(invoice.id);
+    }
+
+    #[test]
+    #[should_panic(expected = "attached deposit is not enough to pay for the invoice storage fee")]
+    fn create_invoice_without_storage_fee() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = setup(&mut ctx);
+        context.attached_deposit = 0;
+        testing_env!(context);
+        ctx.create_invoice(YOCTO.into(), "order #1".to_string(), expiry());
+    }
+
+    #[test]
+    #[should_panic(expected = "invoice can only be closed by the payee")]
+    fn close_invoice_by_non_payee() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = setup(&mut ctx);
+        let invoice = ctx.create_invoice(YOCTO.into(), "order #1".to_string(), expiry());
+
+        context.predecessor_account_id = ctx.account_id.to_string();
+        context.attached_deposit = 0;
+        testing_env!(context);
+        ctx.close_invoice(invoice.id);
+    }
+}
@@ -0,0 +1,545 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::core::Hash;
+use crate::domain::{
+    mul_div, Account, AccountActivityKind, RedeemLock, RedeemStakeBatch, Rounding, StakeTokenValue,
+};
+use crate::errors::liquidity_providers::{
+    INSUFFICIENT_LIQUIDITY_SHARES, INSUFFICIENT_STAKE_FOR_SWAP, LIQUIDITY_DEPOSIT_REQUIRED,
+    LIQUIDITY_PROVIDER_POOL_BALANCE_INSUFFICIENT, SWAP_LIQUIDITY_INSUFFICIENT,
+    SWAP_MIN_NEAR_OUT_NOT_MET, ZERO_LIQUIDITY_AMOUNT, ZERO_SWAP_AMOUNT,
+};
+use crate::interface::liquidity_providers::events::{
+    LiquidityAdded, LiquidityProviderClaim, LiquidityProviderPoolSettled, LiquidityRemoved,
+    StakeSwapped,
+};
+use crate::interface::{
+    LiquidityPoolBalance, LiquidityProviders, LiquidityShares, StakeSwap, YoctoNear, YoctoStake,
+};
+use crate::near::{events, log};
+use crate::*;
+use near_sdk::{json_types::ValidAccountId, near_bindgen, Promise};
+
+const BASIS_POINTS: u128 = 10_000;
+
+#[near_bindgen]
+impl LiquidityProviders for Contract {
+    #[payable]
+    fn add_liquidity(&mut self) -> LiquidityShares {
+        self.assert_not_paused();
+        let amount: domain::YoctoNear = env::attached_deposit().into();
+        assert!(amount.value() > 0, LIQUIDITY_DEPOSIT_REQUIRED);
+        let account = self.predecessor_registered_account();
+        self.settle_liquidity_provider_pool();
+
+        let shares = self
+            .liquidity_provider_pool
+            .shares_for_deposit(amount, self.liquidity_provider_pool_value());
+        self.liquidity_provider_pool.deposit(amount, shares);
+        let account_shares = self.liquidity_provider_shares(&account.id) + shares;
+        self.liquidity_provider_shares
+            .insert(&account.id, &account_shares);
+
+        log(LiquidityAdded {
+            account_id: &env::predecessor_account_id(),
+            amount: amount.value(),
+            shares,
+        });
+        self.liquidity_shares(account_shares)
+    }
+
+    fn remove_liquidity(&mut self, amount: YoctoNear) -> LiquidityShares {
+        self.assert_not_paused();
+        let amount: domain::YoctoNear = amount.into();
+        assert!(amount.value() > 0, ZERO_LIQUIDITY_AMOUNT);
+        let account = self.predecessor_registered_account();
+        self.settle_liquidity_provider_pool();
+        assert!(
+            amount <= self.liquidity_provider_pool.balance(),
+            LIQUIDITY_PROVIDER_POOL_BALANCE_INSUFFICIENT
+        );
+
+        let shares = self
+            .liquidity_provider_pool
+            .shares_for_withdrawal(amount, self.liquidity_provider_pool_value());
+        let account_shares = self.liquidity_provider_shares(&account.id);
+        assert!(shares <= account_shares, INSUFFICIENT_LIQUIDITY_SHARES);
+        self.liquidity_provider_pool.withdraw(amount, shares);
+        let account_shares = account_shares - shares;
+        if account_shares == 0 {
+            self.liquidity_provider_shares.remove(&account.id);
+        } else {
+            self.liquidity_provider_shares
+                .insert(&account.id, &account_shares);
+        }
+
+        let account_id = env::predecessor_account_id();
+        Promise::new(account_id.clone()).transfer(amount.value());
+        log(LiquidityRemoved {
+            account_id: &account_id,
+            amount: amount.value(),
+            shares,
+        });
+        self.liquidity_shares(account_shares)
+    }
+
+    fn swap_stake_for_near(&mut self, amount: YoctoStake, min_near_out: YoctoNear) -> StakeSwap {
+        self.assert_not_paused();
+        let amount: domain::YoctoStake = amount.into();
+        assert!(amount.value() > 0, ZERO_SWAP_AMOUNT);
+
+        let mut account = self.predecessor_registered_account();
+        self.claim_receipt_funds(&mut account);
+        assert!(account.can_redeem(amount), INSUFFICIENT_STAKE_FOR_SWAP);
+
+        self.settle_liquidity_provider_pool();
+        let swap = self.stake_swap_quote(amount);
+        let near: domain::YoctoNear = swap.near_value.into();
+        let fee: domain::YoctoNear = swap.fee.into();
+        let near_out = near - fee;
+        assert!(
+            near_out.value() > 0 && near_out <= self.liquidity_provider_pool.balance(),
+            SWAP_LIQUIDITY_INSUFFICIENT
+        );
+        assert!(
+            near_out.value() >= min_near_out.value(),
+            SWAP_MIN_NEAR_OUT_NOT_MET
+        );
+
+        account.record_redeem(amount, env::epoch_height().into());
+        account.apply_stake_debit(amount);
+        account.apply_near_credit(near_out);
+        self.save_registered_account(&account);
+        self.record_account_activity(
+            &account.id,
+            &account,
+            AccountActivityKind::Redeem,
+            amount.value(),
+            None,
+        );
+        self.total_near.credit(near_out);
+
+        // the swapped STAKE is redeemed on behalf of the pool
+        let mut position = self.liquidity_provider_pool.position();
+        let batch_id = self.add_stake_to_redeem_stake_batch(&mut position, amount);
+        self.liquidity_provider_pool.set_position(position);
+        self.liquidity_provider_pool.pay_out(near, fee);
+        // the swapped STAKE is held by the contract on behalf of the pool until it is redeemed
+        events::ft_transfer(
+            &env::predecessor_account_id(),
+            &env::current_account_id(),
+            amount,
+            Some("swap stake for near"),
+        );
+
+        log(StakeSwapped {
+            account_id: &env::predecessor_account_id(),
+            batch_id: domain::BatchId::from(batch_id).value(),
+            stake: amount.value(),
+            near_out: near_out.value(),
+            fee: fee.value(),
+        });
+        swap
+    }
+
+    fn quote_swap_stake_for_near(&self, amount: YoctoStake) -> StakeSwap {
+        self.stake_swap_quote(amount.into())
+    }
+
+    fn liquidity_pool_balance(&self) -> LiquidityPoolBalance {
+        let available = self.liquidity_provider_pool.balance();
+        let pending_withdrawal = self.liquidity_provider_pool_redeemed_stake_value();
+        LiquidityPoolBalance {
+            available: available.into(),
+            pending_withdrawal: pending_withdrawal.into(),
+            total: (available + pending_withdrawal).into(),
+            total_shares: self.liquidity_provider_pool.total_shares().into(),
+            fees_earned: self.liquidity_provider_pool.fees_earned().into(),
+            fee_basis_points: self.config.liquidity_provider_fee_basis_points(),
+        }
+    }
+
+    fn liquidity_shares_of(&self, account_id: ValidAccountId) -> LiquidityShares {
+        self.liquidity_shares(self.liquidity_provider_shares(&Hash::from(account_id)))
+    }
+}
+
+impl Contract {
+    fn liquidity_provider_shares(&self, account_id_hash: &Hash) -> u128 {
+        self.liquidity_provider_shares
+            .get(account_id_hash)
+            .unwrap_or(0)
+    }
+
+    fn liquidity_shares(&self, shares: u128) -> LiquidityShares {
+        LiquidityShares {
+            shares: shares.into(),
+            near_value: self
+                .liquidity_provider_pool
+                .shares_value(shares, self.liquidity_provider_pool_value())
+                .into(),
+        }
+    }
+
+    /// NEAR value of the redeemed STAKE that the liquidity provider pool holds in the redeem
+    /// pipeline - STAKE in batches that have not yet run is valued at the current STAKE token value
+    fn liquidity_provider_pool_redeemed_stake_value(&self) -> domain::YoctoNear {
+        let position = self.liquidity_provider_pool.position();
+        position
+            .redeem_stake_batch
+            .iter()
+            .chain(position.next_redeem_stake_batch.iter())
+            .map(|batch| {
+                let stake_token_value = self
+                    .redeem_stake_batch_receipts
+                    .get(&batch.id())
+                    .map_or(self.stake_token_value, |receipt| {
+                        receipt.stake_token_value()
+                    });
+                stake_token_value.stake_to_near(batch.balance().amount())
+            })
+            .fold(0.into(), |total, near| total + near)
+    }
+
+    fn liquidity_provider_pool_value(&self) -> domain::YoctoNear {
+        self.liquidity_provider_pool.balance() + self.liquidity_provider_pool_redeemed_stake_value()
+    }
+
+    pub(crate) fn has_pending_withdrawal_claim_liquidity(&self) -> bool {
+        self.near_liquidity_pool.value() > 0 || self.liquidity_provider_pool.balance().value() > 0
+    }
+
+    /// claims the NEAR for the redeemed STAKE that the liquidity provider pool holds in batches
+    /// that have completed, i.e., batches that have a receipt and are not pending withdrawal
+    /// - the NEAR was credited to the total NEAR balance when the batch completed
+    pub(crate) fn settle_liquidity_provider_pool(&mut self) {
+        fn settle_batch(
+            contract: &mut Contract,
+            batch: &mut Option<RedeemStakeBatch>,
+            pending_withdrawal_batch_id: Option<domain::BatchId>,
+        ) {
+            let account_batch = match batch {
+                Some(account_batch) if Some(account_batch.id()) != pending_withdrawal_batch_id => {
+                    *account_batch
+                }
+                _ => return,
+            };
+            let mut receipt = match contract
+                .redeem_stake_batch_receipts
+                .get(&account_batch.id())
+            {
+                Some(receipt) => receipt,
+                None => return,
+            };
+
+            let redeemed_stake = account_batch.balance().amount();
+            let near = receipt.stake_token_value().stake_to_near(redeemed_stake);
+            receipt.stake_tokens_redeemed(redeemed_stake);
+            if receipt.all_claimed() {
+                contract
+                    .redeem_stake_batch_receipts
+                    .remove(&account_batch.id());
+                contract.workflow_counters.receipts_deleted += 1;
+            } else {
+                contract
+                    .redeem_stake_batch_receipts
+                    .insert(&account_batch.id(), &receipt);
+            }
+            contract.total_near.debit(near);
+            contract.liquidity_provider_pool.credit(near);
+            *batch = None;
+
+            log(LiquidityProviderPoolSettled {
+                batch_id: account_batch.id().value(),
+                redeemed_stake: redeemed_stake.value(),
+                near: near.value(),
+            });
+        }
+
+        if let Some(RedeemLock::Unstaking) = self.redeem_stake_batch_lock {
+            return;
+        }
+        let mut position = self.liquidity_provider_pool.position();
+        if position.redeem_stake_batch.is_none() && position.next_redeem_stake_batch.is_none() {
+            return;
+        }
+        let pending_withdrawal_batch_id = self.redeem_stake_batch_pending_withdrawal_id();
+        settle_batch(
+            self,
+            &mut position.redeem_stake_batch,
+            pending_withdrawal_batch_id,
+        );
+        settle_batch(
+            self,
+            &mut position.next_redeem_stake_batch,
+            pending_withdrawal_batch_id,
+        );
+        // same as for accounts, the next batch becomes the current batch once the redeem workflow
+        // is no longer locked
+        if self.redeem_stake_batch_lock.is_none() && position.redeem_stake_batch.is_none() {
+            position.redeem_stake_batch = position.next_redeem_stake_batch.take();
+        }
+        self.liquidity_provider_pool.set_position(position);
+    }
+
+    /// serves the claim against the redeem stake batch that is pending withdrawal from the liquidity
+    /// provider pool - the pool buys the account's redeemed STAKE for its NEAR value minus the fee
+    /// - the redeemed STAKE that the pool cannot afford remains in the account's batch
+    pub(crate) fn serve_claim_from_liquidity_provider_pool(
+        &mut self,
+        account: &mut Account,
+        account_batch: &mut RedeemStakeBatch,
+        stake_token_value: StakeTokenValue,
+    ) {
+        self.settle_liquidity_provider_pool();
+        let mut position = self.liquidity_provider_pool.position();
+        // the pending withdrawal batch is the contract's current redeem stake batch
+        if let Some(batch) = position.redeem_stake_batch {
+            if batch.id() != account_batch.id() {
+                return;
+            }
+        }
+        let available = self.liquidity_provider_pool.balance();
+        if available.value() == 0 {
+            return;
+        }
+
+        let fee_basis_points = self.config.liquidity_provider_fee_basis_points();
+        let redeemed_stake = account_batch.balance().amount();
+        let near = stake_token_value.stake_to_near(redeemed_stake);
+        let (redeemed_stake, near) =
+            if near - liquidity_provider_fee(near, fee_basis_points) <= available {
+                (redeemed_stake, near)
+            } else {
+                // the max NEAR value that the pool can pay out for after the fee is deducted
+                let near: domain::YoctoNear = mul_div(
+                    available.value(),
+                    BASIS_POINTS,
+                    BASIS_POINTS - fee_basis_points as u128,
+                    Rounding::Down,
+                )
+                .into();
+                let redeemed_stake = stake_token_value.near_to_stake(near);
+                (
+                    redeemed_stake,
+                    stake_token_value.stake_to_near(redeemed_stake),
+                )
+            };
+        if redeemed_stake.value() == 0 {
+            return;
+        }
+        let fee = liquidity_provider_fee(near, fee_basis_points);
+
+        account_batch.remove(redeemed_stake);
+        account.apply_near_credit(near - fee);
+        self.total_near.credit(near - fee);
+        let mut pool_batch = position
+            .redeem_stake_batch
+            .unwrap_or_else(|| account_batch.id().new_redeem_stake_batch());
+        pool_batch.add(redeemed_stake);
+        position.redeem_stake_batch = Some(pool_batch);
+        self.liquidity_provider_pool.set_position(position);
+        self.liquidity_provider_pool.pay_out(near, fee);
+
+        log(LiquidityProviderClaim {
+            batch_id: account_batch.id().value(),
+            redeemed_stake: redeemed_stake.value(),
+            near: (near - fee).value(),
+            fee: fee.value(),
+        });
+    }
+
+    /// quotes the STAKE to NEAR swap against the liquidity provider pool at the current STAKE token
+    /// value
+    fn stake_swap_quote(&self, amount: domain::YoctoStake) -> StakeSwap {
+        let near = self.stake_token_value.stake_to_near(amount);
+        let fee_basis_points = self.liquidity_provider_pool.swap_fee_basis_points(
+            near,
+            self.liquidity_provider_pool_redeemed_stake_value(),
+            self.config.swap_fee_min_basis_points(),
+            self.config.swap_fee_max_basis_points(),
+        );
+        let fee = liquidity_provider_fee(near, fee_basis_points);
+        StakeSwap {
+            stake: amount.into(),
+            near_value: near.into(),
+            fee: fee.into(),
+            fee_basis_points,
+            near_out: (near - fee).into(),
+        }
+    }
+}
+
+/// rounds up in favor of the liquidity providers
+fn liquidity_provider_fee(near: domain::YoctoNear, fee_basis_points: u16) -> domain::YoctoNear {
+    mul_div(
+        near.value(),
+        fee_basis_points as u128,
+        BASIS_POINTS,
+        Rounding::Up,
+    )
+    .into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::StakingService;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{test_utils::get_logs, testing_env, MockedBlockchain};
+
+    const LIQUIDITY_PROVIDER_ID: &str = "liquidity-provider.near";
+
+    fn add_liquidity(ctx: &mut TestContext, amount: u128) -> LiquidityShares {
+        let mut context = ctx.set_predecessor_account_id(LIQUIDITY_PROVIDER_ID);
+        context.attached_deposit = amount;
+        testing_env!(context);
+        ctx.add_liquidity()
+    }
+
+    #[test]
+    fn add_and_remove_liquidity() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.register_account(LIQUIDITY_PROVIDER_ID);
+
+        let shares = add_liquidity(&mut ctx, 10 * YOCTO);
+        assert_eq!(shares.shares, (10 * YOCTO).into());
+        assert_eq!(shares.near_value, (10 * YOCTO).into());
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("LiquidityAdded")));
+        assert_eq!(ctx.liquidity_pool_balance().available, (10 * YOCTO).into());
+
+        let context = ctx.set_predecessor_account_id(LIQUIDITY_PROVIDER_ID);
+        testing_env!(context);
+        let shares = ctx.remove_liquidity((4 * YOCTO).into());
+        assert_eq!(shares.shares, (6 * YOCTO).into());
+        assert_eq!(
+            ctx.liquidity_shares_of(to_valid_account_id(LIQUIDITY_PROVIDER_ID)),
+            shares
+        );
+        let balance = ctx.liquidity_pool_balance();
+        assert_eq!(balance.available, (6 * YOCTO).into());
+        assert_eq!(balance.total_shares, (6 * YOCTO).into());
+        let transfer = deserialize_receipts().into_iter().find(|receipt| {
+            receipt.receiver_id == LIQUIDITY_PROVIDER_ID
+                && match receipt.actions[0] {
+                    Action::Transfer { deposit } => deposit == 4 * YOCTO,
+                    _ => false,
+                }
+        });
+        assert!(transfer.is_some());
+    }
+
+    /// Given the contract has no NEAR liquidity
+    /// And the liquidity provider pool has NEAR liquidity
+    /// When an account claims funds for a batch that is pending withdrawal
+    /// Then the claim is served from the liquidity provider pool minus the fee
+    /// And the liquidity providers earn the fee once the pending withdrawal completes
+    #[test]
+    fn claim_pending_withdrawal_served_from_liquidity_provider_pool() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.config.force_merge(interface::Config {
+            liquidity_provider_fee_basis_points: Some(100),
+            ..Default::default()
+        });
+        ctx.register_account(LIQUIDITY_PROVIDER_ID);
+        add_liquidity(&mut ctx, 10 * YOCTO);
+
+        // the account redeems 2 STAKE, which is pending withdrawal
+        let account_id = ctx.account_id;
+        let mut account = ctx.registered_account(account_id);
+        account.apply_stake_credit((2 * YOCTO).into());
+        ctx.save_registered_account(&account);
+        let mut context = ctx.set_predecessor_account_id(account_id);
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        let batch_id = ctx
+            .redeem_all()
+            .map(|id| domain::BatchId(id.into()))
+            .unwrap();
+        let stake_token_value = ctx.stake_token_value;
+        ctx.redeem_stake_batch_receipts.insert(
+            &batch_id,
+            &domain::RedeemStakeBatchReceipt::new(
+                (2 * YOCTO).into(),
+                stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
+        );
+        ctx.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+
+        ctx.claim_receipts();
+        let fee = 2 * YOCTO / 100;
+        let account = ctx.registered_account(account_id);
+        assert_eq!(account.near.unwrap().amount(), (2 * YOCTO - fee).into());
+        assert!(account.redeem_stake_batch.is_none());
+        let balance = ctx.liquidity_pool_balance();
+        assert_eq!(balance.available, (8 * YOCTO + fee).into());
+        assert_eq!(balance.pending_withdrawal, (2 * YOCTO).into());
+        assert_eq!(balance.fees_earned, fee.into());
+        assert_eq!(
+            ctx.liquidity_shares_of(to_valid_account_id(LIQUIDITY_PROVIDER_ID))
+                .near_value,
+            (10 * YOCTO + fee).into()
+        );
+
+        // the pending withdrawal completes
+        ctx.total_near.credit((2 * YOCTO).into());
+        ctx.redeem_stake_batch_lock = None;
+        ctx.pop_redeem_stake_batch();
+        ctx.settle_liquidity_provider_pool();
+        let balance = ctx.liquidity_pool_balance();
+        assert_eq!(balance.available, (10 * YOCTO + fee).into());
+        assert_eq!(balance.pending_withdrawal, 0.into());
+        assert!(ctx.redeem_stake_batch_receipts.get(&batch_id).is_none());
+        assert_eq!(ctx.total_near.amount(), (2 * YOCTO - fee).into());
+    }
+
+    /// Given the liquidity provider pool has NEAR liquidity
+    /// When an account swaps STAKE for NEAR
+    /// Then the NEAR value minus the utilization based fee is credited to the account
+    /// And the STAKE is added to the redeem stake batch on behalf of the pool
+    #[test]
+    fn swap_stake_for_near() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.register_account(LIQUIDITY_PROVIDER_ID);
+        add_liquidity(&mut ctx, 10 * YOCTO);
+
+        let account_id = ctx.account_id;
+        let mut account = ctx.registered_account(account_id);
+        account.apply_stake_credit((5 * YOCTO).into());
+        ctx.save_registered_account(&account);
+        let context = ctx.set_predecessor_account_id(account_id);
+        testing_env!(context);
+
+        let quote = ctx.quote_swap_stake_for_near((5 * YOCTO).into());
+        // 50% utilization after the swap: 10 bps + 50% * (100 bps - 10 bps)
+        assert_eq!(quote.fee_basis_points, 55);
+        let swap = ctx.swap_stake_for_near((5 * YOCTO).into(), quote.near_out);
+        assert_eq!(swap, quote);
+        let fee = 5 * YOCTO * 55 / 10_000;
+        assert_eq!(swap.fee, fee.into());
+        assert_eq!(swap.near_out, (5 * YOCTO - fee).into());
+        assert!(get_logs().iter().any(|log| log.starts_with("StakeSwapped")));
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains(r#""event":"ft_transfer""#)
+                && log.contains(r#""memo":"swap stake for near""#)));
+
+        let account = ctx.registered_account(account_id);
+        assert!(account.stake.is_none());
+        assert_eq!(account.near.unwrap().amount(), (5 * YOCTO - fee).into());
+        // the STAKE is redeemed on behalf of the pool
+        assert!(account.redeem_stake_batch.is_none());
+        assert_eq!(
+            ctx.redeem_stake_batch.unwrap().balance().amount(),
+            (5 * YOCTO).into()
+        );
+
+        let balance = ctx.liquidity_pool_balance();
+        assert_eq!(balance.available, (5 * YOCTO + fee).into());
+        assert_eq!(balance.pending_withdrawal, (5 * YOCTO).into());
+        assert_eq!(balance.total, (10 * YOCTO + fee).into());
+    }
+}
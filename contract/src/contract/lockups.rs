@@ -0,0 +1,254 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::core::Hash;
+use crate::errors::lockups::{
+    INVALID_VESTING_SCHEDULE, LOCKUP_ALREADY_EXISTS, LOCKUP_NOT_FOUND, LOCKUP_STORAGE_FEE_REQUIRED,
+    ZERO_LOCKUP_AMOUNT,
+};
+use crate::interface::lockups::events::{LockupClosed, LockupCreated, VestedStakeClaimed};
+use crate::interface::{BlockTimestamp, Lockup, Lockups, YoctoStake};
+use crate::near::{events, log};
+use crate::*;
+use near_sdk::{json_types::ValidAccountId, near_bindgen, Promise};
+
+#[near_bindgen]
+impl Lockups for Contract {
+    #[payable]
+    fn create_lockup(
+        &mut self,
+        beneficiary_id: ValidAccountId,
+        amount: YoctoStake,
+        vesting_start: BlockTimestamp,
+        vesting_end: BlockTimestamp,
+    ) -> Lockup {
+        self.assert_not_paused();
+        let amount: domain::YoctoStake = amount.into();
+        assert!(amount.value() > 0, ZERO_LOCKUP_AMOUNT);
+        let vesting_start: domain::BlockTimestamp = vesting_start.into();
+        let vesting_end: domain::BlockTimestamp = vesting_end.into();
+        assert!(vesting_end > vesting_start, INVALID_VESTING_SCHEDULE);
+        // the beneficiary must be registered in order to claim the vested STAKE
+        let beneficiary = self.registered_account(beneficiary_id.as_ref());
+        assert!(
+            !self.lockups.contains_key(&beneficiary.id),
+            LOCKUP_ALREADY_EXISTS
+        );
+
+        let grantor_id = env::predecessor_account_id();
+        let mut grantor = self.predecessor_registered_account();
+        self.claim_receipt_funds(&mut grantor);
+        grantor.apply_stake_debit(amount);
+        self.save_registered_account(&grantor);
+
+        let lockup = domain::Lockup::new(
+            grantor_id.clone(),
+            amount,
+            vesting_start,
+            vesting_end,
+            domain::BlockTimeHeight::from_env(),
+        );
+        let storage_fee = self.insert_lockup(&beneficiary.id, lockup);
+        // the locked up STAKE is held by the contract until it vests and is claimed
+        events::ft_transfer(
+            &grantor_id,
+            &env::current_account_id(),
+            amount,
+            Some("lockup"),
+        );
+        let refund = env::attached_deposit() - storage_fee.value();
+        if refund > 0 {
+            Promise::new(grantor_id.clone()).transfer(refund);
+        }
+
+        log(LockupCreated {
+            grantor_id: &grantor_id,
+            beneficiary_id: beneficiary_id.as_ref(),
+            amount: amount.value(),
+            vesting_start: vesting_start.value(),
+            vesting_end: vesting_end.value(),
+        });
+        self.lockup_of(beneficiary_id).unwrap()
+    }
+
+    fn claim_vested(&mut self) -> YoctoStake {
+        self.assert_not_paused();
+        let mut account = self.predecessor_registered_account();
+        let account_id = env::predecessor_account_id();
+        let mut lockup = self.lockups.get(&account.id).expect(LOCKUP_NOT_FOUND);
+
+        let amount = lockup.claim(env::block_timestamp().into());
+        if amount.value() > 0 {
+            account.apply_stake_credit(amount);
+            self.save_registered_account(&account);
+            events::ft_transfer(
+                &env::current_account_id(),
+                &account_id,
+                amount,
+                Some("vested lockup"),
+            );
+        }
+        log(VestedStakeClaimed {
+            beneficiary_id: &account_id,
+            amount: amount.value(),
+            remaining: (lockup.amount() - lockup.claimed()).value(),
+        });
+
+        if lockup.is_fully_claimed() {
+            self.lockups.remove(&account.id);
+            let storage_fee = lockup.storage_fee();
+            self.total_account_storage_escrow -= storage_fee;
+            if storage_fee.value() > 0 {
+                Promise::new(lockup.grantor_id().to_string()).transfer(storage_fee.value());
+            }
+            log(LockupClosed {
+                grantor_id: lockup.grantor_id(),
+                beneficiary_id: &account_id,
+                storage_fee_refund: storage_fee.value(),
+            });
+        } else {
+            self.lockups.insert(&account.id, &lockup);
+        }
+        amount.into()
+    }
+
+    fn lockup_of(&self, account_id: ValidAccountId) -> Option<Lockup> {
+        self.lockups.get(&Hash::from(account_id)).map(|lockup| {
+            Lockup::new(
+                lockup,
+                env::block_timestamp().into(),
+                self.stake_token_value,
+            )
+        })
+    }
+}
+
+impl Contract {
+    /// inserts the lockup record - the storage fee is escrowed
+    /// - returns the storage fee, which is paid from the attached deposit
+    fn insert_lockup(
+        &mut self,
+        beneficiary_id_hash: &Hash,
+        mut lockup: domain::Lockup,
+    ) -> domain::YoctoNear {
+        let initial_storage_usage = env::storage_usage();
+        self.lockups.insert(beneficiary_id_hash, &lockup);
+        let storage_fee = self.storage_fee(env::storage_usage() - initial_storage_usage);
+        assert!(
+            env::attached_deposit() >= storage_fee.value(),
+            "{}: storage fee = {} yoctoNEAR",
+            LOCKUP_STORAGE_FEE_REQUIRED,
+            storage_fee
+        );
+        lockup.set_storage_fee(storage_fee);
+        self.lockups.insert(beneficiary_id_hash, &lockup);
+        self.total_account_storage_escrow += storage_fee;
+        storage_fee
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{test_utils::get_logs, testing_env, MockedBlockchain};
+
+    const BENEFICIARY_ID: &str = "beneficiary.near";
+
+    /// grants 100 STAKE from the test account to the beneficiary, vesting between timestamps 1000
+    /// and 2000
+    fn create_lockup(ctx: &mut TestContext) -> Lockup {
+        ctx.register_account(BENEFICIARY_ID);
+        ctx.credit_stake(ctx.account_id, 100 * YOCTO);
+        let account_id = ctx.account_id;
+        let mut context = ctx.set_predecessor_account_id(account_id);
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        ctx.create_lockup(
+            to_valid_account_id(BENEFICIARY_ID),
+            (100 * YOCTO).into(),
+            BlockTimestamp(1000.into()),
+            BlockTimestamp(2000.into()),
+        )
+    }
+
+    fn claim_vested_at(ctx: &mut TestContext, block_timestamp: u64) -> YoctoStake {
+        let mut context = ctx.set_predecessor_account_id(BENEFICIARY_ID);
+        context.block_timestamp = block_timestamp;
+        testing_env!(context);
+        ctx.claim_vested()
+    }
+
+    #[test]
+    fn create_lockup_escrows_granted_stake() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        let lockup = create_lockup(&mut ctx);
+        assert_eq!(lockup.grantor_id, account_id);
+        assert_eq!(lockup.amount, (100 * YOCTO).into());
+        assert_eq!(lockup.locked, (100 * YOCTO).into());
+        assert_eq!(lockup.claimable, 0.into());
+        assert!(lockup.storage_fee.value() > 0);
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("LockupCreated")));
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains(r#""event":"ft_transfer""#)
+                && log.contains(r#""memo":"lockup""#)));
+
+        // the granted STAKE is neither in the grantor's nor in the beneficiary's account
+        assert!(ctx.registered_account(account_id).stake.is_none());
+        assert!(ctx.registered_account(BENEFICIARY_ID).stake.is_none());
+        assert_eq!(
+            ctx.lockup_of(to_valid_account_id(BENEFICIARY_ID)),
+            Some(lockup)
+        );
+    }
+
+    #[test]
+    fn claim_vested_stake() {
+        let mut ctx = TestContext::with_registered_account();
+        let lockup = create_lockup(&mut ctx);
+
+        // nothing has vested before the vesting start
+        assert_eq!(claim_vested_at(&mut ctx, 500), 0.into());
+        assert!(ctx.registered_account(BENEFICIARY_ID).stake.is_none());
+
+        assert_eq!(claim_vested_at(&mut ctx, 1250), (25 * YOCTO).into());
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains(r#""event":"ft_transfer""#)
+                && log.contains(r#""memo":"vested lockup""#)));
+        assert_eq!(
+            ctx.registered_account(BENEFICIARY_ID)
+                .stake
+                .unwrap()
+                .amount(),
+            (25 * YOCTO).into()
+        );
+        let status = ctx.lockup_of(to_valid_account_id(BENEFICIARY_ID)).unwrap();
+        assert_eq!(status.claimed, (25 * YOCTO).into());
+        assert_eq!(status.locked, (75 * YOCTO).into());
+        assert_eq!(status.claimable, 0.into());
+
+        // the lockup is closed once all of the granted STAKE is claimed
+        assert_eq!(claim_vested_at(&mut ctx, 3000), (75 * YOCTO).into());
+        assert_eq!(
+            ctx.registered_account(BENEFICIARY_ID)
+                .stake
+                .unwrap()
+                .amount(),
+            (100 * YOCTO).into()
+        );
+        assert!(ctx.lockup_of(to_valid_account_id(BENEFICIARY_ID)).is_none());
+        assert!(get_logs().iter().any(|log| log.starts_with("LockupClosed")));
+        let refund = deserialize_receipts().into_iter().find(|receipt| {
+            receipt.receiver_id == ctx.account_id
+                && match receipt.actions[0] {
+                    Action::Transfer { deposit } => deposit == lockup.storage_fee.value(),
+                    _ => false,
+                }
+        });
+        assert!(refund.is_some());
+    }
+}
@@ -0,0 +1,655 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::config::ConfigV1;
+use crate::domain::{
+    BatchKind, BlockTimeHeight, LegacyRedeemStakeBatchReceipt, LegacyStakeBatchReceipt,
+    MigrationCursor, MigrationTask, StateMigration, StateVersion, CURRENT_STATE_VERSION,
+};
+use crate::errors::{
+    asserts::PREDECESSOR_MUST_BE_CONTRACT,
+    migration::{
+        BATCH_RECEIPTS_NOT_MIGRATED, CONTRACT_STATE_NOT_INITIALIZED, MIGRATION_ALREADY_COMPLETED,
+        MIGRATION_IN_PROGRESS, STATE_MIGRATION_NOT_REGISTERED, STATE_VERSION_AHEAD_OF_CODE,
+        UNKNOWN_MIGRATION_TASK,
+    },
+};
+use crate::interface::migration::events::{MigrationCompleted, MigrationStarted, StateMigrated};
+use crate::interface::{ContractVersion, MigrationProgress, Migrations};
+use crate::near::{
+    log,
+    storage_keys::{
+        REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX, STAKE_BATCH_RECEIPTS_KEY_PREFIX, STATE_VERSION_KEY,
+    },
+};
+use crate::*;
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    collections::LookupMap,
+    near_bindgen, AccountId,
+};
+use std::ops::Range;
+
+#[near_bindgen]
+impl Migrations for Contract {
+    fn migrate_chunk(&mut self, task_id: String, limit: u32) -> MigrationProgress {
+        let task = MigrationTask::from_id(&task_id).expect(UNKNOWN_MIGRATION_TASK);
+
+        let mut migration = match self.migrations.get(&task) {
+            Some(migration) => {
+                assert!(!migration.is_complete(), MIGRATION_ALREADY_COMPLETED);
+                migration
+            }
+            None => self.start_migration(task),
+        };
+
+        let chunk = migration.next_chunk(limit);
+        let migrated = chunk
+            .clone()
+            .filter(|cursor| self.migrate_record(task, *cursor))
+            .count() as u64;
+        migration.advance(chunk, migrated, BlockTimeHeight::from_env());
+        self.migrations.insert(&task, &migration);
+
+        if migration.is_complete() {
+            log(MigrationCompleted {
+                task_id: task.id(),
+                migrated: migration.migrated(),
+            });
+        }
+        MigrationProgress::new(task, migration)
+    }
+
+    fn reset_migration(&mut self, task_id: String) -> bool {
+        self.assert_predecessor_is_operator();
+        let task = MigrationTask::from_id(&task_id).expect(UNKNOWN_MIGRATION_TASK);
+        if let Some(migration) = self.migrations.get(&task) {
+            assert!(migration.is_complete(), MIGRATION_IN_PROGRESS);
+        }
+        self.migrations.remove(&task).is_some()
+    }
+
+    fn migration_progress(&self, task_id: String) -> Option<MigrationProgress> {
+        let task = MigrationTask::from_id(&task_id).expect(UNKNOWN_MIGRATION_TASK);
+        self.migrations
+            .get(&task)
+            .map(|migration| MigrationProgress::new(task, migration))
+    }
+
+    fn migration_tasks(&self) -> Vec<String> {
+        MigrationTask::ALL
+            .iter()
+            .map(|task| task.id().to_string())
+            .collect()
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Migrates the persisted contract state to the state layout that the contract code was compiled
+    /// against, i.e., [CURRENT_STATE_VERSION]. The registered migration steps are run in version
+    /// order starting from the persisted state version.
+    /// - is meant to be batched with the deploy contract action, which means it must be invoked by
+    ///   the contract account itself
+    /// - no-op if the persisted state version is current
+    ///
+    /// ## Panics
+    /// - if not invoked by the contract account
+    /// - if the persisted state version is newer than the contract code state version
+    /// - if a migration step is not registered for a state version that needs to be migrated
+    #[init]
+    pub fn migrate() -> Self {
+        assert_eq!(
+            env::predecessor_account_id(),
+            env::current_account_id(),
+            "{}",
+            PREDECESSOR_MUST_BE_CONTRACT
+        );
+        run_state_migrations(&state_migrations());
+        env::state_read().expect(CONTRACT_STATE_NOT_INITIALIZED)
+    }
+
+    /// returns the contract code version along with the persisted state version
+    /// - the contract state is not deserialized, thus the view can be used to check if the persisted
+    ///   state needs to be migrated after new contract code is deployed
+    pub fn contract_version() -> ContractVersion {
+        ContractVersion {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            git_commit: env!("GIT_COMMIT").to_string(),
+            state_schema_hash: env!("STATE_SCHEMA_HASH").to_string(),
+            state_version: CURRENT_STATE_VERSION.value(),
+            persisted_state_version: Self::persisted_state_version().value(),
+        }
+    }
+}
+
+impl Contract {
+    /// state that was persisted before the state version was recorded uses the initial layout
+    pub(crate) fn persisted_state_version() -> StateVersion {
+        env::storage_read(&STATE_VERSION_KEY).map_or(StateVersion(1), |bytes| {
+            StateVersion::try_from_slice(&bytes).unwrap()
+        })
+    }
+
+    pub(crate) fn save_state_version(version: StateVersion) {
+        env::storage_write(&STATE_VERSION_KEY, &version.try_to_vec().unwrap());
+    }
+
+    fn start_migration(&self, task: MigrationTask) -> MigrationCursor {
+        let range = self.migration_range(task);
+        log(MigrationStarted {
+            task_id: task.id(),
+            cursor: range.start,
+            end: range.end,
+        });
+        MigrationCursor::new(range.start, range.end, BlockTimeHeight::from_env())
+    }
+
+    /// receipts for the account's batches can only be claimed once they have been migrated, i.e.,
+    /// untagged legacy receipts are not visible to the claim until they are moved
+    ///
+    /// ## Panics
+    /// if a batch receipt migration that is in progress has not yet reached any of the batches
+    pub(crate) fn assert_batch_receipts_migrated(&self, batch_ids: &[domain::BatchId]) {
+        for batch_id in batch_ids {
+            let task = match batch_id.kind() {
+                BatchKind::Stake => MigrationTask::StakeBatchReceipts,
+                BatchKind::Redeem => MigrationTask::RedeemStakeBatchReceipts,
+            };
+            if let Some(migration) = self.migrations.get(&task) {
+                let cursor = batch_id.sequence() as u64;
+                assert!(
+                    cursor < migration.cursor() || cursor >= migration.end(),
+                    BATCH_RECEIPTS_NOT_MIGRATED
+                );
+            }
+        }
+    }
+
+    /// cursor range that the migration task walks
+    fn migration_range(&self, task: MigrationTask) -> Range<u64> {
+        match task {
+            // batch receipts are keyed by the batch ID, which is allocated from the batch ID sequence
+            MigrationTask::StakeBatchReceipts | MigrationTask::RedeemStakeBatchReceipts => {
+                1..(self.batch_id_sequence.sequence() as u64).saturating_add(1)
+            }
+        }
+    }
+
+    /// returns true if a record exists at the cursor position and was migrated
+    /// - untagged legacy receipts are moved to the versioned receipts - legacy receipts are keyed by
+    ///   the batch ID sequence, i.e., legacy batch IDs are not tagged with the batch kind, thus
+    ///   legacy redeem stake batch receipts are re-keyed using the tagged batch ID
+    fn migrate_record(&mut self, task: MigrationTask, cursor: u64) -> bool {
+        let legacy_batch_id = domain::BatchId(cursor as u128);
+        match task {
+            MigrationTask::StakeBatchReceipts => {
+                let mut legacy_receipts =
+                    LookupMap::<domain::BatchId, LegacyStakeBatchReceipt>::new(
+                        STAKE_BATCH_RECEIPTS_KEY_PREFIX.to_vec(),
+                    );
+                if let Some(receipt) = legacy_receipts.remove(&legacy_batch_id) {
+                    let receipt = receipt.into_receipt(self.staking_pool_id.clone());
+                    self.stake_batch_receipts.insert(&legacy_batch_id, &receipt);
+                    return true;
+                }
+                let batch_id = legacy_batch_id.with_kind(BatchKind::Stake);
+                self.stake_batch_receipts.upgrade(&batch_id)
+            }
+            MigrationTask::RedeemStakeBatchReceipts => {
+                let mut legacy_receipts =
+                    LookupMap::<domain::BatchId, LegacyRedeemStakeBatchReceipt>::new(
+                        REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX.to_vec(),
+                    );
+                let batch_id = legacy_batch_id.with_kind(BatchKind::Redeem);
+                if let Some(receipt) = legacy_receipts.remove(&legacy_batch_id) {
+                    let receipt = receipt.into_receipt(self.staking_pool_id.clone());
+                    self.redeem_stake_batch_receipts.insert(&batch_id, &receipt);
+                    return true;
+                }
+                self.redeem_stake_batch_receipts.upgrade(&batch_id)
+            }
+        }
+    }
+}
+
+/// State migration registry - a migration step must be registered for each [CURRENT_STATE_VERSION]
+/// bump, in version order
+fn state_migrations() -> Vec<Box<dyn StateMigration>> {
+    vec![Box::new(StateV2Migration)]
+}
+
+/// [Contract] state layout that was persisted by state version 1, i.e., the initial contract layout
+/// - persistent collections are Borsh encoded as their storage key prefix
+#[derive(BorshSerialize, BorshDeserialize)]
+struct ContractV1 {
+    owner_id: AccountId,
+    contract_owner_balance: domain::YoctoNear,
+    contract_initial_storage_usage: domain::StorageUsage,
+    collected_earnings: domain::YoctoNear,
+    operator_id: AccountId,
+    config: ConfigV1,
+    config_change_block_height: domain::BlockHeight,
+    account_storage_usage: domain::StorageUsage,
+    total_account_storage_escrow: domain::YoctoNear,
+    accounts_key_prefix: Vec<u8>,
+    accounts_len: u128,
+    total_near: domain::TimestampedNearBalance,
+    total_stake: domain::TimestampedStakeBalance,
+    near_liquidity_pool: domain::YoctoNear,
+    stake_token_value: domain::StakeTokenValue,
+    batch_id_sequence: domain::BatchId,
+    stake_batch: Option<domain::StakeBatch>,
+    next_stake_batch: Option<domain::StakeBatch>,
+    redeem_stake_batch: Option<domain::RedeemStakeBatch>,
+    next_redeem_stake_batch: Option<domain::RedeemStakeBatch>,
+    stake_batch_receipts_key_prefix: Vec<u8>,
+    redeem_stake_batch_receipts_key_prefix: Vec<u8>,
+    staking_pool_id: AccountId,
+    stake_batch_lock: Option<domain::StakeLock>,
+    redeem_stake_batch_lock: Option<domain::RedeemLock>,
+}
+
+/// upgrades the initial contract state layout to state version 2
+/// - state that was introduced by version 2 is initialized the same way as for new contracts
+/// - account storage usage is re-measured because the account layout changed
+/// - redeem stake batch IDs are tagged with the batch kind - see [BatchKind]
+/// - persistent collection records are not rewritten here:
+///   - legacy accounts are read from the original accounts key prefix and upgraded when saved -
+///     their redeem stake batch IDs are tagged when they are read
+///   - legacy batch receipts are moved by the batch receipt [MigrationTask]s, which are started
+///     here - receipts can not be claimed for batches that the migrations have not yet reached,
+///     and anyone can advance the migrations
+///   - legacy account IDs are not indexed - see [index_account](crate::interface::AccountManagement::index_account)
+struct StateV2Migration;
+
+impl StateMigration for StateV2Migration {
+    fn version(&self) -> StateVersion {
+        StateVersion(2)
+    }
+
+    fn migrate(&self) {
+        let state: ContractV1 = env::state_read().expect(CONTRACT_STATE_NOT_INITIALIZED);
+        let mut contract = Contract::initial_state(
+            state.staking_pool_id,
+            state.owner_id,
+            state.operator_id,
+            state.config.into(),
+        );
+        contract.contract_owner_balance = state.contract_owner_balance;
+        contract.contract_initial_storage_usage = state.contract_initial_storage_usage;
+        contract.collected_earnings = state.collected_earnings;
+        contract.config_change_block_height = state.config_change_block_height;
+        contract.total_account_storage_escrow = state.total_account_storage_escrow;
+        contract.accounts_len = state.accounts_len;
+        contract.total_near = state.total_near;
+        contract.total_stake = state.total_stake;
+        contract.near_liquidity_pool = state.near_liquidity_pool;
+        contract.stake_token_value = state.stake_token_value;
+        contract.batch_id_sequence = state.batch_id_sequence;
+        contract.stake_batch = state.stake_batch;
+        contract.next_stake_batch = state.next_stake_batch;
+        contract.redeem_stake_batch = state
+            .redeem_stake_batch
+            .map(domain::RedeemStakeBatch::with_tagged_batch_id);
+        contract.next_redeem_stake_batch = state
+            .next_redeem_stake_batch
+            .map(domain::RedeemStakeBatch::with_tagged_batch_id);
+        contract.stake_batch_lock = state.stake_batch_lock;
+        contract.redeem_stake_batch_lock = state.redeem_stake_batch_lock;
+        contract.measure_account_storage_usage();
+        // the receipt for the batch that is pending withdrawal is read by the redeem workflow
+        if let Some(batch) = contract.redeem_stake_batch {
+            let mut legacy_receipts =
+                LookupMap::<domain::BatchId, LegacyRedeemStakeBatchReceipt>::new(
+                    REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX.to_vec(),
+                );
+            if let Some(receipt) = legacy_receipts.remove(&domain::BatchId(batch.id().sequence())) {
+                let receipt = receipt.into_receipt(contract.staking_pool_id.clone());
+                contract
+                    .redeem_stake_batch_receipts
+                    .insert(&batch.id(), &receipt);
+            }
+        }
+        for task in MigrationTask::ALL.iter() {
+            let migration = contract.start_migration(*task);
+            contract.migrations.insert(task, &migration);
+        }
+        env::state_write(&contract);
+    }
+}
+
+/// runs the migration steps that upgrade the persisted state version to [CURRENT_STATE_VERSION]
+fn run_state_migrations(migrations: &[Box<dyn StateMigration>]) {
+    let persisted_version = Contract::persisted_state_version();
+    assert!(
+        persisted_version <= CURRENT_STATE_VERSION,
+        STATE_VERSION_AHEAD_OF_CODE
+    );
+    for version in (persisted_version.value() + 1)..=CURRENT_STATE_VERSION.value() {
+        let migration = migrations
+            .iter()
+            .find(|migration| migration.version() == StateVersion(version))
+            .expect(STATE_MIGRATION_NOT_REGISTERED);
+        migration.migrate();
+        Contract::save_state_version(StateVersion(version));
+        log(StateMigrated {
+            from: version - 1,
+            to: version,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::{CallBacksGasConfigV1, GasConfig, GasConfigV1, StakingPoolGasConfigV1};
+    use crate::domain::TGAS;
+    use crate::near::{storage_keys::ACCOUNTS_KEY_PREFIX, YOCTO};
+    use crate::test_utils::*;
+    use near_sdk::{test_utils::get_logs, testing_env, MockedBlockchain};
+
+    fn set_operator_as_predecessor(ctx: &mut TestContext) {
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.contract.operator_id.clone();
+        testing_env!(context);
+    }
+
+    #[test]
+    fn migrate_stake_batch_receipts_in_chunks() {
+        let mut ctx = TestContext::new();
+        set_operator_as_predecessor(&mut ctx);
+
+        // receipts are only stored for some of the batch IDs
+        for sequence in 1..=5 {
+            ctx.batch_id_sequence = domain::BatchId(sequence);
+            if sequence % 2 == 1 {
+                let batch = domain::StakeBatch::new(ctx.batch_id_sequence, YOCTO.into());
+                let receipt = domain::StakeBatchReceipt::new(
+                    batch.balance().amount(),
+                    ctx.stake_token_value,
+                    ctx.staking_pool_id.clone(),
+                );
+                ctx.stake_batch_receipts.insert(&batch.id(), &receipt);
+            }
+        }
+        assert_eq!(
+            ctx.migration_tasks(),
+            vec![
+                "stake_batch_receipts".to_string(),
+                "redeem_stake_batch_receipts".to_string()
+            ]
+        );
+        assert!(ctx
+            .migration_progress("stake_batch_receipts".to_string())
+            .is_none());
+
+        let progress = ctx.migrate_chunk("stake_batch_receipts".to_string(), 2);
+        assert_eq!(progress.cursor, 3);
+        assert_eq!(progress.end, 6);
+        assert_eq!(progress.migrated, 1);
+        assert!(progress.completed.is_none());
+
+        let progress = ctx.migrate_chunk("stake_batch_receipts".to_string(), 10);
+        assert_eq!(progress.cursor, 6);
+        assert_eq!(progress.migrated, 3);
+        assert!(progress.completed.is_some());
+        assert_eq!(
+            ctx.migration_progress("stake_batch_receipts".to_string()),
+            Some(progress)
+        );
+        // records are still readable after they were migrated
+        assert!(ctx.stake_batch_receipts.get(&domain::BatchId(5)).is_some());
+
+        assert!(ctx.reset_migration("stake_batch_receipts".to_string()));
+        assert!(ctx
+            .migration_progress("stake_batch_receipts".to_string())
+            .is_none());
+        assert!(!ctx.reset_migration("stake_batch_receipts".to_string()));
+    }
+
+    #[test]
+    fn migrate_legacy_batch_receipts() {
+        let mut ctx = TestContext::new();
+        set_operator_as_predecessor(&mut ctx);
+
+        ctx.batch_id_sequence = domain::BatchId(2);
+        let mut legacy_stake_batch_receipts =
+            LookupMap::<domain::BatchId, LegacyStakeBatchReceipt>::new(
+                STAKE_BATCH_RECEIPTS_KEY_PREFIX.to_vec(),
+            );
+        legacy_stake_batch_receipts.insert(
+            &domain::BatchId(1),
+            &LegacyStakeBatchReceipt {
+                staked_near: YOCTO.into(),
+                stake_token_value: ctx.stake_token_value,
+            },
+        );
+        let mut legacy_redeem_stake_batch_receipts =
+            LookupMap::<domain::BatchId, LegacyRedeemStakeBatchReceipt>::new(
+                REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX.to_vec(),
+            );
+        legacy_redeem_stake_batch_receipts.insert(
+            &domain::BatchId(2),
+            &LegacyRedeemStakeBatchReceipt {
+                redeemed_stake: YOCTO.into(),
+                stake_token_value: ctx.stake_token_value,
+            },
+        );
+        assert!(ctx.stake_batch_receipts.get(&domain::BatchId(1)).is_none());
+
+        let progress = ctx.migrate_chunk("stake_batch_receipts".to_string(), 10);
+        assert_eq!(progress.migrated, 1);
+        let progress = ctx.migrate_chunk("redeem_stake_batch_receipts".to_string(), 10);
+        assert_eq!(progress.migrated, 1);
+
+        // legacy receipts are keyed by the untagged batch ID - redeem stake batch receipts are
+        // re-keyed using the tagged batch ID, which is what the migrated batches reference
+        let receipt = ctx.stake_batch_receipts.get(&domain::BatchId(1)).unwrap();
+        assert_eq!(receipt.staked_near(), YOCTO.into());
+        assert_eq!(receipt.staking_pool_id(), ctx.staking_pool_id);
+        let receipt = ctx
+            .redeem_stake_batch_receipts
+            .get(&domain::BatchId(2).with_kind(BatchKind::Redeem))
+            .unwrap();
+        assert_eq!(receipt.redeemed_stake(), YOCTO.into());
+        assert!(!legacy_stake_batch_receipts.contains_key(&domain::BatchId(1)));
+        assert!(!legacy_redeem_stake_batch_receipts.contains_key(&domain::BatchId(2)));
+    }
+
+    const MIGRATED_KEY: &[u8] = b"MIGRATED";
+
+    /// records that it was run
+    struct TestMigration(StateVersion);
+
+    impl StateMigration for TestMigration {
+        fn version(&self) -> StateVersion {
+            self.0
+        }
+
+        fn migrate(&self) {
+            env::storage_write(MIGRATED_KEY, &self.0.try_to_vec().unwrap());
+        }
+    }
+
+    #[test]
+    fn migrate_when_state_is_current() {
+        let mut ctx = TestContext::new();
+        env::state_write(&ctx.contract);
+        ctx.context.predecessor_account_id = ctx.context.current_account_id.clone();
+        testing_env!(ctx.context.clone());
+
+        assert_eq!(Contract::persisted_state_version(), CURRENT_STATE_VERSION);
+        let contract = Contract::migrate();
+        assert_eq!(contract.operator_id, ctx.operator_id);
+        assert!(get_logs().is_empty());
+
+        let version = Contract::contract_version();
+        assert_eq!(version.state_version, CURRENT_STATE_VERSION.value());
+        assert_eq!(version.persisted_state_version, version.state_version);
+    }
+
+    #[test]
+    fn state_migrations_are_run_from_the_persisted_state_version() {
+        let ctx = TestContext::new();
+        testing_env!(ctx.context.clone());
+        let previous_version = StateVersion(CURRENT_STATE_VERSION.value() - 1);
+        Contract::save_state_version(previous_version);
+        assert_eq!(
+            Contract::contract_version().persisted_state_version,
+            previous_version.value()
+        );
+
+        let migrations: Vec<Box<dyn StateMigration>> = vec![
+            Box::new(TestMigration(CURRENT_STATE_VERSION)),
+            Box::new(TestMigration(previous_version)),
+        ];
+        run_state_migrations(&migrations);
+        assert_eq!(
+            env::storage_read(MIGRATED_KEY),
+            Some(CURRENT_STATE_VERSION.try_to_vec().unwrap())
+        );
+        assert_eq!(Contract::persisted_state_version(), CURRENT_STATE_VERSION);
+        assert!(get_logs()[0].starts_with("StateMigrated"));
+
+        // migrations are only run once
+        env::storage_remove(MIGRATED_KEY);
+        run_state_migrations(&migrations);
+        assert!(env::storage_read(MIGRATED_KEY).is_none());
+    }
+
+    #[test]
+    fn state_migration_is_registered_for_each_state_version_bump() {
+        let versions: Vec<u32> = state_migrations()
+            .iter()
+            .map(|migration| migration.version().value())
+            .collect();
+        assert_eq!(
+            versions,
+            (2..=CURRENT_STATE_VERSION.value()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn migrate_state_v1() {
+        let ctx = TestContext::new();
+        let account_storage_usage = ctx.account_storage_usage;
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context);
+        // the initial contract layout was persisted before the state version was recorded
+        env::storage_remove(&STATE_VERSION_KEY);
+
+        let batch_id = domain::BatchId(3);
+        env::state_write(&ContractV1 {
+            owner_id: ctx.owner_id.clone(),
+            contract_owner_balance: (10 * YOCTO).into(),
+            contract_initial_storage_usage: domain::StorageUsage(1000),
+            collected_earnings: YOCTO.into(),
+            operator_id: ctx.operator_id.clone(),
+            config: ConfigV1 {
+                storage_cost_per_byte: domain::YoctoNear(1000),
+                gas_config: GasConfigV1 {
+                    staking_pool: StakingPoolGasConfigV1 {
+                        deposit_and_stake: TGAS * 60,
+                        deposit: TGAS * 45,
+                        stake: TGAS * 45,
+                        unstake: TGAS * 55,
+                        withdraw: TGAS * 45,
+                        get_account: TGAS * 6,
+                        ping: TGAS * 45,
+                    },
+                    callbacks: CallBacksGasConfigV1 {
+                        on_run_stake_batch: TGAS * 80,
+                        on_deposit_and_stake: TGAS * 5,
+                        on_unstake: TGAS * 5,
+                        unlock: TGAS * 4,
+                        on_run_redeem_stake_batch: TGAS * 85,
+                        on_redeeming_stake_pending_withdrawal: TGAS * 85,
+                        on_redeeming_stake_post_withdrawal: TGAS * 5,
+                        resolve_transfer_gas: TGAS * 12,
+                        on_refresh_stake_token_value: TGAS * 15,
+                    },
+                    function_call_promise: TGAS * 6,
+                    function_call_promise_data_dependency: TGAS * 2,
+                },
+                contract_owner_earnings_percentage: 40,
+            },
+            config_change_block_height: domain::BlockHeight(5),
+            account_storage_usage: domain::StorageUsage(100),
+            total_account_storage_escrow: (2 * YOCTO).into(),
+            accounts_key_prefix: ACCOUNTS_KEY_PREFIX.to_vec(),
+            accounts_len: 2,
+            total_near: domain::TimestampedNearBalance::new(YOCTO.into()),
+            total_stake: domain::TimestampedStakeBalance::new((100 * YOCTO).into()),
+            near_liquidity_pool: YOCTO.into(),
+            stake_token_value: ctx.stake_token_value,
+            batch_id_sequence: batch_id,
+            stake_batch: Some(domain::StakeBatch::new(batch_id, YOCTO.into())),
+            next_stake_batch: None,
+            redeem_stake_batch: Some(domain::RedeemStakeBatch::new(
+                domain::BatchId(2),
+                YOCTO.into(),
+            )),
+            next_redeem_stake_batch: None,
+            stake_batch_receipts_key_prefix: STAKE_BATCH_RECEIPTS_KEY_PREFIX.to_vec(),
+            redeem_stake_batch_receipts_key_prefix: REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX.to_vec(),
+            staking_pool_id: ctx.staking_pool_id.clone(),
+            stake_batch_lock: None,
+            redeem_stake_batch_lock: None,
+        });
+
+        let contract = Contract::migrate();
+        assert_eq!(Contract::persisted_state_version(), CURRENT_STATE_VERSION);
+        assert!(get_logs()[0].starts_with("StateMigrated"));
+        assert_eq!(contract.owner_id, ctx.owner_id);
+        assert_eq!(contract.operator_id, ctx.operator_id);
+        assert_eq!(contract.staking_pool_id, ctx.staking_pool_id);
+        assert_eq!(contract.contract_owner_balance, (10 * YOCTO).into());
+        assert_eq!(
+            contract.contract_initial_storage_usage,
+            domain::StorageUsage(1000)
+        );
+        assert_eq!(contract.collected_earnings, YOCTO.into());
+        assert_eq!(
+            contract.config.storage_cost_per_byte(),
+            domain::YoctoNear(1000)
+        );
+        assert_eq!(contract.config.contract_owner_earnings_percentage(), 40);
+        // the gas config is carried over - gas config that was introduced after state version 1
+        // is initialized to the defaults
+        let gas_config = contract.config.gas_config();
+        assert_eq!(gas_config.staking_pool().deposit_and_stake(), TGAS * 60);
+        assert_eq!(gas_config.staking_pool().unstake(), TGAS * 55);
+        assert_eq!(gas_config.staking_pool().get_account(), TGAS * 6);
+        assert_eq!(
+            gas_config.staking_pool().get_reward_fee_fraction(),
+            GasConfig::default()
+                .staking_pool()
+                .get_reward_fee_fraction()
+        );
+        assert_eq!(gas_config.callbacks().on_run_stake_batch(), TGAS * 80);
+        assert_eq!(gas_config.callbacks().resolve_transfer_gas(), TGAS * 12);
+        assert_eq!(
+            gas_config.callbacks().on_wrap_near_withdraw(),
+            GasConfig::default().callbacks().on_wrap_near_withdraw()
+        );
+        assert_eq!(gas_config.function_call_promise(), TGAS * 6);
+        assert_eq!(gas_config.function_call_promise_data_dependency(), TGAS * 2);
+        assert_eq!(contract.total_account_storage_escrow, (2 * YOCTO).into());
+        assert_eq!(contract.accounts_len, 2);
+        assert_eq!(contract.total_stake.amount(), (100 * YOCTO).into());
+        assert_eq!(contract.batch_id_sequence, batch_id);
+        assert_eq!(contract.stake_batch.unwrap().id(), batch_id);
+        // legacy redeem stake batch IDs are tagged with the batch kind
+        let redeem_stake_batch_id = contract.redeem_stake_batch.unwrap().id();
+        assert_eq!(redeem_stake_batch_id.kind(), BatchKind::Redeem);
+        assert_eq!(redeem_stake_batch_id.sequence(), 2);
+        // the batch receipt migrations are started, which blocks claims until they are run
+        for task in MigrationTask::ALL.iter() {
+            let migration = contract.migrations.get(task).unwrap();
+            assert_eq!(migration.end(), batch_id.value() as u64 + 1);
+            assert!(!migration.is_complete());
+        }
+        // account storage usage is re-measured against the current account layout
+        assert_eq!(contract.account_storage_usage, account_storage_usage);
+    }
+}
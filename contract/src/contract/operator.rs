@@ -1,12 +1,47 @@
 //required in order for near_bindgen macro to work outside of lib.rs
+use crate::contract::StakingPoolAccount;
+use crate::errors::account_management::REGISTRATION_POOL_DEPOSIT_REQUIRED;
+use crate::errors::operator::{
+    CONFIG_VALIDATION_HASH_MISMATCH, CONTRACT_ALREADY_PAUSED, CONTRACT_NOT_PAUSED,
+    INVALID_WORKFLOW_STATE, NO_STALE_LOCK, PAUSE_REASON_REQUIRED, PAUSE_REASON_TOO_LONG,
+    UNKNOWN_STAKING_POOL_METHOD, WORKFLOW_STATE_BATCH_ID_SEQUENCE_BEHIND,
+    WORKFLOW_STATE_IMPORT_REQUIRES_FRESH_DEPLOYMENT, WORKFLOW_STATE_IMPORT_REQUIRES_IDLE_WORKFLOWS,
+};
+use crate::errors::staking_errors::WORKFLOW_BACKOFF_COOLDOWN;
+use crate::errors::staking_service::REDEEM_FORWARDING_TO_SELF;
+use crate::errors::swap_intents::SWAP_DEPLOYMENT_MUST_NOT_BE_SELF;
+use crate::interface::account_management::events::RegistrationPoolFunded;
+use crate::interface::operator::events::{
+    AggregateAlarm, ContractPaused, ContractResumed, StakingPoolGasAutoTuned,
+    StaleLockFundsInFlight, StaleLockReleased, WorkflowBackoffReset, WorkflowFailed,
+    WorkflowStateImported,
+};
 use crate::interface::ContractFinancials;
+use crate::near::{log, versioned_lookup_map, NO_DEPOSIT, YOCTO};
 use crate::*;
 use crate::{
-    domain::RedeemLock,
-    interface::{contract_state::ContractState, AccountManagement},
+    core::Hash,
+    domain::{
+        ContractPause, LockKind, RedeemLock, Workflow, WorkflowState, LOCK_HISTORY_MAX_LEN,
+        MAX_PAUSE_REASON_LEN, STAKING_POOL_METHODS, WORKFLOW_CALLBACKS,
+    },
+    interface::{
+        contract_state::{
+            ContractState, StateBalances, StateBatches, StateConfig, StateLocks,
+            WorkflowStateExport,
+        },
+        AccountManagement, BatchRunLock, BatchRunLockStatus, BatchRunStatus, BatchRunStep,
+        ConfigValidation, ContractVersion, Health, InitConfigPreset, LockHistoryEntry, MetricValue,
+        StakingPoolGasUsage, WorkflowBackoffStatus,
+    },
     interface::{Operator, StakingService},
 };
-use near_sdk::near_bindgen;
+use near_sdk::{
+    borsh::{BorshDeserialize, BorshSerialize},
+    ext_contract,
+    json_types::Base64VecU8,
+    near_bindgen, Promise,
+};
 
 #[near_bindgen]
 impl Operator for Contract {
@@ -15,14 +50,32 @@ impl Operator for Contract {
     }
 
     fn contract_state(&self) -> ContractState {
-        ContractState {
+        ContractState::from_sections(
+            self.state_balances(),
+            self.state_batches(),
+            self.state_config(),
+            self.state_locks(),
+        )
+    }
+
+    fn state_balances(&self) -> StateBalances {
+        StateBalances {
             block: domain::BlockTimeHeight::from_env().into(),
-            config_change_block_height: self.config_change_block_height.into(),
-            staking_pool_id: self.staking_pool_id.clone(),
             registered_accounts_count: self.total_registered_accounts().clone(),
             total_unstaked_near: self.total_near.into(),
             total_stake_supply: self.total_stake.into(),
             stake_token_value: self.stake_token_value.into(),
+            balances: self.balances(),
+            initial_storage_usage: self.contract_initial_storage_usage.into(),
+            storage_usage_growth: (env::storage_usage()
+                - self.contract_initial_storage_usage.value())
+            .into(),
+        }
+    }
+
+    fn state_batches(&self) -> StateBatches {
+        StateBatches {
+            block: domain::BlockTimeHeight::from_env().into(),
             batch_id_sequence: self.batch_id_sequence.into(),
             stake_batch: self.stake_batch.map(interface::StakeBatch::from),
             next_stake_batch: self.next_stake_batch.map(interface::StakeBatch::from),
@@ -38,38 +91,98 @@ impl Operator for Contract {
                     self.redeem_stake_batch_receipt(batch.id().into()),
                 )
             }),
+        }
+    }
+
+    fn state_config(&self) -> StateConfig {
+        StateConfig {
+            block: domain::BlockTimeHeight::from_env().into(),
+            config_change_block_height: self.config_change_block_height.into(),
+            staking_pool_id: self.staking_pool_id.clone(),
+            config: self.config.clone().into(),
+        }
+    }
+
+    fn state_locks(&self) -> StateLocks {
+        StateLocks {
+            block: domain::BlockTimeHeight::from_env().into(),
             stake_batch_lock: self.stake_batch_lock.map(Into::into),
             redeem_stake_batch_lock: self.redeem_stake_batch_lock,
-            balances: self.balances(),
-            initial_storage_usage: self.contract_initial_storage_usage.into(),
-            storage_usage_growth: (env::storage_usage()
-                - self.contract_initial_storage_usage.value())
-            .into(),
+            pause: self.pause.clone().map(Into::into),
         }
     }
 
     fn config(&self) -> interface::Config {
-        self.config.into()
+        self.config.clone().into()
     }
 
-    fn reset_config_default(&mut self) -> interface::Config {
-        self.assert_predecessor_is_operator();
-        self.config = Config::default();
-        self.config.into()
+    fn init_config_preset(&self) -> Vec<InitConfigPreset> {
+        ConfigPreset::ALL
+            .iter()
+            .map(|preset| InitConfigPreset {
+                preset: *preset,
+                config: Config::preset(*preset).into(),
+            })
+            .collect()
     }
 
-    fn update_config(&mut self, config: interface::Config) -> interface::Config {
-        self.assert_predecessor_is_operator();
-        self.config.merge(config);
+    fn reset_config_default(&mut self, validation_hash: Base64VecU8) -> interface::Config {
+        self.assert_predecessor_can_update_config();
+        self.assert_config_validation_hash(&Config::default().into(), &validation_hash);
+        self.config = Config::default();
         self.config_change_block_height = env::block_index().into();
-        self.config.into()
+        self.config.clone().into()
+    }
+
+    fn validate_config(&self, config: interface::Config) -> ConfigValidation {
+        let findings = self.config.validate(&config);
+        let validation_hash = if findings.is_empty() {
+            Some(Base64VecU8(
+                self.config_validation_hash(&config).as_bytes().to_vec(),
+            ))
+        } else {
+            None
+        };
+        ConfigValidation {
+            findings,
+            validation_hash,
+        }
+    }
+
+    fn update_config(
+        &mut self,
+        config: interface::Config,
+        validation_hash: Base64VecU8,
+    ) -> interface::Config {
+        self.assert_predecessor_can_update_config();
+        self.assert_config_validation_hash(&config, &validation_hash);
+        self.apply_config_change(config, true);
+        self.config.clone().into()
     }
 
     fn force_update_config(&mut self, config: interface::Config) -> interface::Config {
-        self.assert_predecessor_is_operator();
-        self.config.force_merge(config);
-        self.config_change_block_height = env::block_index().into();
-        self.config.into()
+        self.assert_predecessor_can_update_config();
+        self.apply_config_change(config, false);
+        self.config.clone().into()
+    }
+
+    fn gas_config(&self) -> interface::GasConfig {
+        self.config.gas_config().into()
+    }
+
+    fn update_gas_config(
+        &mut self,
+        gas_config: interface::GasConfig,
+        validation_hash: Base64VecU8,
+    ) -> interface::GasConfig {
+        self.assert_predecessor_can_update_config();
+        let config = interface::Config {
+            gas_config: Some(gas_config),
+            ..Default::default()
+        };
+        self.assert_config_validation_hash(&config, &validation_hash);
+        self.apply_config_change(config, true);
+        self.config.gas_config().into()
     }
 
     fn clear_stake_lock(&mut self) {
@@ -77,13 +190,28 @@ impl Operator for Contract {
 
         // we only want to release the stake batch lock if the batch funds have not transferred over
         // to the staking pool
+        let invoked_by_self = env::predecessor_account_id() == env::current_account_id();
         let unlock = match self.stake_batch_lock {
-            Some(StakeLock::Staking) => true,
+            Some(StakeLock::Staking) => {
+                // when invoked as the final step of the stake batch workflow, the lock will still be
+                // in the `Staking` state only if the staking pool side of the workflow failed, e.g.,
+                // the staking pool is paused and rejected the `deposit_and_stake` request
+                if invoked_by_self {
+                    self.mark_staking_pool_unavailable();
+                    self.workflow_counters.stake_batch_workflow_failures += 1;
+                    self.record_workflow_failure(Workflow::Stake);
+                }
+                true
+            }
             Some(StakeLock::RefreshingStakeTokenValue) => true,
             _ => false,
         };
         if unlock {
+            if !invoked_by_self {
+                self.workflow_counters.locks_forced += 1;
+            }
             self.stake_batch_lock = None;
+            self.record_lock_released(true, "clear_stake_lock");
         }
     }
 
@@ -91,90 +219,2053 @@ impl Operator for Contract {
         self.assert_predecessor_is_self_or_operator();
 
         if let Some(RedeemLock::Unstaking) = self.redeem_stake_batch_lock {
-            self.redeem_stake_batch_lock = None
+            // when invoked as the final step of the redeem stake batch workflow, the lock will still
+            // be in the `Unstaking` state only if the staking pool side of the workflow failed
+            if env::predecessor_account_id() == env::current_account_id() {
+                self.workflow_counters.redeem_stake_batch_workflow_failures += 1;
+                self.record_workflow_failure(Workflow::Unstake);
+            } else {
+                self.workflow_counters.locks_forced += 1;
+            }
+            self.redeem_stake_batch_lock = None;
+            self.record_lock_released(false, "clear_redeem_lock");
+        }
+    }
+
+    fn release_stale_lock(&mut self) -> Promise {
+        let lock = self.stale_lock().expect(NO_STALE_LOCK);
+        self.staking_pool_promise().get_account().promise().then(
+            ext_stale_lock_callbacks::on_release_stale_lock(
+                lock,
+                &env::current_account_id(),
+                NO_DEPOSIT.value(),
+                self.config
+                    .gas_config()
+                    .callbacks()
+                    .on_release_stale_lock()
+                    .value(),
+            ),
+        )
+    }
+
+    fn pause(&mut self, reason: String) {
+        self.assert_predecessor_can_run_sensitive_operator_action();
+        self.pause_contract(reason);
+    }
+
+    fn resume(&mut self) {
+        self.assert_predecessor_can_run_sensitive_operator_action();
+        self.resume_contract();
+    }
+
+    fn batch_run_status(&self) -> BatchRunStatus {
+        BatchRunStatus {
+            block: domain::BlockTimeHeight::from_env().into(),
+            stake: self.stake_batch_run_status(),
+            redeem: self.redeem_batch_run_status(),
+        }
+    }
+
+    fn lock_history(&self, limit: u32) -> Vec<LockHistoryEntry> {
+        let len = self.lock_history_len.min(LOCK_HISTORY_MAX_LEN);
+        (0..len.min(limit as u64))
+            .filter_map(|i| {
+                let index = self.lock_history_len - 1 - i;
+                self.lock_history.get(&(index % LOCK_HISTORY_MAX_LEN))
+            })
+            .map(LockHistoryEntry::from)
+            .collect()
+    }
+
+    fn workflow_backoff_status(&self) -> Vec<WorkflowBackoffStatus> {
+        Workflow::ALL
+            .iter()
+            .map(|workflow| self.workflow_backoff_status_for(*workflow))
+            .collect()
+    }
+
+    fn reset_workflow_backoff(&mut self, workflow: Workflow) -> WorkflowBackoffStatus {
+        self.assert_predecessor_is_operator();
+        let backoff = self.workflow_backoffs.get_mut(workflow);
+        log(WorkflowBackoffReset {
+            workflow,
+            consecutive_failures: backoff.consecutive_failures(),
+        });
+        backoff.reset();
+        self.workflow_backoff_status_for(workflow)
+    }
+
+    fn export_workflow_state(&self) -> WorkflowStateExport {
+        let state = self.workflow_state();
+        let data = state.try_to_vec().unwrap();
+        WorkflowStateExport {
+            batches: self.state_batches(),
+            locks: self.state_locks(),
+            unverified_stake_batches: state
+                .unverified_stake_batches
+                .iter()
+                .copied()
+                .map(Into::into)
+                .collect(),
+            pending_receipts: state
+                .stake_batch_receipts
+                .iter()
+                .map(|(batch_id, _)| *batch_id)
+                .chain(
+                    state
+                        .redeem_stake_batch_receipts
+                        .iter()
+                        .map(|(batch_id, _)| *batch_id),
+                )
+                .map(Into::into)
+                .collect(),
+            data: Base64VecU8(data),
+        }
+    }
+
+    fn import_workflow_state(&mut self, data: Base64VecU8) -> WorkflowStateExport {
+        self.assert_predecessor_can_run_sensitive_operator_action();
+        assert!(
+            self.accounts_len == 0 && self.total_stake.amount().value() == 0,
+            WORKFLOW_STATE_IMPORT_REQUIRES_FRESH_DEPLOYMENT
+        );
+        let state = WorkflowState::try_from_slice(&data.0).expect(INVALID_WORKFLOW_STATE);
+        assert!(
+            self.workflow_state().is_idle(),
+            WORKFLOW_STATE_IMPORT_REQUIRES_IDLE_WORKFLOWS
+        );
+        assert!(
+            state.batch_id_sequence.value() >= self.batch_id_sequence.value(),
+            WORKFLOW_STATE_BATCH_ID_SEQUENCE_BEHIND
+        );
+
+        self.batch_id_sequence = state.batch_id_sequence;
+        self.stake_batch = state.stake_batch;
+        self.next_stake_batch = state.next_stake_batch;
+        self.redeem_stake_batch = state.redeem_stake_batch;
+        self.next_redeem_stake_batch = state.next_redeem_stake_batch;
+        self.stake_batch_lock = state.stake_batch_lock;
+        self.redeem_stake_batch_lock = state.redeem_stake_batch_lock;
+        // imported locks are timed from the import for stale lock detection
+        let imported = domain::BlockTimeHeight::from_env();
+        self.stake_batch_lock_acquired = self.stake_batch_lock.map(|_| imported);
+        self.redeem_stake_batch_lock_acquired = self.redeem_stake_batch_lock.map(|_| imported);
+        self.unverified_stake_batches = state.unverified_stake_batches;
+        for (batch_id, receipt) in state.stake_batch_receipts.iter() {
+            self.stake_batch_receipts.insert(batch_id, receipt);
+        }
+        for (batch_id, receipt) in state.redeem_stake_batch_receipts.iter() {
+            self.redeem_stake_batch_receipts.insert(batch_id, receipt);
+        }
+        log(WorkflowStateImported {
+            batch_id_sequence: state.batch_id_sequence.value(),
+            stake_batch_receipts: state.stake_batch_receipts.len(),
+            redeem_stake_batch_receipts: state.redeem_stake_batch_receipts.len(),
+        });
+        self.export_workflow_state()
+    }
+
+    fn release_queued_withdrawal(&mut self, account_id: ValidAccountId) -> interface::YoctoNear {
+        self.assert_predecessor_is_operator();
+        self.release_queued_withdrawal_funds(account_id.as_ref(), None)
+            .into()
+    }
+
+    #[payable]
+    fn fund_registration_pool(&mut self) -> interface::YoctoNear {
+        self.assert_predecessor_is_operator();
+        assert!(
+            env::attached_deposit() > 0,
+            REGISTRATION_POOL_DEPOSIT_REQUIRED
+        );
+
+        let amount: YoctoNear = env::attached_deposit().into();
+        self.registration_pool += amount;
+        log(RegistrationPoolFunded {
+            amount: amount.value(),
+            registration_pool: self.registration_pool.value(),
+        });
+        self.registration_pool.into()
+    }
+
+    fn registration_pool(&self) -> interface::YoctoNear {
+        self.registration_pool.into()
+    }
+
+    fn whitelist_auto_registration(&mut self, account_id: ValidAccountId) {
+        self.assert_predecessor_is_operator();
+        self.auto_registration_whitelist
+            .insert(&Hash::from(account_id));
+    }
+
+    fn remove_auto_registration(&mut self, account_id: ValidAccountId) {
+        self.assert_predecessor_is_operator();
+        self.auto_registration_whitelist
+            .remove(&Hash::from(account_id));
+    }
+
+    fn auto_registration_whitelisted(&self, account_id: ValidAccountId) -> bool {
+        self.auto_registration_whitelist
+            .contains(&Hash::from(account_id))
+    }
+
+    fn whitelist_redeem_forwarding_target(&mut self, account_id: ValidAccountId) {
+        self.assert_predecessor_is_operator();
+        assert_ne!(
+            env::current_account_id().as_str(),
+            account_id.as_ref(),
+            "{}",
+            REDEEM_FORWARDING_TO_SELF
+        );
+        self.redeem_forwarding_targets
+            .insert(&Hash::from(account_id.as_ref()), account_id.as_ref());
+    }
+
+    fn remove_redeem_forwarding_target(&mut self, account_id: ValidAccountId) {
+        self.assert_predecessor_is_operator();
+        self.redeem_forwarding_targets
+            .remove(&Hash::from(account_id));
+    }
+
+    fn redeem_forwarding_target_whitelisted(&self, account_id: ValidAccountId) -> bool {
+        self.redeem_forwarding_targets
+            .contains_key(&Hash::from(account_id))
+    }
+
+    fn register_swap_deployment(&mut self, account_id: ValidAccountId) {
+        self.assert_predecessor_is_operator();
+        assert_ne!(
+            env::current_account_id().as_str(),
+            account_id.as_ref(),
+            "{}",
+            SWAP_DEPLOYMENT_MUST_NOT_BE_SELF
+        );
+        self.swap_deployments
+            .insert(&Hash::from(account_id.as_ref()), account_id.as_ref());
+    }
+
+    fn unregister_swap_deployment(&mut self, account_id: ValidAccountId) {
+        self.assert_predecessor_is_operator();
+        self.swap_deployments.remove(&Hash::from(account_id));
+    }
+
+    fn swap_deployment_registered(&self, account_id: ValidAccountId) -> bool {
+        self.swap_deployments.contains_key(&Hash::from(account_id))
+    }
+
+    fn confirm_stake_value_jump(&mut self) -> interface::StakeTokenValue {
+        self.assert_predecessor_is_operator();
+        self.commit_pending_stake_token_value();
+        self.stake_token_value.into()
+    }
+
+    fn pending_stake_token_value_jump(&self) -> Option<interface::StakeTokenValue> {
+        self.pending_stake_token_value.map(Into::into)
+    }
+
+    fn debug_last_storage_delta(&self, method_name: String) -> Option<interface::StorageDelta> {
+        self.storage_deltas.get(&method_name).map(Into::into)
+    }
+
+    fn metric_values(&self) -> Vec<MetricValue> {
+        let counters = self.workflow_counters;
+        vec![
+            MetricValue::new("stake_batches_run_total", counters.stake_batches_run),
+            MetricValue::new(
+                "redeem_stake_batches_run_total",
+                counters.redeem_stake_batches_run,
+            ),
+            MetricValue::new(
+                "stake_batch_workflow_failures_total",
+                counters.stake_batch_workflow_failures,
+            ),
+            MetricValue::new(
+                "redeem_stake_batch_workflow_failures_total",
+                counters.redeem_stake_batch_workflow_failures,
+            ),
+            MetricValue::new("locks_forced_total", counters.locks_forced),
+            MetricValue::new("receipts_deleted_total", counters.receipts_deleted),
+            MetricValue::new(
+                "stake_minted_total",
+                self.stake_supply_stats.total_stake_minted(),
+            ),
+            MetricValue::new(
+                "stake_burned_total",
+                self.stake_supply_stats.total_stake_burned(),
+            ),
+            MetricValue::new("stake_supply", self.total_stake.amount()),
+            MetricValue::new("near_available", self.total_near.amount()),
+            MetricValue::new(
+                "stake_token_value",
+                self.stake_token_value.stake_to_near(YOCTO.into()),
+            ),
+            MetricValue::new(
+                "stake_token_value_epoch_height",
+                self.stake_token_value
+                    .block_time_height()
+                    .epoch_height()
+                    .value(),
+            ),
+            MetricValue::new("near_liquidity_pool", self.near_liquidity_pool),
+            MetricValue::new("collected_earnings", self.collected_earnings),
+            MetricValue::new("contract_owner_balance", self.contract_owner_balance),
+            MetricValue::new("accounts", self.accounts_len),
+            MetricValue::new("stake_batch_locked", self.stake_batch_lock.is_some() as u8),
+            MetricValue::new(
+                "redeem_stake_batch_locked",
+                self.redeem_stake_batch_lock.is_some() as u8,
+            ),
+            MetricValue::new("storage_usage", env::storage_usage()),
+        ]
+    }
+
+    fn version(&self) -> ContractVersion {
+        Self::contract_version()
+    }
+
+    fn record_staking_pool_gas_usage(
+        &mut self,
+        method_name: String,
+        gas_burnt: interface::Gas,
+    ) -> StakingPoolGasUsage {
+        // auto-tuning is enabled via a config change and is kept within the validated gas bounds,
+        // thus recording samples is not a sensitive operator action
+        self.assert_predecessor_is_operator();
+        assert!(
+            STAKING_POOL_METHODS.contains(&method_name.as_str()),
+            UNKNOWN_STAKING_POOL_METHOD
+        );
+
+        let mut usage = self
+            .staking_pool_gas_usage
+            .get(&method_name)
+            .unwrap_or_default();
+        usage.record(gas_burnt.into(), env::block_index().into());
+        self.staking_pool_gas_usage.insert(&method_name, &usage);
+
+        if let Some(gas) = self
+            .config
+            .auto_tune_staking_pool_gas(&method_name, usage.max_burnt())
+        {
+            log(StakingPoolGasAutoTuned {
+                method_name: &method_name,
+                max_burnt: usage.max_burnt().value(),
+                attached: gas.value(),
+            });
+        }
+
+        self.staking_pool_method_gas_usage(method_name.as_str())
+    }
+
+    fn staking_pool_gas_usage(&self) -> Vec<StakingPoolGasUsage> {
+        STAKING_POOL_METHODS
+            .iter()
+            .map(|method_name| self.staking_pool_method_gas_usage(method_name))
+            .chain(
+                WORKFLOW_CALLBACKS
+                    .iter()
+                    .map(|callback| self.workflow_callback_gas_usage(callback)),
+            )
+            .collect()
+    }
+
+    fn health(&self) -> Health {
+        let block = domain::BlockTimeHeight::from_env();
+        let last_aggregate_alarm = self.aggregate_watch.last_alarm();
+        Health {
+            block: block.into(),
+            healthy: last_aggregate_alarm.map_or(true, |alarm| {
+                alarm.block_time_height().epoch_height() != block.epoch_height()
+            }),
+            last_aggregate_alarm: last_aggregate_alarm.map(Into::into),
+            aggregate_alarms: self.aggregate_watch.alarms_count(),
+            stake_batch_locked: self.stake_batch_lock.is_some(),
+            redeem_stake_batch_locked: self.redeem_stake_batch_lock.is_some(),
         }
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::test_utils::*;
-    use near_sdk::{serde_json, testing_env, MockedBlockchain};
+#[ext_contract(ext_stale_lock_callbacks)]
+pub trait ExtStaleLockCallbacks {
+    fn on_release_stale_lock(
+        &mut self,
+        lock: LockKind,
+        #[callback] staking_pool_account: StakingPoolAccount,
+    ) -> bool;
+}
 
-    #[test]
-    fn release_run_redeem_stake_batch_unstaking_lock_with_unstaking_lock() {
-        let mut context = TestContext::new();
-        let contract = &mut context.contract;
-        let mut context = context.context.clone();
+#[near_bindgen]
+impl Contract {
+    /// releases the stale lock if the staking pool account balances show that no funds are in
+    /// flight - see [release_stale_lock](Operator::release_stale_lock)
+    #[private]
+    pub fn on_release_stale_lock(
+        &mut self,
+        lock: LockKind,
+        #[callback] staking_pool_account: StakingPoolAccount,
+    ) -> bool {
+        // the workflow may have made progress while the staking pool account was being fetched
+        if self.stale_lock() != Some(lock) {
+            return false;
+        }
+        if self.stale_lock_funds_in_flight(lock, &staking_pool_account) {
+            log(StaleLockFundsInFlight { lock });
+            return false;
+        }
 
-        contract.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
-        context.predecessor_account_id = context.current_account_id.clone();
-        testing_env!(context);
-        contract.clear_redeem_lock();
+        let acquired = if lock.is_stake_lock() {
+            self.stake_batch_lock = None;
+            self.stake_batch_lock_acquired
+        } else {
+            self.redeem_stake_batch_lock = None;
+            self.redeem_stake_batch_lock_acquired
+        };
+        self.workflow_counters.locks_forced += 1;
+        self.record_lock_released(lock.is_stake_lock(), "release_stale_lock");
+        log(StaleLockReleased {
+            lock,
+            acquired_epoch_height: acquired.map_or(0, |acquired| acquired.epoch_height().value()),
+        });
+        true
+    }
+}
 
-        assert!(contract.redeem_stake_batch_lock.is_none());
+impl Contract {
+    /// merges in the config changes - the config changes are validated unless forced
+    pub(crate) fn apply_config_change(&mut self, config: interface::Config, validate: bool) {
+        if validate {
+            self.config.merge(config);
+        } else {
+            self.config.force_merge(config);
+        }
+        self.config_change_block_height = env::block_index().into();
     }
 
-    #[test]
-    fn release_run_redeem_stake_batch_unstaking_lock_invoked_by_operator() {
-        let mut context = TestContext::new();
-        let contract = &mut context.contract;
-        let mut context = context.context.clone();
+    /// see [pause](Operator::pause)
+    pub(crate) fn pause_contract(&mut self, reason: String) {
+        assert!(self.pause.is_none(), CONTRACT_ALREADY_PAUSED);
+        let reason = Self::check_pause_reason(reason);
+        log(ContractPaused { reason: &reason });
+        self.pause = Some(ContractPause::new(reason));
+    }
 
-        contract.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
-        context.predecessor_account_id = contract.operator_id.clone();
-        testing_env!(context);
-        contract.clear_redeem_lock();
-        assert!(contract.redeem_stake_batch_lock.is_none());
+    /// see [resume](Operator::resume)
+    pub(crate) fn resume_contract(&mut self) {
+        let pause = self.pause.take().expect(CONTRACT_NOT_PAUSED);
+        log(ContractResumed {
+            reason: pause.reason(),
+            paused_block_height: pause.paused().block_height().value(),
+        });
     }
 
-    #[test]
-    fn release_run_redeem_stake_batch_unstaking_lock_with_pending_withdrawal_lock() {
-        // Arrange
-        let mut context = TestContext::new();
-        let contract = &mut context.contract;
-        let mut context = context.context.clone();
+    /// Returns the trimmed pause reason
+    ///
+    /// ## Panics
+    /// if the reason is blank or too long
+    pub(crate) fn check_pause_reason(reason: String) -> String {
+        let reason = reason.trim().to_string();
+        assert!(!reason.is_empty(), PAUSE_REASON_REQUIRED);
+        assert!(reason.len() <= MAX_PAUSE_REASON_LEN, PAUSE_REASON_TOO_LONG);
+        reason
+    }
 
-        contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
-        context.predecessor_account_id = context.current_account_id.clone();
-        testing_env!(context.clone());
+    /// snapshot of the minimal mutable workflow state - see
+    /// [export_workflow_state](crate::interface::Operator::export_workflow_state)
+    fn workflow_state(&self) -> WorkflowState {
+        let pending_receipts = WorkflowState::pending_receipt_batch_ids(
+            self.stake_batch,
+            self.next_stake_batch,
+            self.redeem_stake_batch,
+            self.next_redeem_stake_batch,
+            &self.unverified_stake_batches,
+        );
+        WorkflowState {
+            batch_id_sequence: self.batch_id_sequence,
+            stake_batch: self.stake_batch,
+            next_stake_batch: self.next_stake_batch,
+            redeem_stake_batch: self.redeem_stake_batch,
+            next_redeem_stake_batch: self.next_redeem_stake_batch,
+            stake_batch_lock: self.stake_batch_lock,
+            redeem_stake_batch_lock: self.redeem_stake_batch_lock,
+            unverified_stake_batches: self.unverified_stake_batches.clone(),
+            stake_batch_receipts: pending_receipts
+                .iter()
+                .filter_map(|batch_id| {
+                    self.stake_batch_receipts
+                        .get(batch_id)
+                        .map(|receipt| (*batch_id, receipt))
+                })
+                .collect(),
+            redeem_stake_batch_receipts: pending_receipts
+                .iter()
+                .filter_map(|batch_id| {
+                    self.redeem_stake_batch_receipts
+                        .get(batch_id)
+                        .map(|receipt| (*batch_id, receipt))
+                })
+                .collect(),
+        }
+    }
 
-        // Act
-        contract.clear_redeem_lock();
+    /// every config change, except for [force_update_config](Operator::force_update_config), must
+    /// have been validated against the current config via [validate_config](Operator::validate_config)
+    ///
+    /// ## Panics
+    /// if the validation hash does not match the config changes
+    pub(crate) fn assert_config_validation_hash(
+        &self,
+        config: &interface::Config,
+        validation_hash: &Base64VecU8,
+    ) {
+        assert!(
+            validation_hash.0.as_slice() == self.config_validation_hash(config).as_bytes(),
+            CONFIG_VALIDATION_HASH_MISMATCH
+        );
+    }
 
-        // Assert
-        assert_eq!(
-            contract.redeem_stake_batch_lock,
-            Some(RedeemLock::PendingWithdrawal)
+    /// binds the proposed config changes to the current config, i.e., the hash is invalidated by any
+    /// other config change
+    fn config_validation_hash(&self, config: &interface::Config) -> Hash {
+        let mut merged = self.config.clone();
+        merged.force_merge(config.clone());
+        let mut bytes = self.config.try_to_vec().unwrap();
+        bytes.extend(merged.try_to_vec().unwrap());
+        Hash::from(bytes.as_slice())
+    }
+
+    fn staking_pool_method_gas_usage(&self, method_name: &str) -> StakingPoolGasUsage {
+        StakingPoolGasUsage {
+            method_name: method_name.to_string(),
+            attached: self
+                .config
+                .gas_config()
+                .staking_pool()
+                .gas(method_name)
+                .unwrap_or_default()
+                .into(),
+            usage: self
+                .staking_pool_gas_usage
+                .get(&method_name.to_string())
+                .map(Into::into),
+        }
+    }
+
+    fn workflow_callback_gas_usage(&self, callback: &str) -> StakingPoolGasUsage {
+        StakingPoolGasUsage {
+            method_name: callback.to_string(),
+            attached: self
+                .config
+                .gas_config()
+                .callbacks()
+                .gas(callback)
+                .unwrap_or_default()
+                .into(),
+            usage: self
+                .staking_pool_gas_usage
+                .get(&callback.to_string())
+                .map(Into::into),
+        }
+    }
+
+    /// records the gas used by the staking pool workflow callback, i.e., `env::used_gas()`, which
+    /// should be invoked when the callback completes
+    /// - see [WORKFLOW_CALLBACKS](crate::domain::WORKFLOW_CALLBACKS)
+    pub(crate) fn record_callback_gas_usage(&mut self, callback: &str) {
+        let callback = callback.to_string();
+        let mut usage = self
+            .staking_pool_gas_usage
+            .get(&callback)
+            .unwrap_or_default();
+        usage.record(env::used_gas().into(), env::block_index().into());
+        self.staking_pool_gas_usage.insert(&callback, &usage);
+    }
+
+    /// records that the lock was acquired in the lock history
+    pub(crate) fn record_lock_acquired(
+        &mut self,
+        kind: LockKind,
+        batch_id: Option<domain::BatchId>,
+    ) {
+        let acquired = Some(domain::BlockTimeHeight::from_env());
+        if kind.is_stake_lock() {
+            self.stake_batch_lock_acquired = acquired;
+        } else {
+            self.redeem_stake_batch_lock_acquired = acquired;
+        }
+        let entry = domain::LockHistoryEntry::new(kind, batch_id, env::block_index().into());
+        self.lock_history
+            .insert(&(self.lock_history_len % LOCK_HISTORY_MAX_LEN), &entry);
+        self.lock_history_len += 1;
+    }
+
+    /// records the start of a workflow attempt
+    /// - a pending attempt that timed out is recorded as a failure
+    ///
+    /// ## Panics
+    /// if the workflow is cooling down after failing
+    pub(crate) fn start_workflow(&mut self, workflow: Workflow) {
+        let now: domain::BlockHeight = env::block_index().into();
+        if self
+            .workflow_backoffs
+            .get_mut(workflow)
+            .expire_pending_attempt(now)
+        {
+            self.log_workflow_failed(workflow);
+        }
+        let backoff = self.workflow_backoffs.get_mut(workflow);
+        if let Some(retry_at) = backoff
+            .retry_at(
+                self.config.workflow_backoff_base_blocks(),
+                self.config.workflow_backoff_max_blocks(),
+            )
+            .filter(|retry_at| now < *retry_at)
+        {
+            panic!(
+                "{}: retry at block {}",
+                WORKFLOW_BACKOFF_COOLDOWN,
+                retry_at.value()
+            );
+        }
+        backoff.start(now);
+    }
+
+    /// returns true if [start_workflow](Contract::start_workflow) would reject the workflow because
+    /// it is cooling down
+    pub(crate) fn is_workflow_cooling_down(&self, workflow: Workflow) -> bool {
+        let now: domain::BlockHeight = env::block_index().into();
+        let mut backoff = *self.workflow_backoffs.get(workflow);
+        backoff.expire_pending_attempt(now);
+        backoff.is_cooling_down(
+            now,
+            self.config.workflow_backoff_base_blocks(),
+            self.config.workflow_backoff_max_blocks(),
+        )
+    }
+
+    pub(crate) fn record_workflow_success(&mut self, workflow: Workflow) {
+        self.workflow_backoffs.get_mut(workflow).record_success();
+    }
+
+    pub(crate) fn record_workflow_failure(&mut self, workflow: Workflow) {
+        self.workflow_backoffs
+            .get_mut(workflow)
+            .record_failure(env::block_index().into());
+        self.log_workflow_failed(workflow);
+    }
+
+    fn log_workflow_failed(&self, workflow: Workflow) {
+        let backoff = self.workflow_backoffs.get(workflow);
+        log(WorkflowFailed {
+            workflow,
+            consecutive_failures: backoff.consecutive_failures(),
+            cooldown_blocks: backoff.cooldown_blocks(
+                self.config.workflow_backoff_base_blocks(),
+                self.config.workflow_backoff_max_blocks(),
+            ),
+        });
+    }
+
+    /// a pending attempt that timed out is reported as failed, i.e., the status reflects the
+    /// backoff that will be applied the next time the workflow is started
+    fn workflow_backoff_status_for(&self, workflow: Workflow) -> WorkflowBackoffStatus {
+        let now: domain::BlockHeight = env::block_index().into();
+        let mut backoff = *self.workflow_backoffs.get(workflow);
+        backoff.expire_pending_attempt(now);
+        WorkflowBackoffStatus::new(
+            workflow,
+            &backoff,
+            self.config.workflow_backoff_base_blocks(),
+            self.config.workflow_backoff_max_blocks(),
+            now,
+        )
+    }
+
+    /// invokes the function and, if [storage_delta_diagnostics](crate::config::Config::storage_delta_diagnostics)
+    /// is enabled, records its storage usage delta under the specified method name
+    pub(crate) fn measure_storage_delta<T>(
+        &mut self,
+        method_name: &str,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        if !self.config.storage_delta_diagnostics() {
+            return f(self);
+        }
+
+        let storage_usage_before = env::storage_usage();
+        let records_touched_before = versioned_lookup_map::records_touched();
+        let result = f(self);
+        let delta = domain::StorageDelta::new(
+            storage_usage_before.into(),
+            env::storage_usage().into(),
+            versioned_lookup_map::records_touched() - records_touched_before,
+            env::block_index().into(),
         );
+        self.storage_deltas.insert(&method_name.to_string(), &delta);
+        result
     }
 
-    #[test]
-    #[should_panic(expected = "contract call is only allowed internally or by an operator account")]
-    fn release_run_redeem_stake_batch_unstaking_lock_access_denied() {
-        // Arrange
-        let mut context = TestContext::new();
-        let contract = &mut context.contract;
+    /// invokes the function and checks the STAKE supply, total NEAR, and NEAR liquidity pool
+    /// changes against the configured aggregate alarm thresholds
+    /// - an [AggregateAlarm](crate::interface::operator::events::AggregateAlarm) event is logged
+    ///   for each threshold that is exceeded
+    pub(crate) fn watch_aggregates<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let txn_threshold = self.config.aggregate_alarm_txn_threshold_percentage();
+        let epoch_threshold = self.config.aggregate_alarm_epoch_threshold_percentage();
+        if txn_threshold == 0 && epoch_threshold == 0 {
+            return f(self);
+        }
 
-        // Act
-        contract.clear_redeem_lock();
+        let before = self.aggregate_snapshot();
+        let result = f(self);
+        let after = self.aggregate_snapshot();
+        for alarm in self.aggregate_watch.check(
+            before,
+            after,
+            txn_threshold,
+            epoch_threshold,
+            domain::BlockTimeHeight::from_env(),
+        ) {
+            log(AggregateAlarm::from(alarm));
+        }
+        result
     }
 
-    #[test]
-    fn contract_state_invoked_by_operator() {
-        // Arrange
-        let mut context = TestContext::new();
-        let contract = &mut context.contract;
-        let mut context = context.context.clone();
+    fn aggregate_snapshot(&self) -> domain::AggregateSnapshot {
+        domain::AggregateSnapshot {
+            stake_supply: self.total_stake.amount().value(),
+            total_near: self.total_near.amount().value(),
+            near_liquidity_pool: self.near_liquidity_pool.value(),
+        }
+    }
 
-        const CONTRACT_STATE_STORAGE_OVERHEAD: u64 = 45;
-        context.storage_usage +=
-            contract.try_to_vec().unwrap().len() as u64 + CONTRACT_STATE_STORAGE_OVERHEAD;
+    /// marks the most recent open lock episode for the specified lock as released
+    /// - `stake_lock` is true for [StakeLock](crate::domain::StakeLock) episodes and false for
+    ///   [RedeemLock](crate::domain::RedeemLock) episodes
+    /// - `method` is the name of the contract method that released the lock
+    pub(crate) fn record_lock_released(&mut self, stake_lock: bool, method: &str) {
+        if stake_lock {
+            self.stake_batch_lock_acquired = None;
+        } else {
+            self.redeem_stake_batch_lock_acquired = None;
+        }
+        if let Some((key, mut entry)) = self.open_lock_history_entry(stake_lock) {
+            entry.release(env::block_index().into(), method);
+            self.lock_history.insert(&key, &entry);
+        }
+    }
 
-        context.predecessor_account_id = contract.operator_id.clone();
-        testing_env!(context.clone());
-        let state = contract.contract_state();
-        println!("{}", serde_json::to_string_pretty(&state).unwrap());
+    /// returns the lock that is stale, i.e., that has been held for at least
+    /// [stale_lock_timeout_epochs](crate::config::Config::stale_lock_timeout_epochs) - the stake
+    /// lock is checked first
+    /// - `Staked` and `PendingWithdrawal` locks are never stale because the funds have already moved,
+    ///   i.e., the workflows are resumed via `stake` and `unstake`
+    /// - locks that were acquired before lock acquisition was tracked are never stale
+    pub(crate) fn stale_lock(&self) -> Option<LockKind> {
+        let timeout_epochs = self.config.stale_lock_timeout_epochs();
+        let is_stale = |acquired: Option<domain::BlockTimeHeight>| {
+            acquired.map_or(false, |acquired| {
+                env::epoch_height()
+                    >= acquired
+                        .epoch_height()
+                        .value()
+                        .saturating_add(timeout_epochs)
+            })
+        };
+        let stake_lock = match self.stake_batch_lock {
+            Some(StakeLock::Staking) => Some(LockKind::Stake),
+            Some(StakeLock::RefreshingStakeTokenValue) => Some(LockKind::RefreshStakeTokenValue),
+            _ => None,
+        };
+        stake_lock
+            .filter(|_| is_stale(self.stake_batch_lock_acquired))
+            .or_else(|| match self.redeem_stake_batch_lock {
+                Some(RedeemLock::Unstaking) if is_stale(self.redeem_stake_batch_lock_acquired) => {
+                    Some(LockKind::Redeem)
+                }
+                _ => None,
+            })
+    }
+
+    /// returns true if the staking pool account balances show that the locked workflow funds were
+    /// transferred to or from the staking pool, i.e., the workflow made it past the staking pool
+    /// step but its callback never fired
+    /// - the staking pool rounds when it converts between NEAR and shares, thus the funds are deemed
+    ///   in flight once the balance moved by more than half of the batch amount
+    /// - errs on the side of caution, e.g., staking rewards that were earned since the STAKE token
+    ///   value was last refreshed count towards the stake batch deposit
+    fn stale_lock_funds_in_flight(
+        &self,
+        lock: LockKind,
+        staking_pool_account: &StakingPoolAccount,
+    ) -> bool {
+        match lock {
+            LockKind::Stake => {
+                let batch_amount = self
+                    .stake_batch
+                    .map_or_else(|| 0.into(), |batch| batch.balance().amount());
+                let staked_balance = self.staked_near_balance(
+                    staking_pool_account.staked_balance.into(),
+                    staking_pool_account.unstaked_balance.into(),
+                );
+                // the cached STAKE token value holds the staked balance from before the batch run
+                let pre_run_staked_balance = self.stake_token_value.total_staked_near_balance();
+                staked_balance.value() > pre_run_staked_balance.value() + batch_amount.value() / 2
+            }
+            // refreshing the STAKE token value does not move any funds
+            LockKind::RefreshStakeTokenValue => false,
+            LockKind::Redeem => {
+                let batch = match self.redeem_stake_batch {
+                    Some(batch) => batch,
+                    None => return false,
+                };
+                // the batch audit is recorded with the staking pool balances right before the
+                // unstake call is issued - if it is missing, then the funds never moved
+                let pre_run_unstaked_balance = match self.batch_audits.get(&batch.id()) {
+                    Some(audit) => audit.unstaked_balance(),
+                    None => return false,
+                };
+                let unstake_amount = self
+                    .stake_token_value
+                    .stake_to_near(batch.balance().amount());
+                staking_pool_account.unstaked_balance.0
+                    > pre_run_unstaked_balance.value() + unstake_amount.value() / 2
+            }
+        }
+    }
+
+    /// returns the most recent lock episode for the specified lock, along with its lock history
+    /// key, if the episode has not yet been released
+    fn open_lock_history_entry(&self, stake_lock: bool) -> Option<(u64, domain::LockHistoryEntry)> {
+        let len = self.lock_history_len.min(LOCK_HISTORY_MAX_LEN);
+        (0..len)
+            .map(|i| (self.lock_history_len - 1 - i) % LOCK_HISTORY_MAX_LEN)
+            .filter_map(|key| self.lock_history.get(&key).map(|entry| (key, entry)))
+            .find(|(_, entry)| entry.kind().is_stake_lock() == stake_lock)
+            .filter(|(_, entry)| !entry.is_released())
+    }
+
+    fn stake_batch_run_status(&self) -> Option<BatchRunLockStatus> {
+        let lock = self.stake_batch_lock?;
+        let (batch_ids, next_step) = match lock {
+            domain::StakeLock::Staking => (
+                self.stake_batch
+                    .iter()
+                    .map(|batch| batch.id().into())
+                    .collect(),
+                BatchRunStep::Callback("on_deposit_and_stake".to_string()),
+            ),
+            domain::StakeLock::Staked { .. } => (
+                self.stake_batch
+                    .iter()
+                    .map(|batch| batch.id().into())
+                    .collect(),
+                BatchRunStep::Callback("process_staked_batch".to_string()),
+            ),
+            // the unverified stake batches are verified when the STAKE token value is refreshed
+            domain::StakeLock::RefreshingStakeTokenValue => (
+                self.unverified_stake_batches
+                    .iter()
+                    .map(|batch_id| (*batch_id).into())
+                    .collect(),
+                BatchRunStep::Callback("on_refresh_stake_token_value".to_string()),
+            ),
+        };
+        Some(BatchRunLockStatus {
+            lock: BatchRunLock::Stake(lock.into()),
+            acquired_block_height: self
+                .stake_batch_lock_acquired
+                .map(|acquired| acquired.block_height().into()),
+            batch_ids,
+            next_step,
+        })
+    }
+
+    fn redeem_batch_run_status(&self) -> Option<BatchRunLockStatus> {
+        let lock = self.redeem_stake_batch_lock?;
+        let next_step = match lock {
+            RedeemLock::Unstaking => BatchRunStep::Callback("on_unstake".to_string()),
+            // the unstaked NEAR is withdrawn once it becomes available for withdrawal
+            RedeemLock::PendingWithdrawal => BatchRunStep::Invoke("unstake".to_string()),
+        };
+        Some(BatchRunLockStatus {
+            lock: BatchRunLock::Redeem(lock),
+            acquired_block_height: self
+                .redeem_stake_batch_lock_acquired
+                .map(|acquired| acquired.block_height().into()),
+            batch_ids: self
+                .redeem_stake_batch
+                .iter()
+                .map(|batch| batch.id().into())
+                .collect(),
+            next_step,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::TGAS;
+    use crate::interface::ExitPositions;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{serde_json, test_utils::get_logs, testing_env, MockedBlockchain};
+
+    #[test]
+    fn init_config_preset() {
+        let context = TestContext::new();
+        let presets = context.init_config_preset();
+        assert_eq!(presets.len(), ConfigPreset::ALL.len());
+        assert_eq!(
+            serde_json::to_value(&presets[0].preset).unwrap(),
+            serde_json::json!("mainnet_conservative")
+        );
+        assert_eq!(
+            serde_json::to_value(&presets[1].preset).unwrap(),
+            serde_json::json!("testnet_fast")
+        );
+        assert_eq!(
+            presets[0].config.max_stake_token_value_increase_percentage,
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn validate_config_dry_run_before_update_config() {
+        let mut ctx = TestContext::new();
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.operator_id.clone();
+        testing_env!(context);
+
+        let config: interface::Config = serde_json::from_str(
+            r#"{
+                "aggregate_alarm_txn_threshold_percentage": 20,
+                "aggregate_alarm_epoch_threshold_percentage": 10,
+                "gas_config": {"callbacks": {"on_run_stake_batch": "200000000000000"}}
+            }"#,
+        )
+        .unwrap();
+        let validation = ctx.validate_config(config);
+        assert!(validation.validation_hash.is_none());
+        let fields: Vec<&str> = validation
+            .findings
+            .iter()
+            .map(|finding| finding.field.as_str())
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                "callbacks::on_run_stake_batch",
+                "aggregate_alarm_txn_threshold_percentage"
+            ]
+        );
+
+        let config: interface::Config = serde_json::from_str(
+            r#"{
+                "aggregate_alarm_txn_threshold_percentage": 10,
+                "aggregate_alarm_epoch_threshold_percentage": 20
+            }"#,
+        )
+        .unwrap();
+        let validation = ctx.validate_config(config.clone());
+        assert!(validation.findings.is_empty());
+        let config = ctx.update_config(config, validation.validation_hash.unwrap());
+        assert_eq!(config.aggregate_alarm_epoch_threshold_percentage, Some(20));
+    }
+
+    #[test]
+    fn workflow_gas_must_fit_within_transaction() {
+        let ctx = TestContext::new();
+        let config: interface::Config = serde_json::from_str(
+            r#"{"gas_config": {
+                "staking_pool": {"get_account": "10000000000000"},
+                "callbacks": {"on_run_stake_batch": "150000000000000"}
+            }}"#,
+        )
+        .unwrap();
+        assert!(ctx.validate_config(config).findings.is_empty());
+
+        let mut config = ctx.config.clone();
+        config.force_merge(
+            serde_json::from_str(r#"{"gas_config": {"callbacks": {"unlock": "300000000000000"}}}"#)
+                .unwrap(),
+        );
+        let findings = config.validate(&interface::Config::default());
+        assert_eq!(findings.len(), 3);
+        assert_eq!(findings[0].field, "stake");
+        assert_eq!(
+            findings[0].message,
+            "stake workflow gas must be <= 300 TGas"
+        );
+    }
+
+    #[test]
+    fn update_gas_config() {
+        let mut ctx = TestContext::new();
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.operator_id.clone();
+        testing_env!(context);
+
+        let unstake = ctx.gas_config().staking_pool.unwrap().unstake.unwrap();
+        let gas_config: interface::GasConfig =
+            serde_json::from_str(r#"{"staking_pool": {"get_account": "10000000000000"}}"#).unwrap();
+        let validation_hash = ctx
+            .validate_config(interface::Config {
+                gas_config: Some(gas_config.clone()),
+                ..Default::default()
+            })
+            .validation_hash
+            .unwrap();
+        let gas_config = ctx.update_gas_config(gas_config, validation_hash);
+        let staking_pool = gas_config.staking_pool.unwrap();
+        assert_eq!(staking_pool.get_account.unwrap(), (TGAS * 10).into());
+        // gas values that were not specified are left unchanged
+        assert_eq!(staking_pool.unstake.unwrap(), unstake);
+        assert_eq!(
+            ctx.config.gas_config().staking_pool().get_account(),
+            TGAS * 10
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "callbacks::unlock must be within 5 - 10 TGas")]
+    fn update_gas_config_out_of_bounds() {
+        let mut ctx = TestContext::new();
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.operator_id.clone();
+        testing_env!(context);
+
+        let gas_config: interface::GasConfig =
+            serde_json::from_str(r#"{"callbacks": {"unlock": "300000000000000"}}"#).unwrap();
+        // the validation hash binds the config changes, but is only returned for valid changes
+        let validation_hash = Base64VecU8(
+            ctx.config_validation_hash(&interface::Config {
+                gas_config: Some(gas_config.clone()),
+                ..Default::default()
+            })
+            .as_bytes()
+            .to_vec(),
+        );
+        ctx.update_gas_config(gas_config, validation_hash);
+    }
+
+    #[test]
+    #[should_panic(expected = "config validation hash does not match")]
+    fn update_gas_config_with_validation_hash_for_other_changes() {
+        let mut ctx = TestContext::new();
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.operator_id.clone();
+        testing_env!(context);
+
+        let validation = ctx
+            .validate_config(serde_json::from_str(r#"{"deposit_cooldown_blocks": 10}"#).unwrap());
+        ctx.update_gas_config(
+            serde_json::from_str(r#"{"staking_pool": {"get_account": "10000000000000"}}"#).unwrap(),
+            validation.validation_hash.unwrap(),
+        );
+    }
+
+    #[test]
+    fn reset_config_default() {
+        let mut ctx = TestContext::new();
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.operator_id.clone();
+        testing_env!(context);
+        ctx.config
+            .force_merge(serde_json::from_str(r#"{"deposit_cooldown_blocks": 10}"#).unwrap());
+
+        let validation_hash = ctx
+            .validate_config(Config::default().into())
+            .validation_hash
+            .unwrap();
+        let config = ctx.reset_config_default(validation_hash);
+        assert_eq!(
+            config.deposit_cooldown_blocks,
+            interface::Config::from(Config::default()).deposit_cooldown_blocks
+        );
+        assert_eq!(
+            ctx.config.try_to_vec().unwrap(),
+            Config::default().try_to_vec().unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "config validation hash does not match")]
+    fn update_config_with_stale_validation_hash() {
+        let mut ctx = TestContext::new();
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.operator_id.clone();
+        testing_env!(context);
+
+        let config: interface::Config =
+            serde_json::from_str(r#"{"deposit_cooldown_blocks": 10}"#).unwrap();
+        let validation = ctx.validate_config(config.clone());
+        // the config is changed after the config was validated
+        ctx.force_update_config(
+            serde_json::from_str(r#"{"redeem_limit_increase_delay_epochs": 10}"#).unwrap(),
+        );
+        ctx.update_config(config, validation.validation_hash.unwrap());
+    }
+
+    #[test]
+    fn release_run_redeem_stake_batch_unstaking_lock_with_unstaking_lock() {
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+        let mut context = context.context.clone();
+
+        contract.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context);
+        contract.clear_redeem_lock();
+
+        assert!(contract.redeem_stake_batch_lock.is_none());
+    }
+
+    #[test]
+    fn release_run_redeem_stake_batch_unstaking_lock_invoked_by_operator() {
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+        let mut context = context.context.clone();
+
+        contract.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+        context.predecessor_account_id = contract.operator_id.clone();
+        testing_env!(context);
+        contract.clear_redeem_lock();
+        assert!(contract.redeem_stake_batch_lock.is_none());
+    }
+
+    #[test]
+    fn release_run_redeem_stake_batch_unstaking_lock_with_pending_withdrawal_lock() {
+        // Arrange
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+        let mut context = context.context.clone();
+
+        contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+
+        // Act
+        contract.clear_redeem_lock();
+
+        // Assert
+        assert_eq!(
+            contract.redeem_stake_batch_lock,
+            Some(RedeemLock::PendingWithdrawal)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "contract call is only allowed internally or by an operator account")]
+    fn release_run_redeem_stake_batch_unstaking_lock_access_denied() {
+        // Arrange
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+
+        // Act
+        contract.clear_redeem_lock();
+    }
+
+    #[test]
+    fn lock_history() {
+        let mut test_ctx = TestContext::new();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+        assert!(contract.lock_history(10).is_empty());
+
+        context.block_index = 100;
+        testing_env!(context.clone());
+        contract.refresh_stake_token_value();
+
+        let history = contract.lock_history(10);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].kind, LockKind::RefreshStakeTokenValue);
+        assert!(history[0].batch_id.is_none());
+        assert_eq!(
+            history[0].acquired_block_height,
+            domain::BlockHeight(100).into()
+        );
+        assert!(history[0].released_block_height.is_none());
+
+        // a stuck lock is cleared by the operator
+        context.block_index = 105;
+        context.predecessor_account_id = contract.operator_id.clone();
+        testing_env!(context.clone());
+        contract.clear_stake_lock();
+
+        let history = contract.lock_history(10);
+        assert_eq!(
+            history[0].released_block_height,
+            Some(domain::BlockHeight(105).into())
+        );
+        assert_eq!(history[0].released_by, Some("clear_stake_lock".to_string()));
+
+        // clearing the redeem lock does not affect stake lock episodes
+        contract.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+        contract.record_lock_acquired(LockKind::Redeem, Some(domain::BatchId(1)));
+        contract.clear_redeem_lock();
+        let history = contract.lock_history(10);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].kind, LockKind::Redeem);
+        assert_eq!(
+            history[0].released_by,
+            Some("clear_redeem_lock".to_string())
+        );
+        assert_eq!(history[1].released_by, Some("clear_stake_lock".to_string()));
+
+        assert_eq!(contract.lock_history(1).len(), 1);
+    }
+
+    #[test]
+    fn batch_run_status() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+        let status = contract.batch_run_status();
+        assert!(status.stake.is_none());
+        assert!(status.redeem.is_none());
+
+        context.attached_deposit = 10 * YOCTO;
+        context.block_index = 100;
+        testing_env!(context.clone());
+        let batch_id = contract.deposit(None);
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        contract.stake();
+
+        let status = contract.batch_run_status().stake.unwrap();
+        assert_eq!(
+            status.lock,
+            BatchRunLock::Stake(domain::StakeLock::Staking.into())
+        );
+        assert_eq!(
+            status.acquired_block_height,
+            Some(domain::BlockHeight(100).into())
+        );
+        assert_eq!(status.batch_ids, vec![batch_id]);
+        assert_eq!(
+            status.next_step,
+            BatchRunStep::Callback("on_deposit_and_stake".to_string())
+        );
+
+        // locks that are not acquired via the workflows are not timed
+        contract.stake_batch_lock = None;
+        contract.record_lock_released(true, "clear_stake_lock");
+        contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+        contract.redeem_stake_batch = Some(domain::RedeemStakeBatch::new(
+            domain::BatchId(2),
+            YOCTO.into(),
+        ));
+        let status = contract.batch_run_status();
+        assert!(status.stake.is_none());
+        let status = status.redeem.unwrap();
+        assert_eq!(
+            status.lock,
+            BatchRunLock::Redeem(RedeemLock::PendingWithdrawal)
+        );
+        assert!(status.acquired_block_height.is_none());
+        assert_eq!(status.batch_ids, vec![domain::BatchId(2).into()]);
+        assert_eq!(
+            status.next_step,
+            BatchRunStep::Invoke("unstake".to_string())
+        );
+    }
+
+    fn staking_pool_account(staked_balance: u128, unstaked_balance: u128) -> StakingPoolAccount {
+        StakingPoolAccount {
+            account_id: TEST_STAKING_POOL_ID.to_string(),
+            unstaked_balance: unstaked_balance.into(),
+            staked_balance: staked_balance.into(),
+            can_withdraw: false,
+        }
+    }
+
+    /// runs a stake batch whose callbacks never fire and then advances the epoch height past the
+    /// stale lock timeout
+    fn stale_stake_lock(test_ctx: &mut TestContext) {
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        test_ctx.contract.deposit(None);
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        test_ctx.contract.stake();
+        assert!(test_ctx.stale_lock().is_none());
+
+        context.epoch_height += test_ctx.config.stale_lock_timeout_epochs();
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        test_ctx.context = context;
+    }
+
+    #[test]
+    fn release_stale_stake_lock() {
+        let mut test_ctx = TestContext::with_registered_account();
+        stale_stake_lock(&mut test_ctx);
+        assert_eq!(test_ctx.stale_lock(), Some(LockKind::Stake));
+        test_ctx.release_stale_lock();
+
+        // the deposit never made it to the staking pool
+        assert!(test_ctx.on_release_stale_lock(LockKind::Stake, staking_pool_account(0, 0)));
+        assert!(test_ctx.stake_batch_lock.is_none());
+        assert!(test_ctx.stake_batch_lock_acquired.is_none());
+        assert!(test_ctx.stake_batch.is_some());
+        assert_eq!(test_ctx.workflow_counters.locks_forced, 1);
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("StaleLockReleased")));
+        assert_eq!(
+            test_ctx.lock_history(1)[0].released_by,
+            Some("release_stale_lock".to_string())
+        );
+        assert!(test_ctx.stale_lock().is_none());
+    }
+
+    #[test]
+    fn stale_stake_lock_with_funds_in_flight_is_not_released() {
+        let mut test_ctx = TestContext::with_registered_account();
+        stale_stake_lock(&mut test_ctx);
+
+        // the deposit was staked, but the callback never fired
+        assert!(
+            !test_ctx.on_release_stale_lock(LockKind::Stake, staking_pool_account(10 * YOCTO, 0))
+        );
+        assert_eq!(test_ctx.stake_batch_lock, Some(StakeLock::Staking));
+        assert!(get_logs()[0].starts_with("StaleLockFundsInFlight"));
+
+        // the staking pool rounded the staked balance down
+        assert!(!test_ctx
+            .on_release_stale_lock(LockKind::Stake, staking_pool_account(10 * YOCTO - 1, 0)));
+        assert_eq!(test_ctx.stake_batch_lock, Some(StakeLock::Staking));
+    }
+
+    /// sets up a redeem batch whose unstake callback never fired and then advances the epoch height
+    /// past the stale lock timeout
+    /// - the staking pool account already held 12 NEAR unstaked before the batch was run, i.e., more than
+    ///   the batch amount
+    fn stale_redeem_lock(test_ctx: &mut TestContext) {
+        let mut context = test_ctx.context.clone();
+        testing_env!(context.clone());
+        let batch = domain::RedeemStakeBatch::new(domain::BatchId(1), (10 * YOCTO).into());
+        test_ctx.redeem_stake_batch = Some(batch);
+        let audit = test_ctx.new_batch_audit((100 * YOCTO).into(), (12 * YOCTO).into());
+        test_ctx.record_batch_audit(batch.id(), audit, 0.into());
+        test_ctx.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+        test_ctx.record_lock_acquired(LockKind::Redeem, Some(batch.id()));
+
+        context.epoch_height += test_ctx.config.stale_lock_timeout_epochs();
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        test_ctx.context = context;
+    }
+
+    #[test]
+    fn stale_redeem_lock_accounts_for_pre_existing_unstaked_balance() {
+        let mut test_ctx = TestContext::new();
+        stale_redeem_lock(&mut test_ctx);
+        assert_eq!(test_ctx.stale_lock(), Some(LockKind::Redeem));
+
+        // the pre-existing unstaked balance alone does not count as funds in flight
+        assert!(test_ctx.on_release_stale_lock(
+            LockKind::Redeem,
+            staking_pool_account(100 * YOCTO, 12 * YOCTO)
+        ));
+        assert!(test_ctx.redeem_stake_batch_lock.is_none());
+    }
+
+    #[test]
+    fn stale_redeem_lock_with_funds_in_flight_is_not_released() {
+        let mut test_ctx = TestContext::new();
+        stale_redeem_lock(&mut test_ctx);
+
+        // the unstaked balance rose by the batch amount, give or take the staking pool rounding
+        assert!(!test_ctx.on_release_stale_lock(
+            LockKind::Redeem,
+            staking_pool_account(90 * YOCTO, 22 * YOCTO - 1)
+        ));
+        assert_eq!(
+            test_ctx.redeem_stake_batch_lock,
+            Some(RedeemLock::Unstaking)
+        );
+    }
+
+    #[test]
+    fn stale_lock_excludes_locks_where_funds_moved() {
+        let mut test_ctx = TestContext::new();
+        let mut context = test_ctx.context.clone();
+        testing_env!(context.clone());
+        test_ctx.record_lock_acquired(LockKind::Redeem, None);
+        test_ctx.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+        test_ctx.record_lock_acquired(LockKind::Stake, None);
+        test_ctx.stake_batch_lock = Some(StakeLock::Staked {
+            near_liquidity: None,
+            staked_balance: YOCTO.into(),
+            unstaked_balance: 0.into(),
+        });
+
+        context.epoch_height += test_ctx.config.stale_lock_timeout_epochs();
+        testing_env!(context);
+        assert!(test_ctx.stale_lock().is_none());
+
+        test_ctx.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+        assert_eq!(test_ctx.stale_lock(), Some(LockKind::Redeem));
+    }
+
+    #[test]
+    fn lock_history_ring_buffer() {
+        let mut test_ctx = TestContext::new();
+        let contract = &mut test_ctx.contract;
+
+        for i in 0..(LOCK_HISTORY_MAX_LEN + 10) {
+            contract.record_lock_acquired(LockKind::Stake, Some(domain::BatchId(i as u128)));
+            contract.record_lock_released(true, "process_staked_batch");
+        }
+
+        let history = contract.lock_history(u32::MAX);
+        assert_eq!(history.len() as u64, LOCK_HISTORY_MAX_LEN);
+        // most recent first
+        assert_eq!(
+            history[0].batch_id,
+            Some(domain::BatchId((LOCK_HISTORY_MAX_LEN + 9) as u128).into())
+        );
+        assert_eq!(
+            history.last().unwrap().batch_id,
+            Some(domain::BatchId(10).into())
+        );
+    }
+
+    #[test]
+    fn clear_stake_lock_invoked_by_operator_with_staking_lock() {
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+        let mut context = context.context.clone();
+
+        contract.stake_batch_lock = Some(StakeLock::Staking);
+        context.predecessor_account_id = contract.operator_id.clone();
+        testing_env!(context);
+        contract.clear_stake_lock();
+
+        assert!(contract.stake_batch_lock.is_none());
+        // only stake batch workflow failures mark the staking pool unavailable
+        assert!(contract.staking_pool_unavailable_until.is_none());
+    }
+
+    #[test]
+    fn timed_out_workflow_attempt_backs_off_retries() {
+        let mut test_ctx = TestContext::new();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+        contract
+            .config
+            .merge(serde_json::from_str(r#"{"workflow_backoff_base_blocks": 100}"#).unwrap());
+        let status = |contract: &Contract| {
+            contract
+                .workflow_backoff_status()
+                .into_iter()
+                .find(|status| status.workflow == Workflow::RefreshStakeTokenValue)
+                .unwrap()
+        };
+
+        context.block_index = 100;
+        testing_env!(context.clone());
+        contract.refresh_stake_token_value();
+        assert_eq!(
+            status(contract).pending_since,
+            Some(domain::BlockHeight(100).into())
+        );
+
+        // the callback never ran and the stuck lock is cleared by the operator
+        context.predecessor_account_id = contract.operator_id.clone();
+        context.block_index = 100 + domain::WORKFLOW_ATTEMPT_TIMEOUT_BLOCKS;
+        testing_env!(context.clone());
+        contract.clear_stake_lock();
+        let backoff = status(contract);
+        assert_eq!(backoff.consecutive_failures, 1);
+        assert_eq!(backoff.cooldown_blocks, 100.into());
+        assert_eq!(backoff.retry_at, Some(domain::BlockHeight(200).into()));
+
+        // once the cooldown has passed, the workflow can be retried
+        context.block_index = 200;
+        testing_env!(context.clone());
+        contract.refresh_stake_token_value();
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("WorkflowFailed")));
+        let backoff = status(contract);
+        assert_eq!(backoff.consecutive_failures, 1);
+        assert!(backoff.retry_at.is_none());
+
+        let backoff = contract.reset_workflow_backoff(Workflow::RefreshStakeTokenValue);
+        assert_eq!(backoff.consecutive_failures, 0);
+        assert_eq!(backoff.cooldown_blocks, 0.into());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "workflow failed and cannot be retried until the backoff cooldown period has passed: retry at block 200"
+    )]
+    fn timed_out_workflow_attempt_retried_during_cooldown() {
+        let mut test_ctx = TestContext::new();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+        contract
+            .config
+            .merge(serde_json::from_str(r#"{"workflow_backoff_base_blocks": 100}"#).unwrap());
+
+        context.block_index = 100;
+        testing_env!(context.clone());
+        contract.refresh_stake_token_value();
+
+        context.predecessor_account_id = contract.operator_id.clone();
+        context.block_index = 199;
+        testing_env!(context.clone());
+        contract.clear_stake_lock();
+        contract.refresh_stake_token_value();
+    }
+
+    #[test]
+    fn stake_batch_workflow_failure_is_recorded() {
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+        let mut context = context.context.clone();
+
+        contract.stake_batch_lock = Some(StakeLock::Staking);
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context);
+        contract.clear_stake_lock();
+
+        let status = contract.workflow_backoff_status();
+        assert_eq!(status[0].workflow, Workflow::Stake);
+        assert_eq!(status[0].consecutive_failures, 1);
+        // the backoff is disabled by default
+        assert!(status[0].retry_at.is_none());
+        assert!(status[1..]
+            .iter()
+            .all(|status| status.consecutive_failures == 0));
+    }
+
+    #[test]
+    fn metric_values_track_workflow_counters() {
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+        let mut context = context.context.clone();
+
+        let metric = |contract: &Contract, name: &str| {
+            contract
+                .metric_values()
+                .into_iter()
+                .find(|metric| metric.0 == name)
+                .map(|metric| metric.1 .0)
+                .unwrap()
+        };
+        assert_eq!(metric(contract, "locks_forced_total"), 0);
+        assert_eq!(metric(contract, "stake_batch_locked"), 0);
+        assert_eq!(metric(contract, "stake_token_value"), YOCTO);
+
+        // stake batch workflow callback failure
+        contract.stake_batch_lock = Some(StakeLock::Staking);
+        assert_eq!(metric(contract, "stake_batch_locked"), 1);
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        contract.clear_stake_lock();
+        assert_eq!(metric(contract, "stake_batch_workflow_failures_total"), 1);
+        assert_eq!(metric(contract, "locks_forced_total"), 0);
+
+        // stuck locks forcibly cleared by the operator
+        contract.stake_batch_lock = Some(StakeLock::Staking);
+        contract.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+        context.predecessor_account_id = contract.operator_id.clone();
+        testing_env!(context.clone());
+        contract.clear_stake_lock();
+        contract.clear_redeem_lock();
+        assert_eq!(metric(contract, "stake_batch_workflow_failures_total"), 1);
+        assert_eq!(
+            metric(contract, "redeem_stake_batch_workflow_failures_total"),
+            0
+        );
+        assert_eq!(metric(contract, "locks_forced_total"), 2);
+        assert_eq!(metric(contract, "stake_batch_locked"), 0);
+        assert_eq!(metric(contract, "redeem_stake_batch_locked"), 0);
+    }
+
+    #[test]
+    fn version() {
+        let context = TestContext::new();
+        let version = context.contract.version();
+        assert_eq!(version.crate_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(version.state_version, domain::CURRENT_STATE_VERSION.value());
+        assert_eq!(version.state_schema_hash.len(), 16);
+        assert!(!version.git_commit.is_empty());
+    }
+
+    #[test]
+    fn contract_state_invoked_by_operator() {
+        // Arrange
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+        let mut context = context.context.clone();
+
+        const CONTRACT_STATE_STORAGE_OVERHEAD: u64 = 45;
+        context.storage_usage +=
+            contract.try_to_vec().unwrap().len() as u64 + CONTRACT_STATE_STORAGE_OVERHEAD;
+
+        context.predecessor_account_id = contract.operator_id.clone();
+        testing_env!(context.clone());
+        let state = contract.contract_state();
+        println!("{}", serde_json::to_string_pretty(&state).unwrap());
+    }
+
+    #[test]
+    fn state_sections_match_contract_state() {
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+        let mut context = context.context.clone();
+
+        const CONTRACT_STATE_STORAGE_OVERHEAD: u64 = 45;
+        context.storage_usage +=
+            contract.try_to_vec().unwrap().len() as u64 + CONTRACT_STATE_STORAGE_OVERHEAD;
+        context.is_view = true;
+        testing_env!(context.clone());
+
+        let state = contract.contract_state();
+        let balances = contract.state_balances();
+        let batches = contract.state_batches();
+        let config = contract.state_config();
+        let locks = contract.state_locks();
+
+        assert_eq!(
+            balances.registered_accounts_count,
+            state.registered_accounts_count
+        );
+        assert_eq!(balances.storage_usage_growth, state.storage_usage_growth);
+        assert_eq!(batches.batch_id_sequence, state.batch_id_sequence);
+        assert!(batches.stake_batch.is_none());
+        assert_eq!(config.staking_pool_id, state.staking_pool_id);
+        assert_eq!(
+            config.config_change_block_height,
+            state.config_change_block_height
+        );
+        assert!(locks.stake_batch_lock.is_none());
+        assert!(locks.redeem_stake_batch_lock.is_none());
+    }
+
+    #[test]
+    fn aggregate_alarm_is_reported_by_health() {
+        let mut ctx = TestContext::new();
+        ctx.total_near.credit((10 * YOCTO).into());
+        ctx.near_liquidity_pool = (10 * YOCTO).into();
+
+        // alarms are disabled by default
+        ctx.watch_aggregates(|contract| contract.total_near.credit((5 * YOCTO).into()));
+        assert!(ctx.health().healthy);
+        assert_eq!(ctx.health().aggregate_alarms, 0);
+
+        ctx.config.merge(
+            serde_json::from_str(
+                r#"{"aggregate_alarm_txn_threshold_percentage": 25, "aggregate_alarm_epoch_threshold_percentage": 50}"#,
+            )
+            .unwrap(),
+        );
+        // within the thresholds
+        ctx.watch_aggregates(|contract| contract.total_near.credit((3 * YOCTO).into()));
+        assert!(ctx.health().healthy);
+
+        ctx.watch_aggregates(|contract| {
+            contract.near_liquidity_pool -= domain::YoctoNear(5 * YOCTO);
+        });
+        let health = ctx.health();
+        assert!(!health.healthy);
+        assert_eq!(health.aggregate_alarms, 1);
+        let alarm = health.last_aggregate_alarm.unwrap();
+        assert_eq!(alarm.aggregate, domain::Aggregate::NearLiquidityPool);
+        assert_eq!(alarm.window, domain::AlarmWindow::Transaction);
+        assert_eq!(alarm.before.0, 10 * YOCTO);
+        assert_eq!(alarm.after.0, 5 * YOCTO);
+        assert_eq!(alarm.threshold_percentage, 25);
+        assert!(get_logs().iter().any(|log| log.contains("AggregateAlarm")));
+
+        // the contract is reported healthy again in the next epoch
+        let mut context = ctx.context.clone();
+        context.epoch_height += 1;
+        testing_env!(context);
+        let health = ctx.health();
+        assert!(health.healthy);
+        assert_eq!(health.aggregate_alarms, 1);
+    }
+
+    #[test]
+    fn debug_last_storage_delta() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = ctx.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+
+        // diagnostics are disabled by default
+        ctx.deposit(None);
+        assert!(ctx
+            .debug_last_storage_delta("deposit".to_string())
+            .is_none());
+
+        ctx.config
+            .merge(serde_json::from_str(r#"{"storage_delta_diagnostics": true}"#).unwrap());
+        ctx.deposit(None);
+        let delta = ctx.debug_last_storage_delta("deposit".to_string()).unwrap();
+        // the account record is written
+        assert_eq!(delta.records_touched, 1);
+        assert_eq!(
+            delta.delta,
+            delta.storage_usage_after.0 .0 as i64 - delta.storage_usage_before.0 .0 as i64
+        );
+        assert_eq!(delta.block_height.0 .0, context.block_index);
+        assert!(ctx.debug_last_storage_delta("redeem".to_string()).is_none());
+    }
+
+    #[test]
+    fn record_staking_pool_gas_usage() {
+        let mut ctx = TestContext::new();
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.contract.operator_id.clone();
+        testing_env!(context);
+
+        let usage = ctx
+            .contract
+            .record_staking_pool_gas_usage("deposit_and_stake".to_string(), (TGAS * 20).into());
+        assert_eq!(usage.attached, (TGAS * 45).into());
+        let gas_usage = usage.usage.unwrap();
+        assert_eq!(gas_usage.samples, 1.into());
+        assert_eq!(gas_usage.max_burnt, (TGAS * 20).into());
+        // auto-tuning is disabled by default
+        assert!(get_logs().is_empty());
+
+        ctx.contract
+            .config
+            .merge(serde_json::from_str(r#"{"staking_pool_gas_auto_tune": true}"#).unwrap());
+        let usage = ctx
+            .contract
+            .record_staking_pool_gas_usage("deposit_and_stake".to_string(), (TGAS * 10).into());
+        // nudged down toward 20 TGas + 25% headroom, but not below the min
+        assert_eq!(usage.attached, (TGAS * 40).into());
+        assert_eq!(usage.usage.unwrap().samples, 2.into());
+        assert!(get_logs()[0].starts_with("StakingPoolGasAutoTuned"));
+
+        let gas_usage = ctx.contract.staking_pool_gas_usage();
+        assert_eq!(
+            gas_usage.len(),
+            STAKING_POOL_METHODS.len() + WORKFLOW_CALLBACKS.len()
+        );
+        assert!(gas_usage
+            .iter()
+            .filter(|usage| usage.method_name != "deposit_and_stake")
+            .all(|usage| usage.usage.is_none()));
+    }
+
+    #[test]
+    #[should_panic(expected = "contract call is only allowed by an operator account")]
+    fn record_staking_pool_gas_usage_not_invoked_by_operator() {
+        let mut ctx = TestContext::new();
+        ctx.contract.governance_id = Some("governance.near".to_string());
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = "governance.near".to_string();
+        testing_env!(context);
+
+        ctx.contract
+            .record_staking_pool_gas_usage("deposit_and_stake".to_string(), (TGAS * 20).into());
+    }
+
+    #[test]
+    fn record_callback_gas_usage() {
+        let mut ctx = TestContext::new();
+        let mut context = ctx.context.clone();
+        context.prepaid_gas = 50 * TGAS.value();
+        testing_env!(context);
+
+        ctx.contract.record_callback_gas_usage("on_unstake");
+        ctx.contract.record_callback_gas_usage("on_unstake");
+
+        let gas_usage = ctx.contract.staking_pool_gas_usage();
+        let on_unstake = gas_usage
+            .iter()
+            .find(|usage| usage.method_name == "on_unstake")
+            .unwrap();
+        assert_eq!(
+            on_unstake.attached,
+            ctx.contract
+                .config
+                .gas_config()
+                .callbacks()
+                .on_unstake()
+                .into()
+        );
+        assert_eq!(on_unstake.usage.as_ref().unwrap().samples, 2.into());
+        // callback samples do not touch the staking pool gas config
+        assert_eq!(
+            ctx.contract.config.gas_config().staking_pool().unstake(),
+            crate::config::GasConfig::default().staking_pool().unstake()
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "method is not a staking pool method that is invoked by the contract"
+    )]
+    fn record_staking_pool_gas_usage_for_unknown_method() {
+        let mut ctx = TestContext::new();
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.contract.operator_id.clone();
+        testing_env!(context);
+
+        ctx.contract
+            .record_staking_pool_gas_usage("ft_transfer".to_string(), TGAS.into());
+    }
+
+    /// sets up workflow state with an open stake batch, a redeem batch pending withdrawal, and an
+    /// unverified stake batch receipt
+    fn setup_workflow_state(ctx: &mut TestContext) {
+        let staked_batch_id = domain::BatchId(1);
+        ctx.contract.stake_batch_receipts.insert(
+            &staked_batch_id,
+            &domain::StakeBatchReceipt::new(
+                (10 * YOCTO).into(),
+                ctx.contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
+        );
+        ctx.contract.unverified_stake_batches.push(staked_batch_id);
+
+        let redeem_batch = domain::RedeemStakeBatch::new(domain::BatchId(2), (5 * YOCTO).into());
+        ctx.contract.redeem_stake_batch_receipts.insert(
+            &redeem_batch.id(),
+            &redeem_batch.create_receipt(
+                ctx.contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
+        );
+        ctx.contract.redeem_stake_batch = Some(redeem_batch);
+        ctx.contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+
+        ctx.contract.stake_batch = Some(domain::StakeBatch::new(
+            domain::BatchId(3),
+            (20 * YOCTO).into(),
+        ));
+        ctx.contract.batch_id_sequence = domain::BatchId(3);
+    }
+
+    /// simulates a fresh deployment by clearing the workflow state
+    fn clear_workflow_state(ctx: &mut TestContext) {
+        ctx.contract
+            .stake_batch_receipts
+            .remove(&domain::BatchId(1));
+        ctx.contract
+            .redeem_stake_batch_receipts
+            .remove(&domain::BatchId(2));
+        ctx.contract.unverified_stake_batches.clear();
+        ctx.contract.redeem_stake_batch = None;
+        ctx.contract.redeem_stake_batch_lock = None;
+        ctx.contract.stake_batch = None;
+        ctx.contract.batch_id_sequence = domain::BatchId(0);
+    }
+
+    fn set_predecessor_to_operator(ctx: &mut TestContext) {
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.contract.operator_id.clone();
+        testing_env!(context);
+    }
+
+    #[test]
+    fn export_and_import_workflow_state() {
+        let mut ctx = TestContext::new();
+        setup_workflow_state(&mut ctx);
+
+        let export = ctx.contract.export_workflow_state();
+        assert_eq!(export.batches.batch_id_sequence, domain::BatchId(3).into());
+        assert_eq!(
+            export.locks.redeem_stake_batch_lock,
+            Some(RedeemLock::PendingWithdrawal)
+        );
+        assert_eq!(
+            export.unverified_stake_batches,
+            vec![domain::BatchId(1).into()]
+        );
+        assert_eq!(
+            export.pending_receipts,
+            vec![domain::BatchId(1).into(), domain::BatchId(2).into()]
+        );
+
+        clear_workflow_state(&mut ctx);
+        set_predecessor_to_operator(&mut ctx);
+        let imported = ctx.contract.import_workflow_state(export.data.clone());
+        assert_eq!(imported.data.0, export.data.0);
+        assert!(get_logs()[0].starts_with("WorkflowStateImported"));
+
+        assert_eq!(ctx.contract.batch_id_sequence, domain::BatchId(3));
+        assert_eq!(
+            ctx.contract.stake_batch.unwrap().balance().amount(),
+            (20 * YOCTO).into()
+        );
+        assert_eq!(
+            ctx.contract.redeem_stake_batch_lock,
+            Some(RedeemLock::PendingWithdrawal)
+        );
+        assert_eq!(
+            ctx.contract.unverified_stake_batches,
+            vec![domain::BatchId(1)]
+        );
+        assert!(ctx
+            .contract
+            .stake_batch_receipts
+            .get(&domain::BatchId(1))
+            .is_some());
+        assert!(ctx
+            .contract
+            .redeem_stake_batch_receipts
+            .get(&domain::BatchId(2))
+            .is_some());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "workflow state can only be imported while no batches are open and no workflow locks are held"
+    )]
+    fn import_workflow_state_while_workflows_are_not_idle() {
+        let mut ctx = TestContext::new();
+        setup_workflow_state(&mut ctx);
+        let export = ctx.contract.export_workflow_state();
+
+        set_predecessor_to_operator(&mut ctx);
+        ctx.contract.import_workflow_state(export.data);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "imported batch ID sequence must not be behind the current batch ID sequence"
+    )]
+    fn import_workflow_state_with_batch_id_sequence_behind() {
+        let mut ctx = TestContext::new();
+        setup_workflow_state(&mut ctx);
+        let export = ctx.contract.export_workflow_state();
+        clear_workflow_state(&mut ctx);
+        ctx.contract.batch_id_sequence = domain::BatchId(10);
+
+        set_predecessor_to_operator(&mut ctx);
+        ctx.contract.import_workflow_state(export.data);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "workflow state can only be imported into a fresh deployment, i.e., no accounts are registered and no STAKE has been issued"
+    )]
+    fn import_workflow_state_into_deployment_with_registered_accounts() {
+        let mut ctx = TestContext::with_registered_account();
+        setup_workflow_state(&mut ctx);
+        let export = ctx.contract.export_workflow_state();
+        clear_workflow_state(&mut ctx);
+
+        set_predecessor_to_operator(&mut ctx);
+        ctx.contract.import_workflow_state(export.data);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "operator action requires multiple operator confirmations - it must be proposed"
+    )]
+    fn import_workflow_state_requires_operator_confirmations() {
+        let mut ctx = TestContext::new();
+        setup_workflow_state(&mut ctx);
+        let export = ctx.contract.export_workflow_state();
+        clear_workflow_state(&mut ctx);
+        ctx.contract.operator_required_confirmations = 2;
+
+        set_predecessor_to_operator(&mut ctx);
+        ctx.contract.import_workflow_state(export.data);
+    }
+
+    #[test]
+    fn pause_and_resume() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        set_predecessor_to_operator(&mut ctx);
+        ctx.contract.pause(" staking pool incident ".to_string());
+        assert!(get_logs()[0].starts_with("ContractPaused"));
+
+        // view methods remain live
+        let pause = ctx.contract.contract_state().pause.unwrap();
+        assert_eq!(pause.reason, "staking pool incident");
+        assert_eq!(ctx.contract.state_locks().pause, Some(pause));
+
+        ctx.contract.resume();
+        assert!(get_logs().last().unwrap().starts_with("ContractResumed"));
+        assert!(ctx.contract.contract_state().pause.is_none());
+
+        let mut context = ctx.set_predecessor_account_id(account_id);
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        ctx.contract.deposit(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "contract is paused: staking pool incident")]
+    fn deposit_while_paused() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        set_predecessor_to_operator(&mut ctx);
+        ctx.contract.pause("staking pool incident".to_string());
+
+        let mut context = ctx.set_predecessor_account_id(account_id);
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        ctx.contract.deposit(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "contract is paused: staking pool incident")]
+    fn withdraw_while_paused() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        set_predecessor_to_operator(&mut ctx);
+        ctx.contract.pause("staking pool incident".to_string());
+
+        let context = ctx.set_predecessor_account_id(account_id);
+        testing_env!(context);
+        ctx.contract.withdraw_all();
+    }
+
+    #[test]
+    #[should_panic(expected = "contract is paused: staking pool incident")]
+    fn process_exit_while_paused() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        set_predecessor_to_operator(&mut ctx);
+        ctx.contract.pause("staking pool incident".to_string());
+
+        let context = ctx.set_predecessor_account_id(account_id);
+        testing_env!(context);
+        ctx.contract.process_exit(to_valid_account_id(account_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "contract is paused: staking pool incident")]
+    fn claim_receipts_while_paused() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        set_predecessor_to_operator(&mut ctx);
+        ctx.contract.pause("staking pool incident".to_string());
+
+        let context = ctx.set_predecessor_account_id(account_id);
+        testing_env!(context);
+        ctx.contract.claim_receipts();
+    }
+
+    #[test]
+    #[should_panic(expected = "contract is paused: staking pool incident")]
+    fn deposit_wrap_near_while_paused() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = ctx.account_id;
+        set_predecessor_to_operator(&mut ctx);
+        ctx.contract.pause("staking pool incident".to_string());
+
+        let context = ctx.set_predecessor_account_id("wrap.near");
+        testing_env!(context);
+        ctx.contract
+            .deposit_wrap_near(to_valid_account_id(account_id), (10 * YOCTO).into());
     }
 }
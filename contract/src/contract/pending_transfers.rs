@@ -0,0 +1,205 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::errors::pending_transfers::{
+    PENDING_TRANSFER_ACCESS_DENIED, PENDING_TRANSFER_NOT_FOUND,
+    STALLED_TRANSFER_REFUND_REQUIRES_OPERATOR, TRANSFER_NOT_STALLED,
+};
+use crate::interface::pending_transfers::events::StalledTransferResolved;
+use crate::interface::{PendingTransfer, PendingTransfers, TokenAmount};
+use crate::near::log;
+use crate::*;
+use near_sdk::{json_types::U64, near_bindgen};
+
+#[near_bindgen]
+impl PendingTransfers for Contract {
+    fn ft_transfer_call_status(&self, transfer_id: U64) -> Option<PendingTransfer> {
+        self.pending_transfers.get(&transfer_id.0).map(|transfer| {
+            let stalled = self.is_transfer_stalled(&transfer);
+            PendingTransfer::new(transfer_id.0, transfer, stalled)
+        })
+    }
+
+    fn resolve_stalled_transfer(&mut self, transfer_id: U64, refund: bool) -> TokenAmount {
+        let transfer = self
+            .pending_transfers
+            .get(&transfer_id.0)
+            .expect(PENDING_TRANSFER_NOT_FOUND);
+        let predecessor_account_id = env::predecessor_account_id();
+        let is_operator = self.is_operator(&predecessor_account_id);
+        assert!(
+            is_operator || predecessor_account_id == transfer.sender_id(),
+            PENDING_TRANSFER_ACCESS_DENIED
+        );
+        assert!(self.is_transfer_stalled(&transfer), TRANSFER_NOT_STALLED);
+        assert!(
+            !refund || is_operator,
+            STALLED_TRANSFER_REFUND_REQUIRES_OPERATOR
+        );
+
+        self.pending_transfers.remove(&transfer_id.0);
+        let refund_amount = if refund {
+            self.refund_transfer(
+                transfer.sender_id(),
+                transfer.receiver_id(),
+                transfer.amount().value().into(),
+            )
+        } else {
+            0.into()
+        };
+
+        log(StalledTransferResolved {
+            transfer_id: transfer_id.0,
+            sender_id: transfer.sender_id(),
+            receiver_id: transfer.receiver_id(),
+            amount: transfer.amount().value(),
+            refund: refund_amount.value(),
+            resolved_by: &predecessor_account_id,
+        });
+        refund_amount
+    }
+}
+
+impl Contract {
+    /// registers the transfer call as a pending transfer
+    ///
+    /// Returns the transfer ID, which is passed to the `ft_resolve_transfer_call` callback
+    pub(crate) fn register_pending_transfer(
+        &mut self,
+        sender_id: &str,
+        receiver_id: &str,
+        amount: domain::YoctoStake,
+    ) -> u64 {
+        self.pending_transfer_id_sequence += 1;
+        let transfer_id = self.pending_transfer_id_sequence;
+        self.pending_transfers.insert(
+            &transfer_id,
+            &domain::PendingTransfer::new(
+                sender_id,
+                receiver_id,
+                amount,
+                domain::BlockTimeHeight::from_env(),
+            ),
+        );
+        transfer_id
+    }
+
+    fn is_transfer_stalled(&self, transfer: &domain::PendingTransfer) -> bool {
+        transfer.is_stalled(
+            env::block_index().into(),
+            self.config.stalled_transfer_timeout_blocks(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::{FungibleToken, ResolveTransferCall};
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain, PromiseOrValue, PromiseResult};
+
+    const RECEIVER_ID: &str = "receiver.near";
+
+    /// transfers 10 STAKE from the test account to the receiver via `ft_transfer_call`
+    fn transfer_call(test_ctx: &mut TestContext) -> U64 {
+        let sender_id = test_ctx.account_id;
+        test_ctx.register_account(RECEIVER_ID);
+        test_ctx.credit_stake(sender_id, 100 * YOCTO);
+
+        let mut context = test_ctx.set_predecessor_account_id(sender_id);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_ctx.ft_transfer_call(
+            to_valid_account_id(RECEIVER_ID),
+            (10 * YOCTO).into(),
+            "pay".into(),
+            None,
+            None,
+        );
+        test_ctx.pending_transfer_id_sequence.into()
+    }
+
+    fn advance_past_timeout(test_ctx: &mut TestContext, predecessor_account_id: &str) {
+        let timeout_blocks = test_ctx.config.stalled_transfer_timeout_blocks();
+        test_ctx.context.block_index += timeout_blocks;
+        let context = test_ctx.set_predecessor_account_id(predecessor_account_id);
+        testing_env!(context);
+    }
+
+    fn promise_result_failed(_result_index: u64) -> PromiseResult {
+        PromiseResult::Failed
+    }
+
+    #[test]
+    fn transfer_call_status_becomes_stalled_after_timeout() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let transfer_id = transfer_call(&mut test_ctx);
+
+        let status = test_ctx.ft_transfer_call_status(transfer_id).unwrap();
+        assert_eq!(status.sender_id, test_ctx.account_id);
+        assert_eq!(status.receiver_id, RECEIVER_ID);
+        assert_eq!(status.amount, (10 * YOCTO).into());
+        assert!(!status.stalled);
+
+        let account_id = test_ctx.account_id;
+        advance_past_timeout(&mut test_ctx, account_id);
+        assert!(
+            test_ctx
+                .ft_transfer_call_status(transfer_id)
+                .unwrap()
+                .stalled
+        );
+    }
+
+    #[test]
+    fn sender_finalizes_stalled_transfer() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let transfer_id = transfer_call(&mut test_ctx);
+        let account_id = test_ctx.account_id;
+        advance_past_timeout(&mut test_ctx, account_id);
+
+        let refund = test_ctx.resolve_stalled_transfer(transfer_id, false);
+        assert_eq!(refund, 0.into());
+        assert!(test_ctx.ft_transfer_call_status(transfer_id).is_none());
+        assert_eq!(
+            test_ctx.ft_balance_of(to_valid_account_id(RECEIVER_ID)),
+            (10 * YOCTO).into()
+        );
+    }
+
+    #[test]
+    fn operator_refunds_stalled_transfer_and_late_callback_is_noop() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let transfer_id = transfer_call(&mut test_ctx);
+        advance_past_timeout(&mut test_ctx, TEST_OPERATOR_ID);
+
+        let refund = test_ctx.resolve_stalled_transfer(transfer_id, true);
+        assert_eq!(refund, (10 * YOCTO).into());
+        assert_eq!(
+            test_ctx.ft_balance_of(to_valid_account_id(test_ctx.account_id)),
+            (100 * YOCTO).into()
+        );
+        assert_eq!(
+            test_ctx.ft_balance_of(to_valid_account_id(RECEIVER_ID)),
+            0.into()
+        );
+
+        // the late callback must not refund the transfer a second time
+        set_env_with_promise_result(&mut test_ctx, promise_result_failed);
+        let sender_id = test_ctx.account_id;
+        let result = test_ctx.ft_resolve_transfer_call(
+            to_valid_account_id(sender_id),
+            to_valid_account_id(RECEIVER_ID),
+            (10 * YOCTO).into(),
+            Some(transfer_id),
+        );
+        match result {
+            PromiseOrValue::Value(refund) => assert_eq!(refund, 0.into()),
+            _ => panic!("expected value to be returned"),
+        }
+        assert_eq!(
+            test_ctx.ft_balance_of(to_valid_account_id(test_ctx.account_id)),
+            (100 * YOCTO).into()
+        );
+    }
+}
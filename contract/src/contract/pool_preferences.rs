@@ -0,0 +1,154 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::core::Hash;
+use crate::errors::pool_preferences::POOL_PREFERENCE_REQUIRES_STAKE;
+use crate::interface::pool_preferences::events::PoolPreferenceSignaled;
+use crate::interface::{EpochHeight, PoolPreference, PoolPreferenceTally, PoolPreferences};
+use crate::near::log;
+use crate::*;
+use near_sdk::{json_types::ValidAccountId, near_bindgen};
+
+#[near_bindgen]
+impl PoolPreferences for Contract {
+    fn signal_pool_preference(&mut self, pool_id: ValidAccountId) -> PoolPreference {
+        let mut account = self.predecessor_registered_account();
+        self.claim_receipt_funds(&mut account);
+        let weight = account.stake.map_or(0.into(), |balance| balance.amount());
+        assert!(weight.value() > 0, POOL_PREFERENCE_REQUIRES_STAKE);
+
+        let epoch_height: domain::EpochHeight = env::epoch_height().into();
+        let mut tallies = self
+            .pool_preference_tallies
+            .get(&epoch_height)
+            .unwrap_or_default();
+        // the account's prior signal within the same epoch is replaced
+        if let Some(preference) = account.pool_preference {
+            if preference.epoch_height == epoch_height {
+                tallies.remove(preference.pool_id, preference.weight);
+            }
+        }
+        tallies.add(pool_id.as_ref(), weight);
+        self.pool_preference_tallies.insert(&epoch_height, &tallies);
+
+        account.pool_preference = Some(domain::PoolPreference {
+            pool_id: Hash::from(pool_id.as_ref()),
+            epoch_height,
+            weight,
+        });
+        self.bill_account_storage_growth(&mut account);
+        self.save_registered_account(&account);
+
+        log(PoolPreferenceSignaled {
+            account_id: &env::predecessor_account_id(),
+            pool_id: pool_id.as_ref(),
+            epoch_height: epoch_height.value(),
+            weight: weight.value(),
+        });
+        PoolPreference {
+            pool_id: pool_id.into(),
+            epoch_height: epoch_height.into(),
+            weight: weight.into(),
+        }
+    }
+
+    fn account_pool_preference(&self, account_id: ValidAccountId) -> Option<PoolPreference> {
+        let preference = self
+            .accounts
+            .get(&Hash::from(account_id))?
+            .pool_preference?;
+        let tallies = self.pool_preference_tallies.get(&preference.epoch_height)?;
+        tallies
+            .pool_id(preference.pool_id)
+            .map(|pool_id| PoolPreference {
+                pool_id: pool_id.to_string(),
+                epoch_height: preference.epoch_height.into(),
+                weight: preference.weight.into(),
+            })
+    }
+
+    fn pool_preference_tallies(
+        &self,
+        epoch_height: Option<EpochHeight>,
+    ) -> Vec<PoolPreferenceTally> {
+        let epoch_height = epoch_height.map_or_else(
+            || env::epoch_height().into(),
+            |epoch_height| domain::EpochHeight(epoch_height.0 .0),
+        );
+        self.pool_preference_tallies
+            .get(&epoch_height)
+            .map_or_else(Vec::new, |tallies| {
+                tallies
+                    .tallies()
+                    .iter()
+                    .map(PoolPreferenceTally::from)
+                    .collect()
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn signal_pool_preference() {
+        let mut ctx = TestContext::with_registered_account();
+        testing_env!(ctx.context.clone());
+        let account_id = ValidAccountId::try_from(ctx.account_id).unwrap();
+        ctx.credit_stake(account_id.as_ref(), 10 * YOCTO);
+        ctx.register_account("bob.near");
+        ctx.credit_stake("bob.near", 5 * YOCTO);
+        testing_env!(ctx.context.clone());
+
+        let preference = ctx.signal_pool_preference(to_valid_account_id("pool-1.near"));
+        assert_eq!(preference.pool_id, "pool-1.near");
+        assert_eq!(preference.weight, (10 * YOCTO).into());
+        assert_eq!(
+            ctx.account_pool_preference(account_id.clone()),
+            Some(preference)
+        );
+
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = "bob.near".to_string();
+        testing_env!(context.clone());
+        ctx.signal_pool_preference(to_valid_account_id("pool-1.near"));
+
+        let tallies = ctx.pool_preference_tallies(None);
+        assert_eq!(tallies.len(), 1);
+        assert_eq!(tallies[0].weight, (15 * YOCTO).into());
+        assert_eq!(tallies[0].accounts, 2);
+
+        // signaling again within the same epoch replaces the prior signal
+        ctx.signal_pool_preference(to_valid_account_id("pool-2.near"));
+        let tallies = ctx.pool_preference_tallies(None);
+        assert_eq!(tallies.len(), 2);
+        assert_eq!(tallies[0].weight, (10 * YOCTO).into());
+        assert_eq!(tallies[0].accounts, 1);
+        assert_eq!(tallies[1].pool_id, "pool-2.near");
+        assert_eq!(tallies[1].weight, (5 * YOCTO).into());
+
+        // signals are tallied per epoch
+        let epoch_height = EpochHeight(context.epoch_height.into());
+        context.epoch_height += 1;
+        testing_env!(context);
+        assert!(ctx.pool_preference_tallies(None).is_empty());
+        assert_eq!(ctx.pool_preference_tallies(Some(epoch_height)).len(), 2);
+        assert_eq!(
+            ctx.account_pool_preference(account_id).unwrap().pool_id,
+            "pool-1.near"
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "account must hold STAKE in order to signal a staking pool preference"
+    )]
+    fn signal_pool_preference_with_zero_stake() {
+        let mut ctx = TestContext::with_registered_account();
+        testing_env!(ctx.context.clone());
+        ctx.signal_pool_preference(to_valid_account_id("pool-1.near"));
+    }
+}
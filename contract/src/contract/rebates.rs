@@ -0,0 +1,254 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::domain::RebateAccumulator;
+use crate::errors::rebates::{REBATE_POOL_DEPOSIT_REQUIRED, REBATE_ROUND_TOO_SHORT};
+use crate::interface::rebates::events::{RebateClaimed, RebatePoolFunded, RebateRoundClosed};
+use crate::interface::{AccountRebate, FeeRebates, RebateProgram, RebateRound, RebateTier};
+use crate::near::log;
+use crate::*;
+use near_sdk::{json_types::ValidAccountId, near_bindgen};
+
+#[near_bindgen]
+impl FeeRebates for Contract {
+    #[payable]
+    fn fund_rebate_pool(&mut self) -> interface::YoctoNear {
+        self.assert_predecessor_is_owner();
+        assert!(env::attached_deposit() > 0, REBATE_POOL_DEPOSIT_REQUIRED);
+
+        self.rebate_program.fund(env::attached_deposit().into());
+        log(RebatePoolFunded {
+            amount: env::attached_deposit(),
+            pool: self.rebate_program.pool().value(),
+        });
+        self.rebate_program.pool().into()
+    }
+
+    fn set_rebate_tiers(&mut self, tiers: Vec<RebateTier>) {
+        self.assert_predecessor_is_owner();
+        self.rebate_program
+            .set_tiers(tiers.into_iter().map(Into::into).collect());
+    }
+
+    fn close_rebate_round(&mut self) -> RebateRound {
+        self.assert_predecessor_is_owner();
+        let epoch_height: domain::EpochHeight = env::epoch_height().into();
+        assert!(
+            epoch_height > self.rebate_program.round_start_epoch_height(),
+            REBATE_ROUND_TOO_SHORT
+        );
+
+        // rebates for the prior round can no longer be claimed
+        let expired = self
+            .claimable_rebate_round()
+            .map_or(0.into(), |round| round.unclaimed());
+        self.rebate_program.fund(expired);
+
+        let round = self
+            .rebate_program
+            .close_round(self.total_stake.amount(), epoch_height);
+        self.rebate_rounds.insert(&round.id(), &round);
+        log(RebateRoundClosed {
+            round_id: round.id(),
+            amount: round.amount().value(),
+            expired: expired.value(),
+        });
+        round.into()
+    }
+
+    fn claim_rebate(&mut self) -> interface::YoctoNear {
+        self.assert_not_paused();
+        let mut account = self.predecessor_registered_account();
+        let closed_round = account
+            .rebate_accumulator
+            .and_then(|accumulator| accumulator.claimable_round(&self.rebate_program));
+        let rebate = match (closed_round, self.claimable_rebate_round()) {
+            (Some(closed_round), Some(mut round)) => {
+                let rebate = round.compute_rebate(closed_round.stake_epochs);
+                round.record_claim(rebate);
+                self.rebate_rounds.insert(&round.id(), &round);
+                if let Some(accumulator) = account.rebate_accumulator.as_mut() {
+                    accumulator.clear_closed_round();
+                }
+                if rebate.value() > 0 {
+                    account.apply_near_credit(rebate);
+                    self.total_near.credit(rebate);
+                    log(RebateClaimed {
+                        account_id: &env::predecessor_account_id(),
+                        round_id: round.id(),
+                        amount: rebate.value(),
+                    });
+                }
+                rebate
+            }
+            _ => 0.into(),
+        };
+        self.save_registered_account(&account);
+        rebate.into()
+    }
+
+    fn rebate_program(&self) -> RebateProgram {
+        (&self.rebate_program).into()
+    }
+
+    fn rebate_round(&self, round_id: u64) -> Option<RebateRound> {
+        self.rebate_rounds.get(&round_id).map(Into::into)
+    }
+
+    fn account_rebate(&self, account_id: ValidAccountId) -> Option<AccountRebate> {
+        let account = self.lookup_registered_account(account_id.as_ref())?;
+        let closed_round = account
+            .rebate_accumulator?
+            .claimable_round(&self.rebate_program)?;
+        let round = self.claimable_rebate_round()?;
+        Some(AccountRebate {
+            round_id: round.id(),
+            average_stake: round.average_stake(closed_round.stake_epochs).into(),
+            tier: round.tier(closed_round.stake_epochs).map(Into::into),
+            rebate: round.compute_rebate(closed_round.stake_epochs).into(),
+        })
+    }
+}
+
+impl Contract {
+    /// returns the most recently closed rebate round, which is the only round that rebates can be
+    /// claimed for
+    fn claimable_rebate_round(&self) -> Option<domain::RebateRound> {
+        self.rebate_program
+            .round_id()
+            .checked_sub(1)
+            .and_then(|round_id| self.rebate_rounds.get(&round_id))
+    }
+
+    /// NEAR funds that are reserved for the rebate program, i.e., the rebate pool plus unclaimed
+    /// rebates for the most recently closed round
+    pub(crate) fn rebate_funds(&self) -> domain::YoctoNear {
+        self.rebate_program.pool()
+            + self
+                .claimable_rebate_round()
+                .map_or(0.into(), |round| round.unclaimed())
+    }
+
+    /// brings the account's rebate accumulator up to date using the account's current STAKE balance
+    pub(crate) fn accumulate_account_rebate(&self, mut account: Account) -> Account {
+        let epoch_height: domain::EpochHeight = env::epoch_height().into();
+        let balance = account.stake.map_or(0.into(), |balance| balance.amount());
+        let program = &self.rebate_program;
+        account
+            .rebate_accumulator
+            .get_or_insert_with(|| RebateAccumulator::new(program, epoch_height))
+            .accumulate(balance, program, epoch_height);
+        account
+    }
+
+    /// must be invoked before the total STAKE supply changes
+    pub(crate) fn accumulate_total_stake_rebate(&mut self) {
+        self.rebate_program
+            .accumulate(self.total_stake.amount(), env::epoch_height().into());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{serde_json, testing_env, MockedBlockchain, VMContext};
+    use std::convert::TryFrom;
+
+    /// owner funds the rebate pool and the registered account holds all STAKE
+    fn setup(ctx: &mut TestContext) -> VMContext {
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = ctx.owner_id.clone();
+        context.attached_deposit = 100 * YOCTO;
+        testing_env!(context.clone());
+        ctx.fund_rebate_pool();
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        ctx.set_rebate_tiers(
+            serde_json::from_str(&format!(
+                r#"[{{"min_average_stake": "{}", "rebate_percentage": 100}}]"#,
+                YOCTO
+            ))
+            .unwrap(),
+        );
+
+        let mut account = ctx.registered_account(ctx.account_id);
+        account.apply_stake_credit((10 * YOCTO).into());
+        ctx.save_registered_account(&account);
+        ctx.accumulate_total_stake_rebate();
+        ctx.total_stake.credit((10 * YOCTO).into());
+        context
+    }
+
+    #[test]
+    fn fund_close_and_claim_rebate() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = setup(&mut ctx);
+        let account_id = ValidAccountId::try_from(ctx.account_id).unwrap();
+        assert_eq!(ctx.rebate_program().pool, (100 * YOCTO).into());
+        assert_eq!(ctx.rebate_funds(), (100 * YOCTO).into());
+
+        context.epoch_height += 2;
+        testing_env!(context.clone());
+        let round = ctx.close_rebate_round();
+        assert_eq!(round.id, 0);
+        assert_eq!(round.amount, (100 * YOCTO).into());
+        assert_eq!(ctx.rebate_program().round_id, 1);
+        assert_eq!(ctx.rebate_program().pool, 0.into());
+        // the funds are reserved for the round until they are claimed
+        assert_eq!(ctx.rebate_funds(), (100 * YOCTO).into());
+
+        let account_rebate = ctx.account_rebate(account_id.clone()).unwrap();
+        assert_eq!(account_rebate.round_id, 0);
+        assert_eq!(account_rebate.average_stake, (10 * YOCTO).into());
+        assert_eq!(account_rebate.rebate, (100 * YOCTO).into());
+
+        context.predecessor_account_id = ctx.account_id.to_string();
+        testing_env!(context.clone());
+        assert_eq!(ctx.claim_rebate(), (100 * YOCTO).into());
+        let account = ctx.registered_account(ctx.account_id);
+        assert_eq!(account.near.unwrap().amount(), (100 * YOCTO).into());
+        assert_eq!(ctx.total_near.amount(), (100 * YOCTO).into());
+        assert_eq!(ctx.rebate_funds(), 0.into());
+        assert_eq!(ctx.rebate_round(0).unwrap().claimed, (100 * YOCTO).into());
+
+        // rebate can only be claimed once
+        assert!(ctx.account_rebate(account_id).is_none());
+        assert_eq!(ctx.claim_rebate(), 0.into());
+    }
+
+    #[test]
+    fn unclaimed_rebates_are_returned_to_pool() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = setup(&mut ctx);
+
+        context.epoch_height += 2;
+        testing_env!(context.clone());
+        ctx.close_rebate_round();
+
+        context.epoch_height += 1;
+        testing_env!(context.clone());
+        let round = ctx.close_rebate_round();
+        assert_eq!(round.id, 1);
+        assert_eq!(round.amount, (100 * YOCTO).into());
+        assert_eq!(round.total_stake_epochs, (10 * YOCTO).into());
+        assert_eq!(ctx.rebate_funds(), (100 * YOCTO).into());
+    }
+
+    #[test]
+    #[should_panic(expected = "rebate round must span at least 1 epoch")]
+    fn close_rebate_round_within_same_epoch() {
+        let mut ctx = TestContext::with_registered_account();
+        setup(&mut ctx);
+        ctx.close_rebate_round();
+    }
+
+    #[test]
+    #[should_panic(expected = "contract call is only allowed by the contract owner")]
+    fn fund_rebate_pool_not_owner() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = ctx.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        ctx.fund_rebate_pool();
+    }
+}
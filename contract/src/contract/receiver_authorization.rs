@@ -0,0 +1,311 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::core::Hash;
+use crate::domain::{self, MAX_AUTHORIZED_RECEIVERS, MAX_DELEGATE_KEYS};
+use crate::errors::receiver_authorization::{
+    DELEGATE_KEY_NOT_PERMITTED, MAX_AUTHORIZED_RECEIVERS_REACHED, MAX_DELEGATE_KEYS_REACHED,
+    RECEIVER_AUTHORIZATION_STORAGE_FEE_REQUIRED, RECEIVER_NOT_AUTHORIZED,
+};
+use crate::interface::receiver_authorization::events::{
+    DelegateKeyUpdated, ReceiverAuthorizationUpdated,
+};
+use crate::interface::{ReceiverAuthorization, ReceiverAuthorizations};
+use crate::near::log;
+use crate::*;
+use near_sdk::{
+    env,
+    json_types::{Base58PublicKey, ValidAccountId},
+    near_bindgen, Promise,
+};
+
+#[near_bindgen]
+impl ReceiverAuthorization for Contract {
+    #[payable]
+    fn add_delegate_key(&mut self, public_key: Base58PublicKey) -> ReceiverAuthorizations {
+        let mut authorizations = self.predecessor_receiver_authorizations();
+        if !authorizations.is_delegate_key(&public_key.0) {
+            assert!(
+                authorizations.delegate_keys().len() < MAX_DELEGATE_KEYS,
+                MAX_DELEGATE_KEYS_REACHED
+            );
+            authorizations.add_delegate_key(public_key.0.clone());
+            log(DelegateKeyUpdated {
+                account_id: &env::predecessor_account_id(),
+                public_key: &String::from(&public_key),
+                registered: true,
+            });
+        }
+        self.save_receiver_authorizations(authorizations)
+    }
+
+    fn remove_delegate_key(&mut self, public_key: Base58PublicKey) -> ReceiverAuthorizations {
+        let mut authorizations = self.predecessor_receiver_authorizations();
+        if authorizations.remove_delegate_key(&public_key.0) {
+            log(DelegateKeyUpdated {
+                account_id: &env::predecessor_account_id(),
+                public_key: &String::from(&public_key),
+                registered: false,
+            });
+        }
+        self.save_receiver_authorizations(authorizations)
+    }
+
+    #[payable]
+    fn authorize_receiver(&mut self, receiver_id: ValidAccountId) -> ReceiverAuthorizations {
+        let mut authorizations = self.predecessor_receiver_authorizations();
+        if !authorizations.is_authorized(receiver_id.as_ref()) {
+            assert!(
+                authorizations.receivers().len() < MAX_AUTHORIZED_RECEIVERS,
+                MAX_AUTHORIZED_RECEIVERS_REACHED
+            );
+            authorizations.authorize(receiver_id.as_ref());
+            log(ReceiverAuthorizationUpdated {
+                account_id: &env::predecessor_account_id(),
+                receiver_id: receiver_id.as_ref(),
+                authorized: true,
+            });
+        }
+        self.save_receiver_authorizations(authorizations)
+    }
+
+    fn revoke_receiver(&mut self, receiver_id: ValidAccountId) -> ReceiverAuthorizations {
+        let mut authorizations = self.predecessor_receiver_authorizations();
+        if authorizations.revoke(receiver_id.as_ref()) {
+            log(ReceiverAuthorizationUpdated {
+                account_id: &env::predecessor_account_id(),
+                receiver_id: receiver_id.as_ref(),
+                authorized: false,
+            });
+        }
+        self.save_receiver_authorizations(authorizations)
+    }
+
+    fn receiver_authorizations(&self, account_id: ValidAccountId) -> ReceiverAuthorizations {
+        self.receiver_authorizations
+            .get(&Hash::from(account_id))
+            .unwrap_or_default()
+            .into()
+    }
+}
+
+impl Contract {
+    /// if the transfer call is signed with one of the predecessor account's delegate keys, then the
+    /// receiver must be authorized by the account
+    ///
+    /// ## Panics
+    /// if the receiver is not authorized
+    pub(crate) fn assert_transfer_call_receiver_authorized(&self, receiver_id: &str) {
+        // delegate keys can only sign transactions for the account itself
+        if env::signer_account_id() != env::predecessor_account_id() {
+            return;
+        }
+        if let Some(authorizations) = self
+            .receiver_authorizations
+            .get(&Hash::from(&env::predecessor_account_id()))
+        {
+            assert!(
+                authorizations.permits(&env::signer_account_pk(), receiver_id),
+                RECEIVER_NOT_AUTHORIZED
+            );
+        }
+    }
+
+    /// removes the account's receiver authorizations
+    ///
+    /// Returns the storage fee that was escrowed for the receiver authorizations.
+    pub(crate) fn delete_receiver_authorizations(
+        &mut self,
+        account_id_hash: &Hash,
+    ) -> domain::YoctoNear {
+        match self.receiver_authorizations.remove(account_id_hash) {
+            None => 0.into(),
+            Some(authorizations) => {
+                self.total_account_storage_escrow -= authorizations.storage_escrow();
+                authorizations.storage_escrow()
+            }
+        }
+    }
+
+    /// ## Panics
+    /// - if the predecessor account is not registered
+    /// - if signed with one of the predecessor account's delegate keys
+    fn predecessor_receiver_authorizations(&self) -> domain::ReceiverAuthorizations {
+        self.predecessor_registered_account();
+        let authorizations = self
+            .receiver_authorizations
+            .get(&Hash::from(&env::predecessor_account_id()))
+            .unwrap_or_default();
+        assert!(
+            !authorizations.is_delegate_key(&env::signer_account_pk()),
+            DELEGATE_KEY_NOT_PERMITTED
+        );
+        authorizations
+    }
+
+    /// saves the receiver authorizations for the predecessor account
+    /// - storage that is allocated must be paid for by the attached deposit
+    /// - the storage fee for storage that is freed is refunded along with the attached deposit
+    ///
+    /// ## Panics
+    /// if the attached deposit does not cover the storage fee
+    fn save_receiver_authorizations(
+        &mut self,
+        mut authorizations: domain::ReceiverAuthorizations,
+    ) -> ReceiverAuthorizations {
+        let account_id_hash = Hash::from(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+        if authorizations.is_empty() {
+            self.receiver_authorizations.remove(&account_id_hash);
+        } else {
+            self.receiver_authorizations
+                .insert(&account_id_hash, &authorizations);
+        }
+        let storage_usage = env::storage_usage();
+
+        let mut refund = env::attached_deposit();
+        if storage_usage > initial_storage_usage {
+            let storage_fee = self
+                .storage_fee(storage_usage - initial_storage_usage)
+                .value();
+            assert!(
+                refund >= storage_fee,
+                "{}: storage fee = {} yoctoNEAR",
+                RECEIVER_AUTHORIZATION_STORAGE_FEE_REQUIRED,
+                storage_fee
+            );
+            refund -= storage_fee;
+            authorizations.set_storage_escrow(authorizations.storage_escrow() + storage_fee.into());
+            self.total_account_storage_escrow += storage_fee.into();
+        } else {
+            let freed_storage_fee = if authorizations.is_empty() {
+                authorizations.storage_escrow().value()
+            } else {
+                self.storage_fee(initial_storage_usage - storage_usage)
+                    .value()
+                    .min(authorizations.storage_escrow().value())
+            };
+            refund += freed_storage_fee;
+            authorizations
+                .set_storage_escrow(authorizations.storage_escrow() - freed_storage_fee.into());
+            self.total_account_storage_escrow -= freed_storage_fee.into();
+        }
+        if !authorizations.is_empty() {
+            // the storage escrow is fixed size, thus updating it does not change the storage usage
+            self.receiver_authorizations
+                .insert(&account_id_hash, &authorizations);
+        }
+
+        if refund > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(refund);
+        }
+        authorizations.into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::FungibleToken;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+    use std::convert::TryFrom;
+
+    const DELEGATE_KEY: &str = "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp";
+
+    fn delegate_key() -> Base58PublicKey {
+        Base58PublicKey::try_from(DELEGATE_KEY).unwrap()
+    }
+
+    fn sign_with_delegate_key(ctx: &mut TestContext) {
+        let mut context = ctx.context.clone();
+        context.signer_account_pk = delegate_key().0;
+        context.attached_deposit = 1;
+        testing_env!(context);
+    }
+
+    fn with_attached_deposit(ctx: &mut TestContext, deposit: u128) {
+        let mut context = ctx.context.clone();
+        context.attached_deposit = deposit;
+        testing_env!(context);
+    }
+
+    #[test]
+    fn authorizations_are_charged_for_storage_and_refunded_when_freed() {
+        let mut ctx = TestContext::with_registered_account();
+        let total_account_storage_escrow = ctx.total_account_storage_escrow;
+
+        with_attached_deposit(&mut ctx, YOCTO);
+        ctx.add_delegate_key(delegate_key());
+        let authorizations = ctx.authorize_receiver(to_valid_account_id("dex.near"));
+        assert_eq!(authorizations.delegate_keys, vec![delegate_key()]);
+        assert_eq!(authorizations.receivers, vec!["dex.near".to_string()]);
+        assert!(authorizations.storage_escrow.value() > 0);
+        assert_eq!(
+            ctx.total_account_storage_escrow,
+            total_account_storage_escrow + authorizations.storage_escrow.clone().into()
+        );
+        assert_eq!(
+            ctx.receiver_authorizations(to_valid_account_id(ctx.account_id)),
+            authorizations
+        );
+
+        with_attached_deposit(&mut ctx, 0);
+        ctx.revoke_receiver(to_valid_account_id("dex.near"));
+        let authorizations = ctx.remove_delegate_key(delegate_key());
+        assert!(authorizations.delegate_keys.is_empty());
+        assert_eq!(authorizations.storage_escrow.value(), 0);
+        assert_eq!(
+            ctx.total_account_storage_escrow,
+            total_account_storage_escrow
+        );
+        assert!(ctx
+            .receiver_authorizations
+            .get(&Hash::from(ctx.account_id))
+            .is_none());
+    }
+
+    #[test]
+    fn transfer_call_signed_with_delegate_key_requires_authorized_receiver() {
+        let mut ctx = TestContext::with_registered_account();
+        with_attached_deposit(&mut ctx, YOCTO);
+        ctx.add_delegate_key(delegate_key());
+        ctx.authorize_receiver(to_valid_account_id("dex.near"));
+
+        // transfer calls signed with the account's other keys are not restricted
+        ctx.assert_transfer_call_receiver_authorized("farm.near");
+
+        sign_with_delegate_key(&mut ctx);
+        ctx.assert_transfer_call_receiver_authorized("dex.near");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "receiver is not authorized for transfer calls signed with a delegate key"
+    )]
+    fn transfer_call_signed_with_delegate_key_to_unauthorized_receiver() {
+        let mut ctx = TestContext::with_registered_account();
+        with_attached_deposit(&mut ctx, YOCTO);
+        ctx.add_delegate_key(delegate_key());
+        ctx.authorize_receiver(to_valid_account_id("dex.near"));
+
+        sign_with_delegate_key(&mut ctx);
+        ctx.ft_transfer_call(
+            to_valid_account_id("farm.near"),
+            YOCTO.into(),
+            "".into(),
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "delegate keys cannot change the account receiver authorizations")]
+    fn delegate_key_cannot_authorize_receivers() {
+        let mut ctx = TestContext::with_registered_account();
+        with_attached_deposit(&mut ctx, YOCTO);
+        ctx.add_delegate_key(delegate_key());
+
+        sign_with_delegate_key(&mut ctx);
+        ctx.authorize_receiver(to_valid_account_id("farm.near"));
+    }
+}
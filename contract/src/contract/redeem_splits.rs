@@ -0,0 +1,274 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::core::Hash;
+use crate::domain::{MAX_REDEEM_SPLITS, MAX_REDEEM_SPLIT_BATCHES};
+use crate::errors::redeem_splits::{
+    NO_REDEEM_SPLIT, REDEEM_SPLITS_FULL, REDEEM_SPLIT_ALREADY_EXISTS, REDEEM_SPLIT_BATCHES_INVALID,
+};
+use crate::errors::staking_service::{INSUFFICIENT_STAKE_FOR_REDEEM_REQUEST, ZERO_REDEEM_AMOUNT};
+use crate::interface::redeem_splits::events::{
+    RedeemSplitEnded, RedeemSplitPortionRedeemed, RedeemSplitScheduled,
+};
+use crate::interface::{RedeemSplit, RedeemSplits};
+use crate::near::log;
+use crate::*;
+use near_sdk::{json_types::ValidAccountId, near_bindgen};
+
+#[near_bindgen]
+impl RedeemSplits for Contract {
+    fn redeem_split(&mut self, amount: interface::YoctoStake, max_batches: u8) -> RedeemSplit {
+        self.assert_not_paused();
+        let mut account = self.predecessor_registered_account();
+        assert!(
+            max_batches > 0 && max_batches <= MAX_REDEEM_SPLIT_BATCHES,
+            REDEEM_SPLIT_BATCHES_INVALID
+        );
+        assert!(
+            !self.redeem_splits.contains_key(&account.id),
+            REDEEM_SPLIT_ALREADY_EXISTS
+        );
+        assert!(
+            self.redeem_split_queue.len() < MAX_REDEEM_SPLITS,
+            REDEEM_SPLITS_FULL
+        );
+        let amount: domain::YoctoStake = amount.into();
+        assert!(amount.value() > 0, ZERO_REDEEM_AMOUNT);
+        self.claim_receipt_funds(&mut account);
+        assert!(
+            account.can_redeem(amount),
+            INSUFFICIENT_STAKE_FOR_REDEEM_REQUEST
+        );
+
+        let mut split = domain::RedeemSplit::new(
+            env::predecessor_account_id(),
+            amount,
+            max_batches,
+            domain::BlockTimeHeight::from_env(),
+        );
+        log(RedeemSplitScheduled {
+            account_id: split.account_id(),
+            amount: amount.value(),
+            batches: max_batches,
+        });
+
+        let portion = split.next_portion();
+        let batch_id: domain::BatchId = self.redeem_stake_for_account(&mut account, portion).into();
+        self.save_registered_account(&account);
+        self.log_redeem_stake_batch(batch_id);
+        split.portion_redeemed(portion, batch_id);
+        log(RedeemSplitPortionRedeemed {
+            account_id: split.account_id(),
+            batch_id: batch_id.value(),
+            amount: portion.value(),
+            remaining: split.remaining().value(),
+        });
+
+        if !split.is_complete() {
+            self.redeem_splits.insert(&account.id, &split);
+            self.redeem_split_queue.push(account.id);
+        }
+        split.into()
+    }
+
+    fn cancel_redeem_split(&mut self) -> interface::YoctoStake {
+        let account_id_hash = Hash::from(&env::predecessor_account_id());
+        let split = self
+            .redeem_splits
+            .remove(&account_id_hash)
+            .expect(NO_REDEEM_SPLIT);
+        self.redeem_split_queue
+            .retain(|queued| *queued != account_id_hash);
+        log(RedeemSplitEnded {
+            account_id: split.account_id(),
+            remaining: split.remaining().value(),
+            cancelled: true,
+        });
+        split.remaining().into()
+    }
+
+    fn redeem_split_schedule(&self, account_id: ValidAccountId) -> Option<RedeemSplit> {
+        self.redeem_splits
+            .get(&Hash::from(account_id))
+            .map(RedeemSplit::from)
+    }
+
+    fn redeem_splits(&self) -> Vec<RedeemSplit> {
+        self.redeem_split_queue
+            .iter()
+            .filter_map(|account_id_hash| self.redeem_splits.get(account_id_hash))
+            .map(RedeemSplit::from)
+            .collect()
+    }
+}
+
+impl Contract {
+    /// redeems the next portion of each queued redeem split into the current redeem stake batch
+    /// - must be invoked when the redeem stake batch run starts, i.e., before the redeem stake
+    ///   batch is locked
+    /// - splits that already redeemed a portion into the current batch are skipped
+    /// - splits end when the account STAKE balance is exhausted or the account is no longer
+    ///   registered
+    pub(crate) fn apply_redeem_splits(&mut self) {
+        if self.redeem_split_queue.is_empty() {
+            return;
+        }
+        let current_batch_id = self.redeem_stake_batch.map(|batch| batch.id());
+        let epoch_height: domain::EpochHeight = env::epoch_height().into();
+        let mut batch_id = None;
+        let queue = std::mem::take(&mut self.redeem_split_queue);
+        for account_id_hash in queue {
+            let mut split = match self.redeem_splits.get(&account_id_hash) {
+                Some(split) => split,
+                None => continue,
+            };
+            if current_batch_id.is_some() && split.last_batch_id() == current_batch_id {
+                self.redeem_split_queue.push(account_id_hash);
+                continue;
+            }
+            let mut account = match self.lookup_registered_account(split.account_id()) {
+                Some(mut account) => {
+                    self.claim_receipt_funds(&mut account);
+                    account
+                }
+                None => {
+                    self.end_redeem_split(&account_id_hash, &split);
+                    continue;
+                }
+            };
+            if account.stake.is_none() {
+                self.end_redeem_split(&account_id_hash, &split);
+                continue;
+            }
+            account.apply_redeem_limit_increase(epoch_height);
+            let portion = split.next_portion().min(account.redeemable(epoch_height));
+            if portion.value() == 0 {
+                // the account redeem limit has been reached for the epoch
+                self.redeem_split_queue.push(account_id_hash);
+                continue;
+            }
+
+            let id: domain::BatchId = self.redeem_stake_for_account(&mut account, portion).into();
+            self.save_registered_account(&account);
+            split.portion_redeemed(portion, id);
+            batch_id = Some(id);
+            log(RedeemSplitPortionRedeemed {
+                account_id: split.account_id(),
+                batch_id: id.value(),
+                amount: portion.value(),
+                remaining: split.remaining().value(),
+            });
+
+            if split.is_complete() {
+                self.redeem_splits.remove(&account_id_hash);
+            } else if account.stake.is_none() {
+                self.end_redeem_split(&account_id_hash, &split);
+            } else {
+                self.redeem_splits.insert(&account_id_hash, &split);
+                self.redeem_split_queue.push(account_id_hash);
+            }
+        }
+        if let Some(batch_id) = batch_id {
+            self.log_redeem_stake_batch(batch_id);
+        }
+    }
+
+    fn end_redeem_split(&mut self, account_id_hash: &Hash, split: &domain::RedeemSplit) {
+        self.redeem_splits.remove(account_id_hash);
+        log(RedeemSplitEnded {
+            account_id: split.account_id(),
+            remaining: split.remaining().value(),
+            cancelled: false,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::StakingService;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn redeem_split_across_successive_batches() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = to_valid_account_id(ctx.account_id);
+        ctx.credit_stake(ctx.account_id, 100 * YOCTO);
+
+        let split = ctx.redeem_split((30 * YOCTO).into(), 3);
+        assert_eq!(split.remaining, (20 * YOCTO).into());
+        assert_eq!(split.remaining_batches, 2);
+        assert_eq!(split.next_portion, (10 * YOCTO).into());
+        let first_batch = ctx.redeem_stake_batch.unwrap();
+        assert_eq!(first_batch.balance().amount(), (10 * YOCTO).into());
+        assert_eq!(split.last_batch_id, Some(first_batch.id().into()));
+        assert_eq!(ctx.redeem_splits(), vec![split.clone()]);
+        assert_eq!(ctx.redeem_split_schedule(account_id.clone()), Some(split));
+
+        // the portion for the current batch was already redeemed
+        ctx.apply_redeem_splits();
+        assert_eq!(
+            ctx.redeem_stake_batch.unwrap().balance().amount(),
+            (10 * YOCTO).into()
+        );
+
+        // the first batch was run
+        ctx.redeem_stake_batch = None;
+        ctx.apply_redeem_splits();
+        let second_batch = ctx.redeem_stake_batch.unwrap();
+        assert_ne!(second_batch.id(), first_batch.id());
+        assert_eq!(second_batch.balance().amount(), (10 * YOCTO).into());
+
+        // the last portion is redeemed into the third batch
+        ctx.redeem_stake_batch = None;
+        ctx.apply_redeem_splits();
+        assert_eq!(
+            ctx.redeem_stake_batch.unwrap().balance().amount(),
+            (10 * YOCTO).into()
+        );
+        assert!(ctx.redeem_split_schedule(account_id).is_none());
+        assert!(ctx.redeem_splits().is_empty());
+        assert_eq!(
+            ctx.registered_account(ctx.account_id)
+                .stake
+                .unwrap()
+                .amount(),
+            (70 * YOCTO).into()
+        );
+    }
+
+    #[test]
+    fn redeem_split_ends_when_stake_balance_is_exhausted() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.credit_stake(ctx.account_id, 25 * YOCTO);
+        ctx.redeem_split((20 * YOCTO).into(), 2);
+
+        // the account redeems the rest of its STAKE balance separately
+        ctx.redeem((15 * YOCTO).into());
+        assert_eq!(ctx.redeem_splits().len(), 1);
+
+        ctx.redeem_stake_batch = None;
+        ctx.apply_redeem_splits();
+        assert!(ctx.redeem_stake_batch.is_none());
+        assert!(ctx.redeem_splits().is_empty());
+    }
+
+    #[test]
+    fn cancel_redeem_split() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.credit_stake(ctx.account_id, 100 * YOCTO);
+        ctx.redeem_split((30 * YOCTO).into(), 3);
+
+        assert_eq!(ctx.cancel_redeem_split(), (20 * YOCTO).into());
+        assert!(ctx.redeem_splits().is_empty());
+        // portions that were already redeemed remain in the batch
+        assert_eq!(
+            ctx.redeem_stake_batch.unwrap().balance().amount(),
+            (10 * YOCTO).into()
+        );
+
+        ctx.redeem_stake_batch = None;
+        ctx.apply_redeem_splits();
+        assert!(ctx.redeem_stake_batch.is_none());
+    }
+}
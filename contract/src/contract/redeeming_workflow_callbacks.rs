@@ -4,7 +4,7 @@ use crate::interface::staking_service::events::Unstaked;
 use crate::near::log;
 use crate::*;
 use crate::{
-    domain::RedeemLock,
+    domain::{self, RedeemLock, Workflow},
     errors::{
         illegal_state::{
             ILLEGAL_REDEEM_LOCK_STATE, REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST,
@@ -14,18 +14,19 @@ use crate::{
         staking_pool_failures::{GET_ACCOUNT_FAILURE, UNSTAKE_FAILURE, WITHDRAW_ALL_FAILURE},
     },
     ext_redeeming_workflow_callbacks,
-    interface::BatchId,
-    near::NO_DEPOSIT,
+    interface::{BatchId, UnstakeResult},
+    near::{events, NO_DEPOSIT},
 };
 use near_sdk::{env, near_bindgen, Promise, PromiseOrValue};
 
 #[near_bindgen]
 impl Contract {
+    /// Resolves to the [UnstakeResult] that is returned by [on_unstake](Contract::on_unstake).
     #[private]
     pub fn on_run_redeem_stake_batch(
         &mut self,
         #[callback] staking_pool_account: StakingPoolAccount,
-    ) -> Promise {
+    ) -> PromiseOrValue<UnstakeResult> {
         // this callback should only be invoked when we are unstaking, i.e., when the RedeemStakeBatch
         // is kicked off
         assert!(self.is_unstaking(), ILLEGAL_REDEEM_LOCK_STATE);
@@ -40,17 +41,18 @@ impl Contract {
         assert!(self.promise_result_succeeded(), GET_ACCOUNT_FAILURE);
 
         // update the cached STAKE token value
-        let staked_balance = self.staked_near_balance(
+        let audit = self.new_batch_audit(
             staking_pool_account.staked_balance.into(),
             staking_pool_account.unstaked_balance.into(),
         );
-        self.update_stake_token_value(staked_balance);
+        let compensation = self.update_stake_token_value(audit.total_staked_near_balance());
+        self.record_batch_audit(batch.id(), audit, compensation);
 
         let unstake_amount = self
             .stake_token_value
             .stake_to_near(batch.balance().amount());
 
-        if staking_pool_account.staked_balance.0 < unstake_amount.value() {
+        let promise = if staking_pool_account.staked_balance.0 < unstake_amount.value() {
             // when unstaking the remaining balance, there will probably be some NEAR that is already
             // unstaked because of the rounding issues when the staking pool issued shares
             self.staking_pool_promise()
@@ -62,16 +64,20 @@ impl Contract {
                 .unstake(unstake_amount)
                 .promise()
                 .then(self.invoke_on_unstake())
-        }
+        };
+        self.record_callback_gas_usage("on_run_redeem_stake_batch");
+        promise.into()
     }
 
     #[private]
-    pub fn on_unstake(&mut self) {
+    pub fn on_unstake(&mut self) -> UnstakeResult {
         assert!(self.promise_result_succeeded(), UNSTAKE_FAILURE);
 
-        self.create_redeem_stake_batch_receipt();
+        let result = self.watch_aggregates(Self::create_redeem_stake_batch_receipt);
 
-        self.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal)
+        self.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+        self.record_callback_gas_usage("on_unstake");
+        result
     }
 
     #[private]
@@ -85,7 +91,7 @@ impl Contract {
         // if unstaked balance is zero, then it means the unstaked NEAR funds were withdrawn
         // - unstaked NEAR is restaked to add liquidity, which effectively reduces the unstaked NEAR
         //   balance in the staking pool contract
-        if unstaked_balance > 0 {
+        let result = if unstaked_balance > 0 {
             assert!(
                 staking_pool_account.can_withdraw,
                 UNSTAKED_FUNDS_NOT_AVAILABLE_FOR_WITHDRAWAL
@@ -97,14 +103,18 @@ impl Contract {
                 .then(self.invoke_on_redeeming_stake_post_withdrawal())
                 .into()
         } else {
-            PromiseOrValue::Value(self.finalize_redeem_batch())
-        }
+            PromiseOrValue::Value(self.watch_aggregates(Self::finalize_redeem_batch))
+        };
+        self.record_callback_gas_usage("on_redeeming_stake_pending_withdrawal");
+        result
     }
 
     #[private]
     pub fn on_redeeming_stake_post_withdrawal(&mut self) -> BatchId {
         assert!(self.promise_result_succeeded(), WITHDRAW_ALL_FAILURE);
-        self.finalize_redeem_batch()
+        let batch_id = self.watch_aggregates(Self::finalize_redeem_batch);
+        self.record_callback_gas_usage("on_redeeming_stake_post_withdrawal");
+        batch_id
     }
 
     fn finalize_redeem_batch(&mut self) -> BatchId {
@@ -120,6 +130,8 @@ impl Contract {
         self.total_near.credit(receipt.stake_near_value());
 
         self.redeem_stake_batch_lock = None;
+        self.record_lock_released(false, "finalize_redeem_batch");
+        self.record_workflow_success(Workflow::Withdraw);
         self.pop_redeem_stake_batch();
 
         batch.id().into()
@@ -127,16 +139,41 @@ impl Contract {
 }
 
 impl Contract {
-    fn create_redeem_stake_batch_receipt(&mut self) {
+    fn create_redeem_stake_batch_receipt(&mut self) -> UnstakeResult {
         let batch = self.redeem_stake_batch.expect(STAKE_BATCH_SHOULD_EXIST);
-        let batch_receipt = batch.create_receipt(self.stake_token_value);
+        let batch_receipt =
+            batch.create_receipt(self.stake_token_value, self.staking_pool_id.clone());
         self.redeem_stake_batch_receipts
             .insert(&batch.id(), &batch_receipt);
+        self.workflow_counters.redeem_stake_batches_run += 1;
+        self.record_workflow_success(Workflow::Unstake);
 
         // update the total STAKE supply
+        self.accumulate_total_stake_rebate();
         self.total_stake.debit(batch_receipt.redeemed_stake());
+        self.stake_supply_stats
+            .record_burn(batch_receipt.redeemed_stake(), env::epoch_height().into());
+        events::ft_burn(
+            &env::current_account_id(),
+            batch_receipt.redeemed_stake(),
+            Some(&format!("redeem stake batch {}", batch.id().value())),
+        );
+        events::redeem_batch_run(
+            batch.id(),
+            batch_receipt.redeemed_stake(),
+            batch_receipt.stake_near_value(),
+            self.stake_token_value,
+        );
+        self.record_redeem_batch_history(domain::BatchHistoryEntry::new(
+            batch.id(),
+            batch_receipt.stake_near_value(),
+            batch_receipt.redeemed_stake(),
+            self.stake_token_value,
+            batch.balance().block_timestamp(),
+        ));
 
         log(Unstaked::new(batch.id(), &batch_receipt));
+        UnstakeResult::new(batch.id(), &batch_receipt)
     }
 
     /// moves the next batch into the current batch
@@ -308,6 +345,58 @@ mod test {
         }
     }
 
+    /// Given the staked NEAR balance returned by the staking pool would tick the STAKE value down
+    /// When the redeem batch is run
+    /// Then the batch audit records the raw staking pool balances and the compensation
+    #[test]
+    fn on_run_redeem_stake_batch_records_batch_audit() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        *contract.batch_id_sequence += 1;
+        contract.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+        let redeem_stake_batch =
+            RedeemStakeBatch::new(contract.batch_id_sequence, (100 * YOCTO).into());
+        contract.redeem_stake_batch = Some(redeem_stake_batch);
+        contract.total_stake = TimestampedStakeBalance::new((1000 * YOCTO).into());
+
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        contract.update_stake_token_value((1100 * YOCTO).into());
+        let prior_stake_token_value = contract.stake_token_value;
+
+        contract.on_run_redeem_stake_batch(StakingPoolAccount {
+            account_id: context.current_account_id.to_string(),
+            unstaked_balance: 10.into(),
+            staked_balance: (1000 * YOCTO).into(),
+            can_withdraw: true,
+        });
+
+        let audit = contract
+            .batch_audit(redeem_stake_batch.id().into())
+            .unwrap();
+        assert_eq!(audit.staked_balance.value(), 1000 * YOCTO);
+        assert_eq!(audit.unstaked_balance.value(), 10);
+        assert_eq!(audit.pending_withdrawal.value(), 0);
+        assert_eq!(audit.near_liquidity.value(), 0);
+        assert_eq!(audit.total_staked_near_balance.value(), 1000 * YOCTO + 10);
+        assert_eq!(
+            audit.prior_stake_token_value,
+            interface::StakeTokenValue::from(prior_stake_token_value)
+        );
+        assert_eq!(audit.compensation.value(), 100 * YOCTO - 10);
+        assert_eq!(
+            audit.stake_token_value,
+            interface::StakeTokenValue::from(contract.stake_token_value)
+        );
+        assert_eq!(
+            audit.stake_token_value.value,
+            audit.prior_stake_token_value.value
+        );
+        assert_eq!(contract.near_liquidity_pool.value(), 100 * YOCTO - 10);
+    }
+
     /// When there are unstaked NEAR funds in the staking pool
     /// And the unstaked funds can be withdrawn
     /// Then a request to withdraw all funds is sent to the staking pool
@@ -432,14 +521,24 @@ mod test {
         context.block_index += 1;
         context.block_timestamp += 1;
         testing_env!(context.clone());
-        contract.on_unstake();
+        let result = contract.on_unstake();
+        let batch_id = contract.redeem_stake_batch.unwrap().id();
+        let receipt = contract.redeem_stake_batch_receipts.get(&batch_id).unwrap();
+        assert_eq!(result.batch_id, batch_id.into());
+        assert_eq!(result.redeemed_stake, (100 * YOCTO).into());
+        assert_eq!(result.unstaked_near, receipt.stake_near_value().into());
+        assert_eq!(result.stake_token_value, receipt.stake_token_value().into());
 
         assert_eq!(contract.total_stake.amount(), (900 * YOCTO).into());
+        let stats = contract.stake_supply_stats();
+        assert_eq!(stats.stake_burned_this_epoch, (100 * YOCTO).into());
+        assert_eq!(stats.total_stake_burned, (100 * YOCTO).into());
         let receipt = contract
             .redeem_stake_batch_receipts
             .get(&contract.redeem_stake_batch.unwrap().id())
             .unwrap();
         assert_eq!(receipt.redeemed_stake(), (100 * YOCTO).into());
+        assert_eq!(receipt.staking_pool_id(), contract.staking_pool_id);
         assert_eq!(
             receipt.stake_token_value().total_stake_supply(),
             contract.total_stake.amount() + receipt.redeemed_stake()
@@ -492,8 +591,11 @@ mod test {
         contract.redeem_stake_batch = Some(batch);
         contract.total_stake = TimestampedStakeBalance::new((1000 * YOCTO).into());
 
-        let batch_receipt =
-            RedeemStakeBatchReceipt::new(batch.balance().amount(), contract.stake_token_value);
+        let batch_receipt = RedeemStakeBatchReceipt::new(
+            batch.balance().amount(),
+            contract.stake_token_value,
+            TEST_STAKING_POOL_ID.to_string(),
+        );
         contract
             .redeem_stake_batch_receipts
             .insert(&batch.id(), &batch_receipt);
@@ -0,0 +1,292 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::core::Hash;
+use crate::domain::MAX_REFERRAL_CODES;
+use crate::errors::referrals::{
+    INVALID_REFERRAL_CODE, REFERRAL_CODES_FULL, REFERRAL_CODE_EXPIRED, REFERRAL_CODE_NOT_FOUND,
+    REFERRAL_CODE_OWNER_ONLY, REFERRAL_CODE_STORAGE_FEE_REQUIRED, REFERRAL_CODE_TAKEN,
+    SELF_REFERRAL,
+};
+use crate::interface::referrals::events::{
+    ReferralCodeRegistered, ReferralCodeUnregistered, ReferralDeposit,
+};
+use crate::interface::{ReferralCode, Referrals};
+use crate::near::log;
+use crate::*;
+use near_sdk::{near_bindgen, Promise};
+
+#[near_bindgen]
+impl Referrals for Contract {
+    #[payable]
+    fn register_referral_code(&mut self, code: String) -> ReferralCode {
+        assert!(
+            domain::ReferralCode::is_valid_code(&code),
+            INVALID_REFERRAL_CODE
+        );
+        // the partner must be registered
+        self.predecessor_registered_account();
+        let account_id = env::predecessor_account_id();
+        let now = domain::BlockTimeHeight::from_env();
+        let code_hash = Hash::from(&code);
+
+        let mut refund = env::attached_deposit();
+        match self.referral_codes.get(&code_hash) {
+            Some(mut referral_code) if referral_code.account_id() == account_id => {
+                referral_code.renew(now);
+                self.referral_codes.insert(&code_hash, &referral_code);
+            }
+            Some(referral_code) => {
+                assert!(
+                    referral_code.is_expired(now.epoch_height()),
+                    REFERRAL_CODE_TAKEN
+                );
+                self.remove_referral_code(&code_hash, &referral_code, true);
+                refund -= self.insert_referral_code(&code_hash, &code, &account_id, now);
+            }
+            None => {
+                assert!(
+                    self.referral_code_index.len() < MAX_REFERRAL_CODES,
+                    REFERRAL_CODES_FULL
+                );
+                refund -= self.insert_referral_code(&code_hash, &code, &account_id, now);
+                self.referral_code_index.push(code_hash);
+            }
+        }
+        if refund > 0 {
+            Promise::new(account_id.clone()).transfer(refund);
+        }
+
+        let referral_code = self.referral_codes.get(&code_hash).unwrap();
+        log(ReferralCodeRegistered {
+            code: &code,
+            account_id: &account_id,
+            expires_at: referral_code.expires_at().value(),
+        });
+        ReferralCode::from(referral_code, now.epoch_height())
+    }
+
+    fn unregister_referral_code(&mut self, code: String) -> interface::YoctoNear {
+        let code_hash = Hash::from(&code);
+        let referral_code = self
+            .referral_codes
+            .get(&code_hash)
+            .expect(REFERRAL_CODE_NOT_FOUND);
+        assert_eq!(
+            env::predecessor_account_id(),
+            referral_code.account_id(),
+            "{}",
+            REFERRAL_CODE_OWNER_ONLY
+        );
+
+        self.remove_referral_code(&code_hash, &referral_code, false);
+        self.referral_code_index
+            .retain(|index_code_hash| *index_code_hash != code_hash);
+        referral_code.storage_fee().into()
+    }
+
+    fn referral_code(&self, code: String) -> Option<ReferralCode> {
+        self.referral_codes
+            .get(&Hash::from(&code))
+            .map(|referral_code| ReferralCode::from(referral_code, env::epoch_height().into()))
+    }
+
+    fn referral_codes(&self, from_index: u32, limit: u32) -> Vec<ReferralCode> {
+        self.referral_code_index
+            .iter()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .filter_map(|code_hash| self.referral_codes.get(code_hash))
+            .map(|referral_code| ReferralCode::from(referral_code, env::epoch_height().into()))
+            .collect()
+    }
+}
+
+impl Contract {
+    /// attributes the deposit to the partner that owns the referral code
+    ///
+    /// ## Panics
+    /// - if the code does not exist or has expired
+    /// - if the depositor owns the code
+    pub(crate) fn attribute_referral_deposit(
+        &mut self,
+        code: &str,
+        batch_id: domain::BatchId,
+        amount: domain::YoctoNear,
+    ) {
+        let code_hash = Hash::from(code);
+        let mut referral_code = self
+            .referral_codes
+            .get(&code_hash)
+            .expect(REFERRAL_CODE_NOT_FOUND);
+        assert!(
+            !referral_code.is_expired(env::epoch_height().into()),
+            REFERRAL_CODE_EXPIRED
+        );
+        let account_id = env::predecessor_account_id();
+        assert_ne!(account_id, referral_code.account_id(), "{}", SELF_REFERRAL);
+
+        referral_code.record_deposit(amount);
+        self.referral_codes.insert(&code_hash, &referral_code);
+        log(ReferralDeposit {
+            code,
+            referrer_id: referral_code.account_id(),
+            account_id: &account_id,
+            batch_id: batch_id.value(),
+            amount: amount.value(),
+        });
+    }
+
+    /// inserts a new referral code record - the storage fee is escrowed
+    /// - returns the storage fee, which is paid from the attached deposit
+    fn insert_referral_code(
+        &mut self,
+        code_hash: &Hash,
+        code: &str,
+        account_id: &str,
+        now: domain::BlockTimeHeight,
+    ) -> u128 {
+        let mut referral_code = domain::ReferralCode::new(code.to_string(), account_id, now);
+        let initial_storage_usage = env::storage_usage();
+        self.referral_codes.insert(code_hash, &referral_code);
+        let storage_fee = self.storage_fee(env::storage_usage() - initial_storage_usage);
+        assert!(
+            env::attached_deposit() >= storage_fee.value(),
+            "{}: storage fee = {} yoctoNEAR",
+            REFERRAL_CODE_STORAGE_FEE_REQUIRED,
+            storage_fee
+        );
+        referral_code.set_storage_fee(storage_fee);
+        self.referral_codes.insert(code_hash, &referral_code);
+        self.total_account_storage_escrow += storage_fee;
+        storage_fee.value()
+    }
+
+    /// removes the referral code record and refunds the storage fee to the account that owns it
+    /// - the code index is not updated
+    fn remove_referral_code(
+        &mut self,
+        code_hash: &Hash,
+        referral_code: &domain::ReferralCode,
+        reclaimed: bool,
+    ) {
+        self.referral_codes.remove(code_hash);
+        let storage_fee = referral_code.storage_fee();
+        self.total_account_storage_escrow -= storage_fee;
+        if storage_fee.value() > 0 {
+            Promise::new(referral_code.account_id().to_string()).transfer(storage_fee.value());
+        }
+        log(ReferralCodeUnregistered {
+            code: referral_code.code(),
+            account_id: referral_code.account_id(),
+            reclaimed,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::StakingService;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{test_utils::get_logs, testing_env, MockedBlockchain};
+
+    const PARTNER_ID: &str = "partner.near";
+
+    fn register_code(ctx: &mut TestContext, account_id: &str, code: &str) -> ReferralCode {
+        let mut context = ctx.set_predecessor_account_id(account_id);
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        ctx.register_referral_code(code.to_string())
+    }
+
+    fn deposit(ctx: &mut TestContext, amount: u128, code: &str) -> interface::BatchId {
+        let account_id = ctx.account_id;
+        let mut context = ctx.set_predecessor_account_id(account_id);
+        context.attached_deposit = amount;
+        testing_env!(context);
+        ctx.deposit(Some(code.to_string()))
+    }
+
+    #[test]
+    fn deposit_with_referral_code_is_attributed_to_partner() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.register_account(PARTNER_ID);
+        let referral_code = register_code(&mut ctx, PARTNER_ID, "partner");
+        assert_eq!(referral_code.account_id, PARTNER_ID);
+        assert!(!referral_code.expired);
+        assert!(referral_code.storage_fee.value() > 0);
+
+        deposit(&mut ctx, 10 * YOCTO, "partner");
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("ReferralDeposit")));
+        deposit(&mut ctx, 5 * YOCTO, "partner");
+
+        let referral_code = ctx.referral_code("partner".to_string()).unwrap();
+        assert_eq!(referral_code.deposits, 2.into());
+        assert_eq!(referral_code.referred_near, (15 * YOCTO).into());
+        assert_eq!(ctx.referral_codes(0, 10), vec![referral_code]);
+    }
+
+    #[test]
+    #[should_panic(expected = "referral code is registered to another account and has not expired")]
+    fn register_code_taken_by_another_account() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.register_account(PARTNER_ID);
+        register_code(&mut ctx, PARTNER_ID, "partner");
+        let account_id = ctx.account_id;
+        register_code(&mut ctx, account_id, "partner");
+    }
+
+    #[test]
+    fn expired_code_is_renewed_or_reclaimed() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.register_account(PARTNER_ID);
+        let referral_code = register_code(&mut ctx, PARTNER_ID, "partner");
+
+        // the owner renews the code
+        ctx.context.epoch_height = referral_code.expires_at.0 .0;
+        let renewed = register_code(&mut ctx, PARTNER_ID, "partner");
+        assert!(renewed.expires_at.0 .0 > referral_code.expires_at.0 .0);
+        assert_eq!(renewed.storage_fee, referral_code.storage_fee);
+
+        // once expired, the code can be reclaimed by another account
+        ctx.context.epoch_height = renewed.expires_at.0 .0;
+        let account_id = ctx.account_id;
+        let reclaimed = register_code(&mut ctx, account_id, "partner");
+        assert_eq!(reclaimed.account_id, account_id);
+        assert_eq!(reclaimed.deposits, 0.into());
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("ReferralCodeUnregistered")));
+        assert_eq!(ctx.referral_codes(0, 10).len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "referral code has expired")]
+    fn deposit_with_expired_referral_code() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.register_account(PARTNER_ID);
+        let referral_code = register_code(&mut ctx, PARTNER_ID, "partner");
+
+        ctx.context.epoch_height = referral_code.expires_at.0 .0;
+        deposit(&mut ctx, 10 * YOCTO, "partner");
+    }
+
+    #[test]
+    fn unregister_referral_code() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.register_account(PARTNER_ID);
+        let referral_code = register_code(&mut ctx, PARTNER_ID, "partner");
+        let total_account_storage_escrow = ctx.total_account_storage_escrow;
+
+        let refund = ctx.unregister_referral_code("partner".to_string());
+        assert_eq!(refund, referral_code.storage_fee);
+        assert_eq!(
+            ctx.total_account_storage_escrow,
+            total_account_storage_escrow - refund.into()
+        );
+        assert!(ctx.referral_code("partner".to_string()).is_none());
+        assert!(ctx.referral_codes(0, 10).is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::errors::scheduled_actions::NO_SCHEDULED_ACTION;
+use crate::interface::scheduled_actions::events::ScheduledActionCancelled;
+use crate::interface::{
+    BatchReservations, RedeemSplits, ScheduledAction, ScheduledActionId, ScheduledActions,
+    StakingService, SwapIntents,
+};
+use crate::near::log;
+use crate::*;
+use near_sdk::{json_types::ValidAccountId, near_bindgen};
+use std::convert::TryFrom;
+
+#[near_bindgen]
+impl ScheduledActions for Contract {
+    fn scheduled_actions(&self, account_id: ValidAccountId) -> Vec<ScheduledAction> {
+        let mut actions = vec![];
+        if let Some(target) = self.redeem_forwarding(account_id.clone()) {
+            actions.push(ScheduledAction::redeem_forwarding(target));
+        }
+        if let Some(reservation) = self.batch_reservation(account_id.clone()) {
+            actions.push(ScheduledAction::batch_reservation(reservation));
+        }
+        if let Some(split) = self.redeem_split_schedule(account_id.clone()) {
+            actions.push(ScheduledAction::redeem_split(split));
+        }
+        if let Some(intent) = self.swap_intent(account_id) {
+            // the intent is kept around while fills are pending settlement
+            if intent.amount.value() > 0 {
+                actions.push(ScheduledAction::swap_intent(intent));
+            }
+        }
+        actions
+    }
+
+    fn cancel_scheduled_action(&mut self, id: ScheduledActionId) {
+        self.predecessor_registered_account();
+        let account_id = env::predecessor_account_id();
+        assert!(
+            self.scheduled_actions(ValidAccountId::try_from(account_id.as_str()).unwrap())
+                .iter()
+                .any(|action| action.id == id),
+            NO_SCHEDULED_ACTION
+        );
+        match id {
+            ScheduledActionId::RedeemForwarding => self.set_redeem_forwarding(None),
+            ScheduledActionId::BatchReservation => {
+                self.cancel_batch_reservation();
+            }
+            ScheduledActionId::SwapIntent => {
+                self.cancel_swap_intent();
+            }
+            ScheduledActionId::RedeemSplit => {
+                self.cancel_redeem_split();
+            }
+        }
+        log(ScheduledActionCancelled {
+            account_id: &account_id,
+            id,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::Operator;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{test_utils::get_logs, testing_env, MockedBlockchain};
+
+    const TARGET_STAKE_CONTRACT: &str = "stake.other-validator.near";
+
+    #[test]
+    fn list_and_cancel_scheduled_actions() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = to_valid_account_id(ctx.account_id);
+        assert!(ctx.scheduled_actions(account_id.clone()).is_empty());
+
+        let mut context = ctx.set_predecessor_account_id(TEST_OPERATOR_ID);
+        testing_env!(context.clone());
+        ctx.whitelist_redeem_forwarding_target(to_valid_account_id(TARGET_STAKE_CONTRACT));
+
+        context.predecessor_account_id = ctx.account_id.to_string();
+        testing_env!(context.clone());
+        ctx.set_redeem_forwarding(Some(to_valid_account_id(TARGET_STAKE_CONTRACT)));
+        context.attached_deposit = 11 * YOCTO;
+        testing_env!(context.clone());
+        ctx.reserve_batch_slot((10 * YOCTO).into());
+
+        let actions = ctx.scheduled_actions(account_id.clone());
+        assert_eq!(
+            actions,
+            vec![
+                ScheduledAction::redeem_forwarding(TARGET_STAKE_CONTRACT.to_string()),
+                ScheduledAction {
+                    id: ScheduledActionId::BatchReservation,
+                    target: None,
+                    near_amount: Some((10 * YOCTO).into()),
+                    stake_amount: None,
+                }
+            ]
+        );
+
+        context.attached_deposit = 0;
+        testing_env!(context);
+        ctx.cancel_scheduled_action(ScheduledActionId::RedeemForwarding);
+        assert!(ctx.redeem_forwarding(account_id.clone()).is_none());
+        let log = get_logs().pop().unwrap();
+        assert!(log.starts_with("ScheduledActionCancelled"));
+        assert!(log.contains("id: RedeemForwarding"));
+
+        ctx.cancel_scheduled_action(ScheduledActionId::BatchReservation);
+        assert!(ctx.batch_reservation(account_id.clone()).is_none());
+        assert!(ctx.scheduled_actions(account_id).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "account has no active scheduled action with the specified ID")]
+    fn cancel_inactive_scheduled_action() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.cancel_scheduled_action(ScheduledActionId::SwapIntent);
+    }
+}
@@ -46,6 +46,22 @@ impl<'a> StakingPoolPromiseBuilder<'a> {
         )
     }
 
+    pub fn get_reward_fee_fraction(self) -> Self {
+        Self(
+            self.0.function_call(
+                b"get_reward_fee_fraction".to_vec(),
+                NO_ARGS.to_vec(),
+                NO_DEPOSIT.into(),
+                self.1
+                    .gas_config()
+                    .staking_pool()
+                    .get_reward_fee_fraction()
+                    .value(),
+            ),
+            self.1,
+        )
+    }
+
     pub fn deposit_then_stake(self, deposit_amount: YoctoNear, stake_amount: YoctoNear) -> Self {
         Self(
             self.0
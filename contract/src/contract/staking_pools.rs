@@ -0,0 +1,291 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::errors::staking_pools::{
+    STAKING_POOL_CHANGE_BLOCKED, STAKING_POOL_NOT_WHITELISTED, STAKING_POOL_UNCHANGED,
+    STAKING_POOL_WHITELIST_CHECK_DISABLED,
+};
+use crate::interface::staking_pools::events::{
+    StakingPoolChanged, StakingPoolWhitelistCheck, StakingPoolWhitelisted,
+};
+use crate::interface::StakingPools;
+use crate::near::{log, NO_DEPOSIT};
+use crate::*;
+use near_sdk::{ext_contract, json_types::ValidAccountId, near_bindgen, Promise, PromiseOrValue};
+
+#[near_bindgen]
+impl StakingPools for Contract {
+    fn change_staking_pool(&mut self, account_id: ValidAccountId) -> PromiseOrValue<AccountId> {
+        self.assert_predecessor_can_run_sensitive_operator_action();
+        self.change_staking_pool_account(account_id.into())
+    }
+
+    fn check_staking_pool_whitelist(&mut self) -> Promise {
+        self.assert_predecessor_is_operator();
+        assert!(
+            self.config.staking_pool_whitelist_check(),
+            STAKING_POOL_WHITELIST_CHECK_DISABLED
+        );
+        self.staking_pool_whitelist_check(self.staking_pool_id.clone())
+    }
+}
+
+#[ext_contract(ext_staking_pool_whitelist)]
+pub trait ExtStakingPoolWhitelist {
+    fn is_whitelisted(&self, staking_pool_account_id: AccountId) -> bool;
+}
+
+#[ext_contract(ext_staking_pool_whitelist_callbacks)]
+pub trait ExtStakingPoolWhitelistCallbacks {
+    fn on_staking_pool_whitelist_check(
+        &mut self,
+        #[callback] whitelisted: bool,
+        account_id: AccountId,
+    ) -> bool;
+}
+
+#[near_bindgen]
+impl Contract {
+    /// confirms the current staking pool, or changes the staking pool if the account is not the
+    /// current staking pool, once the staking pool whitelist contract reports it as whitelisted
+    ///
+    /// ## Panics
+    /// - if the staking pool is not whitelisted
+    /// - if the whitelist check failed
+    /// - if the staking pool can no longer be changed
+    #[private]
+    pub fn on_staking_pool_whitelist_check(
+        &mut self,
+        #[callback] whitelisted: bool,
+        account_id: AccountId,
+    ) -> bool {
+        assert!(whitelisted, STAKING_POOL_NOT_WHITELISTED);
+        if account_id != self.staking_pool_id {
+            // STAKE may have been issued while the whitelist was being checked
+            self.assert_staking_pool_can_be_changed();
+            self.set_staking_pool(account_id);
+        }
+        self.staking_pool_whitelisted = true;
+        log(StakingPoolWhitelisted {
+            account_id: &self.staking_pool_id,
+        });
+        true
+    }
+}
+
+impl Contract {
+    /// see [change_staking_pool](StakingPools::change_staking_pool)
+    pub(crate) fn change_staking_pool_account(
+        &mut self,
+        account_id: AccountId,
+    ) -> PromiseOrValue<AccountId> {
+        self.check_staking_pool_change(&account_id);
+        if self.config.staking_pool_whitelist_check() {
+            return PromiseOrValue::Promise(self.staking_pool_whitelist_check(account_id));
+        }
+        self.set_staking_pool(account_id);
+        PromiseOrValue::Value(self.staking_pool_id.clone())
+    }
+
+    /// ## Panics
+    /// - if the account is already the staking pool
+    /// - if the staking pool can not be changed
+    pub(crate) fn check_staking_pool_change(&self, account_id: &str) {
+        assert_ne!(
+            account_id, self.staking_pool_id,
+            "{}",
+            STAKING_POOL_UNCHANGED
+        );
+        self.assert_staking_pool_can_be_changed();
+    }
+
+    /// checks the staking pool against the staking pool whitelist contract
+    pub(crate) fn staking_pool_whitelist_check(&self, account_id: AccountId) -> Promise {
+        let whitelist_contract_id = self.config.staking_pool_whitelist_contract_id();
+        log(StakingPoolWhitelistCheck {
+            account_id: &account_id,
+            whitelist_contract_id,
+        });
+        let gas_config = self.config.gas_config().callbacks();
+        ext_staking_pool_whitelist::is_whitelisted(
+            account_id.clone(),
+            whitelist_contract_id,
+            NO_DEPOSIT.value(),
+            gas_config.staking_pool_whitelist_check().value(),
+        )
+        .then(
+            ext_staking_pool_whitelist_callbacks::on_staking_pool_whitelist_check(
+                account_id,
+                &env::current_account_id(),
+                NO_DEPOSIT.value(),
+                gas_config.on_staking_pool_whitelist_check().value(),
+            ),
+        )
+    }
+
+    /// NEAR can only be staked with the staking pool once the staking pool whitelist contract has
+    /// confirmed it, if the whitelist check is enabled
+    pub(crate) fn is_staking_pool_whitelisted(&self) -> bool {
+        !self.config.staking_pool_whitelist_check() || self.staking_pool_whitelisted
+    }
+
+    /// the staking pool can only be changed while no NEAR is staked with the current staking pool on
+    /// behalf of STAKE holders and no batch workflow is in flight
+    fn assert_staking_pool_can_be_changed(&self) {
+        assert!(
+            self.total_stake.amount().value() == 0
+                && self.stake_batch_lock.is_none()
+                && self.redeem_stake_batch_lock.is_none()
+                && self.redeem_stake_batch.is_none()
+                && self.next_redeem_stake_batch.is_none()
+                && self.unverified_stake_batches.is_empty(),
+            STAKING_POOL_CHANGE_BLOCKED
+        );
+    }
+
+    fn set_staking_pool(&mut self, account_id: AccountId) {
+        log(StakingPoolChanged {
+            from: &self.staking_pool_id,
+            to: &account_id,
+        });
+        self.staking_pool_id = account_id;
+        self.staking_pool_whitelisted = false;
+        // the cached staking pool state belonged to the previous staking pool
+        self.staking_pool_unavailable_until = None;
+        self.staking_pool_reward_fee_fraction = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::StakingService;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{serde_json, test_utils::get_logs, testing_env, MockedBlockchain};
+
+    fn set_predecessor_to_contract(ctx: &mut TestContext) {
+        let contract_id = ctx.context.current_account_id.clone();
+        let context = ctx.set_predecessor_account_id(&contract_id);
+        testing_env!(context);
+    }
+
+    fn enable_whitelist_check(ctx: &mut TestContext) {
+        ctx.config
+            .merge(serde_json::from_str(r#"{"staking_pool_whitelist_check": true}"#).unwrap());
+    }
+
+    #[test]
+    fn change_staking_pool() {
+        let mut ctx = TestContext::new();
+        let context = ctx.set_predecessor_account_id(TEST_OPERATOR_ID);
+        testing_env!(context);
+
+        match ctx.change_staking_pool(to_valid_account_id("pool-1.near")) {
+            PromiseOrValue::Value(account_id) => assert_eq!(account_id, "pool-1.near"),
+            _ => panic!("expected value to be returned"),
+        }
+        assert_eq!(ctx.staking_pool_id(), "pool-1.near");
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("StakingPoolChanged")));
+        // the whitelist check is disabled
+        assert!(ctx.staking_pool_info().whitelisted);
+    }
+
+    #[test]
+    fn change_staking_pool_with_whitelist_check() {
+        let mut ctx = TestContext::new();
+        enable_whitelist_check(&mut ctx);
+        let context = ctx.set_predecessor_account_id(TEST_OPERATOR_ID);
+        testing_env!(context);
+
+        ctx.change_staking_pool(to_valid_account_id("pool-1.near"));
+        // the staking pool is changed once the whitelist check completes
+        assert_eq!(ctx.staking_pool_id(), TEST_STAKING_POOL_ID);
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0].receiver_id, "lockup-whitelist.near");
+        match &receipts[0].actions[0] {
+            Action::FunctionCall {
+                method_name, args, ..
+            } => {
+                assert_eq!(method_name, "is_whitelisted");
+                let args: serde_json::Value = serde_json::from_str(args).unwrap();
+                assert_eq!(args["staking_pool_account_id"], "pool-1.near");
+            }
+            _ => panic!("expected `is_whitelisted` function call"),
+        }
+
+        set_predecessor_to_contract(&mut ctx);
+        assert!(ctx.on_staking_pool_whitelist_check(true, "pool-1.near".to_string()));
+        assert_eq!(ctx.staking_pool_id(), "pool-1.near");
+        assert!(ctx.staking_pool_info().whitelisted);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "staking pool is not whitelisted by the staking pool whitelist contract"
+    )]
+    fn non_whitelisted_staking_pool_is_rejected() {
+        let mut ctx = TestContext::new();
+        enable_whitelist_check(&mut ctx);
+        let context = ctx.set_predecessor_account_id(TEST_OPERATOR_ID);
+        testing_env!(context);
+        ctx.change_staking_pool(to_valid_account_id("pool-1.near"));
+
+        set_predecessor_to_contract(&mut ctx);
+        ctx.on_staking_pool_whitelist_check(false, "pool-1.near".to_string());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "the staking pool must be confirmed by the staking pool whitelist contract before NEAR is staked with it"
+    )]
+    fn stake_batch_is_not_run_until_the_staking_pool_is_whitelisted() {
+        let mut ctx = TestContext::with_registered_account();
+        enable_whitelist_check(&mut ctx);
+        assert!(!ctx.staking_pool_info().whitelisted);
+
+        let mut context = ctx.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        ctx.deposit(None);
+        testing_env!(ctx.context.clone());
+        ctx.stake();
+    }
+
+    #[test]
+    fn stake_batch_is_run_once_the_staking_pool_is_whitelisted() {
+        let mut ctx = TestContext::with_registered_account();
+        enable_whitelist_check(&mut ctx);
+        set_predecessor_to_contract(&mut ctx);
+        ctx.on_staking_pool_whitelist_check(true, TEST_STAKING_POOL_ID.to_string());
+        assert!(ctx.staking_pool_info().whitelisted);
+
+        let mut context = ctx.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        ctx.deposit(None);
+        testing_env!(ctx.context.clone());
+        ctx.stake();
+        assert!(ctx.stake_batch_locked());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "the staking pool can only be changed while no STAKE is outstanding and no batches are pending"
+    )]
+    fn change_staking_pool_with_outstanding_stake() {
+        let mut ctx = TestContext::new();
+        ctx.total_stake.credit(YOCTO.into());
+        let context = ctx.set_predecessor_account_id(TEST_OPERATOR_ID);
+        testing_env!(context);
+        ctx.change_staking_pool(to_valid_account_id("pool-1.near"));
+    }
+
+    #[test]
+    #[should_panic(expected = "contract call is only allowed by an operator account")]
+    fn change_staking_pool_not_invoked_by_operator() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.change_staking_pool(to_valid_account_id("pool-1.near"));
+    }
+}
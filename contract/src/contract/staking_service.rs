@@ -4,8 +4,14 @@ use crate::interface::Operator;
 use crate::near::NO_DEPOSIT;
 use crate::*;
 use crate::{
-    domain::{self, Account, RedeemLock, RedeemStakeBatch, RegisteredAccount, StakeBatch},
+    domain::{
+        self, Account, AccountActivityKind, BatchKind, LockKind, NearLiquidityInflow,
+        NearLiquidityOutflow, RedeemLimit, RedeemLimitIncrease, RedeemLock, RedeemStakeBatch,
+        RegisteredAccount, StakeBatch, Workflow,
+    },
     errors::{
+        asserts::CONTRACT_PAUSED,
+        idle_near_sharing::IDLE_NEAR_LIQUIDITY_BUFFER_EXHAUSTED,
         illegal_state::{
             REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST, REDEEM_STAKE_BATCH_SHOULD_EXIST,
             STAKE_BATCH_SHOULD_EXIST,
@@ -13,24 +19,35 @@ use crate::{
         redeeming_stake_errors::NO_REDEEM_STAKE_BATCH_TO_RUN,
         staking_errors::{
             BLOCKED_BY_BATCH_RUNNING, BLOCKED_BY_STAKE_TOKEN_VALUE_REFRESH,
-            NO_FUNDS_IN_STAKE_BATCH_TO_WITHDRAW,
+            NO_FUNDS_IN_STAKE_BATCH_TO_WITHDRAW, STAKE_BATCH_DEPOSIT_COOLDOWN,
+            STAKE_BATCH_PENDING_VERIFICATION, STAKING_POOL_UNAVAILABLE,
         },
+        staking_pools::STAKING_POOL_WHITELIST_UNCONFIRMED,
         staking_service::{
-            BATCH_BALANCE_INSUFFICIENT, DEPOSIT_REQUIRED_FOR_STAKE,
-            INSUFFICIENT_STAKE_FOR_REDEEM_REQUEST, ZERO_REDEEM_AMOUNT,
+            BATCH_BALANCE_INSUFFICIENT, DEPOSIT_REQUIRED_FOR_STAKE, INSTANT_LIQUIDITY_UNAVAILABLE,
+            INSUFFICIENT_STAKE_FOR_REDEEM_REQUEST, NO_CLAIMED_NEAR_TO_RESTAKE,
+            NO_PENDING_STAKE_TOKEN_VALUE_JUMP, NO_QUEUED_WITHDRAWAL,
+            PENDING_STAKE_TOKEN_VALUE_JUMP_STALE, QUEUED_WITHDRAWAL_NOT_RELEASABLE,
+            QUEUED_WITHDRAWAL_RECIPIENT_MISMATCH, REDEEM_FORWARDING_TARGET_NOT_WHITELISTED,
+            STAKE_TARGET_DEPOSIT_INSUFFICIENT, ZERO_REDEEM_AMOUNT, ZERO_STAKE_TARGET,
         },
     },
     interface::{
-        staking_service::events, BatchId, RedeemStakeBatchReceipt, StakingService, YoctoNear,
-        YoctoStake,
+        staking_service::events, AccountReceipts, AccountRedeemStakeBatchReceipt,
+        AccountStakeBatchReceipt, BatchId, CurrentBatchIds, DepositSimulation, InstantRedeem,
+        QueuedWithdrawal, RedeemParticipation, RedeemParticipationStatus, RedeemSimulation,
+        RedeemStakeBatchReceipt, RedeemStakeBatchReceiptStatus, RedeemTimeline,
+        RedeemTimelineStage, RedeemTimelineStep, RoundingPolicy, RouteQuote, StakeConversion,
+        StakeSupplyStats, StakeTokenValueTwap, StakingPoolInfo, StakingService, WorkflowRun,
+        YoctoNear, YoctoStake,
     },
-    near::{log, YOCTO},
+    near::{log, EPOCH_LENGTH, UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK, YOCTO},
     staking_pool::StakingPoolPromiseBuilder,
 };
 use near_sdk::{
     env, ext_contract, near_bindgen,
     serde::{Deserialize, Serialize},
-    AccountId, Promise, PromiseOrValue,
+    serde_json, AccountId, Promise, PromiseOrValue,
 };
 
 #[near_bindgen]
@@ -39,12 +56,32 @@ impl StakingService for Contract {
         self.staking_pool_id.clone()
     }
 
+    fn staking_pool_info(&self) -> StakingPoolInfo {
+        StakingPoolInfo {
+            staking_pool_id: self.staking_pool_id.clone(),
+            available: self.is_staking_pool_available(),
+            unavailable_until: self
+                .staking_pool_unavailable_until
+                .filter(|_| !self.is_staking_pool_available())
+                .map(Into::into),
+            reward_fee_fraction: self.staking_pool_reward_fee_fraction.map(Into::into),
+            whitelisted: self.is_staking_pool_whitelisted(),
+        }
+    }
+
     fn stake_batch_receipt(&self, batch_id: BatchId) -> Option<interface::StakeBatchReceipt> {
         self.stake_batch_receipts
             .get(&batch_id.into())
             .map(interface::StakeBatchReceipt::from)
     }
 
+    fn unverified_stake_batches(&self) -> Vec<BatchId> {
+        self.unverified_stake_batches
+            .iter()
+            .map(|batch_id| (*batch_id).into())
+            .collect()
+    }
+
     fn redeem_stake_batch_receipt(
         &self,
         batch_id: BatchId,
@@ -54,22 +91,72 @@ impl StakingService for Contract {
             .map(interface::RedeemStakeBatchReceipt::from)
     }
 
-    #[payable]
-    fn deposit(&mut self) -> BatchId {
-        let mut account = self.predecessor_registered_account();
+    fn batch_audit(&self, batch_id: BatchId) -> Option<interface::BatchAudit> {
+        self.batch_audits
+            .get(&batch_id.into())
+            .map(interface::BatchAudit::from)
+    }
 
-        let near_amount = env::attached_deposit().into();
-        let batch_id = self.deposit_near_for_account_to_stake(&mut account, near_amount);
+    fn current_batch_ids(&self) -> CurrentBatchIds {
+        CurrentBatchIds {
+            batch_id_sequence: self.batch_id_sequence.into(),
+            stake_batch: self.stake_batch.map(|batch| batch.id().into()),
+            next_stake_batch: self.next_stake_batch.map(|batch| batch.id().into()),
+            redeem_stake_batch: self.redeem_stake_batch.map(|batch| batch.id().into()),
+            next_redeem_stake_batch: self.next_redeem_stake_batch.map(|batch| batch.id().into()),
+        }
+    }
 
-        self.check_min_required_near_deposit(&account, batch_id);
+    #[payable]
+    fn deposit(&mut self, referral_code: Option<String>) -> BatchId {
+        self.assert_not_paused();
+        self.measure_storage_delta("deposit", |contract| {
+            let mut account = contract.predecessor_registered_account();
+            contract.assert_stake_batches_verified(&account);
+            let near_amount = contract.attached_deposit_net_of_rehydration_fee(&mut account);
+            let batch_id =
+                contract.deposit_near_for_account_to_stake_and_save(&mut account, near_amount);
+            if let Some(code) = referral_code {
+                contract.attribute_referral_deposit(&code, batch_id, near_amount);
+            }
+            batch_id.into()
+        })
+    }
 
-        self.save_registered_account(&account);
-        self.log_stake_batch(batch_id);
-        batch_id.into()
+    #[payable]
+    fn deposit_for_stake_target(&mut self, target_stake_amount: YoctoStake) -> BatchId {
+        self.assert_not_paused();
+        self.measure_storage_delta("deposit_for_stake_target", |contract| {
+            let target_stake_amount: domain::YoctoStake = target_stake_amount.into();
+            assert!(target_stake_amount.value() > 0, ZERO_STAKE_TARGET);
+            let mut account = contract.predecessor_registered_account();
+            contract.assert_stake_batches_verified(&account);
+            let deposit = contract.attached_deposit_net_of_rehydration_fee(&mut account);
+            // STAKE to NEAR conversion rounds up, which guarantees that the NEAR amount converts
+            // back to at least the target STAKE amount
+            let near_amount = contract
+                .stake_token_value
+                .stake_to_near(target_stake_amount);
+            assert!(
+                deposit >= near_amount,
+                "{}: required deposit = {} yoctoNEAR",
+                STAKE_TARGET_DEPOSIT_INSUFFICIENT,
+                env::attached_deposit() - deposit.value() + near_amount.value()
+            );
+            let batch_id =
+                contract.deposit_near_for_account_to_stake_and_save(&mut account, near_amount);
+
+            let refund = deposit - near_amount;
+            if refund.value() > 0 {
+                Promise::new(env::predecessor_account_id()).transfer(refund.value());
+            }
+            batch_id.into()
+        })
     }
 
     /// stakes the funds collected within the contract level `StakeBatch`
     fn stake(&mut self) -> PromiseOrValue<BatchId> {
+        self.assert_not_paused();
         match self.stake_batch_lock {
             None => self.run_stake_batch().into(),
             Some(StakeLock::Staking) => panic!(BLOCKED_BY_BATCH_RUNNING),
@@ -86,9 +173,40 @@ impl StakingService for Contract {
 
     #[payable]
     fn deposit_and_stake(&mut self) -> PromiseOrValue<BatchId> {
-        let batch_id = self.deposit();
+        let batch_id = self.deposit(None);
 
-        if self.can_run_batch() {
+        if self.can_run_batch()
+            && self.is_staking_pool_available()
+            && self.is_staking_pool_whitelisted()
+            && self.stake_batch_deposit_cooldown_passed()
+        {
+            self.stake()
+        } else {
+            PromiseOrValue::Value(batch_id)
+        }
+    }
+
+    #[payable]
+    fn deposit_for(&mut self, account_id: ValidAccountId) -> BatchId {
+        self.assert_not_paused();
+        self.measure_storage_delta("deposit_for", |contract| {
+            let mut account = contract.registered_account(account_id.as_ref());
+            contract.assert_stake_batches_verified(&account);
+            contract
+                .deposit_attached_near_for_account_to_stake(&mut account)
+                .into()
+        })
+    }
+
+    #[payable]
+    fn deposit_and_stake_for(&mut self, account_id: ValidAccountId) -> PromiseOrValue<BatchId> {
+        let batch_id = self.deposit_for(account_id);
+
+        if self.can_run_batch()
+            && self.is_staking_pool_available()
+            && self.is_staking_pool_whitelisted()
+            && self.stake_batch_deposit_cooldown_passed()
+        {
             self.stake()
         } else {
             PromiseOrValue::Value(batch_id)
@@ -96,11 +214,13 @@ impl StakingService for Contract {
     }
 
     fn withdraw_from_stake_batch(&mut self, amount: YoctoNear) {
+        self.assert_not_paused();
         let mut account = self.predecessor_registered_account();
         self.claim_receipt_funds(&mut account);
+        self.assert_stake_batches_verified(&account);
 
         if let Some(mut batch) = account.next_stake_batch {
-            let amount = amount.into();
+            let amount = self.next_stake_batch_withdrawal_amount(batch, amount.into());
             let batch_id = batch.id();
 
             // remove funds from contract level batch
@@ -161,8 +281,10 @@ impl StakingService for Contract {
     }
 
     fn withdraw_all_from_stake_batch(&mut self) -> YoctoNear {
+        self.assert_not_paused();
         let mut account = self.predecessor_registered_account();
         self.claim_receipt_funds(&mut account);
+        self.assert_stake_batches_verified(&account);
 
         if let Some(batch) = account.next_stake_batch {
             let amount = batch.balance().amount();
@@ -216,26 +338,87 @@ impl StakingService for Contract {
     }
 
     fn redeem(&mut self, amount: YoctoStake) -> BatchId {
-        let mut account = self.predecessor_registered_account();
-        let batch_id = self.redeem_stake_for_account(&mut account, amount.into());
-        self.save_registered_account(&account);
-        self.log_redeem_stake_batch(batch_id.clone().into());
-        batch_id
+        self.assert_not_paused();
+        self.measure_storage_delta("redeem", |contract| {
+            let mut account = contract.predecessor_registered_account();
+            let batch_id = contract.redeem_stake_for_account(&mut account, amount.into());
+            contract.bill_account_storage_growth(&mut account);
+            contract.save_registered_account(&account);
+            contract.log_redeem_stake_batch(batch_id.clone().into());
+            batch_id
+        })
     }
 
     fn redeem_all(&mut self) -> Option<BatchId> {
+        self.assert_not_paused();
         let mut account = self.predecessor_registered_account();
         self.claim_receipt_funds(&mut account);
         account.stake.map(|stake| {
             let amount = stake.amount();
             let batch_id = self.redeem_stake_for_account(&mut account, amount);
+            self.bill_account_storage_growth(&mut account);
             self.save_registered_account(&account);
             self.log_redeem_stake_batch(batch_id.clone().into());
             batch_id
         })
     }
 
+    fn redeem_for_near_instant(&mut self, amount: YoctoStake) -> InstantRedeem {
+        self.assert_not_paused();
+        let amount: domain::YoctoStake = amount.into();
+        assert!(amount.value() > 0, ZERO_REDEEM_AMOUNT);
+        assert!(self.can_run_batch(), BLOCKED_BY_BATCH_RUNNING);
+
+        let mut account = self.predecessor_registered_account();
+        self.claim_receipt_funds(&mut account);
+        assert!(
+            account.can_redeem(amount),
+            INSUFFICIENT_STAKE_FOR_REDEEM_REQUEST
+        );
+
+        // NEAR to STAKE conversion rounds down, which guarantees that the redeemed STAKE converts
+        // back to at most the available liquidity
+        let liquidity = self.instant_exit_liquidity();
+        let redeemed = amount.min(self.stake_token_value.near_to_stake(liquidity));
+        let near = self.stake_token_value.stake_to_near(redeemed);
+        assert!(
+            redeemed.value() > 0 && near.value() > 0,
+            INSTANT_LIQUIDITY_UNAVAILABLE
+        );
+
+        account.record_redeem(redeemed, env::epoch_height().into());
+        account.apply_stake_debit(redeemed);
+        account.apply_near_credit(near);
+        self.save_registered_account(&account);
+        self.record_account_activity(
+            &account.id,
+            &account,
+            AccountActivityKind::Redeem,
+            redeemed.value(),
+            None,
+        );
+
+        self.consume_near_liquidity(near, NearLiquidityOutflow::InstantRedeem);
+        self.total_near.credit(near);
+        self.accumulate_total_stake_rebate();
+        self.total_stake.debit(redeemed);
+        self.stake_supply_stats
+            .record_burn(redeemed, env::epoch_height().into());
+        crate::near::events::ft_burn(
+            &env::predecessor_account_id(),
+            redeemed,
+            Some("instant redeem"),
+        );
+
+        InstantRedeem {
+            redeemed: redeemed.into(),
+            near: near.into(),
+            unfilled: (amount - redeemed).into(),
+        }
+    }
+
     fn remove_all_from_redeem_stake_batch(&mut self) -> YoctoStake {
+        self.assert_not_paused();
         let mut account = self.predecessor_registered_account();
         self.claim_receipt_funds(&mut account);
 
@@ -260,6 +443,7 @@ impl StakingService for Contract {
                 account.redeem_stake_batch = None;
                 self.save_registered_account(&account);
                 self.log_redeem_stake_batch(batch_id);
+                self.log_redeem_stake_batch_removal(batch_id, amount);
                 return amount.into();
             }
         } else if let Some(batch) = account.next_redeem_stake_batch {
@@ -282,6 +466,7 @@ impl StakingService for Contract {
             account.next_redeem_stake_batch = None;
             self.save_registered_account(&account);
             self.log_redeem_stake_batch(batch_id);
+            self.log_redeem_stake_batch_removal(batch_id, amount);
             return amount.into();
         }
 
@@ -289,6 +474,7 @@ impl StakingService for Contract {
     }
 
     fn remove_from_redeem_stake_batch(&mut self, amount: YoctoStake) {
+        self.assert_not_paused();
         let mut account = self.predecessor_registered_account();
         self.claim_receipt_funds(&mut account);
 
@@ -320,6 +506,7 @@ impl StakingService for Contract {
                 }
                 self.save_registered_account(&account);
                 self.log_redeem_stake_batch(batch.id());
+                self.log_redeem_stake_batch_removal(batch.id(), amount);
             }
         } else if let Some(mut batch) = account.next_redeem_stake_batch {
             let amount: domain::YoctoStake = amount.into();
@@ -348,19 +535,87 @@ impl StakingService for Contract {
             }
             self.save_registered_account(&account);
             self.log_redeem_stake_batch(batch.id());
+            self.log_redeem_stake_batch_removal(batch.id(), amount);
         }
     }
 
+    fn set_max_redeem_per_epoch(
+        &mut self,
+        max_redeem_per_epoch: Option<YoctoStake>,
+    ) -> Option<interface::RedeemLimit> {
+        let mut account = self.predecessor_registered_account();
+        let epoch_height: domain::EpochHeight = env::epoch_height().into();
+        account.apply_redeem_limit_increase(epoch_height);
+
+        let max_redeem_per_epoch: Option<domain::YoctoStake> = max_redeem_per_epoch.map(Into::into);
+        let (redeem_limit, effective_epoch_height) =
+            match (account.redeem_limit, max_redeem_per_epoch) {
+                (None, None) => (None, epoch_height),
+                // setting a limit where there was none lowers the limit
+                (None, Some(max_redeem_per_epoch)) => (
+                    Some(RedeemLimit::new(max_redeem_per_epoch, epoch_height)),
+                    epoch_height,
+                ),
+                (Some(mut limit), Some(max_redeem_per_epoch))
+                    if max_redeem_per_epoch <= limit.max_redeem_per_epoch() =>
+                {
+                    limit.lower(max_redeem_per_epoch);
+                    (Some(limit), epoch_height)
+                }
+                (Some(mut limit), max_redeem_per_epoch) => {
+                    let effective_epoch_height =
+                        epoch_height + self.config.redeem_limit_increase_delay_epochs();
+                    limit.schedule_increase(RedeemLimitIncrease {
+                        max_redeem_per_epoch,
+                        effective_epoch_height,
+                    });
+                    (Some(limit), effective_epoch_height)
+                }
+            };
+        account.redeem_limit = redeem_limit;
+
+        self.bill_account_storage_growth(&mut account);
+        self.save_registered_account(&account);
+        log(events::RedeemLimitUpdated {
+            account_id: env::predecessor_account_id(),
+            max_redeem_per_epoch: max_redeem_per_epoch.map(|amount| amount.value()),
+            effective_epoch_height: effective_epoch_height.value(),
+        });
+        account
+            .redeem_limit
+            .map(|limit| interface::RedeemLimit::from(limit, epoch_height))
+    }
+
+    fn redeem_limit(&self, account_id: ValidAccountId) -> Option<interface::RedeemLimit> {
+        let epoch_height: domain::EpochHeight = env::epoch_height().into();
+        self.lookup_registered_account(account_id.as_ref())
+            .map(|account| account.redeem_limit)
+            .flatten()
+            .map(|limit| limit.apply_pending_increase(epoch_height))
+            .flatten()
+            .map(|limit| interface::RedeemLimit::from(limit, epoch_height))
+    }
+
     fn unstake(&mut self) -> Promise {
+        self.assert_not_paused();
         assert!(self.can_run_batch(), BLOCKED_BY_BATCH_RUNNING);
 
         match self.redeem_stake_batch_lock {
             None => {
+                // swept idle NEAR is redeemed once the sweep cycle has run its course
+                self.cycle_idle_near_sweep();
+                // scheduled redeem split portions are redeemed into the batch that is about to run
+                self.apply_redeem_splits();
                 assert!(
                     self.redeem_stake_batch.is_some(),
                     NO_REDEEM_STAKE_BATCH_TO_RUN
                 );
+                self.start_workflow(Workflow::Unstake);
                 self.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+                self.record_lock_acquired(
+                    LockKind::Redeem,
+                    self.redeem_stake_batch.map(|batch| batch.id()),
+                );
 
                 self.staking_pool_promise()
                     .get_account()
@@ -368,11 +623,13 @@ impl StakingService for Contract {
                     .then(self.invoke_on_run_redeem_stake_batch())
                     .then(self.invoke_clear_redeem_lock())
             }
-            Some(RedeemLock::PendingWithdrawal) => self
-                .staking_pool_promise()
-                .get_account()
-                .promise()
-                .then(self.invoke_on_redeeming_stake_pending_withdrawal()),
+            Some(RedeemLock::PendingWithdrawal) => {
+                self.start_workflow(Workflow::Withdraw);
+                self.staking_pool_promise()
+                    .get_account()
+                    .promise()
+                    .then(self.invoke_on_redeeming_stake_pending_withdrawal())
+            }
             // this should already be handled by above assert and should never be hit
             // but it was added to satisfy the match clause for completeness
             Some(RedeemLock::Unstaking) => panic!(BLOCKED_BY_BATCH_RUNNING),
@@ -402,39 +659,260 @@ impl StakingService for Contract {
         }
     }
 
+    fn run_pending_workflows(&mut self) -> WorkflowRun {
+        self.assert_not_paused();
+        // NOTE: the workflow promises are scheduled even though they are not returned
+        if self.can_stake() {
+            let batch_id = self.stake_batch.expect(STAKE_BATCH_SHOULD_EXIST).id();
+            self.stake();
+            return WorkflowRun::Stake(batch_id.into());
+        }
+
+        let workflow = match self.redeem_stake_batch_lock {
+            Some(RedeemLock::PendingWithdrawal) => Workflow::Withdraw,
+            _ => Workflow::Unstake,
+        };
+        if self.can_unstake() && !self.is_workflow_cooling_down(workflow) {
+            let batch_id: BatchId = self
+                .redeem_stake_batch
+                .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST)
+                .id()
+                .into();
+            self.unstake();
+            return match workflow {
+                Workflow::Withdraw => WorkflowRun::Withdraw(batch_id),
+                _ => WorkflowRun::Unstake(batch_id),
+            };
+        }
+
+        WorkflowRun::Idle
+    }
+
     fn pending_withdrawal(&self) -> Option<RedeemStakeBatchReceipt> {
         self.get_pending_withdrawal()
             .map(RedeemStakeBatchReceipt::from)
     }
 
+    fn account_receipts(&self, account_id: ValidAccountId) -> Option<AccountReceipts> {
+        let account = self.accounts.get(&Hash::from(account_id))?;
+
+        let stake_batch_receipts = [account.stake_batch, account.next_stake_batch]
+            .iter()
+            .flatten()
+            .filter_map(|batch| {
+                self.load_stake_batch_receipt(batch.id()).map(|receipt| {
+                    let staked_near = batch.balance().amount();
+                    AccountStakeBatchReceipt {
+                        batch_id: batch.id().into(),
+                        staked_near: staked_near.into(),
+                        stake: receipt
+                            .stake_token_value()
+                            .near_to_stake(staked_near)
+                            .into(),
+                    }
+                })
+            })
+            .collect();
+
+        let batch_pending_withdrawal_id = self.redeem_stake_batch_pending_withdrawal_id();
+        let redeem_stake_batch_receipts =
+            [account.redeem_stake_batch, account.next_redeem_stake_batch]
+                .iter()
+                .flatten()
+                .filter_map(|batch| {
+                    self.redeem_stake_batch_receipts
+                        .get(&batch.id())
+                        .map(|receipt| {
+                            let redeemed_stake = batch.balance().amount();
+                            AccountRedeemStakeBatchReceipt {
+                                batch_id: batch.id().into(),
+                                redeemed_stake: redeemed_stake.into(),
+                                near: receipt
+                                    .stake_token_value()
+                                    .stake_to_near(redeemed_stake)
+                                    .into(),
+                                status: if batch_pending_withdrawal_id == Some(batch.id()) {
+                                    RedeemStakeBatchReceiptStatus::PendingWithdrawal
+                                } else {
+                                    RedeemStakeBatchReceiptStatus::Claimable
+                                },
+                            }
+                        })
+                })
+                .collect();
+
+        Some(AccountReceipts {
+            stake_batch_receipts,
+            redeem_stake_batch_receipts,
+        })
+    }
+
+    fn redeem_participation(
+        &self,
+        batch_id: BatchId,
+        account_id: ValidAccountId,
+    ) -> Option<RedeemParticipation> {
+        let account = self.accounts.get(&Hash::from(account_id.clone()))?;
+        let batch_id: domain::BatchId = batch_id.into();
+        let batch = [account.redeem_stake_batch, account.next_redeem_stake_batch]
+            .iter()
+            .flatten()
+            .find(|batch| batch.id() == batch_id)
+            .copied()?;
+        let redeemed_stake = batch.balance().amount();
+        let receipt = self.load_redeem_stake_batch_receipt(batch_id);
+        let status = match receipt {
+            None => RedeemParticipationStatus::Pending,
+            Some(_) if self.redeem_stake_batch_pending_withdrawal_id() == Some(batch_id) => {
+                RedeemParticipationStatus::PendingWithdrawal
+            }
+            Some(_) => RedeemParticipationStatus::Claimable,
+        };
+        Some(RedeemParticipation {
+            batch_id: batch_id.into(),
+            account_id: account_id.into(),
+            redeemed_stake: redeemed_stake.into(),
+            stake_token_value: receipt
+                .as_ref()
+                .map(|receipt| receipt.stake_token_value().into()),
+            near: receipt.map(|receipt| {
+                receipt
+                    .stake_token_value()
+                    .stake_to_near(redeemed_stake)
+                    .into()
+            }),
+            status,
+        })
+    }
+
     fn claim_receipts(&mut self) {
+        self.assert_not_paused();
         let mut account = self.predecessor_registered_account();
         self.claim_receipt_funds(&mut account);
+        // the claimed redeem proceeds were forwarded by the claim - NEAR that was held back, e.g., by
+        // the per epoch withdrawal limit, is forwarded as well
+        let near_balance = account.near.map_or(0.into(), |balance| balance.amount());
+        self.forward_redeem_proceeds(&mut account, near_balance);
     }
 
-    fn withdraw(&mut self, amount: interface::YoctoNear) {
-        let mut account = self.predecessor_registered_account();
-        self.withdraw_near_funds(&mut account, amount.into());
+    fn claim_receipts_for(&mut self, account_id: ValidAccountId) {
+        let mut account = self.registered_account(account_id.as_ref());
+        let stake_balance = account.stake.map_or(0, |balance| balance.amount().value());
+        let near_balance = account.near.map_or(0, |balance| balance.amount().value());
+        self.claim_receipt_funds(&mut account);
+        let stake = account
+            .stake
+            .map_or(0, |balance| balance.amount().value())
+            .saturating_sub(stake_balance);
+        let near = account
+            .near
+            .map_or(0, |balance| balance.amount().value())
+            .saturating_sub(near_balance);
+        if stake > 0 || near > 0 {
+            log(events::ReceiptsClaimedFor {
+                account_id: account_id.into(),
+                claimed_by: env::predecessor_account_id(),
+                stake,
+                near,
+            });
+        }
     }
 
-    fn withdraw_all(&mut self) -> interface::YoctoNear {
+    fn redeem_and_restake_rewards(&mut self) -> BatchId {
+        self.assert_not_paused();
         let mut account = self.predecessor_registered_account();
+        self.assert_stake_batches_verified(&account);
+        // only the NEAR that is claimed is restaked - NEAR that the account already holds is left
+        // alone, i.e., it may have been set aside for withdrawal
+        let near_balance_before_claim = account.near.map_or(0.into(), |balance| balance.amount());
+        // the claimed NEAR is restaked here instead of being compounded or forwarded by the claim
+        let preferences = account.preferences;
+        let redeem_forwarding = account.redeem_forwarding.take();
+        account.preferences.auto_compound = false;
         self.claim_receipt_funds(&mut account);
-        match account.near {
-            None => 0.into(),
-            Some(balance) => {
-                self.withdraw_near_funds(&mut account, balance.amount());
-                balance.amount().into()
+        account.preferences = preferences;
+        account.redeem_forwarding = redeem_forwarding;
+
+        let amount =
+            account.near.map_or(0.into(), |balance| balance.amount()) - near_balance_before_claim;
+        assert!(amount.value() > 0, NO_CLAIMED_NEAR_TO_RESTAKE);
+        account.apply_near_debit(amount);
+        self.debit_total_near(amount);
+        let batch_id = self.add_near_to_stake_batch(&mut account, amount);
+        self.check_min_required_near_deposit(&account, batch_id);
+
+        self.bill_account_storage_growth(&mut account);
+        self.save_registered_account(&account);
+        self.record_account_activity(
+            &account.id,
+            &account,
+            AccountActivityKind::Deposit,
+            amount.value(),
+            Some(batch_id),
+        );
+        self.log_stake_batch(batch_id);
+        batch_id.into()
+    }
+
+    fn set_redeem_forwarding(&mut self, target_stake_contract: Option<ValidAccountId>) {
+        let mut account = self.predecessor_registered_account();
+        account.redeem_forwarding = target_stake_contract.map(|target| {
+            let target = Hash::from(target);
+            assert!(
+                self.redeem_forwarding_targets.contains_key(&target),
+                REDEEM_FORWARDING_TARGET_NOT_WHITELISTED
+            );
+            target
+        });
+        self.save_registered_account(&account);
+    }
+
+    fn redeem_forwarding(&self, account_id: ValidAccountId) -> Option<AccountId> {
+        self.lookup_registered_account(account_id.as_ref())?
+            .redeem_forwarding
+            .and_then(|target| self.redeem_forwarding_targets.get(&target))
+    }
+
+    #[payable]
+    fn deposit_redeem_forwarding(&mut self, account_id: ValidAccountId) -> BatchId {
+        self.assert_not_paused();
+        let mut account = self.registered_account(account_id.as_ref());
+        self.assert_stake_batches_verified(&account);
+        self.deposit_attached_near_for_account_to_stake(&mut account)
+            .into()
+    }
+
+    fn withdraw(&mut self, amount: interface::YoctoNear) {
+        self.assert_not_paused();
+        self.watch_aggregates(|contract| {
+            let mut account = contract.predecessor_registered_account();
+            contract.withdraw_near_funds(&mut account, amount.into());
+        })
+    }
+
+    fn withdraw_all(&mut self) -> interface::YoctoNear {
+        self.assert_not_paused();
+        self.watch_aggregates(|contract| {
+            let mut account = contract.predecessor_registered_account();
+            contract.claim_receipt_funds(&mut account);
+            match account.near {
+                None => 0.into(),
+                Some(balance) => {
+                    contract.withdraw_near_funds(&mut account, balance.amount());
+                    balance.amount().into()
+                }
             }
-        }
+        })
     }
 
     fn transfer_near(&mut self, recipient: ValidAccountId, amount: interface::YoctoNear) {
+        self.assert_not_paused();
         let mut account = self.predecessor_registered_account();
         self.transfer_near_funds(&mut account, amount.into(), recipient);
     }
 
     fn transfer_all_near(&mut self, recipient: ValidAccountId) -> interface::YoctoNear {
+        self.assert_not_paused();
         let mut account = self.predecessor_registered_account();
         self.claim_receipt_funds(&mut account);
         match account.near {
@@ -446,6 +924,28 @@ impl StakingService for Contract {
         }
     }
 
+    fn queued_withdrawal(&self, account_id: ValidAccountId) -> Option<QueuedWithdrawal> {
+        self.queued_withdrawals
+            .get(&Hash::from(account_id.as_ref()))
+            .map(QueuedWithdrawal::from)
+    }
+
+    fn process_queued_withdrawal(&mut self, account_id: ValidAccountId) -> YoctoNear {
+        self.assert_not_paused();
+        let epoch_height: domain::EpochHeight = env::epoch_height().into();
+        let withdrawal = self
+            .queued_withdrawals
+            .get(&Hash::from(account_id.as_ref()))
+            .expect(NO_QUEUED_WITHDRAWAL);
+        assert!(
+            withdrawal.is_releasable(epoch_height),
+            QUEUED_WITHDRAWAL_NOT_RELEASABLE
+        );
+        let available = self.available_for_withdrawal(epoch_height);
+        self.release_queued_withdrawal_funds(account_id.as_ref(), available)
+            .into()
+    }
+
     fn min_required_deposit_to_stake(&self) -> YoctoNear {
         self.min_required_near_deposit().into()
     }
@@ -454,7 +954,9 @@ impl StakingService for Contract {
         match self.stake_batch_lock {
             None => {
                 assert!(!self.is_unstaking(), BLOCKED_BY_BATCH_RUNNING);
+                self.start_workflow(Workflow::RefreshStakeTokenValue);
                 self.stake_batch_lock = Some(StakeLock::RefreshingStakeTokenValue);
+                self.record_lock_acquired(LockKind::RefreshStakeTokenValue, None);
                 StakingPoolPromiseBuilder::new(self.staking_pool_id.clone(), &self.config)
                     .ping()
                     .get_account()
@@ -468,1265 +970,3508 @@ impl StakingService for Contract {
         }
     }
 
+    fn refresh_staking_pool_reward_fee(&mut self) -> Promise {
+        self.staking_pool_promise()
+            .get_reward_fee_fraction()
+            .promise()
+            .then(self.invoke_on_refresh_reward_fee_fraction())
+    }
+
     fn stake_token_value(&self) -> interface::StakeTokenValue {
         self.stake_token_value.into()
     }
-}
 
-// staking pool func call invocations
-impl Contract {
-    fn log_stake_batch(&self, batch_id: domain::BatchId) {
-        if let Some(batch) = self.stake_batch {
-            if batch_id == batch.id() {
-                log(events::StakeBatch::from(batch));
-            }
-        } else if let Some(batch) = self.next_stake_batch {
-            if batch_id == batch.id() {
-                log(events::StakeBatch::from(batch));
-            }
-        } else {
-            log(events::StakeBatchCancelled {
-                batch_id: batch_id.value(),
-            });
+    fn near_to_stake(&self, amount: YoctoNear) -> StakeConversion {
+        StakeConversion {
+            stake: self
+                .stake_token_value
+                .near_to_stake(amount.clone().into())
+                .into(),
+            near: amount,
+            stake_token_value_block_time_height: self.stake_token_value.block_time_height().into(),
         }
     }
 
-    fn log_redeem_stake_batch(&self, batch_id: domain::BatchId) {
-        if let Some(batch) = self.redeem_stake_batch {
-            if batch_id == batch.id() {
-                log(events::RedeemStakeBatch::from(batch));
-            }
-        } else if let Some(batch) = self.next_redeem_stake_batch {
-            if batch_id == batch.id() {
-                log(events::RedeemStakeBatch::from(batch));
-            }
-        } else {
-            log(events::RedeemStakeBatchCancelled {
-                batch_id: batch_id.value(),
-            });
+    fn stake_to_near(&self, amount: YoctoStake) -> StakeConversion {
+        StakeConversion {
+            near: self
+                .stake_token_value
+                .stake_to_near(amount.clone().into())
+                .into(),
+            stake: amount,
+            stake_token_value_block_time_height: self.stake_token_value.block_time_height().into(),
         }
     }
-}
 
-/// NEAR transfers
-impl Contract {
-    fn withdraw_near_funds(&mut self, account: &mut RegisteredAccount, amount: domain::YoctoNear) {
-        self.claim_receipt_funds(account);
-        account.apply_near_debit(amount);
-        self.save_registered_account(&account);
-        // check if there are enough funds to fulfill the request - if not then draw from liquidity
-        if self.total_near.amount() < amount {
-            // access liquidity
-            // NOTE: will panic if there are not enough funds in liquidity pool
-            //       - should never panic unless there is a bug
-            let difference = amount - self.total_near.amount();
-            self.near_liquidity_pool -= difference;
-            self.total_near.credit(difference);
+    fn rounding_policy(&self) -> RoundingPolicy {
+        RoundingPolicy::current()
+    }
+
+    fn entry_quote(&self, near_amount: YoctoNear) -> RouteQuote {
+        let near: domain::YoctoNear = near_amount.into();
+        let delay_epochs = self
+            .staking_pool_unavailable_until
+            .filter(|_| !self.is_staking_pool_available())
+            .map_or(0, |epoch_height| epoch_height.value() - env::epoch_height());
+        RouteQuote {
+            near: near.into(),
+            stake: self.stake_token_value.near_to_stake(near).into(),
+            rate: self.stake_token_value.stake_to_near(YOCTO.into()).into(),
+            stake_token_value_block_time_height: self.stake_token_value.block_time_height().into(),
+            fee: 0.into(),
+            staking_pool_reward_fee: self.staking_pool_reward_fee_fraction.map(Into::into),
+            delay_epochs,
+            instant_liquidity: self.instant_exit_liquidity().into(),
+            instant: delay_epochs == 0,
+            executable: near >= self.min_required_near_deposit(),
         }
-        self.total_near.debit(amount);
-        Promise::new(env::predecessor_account_id()).transfer(amount.value());
     }
 
-    fn transfer_near_funds(
-        &mut self,
-        account: &mut RegisteredAccount,
-        amount: domain::YoctoNear,
-        recipient: ValidAccountId,
-    ) {
-        self.claim_receipt_funds(account);
-        account.apply_near_debit(amount);
-        self.save_registered_account(&account);
-        // check if there are enough funds to fulfill the request - if not then draw from liquidity
-        if self.total_near.amount() < amount {
-            // access liquidity
-            // NOTE: will panic if there are not enough funds in liquidity pool
-            //       - should never panic unless there is a bug
-            let difference = amount - self.total_near.amount();
-            self.near_liquidity_pool -= difference;
-            self.total_near.credit(difference);
+    fn exit_quote(&self, stake_amount: YoctoStake) -> RouteQuote {
+        let stake: domain::YoctoStake = stake_amount.into();
+        let near = self.stake_token_value.stake_to_near(stake);
+        // the next redeem batch can only be run once the current pending withdrawal has cleared
+        let pending_withdrawal_epochs = self.get_pending_withdrawal().map_or(0, |receipt| {
+            receipt
+                .unstaked_near_withdrawal_availability()
+                .value()
+                .saturating_sub(env::epoch_height())
+        });
+        let instant_liquidity = self.instant_exit_liquidity();
+        RouteQuote {
+            near: near.into(),
+            stake: stake.into(),
+            rate: self.stake_token_value.stake_to_near(YOCTO.into()).into(),
+            stake_token_value_block_time_height: self.stake_token_value.block_time_height().into(),
+            fee: 0.into(),
+            staking_pool_reward_fee: self.staking_pool_reward_fee_fraction.map(Into::into),
+            delay_epochs: UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK.value()
+                + pending_withdrawal_epochs,
+            instant_liquidity: instant_liquidity.into(),
+            instant: near.value() > 0 && instant_liquidity >= near,
+            executable: stake.value() > 0,
         }
-        self.total_near.debit(amount);
-        Promise::new(recipient.as_ref().to_string()).transfer(amount.value());
     }
-}
-
-impl Contract {
-    fn run_stake_batch(&mut self) -> Promise {
-        assert!(self.can_run_batch(), BLOCKED_BY_BATCH_RUNNING);
-        let batch = self.stake_batch.expect(STAKE_BATCH_SHOULD_EXIST);
 
-        self.stake_batch_lock = Some(StakeLock::Staking);
-
-        self.distribute_earnings();
+    fn simulate_deposit(&self, amount: YoctoNear) -> DepositSimulation {
+        let near: domain::YoctoNear = amount.into();
+        let batch = if self.stake_batch_locked() {
+            self.next_stake_batch
+        } else {
+            self.stake_batch
+        };
+        let batch_id = batch.map_or_else(
+            || self.batch_id_sequence.next().with_kind(BatchKind::Stake),
+            |batch| batch.id(),
+        );
 
-        if self.is_liquidity_needed() {
-            self.staking_pool_promise()
-                .get_account()
-                .promise()
-                .then(self.invoke_on_run_stake_batch())
-                .then(self.invoke_clear_stake_lock())
+        let mut violations = self.pause_violations();
+        if near.value() == 0 {
+            violations.push(DEPOSIT_REQUIRED_FOR_STAKE.to_string());
         } else {
-            // if liquidity is not needed, then lets stake it
-            // NOTE: liquidity belongs to the stakers - some will leak over when we withdraw all from
-            //       the staking pool because of the shares rounding issue on the staking pool side
-            let stake_amount = batch.balance().amount() + self.near_liquidity_pool;
-            self.near_liquidity_pool = 0.into();
-            self.staking_pool_promise()
-                .deposit_and_stake(stake_amount)
-                .get_account()
-                .promise()
-                .then(self.invoke_on_deposit_and_stake(None))
-                .then(self.invoke_clear_stake_lock())
+            let min_required_near_deposit = self.min_required_near_deposit();
+            if near < min_required_near_deposit {
+                violations.push(format!(
+                    "minimum required NEAR deposit is: {}",
+                    min_required_near_deposit
+                ));
+            }
+        }
+
+        DepositSimulation {
+            near: near.into(),
+            stake: self.stake_token_value.near_to_stake(near).into(),
+            batch_id: batch_id.into(),
+            stake_token_value_block_time_height: self.stake_token_value.block_time_height().into(),
+            violations,
         }
     }
 
-    /// check that batch NEAR amount will issue at least 1 yoctoSTAKE
-    /// we never want to issue 0 yoctoSTAKE tokens if NEAR is deposited and staked
-    ///
-    /// the min required NEAR deposit is calculated using the cached STAKE token value
-    /// thus, to be on the safe side, we will require that minimum amount of NEAR deposit should be
-    /// enough for 1000 yoctoSTAKE
-    fn check_min_required_near_deposit(&self, account: &Account, batch_id: domain::BatchId) {
-        if let Some(batch) = account.stake_batch(batch_id) {
-            self.check_stake_batch_min_required_near_balance(batch)
-        }
-    }
-
-    fn check_stake_batch_min_required_near_balance(&self, batch: StakeBatch) {
-        let min_required_near_deposit = self.min_required_near_deposit();
-        assert!(
-            batch.balance().amount() >= min_required_near_deposit,
-            "minimum required NEAR deposit is: {}",
-            min_required_near_deposit
+    fn simulate_redeem(&self, amount: YoctoStake) -> RedeemSimulation {
+        let stake: domain::YoctoStake = amount.into();
+        let epoch_height: domain::EpochHeight = env::epoch_height().into();
+        let (batch, unstake_epoch_height) = match self.redeem_stake_batch_lock {
+            None => (self.redeem_stake_batch, epoch_height),
+            // the next batch can only be unstaked once the current batch's unstaked NEAR has been
+            // withdrawn
+            _ => (
+                self.next_redeem_stake_batch,
+                self.get_pending_withdrawal()
+                    .map_or(
+                        epoch_height + UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK,
+                        |receipt| receipt.unstaked_near_withdrawal_availability(),
+                    )
+                    .max(epoch_height),
+            ),
+        };
+        let batch_id = batch.map_or_else(
+            || self.batch_id_sequence.next().with_kind(BatchKind::Redeem),
+            |batch| batch.id(),
         );
-    }
-
-    fn min_required_near_deposit(&self) -> domain::YoctoNear {
-        self.stake_token_value.stake_to_near(1000.into())
-    }
 
-    pub(crate) fn get_pending_withdrawal(&self) -> Option<domain::RedeemStakeBatchReceipt> {
-        self.redeem_stake_batch
-            .map(|batch| self.redeem_stake_batch_receipts.get(&batch.id()))
-            .flatten()
-    }
+        let mut violations = self.pause_violations();
+        if stake.value() == 0 {
+            violations.push(ZERO_REDEEM_AMOUNT.to_string());
+        }
 
-    fn can_run_batch(&self) -> bool {
-        !self.stake_batch_locked() && !self.is_unstaking()
+        RedeemSimulation {
+            stake: stake.into(),
+            near: self.stake_token_value.stake_to_near(stake).into(),
+            batch_id: batch_id.into(),
+            available_epoch_height: (unstake_epoch_height
+                + UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK)
+                .into(),
+            stake_token_value_block_time_height: self.stake_token_value.block_time_height().into(),
+            violations,
+        }
     }
 
-    fn can_unstake(&self) -> bool {
-        if self.can_run_batch() {
-            match self.redeem_stake_batch_lock {
-                None => self.redeem_stake_batch.is_some(),
-                Some(RedeemLock::PendingWithdrawal) => {
-                    let batch = self
-                        .redeem_stake_batch
-                        .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST);
-                    let batch_receipt = self
-                        .redeem_stake_batch_receipts
-                        .get(&batch.id())
-                        .expect(REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST);
-                    batch_receipt.unstaked_funds_available_for_withdrawal()
+    fn stake_token_value_twap(&self, window_epochs: u32) -> Option<StakeTokenValueTwap> {
+        assert!(window_epochs > 0, "window_epochs must be > 0");
+
+        let to_epoch_height = env::epoch_height();
+        // first epoch in the window - the window includes the current epoch
+        let window_start = to_epoch_height.saturating_sub(window_epochs as u64 - 1);
+
+        let mut epoch_height = self.last_stake_token_value_checkpoint?;
+        // each checkpoint's value is in effect from its epoch until the next checkpoint's epoch
+        let mut period_end = to_epoch_height;
+        let mut weighted_sum = U256::from(0);
+        let mut epochs = 0_u64;
+        loop {
+            let checkpoint = self
+                .stake_token_value_checkpoints
+                .get(&epoch_height)
+                .expect("STAKE token value checkpoint should exist");
+            let period_start = std::cmp::max(checkpoint.epoch_height().value(), window_start);
+            let period_epochs = period_end - period_start + 1;
+            let value = checkpoint.stake_token_value().stake_to_near(YOCTO.into());
+            weighted_sum += U256::from(value) * U256::from(period_epochs);
+            epochs += period_epochs;
+
+            if period_start == window_start {
+                break;
+            }
+            match checkpoint.prev_epoch_height() {
+                Some(prev_epoch_height) => {
+                    period_end = checkpoint.epoch_height().value() - 1;
+                    epoch_height = prev_epoch_height;
                 }
-                Some(RedeemLock::Unstaking) => false,
+                None => break,
             }
-        } else {
-            self.can_run_batch()
         }
-    }
-
-    /// batches the NEAR to stake at the contract level and account level
-    ///
-    /// ## Panics
-    /// if [amount] is zero
-    ///
-    /// ## Notes
-    /// - before applying the deposit, batch receipts are processed [claim_receipt_funds]
-    pub(crate) fn deposit_near_for_account_to_stake(
-        &mut self,
-        account: &mut RegisteredAccount,
-        amount: domain::YoctoNear,
-    ) -> domain::BatchId {
-        assert!(amount.value() > 0, DEPOSIT_REQUIRED_FOR_STAKE);
 
-        self.claim_receipt_funds(account);
+        Some(StakeTokenValueTwap {
+            value: (weighted_sum / U256::from(epochs)).as_u128().into(),
+            from_epoch_height: domain::EpochHeight(to_epoch_height + 1 - epochs).into(),
+            to_epoch_height: domain::EpochHeight(to_epoch_height).into(),
+            epochs: epochs as u32,
+        })
+    }
 
-        // use current batch if not staking, i.e., the stake batch is not running
-        if !self.stake_batch_locked() {
-            // apply at contract level
-            let mut contract_batch = self.stake_batch.unwrap_or_else(|| self.new_stake_batch());
-            contract_batch.add(amount);
-            self.stake_batch = Some(contract_batch);
+    fn stake_token_value_history(&self, limit: u32) -> Vec<interface::StakeTokenValue> {
+        let limit = limit.min(domain::MAX_STAKE_TOKEN_VALUE_HISTORY_LEN) as usize;
+        let mut history = Vec::with_capacity(limit);
+        let mut epoch_height = self.last_stake_token_value_checkpoint;
+        while let Some(height) = epoch_height {
+            if history.len() == limit {
+                break;
+            }
+            let checkpoint = self
+                .stake_token_value_checkpoints
+                .get(&height)
+                .expect("STAKE token value checkpoint should exist");
+            history.push(checkpoint.stake_token_value().into());
+            epoch_height = checkpoint.prev_epoch_height();
+        }
+        history
+    }
 
-            // apply at account level
-            // NOTE: account batch ID must match contract batch ID
-            let mut account_batch = account
-                .stake_batch
-                .unwrap_or_else(|| contract_batch.id().new_stake_batch());
-            account_batch.add(amount);
-            account.stake_batch = Some(account_batch);
+    fn redeem_timeline(&self, batch_id: BatchId) -> Option<RedeemTimeline> {
+        let batch_id: domain::BatchId = batch_id.into();
+        let epoch_height: domain::EpochHeight = env::epoch_height().into();
+        let is_current_batch = self
+            .redeem_stake_batch
+            .map_or(false, |batch| batch.id() == batch_id);
+
+        let (unstake, unstake_epoch_height) = match self.load_redeem_stake_batch_receipt(batch_id) {
+            Some(receipt) => {
+                let unstaked = receipt.stake_token_value().block_time_height();
+                let step = RedeemTimelineStep {
+                    stage: RedeemTimelineStage::Unstake,
+                    epoch_height: unstaked.epoch_height().into(),
+                    block_height: unstaked.block_height().into(),
+                    reached: true,
+                };
+                (step, unstaked.epoch_height())
+            }
+            None if is_current_batch => (
+                Self::redeem_timeline_step(RedeemTimelineStage::Unstake, epoch_height, false),
+                epoch_height,
+            ),
+            None if self
+                .next_redeem_stake_batch
+                .map_or(false, |batch| batch.id() == batch_id) =>
+            {
+                // the next batch can only be unstaked once the current batch's unstaked NEAR has
+                // been withdrawn
+                let unstake_epoch_height = self
+                    .get_pending_withdrawal()
+                    .map_or(
+                        epoch_height + UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK,
+                        |receipt| receipt.unstaked_near_withdrawal_availability(),
+                    )
+                    .max(epoch_height);
+                (
+                    Self::redeem_timeline_step(
+                        RedeemTimelineStage::Unstake,
+                        unstake_epoch_height,
+                        false,
+                    ),
+                    unstake_epoch_height,
+                )
+            }
+            None => return None,
+        };
 
-            account_batch.id()
+        // the receipt for the current batch is pending withdrawal
+        let withdrawn = unstake.reached && !is_current_batch;
+        let withdrawal_epoch_height =
+            unstake_epoch_height + UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK;
+        let withdrawal_epoch_height = if withdrawn {
+            withdrawal_epoch_height
         } else {
-            // apply at contract level
-            let mut contract_batch = self
-                .next_stake_batch
-                .unwrap_or_else(|| self.new_stake_batch());
-            contract_batch.add(amount);
-            self.next_stake_batch = Some(contract_batch);
+            withdrawal_epoch_height.max(epoch_height)
+        };
+        let withdrawal = Self::redeem_timeline_step(
+            RedeemTimelineStage::Withdrawal,
+            withdrawal_epoch_height,
+            withdrawn,
+        );
+        let claim = Self::redeem_timeline_step(
+            RedeemTimelineStage::Claim,
+            withdrawal_epoch_height,
+            withdrawn,
+        );
 
-            // apply at account level
-            // NOTE: account batch ID must match contract batch ID
-            let mut account_batch = account
-                .next_stake_batch
-                .unwrap_or_else(|| contract_batch.id().new_stake_batch());
-            account_batch.add(amount);
-            account.next_stake_batch = Some(account_batch);
+        Some(RedeemTimeline {
+            batch_id: batch_id.into(),
+            steps: vec![unstake, withdrawal, claim],
+            block_time_height: domain::BlockTimeHeight::from_env().into(),
+        })
+    }
 
-            account_batch.id()
+    fn stake_supply_stats(&self) -> StakeSupplyStats {
+        let epoch_height: domain::EpochHeight = env::epoch_height().into();
+        StakeSupplyStats {
+            epoch_height: epoch_height.into(),
+            stake_minted_this_epoch: self.stake_supply_stats.stake_minted(epoch_height).into(),
+            stake_burned_this_epoch: self.stake_supply_stats.stake_burned(epoch_height).into(),
+            total_stake_minted: self.stake_supply_stats.total_stake_minted().into(),
+            total_stake_burned: self.stake_supply_stats.total_stake_burned().into(),
+            total_stake_supply: self.total_stake.amount().into(),
         }
     }
+}
 
-    fn new_stake_batch(&mut self) -> StakeBatch {
-        *self.batch_id_sequence += 1;
-        self.batch_id_sequence.new_stake_batch()
+/// NEAR liquidity pool
+impl Contract {
+    pub(crate) fn add_near_liquidity(
+        &mut self,
+        amount: domain::YoctoNear,
+        inflow: NearLiquidityInflow,
+    ) {
+        if amount.value() == 0 {
+            return;
+        }
+        self.near_liquidity_pool += amount;
+        self.near_liquidity_stats.record_inflow(inflow, amount);
+        log(events::NearLiquidityAdded {
+            amount: amount.value(),
+            balance: self.near_liquidity_pool.value(),
+            inflow,
+        });
+        crate::near::events::liquidity_added(amount, self.near_liquidity_pool, inflow);
     }
 
-    /// moves STAKE [amount] from account balance to redeem stake batch
-    ///
     /// ## Panics
-    /// - if amount == 0
-    /// - if STAKE account balance is too low to fulfill request
-    ///
-    /// ## Notes
-    /// - before applying the deposit, batch receipts are processed [claim_receipt_funds]
-    fn redeem_stake_for_account(
+    /// if the liquidity pool balance is insufficient
+    pub(crate) fn consume_near_liquidity(
         &mut self,
-        account: &mut RegisteredAccount,
-        amount: domain::YoctoStake,
-    ) -> BatchId {
-        assert!(amount.value() > 0, ZERO_REDEEM_AMOUNT);
+        amount: domain::YoctoNear,
+        outflow: NearLiquidityOutflow,
+    ) {
+        if amount.value() == 0 {
+            return;
+        }
+        self.near_liquidity_pool -= amount;
+        self.near_liquidity_stats.record_outflow(outflow, amount);
+        log(events::NearLiquidityConsumed {
+            amount: amount.value(),
+            balance: self.near_liquidity_pool.value(),
+            outflow,
+        });
+    }
+}
 
-        self.claim_receipt_funds(account);
+impl Contract {
+    /// NEAR funds cannot be claimed from a receipt that is pending withdrawal from the staking pool
+    pub(crate) fn redeem_stake_batch_pending_withdrawal_id(&self) -> Option<domain::BatchId> {
+        match self.redeem_stake_batch_lock {
+            Some(RedeemLock::PendingWithdrawal) => self.redeem_stake_batch.map(|batch| batch.id()),
+            _ => None,
+        }
+    }
+}
 
-        assert!(
-            account.can_redeem(amount),
-            INSUFFICIENT_STAKE_FOR_REDEEM_REQUEST
-        );
+// staking pool func call invocations
+impl Contract {
+    /// logs the batch balance, which may be either the current or the next stake batch
+    /// - if neither batch matches, then the batch was cancelled
+    pub(crate) fn log_stake_batch(&self, batch_id: domain::BatchId) {
+        match self
+            .stake_batch
+            .iter()
+            .chain(self.next_stake_batch.iter())
+            .find(|batch| batch.id() == batch_id)
+        {
+            Some(batch) => log(events::StakeBatch::from(*batch)),
+            None => log(events::StakeBatchCancelled {
+                batch_id: batch_id.value(),
+            }),
+        }
+    }
 
-        // debit the amount of STAKE to redeem from the account
-        let mut stake = account.stake.expect("account has zero STAKE token balance");
-        if stake.debit(amount).value() > 0 {
-            account.stake = Some(stake);
-        } else {
-            account.stake = None;
+    /// logs the batch balance, which may be either the current or the next redeem stake batch
+    /// - if neither batch matches, then the batch was cancelled
+    pub(crate) fn log_redeem_stake_batch(&self, batch_id: domain::BatchId) {
+        match self
+            .redeem_stake_batch
+            .iter()
+            .chain(self.next_redeem_stake_batch.iter())
+            .find(|batch| batch.id() == batch_id)
+        {
+            Some(batch) => log(events::RedeemStakeBatch::from(*batch)),
+            None => log(events::RedeemStakeBatchCancelled {
+                batch_id: batch_id.value(),
+            }),
         }
+    }
 
-        match self.redeem_stake_batch_lock {
-            // use current batch
-            None => {
-                // apply at contract level
-                let mut contract_batch = self
-                    .redeem_stake_batch
-                    .unwrap_or_else(|| self.new_redeem_stake_batch());
-                contract_batch.add(amount);
-                self.redeem_stake_batch = Some(contract_batch);
+    /// STAKE that is removed from a redeem stake batch is transferred back from the contract to the
+    /// predecessor account
+    fn log_redeem_stake_batch_removal(
+        &self,
+        batch_id: domain::BatchId,
+        amount: domain::YoctoStake,
+    ) {
+        crate::near::events::ft_transfer(
+            &env::current_account_id(),
+            &env::predecessor_account_id(),
+            amount,
+            Some(&format!("redeem stake batch {}", batch_id.value())),
+        );
+    }
+}
 
-                // apply at account level
-                // NOTE: account batch ID must match contract batch ID
-                let mut account_batch = account
-                    .redeem_stake_batch
-                    .unwrap_or_else(|| contract_batch.id().new_redeem_stake_batch());
-                account_batch.add(amount);
-                account.redeem_stake_batch = Some(account_batch);
+/// NEAR transfers
+impl Contract {
+    fn withdraw_near_funds(&mut self, account: &mut RegisteredAccount, amount: domain::YoctoNear) {
+        self.debit_near_funds(
+            &env::predecessor_account_id(),
+            account,
+            amount,
+            env::predecessor_account_id(),
+        );
+    }
 
-                account_batch.id().into()
-            }
-            // use next batch
-            _ => {
-                // apply at contract level
-                let mut contract_batch = self
-                    .next_redeem_stake_batch
-                    .unwrap_or_else(|| self.new_redeem_stake_batch());
-                contract_batch.add(amount);
-                self.next_redeem_stake_batch = Some(contract_batch);
+    fn transfer_near_funds(
+        &mut self,
+        account: &mut RegisteredAccount,
+        amount: domain::YoctoNear,
+        recipient: ValidAccountId,
+    ) {
+        self.debit_near_funds(
+            &env::predecessor_account_id(),
+            account,
+            amount,
+            recipient.into(),
+        );
+    }
 
-                // apply at account level
-                // NOTE: account batch ID must match contract batch ID
-                let mut account_batch = account
-                    .next_redeem_stake_batch
-                    .unwrap_or_else(|| contract_batch.id().new_redeem_stake_batch());
-                account_batch.add(amount);
-                account.next_redeem_stake_batch = Some(account_batch);
+    /// debits the NEAR funds from the account and transfers them to the recipient
+    /// - if the per epoch withdrawal limit would be exceeded, or the liquid NEAR is not enough while
+    ///   idle NEAR is swept into staking, then the overflow is queued
+    pub(crate) fn debit_near_funds(
+        &mut self,
+        account_id: &str,
+        account: &mut RegisteredAccount,
+        amount: domain::YoctoNear,
+        recipient: AccountId,
+    ) {
+        self.claim_receipt_funds(account);
+        account.apply_near_debit(amount);
+        self.save_registered_account(&account);
+        self.record_account_activity(
+            &account.id,
+            account,
+            AccountActivityKind::Withdraw,
+            amount.value(),
+            None,
+        );
 
-                account_batch.id().into()
+        let epoch_height: domain::EpochHeight = env::epoch_height().into();
+        let transfer_amount = match self.available_for_withdrawal(epoch_height) {
+            Some(available) if available < amount => {
+                self.queue_withdrawal(
+                    account_id,
+                    recipient.clone(),
+                    amount - available,
+                    epoch_height,
+                );
+                available
             }
+            _ => amount,
+        };
+        if transfer_amount.value() > 0 {
+            self.send_near(recipient, transfer_amount);
         }
     }
 
-    fn new_redeem_stake_batch(&mut self) -> RedeemStakeBatch {
-        *self.batch_id_sequence += 1;
-        self.batch_id_sequence.new_redeem_stake_batch()
-    }
-
-    /// NOTE: the account is saved to storage if funds were claimed
-    pub(crate) fn claim_receipt_funds(&mut self, account: &mut RegisteredAccount) {
-        let claimed_stake_tokens = self.claim_stake_batch_receipts(&mut account.account);
-        let claimed_near_tokens = self.claim_redeem_stake_batch_receipts(&mut account.account);
-        let funds_were_claimed = claimed_stake_tokens || claimed_near_tokens;
-        if funds_were_claimed {
-            self.save_registered_account(&account);
+    /// NEAR that can be transferred out of the contract within the epoch, i.e., the lesser of the
+    /// per epoch withdrawal limit and the liquid NEAR while idle NEAR is swept into staking
+    /// - None means there is no limit
+    fn available_for_withdrawal(
+        &self,
+        epoch_height: domain::EpochHeight,
+    ) -> Option<domain::YoctoNear> {
+        let available = self
+            .epoch_withdrawals
+            .available(self.config.max_withdrawal_per_epoch(), epoch_height);
+        match (available, self.swept_liquidity_limit()) {
+            (Some(available), Some(liquid)) => Some(available.min(liquid)),
+            (available, liquid) => available.or(liquid),
         }
     }
 
-    /// the purpose of this method is to to compute the account's STAKE balance taking into consideration
-    /// that there may be unclaimed receipts on the account
-    /// - this enables the latest account info to be returned within the context of a contract 'view'
-    ///   call - no receipts are physically claimed, i.e., contract state does not change
-    pub(crate) fn apply_receipt_funds_for_view(&self, account: &Account) -> Account {
-        let mut account = account.clone();
+    /// transfers NEAR out of the contract and records the withdrawal against the per epoch limit
+    fn send_near(&mut self, recipient: AccountId, amount: domain::YoctoNear) {
+        self.debit_total_near(amount);
+        self.epoch_withdrawals
+            .record_withdrawal(amount, env::epoch_height().into());
+        Promise::new(recipient).transfer(amount.value());
+    }
 
+    /// forwards the redeem proceeds from the account NEAR balance to the account's redeem
+    /// forwarding target, subject to the per epoch withdrawal limit
+    /// - forwarding is skipped, i.e., the NEAR remains in the account NEAR balance, if the contract
+    ///   is paused or if the prepaid gas does not cover the forwarding workflow
+    /// - NOTE: the account is saved to storage if funds were forwarded
+    fn forward_redeem_proceeds(
+        &mut self,
+        account: &mut RegisteredAccount,
+        proceeds: domain::YoctoNear,
+    ) {
+        let target = match account
+            .redeem_forwarding
+            .and_then(|target| self.redeem_forwarding_targets.get(&target))
         {
-            fn apply_stake_credit(
-                account: &mut Account,
-                batch: StakeBatch,
-                receipt: StakeBatchReceipt,
-            ) {
-                let staked_near = batch.balance().amount();
-                let stake = receipt.stake_token_value().near_to_stake(staked_near);
-                account.apply_stake_credit(stake);
-            }
-
-            if let Some(batch) = account.stake_batch {
-                if let Some(receipt) = self.stake_batch_receipts.get(&batch.id()) {
-                    apply_stake_credit(&mut account, batch, receipt);
-                    account.stake_batch = None;
-                }
-            }
+            Some(target) => target,
+            None => return,
+        };
+        if self.pause.is_some() || !self.redeem_forwarding_gas_available() {
+            return;
+        }
+        let balance = account
+            .near
+            .map_or(0.into(), |balance| balance.amount())
+            .min(proceeds);
+        let epoch_height: domain::EpochHeight = env::epoch_height().into();
+        let amount = match self.available_for_withdrawal(epoch_height) {
+            Some(available) if available < balance => available,
+            _ => balance,
+        };
+        if amount.value() == 0 {
+            return;
+        }
 
-            if let Some(batch) = account.next_stake_batch {
-                if let Some(receipt) = self.stake_batch_receipts.get(&batch.id()) {
-                    apply_stake_credit(&mut account, batch, receipt);
-                    account.next_stake_batch = None;
-                }
-            }
+        account.apply_near_debit(amount);
+        self.save_registered_account(account);
+        self.debit_total_near(amount);
+        self.epoch_withdrawals
+            .record_withdrawal(amount, epoch_height);
+
+        let account_id = account.account_id.as_str();
+        log(events::RedeemProceedsForwarded {
+            account_id: account_id.to_string(),
+            target_stake_contract: target.clone(),
+            amount: amount.value(),
+        });
+        let gas_config = self.config.gas_config().callbacks();
+        Promise::new(target)
+            .function_call(
+                b"deposit_redeem_forwarding".to_vec(),
+                serde_json::to_vec(&DepositRedeemForwardingArgs {
+                    account_id: account_id.to_string(),
+                })
+                .unwrap(),
+                amount.value(),
+                gas_config.deposit_redeem_forwarding().value(),
+            )
+            .then(ext_callbacks::on_redeem_forwarding(
+                account_id.to_string(),
+                amount.into(),
+                &env::current_account_id(),
+                NO_DEPOSIT.value(),
+                gas_config.on_redeem_forwarding().value(),
+            ));
+    }
+
+    /// redeem proceeds are forwarded along the way on every claim path, thus forwarding must not
+    /// cause the transaction to run out of gas
+    fn redeem_forwarding_gas_available(&self) -> bool {
+        let gas_config = self.config.gas_config();
+        let required_gas = gas_config.callbacks().deposit_redeem_forwarding().value()
+            + gas_config.callbacks().on_redeem_forwarding().value()
+            + 2 * gas_config.function_call_promise().value();
+        env::prepaid_gas() - env::used_gas() >= required_gas
+    }
+
+    /// debits NEAR that is being moved out of the account NEAR balances
+    /// - transfers out of the contract are capped by the liquid NEAR up front and the overflow is
+    ///   queued - see [available_for_withdrawal](Contract::available_for_withdrawal)
+    ///
+    /// ## Panics
+    /// if idle NEAR is swept into staking and the liquid NEAR is not enough to cover the amount
+    pub(crate) fn debit_total_near(&mut self, amount: domain::YoctoNear) {
+        let swept = self.idle_near_sweep.swept();
+        if swept.value() > 0 {
+            // swept NEAR is accounted for in total NEAR, but is not liquid until the cycle completes
+            assert!(
+                self.total_near
+                    .amount()
+                    .value()
+                    .saturating_sub(swept.value())
+                    >= amount.value(),
+                IDLE_NEAR_LIQUIDITY_BUFFER_EXHAUSTED
+            );
+        }
+        // check if there are enough funds to fulfill the request - if not then draw from liquidity
+        if self.total_near.amount() < amount {
+            // access liquidity
+            // NOTE: will panic if there are not enough funds in liquidity pool
+            //       - should never panic unless there is a bug
+            let difference = amount - self.total_near.amount();
+            self.consume_near_liquidity(difference, NearLiquidityOutflow::Withdrawal);
+            self.total_near.credit(difference);
         }
+        self.total_near.debit(amount);
+    }
 
-        {
-            fn apply_near_credit(
-                account: &mut Account,
-                batch: RedeemStakeBatch,
-                receipt: domain::RedeemStakeBatchReceipt,
-            ) {
-                let redeemed_stake = batch.balance().amount();
-                let near = receipt.stake_token_value().stake_to_near(redeemed_stake);
-                account.apply_near_credit(near);
+    /// ## Panics
+    /// if the account already has a queued withdrawal for a different recipient
+    fn queue_withdrawal(
+        &mut self,
+        account_id: &str,
+        recipient: AccountId,
+        amount: domain::YoctoNear,
+        epoch_height: domain::EpochHeight,
+    ) {
+        let account_id_hash = Hash::from(account_id);
+        let withdrawal = match self.queued_withdrawals.get(&account_id_hash) {
+            Some(mut withdrawal) => {
+                assert_eq!(
+                    withdrawal.recipient(),
+                    recipient,
+                    "{}",
+                    QUEUED_WITHDRAWAL_RECIPIENT_MISMATCH
+                );
+                withdrawal.add(amount, epoch_height);
+                withdrawal
             }
+            None => domain::QueuedWithdrawal::new(recipient, amount, epoch_height),
+        };
+        self.queued_withdrawals
+            .insert(&account_id_hash, &withdrawal);
+        self.total_queued_withdrawals += amount;
+
+        log(events::WithdrawalQueued {
+            account_id: account_id.to_string(),
+            recipient: withdrawal.recipient().to_string(),
+            amount: amount.value(),
+            total_queued: withdrawal.amount().value(),
+        });
+    }
 
-            if let Some(RedeemLock::PendingWithdrawal) = self.redeem_stake_batch_lock {
-                // NEAR funds cannot be claimed from a receipt that is pending withdrawal from the staking pool
-                let batch_pending_withdrawal_id = self.redeem_stake_batch.as_ref().unwrap().id();
-
-                if let Some(batch) = account.redeem_stake_batch {
-                    if batch_pending_withdrawal_id != batch.id() {
-                        if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
-                            apply_near_credit(&mut account, batch, receipt);
-                            account.redeem_stake_batch = None
-                        }
-                    }
-                }
-
-                if let Some(batch) = account.next_redeem_stake_batch {
-                    if batch_pending_withdrawal_id != batch.id() {
-                        if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
-                            apply_near_credit(&mut account, batch, receipt);
-                            account.next_redeem_stake_batch = None
-                        }
-                    }
-                }
-            } else {
-                if let Some(batch) = account.redeem_stake_batch {
-                    if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
-                        apply_near_credit(&mut account, batch, receipt);
-                        account.redeem_stake_batch = None
-                    }
-                }
+    /// transfers the specified amount from the account's queued withdrawal to the recipient
+    /// - if `amount` is None, then the full queued amount is released
+    /// - the amount is capped by the liquid NEAR while idle NEAR is swept into staking
+    /// - the per epoch withdrawal limit is not checked - it is the caller's responsibility
+    ///
+    /// Returns the amount that was transferred.
+    ///
+    /// ## Panics
+    /// if the account has no queued withdrawal
+    pub(crate) fn release_queued_withdrawal_funds(
+        &mut self,
+        account_id: &str,
+        amount: Option<domain::YoctoNear>,
+    ) -> domain::YoctoNear {
+        let account_id_hash = Hash::from(account_id);
+        let mut withdrawal = self
+            .queued_withdrawals
+            .get(&account_id_hash)
+            .expect(NO_QUEUED_WITHDRAWAL);
+        let amount = amount.map_or(withdrawal.amount(), |amount| {
+            amount.min(withdrawal.amount())
+        });
+        let amount = self
+            .swept_liquidity_limit()
+            .map_or(amount, |liquid| amount.min(liquid));
+        if amount.value() == 0 {
+            return amount;
+        }
 
-                if let Some(batch) = account.next_redeem_stake_batch {
-                    if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
-                        apply_near_credit(&mut account, batch, receipt);
-                        account.next_redeem_stake_batch = None
-                    }
-                }
-            }
+        withdrawal.debit(amount);
+        self.total_queued_withdrawals -= amount;
+        if withdrawal.amount().value() == 0 {
+            self.queued_withdrawals.remove(&account_id_hash);
+        } else {
+            self.queued_withdrawals
+                .insert(&account_id_hash, &withdrawal);
         }
+        self.send_near(withdrawal.recipient().to_string(), amount);
 
-        account
+        log(events::QueuedWithdrawalReleased {
+            account_id: account_id.to_string(),
+            recipient: withdrawal.recipient().to_string(),
+            amount: amount.value(),
+            remaining: withdrawal.amount().value(),
+        });
+        amount
     }
+}
 
-    fn claim_stake_batch_receipts(&mut self, account: &mut Account) -> bool {
-        fn claim_stake_tokens_for_batch(
-            contract: &mut Contract,
-            account: &mut Account,
-            batch: StakeBatch,
-            mut receipt: domain::StakeBatchReceipt,
-        ) {
-            // how much NEAR did the account stake in the batch
-            let staked_near = batch.balance().amount();
+impl Contract {
+    fn run_stake_batch(&mut self) -> Promise {
+        assert!(self.can_run_batch(), BLOCKED_BY_BATCH_RUNNING);
+        assert!(self.is_staking_pool_available(), STAKING_POOL_UNAVAILABLE);
+        assert!(
+            self.is_staking_pool_whitelisted(),
+            STAKING_POOL_WHITELIST_UNCONFIRMED
+        );
+        assert!(
+            self.stake_batch_deposit_cooldown_passed(),
+            STAKE_BATCH_DEPOSIT_COOLDOWN
+        );
+        // reserved funds are included in the batch run before the stake batch is locked
+        self.apply_batch_reservations();
+        // idle NEAR is swept into the batch before the stake batch is locked
+        self.cycle_idle_near_sweep();
+        self.sweep_idle_near();
+        let batch = self.stake_batch.expect(STAKE_BATCH_SHOULD_EXIST);
+        self.start_workflow(Workflow::Stake);
 
-            // claim the STAKE tokens for the account
-            let stake = receipt.stake_token_value().near_to_stake(staked_near);
-            account.apply_stake_credit(stake);
+        self.stake_batch_lock = Some(StakeLock::Staking);
+        self.record_lock_acquired(LockKind::Stake, Some(batch.id()));
 
-            // track that the STAKE tokens were claimed
-            receipt.stake_tokens_issued(staked_near);
-            if receipt.all_claimed() {
-                // then delete the receipt and free the storage
-                contract.stake_batch_receipts.remove(&batch.id());
+        self.distribute_earnings();
+
+        if self.is_liquidity_needed() {
+            self.staking_pool_promise()
+                .get_account()
+                .promise()
+                .then(self.invoke_on_run_stake_batch())
+                .then(self.invoke_clear_stake_lock())
+        } else {
+            // if liquidity is not needed, then lets stake it
+            // NOTE: liquidity belongs to the stakers - some will leak over when we withdraw all from
+            //       the staking pool because of the shares rounding issue on the staking pool side
+            let near_liquidity = self.near_liquidity_pool;
+            self.consume_near_liquidity(near_liquidity, NearLiquidityOutflow::Restake);
+            let stake_amount = batch.balance().amount() + near_liquidity;
+            if self.defer_stake_batch_verification() {
+                self.staking_pool_promise()
+                    .deposit_and_stake(stake_amount)
+                    .promise()
+                    .then(self.invoke_on_stake_batch_deposited(stake_amount))
+                    .then(self.invoke_clear_stake_lock())
             } else {
-                contract.stake_batch_receipts.insert(&batch.id(), &receipt);
+                self.staking_pool_promise()
+                    .deposit_and_stake(stake_amount)
+                    .get_account()
+                    .promise()
+                    .then(self.invoke_on_deposit_and_stake(None))
+                    .then(self.invoke_clear_stake_lock())
             }
         }
+    }
 
-        let mut claimed_funds = false;
-
-        if let Some(batch) = account.stake_batch {
-            if let Some(receipt) = self.stake_batch_receipts.get(&batch.id()) {
-                claim_stake_tokens_for_batch(self, account, batch, receipt);
-                account.stake_batch = None;
-                claimed_funds = true;
-            }
+    /// check that batch NEAR amount will issue at least 1 yoctoSTAKE
+    /// we never want to issue 0 yoctoSTAKE tokens if NEAR is deposited and staked
+    ///
+    /// the min required NEAR deposit is calculated using the cached STAKE token value
+    /// thus, to be on the safe side, we will require that minimum amount of NEAR deposit should be
+    /// enough for 1000 yoctoSTAKE
+    fn check_min_required_near_deposit(&self, account: &Account, batch_id: domain::BatchId) {
+        if let Some(batch) = account.stake_batch(batch_id) {
+            self.check_stake_batch_min_required_near_balance(batch)
         }
+    }
 
-        if let Some(batch) = account.next_stake_batch {
-            if let Some(receipt) = self.stake_batch_receipts.get(&batch.id()) {
-                claim_stake_tokens_for_batch(self, account, batch, receipt);
-                account.next_stake_batch = None;
-                claimed_funds = true;
-            }
+    fn check_stake_batch_min_required_near_balance(&self, batch: StakeBatch) {
+        let min_required_near_deposit = self.min_required_near_deposit();
+        assert!(
+            batch.balance().amount() >= min_required_near_deposit,
+            "minimum required NEAR deposit is: {}",
+            min_required_near_deposit
+        );
+    }
+
+    /// if the withdrawal would leave a batch balance below the min required NEAR deposit and
+    /// [withdraw_full_batch_balance_below_min_deposit](crate::config::Config::withdraw_full_batch_balance_below_min_deposit)
+    /// is enabled, then the full batch balance is withdrawn - otherwise the requested amount is
+    /// withdrawn, which fails the min required NEAR deposit check
+    fn next_stake_batch_withdrawal_amount(
+        &self,
+        batch: StakeBatch,
+        amount: domain::YoctoNear,
+    ) -> domain::YoctoNear {
+        let balance = batch.balance().amount();
+        if self.config.withdraw_full_batch_balance_below_min_deposit()
+            && amount < balance
+            && balance - amount < self.min_required_near_deposit()
+        {
+            balance
+        } else {
+            amount
         }
+    }
 
-        // move the next batch into the current batch as long as the contract is not locked and the
-        // funds for the current batch have been claimed
-        //
-        // NOTE: while the contract is locked for running a stake batch, all deposits must go into
-        //       the next batch
-        if !self.stake_batch_locked() && account.stake_batch.is_none() {
-            account.stake_batch = account.next_stake_batch.take();
+    pub(crate) fn min_required_near_deposit(&self) -> domain::YoctoNear {
+        self.stake_token_value.stake_to_near(1000.into())
+    }
+
+    /// existence check that does not load and deserialize the receipt
+    pub(crate) fn stake_batch_receipt_exists(&self, batch_id: domain::BatchId) -> bool {
+        self.stake_batch_receipts.contains_key(&batch_id)
+    }
+
+    /// existence check that does not load and deserialize the receipt
+    pub(crate) fn redeem_stake_batch_receipt_exists(&self, batch_id: domain::BatchId) -> bool {
+        self.redeem_stake_batch_receipts.contains_key(&batch_id)
+    }
+
+    /// Receipts are looked up for every batch that is referenced by the account each time the
+    /// account's receipt funds are claimed, but in the common case the batch has not yet been
+    /// processed. Checking for the key first is cheaper than a storage read, which means the receipt
+    /// is only loaded and deserialized when there are funds to claim.
+    ///
+    /// Receipts for unverified stake batches are not claimable until they are verified, which means
+    /// verification re-prices the full batch.
+    pub(crate) fn load_stake_batch_receipt(
+        &self,
+        batch_id: domain::BatchId,
+    ) -> Option<domain::StakeBatchReceipt> {
+        if self.stake_batch_receipt_exists(batch_id) && self.stake_batch_verified(batch_id) {
+            self.stake_batch_receipts.get(&batch_id)
+        } else {
+            None
         }
+    }
 
-        claimed_funds
+    pub(crate) fn stake_batch_verified(&self, batch_id: domain::BatchId) -> bool {
+        !self.unverified_stake_batches.contains(&batch_id)
     }
 
-    /// claim NEAR tokens for redeeming STAKE
-    fn claim_redeem_stake_batch_receipts(&mut self, account: &mut Account) -> bool {
-        fn claim_redeemed_stake_for_batch(
-            contract: &mut Contract,
-            account: &mut Account,
-            account_batch: domain::RedeemStakeBatch,
-            mut receipt: domain::RedeemStakeBatchReceipt,
-        ) {
-            // how much STAKE did the account redeem in the batch
-            let redeemed_stake = account_batch.balance().amount();
+    /// the account's STAKE batch funds cannot be moved, and NEAR cannot be added to the account's
+    /// stake batches, while the batch receipt is pending verification because the account batch no
+    /// longer matches the contract batch
+    pub(crate) fn stake_batches_verified(&self, account: &Account) -> bool {
+        [account.stake_batch, account.next_stake_batch]
+            .iter()
+            .flatten()
+            .all(|batch| self.stake_batch_verified(batch.id()))
+    }
 
-            // claim the NEAR tokens for the account
-            let near = receipt.stake_token_value().stake_to_near(redeemed_stake);
-            account.apply_near_credit(near);
+    /// must be checked by the account facing entry points before NEAR is added to the account's
+    /// stake batches - internal workflows defer instead, e.g., see [compound_redeemed_near](Contract::compound_redeemed_near)
+    pub(crate) fn assert_stake_batches_verified(&self, account: &Account) {
+        assert!(
+            self.stake_batches_verified(account),
+            STAKE_BATCH_PENDING_VERIFICATION
+        );
+    }
 
-            // track that the NEAR tokens were claimed
-            receipt.stake_tokens_redeemed(redeemed_stake);
-            if receipt.all_claimed() {
-                // then delete the receipt and free the storage
-                contract
-                    .redeem_stake_batch_receipts
-                    .remove(&account_batch.id());
-            } else {
-                contract
-                    .redeem_stake_batch_receipts
-                    .insert(&account_batch.id(), &receipt);
-            }
+    /// see [load_stake_batch_receipt](Contract::load_stake_batch_receipt)
+    pub(crate) fn load_redeem_stake_batch_receipt(
+        &self,
+        batch_id: domain::BatchId,
+    ) -> Option<domain::RedeemStakeBatchReceipt> {
+        if self.redeem_stake_batch_receipt_exists(batch_id) {
+            self.redeem_stake_batch_receipts.get(&batch_id)
+        } else {
+            None
         }
+    }
 
-        /// for a pending withdrawal, funds can also be claimed against the liquidity pool
-        fn claim_redeemed_stake_for_batch_pending_withdrawal(
-            contract: &mut Contract,
-            account: &mut Account,
-            account_batch: &mut domain::RedeemStakeBatch,
-            mut receipt: domain::RedeemStakeBatchReceipt,
-        ) {
-            // how much STAKE did the account redeem in the batch
-            let redeemed_stake = account_batch.balance().amount();
+    pub(crate) fn get_pending_withdrawal(&self) -> Option<domain::RedeemStakeBatchReceipt> {
+        self.redeem_stake_batch
+            .map(|batch| self.load_redeem_stake_batch_receipt(batch.id()))
+            .flatten()
+    }
 
-            let redeemed_stake_near_value =
-                receipt.stake_token_value().stake_to_near(redeemed_stake);
-            let claimed_near = if contract.near_liquidity_pool >= redeemed_stake_near_value {
-                redeemed_stake_near_value
-            } else {
-                contract.near_liquidity_pool
-            };
-            let redeemable_stake = receipt.stake_token_value().near_to_stake(claimed_near);
-            account_batch.remove(redeemable_stake);
+    /// the block height is estimated relative to the current block based on the epoch length
+    fn redeem_timeline_step(
+        stage: RedeemTimelineStage,
+        epoch_height: domain::EpochHeight,
+        reached: bool,
+    ) -> RedeemTimelineStep {
+        let current_epoch_height = env::epoch_height();
+        let block_height = if epoch_height.value() >= current_epoch_height {
+            env::block_index() + (epoch_height.value() - current_epoch_height) * EPOCH_LENGTH
+        } else {
+            env::block_index()
+                .saturating_sub((current_epoch_height - epoch_height.value()) * EPOCH_LENGTH)
+        };
+        RedeemTimelineStep {
+            stage,
+            epoch_height: epoch_height.into(),
+            block_height: domain::BlockHeight(block_height).into(),
+            reached,
+        }
+    }
 
-            // claim the STAKE tokens for the account
-            // let near = receipt.stake_token_value().stake_to_near(redeemable_stake);
-            account.apply_near_credit(claimed_near);
-            contract.near_liquidity_pool -= claimed_near;
-            contract.total_near.credit(claimed_near);
+    /// used by the dry run simulations to report the same error message that is reported by
+    /// [assert_not_paused](Contract::assert_not_paused)
+    fn pause_violations(&self) -> Vec<String> {
+        self.pause
+            .as_ref()
+            .map(|pause| vec![format!("{}: {}", CONTRACT_PAUSED, pause.reason())])
+            .unwrap_or_default()
+    }
 
-            // track that the STAKE tokens were claimed
-            receipt.stake_tokens_redeemed(redeemable_stake);
-            if receipt.all_claimed() {
-                // this means that effectively all funds have been withdrawn
-                // which means we need to finalize the redeem workflow
-                contract
-                    .redeem_stake_batch_receipts
-                    .remove(&account_batch.id());
-                contract.redeem_stake_batch_lock = None;
-                contract.pop_redeem_stake_batch();
-            } else {
-                contract
-                    .redeem_stake_batch_receipts
-                    .insert(&account_batch.id(), &receipt);
+    fn can_run_batch(&self) -> bool {
+        !self.stake_batch_locked() && !self.is_unstaking()
+    }
+
+    /// NEAR liquidity that is not needed to clear the current pending withdrawal, i.e., that can be
+    /// used to fulfill the next redeem batch without waiting for the unstaked NEAR to be withdrawn
+    fn instant_exit_liquidity(&self) -> domain::YoctoNear {
+        let pending_withdrawal = self
+            .get_pending_withdrawal()
+            .map_or(0, |receipt| receipt.stake_near_value().value());
+        self.near_liquidity_pool
+            .value()
+            .saturating_sub(pending_withdrawal)
+            .into()
+    }
+
+    /// the staking pool is considered available unless a stake batch workflow failed on the staking
+    /// pool side and the backoff period has not yet expired
+    pub(crate) fn is_staking_pool_available(&self) -> bool {
+        self.staking_pool_unavailable_until
+            .map_or(true, |epoch_height| {
+                env::epoch_height() >= epoch_height.value()
+            })
+    }
+
+    /// the stake batch can only be run once [deposit_cooldown_blocks](crate::config::Config::deposit_cooldown_blocks)
+    /// have passed since the batch balance last changed
+    /// - the cooldown restarts whenever funds are deposited into or withdrawn from the batch
+    pub(crate) fn stake_batch_deposit_cooldown_passed(&self) -> bool {
+        self.stake_batch.map_or(true, |batch| {
+            env::block_index()
+                >= batch.balance().block_height().value() + self.config.deposit_cooldown_blocks()
+        })
+    }
+
+    /// marks the staking pool unavailable, which suspends running stake batches until the backoff
+    /// period expires
+    pub(crate) fn mark_staking_pool_unavailable(&mut self) {
+        let unavailable_until =
+            env::epoch_height() + self.config.staking_pool_unavailable_backoff_epochs();
+        self.staking_pool_unavailable_until = Some(unavailable_until.into());
+        log(events::StakingPoolUnavailable {
+            staking_pool_id: self.staking_pool_id.clone(),
+            unavailable_until,
+        });
+    }
+
+    /// returns true if [stake](StakingService::stake) would run the stake batch workflow, or
+    /// process the staked batch, without panicking
+    fn can_stake(&self) -> bool {
+        match self.stake_batch_lock {
+            None => {
+                self.stake_batch.is_some()
+                    && self.can_run_batch()
+                    && self.is_staking_pool_available()
+                    && self.is_staking_pool_whitelisted()
+                    && self.stake_batch_deposit_cooldown_passed()
+                    && !self.is_workflow_cooling_down(Workflow::Stake)
             }
+            Some(StakeLock::Staked { .. }) => self.stake_batch.is_some(),
+            Some(_) => false,
         }
+    }
 
-        let mut claimed_funds = false;
+    fn can_unstake(&self) -> bool {
+        if self.can_run_batch() {
+            match self.redeem_stake_batch_lock {
+                None => self.redeem_stake_batch.is_some(),
+                Some(RedeemLock::PendingWithdrawal) => {
+                    let batch = self
+                        .redeem_stake_batch
+                        .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST);
+                    let batch_receipt = self
+                        .redeem_stake_batch_receipts
+                        .get(&batch.id())
+                        .expect(REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST);
+                    batch_receipt.unstaked_funds_available_for_withdrawal()
+                }
+                Some(RedeemLock::Unstaking) => false,
+            }
+        } else {
+            self.can_run_batch()
+        }
+    }
+
+    /// batches the NEAR to stake at the contract level and account level
+    ///
+    /// ## Panics
+    /// if [amount] is zero
+    ///
+    /// ## Notes
+    /// - before applying the deposit, batch receipts are processed [claim_receipt_funds]
+    /// deposits the attached NEAR into the account's stake batch and saves the account
+    /// - if the account is hibernated, then the account storage escrow is restored from the
+    ///   attached deposit before the remainder is deposited
+    fn deposit_attached_near_for_account_to_stake(
+        &mut self,
+        account: &mut RegisteredAccount,
+    ) -> domain::BatchId {
+        let near_amount = self.attached_deposit_net_of_rehydration_fee(account);
+        self.deposit_near_for_account_to_stake_and_save(account, near_amount)
+    }
+
+    /// if the account is hibernated, then the rehydration fee is paid from the attached deposit
+    fn attached_deposit_net_of_rehydration_fee(
+        &mut self,
+        account: &mut RegisteredAccount,
+    ) -> domain::YoctoNear {
+        let mut near_amount: domain::YoctoNear = env::attached_deposit().into();
+        if self.is_account_hibernated(&account.id) {
+            near_amount -= self.pay_account_rehydration_fee(account, near_amount);
+        }
+        near_amount
+    }
+
+    pub(crate) fn deposit_near_for_account_to_stake_and_save(
+        &mut self,
+        account: &mut RegisteredAccount,
+        near_amount: domain::YoctoNear,
+    ) -> domain::BatchId {
+        let batch_id = self.deposit_near_for_account_to_stake(account, near_amount);
+
+        self.check_min_required_near_deposit(account, batch_id);
+
+        self.bill_account_storage_growth(account);
+        self.save_registered_account(account);
+        self.record_account_activity(
+            &account.id,
+            account,
+            AccountActivityKind::Deposit,
+            near_amount.value(),
+            Some(batch_id),
+        );
+        self.log_stake_batch(batch_id);
+        batch_id
+    }
+
+    pub(crate) fn deposit_near_for_account_to_stake(
+        &mut self,
+        account: &mut RegisteredAccount,
+        amount: domain::YoctoNear,
+    ) -> domain::BatchId {
+        assert!(amount.value() > 0, DEPOSIT_REQUIRED_FOR_STAKE);
+
+        self.claim_receipt_funds(account);
+        self.add_near_to_stake_batch(account, amount)
+    }
+
+    /// adds the NEAR to the account's stake batch - if the stake batch is running, then the NEAR is
+    /// added to the next stake batch
+    /// - the account's stake batches must be verified - see [stake_batches_verified](Contract::stake_batches_verified)
+    pub(crate) fn add_near_to_stake_batch(
+        &mut self,
+        account: &mut Account,
+        amount: domain::YoctoNear,
+    ) -> domain::BatchId {
+        // use current batch if not staking, i.e., the stake batch is not running
+        if !self.stake_batch_locked() {
+            // apply at contract level
+            let mut contract_batch = self.stake_batch.unwrap_or_else(|| self.new_stake_batch());
+            contract_batch.add(amount);
+            self.stake_batch = Some(contract_batch);
+
+            // apply at account level
+            // NOTE: account batch ID must match contract batch ID
+            let mut account_batch = account
+                .stake_batch
+                .unwrap_or_else(|| contract_batch.id().new_stake_batch());
+            account_batch.add(amount);
+            account.stake_batch = Some(account_batch);
+
+            account_batch.id()
+        } else {
+            // apply at contract level
+            let mut contract_batch = self
+                .next_stake_batch
+                .unwrap_or_else(|| self.new_stake_batch());
+            contract_batch.add(amount);
+            self.next_stake_batch = Some(contract_batch);
+
+            // apply at account level
+            // NOTE: account batch ID must match contract batch ID
+            let mut account_batch = account
+                .next_stake_batch
+                .unwrap_or_else(|| contract_batch.id().new_stake_batch());
+            account_batch.add(amount);
+            account.next_stake_batch = Some(account_batch);
+
+            account_batch.id()
+        }
+    }
+
+    fn new_stake_batch(&mut self) -> StakeBatch {
+        self.next_batch_id()
+            .with_kind(BatchKind::Stake)
+            .new_stake_batch()
+    }
+
+    /// moves STAKE [amount] from account balance to redeem stake batch
+    ///
+    /// ## Panics
+    /// - if amount == 0
+    /// - if STAKE account balance is too low to fulfill request
+    ///
+    /// ## Notes
+    /// - before applying the deposit, batch receipts are processed [claim_receipt_funds]
+    pub(crate) fn redeem_stake_for_account(
+        &mut self,
+        account: &mut RegisteredAccount,
+        amount: domain::YoctoStake,
+    ) -> BatchId {
+        assert!(amount.value() > 0, ZERO_REDEEM_AMOUNT);
+
+        self.claim_receipt_funds(account);
+
+        assert!(
+            account.can_redeem(amount),
+            INSUFFICIENT_STAKE_FOR_REDEEM_REQUEST
+        );
+        account.record_redeem(amount, env::epoch_height().into());
+
+        // debit the amount of STAKE to redeem from the account
+        let mut stake = account.stake.expect("account has zero STAKE token balance");
+        if stake.debit(amount).value() > 0 {
+            account.stake = Some(stake);
+        } else {
+            account.stake = None;
+        }
+
+        let batch_id = self.add_stake_to_redeem_stake_batch(account, amount);
+        self.record_account_activity(
+            &account.id,
+            account,
+            AccountActivityKind::Redeem,
+            amount.value(),
+            Some(batch_id.clone().into()),
+        );
+        // the redeemed STAKE is held by the contract until the redeem stake batch is run
+        crate::near::events::ft_transfer(
+            &account.account_id,
+            &env::current_account_id(),
+            amount,
+            Some(&format!(
+                "redeem stake batch {}",
+                domain::BatchId::from(batch_id.clone()).value()
+            )),
+        );
+        batch_id
+    }
 
+    /// adds the STAKE, which must already have been debited from the account STAKE balance, to the
+    /// account's redeem stake batch - if the redeem stake batch is locked, then the STAKE is added to
+    /// the next redeem stake batch
+    pub(crate) fn add_stake_to_redeem_stake_batch(
+        &mut self,
+        account: &mut Account,
+        amount: domain::YoctoStake,
+    ) -> BatchId {
         match self.redeem_stake_batch_lock {
-            // NEAR funds can be claimed for receipts that are not pending on the unstaked NEAR withdrawal
-            // NEAR funds can also be claimed against the NEAR liquidity pool
-            Some(RedeemLock::PendingWithdrawal) => {
-                // NEAR funds cannot be claimed for a receipt that is pending withdrawal of unstaked NEAR from the staking pool
-                let pending_batch_id = self
+            // use current batch
+            None => {
+                // apply at contract level
+                let mut contract_batch = self
                     .redeem_stake_batch
-                    .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST)
-                    .id();
+                    .unwrap_or_else(|| self.new_redeem_stake_batch());
+                contract_batch.add(amount);
+                self.redeem_stake_batch = Some(contract_batch);
 
-                if let Some(mut batch) = account.redeem_stake_batch {
-                    if batch.id() != pending_batch_id {
-                        if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
-                            claim_redeemed_stake_for_batch(self, account, batch, receipt);
-                            account.redeem_stake_batch = None;
-                            claimed_funds = true;
-                        }
-                    } else if self.near_liquidity_pool.value() > 0 {
-                        if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
-                            claim_redeemed_stake_for_batch_pending_withdrawal(
-                                self, account, &mut batch, receipt,
-                            );
-                            if batch.balance().amount().value() == 0 {
-                                account.redeem_stake_batch = None;
-                            } else {
-                                account.redeem_stake_batch = Some(batch);
-                            }
-                            claimed_funds = true;
-                        }
-                    }
-                }
+                // apply at account level
+                // NOTE: account batch ID must match contract batch ID
+                let mut account_batch = account
+                    .redeem_stake_batch
+                    .unwrap_or_else(|| contract_batch.id().new_redeem_stake_batch());
+                account_batch.add(amount);
+                account.redeem_stake_batch = Some(account_batch);
 
-                if let Some(mut batch) = account.next_redeem_stake_batch {
-                    if batch.id() != pending_batch_id {
-                        if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
-                            claim_redeemed_stake_for_batch(self, account, batch, receipt);
-                            account.next_redeem_stake_batch = None;
-                            claimed_funds = true;
-                        }
-                    } else if self.near_liquidity_pool.value() > 0 {
-                        if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
-                            claim_redeemed_stake_for_batch_pending_withdrawal(
-                                self, account, &mut batch, receipt,
-                            );
-                            if batch.balance().amount().value() == 0 {
-                                account.next_redeem_stake_batch = None;
-                            } else {
-                                account.next_redeem_stake_batch = Some(batch);
-                            }
-                            claimed_funds = true;
-                        }
-                    }
-                }
+                account_batch.id().into()
             }
-            None => {
-                if let Some(batch) = account.redeem_stake_batch {
-                    if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
-                        claim_redeemed_stake_for_batch(self, account, batch, receipt);
-                        account.redeem_stake_batch = None;
-                        claimed_funds = true;
-                    }
-                }
+            // use next batch
+            _ => {
+                // apply at contract level
+                let mut contract_batch = self
+                    .next_redeem_stake_batch
+                    .unwrap_or_else(|| self.new_redeem_stake_batch());
+                contract_batch.add(amount);
+                self.next_redeem_stake_batch = Some(contract_batch);
+
+                // apply at account level
+                // NOTE: account batch ID must match contract batch ID
+                let mut account_batch = account
+                    .next_redeem_stake_batch
+                    .unwrap_or_else(|| contract_batch.id().new_redeem_stake_batch());
+                account_batch.add(amount);
+                account.next_redeem_stake_batch = Some(account_batch);
+
+                account_batch.id().into()
+            }
+        }
+    }
+
+    fn new_redeem_stake_batch(&mut self) -> RedeemStakeBatch {
+        self.next_batch_id()
+            .with_kind(BatchKind::Redeem)
+            .new_redeem_stake_batch()
+    }
+
+    /// increments the batch ID sequence, which is shared by stake and redeem batches
+    fn next_batch_id(&mut self) -> domain::BatchId {
+        self.batch_id_sequence = self.batch_id_sequence.next();
+        self.batch_id_sequence
+    }
+
+    /// claimed redeem proceeds are compounded if the account has auto-compounding enabled, else they
+    /// are forwarded if the account has redeem forwarding set
+    ///
+    /// NOTE: the account is saved to storage if funds were claimed
+    ///
+    /// ## Panics
+    /// if the account's batch receipts have not yet been migrated - see [MigrationTask](crate::domain::MigrationTask)
+    pub(crate) fn claim_receipt_funds(&mut self, account: &mut RegisteredAccount) {
+        if account.batch_ids().is_empty() {
+            return;
+        }
+        self.assert_batch_receipts_migrated(&account.batch_ids());
+        let receipts_deleted = self.workflow_counters.receipts_deleted;
+        let initial_storage_usage = env::storage_usage();
+        let stake_balance = account.stake.map_or(0, |balance| balance.amount().value());
+        let near_balance = account.near.map_or(0, |balance| balance.amount().value());
+        let batch_ids = account.batch_ids();
+        let claimed_stake_tokens = self.claim_stake_batch_receipts(&mut account.account);
+        let claimed_near_tokens = self.claim_redeem_stake_batch_receipts(&mut account.account);
+        if self.workflow_counters.receipts_deleted > receipts_deleted {
+            // the account's claim deleted fully claimed receipts - the freed storage is refunded
+            // to the account
+            let freed_bytes = initial_storage_usage.saturating_sub(env::storage_usage());
+            self.credit_storage_refund(&account.id, freed_bytes);
+        }
+        let funds_were_claimed = claimed_stake_tokens || claimed_near_tokens;
+        if funds_were_claimed {
+            self.save_registered_account(&account);
+
+            // the most recent batch of each kind that the account no longer holds funds in
+            let remaining_batch_ids = account.batch_ids();
+            let claimed_batch_id = |kind: BatchKind| {
+                batch_ids.iter().rev().copied().find(|batch_id| {
+                    batch_id.kind() == kind && !remaining_batch_ids.contains(batch_id)
+                })
+            };
+            let stake_claimed = account
+                .stake
+                .map_or(0, |balance| balance.amount().value())
+                .saturating_sub(stake_balance);
+            let near_claimed = account
+                .near
+                .map_or(0, |balance| balance.amount().value())
+                .saturating_sub(near_balance);
+            let stake_batch_id = claimed_batch_id(BatchKind::Stake);
+            let redeem_stake_batch_id = claimed_batch_id(BatchKind::Redeem);
+            // the claimed STAKE was held by the contract since the batch was run
+            if stake_claimed > 0 && account.account_id != env::current_account_id() {
+                crate::near::events::ft_transfer(
+                    &env::current_account_id(),
+                    &account.account_id,
+                    stake_claimed.into(),
+                    stake_batch_id
+                        .map(|batch_id| format!("stake batch {}", batch_id.value()))
+                        .as_deref(),
+                );
+            }
+            self.record_account_activity(
+                &account.id,
+                account,
+                AccountActivityKind::Stake,
+                stake_claimed,
+                stake_batch_id,
+            );
+            self.record_account_activity(
+                &account.id,
+                account,
+                AccountActivityKind::Claim,
+                near_claimed,
+                redeem_stake_batch_id,
+            );
+            if near_claimed > 0 {
+                let compounded = account.preferences.auto_compound
+                    && self
+                        .compound_redeemed_near(account, near_claimed.into())
+                        .is_some();
+                if !compounded {
+                    self.forward_redeem_proceeds(account, near_claimed.into());
+                }
+            }
+        }
+    }
+
+    /// moves the redeemed NEAR from the account NEAR balance into the account's stake batch
+    /// - compounding is skipped, i.e., the NEAR remains in the account NEAR balance, if the contract
+    ///   is paused, if the NEAR is not liquid, if the account's stake batches are pending
+    ///   verification, if the stake batch balance would be below the min required NEAR deposit, or
+    ///   if the account storage escrow does not cover the stake batch
+    ///
+    /// Returns the batch ID that the NEAR was deposited into, or None if compounding was skipped.
+    ///
+    /// NOTE: the account is saved if the NEAR was compounded
+    pub(crate) fn compound_redeemed_near(
+        &mut self,
+        account: &mut RegisteredAccount,
+        amount: domain::YoctoNear,
+    ) -> Option<domain::BatchId> {
+        if amount.value() == 0
+            || self.pause.is_some()
+            || self.liquid_near() < amount
+            || !self.stake_batches_verified(account)
+        {
+            return None;
+        }
+        // the account record must fit within the account storage escrow once the stake batch is added
+        let mut compounded = account.account;
+        let batch = if self.stake_batch_locked() {
+            compounded
+                .next_stake_batch
+                .get_or_insert(StakeBatch::new(0.into(), 0.into()))
+        } else {
+            compounded
+                .stake_batch
+                .get_or_insert(StakeBatch::new(0.into(), 0.into()))
+        };
+        if batch.balance().amount().value() + amount.value()
+            < self.min_required_near_deposit().value()
+            || self.account_storage_cost(&compounded) > account.storage_escrow.amount()
+        {
+            return None;
+        }
+
+        account.apply_near_debit(amount);
+        self.debit_total_near(amount);
+        let batch_id = self.add_near_to_stake_batch(account, amount);
+        self.save_registered_account(account);
+        self.record_account_activity(
+            &account.id,
+            account,
+            AccountActivityKind::Deposit,
+            amount.value(),
+            Some(batch_id),
+        );
+        self.log_stake_batch(batch_id);
+        Some(batch_id)
+    }
+
+    /// the purpose of this method is to to compute the account's STAKE balance taking into consideration
+    /// that there may be unclaimed receipts on the account
+    /// - this enables the latest account info to be returned within the context of a contract 'view'
+    ///   call - no receipts are physically claimed, i.e., contract state does not change
+    pub(crate) fn apply_receipt_funds_for_view(&self, account: &Account) -> Account {
+        let mut account = account.clone();
+
+        {
+            fn apply_stake_credit(
+                account: &mut Account,
+                batch: StakeBatch,
+                receipt: StakeBatchReceipt,
+            ) {
+                let staked_near = batch.balance().amount();
+                let stake = receipt.stake_token_value().near_to_stake(staked_near);
+                account.apply_stake_credit(stake);
+            }
+
+            if let Some(batch) = account.stake_batch {
+                if let Some(receipt) = self.load_stake_batch_receipt(batch.id()) {
+                    apply_stake_credit(&mut account, batch, receipt);
+                    account.stake_batch = None;
+                }
+            }
+
+            if let Some(batch) = account.next_stake_batch {
+                if let Some(receipt) = self.load_stake_batch_receipt(batch.id()) {
+                    apply_stake_credit(&mut account, batch, receipt);
+                    account.next_stake_batch = None;
+                }
+            }
+        }
+
+        {
+            fn apply_near_credit(
+                account: &mut Account,
+                batch: RedeemStakeBatch,
+                receipt: domain::RedeemStakeBatchReceipt,
+            ) {
+                let redeemed_stake = batch.balance().amount();
+                let near = receipt.stake_token_value().stake_to_near(redeemed_stake);
+                account.apply_near_credit(near);
+            }
+
+            if let Some(RedeemLock::PendingWithdrawal) = self.redeem_stake_batch_lock {
+                // NEAR funds cannot be claimed from a receipt that is pending withdrawal from the staking pool
+                let batch_pending_withdrawal_id = self.redeem_stake_batch.as_ref().unwrap().id();
+
+                if let Some(batch) = account.redeem_stake_batch {
+                    if batch_pending_withdrawal_id != batch.id() {
+                        if let Some(receipt) = self.load_redeem_stake_batch_receipt(batch.id()) {
+                            apply_near_credit(&mut account, batch, receipt);
+                            account.redeem_stake_batch = None
+                        }
+                    }
+                }
+
+                if let Some(batch) = account.next_redeem_stake_batch {
+                    if batch_pending_withdrawal_id != batch.id() {
+                        if let Some(receipt) = self.load_redeem_stake_batch_receipt(batch.id()) {
+                            apply_near_credit(&mut account, batch, receipt);
+                            account.next_redeem_stake_batch = None
+                        }
+                    }
+                }
+            } else {
+                if let Some(batch) = account.redeem_stake_batch {
+                    if let Some(receipt) = self.load_redeem_stake_batch_receipt(batch.id()) {
+                        apply_near_credit(&mut account, batch, receipt);
+                        account.redeem_stake_batch = None
+                    }
+                }
+
+                if let Some(batch) = account.next_redeem_stake_batch {
+                    if let Some(receipt) = self.load_redeem_stake_batch_receipt(batch.id()) {
+                        apply_near_credit(&mut account, batch, receipt);
+                        account.next_redeem_stake_batch = None
+                    }
+                }
+            }
+        }
+
+        account
+    }
+
+    pub(crate) fn claim_stake_batch_receipts(&mut self, account: &mut Account) -> bool {
+        fn claim_stake_tokens_for_batch(
+            contract: &mut Contract,
+            account: &mut Account,
+            batch: StakeBatch,
+            mut receipt: domain::StakeBatchReceipt,
+        ) {
+            // how much NEAR did the account stake in the batch
+            let staked_near = batch.balance().amount();
+
+            // claim the STAKE tokens for the account
+            let stake = receipt.stake_token_value().near_to_stake(staked_near);
+            account.apply_stake_credit(stake);
+
+            // track that the STAKE tokens were claimed
+            receipt.stake_tokens_issued(staked_near);
+            if receipt.all_claimed() {
+                // then delete the receipt and free the storage
+                contract.stake_batch_receipts.remove(&batch.id());
+                contract.workflow_counters.receipts_deleted += 1;
+            } else {
+                contract.stake_batch_receipts.insert(&batch.id(), &receipt);
+            }
+        }
+
+        let mut claimed_funds = false;
+
+        if let Some(batch) = account.stake_batch {
+            if let Some(receipt) = self.load_stake_batch_receipt(batch.id()) {
+                claim_stake_tokens_for_batch(self, account, batch, receipt);
+                account.stake_batch = None;
+                claimed_funds = true;
+            }
+        }
+
+        if let Some(batch) = account.next_stake_batch {
+            if let Some(receipt) = self.load_stake_batch_receipt(batch.id()) {
+                claim_stake_tokens_for_batch(self, account, batch, receipt);
+                account.next_stake_batch = None;
+                claimed_funds = true;
+            }
+        }
+
+        // move the next batch into the current batch as long as the contract is not locked and the
+        // funds for the current batch have been claimed
+        //
+        // NOTE: while the contract is locked for running a stake batch, all deposits must go into
+        //       the next batch
+        if !self.stake_batch_locked() && account.stake_batch.is_none() {
+            account.stake_batch = account.next_stake_batch.take();
+        }
+
+        claimed_funds
+    }
+
+    /// claim NEAR tokens for redeeming STAKE
+    pub(crate) fn claim_redeem_stake_batch_receipts(&mut self, account: &mut Account) -> bool {
+        fn claim_redeemed_stake_for_batch(
+            contract: &mut Contract,
+            account: &mut Account,
+            account_batch: domain::RedeemStakeBatch,
+            mut receipt: domain::RedeemStakeBatchReceipt,
+        ) {
+            // how much STAKE did the account redeem in the batch
+            let redeemed_stake = account_batch.balance().amount();
+
+            // claim the NEAR tokens for the account
+            let near = receipt.stake_token_value().stake_to_near(redeemed_stake);
+            account.apply_near_credit(near);
+
+            // track that the NEAR tokens were claimed
+            receipt.stake_tokens_redeemed(redeemed_stake);
+            if receipt.all_claimed() {
+                // then delete the receipt and free the storage
+                contract
+                    .redeem_stake_batch_receipts
+                    .remove(&account_batch.id());
+                contract.workflow_counters.receipts_deleted += 1;
+            } else {
+                contract
+                    .redeem_stake_batch_receipts
+                    .insert(&account_batch.id(), &receipt);
+            }
+        }
+
+        /// for a pending withdrawal, funds can also be claimed against the liquidity pool
+        fn claim_redeemed_stake_for_batch_pending_withdrawal(
+            contract: &mut Contract,
+            account: &mut Account,
+            account_batch: &mut domain::RedeemStakeBatch,
+            mut receipt: domain::RedeemStakeBatchReceipt,
+        ) {
+            // how much STAKE did the account redeem in the batch
+            let redeemed_stake = account_batch.balance().amount();
+
+            if contract.near_liquidity_pool.value() == 0 {
+                // the claim can only be served from the liquidity provider pool
+                contract.serve_claim_from_liquidity_provider_pool(
+                    account,
+                    account_batch,
+                    receipt.stake_token_value(),
+                );
+                return;
+            }
+
+            let redeemed_stake_near_value =
+                receipt.stake_token_value().stake_to_near(redeemed_stake);
+            let claimed_near = if contract.near_liquidity_pool >= redeemed_stake_near_value {
+                redeemed_stake_near_value
+            } else {
+                contract.near_liquidity_pool
+            };
+            let redeemable_stake = receipt.stake_token_value().near_to_stake(claimed_near);
+            account_batch.remove(redeemable_stake);
+
+            // claim the STAKE tokens for the account
+            // let near = receipt.stake_token_value().stake_to_near(redeemable_stake);
+            account.apply_near_credit(claimed_near);
+            contract.consume_near_liquidity(claimed_near, NearLiquidityOutflow::Claim);
+            contract.total_near.credit(claimed_near);
+
+            // the remainder is served from the liquidity provider pool - the liquidity provider pool
+            // takes over the remaining redeemed STAKE, i.e., the receipt is not updated for it
+            if account_batch.balance().amount().value() > 0 {
+                contract.serve_claim_from_liquidity_provider_pool(
+                    account,
+                    account_batch,
+                    receipt.stake_token_value(),
+                );
+            }
+
+            // track that the STAKE tokens were claimed
+            receipt.stake_tokens_redeemed(redeemable_stake);
+            if receipt.all_claimed() {
+                // this means that effectively all funds have been withdrawn
+                // which means we need to finalize the redeem workflow
+                contract
+                    .redeem_stake_batch_receipts
+                    .remove(&account_batch.id());
+                contract.workflow_counters.receipts_deleted += 1;
+                contract.redeem_stake_batch_lock = None;
+                contract.record_lock_released(false, "claim_receipts");
+                contract.pop_redeem_stake_batch();
+            } else {
+                contract
+                    .redeem_stake_batch_receipts
+                    .insert(&account_batch.id(), &receipt);
+            }
+        }
+
+        let mut claimed_funds = false;
+
+        match self.redeem_stake_batch_lock {
+            // NEAR funds can be claimed for receipts that are not pending on the unstaked NEAR withdrawal
+            // NEAR funds can also be claimed against the NEAR liquidity pool
+            Some(RedeemLock::PendingWithdrawal) => {
+                // NEAR funds cannot be claimed for a receipt that is pending withdrawal of unstaked NEAR from the staking pool
+                let pending_batch_id = self
+                    .redeem_stake_batch
+                    .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST)
+                    .id();
+
+                if let Some(mut batch) = account.redeem_stake_batch {
+                    if batch.id() != pending_batch_id {
+                        if let Some(receipt) = self.load_redeem_stake_batch_receipt(batch.id()) {
+                            claim_redeemed_stake_for_batch(self, account, batch, receipt);
+                            account.redeem_stake_batch = None;
+                            claimed_funds = true;
+                        }
+                    } else if self.has_pending_withdrawal_claim_liquidity() {
+                        if let Some(receipt) = self.load_redeem_stake_batch_receipt(batch.id()) {
+                            claim_redeemed_stake_for_batch_pending_withdrawal(
+                                self, account, &mut batch, receipt,
+                            );
+                            if batch.balance().amount().value() == 0 {
+                                account.redeem_stake_batch = None;
+                            } else {
+                                account.redeem_stake_batch = Some(batch);
+                            }
+                            claimed_funds = true;
+                        }
+                    }
+                }
+
+                if let Some(mut batch) = account.next_redeem_stake_batch {
+                    if batch.id() != pending_batch_id {
+                        if let Some(receipt) = self.load_redeem_stake_batch_receipt(batch.id()) {
+                            claim_redeemed_stake_for_batch(self, account, batch, receipt);
+                            account.next_redeem_stake_batch = None;
+                            claimed_funds = true;
+                        }
+                    } else if self.has_pending_withdrawal_claim_liquidity() {
+                        if let Some(receipt) = self.load_redeem_stake_batch_receipt(batch.id()) {
+                            claim_redeemed_stake_for_batch_pending_withdrawal(
+                                self, account, &mut batch, receipt,
+                            );
+                            if batch.balance().amount().value() == 0 {
+                                account.next_redeem_stake_batch = None;
+                            } else {
+                                account.next_redeem_stake_batch = Some(batch);
+                            }
+                            claimed_funds = true;
+                        }
+                    }
+                }
+            }
+            None => {
+                if let Some(batch) = account.redeem_stake_batch {
+                    if let Some(receipt) = self.load_redeem_stake_batch_receipt(batch.id()) {
+                        claim_redeemed_stake_for_batch(self, account, batch, receipt);
+                        account.redeem_stake_batch = None;
+                        claimed_funds = true;
+                    }
+                }
+
+                if let Some(batch) = account.next_redeem_stake_batch {
+                    if let Some(receipt) = self.load_redeem_stake_batch_receipt(batch.id()) {
+                        claim_redeemed_stake_for_batch(self, account, batch, receipt);
+                        account.next_redeem_stake_batch = None;
+                        claimed_funds = true;
+                    }
+                }
+            }
+            Some(_) => {
+                // this should never be reachable
+                // while unstaking STAKE balances need to be locked, which means no receipts should be claimed
+                return false;
+            }
+        }
+
+        // shift the next batch into the current batch if the funds have been claimed for the current batch
+        // and if the contract is not locked because it is running redeem stake batch workflow.
+        //
+        // NOTE: while a contract is locked, all redeem requests must be collected in the next batch
+        if self.redeem_stake_batch_lock.is_none() && account.redeem_stake_batch.is_none() {
+            account.redeem_stake_batch = account.next_redeem_stake_batch.take();
+        }
+
+        claimed_funds
+    }
+
+    pub(crate) fn is_unstaking(&self) -> bool {
+        match self.redeem_stake_batch_lock {
+            Some(RedeemLock::Unstaking) => true,
+            _ => false,
+        }
+    }
+
+    /// updates the [StakeTokenValue](crate::domain::StakeTokenValue) with the new staked NEAR balance
+    ///
+    /// Returns the staked NEAR compensation that was added to the NEAR liquidity pool to prevent the
+    /// STAKE token value from ticking down.
+    pub(crate) fn update_stake_token_value(
+        &mut self,
+        total_staked_near_balance: domain::YoctoNear,
+    ) -> domain::YoctoNear {
+        let new_stake_token_value = domain::StakeTokenValue::new(
+            domain::BlockTimeHeight::from_env(),
+            total_staked_near_balance,
+            self.total_stake.amount(),
+        );
+
+        // the new STAKE token value should never be less than the current STAKE token value, unless
+        // the total staked NEAR balance is zero
+        // - when NEAR is staked, the staking pool converts the NEAR into shares. Because of rounding,
+        //   not all staked NEAR gets converted into shares, and some is left behind as unstaked in
+        //   the staking pool. In the example below 0.25 NEAR was deposited to be staked, however
+        //   after converting the NEAR to shares, there were 5 yoctoNEAR left over that remained
+        //   as unstaked:
+        //
+        // Log [stake.oysterpack.testnet]: @stake.oysterpack.testnet deposited 250000000000000000000000. New unstaked balance is 654566211093653841620326
+        // Log [stake.oysterpack.testnet]: @stake.oysterpack.testnet staking 249999999999999999999995. Received 13510178747482595266283 new staking shares. Total 404566211093653841620331 unstaked balance and 1146041341904922841152939 staking shares
+        //
+        // Thus, if we see that the STAKE value ticks down, we need to compensate the [total_staked_near_balance]
+        // because the STAKE value should never decrease.
+        //
+        // How can this happen? When we withdraw unstaked funds, we do a withdraw all, which will
+        // withdraw unstaked NEAR that should have been staked but couldn't because of the share conversion
+        // rounding. When we need to compensate, then we need to add the compensation to the liquidity
+        // to balance everything out.
+        let new_stake_near_value = new_stake_token_value.stake_to_near(YOCTO.into());
+        let current_stake_near_value = self.stake_token_value.stake_to_near(YOCTO.into());
+        if self
+            .exceeds_max_stake_token_value_increase(current_stake_near_value, new_stake_near_value)
+        {
+            // the current STAKE token value is retained until the operator confirms the jump
+            self.pending_stake_token_value = Some(new_stake_token_value);
+            log(events::StakeTokenValueJumpPending {
+                current_stake_near_value: current_stake_near_value.value(),
+                pending_stake_near_value: new_stake_near_value.value(),
+            });
+            return 0.into();
+        }
+        self.pending_stake_token_value = None;
+        let mut compensation: domain::YoctoNear = 0.into();
+        self.stake_token_value = if new_stake_near_value >= current_stake_near_value
+            || total_staked_near_balance.value() == 0
+        {
+            new_stake_token_value
+        } else {
+            // (staked_near_compensation + total_staked_near_balance)    current_stake_near_value
+            // ------------------------------------------------------ =  ------------------------
+            //           total_staked_near_balance                               YOCTO
+            let required_staked_near_balance = domain::mul_div(
+                current_stake_near_value.value(),
+                self.total_stake.amount().value(),
+                YOCTO,
+                domain::STAKE_TOKEN_VALUE_COMPENSATION_ROUNDING,
+            );
+            let staked_near_compensation =
+                required_staked_near_balance.saturating_sub(total_staked_near_balance.value());
+            // compensation needs to be added back to NEAR liquidity to rebalance the amounts
+            compensation = staked_near_compensation.into();
+            self.add_near_liquidity(
+                compensation,
+                NearLiquidityInflow::StakeTokenValueCompensation,
+            );
+            domain::StakeTokenValue::new(
+                new_stake_token_value.block_time_height(),
+                (total_staked_near_balance.value() + staked_near_compensation).into(),
+                self.total_stake.amount(),
+            )
+        };
+        self.checkpoint_stake_token_value();
+        compensation
+    }
+
+    /// captures the raw staking pool balances and the inputs to the STAKE token value computation
+    /// before the batch STAKE token value is updated
+    /// - the audit is completed and recorded via [record_batch_audit](Contract::record_batch_audit)
+    pub(crate) fn new_batch_audit(
+        &self,
+        staked_balance: domain::YoctoNear,
+        unstaked_balance: domain::YoctoNear,
+    ) -> domain::BatchAudit {
+        domain::BatchAudit::new(
+            staked_balance,
+            unstaked_balance,
+            self.get_pending_withdrawal()
+                .map_or(0.into(), |receipt| receipt.stake_near_value()),
+            self.near_liquidity_pool,
+            self.staked_near_balance(staked_balance, unstaked_balance),
+            self.stake_token_value,
+        )
+    }
+
+    pub(crate) fn record_batch_audit(
+        &mut self,
+        batch_id: domain::BatchId,
+        mut audit: domain::BatchAudit,
+        compensation: domain::YoctoNear,
+    ) {
+        audit.complete(compensation, self.stake_token_value);
+        self.batch_audits.insert(&batch_id, &audit);
+    }
+
+    /// returns true if the STAKE token value increase exceeds the configured max percentage
+    fn exceeds_max_stake_token_value_increase(
+        &self,
+        current_stake_near_value: domain::YoctoNear,
+        new_stake_near_value: domain::YoctoNear,
+    ) -> bool {
+        let max_percentage = self.config.max_stake_token_value_increase_percentage();
+        if max_percentage == 0
+            || self.total_stake.amount().value() == 0
+            || new_stake_near_value <= current_stake_near_value
+        {
+            return false;
+        }
+        let max_increase =
+            U256::from(current_stake_near_value) * U256::from(max_percentage) / U256::from(100);
+        U256::from(new_stake_near_value - current_stake_near_value) > max_increase
+    }
+
+    /// commits the pending STAKE token value jump
+    ///
+    /// ## Panics
+    /// - if there is no pending STAKE token value jump
+    /// - if the STAKE supply has changed since the jump was recorded
+    pub(crate) fn commit_pending_stake_token_value(&mut self) {
+        let pending = self
+            .pending_stake_token_value
+            .take()
+            .expect(NO_PENDING_STAKE_TOKEN_VALUE_JUMP);
+        assert_eq!(
+            pending.total_stake_supply(),
+            self.total_stake.amount(),
+            "{}",
+            PENDING_STAKE_TOKEN_VALUE_JUMP_STALE
+        );
+        self.stake_token_value = pending;
+        self.checkpoint_stake_token_value();
+        log(events::StakeTokenValueJumpConfirmed {
+            stake_near_value: self.stake_token_value.stake_to_near(YOCTO.into()).value(),
+        });
+    }
+
+    /// records the current STAKE token value as the checkpoint for the current epoch
+    /// - if a checkpoint already exists for the epoch, then it is overwritten with the latest value
+    fn checkpoint_stake_token_value(&mut self) {
+        let epoch_height = self.stake_token_value.block_time_height().epoch_height();
+        let checkpoint = match self.last_stake_token_value_checkpoint {
+            Some(last_epoch_height) if last_epoch_height == epoch_height => {
+                let mut checkpoint = self
+                    .stake_token_value_checkpoints
+                    .get(&epoch_height)
+                    .expect("STAKE token value checkpoint should exist");
+                checkpoint.update(self.stake_token_value);
+                checkpoint
+            }
+            last_epoch_height => {
+                domain::StakeTokenValueCheckpoint::new(self.stake_token_value, last_epoch_height)
+            }
+        };
+        self.stake_token_value_checkpoints
+            .insert(&epoch_height, &checkpoint);
+        self.last_stake_token_value_checkpoint = Some(epoch_height);
+    }
+}
+
+type Balance = near_sdk::json_types::U128;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakingPoolAccount {
+    pub account_id: AccountId,
+    /// The unstaked balance that can be withdrawn or staked.
+    pub unstaked_balance: Balance,
+    /// The amount balance staked at the current "stake" share price.
+    pub staked_balance: Balance,
+    /// Whether the unstaked balance is available for withdrawal now.
+    pub can_withdraw: bool,
+}
+
+#[ext_contract(ext_redeeming_workflow_callbacks)]
+pub trait ExtRedeemingWorkflowCallbacks {
+    fn on_run_redeem_stake_batch(
+        &mut self,
+        #[callback] staked_balance: near_sdk::json_types::U128,
+    ) -> near_sdk::PromiseOrValue<interface::UnstakeResult>;
+
+    /// ## Success Workflow
+    /// 1. store the redeem stake batch receipt
+    /// 2. set the redeem stake batch lock state to pending withdrawal
+    fn on_unstake(&mut self) -> interface::UnstakeResult;
+
+    fn clear_redeem_lock(&mut self);
+
+    /// batch ID is returned when all unstaked NEAR has been withdrawn
+    fn on_redeeming_stake_pending_withdrawal(
+        &mut self,
+        #[callback] staking_pool_account: StakingPoolAccount,
+    ) -> near_sdk::PromiseOrValue<BatchId>;
+
+    fn on_redeeming_stake_post_withdrawal(&mut self) -> BatchId;
+}
+
+#[ext_contract(ext_staking_workflow_callbacks)]
+pub trait ExtStakingWorkflowCallbacks {
+    /// callback for getting staked balance from staking pool as part of stake batch processing workflow
+    ///
+    /// ## Success Workflow
+    /// 1. Check if liquidity is needed
+    /// 2. deposit and stake funds with staking pool
+    /// 3. then get account from staking pool
+    /// 4. then invoke [on_deposit_and_stake] callback
+    fn on_run_stake_batch(
+        &mut self,
+        #[callback] staking_pool_account: StakingPoolAccount,
+    ) -> Promise;
+
+    /// ## Success Workflow
+    /// 1. update the StateLock to Staked
+    /// 2. invoke [`process_staked_batch`]
+    fn on_deposit_and_stake(
+        &mut self,
+        near_liquidity: Option<interface::YoctoNear>,
+        #[callback] staking_pool_account: StakingPoolAccount,
+    ) -> near_sdk::PromiseOrValue<interface::StakeBatchResult>;
+
+    /// deferred verification path - the staking pool account is not fetched
+    fn on_stake_batch_deposited(
+        &mut self,
+        stake_amount: interface::YoctoNear,
+    ) -> Option<interface::StakeBatchResult>;
+
+    /// 1. update the stake token value
+    /// 2. store the stake batch receipt
+    /// 3. update the STAKE token supply with the new STAKE tokens that were issued
+    fn process_staked_batch(&mut self) -> interface::StakeBatchResult;
+
+    /// defined on [Operator] interface
+    fn clear_stake_lock(&mut self);
+}
+
+#[ext_contract(ext_callbacks)]
+pub trait Callbacks {
+    fn on_refresh_stake_token_value(
+        &mut self,
+        #[callback] staking_pool_account: StakingPoolAccount,
+    );
+
+    fn on_refresh_reward_fee_fraction(
+        &mut self,
+        #[callback] reward_fee_fraction: interface::RewardFeeFraction,
+    );
+
+    fn on_redeem_forwarding(&mut self, account_id: AccountId, amount: interface::YoctoNear);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DepositRedeemForwardingArgs {
+    pub account_id: AccountId,
+}
+
+#[near_bindgen]
+impl Contract {
+    #[private]
+    pub fn on_refresh_stake_token_value(
+        &mut self,
+        #[callback] staking_pool_account: StakingPoolAccount,
+    ) -> interface::StakeTokenValue {
+        let staked_balance = self.staked_near_balance(
+            staking_pool_account.staked_balance.into(),
+            staking_pool_account.unstaked_balance.into(),
+        );
+        self.update_stake_token_value(staked_balance);
+        self.verify_stake_batches(staked_balance);
+        self.clear_stake_lock();
+        self.record_workflow_success(Workflow::RefreshStakeTokenValue);
+        self.stake_token_value.into()
+    }
+
+    #[private]
+    pub fn on_refresh_reward_fee_fraction(
+        &mut self,
+        #[callback] reward_fee_fraction: interface::RewardFeeFraction,
+    ) -> interface::RewardFeeFraction {
+        let current: domain::RewardFeeFraction = reward_fee_fraction.into();
+        if let Some(previous) = self
+            .staking_pool_reward_fee_fraction
+            .filter(|previous| *previous != current)
+        {
+            log(events::StakingPoolRewardFeeChanged {
+                previous: previous.into(),
+                current: current.into(),
+            });
+        }
+        self.staking_pool_reward_fee_fraction = Some(current);
+        reward_fee_fraction
+    }
+
+    /// if the target STAKE contract rejected the forwarded redeem proceeds, then the NEAR is
+    /// credited back to the account
+    #[private]
+    pub fn on_redeem_forwarding(&mut self, account_id: AccountId, amount: interface::YoctoNear) {
+        if self.promise_result_succeeded() {
+            return;
+        }
+
+        let amount: domain::YoctoNear = amount.into();
+        match self.lookup_registered_account(&account_id) {
+            Some(mut account) => {
+                account.apply_near_credit(amount);
+                self.total_near.credit(amount);
+                self.save_registered_account(&account);
+            }
+            // the account was unregistered while the funds were being forwarded
+            None => self.contract_owner_balance += amount,
+        }
+        log(events::RedeemForwardingFailed {
+            account_id,
+            amount: amount.value(),
+        });
+    }
+}
+
+impl Contract {
+    fn invoke_refresh_stake_token_value(&self) -> Promise {
+        ext_callbacks::on_refresh_stake_token_value(
+            &env::current_account_id(),
+            NO_DEPOSIT.value(),
+            self.config
+                .gas_config()
+                .callbacks()
+                .on_refresh_stake_token_value()
+                .value(),
+        )
+    }
+
+    fn invoke_on_refresh_reward_fee_fraction(&self) -> Promise {
+        ext_callbacks::on_refresh_reward_fee_fraction(
+            &env::current_account_id(),
+            NO_DEPOSIT.value(),
+            self.config
+                .gas_config()
+                .callbacks()
+                .on_refresh_reward_fee_fraction()
+                .value(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_deposit {
+    use super::*;
+
+    use crate::interface::{AccountManagement, Operator};
+    use crate::{near::YOCTO, test_utils::*};
+    use near_sdk::{env, testing_env, MockedBlockchain, VMContext};
+
+    /// Given the contract is not locked
+    /// When an account deposits funds to be staked
+    /// Then the funds are deposited into the current stake batch on the account
+    /// And the funds are deposited into the current stake batch on the contract
+    #[test]
+    fn when_contract_not_locked() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+
+        let mut context = test_context.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+
+        // Act
+        let batch_id = test_context.deposit(None);
+        context.storage_usage = env::storage_usage();
+
+        fn check_stake_batch(
+            test_context: &mut Contract,
+            context: VMContext,
+            batch_id: BatchId,
+            expected_balance: YoctoNear,
+        ) {
+            // check account stake batch
+            let account = test_context
+                .lookup_account(to_valid_account_id(&context.predecessor_account_id))
+                .unwrap();
+            let account_stake_batch = account.stake_batch.as_ref().unwrap();
+            assert_eq!(
+                account_stake_batch.balance.amount.value(),
+                expected_balance.value()
+            );
+            assert_eq!(account_stake_batch.id, batch_id);
+            assert!(account.next_stake_batch.is_none());
+
+            // check contract state
+            {
+                let state = test_context.contract_state();
+                let contract_stake_batch = state.stake_batch.as_ref().unwrap();
+                assert_eq!(contract_stake_batch.balance, account_stake_batch.balance);
+                assert!(state.next_stake_batch.is_none());
+                assert_eq!(
+                    state.balances.customer_batched_stake_deposits.value(),
+                    account_stake_batch.balance.amount.value()
+                );
+                assert_eq!(
+                    state.balances.total_user_accounts_balance.value(),
+                    account_stake_batch.balance.amount.value()
+                        + account.storage_escrow.amount.value()
+                );
+            }
+        };
+
+        // Assert
+        check_stake_batch(
+            &mut test_context,
+            context.clone(),
+            batch_id.clone(),
+            YOCTO.into(),
+        );
+
+        // Act
+        // user makes another deposit into same StakeBatch
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+        let batch_id_2 = test_context.deposit(None);
+        context.storage_usage = env::storage_usage();
+
+        // Assert
+        assert_eq!(
+            batch_id, batch_id_2,
+            "NEAR should have been deposited into same batch"
+        );
+
+        check_stake_batch(
+            &mut test_context,
+            context.clone(),
+            batch_id_2,
+            (2 * YOCTO).into(),
+        );
+    }
+
+    /// Given the contract is locked
+    /// When an account deposits funds to be staked
+    /// Then the funds are deposited into the next stake batch on the account
+    /// And the funds are deposited into the next stake batch on the contract
+    #[test]
+    fn when_contract_locked() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+
+        let mut context = test_context.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+        let batch_id = contract.deposit(None);
+        context.storage_usage = env::storage_usage();
+
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        contract.stake(); // locks the contract
+        context.storage_usage = env::storage_usage();
+
+        // Act
+        context.attached_deposit = 2 * YOCTO;
+        testing_env!(context.clone());
+        let batch_id_2 = contract.deposit(None);
+        context.storage_usage = env::storage_usage();
+        assert_ne!(batch_id, batch_id_2);
+
+        // Assert
+        {
+            // check account STAKE batches
+            let account = contract
+                .lookup_account(to_valid_account_id(test_context.account_id))
+                .unwrap();
+
+            let stake_batch = account.stake_batch.as_ref().unwrap();
+            assert_eq!(stake_batch.balance.amount.value(), YOCTO);
+            assert_eq!(stake_batch.id, batch_id);
+
+            let next_stake_batch = account.next_stake_batch.as_ref().unwrap();
+            assert_eq!(next_stake_batch.balance.amount.value(), (2 * YOCTO));
+            assert_eq!(next_stake_batch.id, batch_id_2);
+
+            {
+                let state = contract.contract_state();
+
+                let contract_stake_batch = state.stake_batch.as_ref().unwrap();
+                assert_eq!(contract_stake_batch.id, stake_batch.id);
+                assert_eq!(
+                    contract_stake_batch.balance.amount,
+                    stake_batch.balance.amount
+                );
+
+                let contract_next_stake_batch = state.next_stake_batch.as_ref().unwrap();
+                assert_eq!(contract_next_stake_batch.id, next_stake_batch.id);
+                assert_eq!(
+                    contract_next_stake_batch.balance.amount,
+                    next_stake_batch.balance.amount
+                );
+            }
+        }
+
+        // Arrange - When another account deposits, then funds should go into next Stakebatch
+        let user_2 = "user-2.near";
+        context.predecessor_account_id = user_2.to_string();
+        context.attached_deposit = contract.account_storage_fee().value();
+        testing_env!(context.clone());
+        contract.register_account();
+        context.storage_usage = env::storage_usage();
+
+        // Act
+        context.attached_deposit = 3 * YOCTO;
+        testing_env!(context.clone());
+        let batch_id_3 = contract.deposit(None);
+        context.storage_usage = env::storage_usage();
+
+        // Assert
+        {
+            assert_eq!(batch_id_3, batch_id_2);
+            // check account STAKE batches
+            let account = contract
+                .lookup_account(to_valid_account_id(&context.predecessor_account_id))
+                .unwrap();
+
+            assert!(account.stake_batch.is_none());
+
+            let next_stake_batch = account.next_stake_batch.as_ref().unwrap();
+            assert_eq!(next_stake_batch.balance.amount.value(), (3 * YOCTO));
+            assert_eq!(next_stake_batch.id, batch_id_3);
+
+            {
+                let state = contract.contract_state();
+
+                let contract_next_stake_batch = state.next_stake_batch.as_ref().unwrap();
+                assert_eq!(contract_next_stake_batch.id, next_stake_batch.id);
+                assert_eq!(contract_next_stake_batch.balance.amount.value(), 5 * YOCTO);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "account is not registered")]
+    fn account_not_registered() {
+        let mut test_ctx = TestContext::new();
+        let contract = &mut test_ctx.contract;
+
+        let mut context = test_ctx.context.clone();
+        context.predecessor_account_id = "unregistered-user.near".to_string();
+        context.attached_deposit = YOCTO;
+        contract.deposit(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "minimum required NEAR deposit is")]
+    fn deposit_lt_min_required_deposit() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let contract = &mut test_ctx.contract;
+
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = contract.min_required_near_deposit().value() - 1;
+        testing_env!(context);
+        contract.deposit(None);
+    }
+
+    #[test]
+    fn deposit_eq_min_required_deposit() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let contract = &mut test_ctx.contract;
+
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = contract.min_required_near_deposit().value();
+        testing_env!(context);
+        contract.deposit(None);
+    }
+
+    #[test]
+    fn with_receipts_to_claim() {
+        // Arrange
+        let mut test_ctx = TestContext::with_registered_account();
+        let contract = &mut test_ctx.contract;
+
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+        let batch_id = contract.deposit(None);
+        context.storage_usage = env::storage_usage();
+
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        contract.stake();
+        context.storage_usage = env::storage_usage();
+
+        // progress the staking workflow to completion
+        {
+            context.attached_deposit = 0;
+            testing_env!(context.clone());
+            contract.on_deposit_and_stake(
+                None,
+                StakingPoolAccount {
+                    account_id: context.predecessor_account_id.clone(),
+                    unstaked_balance: 7.into(),
+                    staked_balance: (YOCTO - 7).into(),
+                    can_withdraw: true,
+                },
+            );
+            context.storage_usage = env::storage_usage();
+
+            testing_env!(context.clone());
+            let result = contract.process_staked_batch();
+            assert_eq!(result.batch_id, batch_id.clone());
+            assert_eq!(result.staked_near, YOCTO.into());
+            assert!(result.verified);
+            context.storage_usage = env::storage_usage();
+
+            context.predecessor_account_id = contract.operator_id();
+            testing_env!(context.clone());
+            contract.clear_stake_lock();
+            context.storage_usage = env::storage_usage();
+        }
+
+        // at this point, the user should have unclaimed batch receipt funds
+        context.is_view = true;
+        testing_env!(context.clone());
+        let receipt = contract
+            .stake_batch_receipt(batch_id.clone().into())
+            .unwrap();
+        assert_eq!(receipt.staked_near, YOCTO.into());
+
+        // Act
+        context.is_view = false;
+        context.predecessor_account_id = test_ctx.account_id.to_string();
+        context.attached_deposit = 2 * YOCTO;
+        testing_env!(context.clone());
+        contract.deposit(None);
+
+        // Assert
+        let account = contract
+            .lookup_account(to_valid_account_id(test_ctx.account_id))
+            .unwrap();
+        assert_eq!(account.stake.unwrap().amount.value(), YOCTO);
+
+        // check receipt was claimed
+        context.is_view = true;
+        testing_env!(context.clone());
+        assert!(contract.stake_batch_receipt(batch_id.into()).is_none());
+    }
+
+    #[test]
+    fn deposit_for_stake_target_refunds_excess_deposit() {
+        let mut ctx = TestContext::with_registered_account();
+        // 1 STAKE = 1.1 NEAR
+        ctx.stake_token_value = domain::StakeTokenValue::new(
+            Default::default(),
+            (1100 * YOCTO).into(),
+            (1000 * YOCTO).into(),
+        );
+
+        let mut context = ctx.context.clone();
+        context.attached_deposit = 200 * YOCTO;
+        testing_env!(context);
+        let batch_id = ctx.deposit_for_stake_target((100 * YOCTO).into());
+
+        let batch = ctx.stake_batch.unwrap();
+        assert_eq!(BatchId::from(batch.id()), batch_id);
+        assert_eq!(batch.balance().amount(), (110 * YOCTO).into());
+        assert_eq!(
+            ctx.stake_token_value
+                .near_to_stake(batch.balance().amount()),
+            (100 * YOCTO).into()
+        );
+        // the excess deposit is refunded
+        let refund = deserialize_receipts().into_iter().find(|receipt| {
+            receipt.receiver_id == ctx.account_id
+                && match receipt.actions[0] {
+                    Action::Transfer { deposit } => deposit == 90 * YOCTO,
+                    _ => false,
+                }
+        });
+        assert!(refund.is_some());
+    }
+
+    #[test]
+    fn deposit_for_stake_target_rounds_near_amount_up() {
+        let mut ctx = TestContext::with_registered_account();
+        // 1 STAKE = 1.5 NEAR
+        ctx.stake_token_value = domain::StakeTokenValue::new(
+            Default::default(),
+            (3 * YOCTO).into(),
+            (2 * YOCTO).into(),
+        );
+
+        let mut context = ctx.context.clone();
+        context.attached_deposit = 2 * YOCTO;
+        testing_env!(context);
+        ctx.deposit_for_stake_target((YOCTO + 1).into());
+
+        let batch = ctx.stake_batch.unwrap();
+        assert_eq!(batch.balance().amount(), (YOCTO * 3 / 2 + 2).into());
+        assert!(
+            ctx.stake_token_value
+                .near_to_stake(batch.balance().amount())
+                >= (YOCTO + 1).into()
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "attached deposit is insufficient to stake for the target STAKE amount: required deposit = 110000000000000000000000000 yoctoNEAR"
+    )]
+    fn deposit_for_stake_target_with_insufficient_deposit() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.stake_token_value = domain::StakeTokenValue::new(
+            Default::default(),
+            (1100 * YOCTO).into(),
+            (1000 * YOCTO).into(),
+        );
+
+        let mut context = ctx.context.clone();
+        context.attached_deposit = 100 * YOCTO;
+        testing_env!(context);
+        ctx.deposit_for_stake_target((100 * YOCTO).into());
+    }
+
+    #[test]
+    fn deposit_for_credits_target_account() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.register_account("faucet.near");
+
+        let mut context = ctx.set_predecessor_account_id("faucet.near");
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        let batch_id = ctx.deposit_for(to_valid_account_id(ctx.account_id));
+
+        let account = ctx
+            .lookup_account(to_valid_account_id(ctx.account_id))
+            .unwrap();
+        let stake_batch = account.stake_batch.unwrap();
+        assert_eq!(stake_batch.id, batch_id);
+        assert_eq!(stake_batch.balance.amount.value(), YOCTO);
+
+        let faucet = ctx
+            .lookup_account(to_valid_account_id("faucet.near"))
+            .unwrap();
+        assert!(faucet.stake_batch.is_none());
+        assert!(faucet.next_stake_batch.is_none());
+    }
+
+    #[test]
+    fn deposit_for_from_unregistered_predecessor() {
+        let mut ctx = TestContext::with_registered_account();
+
+        let mut context = ctx.set_predecessor_account_id("custodian.near");
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        ctx.deposit_for(to_valid_account_id(ctx.account_id));
+
+        let account = ctx
+            .lookup_account(to_valid_account_id(ctx.account_id))
+            .unwrap();
+        assert_eq!(account.stake_batch.unwrap().balance.amount.value(), YOCTO);
+    }
+
+    #[test]
+    #[should_panic(expected = "account is not registered")]
+    fn deposit_for_unregistered_account() {
+        let mut ctx = TestContext::with_registered_account();
+
+        let mut context = ctx.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        ctx.deposit_for(to_valid_account_id("unregistered-user.near"));
+    }
+}
+
+#[cfg(test)]
+mod test_stake_token_value {
+    use super::*;
+
+    use crate::{near::YOCTO, test_utils::*};
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn is_current() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+
+        let mut context = test_context.context.clone();
+        context.epoch_height = 100;
+        testing_env!(context);
+        test_context.total_stake.credit(YOCTO.into());
+        test_context.update_stake_token_value(YOCTO.into());
+
+        // Act - explict false
+        let stake_token_value = test_context.stake_token_value();
+
+        // Assert
+        assert_eq!(
+            stake_token_value.block_time_height.epoch_height,
+            test_context
+                .stake_token_value
+                .block_time_height()
+                .epoch_height()
+                .into()
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_stake_token_value_jump {
+    use super::*;
+
+    use crate::{near::YOCTO, test_utils::*};
+    use near_sdk::{serde_json, testing_env, MockedBlockchain};
+
+    fn setup(test_context: &mut TestContext) {
+        test_context.config.merge(
+            serde_json::from_str(r#"{"max_stake_token_value_increase_percentage": 5}"#).unwrap(),
+        );
+        testing_env!(test_context.context.clone());
+        test_context.total_stake.credit((100 * YOCTO).into());
+        test_context.update_stake_token_value((100 * YOCTO).into());
+    }
+
+    #[test]
+    fn increase_within_max_is_committed() {
+        let mut test_context = TestContext::with_registered_account();
+        setup(&mut test_context);
+
+        test_context.update_stake_token_value((105 * YOCTO).into());
+        assert_eq!(
+            test_context.stake_token_value.stake_to_near(YOCTO.into()),
+            (YOCTO * 105 / 100).into()
+        );
+        assert!(test_context.pending_stake_token_value_jump().is_none());
+    }
+
+    #[test]
+    fn jump_is_held_pending_until_confirmed() {
+        let mut test_context = TestContext::with_registered_account();
+        setup(&mut test_context);
+
+        test_context.update_stake_token_value((110 * YOCTO).into());
+        // the current STAKE token value is retained
+        assert_eq!(
+            test_context.stake_token_value.stake_to_near(YOCTO.into()),
+            YOCTO.into()
+        );
+        let pending = test_context.pending_stake_token_value_jump().unwrap();
+        assert_eq!(pending.total_staked_near_balance, (110 * YOCTO).into());
+
+        let mut context = test_context.context.clone();
+        context.predecessor_account_id = test_context.operator_id.clone();
+        testing_env!(context);
+        test_context.confirm_stake_value_jump();
+        assert_eq!(
+            test_context.stake_token_value.stake_to_near(YOCTO.into()),
+            (YOCTO * 110 / 100).into()
+        );
+        assert!(test_context.pending_stake_token_value_jump().is_none());
+    }
+
+    #[test]
+    fn update_within_max_clears_pending_jump() {
+        let mut test_context = TestContext::with_registered_account();
+        setup(&mut test_context);
+
+        test_context.update_stake_token_value((110 * YOCTO).into());
+        assert!(test_context.pending_stake_token_value_jump().is_some());
+        test_context.update_stake_token_value((101 * YOCTO).into());
+        assert!(test_context.pending_stake_token_value_jump().is_none());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "pending STAKE token value jump is stale because the STAKE supply has changed"
+    )]
+    fn confirm_stale_jump() {
+        let mut test_context = TestContext::with_registered_account();
+        setup(&mut test_context);
+
+        test_context.update_stake_token_value((110 * YOCTO).into());
+        test_context.total_stake.credit(YOCTO.into());
+
+        let mut context = test_context.context.clone();
+        context.predecessor_account_id = test_context.operator_id.clone();
+        testing_env!(context);
+        test_context.confirm_stake_value_jump();
+    }
+
+    #[test]
+    #[should_panic(expected = "contract call is only allowed by an operator account")]
+    fn confirm_not_invoked_by_operator() {
+        let mut test_context = TestContext::with_registered_account();
+        setup(&mut test_context);
+
+        test_context.update_stake_token_value((110 * YOCTO).into());
+        test_context.confirm_stake_value_jump();
+    }
+}
+
+#[cfg(test)]
+mod test_stake_token_value_twap {
+    use super::*;
+
+    use crate::{near::YOCTO, test_utils::*};
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn no_checkpoints() {
+        let test_context = TestContext::with_registered_account();
+        assert!(test_context.stake_token_value_twap(10).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "window_epochs must be > 0")]
+    fn zero_window() {
+        let test_context = TestContext::with_registered_account();
+        test_context.stake_token_value_twap(0);
+    }
 
-                if let Some(batch) = account.next_redeem_stake_batch {
-                    if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
-                        claim_redeemed_stake_for_batch(self, account, batch, receipt);
-                        account.next_redeem_stake_batch = None;
-                        claimed_funds = true;
-                    }
-                }
-            }
-            Some(_) => {
-                // this should never be reachable
-                // while unstaking STAKE balances need to be locked, which means no receipts should be claimed
-                return false;
-            }
-        }
+    /// Given STAKE token value checkpoints were recorded at epochs 10 and 12
+    /// When the TWAP is computed at epoch 13 over 4 epochs
+    /// Then the epoch 10 value is weighted for epochs 10-11 and the epoch 12 value for epochs 12-13
+    #[test]
+    fn with_checkpoint_gaps() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        test_context.total_stake.credit((100 * YOCTO).into());
 
-        // shift the next batch into the current batch if the funds have been claimed for the current batch
-        // and if the contract is not locked because it is running redeem stake batch workflow.
-        //
-        // NOTE: while a contract is locked, all redeem requests must be collected in the next batch
-        if self.redeem_stake_batch_lock.is_none() && account.redeem_stake_batch.is_none() {
-            account.redeem_stake_batch = account.next_redeem_stake_batch.take();
-        }
+        context.epoch_height = 10;
+        testing_env!(context.clone());
+        test_context.update_stake_token_value((100 * YOCTO).into());
 
-        claimed_funds
+        context.epoch_height = 12;
+        testing_env!(context.clone());
+        // value within the same epoch is overwritten by the latest value
+        test_context.update_stake_token_value((110 * YOCTO).into());
+        test_context.update_stake_token_value((120 * YOCTO).into());
+
+        // Act
+        context.epoch_height = 13;
+        testing_env!(context.clone());
+        let twap = test_context.stake_token_value_twap(4).unwrap();
+
+        // Assert
+        assert_eq!(twap.epochs, 4);
+        assert_eq!(twap.from_epoch_height, domain::EpochHeight(10).into());
+        assert_eq!(twap.to_epoch_height, domain::EpochHeight(13).into());
+        assert_eq!(twap.value.value(), (YOCTO * 2 + YOCTO * 12 / 10 * 2) / 4);
+
+        // window that only covers the latest checkpoint
+        let twap = test_context.stake_token_value_twap(2).unwrap();
+        assert_eq!(twap.epochs, 2);
+        assert_eq!(twap.value.value(), YOCTO * 12 / 10);
+
+        // window that extends past the first checkpoint
+        let twap = test_context.stake_token_value_twap(100).unwrap();
+        assert_eq!(twap.epochs, 4);
+        assert_eq!(twap.from_epoch_height, domain::EpochHeight(10).into());
     }
 
-    pub(crate) fn is_unstaking(&self) -> bool {
-        match self.redeem_stake_batch_lock {
-            Some(RedeemLock::Unstaking) => true,
-            _ => false,
+    #[test]
+    fn stake_token_value_history() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        assert!(test_context.stake_token_value_history(10).is_empty());
+        test_context.total_stake.credit((100 * YOCTO).into());
+
+        for (epoch_height, staked_near) in [(10, 100), (12, 110), (13, 120)].iter() {
+            context.epoch_height = *epoch_height;
+            testing_env!(context.clone());
+            test_context.update_stake_token_value((staked_near * YOCTO).into());
         }
-    }
 
-    /// returns a new [StakeTokenValue](crate::domain::StakeTokenValue) updated with the new staked
-    /// NEAR balance.
-    pub(crate) fn update_stake_token_value(
-        &mut self,
-        total_staked_near_balance: domain::YoctoNear,
-    ) {
-        let new_stake_token_value = domain::StakeTokenValue::new(
-            domain::BlockTimeHeight::from_env(),
-            total_staked_near_balance,
-            self.total_stake.amount(),
+        let history = test_context.stake_token_value_history(10);
+        assert_eq!(history.len(), 3);
+        // most recent first
+        assert_eq!(
+            history[0].block_time_height.epoch_height,
+            domain::EpochHeight(13).into()
+        );
+        assert_eq!(history[0].total_staked_near_balance, (120 * YOCTO).into());
+        assert_eq!(history[0].total_stake_supply, (100 * YOCTO).into());
+        assert_eq!(history[0].value, (YOCTO * 12 / 10).into());
+        assert_eq!(
+            history[2].block_time_height.epoch_height,
+            domain::EpochHeight(10).into()
         );
 
-        // the new STAKE token value should never be less than the current STAKE token value, unless
-        // the total staked NEAR balance is zero
-        // - when NEAR is staked, the staking pool converts the NEAR into shares. Because of rounding,
-        //   not all staked NEAR gets converted into shares, and some is left behind as unstaked in
-        //   the staking pool. In the example below 0.25 NEAR was deposited to be staked, however
-        //   after converting the NEAR to shares, there were 5 yoctoNEAR left over that remained
-        //   as unstaked:
-        //
-        // Log [stake.oysterpack.testnet]: @stake.oysterpack.testnet deposited 250000000000000000000000. New unstaked balance is 654566211093653841620326
-        // Log [stake.oysterpack.testnet]: @stake.oysterpack.testnet staking 249999999999999999999995. Received 13510178747482595266283 new staking shares. Total 404566211093653841620331 unstaked balance and 1146041341904922841152939 staking shares
-        //
-        // Thus, if we see that the STAKE value ticks down, we need to compensate the [total_staked_near_balance]
-        // because the STAKE value should never decrease.
-        //
-        // How can this happen? When we withdraw unstaked funds, we do a withdraw all, which will
-        // withdraw unstaked NEAR that should have been staked but couldn't because of the share conversion
-        // rounding. When we need to compensate, then we need to add the compensation to the liquidity
-        // to balance everything out.
-        let new_stake_near_value = new_stake_token_value.stake_to_near(YOCTO.into());
-        let current_stake_near_value = self.stake_token_value.stake_to_near(YOCTO.into());
-        self.stake_token_value = if new_stake_near_value >= current_stake_near_value
-            || total_staked_near_balance.value() == 0
-        {
-            new_stake_token_value
-        } else {
-            let current_stake_near_value: U256 = U256::from(current_stake_near_value);
-            let total_stake_supply: U256 = U256::from(self.total_stake.amount());
-            let total_staked_near_balance: U256 = U256::from(total_staked_near_balance.value());
-            // (staked_near_compensation + total_staked_near_balance)    current_stake_near_value
-            // ------------------------------------------------------ =  ------------------------
-            //           total_staked_near_balance                               YOCTO
-            let staked_near_compensation = (current_stake_near_value * total_stake_supply
-                / U256::from(YOCTO))
-                - total_staked_near_balance;
-            // compensation needs to be added back to NEAR liquidity to rebalance the amounts
-            *self.near_liquidity_pool += staked_near_compensation.as_u128();
-            log(events::NearLiquidityAdded {
-                amount: staked_near_compensation.as_u128(),
-                balance: self.near_liquidity_pool.value(),
-            });
-            domain::StakeTokenValue::new(
-                new_stake_token_value.block_time_height(),
-                (total_staked_near_balance + staked_near_compensation)
-                    .as_u128()
-                    .into(),
-                self.total_stake.amount(),
-            )
-        }
+        let history = test_context.stake_token_value_history(2);
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            history[1].block_time_height.epoch_height,
+            domain::EpochHeight(12).into()
+        );
     }
 }
 
-type Balance = near_sdk::json_types::U128;
+#[cfg(test)]
+mod test_route_quotes {
+    use super::*;
 
-#[derive(Serialize, Deserialize, Clone)]
-#[serde(crate = "near_sdk::serde")]
-pub struct StakingPoolAccount {
-    pub account_id: AccountId,
-    /// The unstaked balance that can be withdrawn or staked.
-    pub unstaked_balance: Balance,
-    /// The amount balance staked at the current "stake" share price.
-    pub staked_balance: Balance,
-    /// Whether the unstaked balance is available for withdrawal now.
-    pub can_withdraw: bool,
-}
+    use crate::{near::YOCTO, test_utils::*};
+    use near_sdk::{testing_env, MockedBlockchain};
 
-#[ext_contract(ext_redeeming_workflow_callbacks)]
-pub trait ExtRedeemingWorkflowCallbacks {
-    fn on_run_redeem_stake_batch(
-        &mut self,
-        #[callback] staked_balance: near_sdk::json_types::U128,
-    ) -> Promise;
+    #[test]
+    fn entry_quote() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        test_context.total_stake.credit((100 * YOCTO).into());
+        test_context.update_stake_token_value((110 * YOCTO).into());
+
+        let quote = test_context.entry_quote((11 * YOCTO).into());
+        assert_eq!(quote.near, (11 * YOCTO).into());
+        assert_eq!(quote.stake, (10 * YOCTO).into());
+        assert_eq!(quote.rate, (YOCTO * 11 / 10).into());
+        assert_eq!(quote.fee, 0.into());
+        assert_eq!(quote.delay_epochs, 0);
+        assert!(quote.instant);
+        assert!(quote.executable);
+
+        // below the min required deposit
+        let quote = test_context.entry_quote(1.into());
+        assert!(!quote.executable);
+
+        // STAKE cannot be minted until the staking pool backoff expires
+        context.epoch_height = 10;
+        testing_env!(context.clone());
+        test_context.staking_pool_unavailable_until = Some(12.into());
+        let quote = test_context.entry_quote((11 * YOCTO).into());
+        assert_eq!(quote.delay_epochs, 2);
+        assert!(!quote.instant);
+    }
 
-    /// ## Success Workflow
-    /// 1. store the redeem stake batch receipt
-    /// 2. set the redeem stake batch lock state to pending withdrawal
-    fn on_unstake(&mut self);
+    #[test]
+    fn exit_quote() {
+        let mut test_context = TestContext::with_registered_account();
+        test_context.total_stake.credit((100 * YOCTO).into());
+        test_context.update_stake_token_value((110 * YOCTO).into());
+
+        let quote = test_context.exit_quote((10 * YOCTO).into());
+        assert_eq!(quote.near, (11 * YOCTO).into());
+        assert_eq!(quote.stake, (10 * YOCTO).into());
+        assert_eq!(quote.rate, (YOCTO * 11 / 10).into());
+        assert_eq!(quote.fee, 0.into());
+        assert_eq!(
+            quote.delay_epochs,
+            UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK.value()
+        );
+        assert_eq!(quote.instant_liquidity, 0.into());
+        assert!(!quote.instant);
+        assert!(quote.executable);
 
-    fn clear_redeem_lock(&mut self);
+        // NEAR liquidity covers the exit
+        test_context.near_liquidity_pool = (11 * YOCTO).into();
+        let quote = test_context.exit_quote((10 * YOCTO).into());
+        assert_eq!(quote.instant_liquidity, (11 * YOCTO).into());
+        assert!(quote.instant);
+        let quote = test_context.exit_quote((11 * YOCTO).into());
+        assert!(!quote.instant);
 
-    /// batch ID is returned when all unstaked NEAR has been withdrawn
-    fn on_redeeming_stake_pending_withdrawal(
-        &mut self,
-        #[callback] staking_pool_account: StakingPoolAccount,
-    ) -> near_sdk::PromiseOrValue<BatchId>;
+        assert!(!test_context.exit_quote(0.into()).executable);
+    }
 
-    fn on_redeeming_stake_post_withdrawal(&mut self) -> BatchId;
-}
+    #[test]
+    fn simulate_deposit() {
+        let mut test_context = TestContext::with_registered_account();
+        test_context.total_stake.credit((100 * YOCTO).into());
+        test_context.update_stake_token_value((110 * YOCTO).into());
+
+        let simulation = test_context.simulate_deposit((11 * YOCTO).into());
+        assert_eq!(simulation.stake, (10 * YOCTO).into());
+        assert!(simulation.violations.is_empty());
+        // the deposit would create the next batch
+        let batch_id: domain::BatchId = simulation.batch_id.into();
+        assert_eq!(
+            batch_id,
+            test_context
+                .batch_id_sequence
+                .next()
+                .with_kind(BatchKind::Stake)
+        );
 
-#[ext_contract(ext_staking_workflow_callbacks)]
-pub trait ExtStakingWorkflowCallbacks {
-    /// callback for getting staked balance from staking pool as part of stake batch processing workflow
-    ///
-    /// ## Success Workflow
-    /// 1. Check if liquidity is needed
-    /// 2. deposit and stake funds with staking pool
-    /// 3. then get account from staking pool
-    /// 4. then invoke [on_deposit_and_stake] callback
-    fn on_run_stake_batch(
-        &mut self,
-        #[callback] staking_pool_account: StakingPoolAccount,
-    ) -> Promise;
+        let mut account = test_context.registered_account(test_context.account_id);
+        test_context.add_near_to_stake_batch(&mut account.account, (11 * YOCTO).into());
+        let simulation = test_context.simulate_deposit((11 * YOCTO).into());
+        assert_eq!(simulation.batch_id, batch_id.into());
 
-    /// ## Success Workflow
-    /// 1. update the StateLock to Staked
-    /// 2. invoke [`process_staked_batch`]
-    fn on_deposit_and_stake(
-        &mut self,
-        near_liquidity: Option<interface::YoctoNear>,
-        #[callback] staking_pool_account: StakingPoolAccount,
-    ) -> Promise;
+        // while the stake batch is running, the deposit goes into the next stake batch
+        test_context.stake_batch_lock = Some(StakeLock::Staking);
+        let simulation = test_context.simulate_deposit((11 * YOCTO).into());
+        assert_ne!(simulation.batch_id, batch_id.into());
 
-    /// 1. update the stake token value
-    /// 2. store the stake batch receipt
-    /// 3. update the STAKE token supply with the new STAKE tokens that were issued
-    fn process_staked_batch(&mut self);
+        assert_eq!(
+            test_context.simulate_deposit(0.into()).violations,
+            vec![DEPOSIT_REQUIRED_FOR_STAKE.to_string()]
+        );
+        let violations = test_context.simulate_deposit(1.into()).violations;
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].starts_with("minimum required NEAR deposit is"));
+    }
 
-    /// defined on [Operator] interface
-    fn clear_stake_lock(&mut self);
+    #[test]
+    fn simulate_redeem() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        context.epoch_height = 10;
+        testing_env!(context);
+        test_context.total_stake.credit((100 * YOCTO).into());
+        test_context.update_stake_token_value((110 * YOCTO).into());
+
+        let simulation = test_context.simulate_redeem((10 * YOCTO).into());
+        assert_eq!(simulation.near, (11 * YOCTO).into());
+        assert_eq!(
+            simulation.available_epoch_height,
+            (10 + UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK.value()).into()
+        );
+        assert!(simulation.violations.is_empty());
+
+        // while the redeem batch is being unstaked, the STAKE goes into the next redeem batch, which
+        // is unstaked once the pending withdrawal clears
+        test_context.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+        let simulation = test_context.simulate_redeem((10 * YOCTO).into());
+        assert_eq!(
+            simulation.available_epoch_height,
+            (10 + 2 * UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK.value()).into()
+        );
+
+        assert_eq!(
+            test_context.simulate_redeem(0.into()).violations,
+            vec![ZERO_REDEEM_AMOUNT.to_string()]
+        );
+    }
 }
 
-#[ext_contract(ext_callbacks)]
-pub trait Callbacks {
-    fn on_refresh_stake_token_value(
-        &mut self,
-        #[callback] staking_pool_account: StakingPoolAccount,
-    );
+#[cfg(test)]
+mod test_redeem_for_near_instant {
+    use super::*;
+
+    use crate::{near::YOCTO, test_utils::*};
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    /// 1 STAKE = 1.1 NEAR and the account holds 10 STAKE
+    fn setup(near_liquidity: u128) -> TestContext<'static> {
+        let mut test_context = TestContext::with_registered_account();
+        test_context.total_stake.credit((100 * YOCTO).into());
+        test_context.update_stake_token_value((110 * YOCTO).into());
+        let mut account = test_context.registered_account(test_context.account_id);
+        account.apply_stake_credit((10 * YOCTO).into());
+        test_context.save_registered_account(&account);
+        test_context.near_liquidity_pool = near_liquidity.into();
+        let context = test_context.context.clone();
+        testing_env!(context);
+        test_context
+    }
+
+    #[test]
+    fn fully_filled() {
+        let mut test_context = setup(20 * YOCTO);
+
+        let result = test_context.redeem_for_near_instant((5 * YOCTO).into());
+        assert_eq!(result.redeemed, (5 * YOCTO).into());
+        assert_eq!(result.near, (YOCTO * 55 / 10).into());
+        assert_eq!(result.unfilled, 0.into());
+
+        let account = test_context.registered_account(test_context.account_id);
+        assert_eq!(account.stake.unwrap().amount(), (5 * YOCTO).into());
+        assert_eq!(account.near.unwrap().amount(), (YOCTO * 55 / 10).into());
+        assert_eq!(test_context.total_stake.amount(), (95 * YOCTO).into());
+        assert_eq!(
+            test_context.near_liquidity_pool,
+            (20 * YOCTO - YOCTO * 55 / 10).into()
+        );
+        assert_eq!(
+            test_context
+                .near_liquidity_stats
+                .consumed_by_instant_redeems(),
+            (YOCTO * 55 / 10).into()
+        );
+    }
+
+    #[test]
+    fn partially_filled() {
+        let mut test_context = setup(11 * YOCTO / 2);
+
+        let result = test_context.redeem_for_near_instant((10 * YOCTO).into());
+        assert_eq!(result.redeemed, (5 * YOCTO).into());
+        assert_eq!(result.near, (11 * YOCTO / 2).into());
+        assert_eq!(result.unfilled, (5 * YOCTO).into());
+        assert_eq!(test_context.near_liquidity_pool, 0.into());
+
+        let account = test_context.registered_account(test_context.account_id);
+        assert_eq!(account.stake.unwrap().amount(), (5 * YOCTO).into());
+    }
+
+    #[test]
+    #[should_panic(expected = "there is no NEAR liquidity available to instantly redeem STAKE")]
+    fn no_liquidity() {
+        let mut test_context = setup(0);
+        test_context.redeem_for_near_instant((5 * YOCTO).into());
+    }
 }
 
-#[near_bindgen]
-impl Contract {
-    #[private]
-    pub fn on_refresh_stake_token_value(
-        &mut self,
-        #[callback] staking_pool_account: StakingPoolAccount,
-    ) -> interface::StakeTokenValue {
-        let staked_balance = self.staked_near_balance(
-            staking_pool_account.staked_balance.into(),
-            staking_pool_account.unstaked_balance.into(),
+#[cfg(test)]
+mod test_redeem_timeline {
+    use super::*;
+
+    use crate::{near::YOCTO, test_utils::*};
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    fn assert_step(
+        step: &RedeemTimelineStep,
+        stage: RedeemTimelineStage,
+        epoch_height: u64,
+        block_height: u64,
+        reached: bool,
+    ) {
+        assert_eq!(step.stage, stage);
+        assert_eq!(step.epoch_height, domain::EpochHeight(epoch_height).into());
+        assert_eq!(step.block_height, domain::BlockHeight(block_height).into());
+        assert_eq!(step.reached, reached);
+    }
+
+    #[test]
+    fn redeem_timeline() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        context.epoch_height = 10;
+        context.block_index = 1000;
+        testing_env!(context.clone());
+        test_context.credit_stake(test_context.account_id, 100 * YOCTO);
+
+        let batch_id = test_context.redeem((10 * YOCTO).into());
+        let timeline = test_context.redeem_timeline(batch_id.clone()).unwrap();
+        assert_eq!(timeline.batch_id, batch_id);
+        assert_step(
+            &timeline.steps[0],
+            RedeemTimelineStage::Unstake,
+            10,
+            1000,
+            false,
+        );
+        assert_step(
+            &timeline.steps[1],
+            RedeemTimelineStage::Withdrawal,
+            14,
+            1000 + 4 * EPOCH_LENGTH,
+            false,
+        );
+        assert_step(
+            &timeline.steps[2],
+            RedeemTimelineStage::Claim,
+            14,
+            1000 + 4 * EPOCH_LENGTH,
+            false,
+        );
+
+        // the batch is unstaked - and STAKE that is redeemed while the batch is pending withdrawal
+        // is added to the next batch
+        context.block_index = 1100;
+        testing_env!(context.clone());
+        test_context.update_stake_token_value((100 * YOCTO).into());
+        test_context.redeem_stake_batch_receipts.insert(
+            &batch_id.clone().into(),
+            &domain::RedeemStakeBatchReceipt::new(
+                (10 * YOCTO).into(),
+                test_context.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
+        );
+        test_context.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+        let next_batch_id = test_context.redeem((10 * YOCTO).into());
+        assert_ne!(next_batch_id, batch_id);
+
+        let timeline = test_context.redeem_timeline(batch_id.clone()).unwrap();
+        assert_step(
+            &timeline.steps[0],
+            RedeemTimelineStage::Unstake,
+            10,
+            1100,
+            true,
+        );
+        assert!(!timeline.steps[1].reached);
+        let timeline = test_context.redeem_timeline(next_batch_id.clone()).unwrap();
+        assert_step(
+            &timeline.steps[0],
+            RedeemTimelineStage::Unstake,
+            14,
+            1100 + 4 * EPOCH_LENGTH,
+            false,
+        );
+        assert_step(
+            &timeline.steps[1],
+            RedeemTimelineStage::Withdrawal,
+            18,
+            1100 + 8 * EPOCH_LENGTH,
+            false,
+        );
+
+        // the unstaked NEAR is withdrawn and the next batch becomes the current batch
+        context.epoch_height = 15;
+        context.block_index = 1100 + 5 * EPOCH_LENGTH;
+        testing_env!(context.clone());
+        test_context.redeem_stake_batch = test_context.next_redeem_stake_batch.take();
+        test_context.redeem_stake_batch_lock = None;
+        let timeline = test_context.redeem_timeline(batch_id).unwrap();
+        assert_step(
+            &timeline.steps[1],
+            RedeemTimelineStage::Withdrawal,
+            14,
+            1100 + 4 * EPOCH_LENGTH,
+            true,
+        );
+        assert!(timeline.steps[2].reached);
+        let timeline = test_context.redeem_timeline(next_batch_id).unwrap();
+        assert_step(
+            &timeline.steps[0],
+            RedeemTimelineStage::Unstake,
+            15,
+            1100 + 5 * EPOCH_LENGTH,
+            false,
         );
-        self.update_stake_token_value(staked_balance);
-        self.clear_stake_lock();
-        self.stake_token_value.into()
-    }
-}
 
-impl Contract {
-    fn invoke_refresh_stake_token_value(&self) -> Promise {
-        ext_callbacks::on_refresh_stake_token_value(
-            &env::current_account_id(),
-            NO_DEPOSIT.value(),
-            self.config
-                .gas_config()
-                .callbacks()
-                .on_refresh_stake_token_value()
-                .value(),
-        )
+        assert!(test_context
+            .redeem_timeline(domain::BatchId(1000).into())
+            .is_none());
     }
 }
 
 #[cfg(test)]
-mod test_deposit {
+mod test_refresh_stake_token_value {
     use super::*;
 
-    use crate::interface::{AccountManagement, Operator};
     use crate::{near::YOCTO, test_utils::*};
-    use near_sdk::{env, testing_env, MockedBlockchain, VMContext};
+    use near_sdk::{testing_env, MockedBlockchain};
 
-    /// Given the contract is not locked
-    /// When an account deposits funds to be staked
-    /// Then the funds are deposited into the current stake batch on the account
-    /// And the funds are deposited into the current stake batch on the contract
     #[test]
-    fn when_contract_not_locked() {
+    #[should_panic(expected = "action is blocked because a batch is running")]
+    fn has_staking_lock() {
         // Arrange
         let mut test_context = TestContext::with_registered_account();
-
-        let mut context = test_context.context.clone();
-        context.attached_deposit = YOCTO;
-        testing_env!(context.clone());
+        test_context.stake_batch_lock = Some(StakeLock::Staking);
 
         // Act
-        let batch_id = test_context.deposit();
-        context.storage_usage = env::storage_usage();
+        test_context.refresh_stake_token_value();
+    }
 
-        fn check_stake_batch(
-            test_context: &mut Contract,
-            context: VMContext,
-            batch_id: BatchId,
-            expected_balance: YoctoNear,
-        ) {
-            // check account stake batch
-            let account = test_context
-                .lookup_account(to_valid_account_id(&context.predecessor_account_id))
-                .unwrap();
-            let account_stake_batch = account.stake_batch.as_ref().unwrap();
-            assert_eq!(
-                account_stake_batch.balance.amount.value(),
-                expected_balance.value()
-            );
-            assert_eq!(account_stake_batch.id, batch_id);
-            assert!(account.next_stake_batch.is_none());
+    #[test]
+    #[should_panic(expected = "action is blocked because a batch is running")]
+    fn has_staked_lock() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        test_context.stake_batch_lock = Some(StakeLock::Staked {
+            near_liquidity: None,
+            staked_balance: Default::default(),
+            unstaked_balance: Default::default(),
+        });
 
-            // check contract state
-            {
-                let state = test_context.contract_state();
-                let contract_stake_batch = state.stake_batch.as_ref().unwrap();
-                assert_eq!(contract_stake_batch.balance, account_stake_batch.balance);
-                assert!(state.next_stake_batch.is_none());
-                assert_eq!(
-                    state.balances.customer_batched_stake_deposits.value(),
-                    account_stake_batch.balance.amount.value()
-                );
-                assert_eq!(
-                    state.balances.total_user_accounts_balance.value(),
-                    account_stake_batch.balance.amount.value()
-                        + account.storage_escrow.amount.value()
-                );
-            }
-        };
+        // Act
+        test_context.refresh_stake_token_value();
+    }
 
-        // Assert
-        check_stake_batch(
-            &mut test_context,
-            context.clone(),
-            batch_id.clone(),
-            YOCTO.into(),
-        );
+    #[test]
+    #[should_panic(expected = "action is blocked because a batch is running")]
+    fn has_unstaking_lock() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        test_context.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
 
         // Act
-        // user makes another deposit into same StakeBatch
-        context.attached_deposit = YOCTO;
-        testing_env!(context.clone());
-        let batch_id_2 = test_context.deposit();
-        context.storage_usage = env::storage_usage();
+        test_context.refresh_stake_token_value();
+    }
 
-        // Assert
-        assert_eq!(
-            batch_id, batch_id_2,
-            "NEAR should have been deposited into same batch"
-        );
+    #[test]
+    #[should_panic(expected = "action is blocked because STAKE token value is being refreshed")]
+    fn has_refreshing_stake_token_value_lock() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        test_context.stake_batch_lock = Some(StakeLock::RefreshingStakeTokenValue);
 
-        check_stake_batch(
-            &mut test_context,
-            context.clone(),
-            batch_id_2,
-            (2 * YOCTO).into(),
-        );
+        // Act
+        test_context.refresh_stake_token_value();
     }
 
-    /// Given the contract is locked
-    /// When an account deposits funds to be staked
-    /// Then the funds are deposited into the next stake batch on the account
-    /// And the funds are deposited into the next stake batch on the contract
     #[test]
-    fn when_contract_locked() {
+    fn no_locks() {
         // Arrange
         let mut test_context = TestContext::with_registered_account();
-        let contract = &mut test_context.contract;
 
         let mut context = test_context.context.clone();
-        context.attached_deposit = YOCTO;
-        testing_env!(context.clone());
-        let batch_id = contract.deposit();
-        context.storage_usage = env::storage_usage();
-
-        context.attached_deposit = 0;
-        testing_env!(context.clone());
-        contract.stake(); // locks the contract
-        context.storage_usage = env::storage_usage();
+        context.epoch_height = 100;
+        testing_env!(context);
+        test_context.total_stake.credit(YOCTO.into());
+        test_context.update_stake_token_value(YOCTO.into());
 
         // Act
-        context.attached_deposit = 2 * YOCTO;
-        testing_env!(context.clone());
-        let batch_id_2 = contract.deposit();
-        context.storage_usage = env::storage_usage();
-        assert_ne!(batch_id, batch_id_2);
+        test_context.refresh_stake_token_value();
 
         // Assert
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 2);
         {
-            // check account STAKE batches
-            let account = contract
-                .lookup_account(to_valid_account_id(test_context.account_id))
-                .unwrap();
-
-            let stake_batch = account.stake_batch.as_ref().unwrap();
-            assert_eq!(stake_batch.balance.amount.value(), YOCTO);
-            assert_eq!(stake_batch.id, batch_id);
-
-            let next_stake_batch = account.next_stake_batch.as_ref().unwrap();
-            assert_eq!(next_stake_batch.balance.amount.value(), (2 * YOCTO));
-            assert_eq!(next_stake_batch.id, batch_id_2);
-
+            let receipt = &receipts[0];
+            let actions = &receipt.actions;
+            assert_eq!(actions.len(), 2);
             {
-                let state = contract.contract_state();
-
-                let contract_stake_batch = state.stake_batch.as_ref().unwrap();
-                assert_eq!(contract_stake_batch.id, stake_batch.id);
-                assert_eq!(
-                    contract_stake_batch.balance.amount,
-                    stake_batch.balance.amount
-                );
-
-                let contract_next_stake_batch = state.next_stake_batch.as_ref().unwrap();
-                assert_eq!(contract_next_stake_batch.id, next_stake_batch.id);
-                assert_eq!(
-                    contract_next_stake_batch.balance.amount,
-                    next_stake_batch.balance.amount
-                );
+                let action = &actions[0];
+                match action {
+                    Action::FunctionCall {
+                        method_name, gas, ..
+                    } => {
+                        assert_eq!(method_name, "ping");
+                        assert_eq!(
+                            *gas,
+                            test_context
+                                .config
+                                .gas_config()
+                                .staking_pool()
+                                .ping()
+                                .value()
+                        );
+                    }
+                    _ => panic!("expected function call"),
+                }
+            }
+            {
+                let action = &actions[1];
+                match action {
+                    Action::FunctionCall {
+                        method_name, gas, ..
+                    } => {
+                        assert_eq!(method_name, "get_account");
+                        assert_eq!(
+                            *gas,
+                            test_context
+                                .config
+                                .gas_config()
+                                .staking_pool()
+                                .get_account()
+                                .value()
+                        );
+                    }
+                    _ => panic!("expected function call"),
+                }
             }
         }
-
-        // Arrange - When another account deposits, then funds should go into next Stakebatch
-        let user_2 = "user-2.near";
-        context.predecessor_account_id = user_2.to_string();
-        context.attached_deposit = contract.account_storage_fee().value();
-        testing_env!(context.clone());
-        contract.register_account();
-        context.storage_usage = env::storage_usage();
-
-        // Act
-        context.attached_deposit = 3 * YOCTO;
-        testing_env!(context.clone());
-        let batch_id_3 = contract.deposit();
-        context.storage_usage = env::storage_usage();
-
-        // Assert
         {
-            assert_eq!(batch_id_3, batch_id_2);
-            // check account STAKE batches
-            let account = contract
-                .lookup_account(to_valid_account_id(&context.predecessor_account_id))
-                .unwrap();
-
-            assert!(account.stake_batch.is_none());
-
-            let next_stake_batch = account.next_stake_batch.as_ref().unwrap();
-            assert_eq!(next_stake_batch.balance.amount.value(), (3 * YOCTO));
-            assert_eq!(next_stake_batch.id, batch_id_3);
-
-            {
-                let state = contract.contract_state();
-
-                let contract_next_stake_batch = state.next_stake_batch.as_ref().unwrap();
-                assert_eq!(contract_next_stake_batch.id, next_stake_batch.id);
-                assert_eq!(contract_next_stake_batch.balance.amount.value(), 5 * YOCTO);
+            let receipt = &receipts[1];
+            assert_eq!(receipt.actions.len(), 1);
+            let action = &receipt.actions[0];
+            match action {
+                Action::FunctionCall {
+                    method_name, gas, ..
+                } => {
+                    assert_eq!(method_name, "on_refresh_stake_token_value");
+                    assert_eq!(
+                        *gas,
+                        test_context
+                            .config
+                            .gas_config()
+                            .callbacks()
+                            .on_refresh_stake_token_value()
+                            .value()
+                    );
+                }
+                _ => panic!("expected function call"),
             }
         }
     }
+}
 
-    #[test]
-    #[should_panic(expected = "account is not registered")]
-    fn account_not_registered() {
-        let mut test_ctx = TestContext::new();
-        let contract = &mut test_ctx.contract;
+#[cfg(test)]
+mod test_refresh_staking_pool_reward_fee {
+    use super::*;
 
-        let mut context = test_ctx.context.clone();
-        context.predecessor_account_id = "unregistered-user.near".to_string();
-        context.attached_deposit = YOCTO;
-        contract.deposit();
-    }
+    use crate::test_utils::*;
+    use near_sdk::{test_utils::get_logs, testing_env, MockedBlockchain};
 
     #[test]
-    #[should_panic(expected = "minimum required NEAR deposit is")]
-    fn deposit_lt_min_required_deposit() {
-        let mut test_ctx = TestContext::with_registered_account();
-        let contract = &mut test_ctx.contract;
-
-        let mut context = test_ctx.context.clone();
-        context.attached_deposit = contract.min_required_near_deposit().value() - 1;
-        testing_env!(context);
-        contract.deposit();
-    }
+    fn refresh_staking_pool_reward_fee() {
+        let mut test_context = TestContext::with_registered_account();
 
-    #[test]
-    fn deposit_eq_min_required_deposit() {
-        let mut test_ctx = TestContext::with_registered_account();
-        let contract = &mut test_ctx.contract;
+        test_context.refresh_staking_pool_reward_fee();
 
-        let mut context = test_ctx.context.clone();
-        context.attached_deposit = contract.min_required_near_deposit().value();
-        testing_env!(context);
-        contract.deposit();
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 2);
+        {
+            let receipt = &receipts[0];
+            assert_eq!(receipt.receiver_id, TEST_STAKING_POOL_ID);
+            assert_eq!(receipt.actions.len(), 1);
+            match &receipt.actions[0] {
+                Action::FunctionCall {
+                    method_name, gas, ..
+                } => {
+                    assert_eq!(method_name, "get_reward_fee_fraction");
+                    assert_eq!(
+                        *gas,
+                        test_context
+                            .config
+                            .gas_config()
+                            .staking_pool()
+                            .get_reward_fee_fraction()
+                            .value()
+                    );
+                }
+                _ => panic!("expected function call"),
+            }
+        }
+        {
+            let receipt = &receipts[1];
+            assert_eq!(receipt.actions.len(), 1);
+            match &receipt.actions[0] {
+                Action::FunctionCall {
+                    method_name, gas, ..
+                } => {
+                    assert_eq!(method_name, "on_refresh_reward_fee_fraction");
+                    assert_eq!(
+                        *gas,
+                        test_context
+                            .config
+                            .gas_config()
+                            .callbacks()
+                            .on_refresh_reward_fee_fraction()
+                            .value()
+                    );
+                }
+                _ => panic!("expected function call"),
+            }
+        }
     }
 
     #[test]
-    fn with_receipts_to_claim() {
-        // Arrange
-        let mut test_ctx = TestContext::with_registered_account();
-        let contract = &mut test_ctx.contract;
-
-        let mut context = test_ctx.context.clone();
-        context.attached_deposit = YOCTO;
-        testing_env!(context.clone());
-        let batch_id = contract.deposit();
-        context.storage_usage = env::storage_usage();
-
-        context.attached_deposit = 0;
+    fn on_refresh_reward_fee_fraction() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        context.predecessor_account_id = context.current_account_id.clone();
         testing_env!(context.clone());
-        contract.stake();
-        context.storage_usage = env::storage_usage();
-
-        // progress the staking workflow to completion
-        {
-            context.attached_deposit = 0;
-            testing_env!(context.clone());
-            contract.on_deposit_and_stake(
-                None,
-                StakingPoolAccount {
-                    account_id: context.predecessor_account_id.clone(),
-                    unstaked_balance: 7.into(),
-                    staked_balance: (YOCTO - 7).into(),
-                    can_withdraw: true,
-                },
-            );
-            context.storage_usage = env::storage_usage();
-
-            testing_env!(context.clone());
-            contract.process_staked_batch();
-            context.storage_usage = env::storage_usage();
+        assert!(test_context
+            .staking_pool_info()
+            .reward_fee_fraction
+            .is_none());
 
-            context.predecessor_account_id = contract.operator_id();
-            testing_env!(context.clone());
-            contract.clear_stake_lock();
-            context.storage_usage = env::storage_usage();
-        }
+        // the first time the fee is cached, no change is reported
+        let fee = interface::RewardFeeFraction {
+            numerator: 10,
+            denominator: 100,
+        };
+        test_context.on_refresh_reward_fee_fraction(fee);
+        assert_eq!(
+            test_context.staking_pool_info().reward_fee_fraction,
+            Some(fee)
+        );
+        assert!(get_logs().is_empty());
 
-        // at this point, the user should have unclaimed batch receipt funds
-        context.is_view = true;
+        // unchanged fee
         testing_env!(context.clone());
-        let receipt = contract
-            .stake_batch_receipt(batch_id.clone().into())
-            .unwrap();
-        assert_eq!(receipt.staked_near, YOCTO.into());
+        test_context.on_refresh_reward_fee_fraction(fee);
+        assert!(get_logs().is_empty());
 
-        // Act
-        context.is_view = false;
-        context.predecessor_account_id = test_ctx.account_id.to_string();
-        context.attached_deposit = 2 * YOCTO;
+        // the staking pool raised its fee
         testing_env!(context.clone());
-        contract.deposit();
-
-        // Assert
-        let account = contract
-            .lookup_account(to_valid_account_id(test_ctx.account_id))
-            .unwrap();
-        assert_eq!(account.stake.unwrap().amount.value(), YOCTO);
+        let raised_fee = interface::RewardFeeFraction {
+            numerator: 20,
+            denominator: 100,
+        };
+        test_context.on_refresh_reward_fee_fraction(raised_fee);
+        assert_eq!(
+            test_context.staking_pool_info().reward_fee_fraction,
+            Some(raised_fee)
+        );
+        let logs = get_logs();
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].starts_with("StakingPoolRewardFeeChanged"));
+    }
 
-        // check receipt was claimed
-        context.is_view = true;
-        testing_env!(context.clone());
-        assert!(contract.stake_batch_receipt(batch_id.into()).is_none());
+    #[test]
+    #[should_panic(expected = "invalid staking pool reward fee fraction: 1/0")]
+    fn on_refresh_reward_fee_fraction_invalid() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context);
+        test_context.on_refresh_reward_fee_fraction(interface::RewardFeeFraction {
+            numerator: 1,
+            denominator: 0,
+        });
     }
 }
 
 #[cfg(test)]
-mod test_stake_token_value {
+mod test_redeem_forwarding {
     use super::*;
 
     use crate::{near::YOCTO, test_utils::*};
-    use near_sdk::{testing_env, MockedBlockchain};
+    use near_sdk::{test_utils::get_logs, testing_env, MockedBlockchain, VMContext};
 
-    #[test]
-    fn is_current() {
-        // Arrange
-        let mut test_context = TestContext::with_registered_account();
+    const TARGET_STAKE_CONTRACT: &str = "stake.other-validator.near";
 
-        let mut context = test_context.context.clone();
-        context.epoch_height = 100;
-        testing_env!(context);
-        test_context.total_stake.credit(YOCTO.into());
-        test_context.update_stake_token_value(YOCTO.into());
+    /// operator whitelists the target and the registered account opts in to redeem forwarding
+    fn setup(ctx: &mut TestContext) -> VMContext {
+        let mut context = ctx.set_predecessor_account_id(TEST_OPERATOR_ID);
+        testing_env!(context.clone());
+        ctx.whitelist_redeem_forwarding_target(to_valid_account_id(TARGET_STAKE_CONTRACT));
 
-        // Act - explict false
-        let stake_token_value = test_context.stake_token_value();
+        context.predecessor_account_id = ctx.account_id.to_string();
+        testing_env!(context.clone());
+        ctx.set_redeem_forwarding(Some(to_valid_account_id(TARGET_STAKE_CONTRACT)));
+        context
+    }
 
-        // Assert
+    fn credit_near(ctx: &mut TestContext, amount: domain::YoctoNear) {
+        let mut account = ctx.registered_account(ctx.account_id);
+        account.apply_near_credit(amount);
+        ctx.total_near.credit(amount);
+        ctx.save_registered_account(&account);
+    }
+
+    #[test]
+    fn claim_receipts_forwards_near_balance() {
+        let mut ctx = TestContext::with_registered_account();
+        setup(&mut ctx);
         assert_eq!(
-            stake_token_value.block_time_height.epoch_height,
-            test_context
-                .stake_token_value
-                .block_time_height()
-                .epoch_height()
-                .into()
+            ctx.redeem_forwarding(to_valid_account_id(ctx.account_id)),
+            Some(TARGET_STAKE_CONTRACT.to_string())
+        );
+        credit_near(&mut ctx, (10 * YOCTO).into());
+
+        ctx.claim_receipts();
+
+        let account = ctx.registered_account(ctx.account_id);
+        assert!(account.near.is_none());
+        assert_eq!(ctx.total_near.amount(), 0.into());
+
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 2);
+        {
+            let receipt = &receipts[0];
+            assert_eq!(receipt.receiver_id, TARGET_STAKE_CONTRACT);
+            match &receipt.actions[0] {
+                Action::FunctionCall {
+                    method_name,
+                    args,
+                    deposit,
+                    ..
+                } => {
+                    assert_eq!(method_name, "deposit_redeem_forwarding");
+                    assert_eq!(args, &format!(r#"{{"account_id":"{}"}}"#, ctx.account_id));
+                    assert_eq!(*deposit, 10 * YOCTO);
+                }
+                _ => panic!("expected function call"),
+            }
+        }
+        {
+            let receipt = &receipts[1];
+            assert_eq!(receipt.receiver_id, env::current_account_id());
+            match &receipt.actions[0] {
+                Action::FunctionCall { method_name, .. } => {
+                    assert_eq!(method_name, "on_redeem_forwarding")
+                }
+                _ => panic!("expected function call"),
+            }
+        }
+    }
+
+    #[test]
+    fn forwarding_is_limited_by_max_withdrawal_per_epoch() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.config.merge(
+            serde_json::from_str(&format!(
+                r#"{{"max_withdrawal_per_epoch": "{}"}}"#,
+                4 * YOCTO
+            ))
+            .unwrap(),
         );
+        setup(&mut ctx);
+        credit_near(&mut ctx, (10 * YOCTO).into());
+
+        ctx.claim_receipts();
+
+        let account = ctx.registered_account(ctx.account_id);
+        assert_eq!(account.near.unwrap().amount(), (6 * YOCTO).into());
     }
-}
 
-#[cfg(test)]
-mod test_refresh_stake_token_value {
-    use super::*;
+    #[test]
+    fn removed_target_is_not_forwarded_to() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = setup(&mut ctx);
+        credit_near(&mut ctx, (10 * YOCTO).into());
 
-    use crate::{near::YOCTO, test_utils::*};
-    use near_sdk::{testing_env, MockedBlockchain};
+        context.predecessor_account_id = TEST_OPERATOR_ID.to_string();
+        testing_env!(context.clone());
+        ctx.remove_redeem_forwarding_target(to_valid_account_id(TARGET_STAKE_CONTRACT));
+        assert!(ctx
+            .redeem_forwarding(to_valid_account_id(ctx.account_id))
+            .is_none());
+
+        context.predecessor_account_id = ctx.account_id.to_string();
+        testing_env!(context);
+        ctx.claim_receipts();
+        let account = ctx.registered_account(ctx.account_id);
+        assert_eq!(account.near.unwrap().amount(), (10 * YOCTO).into());
+        assert!(deserialize_receipts().is_empty());
+    }
 
     #[test]
-    #[should_panic(expected = "action is blocked because a batch is running")]
-    fn has_staking_lock() {
-        // Arrange
-        let mut test_context = TestContext::with_registered_account();
-        test_context.stake_batch_lock = Some(StakeLock::Staking);
+    fn claim_receipts_for_forwards_claimed_redeem_proceeds() {
+        let mut ctx = TestContext::with_registered_account();
+        setup(&mut ctx);
+        credit_near(&mut ctx, (2 * YOCTO).into());
+        let mut account = ctx.registered_account(ctx.account_id);
+        account.apply_stake_credit(YOCTO.into());
+        ctx.save_registered_account(&account);
+        let batch_id = ctx
+            .redeem_all()
+            .map(|batch_id| domain::BatchId(batch_id.into()))
+            .unwrap();
+        ctx.redeem_stake_batch_receipts.insert(
+            &batch_id,
+            &domain::RedeemStakeBatchReceipt::new(
+                YOCTO.into(),
+                ctx.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
+        );
+        // simulate that the unstaked NEAR was withdrawn from the staking pool
+        ctx.total_near.credit(YOCTO.into());
 
-        // Act
-        test_context.refresh_stake_token_value();
+        let context = ctx.set_predecessor_account_id("keeper.near");
+        testing_env!(context);
+        let account_id = to_valid_account_id(ctx.account_id);
+        ctx.claim_receipts_for(account_id);
+
+        // only the claimed redeem proceeds are forwarded
+        let account = ctx.registered_account(ctx.account_id);
+        assert_eq!(account.near.unwrap().amount(), (2 * YOCTO).into());
+        assert_eq!(ctx.total_near.amount(), (2 * YOCTO).into());
+        let forwarded = deserialize_receipts()
+            .into_iter()
+            .filter(|receipt| receipt.receiver_id == TARGET_STAKE_CONTRACT)
+            .map(|receipt| match &receipt.actions[0] {
+                Action::FunctionCall { deposit, .. } => *deposit,
+                _ => panic!("expected function call"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(forwarded, vec![YOCTO]);
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("RedeemProceedsForwarded")));
     }
 
     #[test]
-    #[should_panic(expected = "action is blocked because a batch is running")]
-    fn has_staked_lock() {
-        // Arrange
-        let mut test_context = TestContext::with_registered_account();
-        test_context.stake_batch_lock = Some(StakeLock::Staked {
-            near_liquidity: None,
-            staked_balance: Default::default(),
-            unstaked_balance: Default::default(),
-        });
+    fn redeem_proceeds_are_not_forwarded_without_enough_prepaid_gas() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = setup(&mut ctx);
+        credit_near(&mut ctx, (10 * YOCTO).into());
+
+        context.prepaid_gas = ctx
+            .config
+            .gas_config()
+            .callbacks()
+            .deposit_redeem_forwarding()
+            .value();
+        testing_env!(context);
+        ctx.claim_receipts();
 
-        // Act
-        test_context.refresh_stake_token_value();
+        let account = ctx.registered_account(ctx.account_id);
+        assert_eq!(account.near.unwrap().amount(), (10 * YOCTO).into());
+        assert!(deserialize_receipts().is_empty());
     }
 
     #[test]
-    #[should_panic(expected = "action is blocked because a batch is running")]
-    fn has_unstaking_lock() {
-        // Arrange
-        let mut test_context = TestContext::with_registered_account();
-        test_context.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+    fn on_redeem_forwarding_failed() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = setup(&mut ctx);
+        credit_near(&mut ctx, (10 * YOCTO).into());
+        ctx.claim_receipts();
 
-        // Act
-        test_context.refresh_stake_token_value();
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context);
+        set_env_with_failed_promise_result(&mut ctx);
+        let account_id = ctx.account_id.to_string();
+        ctx.on_redeem_forwarding(account_id, (10 * YOCTO).into());
+
+        let account = ctx.registered_account(ctx.account_id);
+        assert_eq!(account.near.unwrap().amount(), (10 * YOCTO).into());
+        assert_eq!(ctx.total_near.amount(), (10 * YOCTO).into());
+        let logs = get_logs();
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].starts_with("RedeemForwardingFailed"));
     }
 
     #[test]
-    #[should_panic(expected = "action is blocked because STAKE token value is being refreshed")]
-    fn has_refreshing_stake_token_value_lock() {
-        // Arrange
-        let mut test_context = TestContext::with_registered_account();
-        test_context.stake_batch_lock = Some(StakeLock::RefreshingStakeTokenValue);
+    fn deposit_redeem_forwarding() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = ctx.set_predecessor_account_id("stake.source-validator.near");
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context);
 
-        // Act
-        test_context.refresh_stake_token_value();
+        let account_id = to_valid_account_id(ctx.account_id);
+        let batch_id = ctx.deposit_redeem_forwarding(account_id);
+
+        let account = ctx.registered_account(ctx.account_id);
+        let batch = account.stake_batch.unwrap();
+        assert_eq!(batch.id(), batch_id.into());
+        assert_eq!(batch.balance().amount(), (10 * YOCTO).into());
     }
 
     #[test]
-    fn no_locks() {
-        // Arrange
-        let mut test_context = TestContext::with_registered_account();
-
-        let mut context = test_context.context.clone();
-        context.epoch_height = 100;
+    #[should_panic(expected = "account is not registered")]
+    fn deposit_redeem_forwarding_account_not_registered() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = ctx.set_predecessor_account_id("stake.source-validator.near");
+        context.attached_deposit = 10 * YOCTO;
         testing_env!(context);
-        test_context.total_stake.credit(YOCTO.into());
-        test_context.update_stake_token_value(YOCTO.into());
 
-        // Act
-        test_context.refresh_stake_token_value();
+        ctx.deposit_redeem_forwarding(to_valid_account_id("unregistered.near"));
+    }
 
-        // Assert
-        let receipts = deserialize_receipts();
-        assert_eq!(receipts.len(), 2);
-        {
-            let receipt = &receipts[0];
-            let actions = &receipt.actions;
-            assert_eq!(actions.len(), 2);
-            {
-                let action = &actions[0];
-                match action {
-                    Action::FunctionCall {
-                        method_name, gas, ..
-                    } => {
-                        assert_eq!(method_name, "ping");
-                        assert_eq!(
-                            *gas,
-                            test_context
-                                .config
-                                .gas_config()
-                                .staking_pool()
-                                .ping()
-                                .value()
-                        );
-                    }
-                    _ => panic!("expected function call"),
-                }
-            }
-            {
-                let action = &actions[1];
-                match action {
-                    Action::FunctionCall {
-                        method_name, gas, ..
-                    } => {
-                        assert_eq!(method_name, "get_account");
-                        assert_eq!(
-                            *gas,
-                            test_context
-                                .config
-                                .gas_config()
-                                .staking_pool()
-                                .get_account()
-                                .value()
-                        );
-                    }
-                    _ => panic!("expected function call"),
-                }
-            }
-        }
-        {
-            let receipt = &receipts[1];
-            assert_eq!(receipt.actions.len(), 1);
-            let action = &receipt.actions[0];
-            match action {
-                Action::FunctionCall {
-                    method_name, gas, ..
-                } => {
-                    assert_eq!(method_name, "on_refresh_stake_token_value");
-                    assert_eq!(
-                        *gas,
-                        test_context
-                            .config
-                            .gas_config()
-                            .callbacks()
-                            .on_refresh_stake_token_value()
-                            .value()
-                    );
-                }
-                _ => panic!("expected function call"),
-            }
-        }
+    #[test]
+    #[should_panic(expected = "redeem forwarding target is not whitelisted")]
+    fn set_redeem_forwarding_target_not_whitelisted() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.set_redeem_forwarding(Some(to_valid_account_id(TARGET_STAKE_CONTRACT)));
+    }
+
+    #[test]
+    #[should_panic(expected = "redeem proceeds cannot be forwarded to this contract")]
+    fn whitelist_redeem_forwarding_target_self() {
+        let mut ctx = TestContext::with_registered_account();
+        let context = ctx.set_predecessor_account_id(TEST_OPERATOR_ID);
+        testing_env!(context.clone());
+        ctx.whitelist_redeem_forwarding_target(to_valid_account_id(&context.current_account_id));
     }
 }
 
@@ -1739,6 +4484,65 @@ mod test_stake {
     use crate::{near::YOCTO, test_utils::*};
     use near_sdk::{env, serde_json, testing_env, MockedBlockchain};
 
+    #[test]
+    fn deposit_cooldown() {
+        let mut test_ctx = TestContext::with_registered_account();
+        test_ctx
+            .config
+            .merge(serde_json::from_str(r#"{"deposit_cooldown_blocks": 10}"#).unwrap());
+        let contract = &mut test_ctx.contract;
+
+        let mut context = test_ctx.context.clone();
+        context.block_index = 100;
+        context.attached_deposit = 2 * YOCTO;
+        testing_env!(context.clone());
+        // the deposit cannot be staked until the cooldown has passed
+        if let PromiseOrValue::Promise(_) = contract.deposit_and_stake() {
+            panic!("deposit should not have been staked during the cooldown");
+        }
+        assert!(contract.stake_batch_lock.is_none());
+
+        // deposits can be withdrawn during the cooldown
+        context.attached_deposit = 0;
+        context.block_index = 105;
+        testing_env!(context.clone());
+        assert!(!contract.stake_batch_deposit_cooldown_passed());
+        contract.withdraw_from_stake_batch(YOCTO.into());
+        // the cooldown restarts when the batch balance changes
+        context.block_index = 114;
+        testing_env!(context.clone());
+        assert!(!contract.stake_batch_deposit_cooldown_passed());
+
+        context.block_index = 115;
+        testing_env!(context.clone());
+        assert!(contract.stake_batch_deposit_cooldown_passed());
+        contract.stake();
+        assert_eq!(contract.stake_batch_lock, Some(StakeLock::Staking));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "stake batch cannot be run until the deposit cooldown period has passed"
+    )]
+    fn deposit_cooldown_not_passed() {
+        let mut test_ctx = TestContext::with_registered_account();
+        test_ctx
+            .config
+            .merge(serde_json::from_str(r#"{"deposit_cooldown_blocks": 10}"#).unwrap());
+        let contract = &mut test_ctx.contract;
+
+        let mut context = test_ctx.context.clone();
+        context.block_index = 100;
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+        contract.deposit(None);
+
+        context.attached_deposit = 0;
+        context.block_index = 109;
+        testing_env!(context.clone());
+        contract.stake();
+    }
+
     /// any account can invoke stake
     #[test]
     fn account_not_registered() {
@@ -1749,7 +4553,7 @@ mod test_stake {
         let mut context = test_ctx.context.clone();
         context.attached_deposit = YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         // Act
         context.attached_deposit = 0;
@@ -1836,7 +4640,7 @@ mod test_stake {
         let mut context = test_context.context.clone();
         context.attached_deposit = YOCTO;
         testing_env!(context.clone());
-        let batch_id = contract.deposit();
+        let batch_id = contract.deposit(None);
 
         // Act
         context.attached_deposit = 0;
@@ -1870,6 +4674,7 @@ mod test_stake {
                         match contract.stake_batch_receipt(batch_id.into()) {
                             Some(receipt) => {
                                 assert_eq!(receipt.staked_near.value(), YOCTO);
+                                assert_eq!(receipt.staking_pool_id, contract.staking_pool_id);
                             }
                             None => panic!("receipt should have been created"),
                         }
@@ -1895,7 +4700,7 @@ mod test_stake {
         let mut context = test_context.context.clone();
         context.attached_deposit = YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         context.attached_deposit = 0;
         testing_env!(context.clone());
@@ -1914,7 +4719,7 @@ mod test_stake {
         let mut context = test_context.context.clone();
         context.attached_deposit = YOCTO;
         testing_env!(context.clone());
-        let batch_id = contract.deposit();
+        let batch_id = contract.deposit(None);
 
         context.attached_deposit = 0;
         testing_env!(context.clone());
@@ -1974,7 +4779,7 @@ mod test_stake {
         let mut context = test_context.context.clone();
         context.attached_deposit = YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         let mut account = contract.predecessor_registered_account();
         account.apply_stake_credit(YOCTO.into());
@@ -2040,7 +4845,7 @@ mod test_stake {
         let mut context = test_context.context.clone();
         context.attached_deposit = YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         // simulate STAKE was redeemed and there is a pending withdrawal
         {
@@ -2049,7 +4854,8 @@ mod test_stake {
             let redeem_stake_batch =
                 domain::RedeemStakeBatch::new(contract.batch_id_sequence, YOCTO.into());
             contract.redeem_stake_batch = Some(redeem_stake_batch);
-            let receipt = redeem_stake_batch.create_receipt(contract.stake_token_value);
+            let receipt = redeem_stake_batch
+                .create_receipt(contract.stake_token_value, TEST_STAKING_POOL_ID.to_string());
             contract
                 .redeem_stake_batch_receipts
                 .insert(&contract.batch_id_sequence, &receipt);
@@ -2079,7 +4885,7 @@ mod test_stake {
         const CONTRACT_EARNINGS: u128 = 10 * YOCTO;
         context.account_balance += CONTRACT_EARNINGS;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
         context.storage_usage = env::storage_usage();
 
         context.attached_deposit = 0;
@@ -2324,6 +5130,45 @@ collected_earnings: {} -> {}
         assert_eq!(balances.near_liquidity_pool.value(), YOCTO / 2);
     }
 
+    #[test]
+    fn process_staked_batch_records_batch_audit() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        test_context.deposit_and_stake();
+        let batch_id = test_context.stake_batch.unwrap().id();
+        assert!(test_context.batch_audit(batch_id.into()).is_none());
+        let prior_stake_token_value = test_context.stake_token_value;
+
+        test_context.on_deposit_and_stake(
+            None,
+            StakingPoolAccount {
+                account_id: env::current_account_id(),
+                unstaked_balance: 7.into(),
+                staked_balance: (YOCTO - 7).into(),
+                can_withdraw: true,
+            },
+        );
+        test_context.process_staked_batch();
+
+        let audit = test_context.batch_audit(batch_id.into()).unwrap();
+        assert_eq!(audit.staked_balance.value(), YOCTO - 7);
+        assert_eq!(audit.unstaked_balance.value(), 7);
+        assert_eq!(audit.pending_withdrawal.value(), 0);
+        assert_eq!(audit.near_liquidity.value(), 0);
+        assert_eq!(audit.total_staked_near_balance.value(), YOCTO);
+        assert_eq!(audit.compensation.value(), 0);
+        assert_eq!(
+            audit.prior_stake_token_value,
+            interface::StakeTokenValue::from(prior_stake_token_value)
+        );
+        assert_eq!(
+            audit.stake_token_value,
+            interface::StakeTokenValue::from(test_context.stake_token_value)
+        );
+    }
+
     #[test]
     fn when_partial_batch_balance_is_used_for_liquidity() {
         // Arrange
@@ -2456,46 +5301,170 @@ collected_earnings: {} -> {}
                 can_withdraw: false,
             },
         );
-        println!("on_deposit_and_stake receipts");
-        let receipts = deserialize_receipts();
-        assert_eq!(receipts.len(), 1);
-        {
-            let receipt = &receipts[0];
-            match &receipt.actions[0] {
-                Action::FunctionCall { method_name, .. } => {
-                    assert_eq!(method_name, "process_staked_batch")
-                }
-                _ => panic!("expected FunctionCall"),
-            }
-        }
+        println!("on_deposit_and_stake receipts");
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 1);
+        {
+            let receipt = &receipts[0];
+            match &receipt.actions[0] {
+                Action::FunctionCall { method_name, .. } => {
+                    assert_eq!(method_name, "process_staked_batch")
+                }
+                _ => panic!("expected FunctionCall"),
+            }
+        }
+
+        let mut context = test_context.context.clone();
+        context.predecessor_account_id = env::current_account_id();
+        testing_env!(context);
+        test_context.process_staked_batch();
+
+        testing_env!(test_context.context.clone());
+        // enough liquidity was added to clear the pending withdrawal
+        assert!(test_context.pending_withdrawal().is_none());
+
+        // funds from liquidity pool should have been moved over to unstaked NEAR balance, which is
+        // available for withdrawal
+        let balances = test_context.balances();
+        assert_eq!(balances.near_liquidity_pool.value(), 0);
+        assert_eq!(balances.total_available_unstaked_near.value(), YOCTO);
+    }
+
+    #[test]
+    fn clear_stake_batch_lock_when_staked_should_retain_lock() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+
+        let mut context = test_context.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+        test_context.deposit_and_stake();
+
+        testing_env!(test_context.context.clone());
+        test_context.on_deposit_and_stake(
+            None,
+            StakingPoolAccount {
+                account_id: env::current_account_id(),
+                unstaked_balance: 0.into(),
+                staked_balance: YOCTO.into(),
+                can_withdraw: true,
+            },
+        );
+
+        // simulate StakeTokenContract::process_staked_batch() fails by not calling it
+
+        // Act
+        let mut context = test_context.context.clone();
+        context.predecessor_account_id = env::current_account_id();
+        testing_env!(context);
+        test_context.clear_stake_lock();
+
+        match test_context.stake_batch_lock {
+            Some(StakeLock::Staked { .. }) => println!("{:?}", test_context.stake_batch_lock),
+            _ => panic!(
+                "expected Staked but was: {:?}",
+                test_context.stake_batch_lock
+            ),
+        }
+    }
+
+    #[test]
+    fn staking_pool_failure_marks_staking_pool_unavailable() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+
+        let mut context = test_context.context.clone();
+        context.epoch_height = 100;
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+        test_context.deposit_and_stake();
+        assert!(test_context.staking_pool_info().available);
+
+        // simulate the staking pool rejecting the `deposit_and_stake` request, which means
+        // `on_deposit_and_stake` is never invoked
+        context.attached_deposit = 0;
+        context.predecessor_account_id = env::current_account_id();
+        testing_env!(context.clone());
+
+        // Act
+        test_context.clear_stake_lock();
+
+        // Assert
+        assert!(test_context.stake_batch_lock.is_none());
+        assert!(test_context.stake_batch.is_some());
+        let staking_pool_info = test_context.staking_pool_info();
+        assert!(!staking_pool_info.available);
+        assert_eq!(
+            staking_pool_info.unavailable_until,
+            Some(domain::EpochHeight(101).into())
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "staking pool is unavailable - stake batch will be retried after the backoff period"
+    )]
+    fn staking_pool_unavailable() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
 
         let mut context = test_context.context.clone();
-        context.predecessor_account_id = env::current_account_id();
-        testing_env!(context);
-        test_context.process_staked_batch();
+        context.epoch_height = 100;
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+        test_context.deposit(None);
+        test_context.staking_pool_unavailable_until = Some(101.into());
 
-        testing_env!(test_context.context.clone());
-        // enough liquidity was added to clear the pending withdrawal
-        assert!(test_context.pending_withdrawal().is_none());
+        // Act
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        test_context.stake();
+    }
 
-        // funds from liquidity pool should have been moved over to unstaked NEAR balance, which is
-        // available for withdrawal
-        let balances = test_context.balances();
-        assert_eq!(balances.near_liquidity_pool.value(), 0);
-        assert_eq!(balances.total_available_unstaked_near.value(), YOCTO);
+    #[test]
+    fn deposit_and_stake_while_staking_pool_unavailable() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        test_context.staking_pool_unavailable_until = Some(101.into());
+
+        let mut context = test_context.context.clone();
+        context.epoch_height = 100;
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+
+        // Act
+        if let PromiseOrValue::Value(batch_id) = test_context.deposit_and_stake() {
+            assert_eq!(batch_id, test_context.stake_batch.unwrap().id().into());
+        } else {
+            panic!("expected deposit to be batched without running the stake batch");
+        }
+
+        // Assert
+        assert!(test_context.stake_batch_lock.is_none());
+        assert!(deserialize_receipts().is_empty());
     }
 
     #[test]
-    fn clear_stake_batch_lock_when_staked_should_retain_lock() {
+    fn staking_pool_unavailable_backoff_expired() {
         // Arrange
         let mut test_context = TestContext::with_registered_account();
 
         let mut context = test_context.context.clone();
+        context.epoch_height = 101;
         context.attached_deposit = YOCTO;
-        testing_env!(context);
-        test_context.deposit_and_stake();
+        testing_env!(context.clone());
+        test_context.deposit(None);
+        test_context.staking_pool_unavailable_until = Some(101.into());
+        assert!(test_context.staking_pool_info().available);
 
-        testing_env!(test_context.context.clone());
+        // Act - the stake batch is retried
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        test_context.stake();
+        assert_eq!(test_context.stake_batch_lock, Some(StakeLock::Staking));
+
+        context.predecessor_account_id = env::current_account_id();
+        testing_env!(context.clone());
         test_context.on_deposit_and_stake(
             None,
             StakingPoolAccount {
@@ -2506,21 +5475,11 @@ collected_earnings: {} -> {}
             },
         );
 
-        // simulate StakeTokenContract::process_staked_batch() fails by not calling it
-
-        // Act
-        let mut context = test_context.context.clone();
-        context.predecessor_account_id = env::current_account_id();
-        testing_env!(context);
-        test_context.clear_stake_lock();
-
-        match test_context.stake_batch_lock {
-            Some(StakeLock::Staked { .. }) => println!("{:?}", test_context.stake_batch_lock),
-            _ => panic!(
-                "expected Staked but was: {:?}",
-                test_context.stake_batch_lock
-            ),
-        }
+        // Assert
+        assert!(test_context.staking_pool_unavailable_until.is_none());
+        let staking_pool_info = test_context.staking_pool_info();
+        assert!(staking_pool_info.available);
+        assert!(staking_pool_info.unavailable_until.is_none());
     }
 }
 
@@ -2544,7 +5503,7 @@ mod test_withdraw_from_stake_batch {
 
         context.attached_deposit = 10 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         testing_env!(context.clone());
         contract.withdraw_from_stake_batch(YOCTO.into());
@@ -2582,7 +5541,7 @@ mod test_withdraw_from_stake_batch {
 
         context.attached_deposit = 10 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         testing_env!(context.clone());
         contract.withdraw_from_stake_batch(context.attached_deposit.into());
@@ -2617,7 +5576,7 @@ mod test_withdraw_from_stake_batch {
 
         context.attached_deposit = 10 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         testing_env!(context.clone());
         contract.withdraw_from_stake_batch(YOCTO.into());
@@ -2657,7 +5616,7 @@ mod test_withdraw_from_stake_batch {
 
         context.attached_deposit = 10 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         testing_env!(context.clone());
         contract.withdraw_from_stake_batch(context.attached_deposit.into());
@@ -2678,6 +5637,66 @@ mod test_withdraw_from_stake_batch {
             .unwrap();
         assert!(account.next_stake_batch.is_none());
     }
+
+    /// Given an account has deposited funds into the next stake batch
+    /// When the account tries to withdraw an amount that would leave a batch balance below the min
+    /// required NEAR deposit
+    /// Then the withdrawal fails
+    #[test]
+    #[should_panic(expected = "minimum required NEAR deposit is")]
+    fn withdraw_leaving_balance_below_min_required_near_deposit() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+        contract.stake_batch_lock = Some(StakeLock::Staking);
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        contract.deposit(None);
+
+        testing_env!(context.clone());
+        contract.withdraw_from_stake_batch((context.attached_deposit - 1).into());
+    }
+
+    /// Given withdrawing the full batch balance below the min required NEAR deposit is enabled
+    /// And an account has deposited funds into the next stake batch
+    /// When the account tries to withdraw an amount that would leave a batch balance below the min
+    /// required NEAR deposit
+    /// Then the full batch balance is transferred back to the account
+    /// And the batch is deleted on the account
+    #[test]
+    fn withdraw_full_batch_balance_below_min_required_near_deposit() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+        contract.stake_batch_lock = Some(StakeLock::Staking);
+        contract.config.merge(interface::Config {
+            withdraw_full_batch_balance_below_min_deposit: Some(true),
+            ..Default::default()
+        });
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        contract.deposit(None);
+
+        testing_env!(context.clone());
+        contract.withdraw_from_stake_batch((context.attached_deposit - 1).into());
+
+        {
+            let receipts = deserialize_receipts();
+            assert_eq!(receipts.len(), 1);
+            match receipts.first().unwrap().actions.first().unwrap() {
+                Action::Transfer { deposit } => assert_eq!(*deposit, context.attached_deposit),
+                _ => panic!("unexpected action type"),
+            }
+        }
+
+        let account = contract
+            .lookup_account(ValidAccountId::try_from(test_context.account_id).unwrap())
+            .unwrap();
+        assert!(account.next_stake_batch.is_none());
+        assert!(contract.next_stake_batch.is_none());
+    }
 }
 
 #[cfg(test)]
@@ -2701,7 +5720,7 @@ mod test_withdraw_all_from_stake_batch {
 
         context.attached_deposit = 10 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         testing_env!(context.clone());
         contract.withdraw_all_from_stake_batch();
@@ -2732,7 +5751,7 @@ mod test_withdraw_all_from_stake_batch {
 
         context.attached_deposit = 10 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
         assert!(contract.next_stake_batch.is_some());
         if let Some(batch) = contract.next_stake_batch.as_mut() {
             batch.add(YOCTO.into());
@@ -2770,7 +5789,7 @@ mod test_withdraw_all_from_stake_batch {
 
         context.attached_deposit = 10 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
         let account = contract
             .lookup_account(ValidAccountId::try_from(test_context.account_id).unwrap())
             .unwrap();
@@ -2806,7 +5825,7 @@ mod test_withdraw_all_from_stake_batch {
 
         context.attached_deposit = 10 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
         let account = contract
             .lookup_account(ValidAccountId::try_from(test_context.account_id).unwrap())
             .unwrap();
@@ -2859,7 +5878,7 @@ mod test_withdraw_all_from_stake_batch {
 
         context.attached_deposit = 10 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         contract.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
 
@@ -2876,7 +5895,7 @@ mod test_withdraw_all_from_stake_batch {
 
         context.attached_deposit = 10 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         contract.stake_batch_lock = Some(StakeLock::Staking);
 
@@ -2942,7 +5961,7 @@ mod test_withdraw {
         let mut context = test_context.context.clone();
         context.attached_deposit = 10 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         testing_env!(test_context.context.clone());
         contract.stake();
@@ -2962,7 +5981,7 @@ mod test_withdraw {
         let mut context = test_context.context.clone();
         context.attached_deposit = 10 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         testing_env!(test_context.context.clone());
         contract.stake();
@@ -2986,7 +6005,7 @@ mod test_withdraw {
 
         context.attached_deposit = 10 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         contract.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
 
@@ -3046,7 +6065,11 @@ mod test_withdraw_all {
         contract.total_near.credit(YOCTO.into());
         contract.redeem_stake_batch_receipts.insert(
             &contract.batch_id_sequence,
-            &domain::RedeemStakeBatchReceipt::new(YOCTO.into(), contract.stake_token_value),
+            &domain::RedeemStakeBatchReceipt::new(
+                YOCTO.into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
         );
 
         contract.withdraw_all();
@@ -3079,6 +6102,53 @@ mod test_claim_receipts {
     use near_sdk::{testing_env, MockedBlockchain};
     use std::convert::TryInto;
 
+    /// Given the account has funds in a stake batch that has a receipt
+    /// When another account claims the receipts on behalf of the account
+    /// Then the STAKE tokens should be credited to the account
+    /// And the receipt should be deleted
+    /// And the claim should be logged
+    #[test]
+    fn claim_receipts_for_another_account() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+        let mut context = test_context.context.clone();
+
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+        let batch_id = contract.deposit(None);
+        let batch_id: domain::BatchId = domain::BatchId(batch_id.into());
+        {
+            let stake_token_value =
+                domain::StakeTokenValue::new(Default::default(), YOCTO.into(), YOCTO.into());
+            let receipt = domain::StakeBatchReceipt::new(
+                YOCTO.into(),
+                stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            );
+            contract.stake_batch_receipts.insert(&batch_id, &receipt);
+        }
+
+        // Act
+        context.predecessor_account_id = "bot.near".to_string();
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        contract.claim_receipts_for(to_valid_account_id(test_context.account_id));
+
+        // Assert
+        let account = contract.registered_account(test_context.account_id).account;
+        assert_eq!(account.stake.unwrap().amount().value(), YOCTO);
+        assert!(account.stake_batch.is_none());
+        assert!(contract.stake_batch_receipts.get(&batch_id).is_none());
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs.iter().any(|log| log.starts_with("ReceiptsClaimedFor")));
+
+        // when there is nothing to claim, then nothing is logged
+        testing_env!(context.clone());
+        contract.claim_receipts_for(to_valid_account_id(test_context.account_id));
+        assert!(near_sdk::test_utils::get_logs().is_empty());
+    }
+
     #[test]
     #[should_panic(expected = "account is not registered")]
     fn when_account_is_not_registered() {
@@ -3116,7 +6186,7 @@ mod test_claim_receipts {
         // deposit NEAR into StakeBatch
         test_context.context.attached_deposit = YOCTO;
         testing_env!(test_context.context.clone());
-        let batch_id = contract.deposit();
+        let batch_id = contract.deposit(None);
 
         // Act
         contract.claim_receipts();
@@ -3145,7 +6215,7 @@ mod test_claim_receipts {
 
         context.attached_deposit = YOCTO;
         testing_env!(context.clone());
-        let batch_id = contract.deposit();
+        let batch_id = contract.deposit(None);
         let batch_id: domain::BatchId = domain::BatchId(batch_id.into());
 
         // create a receipt for the batch to simulate that the batch has been staked
@@ -3155,6 +6225,7 @@ mod test_claim_receipts {
             let receipt = domain::StakeBatchReceipt::new(
                 (context.attached_deposit * 2).into(), // simulate that other accounts have deposited into the same batch
                 stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
             );
             contract.stake_batch_receipts.insert(&batch_id, &receipt);
         }
@@ -3195,13 +6266,16 @@ mod test_claim_receipts {
 
         context.attached_deposit = YOCTO;
         testing_env!(context.clone());
-        let batch_id = contract.deposit();
+        let batch_id = contract.deposit(None);
         let batch_id: domain::BatchId = domain::BatchId(batch_id.into());
 
         let stake_token_value =
             domain::StakeTokenValue::new(Default::default(), YOCTO.into(), YOCTO.into());
-        let receipt =
-            domain::StakeBatchReceipt::new(context.attached_deposit.into(), stake_token_value);
+        let receipt = domain::StakeBatchReceipt::new(
+            context.attached_deposit.into(),
+            stake_token_value,
+            TEST_STAKING_POOL_ID.to_string(),
+        );
         contract.stake_batch_receipts.insert(&batch_id, &receipt);
 
         // Act
@@ -3238,13 +6312,13 @@ mod test_claim_receipts {
 
         context.attached_deposit = YOCTO;
         testing_env!(context.clone());
-        let batch_id = contract.deposit();
+        let batch_id = contract.deposit(None);
         let batch_id_1: domain::BatchId = domain::BatchId(batch_id.into());
 
         contract.stake_batch_lock = Some(StakeLock::Staking);
         context.attached_deposit = YOCTO * 2;
         testing_env!(context.clone());
-        let batch_id = contract.deposit();
+        let batch_id = contract.deposit(None);
         let batch_id_2: domain::BatchId = domain::BatchId(batch_id.into());
         assert_ne!(batch_id_1, batch_id_2);
 
@@ -3253,11 +6327,19 @@ mod test_claim_receipts {
                 domain::StakeTokenValue::new(Default::default(), YOCTO.into(), YOCTO.into());
             contract.stake_batch_receipts.insert(
                 &batch_id_1,
-                &domain::StakeBatchReceipt::new(YOCTO.into(), stake_token_value),
+                &domain::StakeBatchReceipt::new(
+                    YOCTO.into(),
+                    stake_token_value,
+                    TEST_STAKING_POOL_ID.to_string(),
+                ),
             );
             contract.stake_batch_receipts.insert(
                 &batch_id_2,
-                &domain::StakeBatchReceipt::new((YOCTO * 2).into(), stake_token_value),
+                &domain::StakeBatchReceipt::new(
+                    (YOCTO * 2).into(),
+                    stake_token_value,
+                    TEST_STAKING_POOL_ID.to_string(),
+                ),
             );
         }
 
@@ -3287,13 +6369,13 @@ mod test_claim_receipts {
 
         context.attached_deposit = YOCTO;
         testing_env!(context.clone());
-        let batch_id = contract.deposit();
+        let batch_id = contract.deposit(None);
         let batch_id_1: domain::BatchId = domain::BatchId(batch_id.into());
 
         contract.stake_batch_lock = Some(StakeLock::Staking);
         context.attached_deposit = YOCTO * 2;
         testing_env!(context.clone());
-        let batch_id = contract.deposit();
+        let batch_id = contract.deposit(None);
         let batch_id_2: domain::BatchId = domain::BatchId(batch_id.into());
         assert_ne!(batch_id_1, batch_id_2);
 
@@ -3302,37 +6384,189 @@ mod test_claim_receipts {
                 domain::StakeTokenValue::new(Default::default(), YOCTO.into(), YOCTO.into());
             contract.stake_batch_receipts.insert(
                 &batch_id_1,
-                &domain::StakeBatchReceipt::new(YOCTO.into(), stake_token_value),
+                &domain::StakeBatchReceipt::new(
+                    YOCTO.into(),
+                    stake_token_value,
+                    TEST_STAKING_POOL_ID.to_string(),
+                ),
             );
         }
 
-        contract.stake_batch_lock = None;
+        contract.stake_batch_lock = None;
+
+        // Act
+        contract.claim_receipts();
+
+        // Assert
+        assert!(contract.stake_batch_receipts.get(&batch_id_1).is_none());
+
+        let account = contract.predecessor_registered_account().account;
+        // and the account batches have been cleared
+        assert_eq!(account.stake_batch.unwrap().id(), batch_id_2);
+        assert!(account.next_stake_batch.is_none());
+        // and the STAKE tokens were claimed and credited to the account
+        assert_eq!(account.stake.unwrap().amount().value(), YOCTO);
+    }
+
+    /// Given an account has redeemed STAKE
+    /// And the batch has completed
+    /// Then the account can claim the NEAR funds
+    #[test]
+    fn when_account_has_redeem_stake_batch_with_receipt() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+
+        let mut account = contract.predecessor_registered_account();
+        account.apply_stake_credit(YOCTO.into());
+        contract.save_registered_account(&account);
+        let batch_id = contract
+            .redeem_all()
+            .map(|batch_id| domain::BatchId(batch_id.into()))
+            .unwrap();
+
+        contract.redeem_stake_batch_receipts.insert(
+            &batch_id,
+            &domain::RedeemStakeBatchReceipt::new(
+                (2 * YOCTO).into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
+        );
+
+        // Act
+        contract.claim_receipts();
+
+        // Assert
+        let account = contract.predecessor_registered_account().account;
+        assert_eq!(account.near.unwrap().amount(), (YOCTO).into());
+        assert!(account.redeem_stake_batch.is_none());
+
+        // Then there should be 1 STAKE left unclaimed on the receipt
+        let receipt = contract.redeem_stake_batch_receipts.get(&batch_id).unwrap();
+        assert_eq!(receipt.redeemed_stake(), YOCTO.into());
+    }
+
+    /// Given an account has redeemed STAKE
+    /// And the account has auto compound enabled
+    /// When the NEAR funds are claimed
+    /// Then the NEAR is moved into the account's stake batch
+    #[test]
+    fn when_account_has_redeem_stake_batch_with_receipt_and_auto_compound() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+        contract.set_account_preferences(interface::AccountPreferences {
+            auto_claim_receipts: false,
+            auto_compound: true,
+        });
+
+        let mut account = contract.predecessor_registered_account();
+        account.apply_stake_credit(YOCTO.into());
+        contract.save_registered_account(&account);
+        let batch_id = contract
+            .redeem_all()
+            .map(|batch_id| domain::BatchId(batch_id.into()))
+            .unwrap();
+
+        contract.redeem_stake_batch_receipts.insert(
+            &batch_id,
+            &domain::RedeemStakeBatchReceipt::new(
+                YOCTO.into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
+        );
+        // simulate that the unstaked NEAR was withdrawn from the staking pool
+        contract.total_near.credit(YOCTO.into());
+
+        // Act
+        contract.claim_receipts();
+
+        // Assert
+        let account = contract.predecessor_registered_account().account;
+        assert!(account.near.is_none());
+        assert!(account.redeem_stake_batch.is_none());
+        assert_eq!(
+            account.stake_batch.unwrap().balance().amount(),
+            YOCTO.into()
+        );
+        assert_eq!(
+            contract.stake_batch.unwrap().balance().amount(),
+            YOCTO.into()
+        );
+        assert_eq!(contract.total_near.amount(), 0.into());
+    }
+
+    /// Given an account has redeemed STAKE
+    /// And the account has auto compound enabled
+    /// And the account's stake batch is pending verification
+    /// When the NEAR funds are claimed
+    /// Then compounding is deferred, i.e., the NEAR is credited to the account NEAR balance
+    #[test]
+    fn when_account_has_redeem_stake_batch_with_receipt_and_auto_compound_with_unverified_stake_batch(
+    ) {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+        contract.set_account_preferences(interface::AccountPreferences {
+            auto_claim_receipts: false,
+            auto_compound: true,
+        });
+
+        let mut account = contract.predecessor_registered_account();
+        account.apply_stake_credit(YOCTO.into());
+        let unverified_batch_id = domain::BatchId(100);
+        account.stake_batch = Some(StakeBatch::new(unverified_batch_id, YOCTO.into()));
+        contract.save_registered_account(&account);
+        contract.unverified_stake_batches.push(unverified_batch_id);
+        let batch_id = contract
+            .redeem_all()
+            .map(|batch_id| domain::BatchId(batch_id.into()))
+            .unwrap();
+
+        contract.redeem_stake_batch_receipts.insert(
+            &batch_id,
+            &domain::RedeemStakeBatchReceipt::new(
+                YOCTO.into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
+        );
+        // simulate that the unstaked NEAR was withdrawn from the staking pool
+        contract.total_near.credit(YOCTO.into());
 
         // Act
         contract.claim_receipts();
 
         // Assert
-        assert!(contract.stake_batch_receipts.get(&batch_id_1).is_none());
-
         let account = contract.predecessor_registered_account().account;
-        // and the account batches have been cleared
-        assert_eq!(account.stake_batch.unwrap().id(), batch_id_2);
-        assert!(account.next_stake_batch.is_none());
-        // and the STAKE tokens were claimed and credited to the account
-        assert_eq!(account.stake.unwrap().amount().value(), YOCTO);
+        assert_eq!(account.near.unwrap().amount(), YOCTO.into());
+        assert!(account.redeem_stake_batch.is_none());
+        assert_eq!(account.stake_batch.unwrap().id(), unverified_batch_id);
+        assert_eq!(
+            account.stake_batch.unwrap().balance().amount(),
+            YOCTO.into()
+        );
+        assert!(contract.stake_batch.is_none());
+        assert_eq!(contract.total_near.amount(), YOCTO.into());
     }
 
     /// Given an account has redeemed STAKE
     /// And the batch has completed
-    /// Then the account can claim the NEAR funds
+    /// When the account redeems and restakes
+    /// Then the claimed NEAR is moved into the account's stake batch
     #[test]
-    fn when_account_has_redeem_stake_batch_with_receipt() {
+    fn redeem_and_restake_rewards() {
         // Arrange
         let mut test_context = TestContext::with_registered_account();
         let contract = &mut test_context.contract;
 
         let mut account = contract.predecessor_registered_account();
         account.apply_stake_credit(YOCTO.into());
+        // NEAR that the account already holds is not restaked
+        account.apply_near_credit((2 * YOCTO).into());
+        contract.total_near.credit((2 * YOCTO).into());
         contract.save_registered_account(&account);
         let batch_id = contract
             .redeem_all()
@@ -3341,20 +6575,27 @@ mod test_claim_receipts {
 
         contract.redeem_stake_batch_receipts.insert(
             &batch_id,
-            &domain::RedeemStakeBatchReceipt::new((2 * YOCTO).into(), contract.stake_token_value),
+            &domain::RedeemStakeBatchReceipt::new(
+                YOCTO.into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
         );
+        // simulate that the unstaked NEAR was withdrawn from the staking pool
+        contract.total_near.credit(YOCTO.into());
 
         // Act
-        contract.claim_receipts();
+        let stake_batch_id = contract.redeem_and_restake_rewards();
 
         // Assert
         let account = contract.predecessor_registered_account().account;
-        assert_eq!(account.near.unwrap().amount(), (YOCTO).into());
+        assert_eq!(account.near.unwrap().amount(), (2 * YOCTO).into());
         assert!(account.redeem_stake_batch.is_none());
-
-        // Then there should be 1 STAKE left unclaimed on the receipt
-        let receipt = contract.redeem_stake_batch_receipts.get(&batch_id).unwrap();
-        assert_eq!(receipt.redeemed_stake(), YOCTO.into());
+        let stake_batch = account.stake_batch.unwrap();
+        let stake_batch_id: domain::BatchId = stake_batch_id.into();
+        assert_eq!(stake_batch.id(), stake_batch_id);
+        assert_eq!(stake_batch.balance().amount(), YOCTO.into());
+        assert_eq!(contract.total_near.amount(), (2 * YOCTO).into());
     }
 
     #[test]
@@ -3376,6 +6617,7 @@ mod test_claim_receipts {
                 &domain::RedeemStakeBatchReceipt::new(
                     (2 * YOCTO).into(),
                     contract.stake_token_value,
+                    TEST_STAKING_POOL_ID.to_string(),
                 ),
             );
             batch_id
@@ -3395,6 +6637,7 @@ mod test_claim_receipts {
                 &domain::RedeemStakeBatchReceipt::new(
                     (4 * YOCTO).into(),
                     contract.stake_token_value,
+                    TEST_STAKING_POOL_ID.to_string(),
                 ),
             );
             contract.redeem_stake_batch_lock = None;
@@ -3441,7 +6684,11 @@ mod test_claim_receipts {
                 .unwrap();
             contract.redeem_stake_batch_receipts.insert(
                 &batch_id,
-                &domain::RedeemStakeBatchReceipt::new(YOCTO.into(), contract.stake_token_value),
+                &domain::RedeemStakeBatchReceipt::new(
+                    YOCTO.into(),
+                    contract.stake_token_value,
+                    TEST_STAKING_POOL_ID.to_string(),
+                ),
             );
             batch_id
         };
@@ -3457,7 +6704,11 @@ mod test_claim_receipts {
                 .unwrap();
             contract.redeem_stake_batch_receipts.insert(
                 &batch_id,
-                &domain::RedeemStakeBatchReceipt::new(YOCTO.into(), contract.stake_token_value),
+                &domain::RedeemStakeBatchReceipt::new(
+                    YOCTO.into(),
+                    contract.stake_token_value,
+                    TEST_STAKING_POOL_ID.to_string(),
+                ),
             );
             contract.redeem_stake_batch_lock = None;
             batch_id
@@ -3502,6 +6753,7 @@ mod test_claim_receipts {
                 &domain::RedeemStakeBatchReceipt::new(
                     (2 * YOCTO).into(),
                     contract.stake_token_value,
+                    TEST_STAKING_POOL_ID.to_string(),
                 ),
             );
             batch_id
@@ -3556,7 +6808,11 @@ mod test_claim_receipts {
         contract.near_liquidity_pool = YOCTO.into();
         contract.redeem_stake_batch_receipts.insert(
             &batch_id,
-            &domain::RedeemStakeBatchReceipt::new((2 * YOCTO).into(), contract.stake_token_value),
+            &domain::RedeemStakeBatchReceipt::new(
+                (2 * YOCTO).into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
         );
         contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
 
@@ -3604,7 +6860,11 @@ mod test_claim_receipts {
         contract.near_liquidity_pool = YOCTO.into();
         contract.redeem_stake_batch_receipts.insert(
             &batch_id,
-            &domain::RedeemStakeBatchReceipt::new(YOCTO.into(), contract.stake_token_value),
+            &domain::RedeemStakeBatchReceipt::new(
+                YOCTO.into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
         );
         contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
 
@@ -3649,6 +6909,7 @@ mod test_claim_receipts {
                     (3 * YOCTO).into(),
                     YOCTO.into(),
                 ),
+                TEST_STAKING_POOL_ID.to_string(),
             ),
         );
         contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
@@ -3717,7 +6978,11 @@ mod test_claim_receipts {
 
         contract.redeem_stake_batch_receipts.insert(
             &(contract.batch_id_sequence.value() - 1).into(),
-            &domain::RedeemStakeBatchReceipt::new((10 * YOCTO).into(), contract.stake_token_value),
+            &domain::RedeemStakeBatchReceipt::new(
+                (10 * YOCTO).into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
         );
 
         contract.claim_receipt_funds(&mut account);
@@ -3740,44 +7005,449 @@ mod test_claim_receipts {
     /// And there is a current batch pending withdrawal
     /// Then the account can claim the NEAR funds
     #[test]
-    fn claim_redeem_stake_batch_receipts_for_old_batch_receipt_while_pending_withdrawal_on_current_batch(
-    ) {
-        let mut ctx = TestContext::with_registered_account();
-        let contract = &mut ctx.contract;
+    fn claim_redeem_stake_batch_receipts_for_old_batch_receipt_while_pending_withdrawal_on_current_batch(
+    ) {
+        let mut ctx = TestContext::with_registered_account();
+        let contract = &mut ctx.contract;
+
+        let mut account = contract.predecessor_registered_account();
+        let batch_id = contract.batch_id_sequence;
+        account.redeem_stake_batch =
+            Some(domain::RedeemStakeBatch::new(batch_id, (10 * YOCTO).into()));
+        account.next_redeem_stake_batch = Some(domain::RedeemStakeBatch::new(
+            (batch_id.value() + 1).into(),
+            (10 * YOCTO).into(),
+        ));
+        contract.save_registered_account(&account);
+
+        *contract.batch_id_sequence += 10;
+        contract.redeem_stake_batch = Some(domain::RedeemStakeBatch::new(
+            contract.batch_id_sequence,
+            (100 * YOCTO).into(),
+        ));
+
+        contract.redeem_stake_batch_receipts.insert(
+            &batch_id,
+            &domain::RedeemStakeBatchReceipt::new(
+                (20 * YOCTO).into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
+        );
+        contract.redeem_stake_batch_receipts.insert(
+            &(batch_id.value() + 1).into(),
+            &domain::RedeemStakeBatchReceipt::new(
+                (20 * YOCTO).into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
+        );
+
+        contract.claim_receipt_funds(&mut account);
+        contract.save_registered_account(&account);
+        let account = contract.predecessor_registered_account();
+        assert_eq!(account.near.unwrap().amount(), (20 * YOCTO).into());
+        assert!(account.redeem_stake_batch.is_none());
+
+        let receipt = contract.redeem_stake_batch_receipts.get(&batch_id).unwrap();
+        assert_eq!(receipt.redeemed_stake(), (10 * YOCTO).into());
+    }
+}
+
+#[cfg(test)]
+mod test_redeem_limit {
+    use super::*;
+
+    use crate::{near::YOCTO, test_utils::*};
+    use near_sdk::{json_types::ValidAccountId, testing_env, MockedBlockchain};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn redeem_within_limit_resets_each_epoch() {
+        let mut test_context = TestContext::with_registered_account();
+        test_context.credit_stake(test_context.account_id, 100 * YOCTO);
+        let account_id = ValidAccountId::try_from(test_context.account_id).unwrap();
+        let mut context = test_context.context.clone();
+        context.epoch_height = 10;
+        testing_env!(context.clone());
+
+        assert!(test_context.redeem_limit(account_id.clone()).is_none());
+        let limit = test_context
+            .set_max_redeem_per_epoch(Some((10 * YOCTO).into()))
+            .unwrap();
+        assert_eq!(limit.max_redeem_per_epoch, (10 * YOCTO).into());
+        assert!(limit.pending_increase.is_none());
+
+        test_context.redeem((6 * YOCTO).into());
+        test_context.redeem((4 * YOCTO).into());
+        let limit = test_context.redeem_limit(account_id.clone()).unwrap();
+        assert_eq!(limit.redeemed, (10 * YOCTO).into());
+        assert_eq!(limit.available, 0.into());
+
+        context.epoch_height = 11;
+        testing_env!(context.clone());
+        let limit = test_context.redeem_limit(account_id.clone()).unwrap();
+        assert_eq!(limit.redeemed, 0.into());
+        assert_eq!(limit.available, (10 * YOCTO).into());
+        test_context.redeem((10 * YOCTO).into());
+    }
+
+    #[test]
+    #[should_panic(expected = "account redeem limit for the current epoch would be exceeded")]
+    fn redeem_exceeds_limit() {
+        let mut test_context = TestContext::with_registered_account();
+        test_context.credit_stake(test_context.account_id, 100 * YOCTO);
+
+        test_context.set_max_redeem_per_epoch(Some((10 * YOCTO).into()));
+        test_context.redeem((6 * YOCTO).into());
+        test_context.redeem((5 * YOCTO).into());
+    }
+
+    #[test]
+    #[should_panic(expected = "account redeem limit for the current epoch would be exceeded")]
+    fn redeem_all_exceeds_limit() {
+        let mut test_context = TestContext::with_registered_account();
+        test_context.credit_stake(test_context.account_id, 100 * YOCTO);
+
+        test_context.set_max_redeem_per_epoch(Some((10 * YOCTO).into()));
+        test_context.redeem_all();
+    }
+
+    #[test]
+    fn raising_limit_is_delayed() {
+        let mut test_context = TestContext::with_registered_account();
+        test_context.credit_stake(test_context.account_id, 100 * YOCTO);
+        let account_id = ValidAccountId::try_from(test_context.account_id).unwrap();
+        let delay = test_context.config.redeem_limit_increase_delay_epochs();
+        let mut context = test_context.context.clone();
+        context.epoch_height = 10;
+        testing_env!(context.clone());
+
+        test_context.set_max_redeem_per_epoch(Some((10 * YOCTO).into()));
+        let limit = test_context
+            .set_max_redeem_per_epoch(Some((20 * YOCTO).into()))
+            .unwrap();
+        assert_eq!(limit.max_redeem_per_epoch, (10 * YOCTO).into());
+        let pending_increase = limit.pending_increase.unwrap();
+        assert_eq!(
+            pending_increase.max_redeem_per_epoch,
+            Some((20 * YOCTO).into())
+        );
+        assert_eq!(
+            pending_increase.effective_epoch_height,
+            domain::EpochHeight(10 + delay).into()
+        );
+
+        context.epoch_height = 10 + delay;
+        testing_env!(context.clone());
+        let limit = test_context.redeem_limit(account_id.clone()).unwrap();
+        assert_eq!(limit.max_redeem_per_epoch, (20 * YOCTO).into());
+        assert!(limit.pending_increase.is_none());
+        test_context.redeem((20 * YOCTO).into());
+
+        // removing the limit is also delayed
+        test_context.set_max_redeem_per_epoch(None);
+        assert!(test_context.redeem_limit(account_id.clone()).is_some());
+        context.epoch_height = 10 + (delay * 2);
+        testing_env!(context.clone());
+        assert!(test_context.redeem_limit(account_id.clone()).is_none());
+        test_context.redeem((50 * YOCTO).into());
+    }
+
+    #[test]
+    fn lowering_limit_cancels_pending_increase() {
+        let mut test_context = TestContext::with_registered_account();
+        let account_id = ValidAccountId::try_from(test_context.account_id).unwrap();
+
+        test_context.set_max_redeem_per_epoch(Some((10 * YOCTO).into()));
+        test_context.set_max_redeem_per_epoch(None);
+        let limit = test_context
+            .set_max_redeem_per_epoch(Some((5 * YOCTO).into()))
+            .unwrap();
+        assert_eq!(limit.max_redeem_per_epoch, (5 * YOCTO).into());
+        assert!(limit.pending_increase.is_none());
+        assert_eq!(test_context.redeem_limit(account_id).unwrap(), limit);
+    }
+}
+
+#[cfg(test)]
+mod test_withdrawal_rate_limit {
+    use super::*;
+
+    use crate::interface::Operator;
+    use crate::{near::YOCTO, test_utils::*};
+    use near_sdk::{json_types::ValidAccountId, serde_json, testing_env, MockedBlockchain};
+    use std::convert::{TryFrom, TryInto};
+
+    /// credits the account with the NEAR balance and sets the per epoch withdrawal limit
+    fn setup(test_context: &mut TestContext, near: u128, max_withdrawal_per_epoch: u128) {
+        let mut account = test_context.registered_account(test_context.account_id);
+        account.apply_near_credit(near.into());
+        test_context.save_registered_account(&account);
+        test_context.total_near.credit(near.into());
+        test_context.config.merge(
+            serde_json::from_str(&format!(
+                r#"{{"max_withdrawal_per_epoch": "{}"}}"#,
+                max_withdrawal_per_epoch
+            ))
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn withdraw_overflow_is_queued_and_released_next_epoch() {
+        let mut test_context = TestContext::with_registered_account();
+        setup(&mut test_context, 100 * YOCTO, 10 * YOCTO);
+        let account_id = ValidAccountId::try_from(test_context.account_id).unwrap();
+        let mut context = test_context.context.clone();
+        context.epoch_height = 10;
+        testing_env!(context.clone());
+
+        test_context.withdraw((6 * YOCTO).into());
+        assert!(test_context.queued_withdrawal(account_id.clone()).is_none());
+
+        // When the withdrawal would exceed the limit
+        test_context.withdraw((8 * YOCTO).into());
+        // Then the overflow is queued
+        let queued_withdrawal = test_context.queued_withdrawal(account_id.clone()).unwrap();
+        assert_eq!(queued_withdrawal.recipient, test_context.account_id);
+        assert_eq!(queued_withdrawal.amount, (4 * YOCTO).into());
+        assert_eq!(queued_withdrawal.epoch_height, 10.into());
+        // And the full amount is debited from the account
+        let account = test_context.registered_account(test_context.account_id);
+        assert_eq!(account.near.unwrap().amount(), (86 * YOCTO).into());
+        // And the queued NEAR remains in the contract until it is released
+        assert_eq!(test_context.total_near.amount(), (90 * YOCTO).into());
+        assert_eq!(
+            test_context.epoch_withdrawals.withdrawn(10.into()),
+            (10 * YOCTO).into()
+        );
+
+        // When the queued withdrawal is processed in the next epoch
+        context.epoch_height = 11;
+        context.predecessor_account_id = "anyone.near".to_string();
+        testing_env!(context.clone());
+        let amount = test_context.process_queued_withdrawal(account_id.clone());
+        // Then the queued funds are transferred
+        assert_eq!(amount, (4 * YOCTO).into());
+        assert!(test_context.queued_withdrawal(account_id.clone()).is_none());
+        assert_eq!(test_context.total_near.amount(), (86 * YOCTO).into());
+        assert_eq!(
+            test_context.epoch_withdrawals.withdrawn(11.into()),
+            (4 * YOCTO).into()
+        );
+    }
+
+    #[test]
+    fn process_queued_withdrawal_is_subject_to_limit() {
+        let mut test_context = TestContext::with_registered_account();
+        setup(&mut test_context, 100 * YOCTO, 10 * YOCTO);
+        let account_id = ValidAccountId::try_from(test_context.account_id).unwrap();
+        let mut context = test_context.context.clone();
+        context.epoch_height = 10;
+        testing_env!(context.clone());
+
+        test_context.withdraw((35 * YOCTO).into());
+        assert_eq!(
+            test_context
+                .queued_withdrawal(account_id.clone())
+                .unwrap()
+                .amount,
+            (25 * YOCTO).into()
+        );
+
+        context.epoch_height = 11;
+        testing_env!(context.clone());
+        assert_eq!(
+            test_context.process_queued_withdrawal(account_id.clone()),
+            (10 * YOCTO).into()
+        );
+        // the limit has been reached for the epoch
+        assert_eq!(
+            test_context.process_queued_withdrawal(account_id.clone()),
+            0.into()
+        );
+        let queued_withdrawal = test_context.queued_withdrawal(account_id.clone()).unwrap();
+        assert_eq!(queued_withdrawal.amount, (15 * YOCTO).into());
+        // processing a queued withdrawal does not push out its release epoch
+        assert_eq!(queued_withdrawal.epoch_height, 10.into());
+
+        context.epoch_height = 12;
+        testing_env!(context.clone());
+        assert_eq!(
+            test_context.process_queued_withdrawal(account_id.clone()),
+            (10 * YOCTO).into()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "queued withdrawal is not releasable until the next epoch")]
+    fn process_queued_withdrawal_within_same_epoch() {
+        let mut test_context = TestContext::with_registered_account();
+        setup(&mut test_context, 100 * YOCTO, 10 * YOCTO);
+        let account_id = ValidAccountId::try_from(test_context.account_id).unwrap();
+
+        test_context.withdraw((15 * YOCTO).into());
+        test_context.process_queued_withdrawal(account_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "account has no queued withdrawal")]
+    fn process_queued_withdrawal_with_none_queued() {
+        let mut test_context = TestContext::with_registered_account();
+        let account_id = ValidAccountId::try_from(test_context.account_id).unwrap();
+        test_context.process_queued_withdrawal(account_id);
+    }
+
+    #[test]
+    fn transfer_near_overflow_is_queued_for_recipient() {
+        let mut test_context = TestContext::with_registered_account();
+        setup(&mut test_context, 100 * YOCTO, 10 * YOCTO);
+        let account_id = ValidAccountId::try_from(test_context.account_id).unwrap();
+
+        test_context.transfer_near("bob.near".try_into().unwrap(), (15 * YOCTO).into());
+        let queued_withdrawal = test_context.queued_withdrawal(account_id.clone()).unwrap();
+        assert_eq!(queued_withdrawal.recipient, "bob.near");
+        assert_eq!(queued_withdrawal.amount, (5 * YOCTO).into());
+
+        // queuing more funds for the same recipient adds to the queued withdrawal
+        test_context.transfer_near("bob.near".try_into().unwrap(), (5 * YOCTO).into());
+        let queued_withdrawal = test_context.queued_withdrawal(account_id).unwrap();
+        assert_eq!(queued_withdrawal.amount, (10 * YOCTO).into());
+    }
+
+    #[test]
+    #[should_panic(expected = "account has a queued withdrawal for a different recipient")]
+    fn queue_withdrawal_for_different_recipient() {
+        let mut test_context = TestContext::with_registered_account();
+        setup(&mut test_context, 100 * YOCTO, 10 * YOCTO);
+
+        test_context.transfer_near("bob.near".try_into().unwrap(), (15 * YOCTO).into());
+        test_context.withdraw((5 * YOCTO).into());
+    }
+
+    #[test]
+    fn no_limit_configured() {
+        let mut test_context = TestContext::with_registered_account();
+        setup(&mut test_context, 100 * YOCTO, 0);
+        let account_id = ValidAccountId::try_from(test_context.account_id).unwrap();
+
+        test_context.withdraw_all();
+        assert!(test_context.queued_withdrawal(account_id).is_none());
+        assert_eq!(test_context.total_near.amount(), 0.into());
+    }
+
+    #[test]
+    fn operator_releases_queued_withdrawal() {
+        let mut test_context = TestContext::with_registered_account();
+        setup(&mut test_context, 100 * YOCTO, 10 * YOCTO);
+        let account_id = ValidAccountId::try_from(test_context.account_id).unwrap();
+        let mut context = test_context.context.clone();
+
+        test_context.withdraw((30 * YOCTO).into());
+
+        // the operator override bypasses the limit and does not wait for the next epoch
+        context.predecessor_account_id = test_context.operator_id.clone();
+        testing_env!(context.clone());
+        let amount = test_context.release_queued_withdrawal(account_id.clone());
+        assert_eq!(amount, (20 * YOCTO).into());
+        assert!(test_context.queued_withdrawal(account_id).is_none());
+        assert_eq!(test_context.total_near.amount(), (70 * YOCTO).into());
+    }
+
+    #[test]
+    #[should_panic(expected = "contract call is only allowed by an operator account")]
+    fn release_queued_withdrawal_not_invoked_by_operator() {
+        let mut test_context = TestContext::with_registered_account();
+        setup(&mut test_context, 100 * YOCTO, 10 * YOCTO);
+        let account_id = ValidAccountId::try_from(test_context.account_id).unwrap();
+
+        test_context.withdraw((30 * YOCTO).into());
+        test_context.release_queued_withdrawal(account_id);
+    }
+}
+
+#[cfg(test)]
+mod test_run_pending_workflows {
+    use super::*;
+
+    use crate::{near::YOCTO, test_utils::*};
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn no_workflow_is_runnable() {
+        let mut test_context = TestContext::with_registered_account();
+        assert_eq!(test_context.run_pending_workflows(), WorkflowRun::Idle);
+        assert!(deserialize_receipts().is_empty());
+    }
+
+    #[test]
+    fn stake_batch_is_run() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+        let batch_id = test_context.deposit(None);
 
-        let mut account = contract.predecessor_registered_account();
-        let batch_id = contract.batch_id_sequence;
-        account.redeem_stake_batch =
-            Some(domain::RedeemStakeBatch::new(batch_id, (10 * YOCTO).into()));
-        account.next_redeem_stake_batch = Some(domain::RedeemStakeBatch::new(
-            (batch_id.value() + 1).into(),
-            (10 * YOCTO).into(),
-        ));
-        contract.save_registered_account(&account);
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        assert_eq!(
+            test_context.run_pending_workflows(),
+            WorkflowRun::Stake(batch_id)
+        );
+        assert_eq!(test_context.stake_batch_lock, Some(StakeLock::Staking));
+        assert!(!deserialize_receipts().is_empty());
 
-        *contract.batch_id_sequence += 10;
-        contract.redeem_stake_batch = Some(domain::RedeemStakeBatch::new(
-            contract.batch_id_sequence,
-            (100 * YOCTO).into(),
-        ));
+        // the stake batch workflow is in progress
+        assert_eq!(test_context.run_pending_workflows(), WorkflowRun::Idle);
+    }
 
-        contract.redeem_stake_batch_receipts.insert(
-            &batch_id,
-            &domain::RedeemStakeBatchReceipt::new((20 * YOCTO).into(), contract.stake_token_value),
+    #[test]
+    fn redeem_stake_batch_is_unstaked() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut account = test_context.registered_account(test_context.account_id);
+        account.apply_stake_credit((10 * YOCTO).into());
+        test_context.save_registered_account(&account);
+        let batch_id = test_context.redeem((10 * YOCTO).into());
+
+        assert_eq!(
+            test_context.run_pending_workflows(),
+            WorkflowRun::Unstake(batch_id)
         );
-        contract.redeem_stake_batch_receipts.insert(
-            &(batch_id.value() + 1).into(),
-            &domain::RedeemStakeBatchReceipt::new((20 * YOCTO).into(), contract.stake_token_value),
+        assert_eq!(
+            test_context.redeem_stake_batch_lock,
+            Some(RedeemLock::Unstaking)
         );
+    }
 
-        contract.claim_receipt_funds(&mut account);
-        contract.save_registered_account(&account);
-        let account = contract.predecessor_registered_account();
-        assert_eq!(account.near.unwrap().amount(), (20 * YOCTO).into());
-        assert!(account.redeem_stake_batch.is_none());
+    #[test]
+    fn stake_batch_cooling_down_is_skipped() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        context.attached_deposit = YOCTO;
+        context.block_index = 100;
+        testing_env!(context.clone());
+        test_context.deposit(None);
+        test_context
+            .config
+            .merge(serde_json::from_str(r#"{"workflow_backoff_base_blocks": 100}"#).unwrap());
+        test_context
+            .workflow_backoffs
+            .get_mut(Workflow::Stake)
+            .record_failure(100.into());
 
-        let receipt = contract.redeem_stake_batch_receipts.get(&batch_id).unwrap();
-        assert_eq!(receipt.redeemed_stake(), (10 * YOCTO).into());
+        context.attached_deposit = 0;
+        context.block_index = 199;
+        testing_env!(context.clone());
+        assert_eq!(test_context.run_pending_workflows(), WorkflowRun::Idle);
+        assert!(test_context.stake_batch_lock.is_none());
+
+        context.block_index = 200;
+        testing_env!(context.clone());
+        assert!(matches!(
+            test_context.run_pending_workflows(),
+            WorkflowRun::Stake(_)
+        ));
     }
 }
 
@@ -3793,7 +7463,9 @@ mod test {
         near::YOCTO,
         test_utils::*,
     };
-    use near_sdk::{json_types::ValidAccountId, testing_env, MockedBlockchain};
+    use near_sdk::{
+        env, json_types::ValidAccountId, test_utils::get_logs, testing_env, MockedBlockchain,
+    };
     use std::convert::{TryFrom, TryInto};
 
     /// Given the account has no funds in stake batches
@@ -3809,6 +7481,52 @@ mod test {
         contract.claim_receipt_funds(&mut account);
     }
 
+    /// returns the gas that was burnt by `f` - excluding the cost of the `used_gas` host call
+    fn gas_burnt<T>(f: impl FnOnce() -> T) -> u64 {
+        let start = env::used_gas();
+        let used_gas_cost = env::used_gas() - start;
+        let start = env::used_gas();
+        f();
+        env::used_gas() - start - used_gas_cost
+    }
+
+    /// Given the account has no funds in stake batches
+    /// When funds are claimed, e.g., on deposit or ft_transfer
+    /// Then the receipts maps are not accessed
+    #[test]
+    fn claim_receipt_funds_with_no_batched_funds_burns_no_gas() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+
+        let mut account = contract.registered_account(test_context.account_id);
+        assert_eq!(gas_burnt(|| contract.claim_receipt_funds(&mut account)), 0);
+    }
+
+    /// Given the account has funds in a stake batch that has not yet been processed
+    /// When funds are claimed
+    /// Then the receipt existence check costs less gas than reading the receipt from storage
+    #[test]
+    fn claim_receipt_funds_for_unprocessed_batch_only_checks_receipt_existence() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+
+        let mut account = contract.registered_account(test_context.account_id);
+        let batch_id = contract.deposit_near_for_account_to_stake(&mut account, YOCTO.into());
+        contract.save_registered_account(&account);
+        let mut account = contract.registered_account(test_context.account_id);
+
+        let claim_gas = gas_burnt(|| contract.claim_receipt_funds(&mut account));
+        let storage_read_gas = gas_burnt(|| contract.stake_batch_receipts.get(&batch_id));
+        assert!(claim_gas > 0);
+        assert!(
+            claim_gas < storage_read_gas,
+            "claim gas: {}, storage read gas: {}",
+            claim_gas,
+            storage_read_gas
+        );
+        assert_eq!(account.stake_batch.unwrap().id(), batch_id);
+    }
+
     /// Given the account has funds in the stake batch
     /// And there is no receipt for the batch
     /// When funds are claimed
@@ -3858,7 +7576,11 @@ mod test {
         // And STAKE token value = 1 NEAR
         let stake_token_value =
             domain::StakeTokenValue::new(Default::default(), YOCTO.into(), YOCTO.into());
-        let receipt = domain::StakeBatchReceipt::new((2 * YOCTO).into(), stake_token_value);
+        let receipt = domain::StakeBatchReceipt::new(
+            (2 * YOCTO).into(),
+            stake_token_value,
+            TEST_STAKING_POOL_ID.to_string(),
+        );
         let batch_id = domain::BatchId(batch_id.into());
         contract.stake_batch_receipts.insert(&batch_id, &receipt);
         // When batch receipts are claimed
@@ -3877,6 +7599,15 @@ mod test {
             account.stake_batch.is_none(),
             "stake batch should be set to None"
         );
+        // the claimed STAKE is logged as transferred from the contract to the account
+        let transfer = format!(
+            r#""event":"ft_transfer","data":[{{"old_owner_id":"{}","new_owner_id":"{}","amount":"{}","memo":"stake batch {}"}}]"#,
+            env::current_account_id(),
+            test_context.account_id,
+            YOCTO,
+            batch_id.value()
+        );
+        assert!(get_logs().iter().any(|log| log.contains(&transfer)));
         let receipt = contract.stake_batch_receipts.get(&batch_id).unwrap();
         assert_eq!(
             receipt.staked_near().value(),
@@ -3934,7 +7665,11 @@ mod test {
         // And STAKE token value = 1 NEAR
         let stake_token_value =
             domain::StakeTokenValue::new(Default::default(), YOCTO.into(), YOCTO.into());
-        let receipt = domain::StakeBatchReceipt::new((2 * YOCTO).into(), stake_token_value);
+        let receipt = domain::StakeBatchReceipt::new(
+            (2 * YOCTO).into(),
+            stake_token_value,
+            TEST_STAKING_POOL_ID.to_string(),
+        );
         let batch_id = domain::BatchId(batch_id.into());
         contract.stake_batch_receipts.insert(&batch_id, &receipt);
         // When batch receipts are claimed
@@ -4010,11 +7745,19 @@ mod test {
         // And STAKE token value = 1 NEAR
         let stake_token_value =
             domain::StakeTokenValue::new(Default::default(), YOCTO.into(), YOCTO.into());
-        let receipt = domain::StakeBatchReceipt::new((2 * YOCTO).into(), stake_token_value);
+        let receipt = domain::StakeBatchReceipt::new(
+            (2 * YOCTO).into(),
+            stake_token_value,
+            TEST_STAKING_POOL_ID.to_string(),
+        );
         contract
             .stake_batch_receipts
             .insert(&domain::BatchId(stake_batch_id.into()), &receipt);
-        let receipt = domain::StakeBatchReceipt::new((3 * YOCTO).into(), stake_token_value);
+        let receipt = domain::StakeBatchReceipt::new(
+            (3 * YOCTO).into(),
+            stake_token_value,
+            TEST_STAKING_POOL_ID.to_string(),
+        );
         contract
             .stake_batch_receipts
             .insert(&domain::BatchId(next_stake_batch_id.into()), &receipt);
@@ -4061,7 +7804,7 @@ mod test {
 
         context.attached_deposit = YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
         context.account_balance += context.attached_deposit;
 
         context.attached_deposit = 0;
@@ -4132,7 +7875,7 @@ mod test {
 
         context.attached_deposit = YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
         contract.stake();
@@ -4258,7 +8001,7 @@ mod test {
             let staked_near_amount = 100 * YOCTO;
             context.attached_deposit = staked_near_amount;
             testing_env!(context.clone());
-            contract.deposit();
+            contract.deposit(None);
             context.account_balance += context.attached_deposit;
 
             {
@@ -4462,12 +8205,15 @@ mod test {
         let contract = &mut test_context.contract;
         context.attached_deposit = 5 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         // Given an account has unclaimed stake batch receipts
         let batch = contract.stake_batch.unwrap();
-        let receipt =
-            domain::StakeBatchReceipt::new(batch.balance().amount(), contract.stake_token_value);
+        let receipt = domain::StakeBatchReceipt::new(
+            batch.balance().amount(),
+            contract.stake_token_value,
+            TEST_STAKING_POOL_ID.to_string(),
+        );
         contract.stake_batch_receipts.insert(&batch.id(), &receipt);
 
         // When the account tries to redeem STAKE
@@ -4492,12 +8238,15 @@ mod test {
         let contract = &mut test_context.contract;
         context.attached_deposit = 5 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         // Given an account has unclaimed stake batch receipts
         let batch = contract.stake_batch.unwrap();
-        let receipt =
-            domain::StakeBatchReceipt::new(batch.balance().amount(), contract.stake_token_value);
+        let receipt = domain::StakeBatchReceipt::new(
+            batch.balance().amount(),
+            contract.stake_token_value,
+            TEST_STAKING_POOL_ID.to_string(),
+        );
         contract.stake_batch_receipts.insert(&batch.id(), &receipt);
 
         // When the account tries to redeem STAKE
@@ -4810,7 +8559,11 @@ mod test {
         ));
         contract.redeem_stake_batch_receipts.insert(
             &contract.batch_id_sequence,
-            &domain::RedeemStakeBatchReceipt::new((10 * YOCTO).into(), contract.stake_token_value),
+            &domain::RedeemStakeBatchReceipt::new(
+                (10 * YOCTO).into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
         );
         contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
         context.epoch_height += UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK.value();
@@ -4873,7 +8626,11 @@ mod test {
         ));
         contract.redeem_stake_batch_receipts.insert(
             &contract.batch_id_sequence,
-            &domain::RedeemStakeBatchReceipt::new((20 * YOCTO).into(), contract.stake_token_value),
+            &domain::RedeemStakeBatchReceipt::new(
+                (20 * YOCTO).into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
         );
 
         contract.claim_receipt_funds(&mut account);
@@ -4909,11 +8666,19 @@ mod test {
 
         contract.redeem_stake_batch_receipts.insert(
             &(contract.batch_id_sequence.value() - 1).into(),
-            &domain::RedeemStakeBatchReceipt::new((10 * YOCTO).into(), contract.stake_token_value),
+            &domain::RedeemStakeBatchReceipt::new(
+                (10 * YOCTO).into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
         );
         contract.redeem_stake_batch_receipts.insert(
             &contract.batch_id_sequence,
-            &domain::RedeemStakeBatchReceipt::new((20 * YOCTO).into(), contract.stake_token_value),
+            &domain::RedeemStakeBatchReceipt::new(
+                (20 * YOCTO).into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
         );
 
         contract.claim_receipt_funds(&mut account);
@@ -4966,6 +8731,7 @@ mod test {
             &domain::RedeemStakeBatchReceipt::new(
                 contract.redeem_stake_batch.unwrap().balance().amount(),
                 contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
             ),
         );
 
@@ -5019,6 +8785,7 @@ mod test {
             &domain::RedeemStakeBatchReceipt::new(
                 contract.redeem_stake_batch.unwrap().balance().amount(),
                 contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
             ),
         );
 
@@ -5062,6 +8829,7 @@ mod test {
             &domain::RedeemStakeBatchReceipt::new(
                 account.redeem_stake_batch.unwrap().balance().amount(),
                 contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
             ),
         );
         *contract.batch_id_sequence += 1;
@@ -5112,11 +8880,19 @@ mod test {
 
         contract.redeem_stake_batch_receipts.insert(
             &batch_id,
-            &domain::RedeemStakeBatchReceipt::new((20 * YOCTO).into(), contract.stake_token_value),
+            &domain::RedeemStakeBatchReceipt::new(
+                (20 * YOCTO).into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
         );
         contract.redeem_stake_batch_receipts.insert(
             &(batch_id.value() + 1).into(),
-            &domain::RedeemStakeBatchReceipt::new((20 * YOCTO).into(), contract.stake_token_value),
+            &domain::RedeemStakeBatchReceipt::new(
+                (20 * YOCTO).into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
         );
 
         contract.claim_receipt_funds(&mut account);
@@ -5137,7 +8913,7 @@ mod test {
 
         context.attached_deposit = 10 * YOCTO;
         testing_env!(context.clone());
-        contract.deposit();
+        contract.deposit(None);
 
         let mut account = contract.predecessor_registered_account();
 
@@ -5147,6 +8923,7 @@ mod test {
             let receipt = domain::StakeBatchReceipt::new(
                 batch.balance().amount(),
                 contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
             );
             contract.stake_batch_receipts.insert(&batch.id(), &receipt);
 
@@ -5156,6 +8933,7 @@ mod test {
             let receipt = domain::StakeBatchReceipt::new(
                 batch.balance().amount(),
                 contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
             );
             contract
                 .stake_batch_receipts
@@ -5175,6 +8953,7 @@ mod test {
                 &domain::RedeemStakeBatchReceipt::new(
                     redeem_stake_batch.balance().amount(),
                     contract.stake_token_value,
+                    TEST_STAKING_POOL_ID.to_string(),
                 ),
             );
             account.redeem_stake_batch = Some(redeem_stake_batch);
@@ -5187,6 +8966,7 @@ mod test {
                 &domain::RedeemStakeBatchReceipt::new(
                     redeem_stake_batch.balance().amount(),
                     contract.stake_token_value,
+                    TEST_STAKING_POOL_ID.to_string(),
                 ),
             );
             account.next_redeem_stake_batch = Some(redeem_stake_batch);
@@ -5361,7 +9141,11 @@ mod test {
         *contract.batch_id_sequence += 1;
         contract.stake_batch_receipts.insert(
             &contract.batch_id_sequence,
-            &domain::StakeBatchReceipt::new(YOCTO.into(), contract.stake_token_value),
+            &domain::StakeBatchReceipt::new(
+                YOCTO.into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
         );
 
         assert_eq!(
@@ -5385,7 +9169,11 @@ mod test {
         *contract.batch_id_sequence += 1;
         contract.redeem_stake_batch_receipts.insert(
             &contract.batch_id_sequence,
-            &domain::RedeemStakeBatchReceipt::new(YOCTO.into(), contract.stake_token_value),
+            &domain::RedeemStakeBatchReceipt::new(
+                YOCTO.into(),
+                contract.stake_token_value,
+                TEST_STAKING_POOL_ID.to_string(),
+            ),
         );
 
         assert_eq!(
@@ -5397,6 +9185,285 @@ mod test {
         );
     }
 
+    #[test]
+    fn current_batch_ids() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        let batch_ids = contract.current_batch_ids();
+        assert_eq!(batch_ids.batch_id_sequence, domain::BatchId(0).into());
+        assert!(batch_ids.stake_batch.is_none());
+        assert!(batch_ids.redeem_stake_batch.is_none());
+
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+        let stake_batch_id: domain::BatchId = contract.deposit(None).into();
+
+        let mut account = contract.predecessor_registered_account();
+        account.apply_stake_credit(YOCTO.into());
+        contract.save_registered_account(&account);
+        let redeem_batch_id: domain::BatchId = contract.redeem(YOCTO.into()).into();
+
+        // stake and redeem batches share the sequence, but the batch kind is encoded in the ID
+        assert_eq!(stake_batch_id.kind(), domain::BatchKind::Stake);
+        assert_eq!(stake_batch_id.sequence(), 1);
+        assert_eq!(redeem_batch_id.kind(), domain::BatchKind::Redeem);
+        assert_eq!(redeem_batch_id.sequence(), 2);
+
+        let batch_ids = contract.current_batch_ids();
+        assert_eq!(batch_ids.batch_id_sequence, domain::BatchId(2).into());
+        assert_eq!(batch_ids.stake_batch, Some(stake_batch_id.into()));
+        assert_eq!(batch_ids.redeem_stake_batch, Some(redeem_batch_id.into()));
+        assert!(batch_ids.next_stake_batch.is_none());
+        assert!(batch_ids.next_redeem_stake_batch.is_none());
+    }
+
+    #[test]
+    fn stake_conversions() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let contract = &mut test_ctx.contract;
+
+        contract.total_stake = TimestampedStakeBalance::new((100 * YOCTO).into());
+        contract.update_stake_token_value((110 * YOCTO).into());
+
+        let conversion = contract.near_to_stake((11 * YOCTO).into());
+        assert_eq!(conversion.near, (11 * YOCTO).into());
+        assert_eq!(
+            conversion.stake,
+            contract
+                .stake_token_value
+                .near_to_stake((11 * YOCTO).into())
+                .into()
+        );
+        assert_eq!(
+            conversion.stake_token_value_block_time_height,
+            contract.stake_token_value.block_time_height().into()
+        );
+
+        let conversion = contract.stake_to_near((10 * YOCTO).into());
+        assert_eq!(conversion.stake, (10 * YOCTO).into());
+        assert_eq!(
+            conversion.near,
+            contract
+                .stake_token_value
+                .stake_to_near((10 * YOCTO).into())
+                .into()
+        );
+    }
+
+    #[test]
+    fn rounding_policy_favors_the_protocol_on_mint() {
+        let test_ctx = TestContext::new();
+        let policy = test_ctx.rounding_policy();
+        assert_eq!(policy.near_to_stake, interface::Rounding::Down);
+        assert_eq!(policy.stake_to_near, interface::Rounding::Up);
+        assert_eq!(
+            policy.stake_token_value_compensation,
+            interface::Rounding::Down
+        );
+    }
+
+    #[test]
+    fn update_stake_token_value_when_all_stake_is_redeemed() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let contract = &mut test_ctx.contract;
+        contract.total_stake = TimestampedStakeBalance::new((1000 * YOCTO).into());
+        contract.update_stake_token_value((1100 * YOCTO).into());
+
+        // all STAKE was redeemed, but some staked NEAR dust remains
+        contract.total_stake = TimestampedStakeBalance::new(0.into());
+        let compensation = contract.update_stake_token_value(1000.into());
+        assert_eq!(compensation, 0.into());
+        assert_eq!(
+            contract.stake_token_value.near_to_stake(YOCTO.into()),
+            YOCTO.into()
+        );
+    }
+
+    #[test]
+    fn account_receipts() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let mut context = test_ctx.context.clone();
+        let account_id = test_ctx.account_id;
+        let contract = &mut test_ctx.contract;
+
+        let receipts = contract
+            .account_receipts(account_id.try_into().unwrap())
+            .unwrap();
+        assert!(receipts.stake_batch_receipts.is_empty());
+        assert!(receipts.redeem_stake_batch_receipts.is_empty());
+        assert!(contract
+            .account_receipts("unregistered.near".try_into().unwrap())
+            .is_none());
+
+        // deposit funds into a stake batch and simulate that the batch was processed
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        contract.deposit(None);
+        let batch = contract.stake_batch.take().unwrap();
+        let receipt = domain::StakeBatchReceipt::new(
+            batch.balance().amount(),
+            contract.stake_token_value,
+            TEST_STAKING_POOL_ID.to_string(),
+        );
+        contract.stake_batch_receipts.insert(&batch.id(), &receipt);
+
+        // redeem STAKE and simulate that the batch was unstaked and is pending withdrawal
+        let mut account = contract.predecessor_registered_account();
+        account.apply_stake_credit((2 * YOCTO).into());
+        contract.save_registered_account(&account);
+        contract.redeem((2 * YOCTO).into());
+        let redeem_stake_batch = contract.redeem_stake_batch.unwrap();
+        let redeem_stake_batch_receipt = redeem_stake_batch
+            .create_receipt(contract.stake_token_value, TEST_STAKING_POOL_ID.to_string());
+        contract
+            .redeem_stake_batch_receipts
+            .insert(&redeem_stake_batch.id(), &redeem_stake_batch_receipt);
+        contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+
+        let receipts = contract
+            .account_receipts(account_id.try_into().unwrap())
+            .unwrap();
+        assert_eq!(receipts.stake_batch_receipts.len(), 1);
+        let stake_batch_receipt = &receipts.stake_batch_receipts[0];
+        assert_eq!(stake_batch_receipt.batch_id, batch.id().into());
+        assert_eq!(stake_batch_receipt.staked_near, (10 * YOCTO).into());
+        assert_eq!(stake_batch_receipt.stake, (10 * YOCTO).into());
+
+        assert_eq!(receipts.redeem_stake_batch_receipts.len(), 1);
+        let redeem_receipt = &receipts.redeem_stake_batch_receipts[0];
+        assert_eq!(redeem_receipt.batch_id, redeem_stake_batch.id().into());
+        assert_eq!(redeem_receipt.redeemed_stake, (2 * YOCTO).into());
+        assert_eq!(redeem_receipt.near, (2 * YOCTO).into());
+        assert_eq!(
+            redeem_receipt.status,
+            RedeemStakeBatchReceiptStatus::PendingWithdrawal
+        );
+
+        // once the unstaked NEAR is withdrawn from the staking pool, the funds can be claimed
+        contract.redeem_stake_batch_lock = None;
+        let receipts = contract
+            .account_receipts(account_id.try_into().unwrap())
+            .unwrap();
+        assert_eq!(
+            receipts.redeem_stake_batch_receipts[0].status,
+            RedeemStakeBatchReceiptStatus::Claimable
+        );
+    }
+
+    #[test]
+    fn redeem_participation() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let account_id = test_ctx.account_id;
+        let contract = &mut test_ctx.contract;
+
+        let mut account = contract.predecessor_registered_account();
+        account.apply_stake_credit((2 * YOCTO).into());
+        contract.save_registered_account(&account);
+        let batch_id = contract.redeem((2 * YOCTO).into());
+
+        // the batch has not yet been run
+        let participation = contract
+            .redeem_participation(batch_id.clone(), account_id.try_into().unwrap())
+            .unwrap();
+        assert_eq!(participation.batch_id, batch_id);
+        assert_eq!(participation.account_id, account_id);
+        assert_eq!(participation.redeemed_stake, (2 * YOCTO).into());
+        assert!(participation.stake_token_value.is_none());
+        assert!(participation.near.is_none());
+        assert_eq!(participation.status, RedeemParticipationStatus::Pending);
+        assert!(contract
+            .redeem_participation(
+                domain::BatchId(u128::from(batch_id.clone()) + 1).into(),
+                account_id.try_into().unwrap()
+            )
+            .is_none());
+        assert!(contract
+            .redeem_participation(batch_id.clone(), "unregistered.near".try_into().unwrap())
+            .is_none());
+
+        // simulate that the batch was unstaked and is pending withdrawal
+        let redeem_stake_batch = contract.redeem_stake_batch.unwrap();
+        let receipt = redeem_stake_batch
+            .create_receipt(contract.stake_token_value, TEST_STAKING_POOL_ID.to_string());
+        contract
+            .redeem_stake_batch_receipts
+            .insert(&redeem_stake_batch.id(), &receipt);
+        contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+        let participation = contract
+            .redeem_participation(batch_id.clone(), account_id.try_into().unwrap())
+            .unwrap();
+        assert_eq!(
+            participation.stake_token_value,
+            Some(contract.stake_token_value.into())
+        );
+        assert_eq!(participation.near, Some((2 * YOCTO).into()));
+        assert_eq!(
+            participation.status,
+            RedeemParticipationStatus::PendingWithdrawal
+        );
+
+        contract.redeem_stake_batch_lock = None;
+        let participation = contract
+            .redeem_participation(batch_id, account_id.try_into().unwrap())
+            .unwrap();
+        assert_eq!(participation.status, RedeemParticipationStatus::Claimable);
+    }
+
+    #[test]
+    fn stake_supply_stats() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        let stats = contract.stake_supply_stats();
+        assert_eq!(stats.stake_minted_this_epoch, 0.into());
+        assert_eq!(stats.total_stake_minted, 0.into());
+
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+        contract.deposit(None);
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        contract.stake();
+
+        context.predecessor_account_id = env::current_account_id();
+        testing_env!(context.clone());
+        contract.on_deposit_and_stake(
+            None,
+            StakingPoolAccount {
+                account_id: contract.staking_pool_id(),
+                unstaked_balance: 0.into(),
+                staked_balance: YOCTO.into(),
+                can_withdraw: true,
+            },
+        );
+        // STAKE is minted when the staked batch is processed
+        context.predecessor_account_id = contract.operator_id();
+        testing_env!(context.clone());
+        contract.stake();
+
+        let stats = contract.stake_supply_stats();
+        assert_eq!(
+            stats.epoch_height,
+            domain::EpochHeight(context.epoch_height).into()
+        );
+        assert_eq!(stats.stake_minted_this_epoch, YOCTO.into());
+        assert_eq!(stats.stake_burned_this_epoch, 0.into());
+        assert_eq!(stats.total_stake_minted, YOCTO.into());
+        assert_eq!(stats.total_stake_supply, YOCTO.into());
+
+        // per epoch counters are reset in the next epoch, but lifetime totals are retained
+        context.epoch_height += 1;
+        testing_env!(context.clone());
+        let stats = contract.stake_supply_stats();
+        assert_eq!(stats.stake_minted_this_epoch, 0.into());
+        assert_eq!(stats.stake_burned_this_epoch, 0.into());
+        assert_eq!(stats.total_stake_minted, YOCTO.into());
+        assert_eq!(stats.total_stake_burned, 0.into());
+    }
+
     #[test]
     fn stake_token_value_compensation() {
         // StakeTokenValue {
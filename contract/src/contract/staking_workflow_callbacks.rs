@@ -1,13 +1,20 @@
 //required in order for near_bindgen macro to work outside of lib.rs
 use crate::*;
 use crate::{
-    domain::{self, YoctoNear, YoctoStake, TGAS},
+    domain::{
+        self, NearLiquidityInflow, NearLiquidityOutflow, Workflow, YoctoNear, YoctoStake, TGAS,
+    },
     errors::illegal_state::STAKE_BATCH_SHOULD_EXIST,
     ext_staking_workflow_callbacks,
-    interface::staking_service::events::{NearLiquidityAdded, PendingWithdrawalCleared, Staked},
-    near::{log, NO_DEPOSIT},
+    interface::{
+        staking_service::events::{
+            PendingWithdrawalCleared, StakeBatchVerificationDeferred, StakeBatchVerified, Staked,
+        },
+        StakeBatchResult,
+    },
+    near::{events, log, NO_DEPOSIT},
 };
-use near_sdk::{env, near_bindgen, Promise};
+use near_sdk::{env, near_bindgen, Promise, PromiseOrValue};
 
 #[near_bindgen]
 impl Contract {
@@ -35,13 +42,13 @@ impl Contract {
 
         let is_liquidity_needed = self.is_liquidity_needed();
         let unstaked_balance = staking_pool_account.unstaked_balance.0;
-        if unstaked_balance > 0 && is_liquidity_needed {
+        let promise = if unstaked_balance > 0 && is_liquidity_needed {
             self.add_liquidity_then_deposit_and_stake(unstaked_balance, batch)
         } else {
             // if liquidity is not needed, then stake it
             let stake_amount = if is_liquidity_needed {
                 let near_liquidity = self.near_liquidity_pool;
-                self.near_liquidity_pool = 0.into();
+                self.consume_near_liquidity(near_liquidity, NearLiquidityOutflow::Restake);
                 batch.balance().amount() + near_liquidity
             } else {
                 batch.balance().amount()
@@ -52,7 +59,9 @@ impl Contract {
                 .get_account()
                 .promise()
                 .then(self.invoke_on_deposit_and_stake(None))
-        }
+        };
+        self.record_callback_gas_usage("on_run_stake_batch");
+        promise
     }
 
     /// ## Workflow
@@ -65,6 +74,8 @@ impl Contract {
     ///    - [Staked](crate::interface::staking_service::events::Staked) event is logged
     /// 5. pop the [StakeBatch](crate::domain::StakeBatch)
     ///
+    /// Resolves to the [StakeBatchResult] that is returned by [process_staked_batch](Contract::process_staked_batch).
+    ///
     /// ## Panics
     /// - if not called by self
     /// - if [StakeBatch](crate::domain::StakeBatch) does not exist
@@ -74,13 +85,48 @@ impl Contract {
         &mut self,
         near_liquidity: Option<interface::YoctoNear>,
         #[callback] staking_pool_account: StakingPoolAccount,
-    ) -> Promise {
+    ) -> PromiseOrValue<StakeBatchResult> {
+        // the staking pool accepted the deposit, which means it is available
+        self.staking_pool_unavailable_until = None;
         self.stake_batch_lock = Some(StakeLock::Staked {
             near_liquidity: near_liquidity.map(Into::into),
             staked_balance: staking_pool_account.staked_balance.0.into(),
             unstaked_balance: staking_pool_account.unstaked_balance.0.into(),
         });
-        self.invoke_process_stake_batch()
+        // recorded before the remaining gas is passed along to `process_staked_batch`
+        self.record_callback_gas_usage("on_deposit_and_stake");
+        self.invoke_process_stake_batch().into()
+    }
+
+    /// Deferred verification path of the stake batch workflow, which is run when the prepaid gas
+    /// does not cover fetching the staking pool account after `deposit_and_stake`.
+    /// - the staked NEAR balance is derived from the cached STAKE token value, i.e., the batch is
+    ///   provisionally minted at the cached STAKE token value
+    /// - the batch receipt is verified on the next [refresh_stake_token_value](crate::interface::StakingService::refresh_stake_token_value)
+    ///
+    /// If the deposit failed, then the stake lock is left in the `Staking` state, which is then
+    /// cleared by [clear_stake_lock](crate::interface::Operator::clear_stake_lock), and None is
+    /// returned.
+    ///
+    /// ## Panics
+    /// - if not called by self
+    /// - if [StakeBatch](crate::domain::StakeBatch) does not exist
+    #[private]
+    pub fn on_stake_batch_deposited(
+        &mut self,
+        stake_amount: interface::YoctoNear,
+    ) -> Option<StakeBatchResult> {
+        if !self.promise_result_succeeded() {
+            return None;
+        }
+        // the staking pool accepted the deposit, which means it is available
+        self.staking_pool_unavailable_until = None;
+        let staked_balance =
+            self.stake_token_value.total_staked_near_balance() + stake_amount.into();
+        let result = self
+            .watch_aggregates(|contract| contract.apply_unverified_staked_batch(staked_balance));
+        self.record_callback_gas_usage("on_stake_batch_deposited");
+        Some(result)
     }
 
     /// ## Workflow
@@ -98,7 +144,13 @@ impl Contract {
     /// - if [StakeBatch](crate::domain::StakeBatch) does not exist
     /// - if any of the upstream Promises failed
     #[private]
-    pub fn process_staked_batch(&mut self) {
+    pub fn process_staked_batch(&mut self) -> StakeBatchResult {
+        self.watch_aggregates(Self::apply_staked_batch)
+    }
+}
+
+impl Contract {
+    fn apply_staked_batch(&mut self) -> StakeBatchResult {
         let batch = self.stake_batch.take().expect(STAKE_BATCH_SHOULD_EXIST);
 
         if let Some(StakeLock::Staked {
@@ -109,11 +161,7 @@ impl Contract {
         {
             if let Some(near_liquidity) = near_liquidity {
                 if near_liquidity.value() > 0 {
-                    *self.near_liquidity_pool += near_liquidity.value();
-                    log(NearLiquidityAdded {
-                        amount: near_liquidity.value(),
-                        balance: self.near_liquidity_pool.value(),
-                    });
+                    self.add_near_liquidity(near_liquidity, NearLiquidityInflow::StakeBatch);
 
                     // check if liquidity can clear the pending withdrawal
                     if let Some(receipt) = self.get_pending_withdrawal() {
@@ -123,9 +171,14 @@ impl Contract {
                                 log(PendingWithdrawalCleared::new(batch, &receipt));
                             }
                             // move the liquidity to the contract's NEAR balance to make it available for withdrawal
-                            self.near_liquidity_pool -= stake_near_value;
+                            self.consume_near_liquidity(
+                                stake_near_value,
+                                NearLiquidityOutflow::PendingWithdrawal,
+                            );
                             self.total_near.credit(stake_near_value);
                             self.redeem_stake_batch_lock = None;
+                            self.record_lock_released(false, "process_staked_batch");
+                            self.record_workflow_success(Workflow::Withdraw);
                             self.pop_redeem_stake_batch();
                         }
                     }
@@ -133,27 +186,98 @@ impl Contract {
             }
 
             self.mint_stake_and_update_stake_token_value(staked_balance, unstaked_balance, batch);
-            self.create_stake_batch_receipt(batch);
+            let receipt = self.create_stake_batch_receipt(batch);
             self.pop_stake_batch();
-            self.stake_batch_lock = None
+            self.stake_batch_lock = None;
+            self.record_lock_released(true, "process_staked_batch");
+            StakeBatchResult::new(batch.id(), &receipt, true)
         } else {
             panic!("ERROR: illegal state - should only be called when StakeLock::Staked - current state is: {:?}", self.stake_batch_lock);
         }
     }
-}
 
-impl Contract {
+    fn apply_unverified_staked_batch(&mut self, staked_balance: YoctoNear) -> StakeBatchResult {
+        let batch = self.stake_batch.take().expect(STAKE_BATCH_SHOULD_EXIST);
+        self.mint_stake_and_update_stake_token_value(staked_balance, 0.into(), batch);
+        let receipt = self.create_stake_batch_receipt(batch);
+        self.pop_stake_batch();
+        self.unverified_stake_batches.push(batch.id());
+        self.stake_batch_lock = None;
+        self.record_lock_released(true, "on_stake_batch_deposited");
+        log(StakeBatchVerificationDeferred {
+            batch_id: batch.id().value(),
+            stake_token_value: self.stake_token_value.into(),
+        });
+        StakeBatchResult::new(batch.id(), &receipt, false)
+    }
+
+    /// re-prices the unverified stake batch receipts using the STAKE token value that was computed
+    /// from the verified staking pool balances, and then realigns the total STAKE supply
+    /// - receipts cannot be claimed until they are verified, which means the full batch is re-priced
+    /// - the STAKE supply delta is recorded and logged as a NEP-141 mint or burn
+    pub(crate) fn verify_stake_batches(&mut self, staked_balance: YoctoNear) {
+        if self.unverified_stake_batches.is_empty() {
+            return;
+        }
+        let stake_token_value = self.stake_token_value;
+        for batch_id in std::mem::take(&mut self.unverified_stake_batches) {
+            if let Some(mut receipt) = self.stake_batch_receipts.get(&batch_id) {
+                let provisional_stake = receipt.near_stake_value();
+                receipt.set_stake_token_value(stake_token_value);
+                let verified_stake = receipt.near_stake_value();
+                self.stake_batch_receipts.insert(&batch_id, &receipt);
+                let memo = format!("stake batch {} verified", batch_id.value());
+                if verified_stake > provisional_stake {
+                    let amount = verified_stake - provisional_stake;
+                    self.total_stake.credit(amount);
+                    self.stake_supply_stats
+                        .record_mint(amount, env::epoch_height().into());
+                    events::ft_mint(&env::current_account_id(), amount, Some(&memo));
+                } else if verified_stake < provisional_stake {
+                    let amount = provisional_stake - verified_stake;
+                    self.total_stake.debit(amount);
+                    self.stake_supply_stats
+                        .record_burn(amount, env::epoch_height().into());
+                    events::ft_burn(&env::current_account_id(), amount, Some(&memo));
+                }
+            }
+            log(StakeBatchVerified {
+                batch_id: batch_id.value(),
+                stake_token_value: stake_token_value.into(),
+            });
+        }
+        self.update_stake_token_value(staked_balance);
+    }
+
+    /// The staking pool account verification is deferred when the prepaid gas does not cover the
+    /// full stake batch workflow, i.e., `deposit_and_stake` then `get_account`.
+    /// - verification can only be deferred when there is no pending withdrawal because the
+    ///   provisional staked NEAR balance is derived from the cached STAKE token value
+    pub(crate) fn defer_stake_batch_verification(&self) -> bool {
+        if self.get_pending_withdrawal().is_some() {
+            return false;
+        }
+        let gas_config = self.config.gas_config();
+        let required_gas = gas_config.staking_pool().deposit_and_stake().value()
+            + gas_config.staking_pool().get_account().value()
+            + gas_config.callbacks().on_deposit_and_stake().value()
+            + gas_config.callbacks().unlock().value()
+            + 4 * gas_config.function_call_promise().value();
+        env::prepaid_gas() - env::used_gas() < required_gas
+    }
+
     pub fn mint_stake_and_update_stake_token_value(
         &mut self,
         staked_balance: YoctoNear,
         unstaked_balance: YoctoNear,
         batch: StakeBatch,
     ) {
-        let staked_balance = self.staked_near_balance(staked_balance, unstaked_balance);
+        let audit = self.new_batch_audit(staked_balance, unstaked_balance);
+        let staked_balance = audit.total_staked_near_balance();
         // this is minted using the prior STAKE token value - however, if rewards were issued, then
         // the STAKE token value is stale
         let stake_minted_amount = self.mint_stake(batch);
-        self.update_stake_token_value(staked_balance.into());
+        let mut compensation = self.update_stake_token_value(staked_balance);
         // recompute the batch STAKE value using the updated staked NEAR balance
         let batch_stake_value = self
             .stake_token_value
@@ -162,8 +286,29 @@ impl Contract {
         {
             self.total_stake.debit(stake_minted_amount);
             self.total_stake.credit(batch_stake_value);
-            self.update_stake_token_value(staked_balance.into());
+            compensation += self.update_stake_token_value(staked_balance);
         }
+        self.record_batch_audit(batch.id(), audit, compensation);
+        self.stake_supply_stats
+            .record_mint(batch_stake_value, env::epoch_height().into());
+        events::ft_mint(
+            &env::current_account_id(),
+            batch_stake_value,
+            Some(&format!("stake batch {}", batch.id().value())),
+        );
+        events::stake_batch_run(
+            batch.id(),
+            batch.balance().amount(),
+            batch_stake_value,
+            self.stake_token_value,
+        );
+        self.record_stake_batch_history(domain::BatchHistoryEntry::new(
+            batch.id(),
+            batch.balance().amount(),
+            batch_stake_value,
+            self.stake_token_value,
+            batch.balance().block_timestamp(),
+        ));
     }
 
     /// the staked NEAR balance is total amount of NEAR deposited and staked in the staking pool
@@ -236,13 +381,22 @@ impl Contract {
 
     /// creates a create for the batch and saves it to storage
     /// - [Staked](crate::interface::staking_service::events::Staked) event is logged
-    fn create_stake_batch_receipt(&mut self, batch: domain::StakeBatch) {
-        let stake_batch_receipt =
-            domain::StakeBatchReceipt::new(batch.balance().amount(), self.stake_token_value);
+    fn create_stake_batch_receipt(
+        &mut self,
+        batch: domain::StakeBatch,
+    ) -> domain::StakeBatchReceipt {
+        let stake_batch_receipt = domain::StakeBatchReceipt::new(
+            batch.balance().amount(),
+            self.stake_token_value,
+            self.staking_pool_id.clone(),
+        );
         self.stake_batch_receipts
             .insert(&batch.id(), &stake_batch_receipt);
+        self.workflow_counters.stake_batches_run += 1;
+        self.record_workflow_success(Workflow::Stake);
 
         log(Staked::new(batch.id(), &stake_batch_receipt));
+        stake_batch_receipt
     }
 
     /// mints new STAKE from the batch using the [stake_token_value] and updates the total STAKE supply
@@ -250,6 +404,7 @@ impl Contract {
         let stake_amount = self
             .stake_token_value
             .near_to_stake(batch.balance().amount());
+        self.accumulate_total_stake_rebate();
         self.total_stake.credit(stake_amount);
         stake_amount
     }
@@ -295,6 +450,19 @@ impl Contract {
         )
     }
 
+    pub(crate) fn invoke_on_stake_batch_deposited(&self, stake_amount: YoctoNear) -> Promise {
+        ext_staking_workflow_callbacks::on_stake_batch_deposited(
+            stake_amount.into(),
+            &env::current_account_id(),
+            NO_DEPOSIT.into(),
+            self.config
+                .gas_config()
+                .callbacks()
+                .on_deposit_and_stake()
+                .value(),
+        )
+    }
+
     pub(crate) fn invoke_process_stake_batch(&self) -> Promise {
         ext_staking_workflow_callbacks::process_staked_batch(
             &env::current_account_id(),
@@ -336,7 +504,7 @@ mod test {
         testing_env!(context.clone());
 
         // account deposits into stake batch
-        contract.deposit();
+        contract.deposit(None);
         contract.stake();
 
         // callback can only be invoked from itself
@@ -434,6 +602,132 @@ mod test {
         }
     }
 
+    /// Given the prepaid gas does not cover fetching the staking pool account
+    /// When the stake batch is run
+    /// Then only `deposit_and_stake` is submitted to the staking pool
+    /// And the batch is minted at the cached STAKE token value
+    /// And the batch receipt is verified on the next STAKE token value refresh
+    #[test]
+    fn deferred_stake_batch_verification() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        context.attached_deposit = 100 * YOCTO;
+        testing_env!(context.clone());
+        contract.deposit(None);
+        let batch_id = contract.stake_batch.unwrap().id();
+
+        context.prepaid_gas = contract
+            .config
+            .gas_config()
+            .staking_pool()
+            .deposit_and_stake()
+            .value()
+            + (TGAS * 30).value();
+        testing_env!(context.clone());
+        contract.stake();
+
+        let receipts: Vec<Receipt> = deserialize_receipts();
+        assert_eq!(receipts[0].actions.len(), 1);
+        match &receipts[0].actions[0] {
+            Action::FunctionCall { method_name, .. } => {
+                assert_eq!(method_name, "deposit_and_stake")
+            }
+            _ => panic!("expected `deposit_and_stake` func call"),
+        }
+        match &receipts[1].actions[0] {
+            Action::FunctionCall { method_name, .. } => {
+                assert_eq!(method_name, "on_stake_batch_deposited")
+            }
+            _ => panic!("expected `on_stake_batch_deposited` func call"),
+        }
+
+        // callback can only be invoked from itself
+        context.predecessor_account_id = context.current_account_id.clone();
+        context.prepaid_gas = 300 * TGAS.value();
+        testing_env!(context.clone());
+        set_env_with_success_promise_result(contract);
+        let result = contract
+            .on_stake_batch_deposited((100 * YOCTO).into())
+            .unwrap();
+        assert_eq!(result.batch_id, batch_id.into());
+        assert_eq!(result.staked_near, (100 * YOCTO).into());
+        assert_eq!(result.stake_minted, (100 * YOCTO).into());
+        assert!(!result.verified);
+        assert!(contract.stake_batch.is_none());
+        assert!(contract.stake_batch_lock.is_none());
+        assert_eq!(
+            contract.unverified_stake_batches(),
+            vec![interface::BatchId::from(batch_id)]
+        );
+        assert_eq!(contract.total_stake.amount(), (100 * YOCTO).into());
+        let receipt = contract.stake_batch_receipts.get(&batch_id).unwrap();
+        assert_eq!(receipt.near_stake_value(), (100 * YOCTO).into());
+        assert_eq!(contract.stake_supply_stats.total_stake_burned(), 0.into());
+
+        // the receipt cannot be claimed until it is verified
+        let mut account = contract.registered_account(test_context.account_id);
+        contract.claim_receipt_funds(&mut account);
+        assert!(account.stake.is_none());
+        assert_eq!(account.stake_batch.unwrap().id(), batch_id);
+
+        // staking rewards were earned since the STAKE token value was cached
+        contract.stake_batch_lock = Some(StakeLock::RefreshingStakeTokenValue);
+        contract.on_refresh_stake_token_value(StakingPoolAccount {
+            account_id: context.predecessor_account_id.clone(),
+            unstaked_balance: 0.into(),
+            staked_balance: (101 * YOCTO).into(),
+            can_withdraw: true,
+        });
+        assert!(contract.unverified_stake_batches().is_empty());
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("StakeBatchVerified")));
+        let receipt = contract.stake_batch_receipts.get(&batch_id).unwrap();
+        assert!(receipt.near_stake_value() < (100 * YOCTO).into());
+        // the STAKE supply is aligned with the STAKE that will be issued against the receipt
+        assert_eq!(contract.total_stake.amount(), receipt.near_stake_value());
+        // the re-priced STAKE is recorded as burned
+        assert_eq!(
+            contract.stake_supply_stats.total_stake_burned(),
+            domain::YoctoStake(100 * YOCTO) - receipt.near_stake_value()
+        );
+        assert!(get_logs().iter().any(|log| log.starts_with(
+            r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_burn""#
+        )));
+
+        // the verified receipt is claimed in full
+        contract.claim_receipt_funds(&mut account);
+        assert_eq!(account.stake.unwrap().amount(), receipt.near_stake_value());
+        assert!(account.stake_batch.is_none());
+    }
+
+    /// Given a deferred stake batch deposit that failed
+    /// When the callback is invoked
+    /// Then the stake lock is left for `clear_stake_lock` to handle the failure
+    #[test]
+    fn deferred_stake_batch_deposit_failed() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        context.attached_deposit = 100 * YOCTO;
+        testing_env!(context.clone());
+        contract.deposit(None);
+        contract.stake_batch_lock = Some(StakeLock::Staking);
+
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        set_env_with_failed_promise_result(contract);
+        assert!(contract
+            .on_stake_batch_deposited((100 * YOCTO).into())
+            .is_none());
+        assert!(contract.stake_batch.is_some());
+        assert_eq!(contract.stake_batch_lock, Some(StakeLock::Staking));
+        assert!(contract.unverified_stake_batches().is_empty());
+    }
+
     /// Given there is a pending withdrawal
     /// And the amount of unstaked NEAR is more than is being staked
     /// When the callback is invoked
@@ -449,7 +743,7 @@ mod test {
         testing_env!(context.clone());
 
         // account deposits into stake batch
-        contract.deposit();
+        contract.deposit(None);
         contract.stake();
 
         // callback can only be invoked from itself
@@ -463,7 +757,8 @@ mod test {
         *contract.batch_id_sequence += 1;
         let redeem_stake_batch =
             domain::RedeemStakeBatch::new(contract.batch_id_sequence, (10 * YOCTO).into());
-        let receipt = redeem_stake_batch.create_receipt(contract.stake_token_value);
+        let receipt = redeem_stake_batch
+            .create_receipt(contract.stake_token_value, TEST_STAKING_POOL_ID.to_string());
         contract.redeem_stake_batch = Some(redeem_stake_batch);
         contract
             .redeem_stake_batch_receipts
@@ -578,7 +873,7 @@ mod test {
         testing_env!(context.clone());
 
         // account deposits 100 NEAR into stake batch
-        contract.deposit();
+        contract.deposit(None);
         contract.stake();
 
         // callback can only be invoked from itself
@@ -593,7 +888,8 @@ mod test {
             *contract.batch_id_sequence += 1;
             let redeem_stake_batch =
                 domain::RedeemStakeBatch::new(contract.batch_id_sequence, (10 * YOCTO).into());
-            let receipt = redeem_stake_batch.create_receipt(contract.stake_token_value);
+            let receipt = redeem_stake_batch
+                .create_receipt(contract.stake_token_value, TEST_STAKING_POOL_ID.to_string());
             contract.redeem_stake_batch = Some(redeem_stake_batch);
             contract
                 .redeem_stake_batch_receipts
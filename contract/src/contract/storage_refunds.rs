@@ -0,0 +1,222 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::core::Hash;
+use crate::domain::{
+    BatchId, RedeemStakeBatchReceipt, StakeBatchReceipt, StakeTokenValue, Versioned,
+};
+use crate::errors::storage_refunds::NO_STORAGE_REFUND;
+use crate::interface::storage_refunds::events::{StorageRefundCredited, StorageRefundWithdrawn};
+use crate::interface::StorageRefunds;
+use crate::near::{
+    log,
+    storage_keys::{
+        VERSIONED_REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX, VERSIONED_STAKE_BATCH_RECEIPTS_KEY_PREFIX,
+    },
+    STORAGE_RECORD_OVERHEAD,
+};
+use crate::*;
+use near_sdk::{borsh::BorshSerialize, json_types::ValidAccountId, near_bindgen, Promise};
+
+#[near_bindgen]
+impl StorageRefunds for Contract {
+    fn storage_refund_balance(&self, account_id: ValidAccountId) -> interface::YoctoNear {
+        self.storage_refunds
+            .get(&Hash::from(account_id))
+            .unwrap_or_default()
+            .into()
+    }
+
+    fn withdraw_storage_refund(&mut self) -> interface::YoctoNear {
+        self.assert_not_paused();
+        let account_id = env::predecessor_account_id();
+        let refund = self.take_storage_refund(&Hash::from(&account_id));
+        assert!(refund.value() > 0, NO_STORAGE_REFUND);
+        log(StorageRefundWithdrawn {
+            account_id: &account_id,
+            amount: refund.value(),
+        });
+        Promise::new(account_id).transfer(refund.value());
+        refund.into()
+    }
+}
+
+impl Contract {
+    /// credits the account with the storage fee for the storage that the account's action freed
+    /// - storage is refunded at the rate that it is charged - see [storage_fee](Contract::storage_fee)
+    /// - only the storage that the account paid for is refunded, i.e., the account storage fee covers
+    ///   the account's batch receipts
+    pub(crate) fn credit_storage_refund(&mut self, account_id_hash: &Hash, freed_bytes: u64) {
+        let freed_bytes = freed_bytes.min(self.batch_receipts_storage_usage());
+        let amount = self.storage_fee(freed_bytes);
+        if amount.value() == 0 {
+            return;
+        }
+        let balance = self
+            .storage_refunds
+            .get(account_id_hash)
+            .unwrap_or_default()
+            + amount;
+        self.storage_refunds.insert(account_id_hash, &balance);
+        self.total_storage_refunds += amount;
+        log(StorageRefundCredited {
+            freed_bytes,
+            amount: amount.value(),
+            balance: balance.value(),
+        });
+    }
+
+    /// storage for a stake batch receipt and a redeem stake batch receipt, which is included in the
+    /// account storage fee when the account storage usage is measured
+    pub(crate) fn batch_receipts_storage_usage(&self) -> u64 {
+        let batch_id_len = BatchId(0).try_to_vec().unwrap().len();
+        let stake_batch_receipt_len = StakeBatchReceipt::new(
+            0.into(),
+            StakeTokenValue::default(),
+            self.staking_pool_id.clone(),
+        )
+        .to_record()
+        .try_to_vec()
+        .unwrap()
+        .len();
+        let redeem_stake_batch_receipt_len = RedeemStakeBatchReceipt::new(
+            0.into(),
+            StakeTokenValue::default(),
+            self.staking_pool_id.clone(),
+        )
+        .to_record()
+        .try_to_vec()
+        .unwrap()
+        .len();
+        (VERSIONED_STAKE_BATCH_RECEIPTS_KEY_PREFIX.len()
+            + VERSIONED_REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX.len()
+            + 2 * batch_id_len
+            + stake_batch_receipt_len
+            + redeem_stake_batch_receipt_len) as u64
+            + 2 * STORAGE_RECORD_OVERHEAD
+    }
+
+    /// removes the storage refund that is owed to the account
+    /// - returns the refund amount, which the caller is responsible for transferring
+    pub(crate) fn take_storage_refund(&mut self, account_id_hash: &Hash) -> YoctoNear {
+        match self.storage_refunds.remove(account_id_hash) {
+            None => 0.into(),
+            Some(refund) => {
+                self.total_storage_refunds -= refund;
+                refund
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::{AccountManagement, StakingService};
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    /// deposits funds into a stake batch and simulates that the batch was run
+    fn run_stake_batch(ctx: &mut TestContext) {
+        let mut context = ctx.context.clone();
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context);
+        ctx.deposit(None);
+        let batch = ctx.stake_batch.unwrap();
+        let receipt = domain::StakeBatchReceipt::new(
+            batch.balance().amount(),
+            ctx.stake_token_value,
+            TEST_STAKING_POOL_ID.to_string(),
+        );
+        ctx.stake_batch_receipts.insert(&batch.id(), &receipt);
+        ctx.stake_batch = None;
+        testing_env!(ctx.context.clone());
+    }
+
+    #[test]
+    fn claim_that_deletes_receipt_is_credited_storage_refund() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id = to_valid_account_id(ctx.account_id);
+        run_stake_batch(&mut ctx);
+        assert_eq!(ctx.storage_refund_balance(account_id.clone()), 0.into());
+        let total_user_accounts_balance = ctx.total_user_accounts_balance();
+
+        let mut account = ctx.registered_account(ctx.account_id);
+        ctx.claim_receipt_funds(&mut account);
+        assert_eq!(ctx.workflow_counters.receipts_deleted, 1);
+        let refund = ctx.storage_refund_balance(account_id.clone());
+        assert!(refund.value() > 0);
+        // refunded at the rate that storage is charged
+        assert_eq!(refund.value() % ctx.storage_fee(1).value(), 0);
+        assert!(refund.value() <= ctx.storage_fee(ctx.batch_receipts_storage_usage()).value());
+        assert_eq!(ctx.total_storage_refunds, refund.clone().into());
+        assert_eq!(
+            ctx.total_user_accounts_balance(),
+            total_user_accounts_balance + refund.clone().into()
+        );
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.starts_with("StorageRefundCredited")));
+
+        assert_eq!(ctx.withdraw_storage_refund(), refund);
+        assert_eq!(ctx.storage_refund_balance(account_id), 0.into());
+        assert_eq!(ctx.total_storage_refunds, 0.into());
+    }
+
+    #[test]
+    fn claim_that_does_not_delete_receipt_is_not_credited() {
+        let mut ctx = TestContext::with_registered_account();
+        run_stake_batch(&mut ctx);
+        // another account's funds are still batched in the receipt
+        let batch_id = ctx
+            .registered_account(ctx.account_id)
+            .stake_batch
+            .unwrap()
+            .id();
+        let receipt = ctx.stake_batch_receipts.get(&batch_id).unwrap();
+        let receipt = domain::StakeBatchReceipt::new(
+            receipt.staked_near() + YOCTO.into(),
+            receipt.stake_token_value(),
+            TEST_STAKING_POOL_ID.to_string(),
+        );
+        ctx.stake_batch_receipts.insert(&batch_id, &receipt);
+
+        let mut account = ctx.registered_account(ctx.account_id);
+        ctx.claim_receipt_funds(&mut account);
+        assert_eq!(ctx.workflow_counters.receipts_deleted, 0);
+        assert_eq!(
+            ctx.storage_refund_balance(to_valid_account_id(ctx.account_id)),
+            0.into()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "no storage refund is owed to the account")]
+    fn withdraw_storage_refund_with_zero_balance() {
+        let mut ctx = TestContext::with_registered_account();
+        ctx.withdraw_storage_refund();
+    }
+
+    #[test]
+    fn storage_refund_is_limited_to_the_storage_the_account_paid_for() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id_hash = Hash::from(ctx.account_id);
+        let paid_bytes = ctx.batch_receipts_storage_usage();
+        ctx.credit_storage_refund(&account_id_hash, paid_bytes * 10);
+        assert_eq!(
+            ctx.storage_refund_balance(to_valid_account_id(ctx.account_id)),
+            ctx.storage_fee(paid_bytes).into()
+        );
+    }
+
+    #[test]
+    fn unregister_account_pays_out_storage_refund() {
+        let mut ctx = TestContext::with_registered_account();
+        let account_id_hash = Hash::from(ctx.account_id);
+        ctx.credit_storage_refund(&account_id_hash, 100);
+        assert!(ctx.total_storage_refunds.value() > 0);
+
+        ctx.unregister_account(None);
+        assert!(ctx.storage_refunds.get(&account_id_hash).is_none());
+        assert_eq!(ctx.total_storage_refunds, 0.into());
+    }
+}
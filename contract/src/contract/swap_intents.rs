@@ -0,0 +1,603 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::core::Hash;
+use crate::errors::swap_intents::{
+    INVALID_FILL_SWAP_INTENT_MESSAGE, SWAP_DEPLOYMENT_NOT_REGISTERED, SWAP_INTENT_NOT_FOUND,
+    SWAP_INTENT_TARGET_MISMATCH, ZERO_SWAP_INTENT_AMOUNT,
+};
+use crate::interface::swap_intents::events::{
+    SwapIntentCancelled, SwapIntentFillRejected, SwapIntentFilled, SwapIntentPosted,
+    SwapSettlementFailed,
+};
+use crate::interface::{
+    FillSwapIntentMessage, Memo, SwapIntent, SwapIntents, TokenAmount, TransferCallMessage,
+    TransferReceiver, YoctoStake,
+};
+use crate::near::{events, log, NO_DEPOSIT};
+use crate::*;
+use near_sdk::{
+    ext_contract, json_types::ValidAccountId, near_bindgen, serde_json, AccountId, PromiseOrValue,
+};
+
+#[near_bindgen]
+impl SwapIntents for Contract {
+    fn post_swap_intent(
+        &mut self,
+        target_stake_contract: ValidAccountId,
+        amount: YoctoStake,
+    ) -> SwapIntent {
+        let amount: domain::YoctoStake = amount.into();
+        assert!(amount.value() > 0, ZERO_SWAP_INTENT_AMOUNT);
+        let target = Hash::from(target_stake_contract.as_ref());
+        assert!(
+            self.swap_deployments.contains_key(&target),
+            SWAP_DEPLOYMENT_NOT_REGISTERED
+        );
+
+        let mut account = self.predecessor_registered_account();
+        self.claim_receipt_funds(&mut account);
+        account.apply_stake_debit(amount);
+        self.save_registered_account(&account);
+        // the STAKE is held by the contract until the swap intent is filled or cancelled
+        events::ft_transfer(
+            &env::predecessor_account_id(),
+            &env::current_account_id(),
+            amount,
+            Some("swap intent"),
+        );
+
+        let mut intent = self
+            .swap_intents
+            .get(&account.id)
+            .unwrap_or_else(|| domain::SwapIntent::new(target, env::epoch_height().into()));
+        assert!(
+            intent.target_stake_contract() == target,
+            SWAP_INTENT_TARGET_MISMATCH
+        );
+        intent.add(amount);
+        self.swap_intents.insert(&account.id, &intent);
+
+        log(SwapIntentPosted {
+            account_id: &env::predecessor_account_id(),
+            target_stake_contract: target_stake_contract.as_ref(),
+            amount: amount.value(),
+        });
+        self.to_swap_intent(intent)
+    }
+
+    fn cancel_swap_intent(&mut self) -> YoctoStake {
+        let mut account = self.predecessor_registered_account();
+        let mut intent = self
+            .swap_intents
+            .get(&account.id)
+            .expect(SWAP_INTENT_NOT_FOUND);
+        let amount = intent.withdraw();
+        if amount.value() > 0 {
+            account.apply_stake_credit(amount);
+            self.save_registered_account(&account);
+            events::ft_transfer(
+                &env::current_account_id(),
+                &env::predecessor_account_id(),
+                amount,
+                Some("swap intent cancelled"),
+            );
+        }
+        self.save_swap_intent(&account.id, intent);
+
+        log(SwapIntentCancelled {
+            account_id: &env::predecessor_account_id(),
+            amount: amount.value(),
+        });
+        amount.into()
+    }
+
+    fn swap_intent(&self, account_id: ValidAccountId) -> Option<SwapIntent> {
+        self.swap_intents
+            .get(&Hash::from(account_id))
+            .map(|intent| self.to_swap_intent(intent))
+    }
+}
+
+/// STAKE is received from registered swap deployments to fill swap intents, and wNEAR is received
+/// from the configured wNEAR contract to be deposited into the sender's stake batch
+#[near_bindgen]
+impl TransferReceiver for Contract {
+    /// the target contract STAKE is held by this contract on the target contract while the fill is
+    /// being settled - any STAKE that is not used to fill the intent is refunded to the filler by
+    /// the target contract
+    ///
+    /// wNEAR transfers are delegated to [deposit_wrap_near](Contract::deposit_wrap_near)
+    ///
+    /// ## Panics
+    /// - if the predecessor is neither the wNEAR contract nor a registered swap deployment
+    /// - if the message is not a [FillSwapIntentMessage]
+    /// - if the filler account is not registered
+    /// - if the swap intent does not exist for the predecessor swap deployment
+    fn ft_on_transfer(
+        &mut self,
+        sender_id: ValidAccountId,
+        amount: TokenAmount,
+        msg: TransferCallMessage,
+    ) -> PromiseOrValue<TokenAmount> {
+        self.assert_not_paused();
+        let target_stake_contract = env::predecessor_account_id();
+        if self.is_wrap_near_contract(&target_stake_contract) {
+            return self.deposit_wrap_near(sender_id, amount);
+        }
+        let target = Hash::from(&target_stake_contract);
+        assert!(
+            self.swap_deployments.contains_key(&target),
+            SWAP_DEPLOYMENT_NOT_REGISTERED
+        );
+        let message: FillSwapIntentMessage =
+            serde_json::from_str(&msg).expect(INVALID_FILL_SWAP_INTENT_MESSAGE);
+        // the filler is credited with STAKE on this contract
+        self.registered_account(sender_id.as_ref());
+        assert!(
+            self.swap_intents
+                .get(&Hash::from(&message.intent_owner_id))
+                .map_or(false, |intent| intent.target_stake_contract() == target),
+            SWAP_INTENT_NOT_FOUND
+        );
+
+        let gas_config = self.config.gas_config().callbacks();
+        ext_swap_deployment::stake_token_value(
+            &target_stake_contract,
+            NO_DEPOSIT.value(),
+            gas_config.swap_stake_token_value().value(),
+        )
+        .then(ext_swap_callbacks::on_swap_intent_fill(
+            message.intent_owner_id,
+            sender_id.into(),
+            amount,
+            &env::current_account_id(),
+            NO_DEPOSIT.value(),
+            gas_config.on_swap_intent_fill().value(),
+        ))
+        .into()
+    }
+}
+
+#[ext_contract(ext_swap_deployment)]
+pub trait SwapDeployment {
+    fn stake_token_value(&self) -> interface::StakeTokenValue;
+
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: TokenAmount, memo: Option<Memo>);
+}
+
+#[ext_contract(ext_swap_callbacks)]
+pub trait SwapCallbacks {
+    fn on_swap_intent_fill(
+        &mut self,
+        #[callback] target_stake_token_value: interface::StakeTokenValue,
+        intent_owner_id: AccountId,
+        filler_id: AccountId,
+        amount: TokenAmount,
+    ) -> PromiseOrValue<TokenAmount>;
+
+    fn on_swap_settlement(
+        &mut self,
+        intent_owner_id: AccountId,
+        filler_id: AccountId,
+        amount: YoctoStake,
+        target_amount: YoctoStake,
+        transfer_amount: TokenAmount,
+    ) -> TokenAmount;
+}
+
+#[near_bindgen]
+impl Contract {
+    /// computes the swap using the STAKE token values of both contracts and reserves the escrowed
+    /// STAKE while the target STAKE is transferred to the intent owner
+    ///
+    /// Returns the unused target STAKE amount, which is refunded to the filler.
+    #[private]
+    pub fn on_swap_intent_fill(
+        &mut self,
+        #[callback] target_stake_token_value: interface::StakeTokenValue,
+        intent_owner_id: AccountId,
+        filler_id: AccountId,
+        amount: TokenAmount,
+    ) -> PromiseOrValue<TokenAmount> {
+        let epoch_height = env::epoch_height();
+        if target_stake_token_value.block_time_height.epoch_height.0 .0 != epoch_height
+            || self
+                .stake_token_value
+                .block_time_height()
+                .epoch_height()
+                .value()
+                != epoch_height
+        {
+            return self.reject_swap_intent_fill(
+                intent_owner_id,
+                filler_id,
+                amount,
+                "STAKE token values must be refreshed within the current epoch",
+            );
+        }
+
+        let owner_id_hash = Hash::from(&intent_owner_id);
+        let (mut intent, target_stake_contract) =
+            match self.swap_intents.get(&owner_id_hash).and_then(|intent| {
+                self.swap_deployments
+                    .get(&intent.target_stake_contract())
+                    .map(|target_stake_contract| (intent, target_stake_contract))
+            }) {
+                Some((intent, target_stake_contract)) if intent.amount().value() > 0 => {
+                    (intent, target_stake_contract)
+                }
+                _ => {
+                    return self.reject_swap_intent_fill(
+                        intent_owner_id,
+                        filler_id,
+                        amount,
+                        "swap intent has no STAKE available to fill",
+                    )
+                }
+            };
+
+        let fill = intent.quote_fill(
+            self.stake_token_value,
+            target_stake_token_value.into(),
+            amount.value().into(),
+        );
+        if fill.amount.value() == 0 || fill.target_amount.value() == 0 {
+            return self.reject_swap_intent_fill(
+                intent_owner_id,
+                filler_id,
+                amount,
+                "transfer amount is too small to fill the swap intent",
+            );
+        }
+        intent.reserve(fill.amount);
+        self.swap_intents.insert(&owner_id_hash, &intent);
+
+        let gas_config = self.config.gas_config().callbacks();
+        ext_swap_deployment::ft_transfer(
+            intent_owner_id.clone(),
+            fill.target_amount.value().into(),
+            Some("swap intent fill".into()),
+            &target_stake_contract,
+            1,
+            gas_config.swap_settlement_transfer().value(),
+        )
+        .then(ext_swap_callbacks::on_swap_settlement(
+            intent_owner_id,
+            filler_id,
+            fill.amount.into(),
+            fill.target_amount.into(),
+            amount,
+            &env::current_account_id(),
+            NO_DEPOSIT.value(),
+            gas_config.on_swap_settlement().value(),
+        ))
+        .into()
+    }
+
+    /// if the target STAKE was transferred to the intent owner, then the reserved STAKE is credited
+    /// to the filler - otherwise the reserved STAKE is returned to the swap intent escrow and the
+    /// full transfer amount is refunded to the filler
+    ///
+    /// NOTE: if the filler unregistered while the fill was being settled, then the reserved STAKE is
+    ///       returned to the swap intent escrow
+    ///
+    /// Returns the unused target STAKE amount, which is refunded to the filler.
+    #[private]
+    pub fn on_swap_settlement(
+        &mut self,
+        intent_owner_id: AccountId,
+        filler_id: AccountId,
+        amount: YoctoStake,
+        target_amount: YoctoStake,
+        transfer_amount: TokenAmount,
+    ) -> TokenAmount {
+        let owner_id_hash = Hash::from(&intent_owner_id);
+        // accounts cannot unregister while they have an open swap intent
+        let mut intent = self
+            .swap_intents
+            .get(&owner_id_hash)
+            .expect(SWAP_INTENT_NOT_FOUND);
+        let amount: domain::YoctoStake = amount.into();
+        let target_amount: domain::YoctoStake = target_amount.into();
+
+        let filler = if self.promise_result_succeeded() {
+            self.lookup_registered_account(&filler_id)
+        } else {
+            None
+        };
+        match filler {
+            Some(mut filler) => {
+                intent.settle();
+                filler.apply_stake_credit(amount);
+                self.save_registered_account(&filler);
+                events::ft_transfer(
+                    &env::current_account_id(),
+                    &filler_id,
+                    amount,
+                    Some("swap intent filled"),
+                );
+                log(SwapIntentFilled {
+                    intent_owner_id,
+                    filler_id,
+                    target_stake_contract: self
+                        .swap_deployments
+                        .get(&intent.target_stake_contract())
+                        .unwrap_or_default(),
+                    amount: amount.value(),
+                    target_amount: target_amount.value(),
+                });
+                self.save_swap_intent(&owner_id_hash, intent);
+                (transfer_amount.value() - target_amount.value()).into()
+            }
+            None => {
+                intent.release(amount);
+                log(SwapSettlementFailed {
+                    intent_owner_id,
+                    filler_id,
+                    amount: amount.value(),
+                    target_amount: target_amount.value(),
+                });
+                self.save_swap_intent(&owner_id_hash, intent);
+                transfer_amount
+            }
+        }
+    }
+}
+
+impl Contract {
+    fn to_swap_intent(&self, intent: domain::SwapIntent) -> SwapIntent {
+        SwapIntent {
+            target_stake_contract: self
+                .swap_deployments
+                .get(&intent.target_stake_contract())
+                .unwrap_or_default(),
+            amount: intent.amount().into(),
+            pending_fills: intent.pending_fills(),
+            posted_epoch_height: intent.posted_epoch_height().into(),
+        }
+    }
+
+    /// closed swap intents are deleted
+    fn save_swap_intent(&mut self, account_id_hash: &Hash, intent: domain::SwapIntent) {
+        if intent.is_closed() {
+            self.swap_intents.remove(account_id_hash);
+        } else {
+            self.swap_intents.insert(account_id_hash, &intent);
+        }
+    }
+
+    /// the full transfer amount is refunded to the filler
+    fn reject_swap_intent_fill(
+        &self,
+        intent_owner_id: AccountId,
+        filler_id: AccountId,
+        amount: TokenAmount,
+        reason: &str,
+    ) -> PromiseOrValue<TokenAmount> {
+        log(SwapIntentFillRejected {
+            intent_owner_id,
+            filler_id,
+            reason,
+        });
+        PromiseOrValue::Value(amount)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::{AccountManagement, Operator};
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain, VMContext};
+    use std::convert::TryFrom;
+
+    const TARGET_STAKE_CONTRACT: &str = "stake.target.near";
+
+    /// registers the target swap deployment and credits the registered account with STAKE
+    fn setup(ctx: &mut TestContext) -> VMContext {
+        let operator_id = ctx.operator_id.clone();
+        let mut context = ctx.set_predecessor_account_id(&operator_id);
+        testing_env!(context.clone());
+        ctx.register_swap_deployment(to_valid_account_id(TARGET_STAKE_CONTRACT));
+
+        ctx.credit_stake(ctx.account_id, 100 * YOCTO);
+
+        context.predecessor_account_id = ctx.account_id.to_string();
+        testing_env!(context.clone());
+        context
+    }
+
+    fn target_stake_token_value(epoch_height: u64) -> interface::StakeTokenValue {
+        serde_json::from_value(serde_json::json!({
+            "block_time_height": {
+                "block_height": "100",
+                "block_timestamp": "100",
+                "epoch_height": epoch_height.to_string()
+            },
+            "total_staked_near_balance": (150 * YOCTO).to_string(),
+            "total_stake_supply": (100 * YOCTO).to_string(),
+            "value": (YOCTO * 3 / 2).to_string()
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn post_and_cancel_swap_intent() {
+        let mut ctx = TestContext::with_registered_account();
+        setup(&mut ctx);
+        let account_id = ValidAccountId::try_from(ctx.account_id).unwrap();
+
+        ctx.post_swap_intent(
+            to_valid_account_id(TARGET_STAKE_CONTRACT),
+            (30 * YOCTO).into(),
+        );
+        let intent = ctx.post_swap_intent(
+            to_valid_account_id(TARGET_STAKE_CONTRACT),
+            (10 * YOCTO).into(),
+        );
+        assert_eq!(intent.target_stake_contract, TARGET_STAKE_CONTRACT);
+        assert_eq!(intent.amount, (40 * YOCTO).into());
+        assert_eq!(ctx.swap_intent(account_id.clone()), Some(intent));
+        let account = ctx.registered_account(ctx.account_id);
+        assert_eq!(account.stake.unwrap().amount(), (60 * YOCTO).into());
+
+        assert_eq!(ctx.cancel_swap_intent(), (40 * YOCTO).into());
+        assert!(ctx.swap_intent(account_id).is_none());
+        let account = ctx.registered_account(ctx.account_id);
+        assert_eq!(account.stake.unwrap().amount(), (100 * YOCTO).into());
+    }
+
+    #[test]
+    #[should_panic(expected = "STAKE contract is not a registered swap deployment")]
+    fn post_swap_intent_target_not_registered() {
+        let mut ctx = TestContext::with_registered_account();
+        setup(&mut ctx);
+        ctx.post_swap_intent(to_valid_account_id("stake.other.near"), YOCTO.into());
+    }
+
+    #[test]
+    fn fill_swap_intent() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = setup(&mut ctx);
+        let owner_id = ctx.account_id.to_string();
+        ctx.post_swap_intent(
+            to_valid_account_id(TARGET_STAKE_CONTRACT),
+            (100 * YOCTO).into(),
+        );
+        // 1 STAKE = 2 NEAR
+        ctx.stake_token_value = domain::StakeTokenValue::new(
+            domain::BlockTimeHeight::from_env(),
+            (200 * YOCTO).into(),
+            (100 * YOCTO).into(),
+        );
+
+        let filler_id = "filler.near";
+        ctx.register_account(filler_id);
+
+        context.predecessor_account_id = TARGET_STAKE_CONTRACT.to_string();
+        testing_env!(context.clone());
+        ctx.ft_on_transfer(
+            to_valid_account_id(filler_id),
+            (40 * YOCTO).into(),
+            serde_json::json!({ "intent_owner_id": owner_id })
+                .to_string()
+                .as_str()
+                .into(),
+        );
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0].receiver_id, TARGET_STAKE_CONTRACT);
+
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        ctx.on_swap_intent_fill(
+            target_stake_token_value(context.epoch_height),
+            owner_id.clone(),
+            filler_id.to_string(),
+            (40 * YOCTO).into(),
+        );
+        let intent = ctx.swap_intents.get(&Hash::from(&owner_id)).unwrap();
+        assert_eq!(intent.amount(), (70 * YOCTO).into());
+        assert_eq!(intent.pending_fills(), 1);
+        let receipts = deserialize_receipts();
+        match &receipts[0].actions[0] {
+            Action::FunctionCall {
+                method_name,
+                deposit,
+                ..
+            } => {
+                assert_eq!(method_name, "ft_transfer");
+                assert_eq!(*deposit, 1);
+            }
+            _ => panic!("expected function call"),
+        }
+
+        let unused = ctx.on_swap_settlement(
+            owner_id.clone(),
+            filler_id.to_string(),
+            (30 * YOCTO).into(),
+            (40 * YOCTO).into(),
+            (40 * YOCTO).into(),
+        );
+        assert_eq!(unused, 0.into());
+        let filler = ctx.registered_account(filler_id);
+        assert_eq!(filler.stake.unwrap().amount(), (30 * YOCTO).into());
+        let intent = ctx.swap_intents.get(&Hash::from(&owner_id)).unwrap();
+        assert_eq!(intent.pending_fills(), 0);
+        assert_eq!(intent.amount(), (70 * YOCTO).into());
+    }
+
+    #[test]
+    fn fill_swap_intent_with_stale_stake_token_value() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = setup(&mut ctx);
+        let owner_id = ctx.account_id.to_string();
+        ctx.post_swap_intent(
+            to_valid_account_id(TARGET_STAKE_CONTRACT),
+            (100 * YOCTO).into(),
+        );
+
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        match ctx.on_swap_intent_fill(
+            target_stake_token_value(context.epoch_height + 1),
+            owner_id.clone(),
+            "filler.near".to_string(),
+            (40 * YOCTO).into(),
+        ) {
+            PromiseOrValue::Value(unused) => assert_eq!(unused, (40 * YOCTO).into()),
+            _ => panic!("fill should have been rejected"),
+        }
+        let intent = ctx.swap_intents.get(&Hash::from(&owner_id)).unwrap();
+        assert_eq!(intent.amount(), (100 * YOCTO).into());
+    }
+
+    #[test]
+    fn swap_settlement_failed() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = setup(&mut ctx);
+        let owner_id = ctx.account_id.to_string();
+        ctx.post_swap_intent(
+            to_valid_account_id(TARGET_STAKE_CONTRACT),
+            (100 * YOCTO).into(),
+        );
+        let mut intent = ctx.swap_intents.get(&Hash::from(&owner_id)).unwrap();
+        intent.reserve((30 * YOCTO).into());
+        ctx.swap_intents.insert(&Hash::from(&owner_id), &intent);
+
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        set_env_with_failed_promise_result(&mut ctx);
+        let unused = ctx.on_swap_settlement(
+            owner_id.clone(),
+            "filler.near".to_string(),
+            (30 * YOCTO).into(),
+            (40 * YOCTO).into(),
+            (40 * YOCTO).into(),
+        );
+        assert_eq!(unused, (40 * YOCTO).into());
+        let intent = ctx.swap_intents.get(&Hash::from(&owner_id)).unwrap();
+        assert_eq!(intent.amount(), (100 * YOCTO).into());
+        assert_eq!(intent.pending_fills(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "account cannot be unregistered while it has an open swap intent")]
+    fn unregister_account_with_open_swap_intent() {
+        let mut ctx = TestContext::with_registered_account();
+        setup(&mut ctx);
+        let mut intent = domain::SwapIntent::new(
+            Hash::from(TARGET_STAKE_CONTRACT),
+            env::epoch_height().into(),
+        );
+        intent.add(YOCTO.into());
+        intent.reserve(YOCTO.into());
+        let account_id_hash = Hash::from(ctx.account_id);
+        ctx.swap_intents.insert(&account_id_hash, &intent);
+        let mut account = ctx.registered_account(ctx.account_id);
+        account.stake = None;
+        ctx.save_registered_account(&account);
+
+        ctx.unregister_account(None);
+    }
+}
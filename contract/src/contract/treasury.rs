@@ -0,0 +1,214 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::domain::{self, Account, AccountActivityKind, RegisteredAccount};
+use crate::errors::treasury::{
+    TREASURY_BALANCE_INSUFFICIENT, TREASURY_TRANSFER_TO_TREASURY, ZERO_TREASURY_TRANSFER_AMOUNT,
+};
+use crate::interface::treasury::events::{ProtocolFeeCollected, TreasuryTransfer};
+use crate::interface::{Treasury, TreasuryBalance, YoctoStake};
+use crate::near::{events, log};
+use crate::*;
+use near_sdk::{json_types::ValidAccountId, near_bindgen};
+
+#[near_bindgen]
+impl Treasury for Contract {
+    fn treasury_balance(&self) -> TreasuryBalance {
+        let account = self
+            .lookup_registered_account(&env::current_account_id())
+            .map(|account| self.apply_receipt_funds_for_view(&account))
+            .unwrap_or_else(|| Account::new(0.into()));
+        let pending_fees = account
+            .stake_batch
+            .iter()
+            .chain(account.next_stake_batch.iter())
+            .map(|batch| batch.balance().amount().value())
+            .sum::<u128>();
+        TreasuryBalance {
+            account_id: env::current_account_id(),
+            protocol_fee_basis_points: self.config.protocol_fee_basis_points(),
+            stake: account
+                .stake
+                .map_or(0, |balance| balance.amount().value())
+                .into(),
+            pending_fees: pending_fees.into(),
+            total_fees_collected: self.treasury_fees_collected.into(),
+        }
+    }
+
+    fn treasury_transfer(&mut self, recipient: ValidAccountId, amount: YoctoStake) {
+        self.assert_predecessor_is_owner();
+        self.assert_not_paused();
+        assert!(amount.value() > 0, ZERO_TREASURY_TRANSFER_AMOUNT);
+        assert!(
+            recipient.as_ref() != &env::current_account_id(),
+            TREASURY_TRANSFER_TO_TREASURY
+        );
+
+        let stake_amount: domain::YoctoStake = amount.into();
+        let mut treasury = self
+            .lookup_registered_account(&env::current_account_id())
+            .expect(TREASURY_BALANCE_INSUFFICIENT);
+        self.claim_receipt_funds(&mut treasury);
+        assert!(
+            treasury
+                .stake
+                .map_or(false, |balance| balance.amount() >= stake_amount),
+            TREASURY_BALANCE_INSUFFICIENT
+        );
+        treasury.apply_stake_debit(stake_amount);
+
+        let mut receiver = self.registered_account(recipient.as_ref());
+        receiver.apply_stake_credit(stake_amount);
+
+        self.save_registered_account(&treasury);
+        self.save_registered_account(&receiver);
+        self.record_account_activity(
+            &receiver.id,
+            &receiver,
+            AccountActivityKind::TransferIn,
+            stake_amount.value(),
+            None,
+        );
+        events::ft_transfer(
+            &env::current_account_id(),
+            recipient.as_ref(),
+            stake_amount,
+            Some("treasury"),
+        );
+        log(TreasuryTransfer {
+            recipient: recipient.as_ref(),
+            amount: stake_amount.value(),
+        });
+    }
+}
+
+impl Contract {
+    /// deposits the protocol fee into the treasury account's stake batch - the treasury account is
+    /// registered on the first fee deposit
+    /// - the treasury account's storage is paid for by the contract, i.e., there is no storage escrow
+    /// - while the treasury stake batch is pending verification, the fee is credited to the treasury
+    ///   NEAR balance instead, because fees are collected by the batch workflows
+    pub(crate) fn stake_protocol_fee(&mut self, amount: domain::YoctoNear) {
+        let mut treasury = self.treasury_account();
+        let batch_id = if self.stake_batches_verified(&treasury) {
+            Some(self.deposit_near_for_account_to_stake(&mut treasury, amount))
+        } else {
+            treasury.apply_near_credit(amount);
+            self.total_near.credit(amount);
+            None
+        };
+        self.save_registered_account(&treasury);
+        self.treasury_fees_collected += amount;
+        log(ProtocolFeeCollected {
+            batch_id: batch_id.map(|batch_id| batch_id.value()),
+            amount: amount.value(),
+            total: self.treasury_fees_collected.value(),
+        });
+    }
+
+    fn treasury_account(&mut self) -> RegisteredAccount {
+        let treasury_id = env::current_account_id();
+        if let Some(account) = self.lookup_registered_account(&treasury_id) {
+            return account;
+        }
+        self.save_account(&treasury_id, &Account::new(0.into()));
+        self.registered_account(&treasury_id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::{ContractFinancials, FungibleToken};
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{serde_json, testing_env, MockedBlockchain};
+
+    fn set_protocol_fee(test_ctx: &mut TestContext, basis_points: u16) {
+        test_ctx.config.merge(
+            serde_json::from_str(&format!(
+                r#"{{"protocol_fee_basis_points": {}}}"#,
+                basis_points
+            ))
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn protocol_fee_is_skimmed_from_distributed_earnings() {
+        let mut test_ctx = TestContext::with_registered_account();
+        set_protocol_fee(&mut test_ctx, 1_000);
+
+        test_ctx.collected_earnings = (100 * YOCTO).into();
+        let earnings = test_ctx.total_earnings();
+        let protocol_fee = test_ctx.protocol_fee_earnings();
+        assert_eq!(protocol_fee, (earnings.value() / 10).into());
+        assert_eq!(
+            test_ctx.contract_owner_earnings(),
+            ((earnings - protocol_fee).value() / 2).into()
+        );
+
+        test_ctx.distribute_earnings();
+
+        let treasury = test_ctx.treasury_balance();
+        assert_eq!(treasury.account_id, test_ctx.context.current_account_id);
+        assert_eq!(treasury.protocol_fee_basis_points, 1_000);
+        assert_eq!(treasury.pending_fees, protocol_fee.into());
+        assert_eq!(treasury.total_fees_collected, protocol_fee.into());
+        assert_eq!(treasury.stake, 0.into());
+        // the fee NEAR is held in the stake batch and is no longer counted as earnings
+        assert_eq!(test_ctx.customer_batched_stake_deposits(), protocol_fee);
+        assert_eq!(test_ctx.balances().protocol_fee_earnings, 0.into());
+    }
+
+    #[test]
+    fn protocol_fee_disabled_by_default() {
+        let mut test_ctx = TestContext::with_registered_account();
+        test_ctx.collected_earnings = (100 * YOCTO).into();
+
+        assert_eq!(test_ctx.protocol_fee_earnings(), 0.into());
+        test_ctx.distribute_earnings();
+        let treasury = test_ctx.treasury_balance();
+        assert_eq!(treasury.pending_fees, 0.into());
+        assert!(test_ctx
+            .lookup_registered_account(&test_ctx.context.current_account_id)
+            .is_none());
+    }
+
+    #[test]
+    fn treasury_transfer() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let account_id = test_ctx.account_id;
+        let contract_id = test_ctx.context.current_account_id.clone();
+
+        // treasury owned STAKE
+        let mut treasury = test_ctx.treasury_account();
+        treasury.apply_stake_credit((10 * YOCTO).into());
+        test_ctx.save_registered_account(&treasury);
+        test_ctx.total_stake.credit((10 * YOCTO).into());
+        assert_eq!(
+            test_ctx.ft_balance_of(to_valid_account_id(&contract_id)),
+            (10 * YOCTO).into()
+        );
+
+        let context = test_ctx.set_predecessor_account_id(TEST_OWNER_ID);
+        testing_env!(context);
+        test_ctx.treasury_transfer(to_valid_account_id(account_id), (4 * YOCTO).into());
+
+        assert_eq!(test_ctx.treasury_balance().stake, (6 * YOCTO).into());
+        assert_eq!(
+            test_ctx.ft_balance_of(to_valid_account_id(account_id)),
+            (4 * YOCTO).into()
+        );
+        assert_eq!(test_ctx.ft_total_supply(), (10 * YOCTO).into());
+    }
+
+    #[test]
+    #[should_panic(expected = "contract call is only allowed by the contract owner")]
+    fn treasury_transfer_not_owner() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let account_id = test_ctx.account_id;
+        let context = test_ctx.set_predecessor_account_id(account_id);
+        testing_env!(context);
+        test_ctx.treasury_transfer(to_valid_account_id(account_id), YOCTO.into());
+    }
+}
@@ -0,0 +1,282 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::domain::{RegisteredAccount, StakeBatch};
+use crate::interface::fungible_token::events::{WrapNearDepositFailed, WrapNearDeposited};
+use crate::interface::TokenAmount;
+use crate::near::{log, NO_DEPOSIT};
+use crate::*;
+use near_sdk::{ext_contract, json_types::ValidAccountId, near_bindgen, Promise, PromiseOrValue};
+
+#[ext_contract(ext_wrap_near)]
+pub trait ExtWrapNear {
+    /// burns the wNEAR from the predecessor account and transfers the same amount of NEAR
+    /// - requires exactly 1 yoctoNEAR attached
+    fn near_withdraw(&mut self, amount: TokenAmount);
+}
+
+#[ext_contract(ext_wrap_near_callbacks)]
+pub trait ExtWrapNearCallbacks {
+    fn on_wrap_near_withdraw(&mut self, sender_id: AccountId, amount: TokenAmount) -> TokenAmount;
+}
+
+impl Contract {
+    /// the deposit must not fail once the wNEAR has been unwrapped - the checks mirror the checks
+    /// that are applied by the deposit
+    fn can_stake_unwrapped_near(
+        &self,
+        account: &RegisteredAccount,
+        amount: domain::YoctoNear,
+    ) -> bool {
+        if self.pause.is_some()
+            || !self.stake_batches_verified(account)
+            || amount < self.min_required_near_deposit()
+        {
+            return false;
+        }
+        // the account storage escrow growth is billed against the account NEAR balance
+        let mut deposited = account.account;
+        let batch = if self.stake_batch_locked() {
+            deposited
+                .next_stake_batch
+                .get_or_insert(StakeBatch::new(0.into(), 0.into()))
+        } else {
+            deposited
+                .stake_batch
+                .get_or_insert(StakeBatch::new(0.into(), 0.into()))
+        };
+        batch.add(amount);
+        let near_balance = account.near.map_or(0.into(), |balance| balance.amount());
+        self.account_storage_cost(&deposited).value()
+            <= account.storage_escrow.amount().value() + near_balance.value()
+    }
+
+    pub(crate) fn is_wrap_near_contract(&self, account_id: &str) -> bool {
+        self.config.wrap_near_contract_id() == Some(account_id)
+    }
+
+    /// wNEAR that is transferred to this contract via `ft_transfer_call` is unwrapped and the NEAR
+    /// is deposited into the sender's stake batch
+    /// - the transfer message is ignored
+    ///
+    /// The sender and the deposit are validated up front - once the wNEAR has been unwrapped, it can
+    /// no longer be refunded by the wNEAR contract.
+    ///
+    /// ## Panics
+    /// - if the contract is paused
+    /// - if the sender account is not registered
+    /// - if the amount is less than the min required NEAR deposit
+    pub(crate) fn deposit_wrap_near(
+        &mut self,
+        sender_id: ValidAccountId,
+        amount: TokenAmount,
+    ) -> PromiseOrValue<TokenAmount> {
+        self.assert_not_paused();
+        let account = self.registered_account(sender_id.as_ref());
+        // the wNEAR is unwrapped before the NEAR is deposited
+        self.assert_stake_batches_verified(&account);
+        let min_required_near_deposit = self.min_required_near_deposit();
+        assert!(
+            amount.value() >= min_required_near_deposit.value(),
+            "minimum required NEAR deposit is: {}",
+            min_required_near_deposit
+        );
+
+        let gas_config = self.config.gas_config().callbacks();
+        ext_wrap_near::near_withdraw(
+            amount.clone(),
+            &env::predecessor_account_id(),
+            1,
+            gas_config.wrap_near_withdraw().value(),
+        )
+        .then(ext_wrap_near_callbacks::on_wrap_near_withdraw(
+            sender_id.into(),
+            amount,
+            &env::current_account_id(),
+            NO_DEPOSIT.value(),
+            gas_config.on_wrap_near_withdraw().value(),
+        ))
+        .into()
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// if the wNEAR was unwrapped, then the NEAR is deposited into the sender's stake batch -
+    /// otherwise the full amount is returned as unused, which the wNEAR contract refunds to the sender
+    /// - the unwrapped NEAR can no longer be refunded as wNEAR - if the deposit can no longer be
+    ///   applied, e.g., because the contract was paused while the wNEAR was being unwrapped, then the
+    ///   NEAR is credited to the account NEAR balance
+    ///
+    /// Returns the unused wNEAR amount.
+    #[private]
+    pub fn on_wrap_near_withdraw(
+        &mut self,
+        sender_id: AccountId,
+        amount: TokenAmount,
+    ) -> TokenAmount {
+        if !self.promise_result_succeeded() {
+            log(WrapNearDepositFailed {
+                account_id: &sender_id,
+                amount: amount.value(),
+                reason: "wNEAR could not be unwrapped",
+            });
+            return amount;
+        }
+
+        let near_amount: domain::YoctoNear = amount.value().into();
+        match self.lookup_registered_account(&sender_id) {
+            Some(mut account) if self.can_stake_unwrapped_near(&account, near_amount) => {
+                let batch_id =
+                    self.deposit_near_for_account_to_stake_and_save(&mut account, near_amount);
+                log(WrapNearDeposited {
+                    account_id: &sender_id,
+                    amount: near_amount.value(),
+                    batch_id: batch_id.value(),
+                });
+            }
+            Some(mut account) => {
+                log(WrapNearDepositFailed {
+                    account_id: &sender_id,
+                    amount: near_amount.value(),
+                    reason: "NEAR can not be deposited into the stake batch - NEAR is credited to the account NEAR balance",
+                });
+                account.apply_near_credit(near_amount);
+                self.total_near.credit(near_amount);
+                self.bill_account_storage_growth(&mut account);
+                self.save_registered_account(&account);
+            }
+            None => {
+                log(WrapNearDepositFailed {
+                    account_id: &sender_id,
+                    amount: near_amount.value(),
+                    reason: "account is not registered - NEAR is transferred to the account",
+                });
+                Promise::new(sender_id).transfer(near_amount.value());
+            }
+        }
+        0.into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::TransferReceiver;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{serde_json, testing_env, MockedBlockchain};
+
+    const WRAP_NEAR_CONTRACT: &str = "wrap.near";
+
+    fn setup(ctx: &mut TestContext) {
+        ctx.config.merge(
+            serde_json::from_str(&format!(
+                r#"{{"wrap_near_contract_id": "{}"}}"#,
+                WRAP_NEAR_CONTRACT
+            ))
+            .unwrap(),
+        );
+        let context = ctx.set_predecessor_account_id(WRAP_NEAR_CONTRACT);
+        testing_env!(context);
+    }
+
+    #[test]
+    fn ft_on_transfer_unwraps_wrap_near() {
+        let mut ctx = TestContext::with_registered_account();
+        setup(&mut ctx);
+
+        let sender_id = to_valid_account_id(ctx.account_id);
+        ctx.ft_on_transfer(sender_id, (10 * YOCTO).into(), "".into());
+
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0].receiver_id, WRAP_NEAR_CONTRACT);
+        match &receipts[0].actions[0] {
+            Action::FunctionCall {
+                method_name,
+                deposit,
+                ..
+            } => {
+                assert_eq!(method_name, "near_withdraw");
+                assert_eq!(*deposit, 1);
+            }
+            _ => panic!("expected `near_withdraw` function call"),
+        }
+        match &receipts[1].actions[0] {
+            Action::FunctionCall { method_name, .. } => {
+                assert_eq!(method_name, "on_wrap_near_withdraw")
+            }
+            _ => panic!("expected `on_wrap_near_withdraw` function call"),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "account is not registered")]
+    fn ft_on_transfer_sender_not_registered() {
+        let mut ctx = TestContext::with_registered_account();
+        setup(&mut ctx);
+        ctx.ft_on_transfer(
+            to_valid_account_id("unregistered.near"),
+            (10 * YOCTO).into(),
+            "".into(),
+        );
+    }
+
+    #[test]
+    fn on_wrap_near_withdraw_success() {
+        let mut ctx = TestContext::with_registered_account();
+        set_env_with_success_promise_result(&mut ctx);
+
+        let account_id = ctx.account_id.to_string();
+        let unused = ctx.on_wrap_near_withdraw(account_id, (10 * YOCTO).into());
+        assert_eq!(unused, 0.into());
+        let account = ctx.registered_account(ctx.account_id);
+        assert_eq!(
+            account.stake_batch.unwrap().balance().amount(),
+            (10 * YOCTO).into()
+        );
+        assert_eq!(
+            ctx.stake_batch.unwrap().balance().amount(),
+            (10 * YOCTO).into()
+        );
+    }
+
+    #[test]
+    fn on_wrap_near_withdraw_with_stake_batch_pending_verification() {
+        let mut ctx = TestContext::with_registered_account();
+        let unverified_batch_id = domain::BatchId(100);
+        let mut account = ctx.registered_account(ctx.account_id);
+        account.stake_batch = Some(StakeBatch::new(unverified_batch_id, YOCTO.into()));
+        ctx.save_registered_account(&account);
+        ctx.unverified_stake_batches.push(unverified_batch_id);
+        let total_near = ctx.total_near.amount();
+        set_env_with_success_promise_result(&mut ctx);
+
+        let account_id = ctx.account_id.to_string();
+        let unused = ctx.on_wrap_near_withdraw(account_id, (10 * YOCTO).into());
+        // the NEAR was unwrapped, thus it is credited to the account NEAR balance
+        assert_eq!(unused, 0.into());
+        let account = ctx.registered_account(ctx.account_id);
+        assert_eq!(account.near.unwrap().amount(), (10 * YOCTO).into());
+        assert_eq!(
+            account.stake_batch.unwrap().balance().amount(),
+            YOCTO.into()
+        );
+        assert!(ctx.stake_batch.is_none());
+        assert_eq!(
+            ctx.total_near.amount(),
+            total_near + domain::YoctoNear(10 * YOCTO)
+        );
+    }
+
+    #[test]
+    fn on_wrap_near_withdraw_failure() {
+        let mut ctx = TestContext::with_registered_account();
+        set_env_with_failed_promise_result(&mut ctx);
+
+        let account_id = ctx.account_id.to_string();
+        let unused = ctx.on_wrap_near_withdraw(account_id, (10 * YOCTO).into());
+        assert_eq!(unused, (10 * YOCTO).into());
+        assert!(ctx.registered_account(ctx.account_id).stake_batch.is_none());
+        assert!(ctx.stake_batch.is_none());
+    }
+}
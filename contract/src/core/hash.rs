@@ -21,7 +21,11 @@ use std::convert::TryInto;
 pub struct Hash([u8; Hash::LENGTH]);
 
 impl Hash {
-    const LENGTH: usize = 32;
+    pub const LENGTH: usize = 32;
+
+    pub fn as_bytes(&self) -> &[u8; Hash::LENGTH] {
+        &self.0
+    }
 }
 
 impl From<[u8; Hash::LENGTH]> for Hash {
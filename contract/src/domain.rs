@@ -4,40 +4,152 @@
 //! closely mirrors the domain model.
 
 mod account;
+mod account_activity;
+mod account_preferences;
+mod aggregate_watch;
+mod batch_audit;
+mod batch_history;
 mod batch_id;
+mod batch_reservation;
+mod batch_totals_rebuild;
 mod block_height;
 mod block_time_height;
 mod block_timestamp;
+mod contract_pause;
 mod epoch_height;
+mod epoch_withdrawals;
+mod exit_position;
+mod feature_rollout;
+mod financials_snapshot;
 mod gas;
+mod gas_usage;
+mod idle_near_sweep;
+mod invoice;
+mod liquidity_provider_pool;
 mod lock;
+mod lock_history;
+mod lockup;
+mod migration;
+mod near_liquidity_stats;
+mod operator_proposal;
+mod pending_transfer;
+mod pool_preference;
+mod public_goods_donations;
+mod queued_withdrawal;
+mod rebate;
+mod receiver_authorizations;
+mod redeem_limit;
+mod redeem_split;
 mod redeem_stake_batch;
 mod redeem_stake_batch_receipt;
+mod referral_code;
+mod reward_fee_fraction;
 mod stake_batch;
 mod stake_batch_receipt;
+mod stake_supply_stats;
 mod stake_token_value;
+mod stake_token_value_checkpoint;
+mod state_version;
+mod storage_delta;
 mod storage_usage;
+mod swap_intent;
 mod timestamped_near_balance;
 mod timestamped_stake_balance;
+mod versioned;
+mod workflow_backoff;
+mod workflow_counters;
+mod workflow_state;
 mod yocto_near;
 mod yocto_stake;
 
 pub use crate::interface::contract_state::ContractState;
 pub use account::{Account, RegisteredAccount};
-pub use batch_id::BatchId;
+pub use account_activity::{
+    AccountActivity, AccountActivityKind, AccountActivityLog, MAX_ACCOUNT_ACTIVITY_LOG_LEN,
+};
+pub use account_preferences::AccountPreferences;
+pub use aggregate_watch::{
+    Aggregate, AggregateAlarm, AggregateSnapshot, AggregateWatch, AlarmWindow,
+};
+pub use batch_audit::BatchAudit;
+pub use batch_history::{
+    BatchHistoryEntry, BatchHistoryWindow, MAX_BATCH_HISTORY_CAPACITY, MAX_BATCH_HISTORY_PRUNE_LEN,
+};
+pub use batch_id::{BatchId, BatchKind, MAX_BATCH_ID_SEQUENCE};
+pub use batch_reservation::{BatchReservation, MAX_BATCH_RESERVATIONS};
+pub use batch_totals_rebuild::BatchTotalsRebuild;
 pub use block_height::BlockHeight;
 pub use block_time_height::BlockTimeHeight;
 pub use block_timestamp::BlockTimestamp;
+pub use contract_pause::{ContractPause, MAX_PAUSE_REASON_LEN};
 pub use epoch_height::EpochHeight;
+pub use epoch_withdrawals::EpochWithdrawals;
+pub use exit_position::ExitPosition;
+pub use feature_rollout::{
+    FeatureRollout, MAX_FEATURE_ALLOWLIST_LEN, MAX_FEATURE_NAME_LEN, MAX_FEATURE_ROLLOUTS,
+};
+pub use financials_snapshot::FinancialsSnapshot;
 pub use gas::{Gas, TGAS};
+pub use gas_usage::{GasUsage, STAKING_POOL_METHODS, WORKFLOW_CALLBACKS};
+pub use idle_near_sweep::{
+    idle_near_sweep_amount, IdleNearShare, IdleNearSweep, IDLE_NEAR_YIELD_INDEX_SCALE,
+};
+pub use invoice::{Invoice, InvoicePayment, MAX_INVOICE_MEMO_LEN};
+pub use liquidity_provider_pool::LiquidityProviderPool;
 pub use lock::{RedeemLock, StakeLock};
+pub use lock_history::{LockHistoryEntry, LockKind, LOCK_HISTORY_MAX_LEN};
+pub use lockup::Lockup;
+pub use migration::{MigrationCursor, MigrationTask};
+pub use near_liquidity_stats::{NearLiquidityInflow, NearLiquidityOutflow, NearLiquidityStats};
+pub use operator_proposal::{
+    OperatorAction, OperatorProposal, MAX_OPERATORS, MAX_PENDING_OPERATOR_PROPOSALS,
+};
+pub use pending_transfer::PendingTransfer;
+pub use pool_preference::{
+    PoolPreference, PoolPreferenceTallies, PoolPreferenceTally, MAX_POOL_PREFERENCES_PER_EPOCH,
+};
+pub use public_goods_donations::PublicGoodsDonations;
+pub use queued_withdrawal::QueuedWithdrawal;
+pub use rebate::{
+    ClosedRoundStakeEpochs, RebateAccumulator, RebateProgram, RebateRound, RebateTier,
+};
+pub use receiver_authorizations::{
+    ReceiverAuthorizations, MAX_AUTHORIZED_RECEIVERS, MAX_DELEGATE_KEYS,
+};
+pub use redeem_limit::{RedeemLimit, RedeemLimitIncrease};
+pub use redeem_split::{RedeemSplit, MAX_REDEEM_SPLITS, MAX_REDEEM_SPLIT_BATCHES};
 pub use redeem_stake_batch::RedeemStakeBatch;
 pub use redeem_stake_batch_receipt::RedeemStakeBatchReceipt;
+pub use referral_code::{
+    ReferralCode, MAX_REFERRAL_CODES, MAX_REFERRAL_CODE_LEN, MIN_REFERRAL_CODE_LEN,
+    REFERRAL_CODE_TTL_EPOCHS,
+};
+pub use reward_fee_fraction::RewardFeeFraction;
 pub use stake_batch::StakeBatch;
 pub use stake_batch_receipt::StakeBatchReceipt;
-pub use stake_token_value::StakeTokenValue;
+pub use stake_supply_stats::StakeSupplyStats;
+pub use stake_token_value::{
+    mul_div, Rounding, StakeTokenValue, NEAR_TO_STAKE_ROUNDING,
+    STAKE_TOKEN_VALUE_COMPENSATION_ROUNDING, STAKE_TO_NEAR_ROUNDING,
+};
+pub use stake_token_value_checkpoint::{
+    StakeTokenValueCheckpoint, MAX_STAKE_TOKEN_VALUE_HISTORY_LEN,
+};
+pub use state_version::{StateMigration, StateVersion, CURRENT_STATE_VERSION};
+pub use storage_delta::StorageDelta;
 pub use storage_usage::StorageUsage;
+pub use swap_intent::{SwapFill, SwapIntent};
 pub use timestamped_near_balance::TimestampedNearBalance;
 pub use timestamped_stake_balance::TimestampedStakeBalance;
+pub use versioned::{
+    AccountV1, LegacyAccount, LegacyRedeemStakeBatchReceipt, LegacyStakeBatchReceipt,
+    LegacyVersioned, Versioned, VersionedAccount, VersionedRedeemStakeBatchReceipt,
+    VersionedStakeBatchReceipt,
+};
+pub use workflow_backoff::{
+    Workflow, WorkflowBackoff, WorkflowBackoffs, WORKFLOW_ATTEMPT_TIMEOUT_BLOCKS,
+};
+pub use workflow_counters::WorkflowCounters;
+pub use workflow_state::WorkflowState;
 pub use yocto_near::YoctoNear;
 pub use yocto_stake::YoctoStake;
@@ -1,10 +1,15 @@
 use crate::core::Hash;
 use crate::domain::stake_batch::StakeBatch;
 use crate::domain::{
-    BatchId, RedeemStakeBatch, TimestampedNearBalance, TimestampedStakeBalance, YoctoNear,
-    YoctoStake,
+    AccountPreferences, BatchId, BatchKind, EpochHeight, IdleNearShare, PoolPreference,
+    RebateAccumulator, RedeemLimit, RedeemStakeBatch, TimestampedNearBalance,
+    TimestampedStakeBalance, YoctoNear, YoctoStake,
 };
-use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+use std::cell::Cell;
 use std::ops::{Deref, DerefMut};
 
 #[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
@@ -40,6 +45,33 @@ pub struct Account {
     pub redeem_stake_batch: Option<RedeemStakeBatch>,
     /// if the contract is locked, then deposit the NEAR funds in the next batch
     pub next_redeem_stake_batch: Option<RedeemStakeBatch>,
+
+    /// optional limit that the account sets on itself for how much STAKE can be redeemed per epoch
+    pub redeem_limit: Option<RedeemLimit>,
+
+    /// tracks the account STAKE balance over time for the fee rebate program
+    /// - the accumulator is updated each time the account is loaded - see [RebateAccumulator]
+    pub rebate_accumulator: Option<RebateAccumulator>,
+
+    /// the staking pool preference that the account last signaled
+    pub pool_preference: Option<PoolPreference>,
+
+    /// STAKE contract deployment that claimed redeem proceeds are forwarded to and staked with
+    /// - the account ID is hashed to keep the account record fixed size - the account ID is resolved
+    ///   from the redeem forwarding targets that are whitelisted by the operator
+    pub redeem_forwarding: Option<Hash>,
+
+    /// tracks the yield that the account NEAR balance earns while idle NEAR is swept into staking
+    /// - the yield is credited each time the account is loaded - see [IdleNearShare]
+    pub idle_near_share: Option<IdleNearShare>,
+
+    /// see [set_account_preferences](crate::interface::AccountManagement::set_account_preferences)
+    pub preferences: AccountPreferences,
+
+    /// the global mutation nonce at the time the account record was last mutated
+    /// - strictly increases each time the account record is mutated, which lets clients cheaply
+    ///   detect that cached account state is stale - see [account_nonce](crate::interface::AccountManagement::account_nonce)
+    pub nonce: u64,
 }
 
 impl Account {
@@ -52,6 +84,13 @@ impl Account {
             next_stake_batch: None,
             redeem_stake_batch: None,
             next_redeem_stake_batch: None,
+            redeem_limit: None,
+            rebate_accumulator: None,
+            pool_preference: None,
+            redeem_forwarding: None,
+            idle_near_share: None,
+            preferences: AccountPreferences::default(),
+            nonce: 0,
         }
     }
 
@@ -84,6 +123,31 @@ impl Account {
             next_stake_batch: Some(StakeBatch::new(0.into(), 0.into())),
             redeem_stake_batch: Some(RedeemStakeBatch::new(0.into(), 0.into())),
             next_redeem_stake_batch: Some(RedeemStakeBatch::new(0.into(), 0.into())),
+            redeem_limit: Some(RedeemLimit::template_to_measure_storage_usage()),
+            rebate_accumulator: Some(RebateAccumulator::template_to_measure_storage_usage()),
+            pool_preference: Some(PoolPreference::template_to_measure_storage_usage()),
+            redeem_forwarding: Some(Hash::default()),
+            idle_near_share: Some(IdleNearShare::default()),
+            preferences: AccountPreferences::default(),
+            nonce: 0,
+        }
+    }
+
+    /// applies any pending redeem limit increase that has taken effect
+    pub fn apply_redeem_limit_increase(&mut self, epoch_height: EpochHeight) {
+        self.redeem_limit = self
+            .redeem_limit
+            .and_then(|limit| limit.apply_pending_increase(epoch_height));
+    }
+
+    /// if the account has a redeem limit set, then the redeem amount is tracked against the limit
+    ///
+    /// ## Panics
+    /// if the account redeem limit for the epoch would be exceeded
+    pub fn record_redeem(&mut self, amount: YoctoStake, epoch_height: EpochHeight) {
+        self.apply_redeem_limit_increase(epoch_height);
+        if let Some(limit) = self.redeem_limit.as_mut() {
+            limit.record_redeem(amount, epoch_height);
         }
     }
 
@@ -92,6 +156,62 @@ impl Account {
         self.stake.map_or(false, |stake| stake.amount() >= amount)
     }
 
+    /// max STAKE that the account can redeem within the epoch, i.e., the STAKE balance capped by the
+    /// account redeem limit
+    /// - pending redeem limit increases must be applied first
+    pub fn redeemable(&self, epoch_height: EpochHeight) -> YoctoStake {
+        let balance = self
+            .stake
+            .map_or_else(YoctoStake::default, |stake| stake.amount());
+        match self.redeem_limit.as_ref() {
+            Some(limit) => balance.min(limit.available(epoch_height)),
+            None => balance,
+        }
+    }
+
+    /// IDs of the batches that the account currently holds funds in
+    pub fn batch_ids(&self) -> Vec<BatchId> {
+        let mut batch_ids = Vec::with_capacity(4);
+        batch_ids.extend(self.stake_batch.map(|batch| batch.id()));
+        batch_ids.extend(self.next_stake_batch.map(|batch| batch.id()));
+        batch_ids.extend(self.redeem_stake_batch.map(|batch| batch.id()));
+        batch_ids.extend(self.next_redeem_stake_batch.map(|batch| batch.id()));
+        batch_ids
+    }
+
+    /// returns the amount that the account contributed to the specified batch
+    /// - yoctoNEAR for stake batches and yoctoSTAKE for redeem stake batches
+    pub fn batch_contribution(&self, batch_id: BatchId) -> u128 {
+        match batch_id.kind() {
+            BatchKind::Stake => self
+                .stake_batch(batch_id)
+                .map_or(0, |batch| batch.balance().amount().value()),
+            BatchKind::Redeem => self
+                .redeem_stake_batch
+                .iter()
+                .chain(self.next_redeem_stake_batch.iter())
+                .find(|batch| batch.id() == batch_id)
+                .map_or(0, |batch| batch.balance().amount().value()),
+        }
+    }
+
+    /// returns true if the account has zero balances, no batches, and no settings that would be
+    /// lost if the account record was compressed into a hibernated tombstone
+    pub fn can_hibernate(&self) -> bool {
+        !self.has_funds()
+            && self.batch_ids().is_empty()
+            && self.redeem_limit.is_none()
+            && self.pool_preference.is_none()
+            && self.redeem_forwarding.is_none()
+            && self
+                .rebate_accumulator
+                .map_or(true, |accumulator| accumulator.is_empty())
+            && self
+                .idle_near_share
+                .map_or(true, |share| !share.opted_out())
+            && self.preferences == AccountPreferences::default()
+    }
+
     pub fn has_funds(&self) -> bool {
         self.near.map_or(false, |balance| balance > 0)
             || self.stake.map_or(false, |balance| balance > 0)
@@ -147,6 +267,12 @@ impl Account {
 pub struct RegisteredAccount {
     pub account: Account,
     pub id: Hash,
+    /// used as the owner ID for NEP-141 events
+    pub account_id: AccountId,
+    /// idle NEAR yield that was credited to the account NEAR balance when the account was loaded
+    /// - the yield is moved out of the idle NEAR yield pool when the account is saved
+    /// - [Cell] is used because the account may be saved more than once
+    pub idle_near_yield: Cell<YoctoNear>,
 }
 
 impl Deref for RegisteredAccount {
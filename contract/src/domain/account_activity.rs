@@ -0,0 +1,131 @@
+use crate::domain::{BatchId, BlockTimeHeight};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+};
+
+/// max number of activity entries that are retained per account - the account storage fee covers a
+/// fully allocated activity log
+pub const MAX_ACCOUNT_ACTIVITY_LOG_LEN: u8 = 10;
+
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AccountActivityKind {
+    /// NEAR was deposited into a stake batch
+    Deposit,
+    /// STAKE was claimed from a stake batch receipt
+    Stake,
+    /// STAKE was submitted to a redeem stake batch
+    Redeem,
+    /// NEAR was claimed from a redeem stake batch receipt
+    Claim,
+    /// NEAR was withdrawn from the account
+    Withdraw,
+    /// STAKE was transferred to another account
+    TransferOut,
+    /// STAKE was received from another account
+    TransferIn,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct AccountActivity {
+    kind: AccountActivityKind,
+    /// yoctoNEAR or yoctoSTAKE depending on the activity kind
+    amount: u128,
+    batch_id: Option<BatchId>,
+    block_time_height: BlockTimeHeight,
+}
+
+impl AccountActivity {
+    pub fn new(kind: AccountActivityKind, amount: u128, batch_id: Option<BatchId>) -> Self {
+        Self {
+            kind,
+            amount,
+            batch_id,
+            block_time_height: BlockTimeHeight::from_env(),
+        }
+    }
+
+    pub fn kind(&self) -> AccountActivityKind {
+        self.kind
+    }
+
+    pub fn amount(&self) -> u128 {
+        self.amount
+    }
+
+    pub fn batch_id(&self) -> Option<BatchId> {
+        self.batch_id
+    }
+
+    pub fn block_time_height(&self) -> BlockTimeHeight {
+        self.block_time_height
+    }
+}
+
+/// Retains the account's most recent activity, ordered oldest first - once the log is full, the
+/// oldest entry is dropped
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct AccountActivityLog(Vec<AccountActivity>);
+
+impl AccountActivityLog {
+    /// fully allocated log that is used to measure the storage usage
+    pub fn template_to_measure_storage_usage() -> Self {
+        let entry = AccountActivity::new(AccountActivityKind::Deposit, 0, Some(BatchId(0)));
+        Self(vec![entry; MAX_ACCOUNT_ACTIVITY_LOG_LEN as usize])
+    }
+
+    /// appends the activity and drops the oldest entries that exceed the specified max length
+    pub fn record(&mut self, activity: AccountActivity, max_len: u8) {
+        self.0.push(activity);
+        let max_len = (max_len.min(MAX_ACCOUNT_ACTIVITY_LOG_LEN) as usize).max(1);
+        if self.0.len() > max_len {
+            let overflow = self.0.len() - max_len;
+            self.0.drain(..overflow);
+        }
+    }
+
+    pub fn entries(&self) -> &[AccountActivity] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn record_drops_oldest_entries_once_full() {
+        testing_env!(new_context("bob.near"));
+        let mut log = AccountActivityLog::default();
+        for amount in 0..12 {
+            log.record(
+                AccountActivity::new(AccountActivityKind::Deposit, amount, None),
+                MAX_ACCOUNT_ACTIVITY_LOG_LEN,
+            );
+        }
+        assert_eq!(log.len(), MAX_ACCOUNT_ACTIVITY_LOG_LEN as usize);
+        assert_eq!(log.entries()[0].amount(), 2);
+        assert_eq!(log.entries()[9].amount(), 11);
+
+        // when the configured length is reduced, the log is trimmed on the next record
+        log.record(
+            AccountActivity::new(AccountActivityKind::Withdraw, 12, None),
+            3,
+        );
+        let amounts: Vec<u128> = log.entries().iter().map(|entry| entry.amount()).collect();
+        assert_eq!(amounts, vec![10, 11, 12]);
+    }
+}
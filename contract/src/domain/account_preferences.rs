@@ -0,0 +1,11 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// account level preferences that are applied by the contract on the account's behalf
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct AccountPreferences {
+    /// claim the account's receipts whenever the account is touched, including when the account
+    /// receives STAKE from other accounts
+    pub auto_claim_receipts: bool,
+    /// move claimed redeemed NEAR into the account's stake batch, i.e., compound the redeemed NEAR
+    pub auto_compound: bool,
+}
@@ -0,0 +1,285 @@
+use crate::domain::{mul_div, BlockTimeHeight, EpochHeight, Rounding};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+};
+
+/// critical contract aggregates that are watched for abrupt changes
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Aggregate {
+    StakeSupply,
+    TotalNear,
+    NearLiquidityPool,
+}
+
+/// the window over which an aggregate change is measured
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AlarmWindow {
+    /// the change made by a single contract call
+    Transaction,
+    /// the change since the first watched contract call within the current epoch
+    Epoch,
+}
+
+/// point in time values for the watched aggregates
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub struct AggregateSnapshot {
+    pub stake_supply: u128,
+    pub total_near: u128,
+    pub near_liquidity_pool: u128,
+}
+
+impl AggregateSnapshot {
+    pub fn values(&self) -> [(Aggregate, u128); 3] {
+        [
+            (Aggregate::StakeSupply, self.stake_supply),
+            (Aggregate::TotalNear, self.total_near),
+            (Aggregate::NearLiquidityPool, self.near_liquidity_pool),
+        ]
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct AggregateAlarm {
+    aggregate: Aggregate,
+    window: AlarmWindow,
+    before: u128,
+    after: u128,
+    threshold_percentage: u8,
+    block_time_height: BlockTimeHeight,
+}
+
+impl AggregateAlarm {
+    pub fn aggregate(&self) -> Aggregate {
+        self.aggregate
+    }
+
+    pub fn window(&self) -> AlarmWindow {
+        self.window
+    }
+
+    pub fn before(&self) -> u128 {
+        self.before
+    }
+
+    pub fn after(&self) -> u128 {
+        self.after
+    }
+
+    pub fn threshold_percentage(&self) -> u8 {
+        self.threshold_percentage
+    }
+
+    pub fn block_time_height(&self) -> BlockTimeHeight {
+        self.block_time_height
+    }
+}
+
+/// Watches the critical aggregates for changes that exceed the configured thresholds.
+///
+/// The epoch baseline is the snapshot taken before the first watched contract call within the
+/// epoch.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub struct AggregateWatch {
+    epoch_height: Option<EpochHeight>,
+    epoch_baseline: AggregateSnapshot,
+    last_alarm: Option<AggregateAlarm>,
+    alarms_count: u64,
+}
+
+impl AggregateWatch {
+    pub fn epoch_baseline(&self) -> AggregateSnapshot {
+        self.epoch_baseline
+    }
+
+    pub fn last_alarm(&self) -> Option<AggregateAlarm> {
+        self.last_alarm
+    }
+
+    /// number of alarms that have been raised
+    pub fn alarms_count(&self) -> u64 {
+        self.alarms_count
+    }
+
+    /// compares the aggregates after a contract call against the aggregates before the call and
+    /// against the epoch baseline
+    /// - a threshold of zero disables the window
+    /// - aggregates that change from zero are skipped because the relative change is undefined
+    ///
+    /// Returns the alarms that were raised.
+    pub fn check(
+        &mut self,
+        before: AggregateSnapshot,
+        after: AggregateSnapshot,
+        txn_threshold_percentage: u8,
+        epoch_threshold_percentage: u8,
+        block_time_height: BlockTimeHeight,
+    ) -> Vec<AggregateAlarm> {
+        if self.epoch_height != Some(block_time_height.epoch_height()) {
+            self.epoch_height = Some(block_time_height.epoch_height());
+            self.epoch_baseline = before;
+        }
+
+        let mut alarms = vec![];
+        let windows = [
+            (AlarmWindow::Transaction, before, txn_threshold_percentage),
+            (
+                AlarmWindow::Epoch,
+                self.epoch_baseline,
+                epoch_threshold_percentage,
+            ),
+        ];
+        for (window, baseline, threshold_percentage) in windows.iter() {
+            if *threshold_percentage == 0 {
+                continue;
+            }
+            let current = after.values();
+            for ((aggregate, from), (_, to)) in baseline.values().iter().zip(current.iter()) {
+                if change_exceeds_threshold(*from, *to, *threshold_percentage) {
+                    alarms.push(AggregateAlarm {
+                        aggregate: *aggregate,
+                        window: *window,
+                        before: *from,
+                        after: *to,
+                        threshold_percentage: *threshold_percentage,
+                        block_time_height,
+                    });
+                }
+            }
+        }
+
+        if let Some(alarm) = alarms.last() {
+            self.last_alarm = Some(*alarm);
+            self.alarms_count += alarms.len() as u64;
+        }
+        alarms
+    }
+}
+
+fn change_exceeds_threshold(before: u128, after: u128, threshold_percentage: u8) -> bool {
+    if before == 0 {
+        return false;
+    }
+    let change = if after > before {
+        after - before
+    } else {
+        before - after
+    };
+    change > mul_div(before, threshold_percentage as u128, 100, Rounding::Down)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    fn block_time_height(epoch_height: u64) -> BlockTimeHeight {
+        let mut context = new_context("bob.near");
+        context.epoch_height = epoch_height;
+        testing_env!(context);
+        BlockTimeHeight::from_env()
+    }
+
+    fn snapshot(
+        stake_supply: u128,
+        total_near: u128,
+        near_liquidity_pool: u128,
+    ) -> AggregateSnapshot {
+        AggregateSnapshot {
+            stake_supply,
+            total_near,
+            near_liquidity_pool,
+        }
+    }
+
+    #[test]
+    fn change_exceeds_threshold_boundaries() {
+        assert!(!change_exceeds_threshold(100, 110, 10));
+        assert!(change_exceeds_threshold(100, 111, 10));
+        assert!(!change_exceeds_threshold(100, 90, 10));
+        assert!(change_exceeds_threshold(100, 89, 10));
+        assert!(!change_exceeds_threshold(0, 1_000, 10));
+        assert!(!change_exceeds_threshold(u128::MAX, u128::MAX - 1, 1));
+        assert!(change_exceeds_threshold(u128::MAX, 0, 99));
+    }
+
+    #[test]
+    fn transaction_window_alarm() {
+        let mut watch = AggregateWatch::default();
+        let alarms = watch.check(
+            snapshot(100, 100, 100),
+            snapshot(100, 150, 100),
+            10,
+            0,
+            block_time_height(1),
+        );
+        assert_eq!(alarms.len(), 1);
+        assert_eq!(alarms[0].aggregate(), Aggregate::TotalNear);
+        assert_eq!(alarms[0].window(), AlarmWindow::Transaction);
+        assert_eq!(alarms[0].before(), 100);
+        assert_eq!(alarms[0].after(), 150);
+        assert_eq!(watch.last_alarm(), Some(alarms[0]));
+        assert_eq!(watch.alarms_count(), 1);
+    }
+
+    #[test]
+    fn epoch_window_accumulates_changes_and_resets_each_epoch() {
+        let mut watch = AggregateWatch::default();
+        // each change is within the transaction threshold, but the changes accumulate within the epoch
+        assert!(watch
+            .check(
+                snapshot(100, 100, 100),
+                snapshot(108, 100, 100),
+                10,
+                15,
+                block_time_height(1)
+            )
+            .is_empty());
+        let alarms = watch.check(
+            snapshot(108, 100, 100),
+            snapshot(116, 100, 100),
+            10,
+            15,
+            block_time_height(1),
+        );
+        assert_eq!(alarms.len(), 1);
+        assert_eq!(alarms[0].aggregate(), Aggregate::StakeSupply);
+        assert_eq!(alarms[0].window(), AlarmWindow::Epoch);
+        assert_eq!(alarms[0].before(), 100);
+
+        // the baseline is reset in the next epoch
+        assert!(watch
+            .check(
+                snapshot(116, 100, 100),
+                snapshot(124, 100, 100),
+                10,
+                15,
+                block_time_height(2)
+            )
+            .is_empty());
+        assert_eq!(watch.epoch_baseline(), snapshot(116, 100, 100));
+        assert_eq!(watch.alarms_count(), 1);
+    }
+
+    #[test]
+    fn zero_thresholds_disable_alarms() {
+        let mut watch = AggregateWatch::default();
+        assert!(watch
+            .check(
+                snapshot(100, 100, 100),
+                snapshot(1, 1, 1),
+                0,
+                0,
+                block_time_height(1)
+            )
+            .is_empty());
+        assert!(watch.last_alarm().is_none());
+    }
+}
@@ -0,0 +1,92 @@
+use crate::domain::{StakeTokenValue, YoctoNear};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Audit record for an executed batch, which captures the raw staking pool account balances and
+/// the inputs and outputs of the STAKE token value computation. This enables the liquidity
+/// compensation math to be independently verified for any historical batch:
+///
+/// ```text
+/// total_staked_near_balance = staked_balance + unstaked_balance                      (no pending withdrawal)
+/// total_staked_near_balance = staked_balance + unstaked_balance
+///                             - pending_withdrawal + near_liquidity                   (pending withdrawal)
+/// compensation = max(0, prior STAKE value * STAKE supply / YOCTO - total_staked_near_balance)
+/// ```
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct BatchAudit {
+    /// staked balance returned by the staking pool
+    staked_balance: YoctoNear,
+    /// unstaked balance returned by the staking pool
+    unstaked_balance: YoctoNear,
+    /// NEAR value of the pending withdrawal at the time the batch was run
+    pending_withdrawal: YoctoNear,
+    /// NEAR liquidity pool balance at the time the batch was run
+    near_liquidity: YoctoNear,
+    /// staked NEAR balance that was derived from the raw staking pool balances
+    total_staked_near_balance: YoctoNear,
+    /// STAKE token value before the batch was run
+    prior_stake_token_value: StakeTokenValue,
+    /// NEAR that was added to the liquidity pool to prevent the STAKE token value from ticking down
+    compensation: YoctoNear,
+    /// STAKE token value after the batch was run
+    stake_token_value: StakeTokenValue,
+}
+
+impl BatchAudit {
+    pub fn new(
+        staked_balance: YoctoNear,
+        unstaked_balance: YoctoNear,
+        pending_withdrawal: YoctoNear,
+        near_liquidity: YoctoNear,
+        total_staked_near_balance: YoctoNear,
+        prior_stake_token_value: StakeTokenValue,
+    ) -> Self {
+        Self {
+            staked_balance,
+            unstaked_balance,
+            pending_withdrawal,
+            near_liquidity,
+            total_staked_near_balance,
+            prior_stake_token_value,
+            compensation: 0.into(),
+            stake_token_value: prior_stake_token_value,
+        }
+    }
+
+    pub fn staked_balance(&self) -> YoctoNear {
+        self.staked_balance
+    }
+
+    pub fn unstaked_balance(&self) -> YoctoNear {
+        self.unstaked_balance
+    }
+
+    pub fn pending_withdrawal(&self) -> YoctoNear {
+        self.pending_withdrawal
+    }
+
+    pub fn near_liquidity(&self) -> YoctoNear {
+        self.near_liquidity
+    }
+
+    pub fn total_staked_near_balance(&self) -> YoctoNear {
+        self.total_staked_near_balance
+    }
+
+    pub fn prior_stake_token_value(&self) -> StakeTokenValue {
+        self.prior_stake_token_value
+    }
+
+    pub fn compensation(&self) -> YoctoNear {
+        self.compensation
+    }
+
+    pub fn stake_token_value(&self) -> StakeTokenValue {
+        self.stake_token_value
+    }
+
+    /// records the outcome of the STAKE token value computation
+    pub fn complete(&mut self, compensation: YoctoNear, stake_token_value: StakeTokenValue) {
+        self.compensation = compensation;
+        self.stake_token_value = stake_token_value;
+    }
+}
@@ -0,0 +1,147 @@
+use crate::domain::{
+    BatchId, BlockTimeHeight, BlockTimestamp, StakeTokenValue, YoctoNear, YoctoStake,
+};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// max number of batch history entries that can be retained per batch kind
+pub const MAX_BATCH_HISTORY_CAPACITY: u32 = 1000;
+
+/// max number of entries that are pruned per recorded entry - when the capacity is reduced, the
+/// entries that fall outside the history window are pruned incrementally to keep the gas bounded
+pub const MAX_BATCH_HISTORY_PRUNE_LEN: u64 = 10;
+
+/// Summary record for a stake or redeem batch that was run. Batch receipts are deleted once they
+/// are fully claimed, thus the batch history is retained separately.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct BatchHistoryEntry {
+    batch_id: BatchId,
+    /// NEAR that was staked, or the NEAR value of the STAKE that was redeemed
+    near_amount: YoctoNear,
+    /// STAKE that was minted, or the STAKE that was redeemed
+    stake_amount: YoctoStake,
+    /// STAKE token value that was used to run the batch
+    stake_token_value: StakeTokenValue,
+    /// when funds were last added to the batch
+    last_deposit_block_timestamp: BlockTimestamp,
+    /// when the batch was run
+    run: BlockTimeHeight,
+}
+
+impl BatchHistoryEntry {
+    pub fn new(
+        batch_id: BatchId,
+        near_amount: YoctoNear,
+        stake_amount: YoctoStake,
+        stake_token_value: StakeTokenValue,
+        last_deposit_block_timestamp: BlockTimestamp,
+    ) -> Self {
+        Self {
+            batch_id,
+            near_amount,
+            stake_amount,
+            stake_token_value,
+            last_deposit_block_timestamp,
+            run: BlockTimeHeight::from_env(),
+        }
+    }
+
+    pub fn batch_id(&self) -> BatchId {
+        self.batch_id
+    }
+
+    pub fn near_amount(&self) -> YoctoNear {
+        self.near_amount
+    }
+
+    pub fn stake_amount(&self) -> YoctoStake {
+        self.stake_amount
+    }
+
+    pub fn stake_token_value(&self) -> StakeTokenValue {
+        self.stake_token_value
+    }
+
+    pub fn last_deposit_block_timestamp(&self) -> BlockTimestamp {
+        self.last_deposit_block_timestamp
+    }
+
+    pub fn run(&self) -> BlockTimeHeight {
+        self.run
+    }
+}
+
+/// Tracks the range of history entry indexes that are retained - entries are indexed sequentially
+/// in the order they were recorded
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct BatchHistoryWindow {
+    /// index of the oldest retained entry
+    first: u64,
+    /// total number of entries that have been recorded, i.e., the next entry index
+    len: u64,
+}
+
+impl BatchHistoryWindow {
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// index of the oldest entry that is within the history window for the specified capacity
+    pub fn first(&self, capacity: u32) -> u64 {
+        self.first.max(self.len.saturating_sub(capacity as u64))
+    }
+
+    /// advances the window for a new entry
+    ///
+    /// Returns the index for the new entry along with the indexes of the entries that fell outside
+    /// the window and should be pruned - at most [MAX_BATCH_HISTORY_PRUNE_LEN] entries are pruned
+    pub fn push(&mut self, capacity: u32) -> (u64, std::ops::Range<u64>) {
+        let index = self.len;
+        self.len += 1;
+        let first = self
+            .len
+            .saturating_sub(capacity as u64)
+            .min(self.first + MAX_BATCH_HISTORY_PRUNE_LEN);
+        let pruned = self.first..first.max(self.first);
+        self.first = pruned.end;
+        (index, pruned)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn window_prunes_entries_that_fall_outside_capacity() {
+        let mut window = BatchHistoryWindow::default();
+        for i in 0..3 {
+            let (index, pruned) = window.push(3);
+            assert_eq!(index, i);
+            assert!(pruned.is_empty());
+        }
+        assert_eq!(window.push(3), (3, 0..1));
+        assert_eq!(window.first(3), 1);
+
+        // when the capacity is reduced, the older entries are no longer within the window and are
+        // pruned on the next push
+        assert_eq!(window.first(1), 3);
+        assert_eq!(window.push(1), (4, 1..4));
+        assert_eq!(window.first(1), 4);
+        assert_eq!(window.len(), 5);
+    }
+
+    #[test]
+    fn pruning_is_bounded() {
+        let mut window = BatchHistoryWindow::default();
+        for _ in 0..100 {
+            window.push(MAX_BATCH_HISTORY_CAPACITY);
+        }
+        let (_, pruned) = window.push(0);
+        assert_eq!(pruned, 0..MAX_BATCH_HISTORY_PRUNE_LEN);
+        assert_eq!(window.first(0), window.len());
+    }
+}
@@ -1,8 +1,23 @@
 use crate::domain::{RedeemStakeBatch, StakeBatch, YoctoNear, YoctoStake};
+use crate::errors::illegal_state::BATCH_ID_SEQUENCE_EXHAUSTED;
 use crate::interface;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use std::ops::{Deref, DerefMut};
 
+/// the most significant bit encodes the [BatchKind] - it is set for [RedeemStakeBatch] IDs
+const REDEEM_BATCH_ID_FLAG: u128 = 1 << 127;
+
+/// the batch ID sequence must fit within the bits that are not used to encode the [BatchKind]
+pub const MAX_BATCH_ID_SEQUENCE: u128 = REDEEM_BATCH_ID_FLAG - 1;
+
+/// Stake and redeem batches share the same ID sequence, but the batch kind is encoded into the ID.
+/// This guarantees that the 2 ID spaces can never be confused in logs and receipts.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BatchKind {
+    Stake,
+    Redeem,
+}
+
 #[derive(
     BorshSerialize,
     BorshDeserialize,
@@ -29,6 +44,41 @@ impl BatchId {
         self.0
     }
 
+    pub fn kind(&self) -> BatchKind {
+        if self.0 & REDEEM_BATCH_ID_FLAG == 0 {
+            BatchKind::Stake
+        } else {
+            BatchKind::Redeem
+        }
+    }
+
+    /// the batch ID sequence number with the [BatchKind] stripped off
+    pub fn sequence(&self) -> u128 {
+        self.0 & MAX_BATCH_ID_SEQUENCE
+    }
+
+    /// returns the next ID in the sequence
+    ///
+    /// ## Panics
+    /// if the sequence is exhausted - the sequence never wraps around because batch IDs are used to
+    /// look up batch receipts, i.e., reusing a batch ID could clobber an unclaimed receipt
+    pub fn next(&self) -> Self {
+        let sequence = self.sequence();
+        assert!(
+            sequence < MAX_BATCH_ID_SEQUENCE,
+            BATCH_ID_SEQUENCE_EXHAUSTED
+        );
+        Self(sequence + 1)
+    }
+
+    /// encodes the [BatchKind] into the batch ID
+    pub fn with_kind(&self, kind: BatchKind) -> Self {
+        match kind {
+            BatchKind::Stake => Self(self.sequence()),
+            BatchKind::Redeem => Self(self.sequence() | REDEEM_BATCH_ID_FLAG),
+        }
+    }
+
     pub fn new_stake_batch(&self) -> StakeBatch {
         StakeBatch::new(*self, YoctoNear(0))
     }
@@ -66,7 +116,7 @@ impl DerefMut for BatchId {
 
 #[cfg(test)]
 mod test {
-    use crate::domain::BatchId;
+    use super::*;
 
     #[test]
     fn batch_id_deref() {
@@ -87,4 +137,29 @@ mod test {
         *batch_id += 1;
         assert_eq!(*batch_id, 1);
     }
+
+    #[test]
+    fn batch_id_next() {
+        let batch_id = BatchId::default().next();
+        assert_eq!(batch_id.value(), 1);
+        assert_eq!(batch_id.kind(), BatchKind::Stake);
+
+        // the sequence is shared, but the kind is encoded in the ID
+        let stake_batch_id = batch_id.with_kind(BatchKind::Stake);
+        let redeem_batch_id = batch_id.with_kind(BatchKind::Redeem);
+        assert_eq!(stake_batch_id.kind(), BatchKind::Stake);
+        assert_eq!(redeem_batch_id.kind(), BatchKind::Redeem);
+        assert_ne!(stake_batch_id, redeem_batch_id);
+        assert_eq!(stake_batch_id.sequence(), redeem_batch_id.sequence());
+        assert_eq!(redeem_batch_id.with_kind(BatchKind::Stake), stake_batch_id);
+
+        // the next ID is derived from the sequence, independent of the kind
+        assert_eq!(redeem_batch_id.next(), BatchId(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "ILLEGAL STATE : batch ID sequence is exhausted")]
+    fn batch_id_next_does_not_wrap_around() {
+        BatchId(MAX_BATCH_ID_SEQUENCE).next();
+    }
 }
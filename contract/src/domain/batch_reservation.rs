@@ -0,0 +1,75 @@
+use crate::domain::{BlockHeight, BlockTimeHeight, YoctoNear};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// max number of batch reservations that can be queued at any point in time
+/// - reservations are folded into the stake batch when the batch is run, which bounds the gas that
+///   is used by the batch run
+pub const MAX_BATCH_RESERVATIONS: usize = 10;
+
+/// NEAR that is escrowed by the account to guarantee inclusion in the next stake batch run.
+///
+/// Deposits that are made while the stake batch is locked are pushed into the next stake batch,
+/// i.e., they miss the batch run. Reserved funds are held outside of the stake batches and are
+/// folded into the stake batch when the next batch run starts, thus they cannot be pushed out by a
+/// lock that was acquired in between.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct BatchReservation {
+    account_id: AccountId,
+    amount: YoctoNear,
+    /// escrowed fee that is refunded when the reservation is used - the fee is forfeited if the
+    /// reservation expires or is cancelled
+    fee: YoctoNear,
+    created: BlockTimeHeight,
+    /// the reservation can no longer be used once the block height reaches the expiry
+    expires_at: BlockHeight,
+}
+
+impl BatchReservation {
+    pub fn new(
+        account_id: AccountId,
+        amount: YoctoNear,
+        fee: YoctoNear,
+        created: BlockTimeHeight,
+        expires_at: BlockHeight,
+    ) -> Self {
+        Self {
+            account_id,
+            amount,
+            fee,
+            created,
+            expires_at,
+        }
+    }
+
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn amount(&self) -> YoctoNear {
+        self.amount
+    }
+
+    pub fn fee(&self) -> YoctoNear {
+        self.fee
+    }
+
+    pub fn created(&self) -> BlockTimeHeight {
+        self.created
+    }
+
+    pub fn expires_at(&self) -> BlockHeight {
+        self.expires_at
+    }
+
+    pub fn is_expired(&self, block_height: BlockHeight) -> bool {
+        block_height >= self.expires_at
+    }
+
+    /// total NEAR that is escrowed by the reservation
+    pub fn escrow(&self) -> YoctoNear {
+        self.amount + self.fee
+    }
+}
@@ -0,0 +1,66 @@
+use crate::domain::{BatchId, BlockHeight};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Tracks a chunked rebuild of a contract level batch balance from the per account contributions.
+///
+/// The rebuild walks the batch contributor index in chunks. The recorded batch balance is
+/// snapshotted when the rebuild starts - if the batch balance changes while the rebuild is in
+/// progress, then the partial total is no longer valid and the rebuild must be restarted.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct BatchTotalsRebuild {
+    batch_id: BatchId,
+    /// index of the next batch contributor to process
+    next_index: u64,
+    /// batch balance re-derived from the contributors that have been processed so far
+    /// - yoctoNEAR for stake batches and yoctoSTAKE for redeem stake batches
+    rebuilt_balance: u128,
+    /// contract level batch balance when the rebuild was started
+    recorded_balance: u128,
+    /// block height when the contract level batch balance last changed
+    recorded_block_height: BlockHeight,
+}
+
+impl BatchTotalsRebuild {
+    pub fn new(
+        batch_id: BatchId,
+        rebuilt_balance: u128,
+        recorded_balance: u128,
+        recorded_block_height: BlockHeight,
+    ) -> Self {
+        Self {
+            batch_id,
+            next_index: 0,
+            rebuilt_balance,
+            recorded_balance,
+            recorded_block_height,
+        }
+    }
+
+    pub fn batch_id(&self) -> BatchId {
+        self.batch_id
+    }
+
+    pub fn next_index(&self) -> u64 {
+        self.next_index
+    }
+
+    pub fn rebuilt_balance(&self) -> u128 {
+        self.rebuilt_balance
+    }
+
+    pub fn recorded_balance(&self) -> u128 {
+        self.recorded_balance
+    }
+
+    /// returns true if the contract level batch balance has not changed since the rebuild started
+    pub fn matches(&self, recorded_balance: u128, recorded_block_height: BlockHeight) -> bool {
+        self.recorded_balance == recorded_balance
+            && self.recorded_block_height == recorded_block_height
+    }
+
+    /// adds the contribution for the batch contributor at the next index
+    pub fn add_contribution(&mut self, amount: u128) {
+        self.rebuilt_balance += amount;
+        self.next_index += 1;
+    }
+}
@@ -1,3 +1,4 @@
+use crate::interface;
 use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
 };
@@ -29,6 +30,12 @@ impl BlockTimestamp {
     }
 }
 
+impl From<interface::BlockTimestamp> for BlockTimestamp {
+    fn from(value: interface::BlockTimestamp) -> Self {
+        Self(value.0 .0)
+    }
+}
+
 impl From<BlockTimestamp> for u64 {
     fn from(value: BlockTimestamp) -> Self {
         value.0
@@ -0,0 +1,30 @@
+use crate::domain::BlockTimeHeight;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// max length in bytes for the reason that is recorded when the contract is paused
+pub const MAX_PAUSE_REASON_LEN: usize = 256;
+
+/// Recorded while the contract is paused - see [pause](crate::interface::Operator::pause)
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ContractPause {
+    reason: String,
+    /// when the contract was paused
+    paused: BlockTimeHeight,
+}
+
+impl ContractPause {
+    pub fn new(reason: String) -> Self {
+        Self {
+            reason,
+            paused: BlockTimeHeight::from_env(),
+        }
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    pub fn paused(&self) -> BlockTimeHeight {
+        self.paused
+    }
+}
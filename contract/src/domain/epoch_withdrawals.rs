@@ -0,0 +1,76 @@
+use crate::domain::{EpochHeight, YoctoNear};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Tracks how much NEAR has left the contract within the current epoch via account withdrawals and
+/// NEAR transfers. It is used to enforce the contract level
+/// [max_withdrawal_per_epoch](crate::config::Config::max_withdrawal_per_epoch) limit.
+///
+/// The counter is reset lazily, i.e., the first time NEAR is withdrawn within a new epoch.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct EpochWithdrawals {
+    /// the epoch in which [withdrawn](EpochWithdrawals::withdrawn) was tracked
+    epoch_height: EpochHeight,
+    withdrawn: YoctoNear,
+}
+
+impl EpochWithdrawals {
+    /// returns how much NEAR was withdrawn within the specified epoch
+    pub fn withdrawn(&self, epoch_height: EpochHeight) -> YoctoNear {
+        if self.epoch_height == epoch_height {
+            self.withdrawn
+        } else {
+            0.into()
+        }
+    }
+
+    /// returns how much more NEAR can be withdrawn within the specified epoch
+    /// - if `max_withdrawal_per_epoch` is zero, then there is no limit
+    pub fn available(
+        &self,
+        max_withdrawal_per_epoch: YoctoNear,
+        epoch_height: EpochHeight,
+    ) -> Option<YoctoNear> {
+        if max_withdrawal_per_epoch.value() == 0 {
+            return None;
+        }
+        Some(
+            max_withdrawal_per_epoch
+                .value()
+                .saturating_sub(self.withdrawn(epoch_height).value())
+                .into(),
+        )
+    }
+
+    pub fn record_withdrawal(&mut self, amount: YoctoNear, epoch_height: EpochHeight) {
+        self.withdrawn = self.withdrawn(epoch_height) + amount;
+        self.epoch_height = epoch_height;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn record_withdrawal_resets_per_epoch() {
+        let mut withdrawals = EpochWithdrawals::default();
+        assert!(withdrawals.available(0.into(), 10.into()).is_none());
+
+        withdrawals.record_withdrawal(60.into(), 10.into());
+        assert_eq!(withdrawals.withdrawn(10.into()), 60.into());
+        assert_eq!(
+            withdrawals.available(100.into(), 10.into()),
+            Some(40.into())
+        );
+        assert_eq!(
+            withdrawals.available(100.into(), 11.into()),
+            Some(100.into())
+        );
+        // the limit may have been lowered after funds were withdrawn
+        assert_eq!(withdrawals.available(50.into(), 10.into()), Some(0.into()));
+
+        withdrawals.record_withdrawal(30.into(), 11.into());
+        assert_eq!(withdrawals.withdrawn(11.into()), 30.into());
+        assert_eq!(withdrawals.withdrawn(10.into()), 0.into());
+    }
+}
@@ -0,0 +1,63 @@
+use crate::domain::{BatchId, BlockTimeHeight, YoctoNear, YoctoStake};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// Registered when an account exits its position - once the account's pending redeem stake batches
+/// have completed, the account's available NEAR balance is transferred to the beneficiary.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ExitPosition {
+    beneficiary: AccountId,
+    /// STAKE that was redeemed when the exit was requested
+    redeemed: YoctoStake,
+    /// redeem stake batch that the STAKE was redeemed into
+    /// - None if the account had no STAKE balance, i.e., the exit only waits on the account's
+    ///   pending redeem stake batches
+    batch_id: Option<BatchId>,
+    requested: BlockTimeHeight,
+    /// NEAR that was paid by the account for the exit position storage - transferred to the
+    /// beneficiary along with the redeemed NEAR
+    storage_fee: YoctoNear,
+}
+
+impl ExitPosition {
+    pub fn new(
+        beneficiary: AccountId,
+        redeemed: YoctoStake,
+        batch_id: Option<BatchId>,
+        requested: BlockTimeHeight,
+    ) -> Self {
+        Self {
+            beneficiary,
+            redeemed,
+            batch_id,
+            requested,
+            storage_fee: 0.into(),
+        }
+    }
+
+    pub fn beneficiary(&self) -> &str {
+        &self.beneficiary
+    }
+
+    pub fn redeemed(&self) -> YoctoStake {
+        self.redeemed
+    }
+
+    pub fn batch_id(&self) -> Option<BatchId> {
+        self.batch_id
+    }
+
+    pub fn requested(&self) -> BlockTimeHeight {
+        self.requested
+    }
+
+    pub fn storage_fee(&self) -> YoctoNear {
+        self.storage_fee
+    }
+
+    pub fn set_storage_fee(&mut self, storage_fee: YoctoNear) {
+        self.storage_fee = storage_fee;
+    }
+}
@@ -0,0 +1,106 @@
+use crate::core::Hash;
+use crate::domain::BlockTimeHeight;
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// max number of features that can be rolled out at any point in time
+/// - the feature names are stored in the contract state, which is loaded on every contract call
+pub const MAX_FEATURE_ROLLOUTS: usize = 20;
+
+/// max number of accounts that can be explicitly allowlisted per feature
+pub const MAX_FEATURE_ALLOWLIST_LEN: usize = 50;
+
+pub const MAX_FEATURE_NAME_LEN: usize = 64;
+
+/// Gates a new contract behavior while it is being rolled out.
+///
+/// The feature is enabled for an account if the account is explicitly allowlisted or if the account
+/// falls within the rollout percentage cohort. The account cohort bucket is derived by hashing the
+/// feature name together with the account ID, which means:
+/// - the cohort is stable per feature, i.e., raising the rollout percentage only adds accounts to
+///   the cohort
+/// - different features are rolled out to different cohorts
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct FeatureRollout {
+    name: String,
+    /// percentage of accounts, in the range 0-100, that the feature is enabled for
+    percentage: u8,
+    allowlist: Vec<AccountId>,
+    updated: BlockTimeHeight,
+}
+
+impl FeatureRollout {
+    pub fn new(name: String, percentage: u8, updated: BlockTimeHeight) -> Self {
+        Self {
+            name,
+            percentage,
+            allowlist: vec![],
+            updated,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn percentage(&self) -> u8 {
+        self.percentage
+    }
+
+    pub fn allowlist(&self) -> &[AccountId] {
+        &self.allowlist
+    }
+
+    pub fn updated(&self) -> BlockTimeHeight {
+        self.updated
+    }
+
+    pub fn set_percentage(&mut self, percentage: u8, updated: BlockTimeHeight) {
+        self.percentage = percentage;
+        self.updated = updated;
+    }
+
+    /// returns false if the account is already allowlisted
+    pub fn allow(&mut self, account_id: &str, updated: BlockTimeHeight) -> bool {
+        if self.is_allowlisted(account_id) {
+            return false;
+        }
+        self.allowlist.push(account_id.to_string());
+        self.updated = updated;
+        true
+    }
+
+    /// returns false if the account was not allowlisted
+    pub fn disallow(&mut self, account_id: &str, updated: BlockTimeHeight) -> bool {
+        let len = self.allowlist.len();
+        self.allowlist.retain(|id| id != account_id);
+        if self.allowlist.len() == len {
+            return false;
+        }
+        self.updated = updated;
+        true
+    }
+
+    pub fn is_allowlisted(&self, account_id: &str) -> bool {
+        self.allowlist.iter().any(|id| id == account_id)
+    }
+
+    pub fn is_enabled(&self, account_id: &str) -> bool {
+        if self.percentage >= 100 || self.is_allowlisted(account_id) {
+            return true;
+        }
+        self.percentage > 0 && self.cohort_bucket(account_id) < self.percentage
+    }
+
+    /// maps the account to a bucket in the range 0-99
+    ///
+    /// ## Panics
+    /// if NEAR runtime context is not available
+    fn cohort_bucket(&self, account_id: &str) -> u8 {
+        let hash = Hash::from(format!("{}:{}", self.name, account_id).as_str());
+        let bytes = hash.as_bytes();
+        (u16::from_le_bytes([bytes[0], bytes[1]]) % 100) as u8
+    }
+}
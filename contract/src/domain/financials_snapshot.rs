@@ -0,0 +1,64 @@
+use crate::domain::{BlockTimeHeight, YoctoNear};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Snapshot of the contract financials that is recorded each time earnings are distributed.
+///
+/// The snapshots provide the contract owner with a revenue history, which would otherwise need to
+/// be reconstructed by scraping transaction history.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct FinancialsSnapshot {
+    block_time_height: BlockTimeHeight,
+    /// contract owner balance after the earnings were distributed
+    contract_owner_balance: YoctoNear,
+    /// earnings that were distributed to the contract owner
+    contract_owner_earnings: YoctoNear,
+    /// earnings that were distributed to user accounts via the liquidity pool
+    user_accounts_earnings: YoctoNear,
+    /// collected earnings that were included in the distribution
+    collected_earnings: YoctoNear,
+    /// total user accounts balance after the earnings were distributed
+    total_user_accounts_balance: YoctoNear,
+}
+
+impl FinancialsSnapshot {
+    pub fn new(
+        contract_owner_balance: YoctoNear,
+        contract_owner_earnings: YoctoNear,
+        user_accounts_earnings: YoctoNear,
+        collected_earnings: YoctoNear,
+        total_user_accounts_balance: YoctoNear,
+    ) -> Self {
+        Self {
+            block_time_height: BlockTimeHeight::from_env(),
+            contract_owner_balance,
+            contract_owner_earnings,
+            user_accounts_earnings,
+            collected_earnings,
+            total_user_accounts_balance,
+        }
+    }
+
+    pub fn block_time_height(&self) -> BlockTimeHeight {
+        self.block_time_height
+    }
+
+    pub fn contract_owner_balance(&self) -> YoctoNear {
+        self.contract_owner_balance
+    }
+
+    pub fn contract_owner_earnings(&self) -> YoctoNear {
+        self.contract_owner_earnings
+    }
+
+    pub fn user_accounts_earnings(&self) -> YoctoNear {
+        self.user_accounts_earnings
+    }
+
+    pub fn collected_earnings(&self) -> YoctoNear {
+        self.collected_earnings
+    }
+
+    pub fn total_user_accounts_balance(&self) -> YoctoNear {
+        self.total_user_accounts_balance
+    }
+}
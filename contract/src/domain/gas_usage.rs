@@ -0,0 +1,81 @@
+use crate::domain::{BlockHeight, Gas};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// staking pool methods that the contract invokes via cross-contract calls, for which gas usage is
+/// tracked
+pub const STAKING_POOL_METHODS: [&str; 8] = [
+    "deposit_and_stake",
+    "deposit",
+    "stake",
+    "unstake",
+    "withdraw",
+    "get_account",
+    "get_reward_fee_fraction",
+    "ping",
+];
+
+/// staking pool workflow callbacks that record their own gas usage, i.e., `env::used_gas()` when
+/// the callback completes, which includes the gas that is attached to the promises it creates
+pub const WORKFLOW_CALLBACKS: [&str; 7] = [
+    "on_run_stake_batch",
+    "on_deposit_and_stake",
+    "on_stake_batch_deposited",
+    "on_run_redeem_stake_batch",
+    "on_unstake",
+    "on_redeeming_stake_pending_withdrawal",
+    "on_redeeming_stake_post_withdrawal",
+];
+
+/// gas burned by a staking pool cross-contract call or workflow callback
+/// - the runtime does not expose the gas burned by a receipt to its callback, thus the staking pool
+///   samples are reported from the staking pool receipt outcomes
+/// - workflow callback samples are recorded by the contract itself
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct GasUsage {
+    samples: u64,
+    last_burnt: Gas,
+    /// max gas burned across all samples - used to auto-tune the attached gas
+    max_burnt: Gas,
+    block_height: BlockHeight,
+}
+
+impl GasUsage {
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    pub fn last_burnt(&self) -> Gas {
+        self.last_burnt
+    }
+
+    pub fn max_burnt(&self) -> Gas {
+        self.max_burnt
+    }
+
+    pub fn block_height(&self) -> BlockHeight {
+        self.block_height
+    }
+
+    pub fn record(&mut self, gas_burnt: Gas, block_height: BlockHeight) {
+        self.samples += 1;
+        self.last_burnt = gas_burnt;
+        self.max_burnt = self.max_burnt.max(gas_burnt);
+        self.block_height = block_height;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn record() {
+        let mut usage = GasUsage::default();
+        usage.record(10.into(), 1.into());
+        usage.record(5.into(), 2.into());
+        assert_eq!(usage.samples(), 2);
+        assert_eq!(usage.last_burnt(), 5.into());
+        assert_eq!(usage.max_burnt(), 10.into());
+        assert_eq!(usage.block_height(), 2.into());
+    }
+}
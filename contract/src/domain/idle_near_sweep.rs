@@ -0,0 +1,332 @@
+use crate::core::U256;
+use crate::domain::{Account, EpochHeight, YoctoNear};
+use crate::near::YOCTO;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// the yield index is the cumulative yield per 1 NEAR of idle balance
+pub const IDLE_NEAR_YIELD_INDEX_SCALE: u128 = YOCTO;
+
+/// Idle NEAR is the NEAR held in account NEAR balances, i.e., NEAR awaiting withdrawal. A portion of
+/// the idle NEAR is swept into short staking cycles, and the yield is credited back pro-rata to the
+/// idle balances that have not opted out.
+///
+/// The sweep position is staked and redeemed through the regular stake and redeem batches. The
+/// account NEAR balances are not debited while the NEAR is swept - instead, the swept NEAR is
+/// excluded from the contract's liquid NEAR.
+///
+/// The yield is distributed via a cumulative yield index, i.e., each account checkpoints the index
+/// and is credited with `balance * (index - checkpoint)` the next time the account is loaded. This
+/// avoids iterating over accounts.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub struct IdleNearSweep {
+    /// sweep position, which holds the batches, STAKE, and redeemed NEAR for the current cycle
+    /// - the position is not a registered account
+    position: Account,
+    /// idle NEAR that is currently swept into staking, i.e., not liquid
+    swept: YoctoNear,
+    /// when the current cycle's NEAR was swept
+    swept_epoch: EpochHeight,
+    yield_index: u128,
+    /// yield that has been distributed via the yield index, but not yet credited to accounts
+    yield_pool: YoctoNear,
+    /// lifetime yield that was distributed
+    total_yield: YoctoNear,
+    /// idle NEAR held by accounts that opted out - excluded from the sweep and the yield distribution
+    total_opted_out: YoctoNear,
+}
+
+impl Default for IdleNearSweep {
+    fn default() -> Self {
+        Self {
+            position: Account::new(0.into()),
+            swept: 0.into(),
+            swept_epoch: 0.into(),
+            yield_index: 0,
+            yield_pool: 0.into(),
+            total_yield: 0.into(),
+            total_opted_out: 0.into(),
+        }
+    }
+}
+
+impl IdleNearSweep {
+    pub fn position(&self) -> Account {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Account) {
+        self.position = position;
+    }
+
+    pub fn swept(&self) -> YoctoNear {
+        self.swept
+    }
+
+    pub fn swept_epoch(&self) -> EpochHeight {
+        self.swept_epoch
+    }
+
+    pub fn yield_index(&self) -> u128 {
+        self.yield_index
+    }
+
+    pub fn yield_pool(&self) -> YoctoNear {
+        self.yield_pool
+    }
+
+    pub fn total_yield(&self) -> YoctoNear {
+        self.total_yield
+    }
+
+    pub fn total_opted_out(&self) -> YoctoNear {
+        self.total_opted_out
+    }
+
+    /// true while a sweep cycle is in progress, i.e., the position holds funds
+    pub fn cycle_in_progress(&self) -> bool {
+        self.swept.value() > 0 || self.position.has_funds()
+    }
+
+    pub fn record_sweep(&mut self, amount: YoctoNear, epoch_height: EpochHeight) {
+        self.swept += amount;
+        self.swept_epoch = epoch_height;
+    }
+
+    /// closes the cycle once the swept NEAR has been returned from staking
+    /// - `returned` is the NEAR that the position redeemed
+    /// - `base` is the idle NEAR that shares the yield
+    ///
+    /// Returns the yield that was distributed via the yield index. Rounding dust and any yield that
+    /// cannot be distributed because the base is zero are not distributed.
+    ///
+    /// NOTE: if less NEAR was returned than was swept, e.g., because of STAKE conversion rounding,
+    ///       then no yield is distributed - the shortfall is absorbed by the contract
+    pub fn settle(&mut self, returned: YoctoNear, base: YoctoNear) -> YoctoNear {
+        let yield_amount = returned.value().saturating_sub(self.swept.value());
+        self.swept = 0.into();
+        self.position = Account::new(0.into());
+        if yield_amount == 0 || base.value() == 0 {
+            return 0.into();
+        }
+
+        let index_delta = (U256::from(yield_amount) * U256::from(IDLE_NEAR_YIELD_INDEX_SCALE)
+            / U256::from(base.value()))
+        .as_u128();
+        self.yield_index += index_delta;
+        // the index is rounded down, thus the amount that will be credited is recomputed from the
+        // index delta
+        let distributed: YoctoNear = (U256::from(index_delta) * U256::from(base.value())
+            / U256::from(IDLE_NEAR_YIELD_INDEX_SCALE))
+        .as_u128()
+        .into();
+        self.yield_pool += distributed;
+        self.total_yield += distributed;
+        distributed
+    }
+
+    /// moves yield that was credited to an account out of the yield pool
+    pub fn record_yield_credited(&mut self, amount: YoctoNear) {
+        // saturating to guard against rounding - the yield credited per account is rounded down
+        self.yield_pool = self
+            .yield_pool
+            .value()
+            .saturating_sub(amount.value())
+            .into();
+    }
+
+    /// adjusts the opted out total by the change in the NEAR balance of an opted out account
+    pub fn update_opted_out(&mut self, before: YoctoNear, after: YoctoNear) {
+        self.total_opted_out = (self.total_opted_out.value() + after.value())
+            .saturating_sub(before.value())
+            .into();
+    }
+}
+
+/// per account idle NEAR sharing state
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct IdleNearShare {
+    /// yield index checkpoint
+    yield_index: u128,
+    opted_out: bool,
+}
+
+impl IdleNearShare {
+    pub fn new(yield_index: u128) -> Self {
+        Self {
+            yield_index,
+            opted_out: false,
+        }
+    }
+
+    pub fn opted_out(&self) -> bool {
+        self.opted_out
+    }
+
+    pub fn set_opted_out(&mut self, opted_out: bool) {
+        self.opted_out = opted_out;
+    }
+
+    /// returns the yield that has accrued on the balance since the last checkpoint and moves the
+    /// checkpoint to the specified yield index
+    /// - accounts that opted out accrue no yield
+    pub fn accrue(&mut self, balance: YoctoNear, yield_index: u128) -> YoctoNear {
+        let index_delta = yield_index.saturating_sub(self.yield_index);
+        self.yield_index = yield_index;
+        if self.opted_out || index_delta == 0 {
+            return 0.into();
+        }
+        (U256::from(balance.value()) * U256::from(index_delta)
+            / U256::from(IDLE_NEAR_YIELD_INDEX_SCALE))
+        .as_u128()
+        .into()
+    }
+}
+
+/// returns the amount of idle NEAR that can be swept while keeping the liquidity buffer
+/// - `total_idle` is the idle NEAR that is eligible for the sweep, which includes the NEAR that is
+///   already swept
+/// - the liquidity buffer is rounded up and the sweep amount is rounded down, i.e., the math errs
+///   on the side of liquidity
+pub fn idle_near_sweep_amount(
+    total_idle: YoctoNear,
+    swept: YoctoNear,
+    liquidity_buffer_percentage: u8,
+    sweep_percentage: u8,
+) -> YoctoNear {
+    let total_idle = U256::from(total_idle.value());
+    let buffer =
+        (total_idle * U256::from(liquidity_buffer_percentage) + U256::from(99)) / U256::from(100);
+    let liquid = total_idle.saturating_sub(U256::from(swept.value()));
+    let sweepable = liquid.saturating_sub(buffer);
+    (sweepable * U256::from(sweep_percentage) / U256::from(100))
+        .as_u128()
+        .into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn sweep_amount_keeps_liquidity_buffer() {
+        // 50% buffer, sweep all of the sweepable NEAR
+        assert_eq!(
+            idle_near_sweep_amount((100 * YOCTO).into(), 0.into(), 50, 100),
+            (50 * YOCTO).into()
+        );
+        // sweep half of the sweepable NEAR
+        assert_eq!(
+            idle_near_sweep_amount((100 * YOCTO).into(), 0.into(), 50, 50),
+            (25 * YOCTO).into()
+        );
+        // NEAR that is already swept is not liquid
+        assert_eq!(
+            idle_near_sweep_amount((100 * YOCTO).into(), (40 * YOCTO).into(), 50, 100),
+            (10 * YOCTO).into()
+        );
+        assert_eq!(
+            idle_near_sweep_amount((100 * YOCTO).into(), (60 * YOCTO).into(), 50, 100),
+            0.into()
+        );
+        assert_eq!(
+            idle_near_sweep_amount((100 * YOCTO).into(), 0.into(), 100, 100),
+            0.into()
+        );
+        assert_eq!(
+            idle_near_sweep_amount((100 * YOCTO).into(), 0.into(), 50, 0),
+            0.into()
+        );
+        // the buffer is rounded up
+        assert_eq!(
+            idle_near_sweep_amount(3.into(), 0.into(), 50, 100),
+            1.into()
+        );
+    }
+
+    #[quickcheck]
+    fn sweep_never_breaches_liquidity_buffer(
+        total_idle: u128,
+        swept: u128,
+        liquidity_buffer_percentage: u8,
+        sweep_percentage: u8,
+    ) -> bool {
+        let liquidity_buffer_percentage = liquidity_buffer_percentage % 101;
+        let sweep_percentage = sweep_percentage % 101;
+        let swept = swept.min(total_idle);
+        let amount = idle_near_sweep_amount(
+            total_idle.into(),
+            swept.into(),
+            liquidity_buffer_percentage,
+            sweep_percentage,
+        )
+        .value();
+
+        let liquid_after_sweep = U256::from(total_idle - swept - amount);
+        let buffer = U256::from(total_idle) * U256::from(liquidity_buffer_percentage);
+        // liquid NEAR after the sweep >= buffer, i.e., liquid * 100 >= total * buffer percentage
+        liquid_after_sweep * U256::from(100) >= buffer
+    }
+
+    #[quickcheck]
+    fn yield_credited_never_exceeds_yield_distributed(
+        balances: Vec<u64>,
+        swept: u64,
+        yield_amount: u64,
+    ) -> bool {
+        let base: u128 = balances.iter().map(|balance| *balance as u128).sum();
+        let mut sweep = IdleNearSweep::default();
+        sweep.record_sweep((swept as u128).into(), 0.into());
+        let distributed = sweep.settle((swept as u128 + yield_amount as u128).into(), base.into());
+
+        let credited: u128 = balances
+            .iter()
+            .map(|balance| {
+                IdleNearShare::default()
+                    .accrue((*balance as u128).into(), sweep.yield_index())
+                    .value()
+            })
+            .sum();
+        distributed.value() <= yield_amount as u128 && credited <= distributed.value()
+    }
+
+    #[test]
+    fn settle_distributes_yield_pro_rata() {
+        let mut sweep = IdleNearSweep::default();
+        sweep.record_sweep((10 * YOCTO).into(), 1.into());
+        assert!(sweep.cycle_in_progress());
+        let distributed = sweep.settle((11 * YOCTO).into(), (100 * YOCTO).into());
+        assert_eq!(distributed, YOCTO.into());
+        assert_eq!(sweep.swept(), 0.into());
+        assert!(!sweep.cycle_in_progress());
+        assert_eq!(sweep.yield_pool(), YOCTO.into());
+
+        let mut share = IdleNearShare::new(0);
+        assert_eq!(
+            share.accrue((30 * YOCTO).into(), sweep.yield_index()),
+            (YOCTO * 3 / 10).into()
+        );
+        // the checkpoint moved
+        assert_eq!(
+            share.accrue((30 * YOCTO).into(), sweep.yield_index()),
+            0.into()
+        );
+
+        let mut share = IdleNearShare::new(0);
+        share.set_opted_out(true);
+        assert_eq!(
+            share.accrue((30 * YOCTO).into(), sweep.yield_index()),
+            0.into()
+        );
+    }
+
+    #[test]
+    fn settle_with_shortfall() {
+        let mut sweep = IdleNearSweep::default();
+        sweep.record_sweep((10 * YOCTO).into(), 1.into());
+        let distributed = sweep.settle((10 * YOCTO - 1).into(), (100 * YOCTO).into());
+        assert_eq!(distributed, 0.into());
+        assert_eq!(sweep.yield_index(), 0);
+        assert_eq!(sweep.swept(), 0.into());
+    }
+}
@@ -0,0 +1,146 @@
+use crate::domain::{BlockTimeHeight, BlockTimestamp, YoctoNear, YoctoStake};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// max invoice memo length in bytes - the memo is stored on chain, which is paid for by the payee
+pub const MAX_INVOICE_MEMO_LEN: usize = 256;
+
+/// STAKE payment request that is created by the payee and settled by the payer via an internal STAKE
+/// transfer - the invoice ID serves as the payment reference
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct Invoice {
+    payee_id: AccountId,
+    amount: YoctoStake,
+    memo: String,
+    /// the invoice can no longer be paid once the block timestamp reaches the expiry
+    expires_at: BlockTimestamp,
+    /// NEAR that was attached by the payee to pay for the invoice storage
+    /// - refunded to the payee when the invoice is closed
+    storage_fee: YoctoNear,
+    created: BlockTimeHeight,
+    payment: Option<InvoicePayment>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct InvoicePayment {
+    pub payer_id: AccountId,
+    pub paid: BlockTimeHeight,
+}
+
+impl Invoice {
+    pub fn new(
+        payee_id: &str,
+        amount: YoctoStake,
+        memo: String,
+        expires_at: BlockTimestamp,
+        created: BlockTimeHeight,
+    ) -> Self {
+        Self {
+            payee_id: payee_id.to_string(),
+            amount,
+            memo,
+            expires_at,
+            storage_fee: 0.into(),
+            created,
+            payment: None,
+        }
+    }
+
+    pub fn payee_id(&self) -> &str {
+        &self.payee_id
+    }
+
+    pub fn amount(&self) -> YoctoStake {
+        self.amount
+    }
+
+    pub fn memo(&self) -> &str {
+        &self.memo
+    }
+
+    pub fn expires_at(&self) -> BlockTimestamp {
+        self.expires_at
+    }
+
+    pub fn storage_fee(&self) -> YoctoNear {
+        self.storage_fee
+    }
+
+    pub fn created(&self) -> BlockTimeHeight {
+        self.created
+    }
+
+    pub fn payment(&self) -> Option<&InvoicePayment> {
+        self.payment.as_ref()
+    }
+
+    pub fn is_expired(&self, now: BlockTimestamp) -> bool {
+        self.payment.is_none() && now >= self.expires_at
+    }
+
+    pub fn is_payable(&self, now: BlockTimestamp) -> bool {
+        self.payment.is_none() && now < self.expires_at
+    }
+
+    /// the storage fee can only be computed after the invoice has been stored - the storage fee is
+    /// a fixed size field, thus setting it does not change the invoice storage usage
+    pub fn set_storage_fee(&mut self, storage_fee: YoctoNear) {
+        self.storage_fee = storage_fee;
+    }
+
+    /// ## Panics
+    /// if the invoice has already been paid
+    pub fn record_payment(&mut self, payer_id: &str, paid: BlockTimeHeight) {
+        assert!(self.payment.is_none(), "invoice has already been paid");
+        self.payment = Some(InvoicePayment {
+            payer_id: payer_id.to_string(),
+            paid,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::near::YOCTO;
+
+    fn invoice() -> Invoice {
+        Invoice::new(
+            "payee.near",
+            YOCTO.into(),
+            "order #1".to_string(),
+            1000.into(),
+            BlockTimeHeight::default(),
+        )
+    }
+
+    #[test]
+    fn payable_until_expired() {
+        let invoice = invoice();
+        assert!(invoice.is_payable(999.into()));
+        assert!(!invoice.is_expired(999.into()));
+        assert!(!invoice.is_payable(1000.into()));
+        assert!(invoice.is_expired(1000.into()));
+    }
+
+    #[test]
+    fn record_payment() {
+        let mut invoice = invoice();
+        invoice.record_payment("payer.near", BlockTimeHeight::default());
+        let payment = invoice.payment().unwrap();
+        assert_eq!(payment.payer_id, "payer.near");
+        // paid invoices never expire
+        assert!(!invoice.is_payable(2000.into()));
+        assert!(!invoice.is_expired(2000.into()));
+    }
+
+    #[test]
+    #[should_panic(expected = "invoice has already been paid")]
+    fn record_payment_twice() {
+        let mut invoice = invoice();
+        invoice.record_payment("payer.near", BlockTimeHeight::default());
+        invoice.record_payment("payer.near", BlockTimeHeight::default());
+    }
+}
@@ -0,0 +1,198 @@
+use crate::domain::{mul_div, Account, Rounding, YoctoNear};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// NEAR liquidity that is provided by third party liquidity providers in exchange for pool shares.
+///
+/// The pool is kept separate from the contract's own NEAR liquidity pool, which is owned by the
+/// STAKE holders. When there is not enough contract liquidity to serve claims against a redeem
+/// stake batch that is pending withdrawal, the remainder is served from the liquidity provider
+/// pool: the pool buys the account's share of the redeemed STAKE at a discount, i.e., the fee. The
+/// pool then claims the NEAR for the redeemed STAKE once the unstaked NEAR has been withdrawn, which
+/// is how the fee is earned by the liquidity providers.
+///
+/// Accounts can also swap STAKE for NEAR against the pool - the swapped STAKE is redeemed through
+/// the regular redeem stake batches.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub struct LiquidityProviderPool {
+    /// NEAR that is available to serve claims and to be removed by liquidity providers
+    balance: YoctoNear,
+    total_shares: u128,
+    /// pool position, which holds the redeemed STAKE that the pool bought in its redeem stake batches
+    /// - the position is not a registered account
+    position: Account,
+    /// total fees that have been earned by the pool
+    fees_earned: YoctoNear,
+}
+
+impl Default for LiquidityProviderPool {
+    fn default() -> Self {
+        Self {
+            balance: 0.into(),
+            total_shares: 0,
+            position: Account::new(0.into()),
+            fees_earned: 0.into(),
+        }
+    }
+}
+
+impl LiquidityProviderPool {
+    pub fn balance(&self) -> YoctoNear {
+        self.balance
+    }
+
+    pub fn total_shares(&self) -> u128 {
+        self.total_shares
+    }
+
+    pub fn position(&self) -> Account {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Account) {
+        self.position = position;
+    }
+
+    pub fn fees_earned(&self) -> YoctoNear {
+        self.fees_earned
+    }
+
+    /// shares that are minted for the NEAR amount - rounds down in favor of the pool
+    /// - `pool_value` is the pool's total NEAR value, i.e., including the NEAR value of the redeemed
+    ///   STAKE that the pool holds
+    pub fn shares_for_deposit(&self, amount: YoctoNear, pool_value: YoctoNear) -> u128 {
+        if self.total_shares == 0 || pool_value.value() == 0 {
+            amount.value()
+        } else {
+            mul_div(
+                amount.value(),
+                self.total_shares,
+                pool_value.value(),
+                Rounding::Down,
+            )
+        }
+    }
+
+    /// shares that are burned to remove the NEAR amount - rounds up in favor of the pool
+    pub fn shares_for_withdrawal(&self, amount: YoctoNear, pool_value: YoctoNear) -> u128 {
+        if self.total_shares == 0 || pool_value.value() == 0 {
+            return 0;
+        }
+        mul_div(
+            amount.value(),
+            self.total_shares,
+            pool_value.value(),
+            Rounding::Up,
+        )
+    }
+
+    /// NEAR value of the shares - rounds down in favor of the pool
+    pub fn shares_value(&self, shares: u128, pool_value: YoctoNear) -> YoctoNear {
+        if self.total_shares == 0 {
+            return 0.into();
+        }
+        mul_div(
+            shares,
+            pool_value.value(),
+            self.total_shares,
+            Rounding::Down,
+        )
+        .into()
+    }
+
+    pub fn deposit(&mut self, amount: YoctoNear, shares: u128) {
+        self.balance += amount;
+        self.total_shares += shares;
+    }
+
+    pub fn withdraw(&mut self, amount: YoctoNear, shares: u128) {
+        self.balance -= amount;
+        self.total_shares -= shares;
+    }
+
+    /// the pool pays out `near - fee` for redeemed STAKE that is worth `near`
+    /// - the redeemed STAKE is expected to be added to the pool position's redeem stake batches
+    pub fn pay_out(&mut self, near: YoctoNear, fee: YoctoNear) {
+        self.balance -= near - fee;
+        self.fees_earned += fee;
+    }
+
+    /// credits the NEAR that was claimed for the redeemed STAKE that the pool held
+    pub fn credit(&mut self, near: YoctoNear) {
+        self.balance += near;
+    }
+
+    /// swap fee in basis points, which increases linearly with the pool utilization from the min
+    /// fee to the max fee
+    /// - the utilization is the share of the pool value that is tied up in the redeem pipeline
+    ///   after the swap, i.e., `redeemed_stake_value` is the NEAR value of the redeemed STAKE that
+    ///   the pool already holds
+    pub fn swap_fee_basis_points(
+        &self,
+        near: YoctoNear,
+        redeemed_stake_value: YoctoNear,
+        min_fee_basis_points: u16,
+        max_fee_basis_points: u16,
+    ) -> u16 {
+        let pool_value = self.balance.value() + redeemed_stake_value.value();
+        let utilized = (redeemed_stake_value.value() + near.value()).min(pool_value);
+        if pool_value == 0 {
+            return max_fee_basis_points;
+        }
+        let fee_range = (max_fee_basis_points - min_fee_basis_points) as u128;
+        min_fee_basis_points + mul_div(fee_range, utilized, pool_value, Rounding::Up) as u16
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn shares_track_pool_value() {
+        testing_env!(new_context("bob.near"));
+        let mut pool = LiquidityProviderPool::default();
+        let shares = pool.shares_for_deposit(1000.into(), 0.into());
+        assert_eq!(shares, 1000);
+        pool.deposit(1000.into(), shares);
+
+        // the pool value grew by 10% from fees
+        let pool_value: YoctoNear = 1100.into();
+        let shares = pool.shares_for_deposit(1100.into(), pool_value);
+        assert_eq!(shares, 1000);
+        pool.deposit(1100.into(), shares);
+        assert_eq!(pool.total_shares(), 2000);
+
+        let pool_value: YoctoNear = 2200.into();
+        assert_eq!(pool.shares_value(1000, pool_value), 1100.into());
+        assert_eq!(pool.shares_for_withdrawal(1100.into(), pool_value), 1000);
+        // rounds up in favor of the pool
+        assert_eq!(pool.shares_for_withdrawal(1.into(), pool_value), 1);
+    }
+
+    #[test]
+    fn swap_fee_increases_with_utilization() {
+        testing_env!(new_context("bob.near"));
+        let mut pool = LiquidityProviderPool::default();
+        pool.deposit(1000.into(), 1000);
+        assert_eq!(pool.swap_fee_basis_points(0.into(), 0.into(), 10, 100), 10);
+        assert_eq!(
+            pool.swap_fee_basis_points(500.into(), 0.into(), 10, 100),
+            55
+        );
+        assert_eq!(
+            pool.swap_fee_basis_points(500.into(), 1000.into(), 10, 100),
+            78
+        );
+        assert_eq!(
+            pool.swap_fee_basis_points(1000.into(), 0.into(), 10, 100),
+            100
+        );
+        // the utilization is capped at 100%
+        assert_eq!(
+            pool.swap_fee_basis_points(2000.into(), 0.into(), 10, 100),
+            100
+        );
+    }
+}
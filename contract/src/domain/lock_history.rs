@@ -0,0 +1,84 @@
+use crate::domain::{BatchId, BlockHeight};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+};
+
+/// max number of lock history entries that are retained - once the history is full, the oldest
+/// entry is overwritten, i.e., the history is stored as a ring buffer
+pub const LOCK_HISTORY_MAX_LEN: u64 = 100;
+
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum LockKind {
+    /// [StakeLock](crate::domain::StakeLock) acquired to run a stake batch
+    Stake,
+    /// [StakeLock](crate::domain::StakeLock) acquired to refresh the STAKE token value
+    RefreshStakeTokenValue,
+    /// [RedeemLock](crate::domain::RedeemLock) acquired to run a redeem stake batch
+    Redeem,
+}
+
+impl LockKind {
+    /// returns true if the lock kind is tracked via the [StakeLock](crate::domain::StakeLock)
+    pub fn is_stake_lock(&self) -> bool {
+        match self {
+            LockKind::Stake | LockKind::RefreshStakeTokenValue => true,
+            LockKind::Redeem => false,
+        }
+    }
+}
+
+/// Records a lock episode, i.e., from the time the lock was acquired until it was released
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct LockHistoryEntry {
+    kind: LockKind,
+    batch_id: Option<BatchId>,
+    acquired_block_height: BlockHeight,
+    released_block_height: Option<BlockHeight>,
+    /// name of the contract method that released the lock
+    released_by: Option<String>,
+}
+
+impl LockHistoryEntry {
+    pub fn new(kind: LockKind, batch_id: Option<BatchId>, block_height: BlockHeight) -> Self {
+        Self {
+            kind,
+            batch_id,
+            acquired_block_height: block_height,
+            released_block_height: None,
+            released_by: None,
+        }
+    }
+
+    pub fn kind(&self) -> LockKind {
+        self.kind
+    }
+
+    pub fn batch_id(&self) -> Option<BatchId> {
+        self.batch_id
+    }
+
+    pub fn acquired_block_height(&self) -> BlockHeight {
+        self.acquired_block_height
+    }
+
+    pub fn released_block_height(&self) -> Option<BlockHeight> {
+        self.released_block_height
+    }
+
+    pub fn released_by(&self) -> Option<&str> {
+        self.released_by.as_deref()
+    }
+
+    pub fn is_released(&self) -> bool {
+        self.released_block_height.is_some()
+    }
+
+    pub fn release(&mut self, block_height: BlockHeight, method: &str) {
+        self.released_block_height = Some(block_height);
+        self.released_by = Some(method.to_string());
+    }
+}
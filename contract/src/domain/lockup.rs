@@ -0,0 +1,161 @@
+use crate::domain::{mul_div, BlockTimeHeight, BlockTimestamp, Rounding, YoctoNear, YoctoStake};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// STAKE that is granted to a beneficiary under a linear vesting schedule.
+///
+/// The granted STAKE is escrowed by the contract, i.e., it remains part of the STAKE supply and
+/// accrues staking rewards, but it cannot be transferred or redeemed by the beneficiary until it
+/// vests and is claimed into the beneficiary's account.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct Lockup {
+    grantor_id: AccountId,
+    /// total STAKE that was granted
+    amount: YoctoStake,
+    /// vested STAKE that has been claimed by the beneficiary
+    claimed: YoctoStake,
+    vesting_start: BlockTimestamp,
+    vesting_end: BlockTimestamp,
+    created: BlockTimeHeight,
+    /// NEAR that was paid by the grantor for the lockup storage - refunded to the grantor once the
+    /// lockup is fully claimed
+    storage_fee: YoctoNear,
+}
+
+impl Lockup {
+    /// the vesting end is expected to be after the vesting start
+    pub fn new(
+        grantor_id: AccountId,
+        amount: YoctoStake,
+        vesting_start: BlockTimestamp,
+        vesting_end: BlockTimestamp,
+        created: BlockTimeHeight,
+    ) -> Self {
+        Self {
+            grantor_id,
+            amount,
+            claimed: 0.into(),
+            vesting_start,
+            vesting_end,
+            created,
+            storage_fee: 0.into(),
+        }
+    }
+
+    pub fn grantor_id(&self) -> &str {
+        &self.grantor_id
+    }
+
+    pub fn amount(&self) -> YoctoStake {
+        self.amount
+    }
+
+    pub fn claimed(&self) -> YoctoStake {
+        self.claimed
+    }
+
+    pub fn vesting_start(&self) -> BlockTimestamp {
+        self.vesting_start
+    }
+
+    pub fn vesting_end(&self) -> BlockTimestamp {
+        self.vesting_end
+    }
+
+    pub fn created(&self) -> BlockTimeHeight {
+        self.created
+    }
+
+    pub fn storage_fee(&self) -> YoctoNear {
+        self.storage_fee
+    }
+
+    pub fn set_storage_fee(&mut self, storage_fee: YoctoNear) {
+        self.storage_fee = storage_fee;
+    }
+
+    /// STAKE that has vested as of the specified time - vesting is linear between the vesting start
+    /// and end, and rounds down in favor of the lockup
+    pub fn vested(&self, now: BlockTimestamp) -> YoctoStake {
+        if now <= self.vesting_start {
+            0.into()
+        } else if now >= self.vesting_end {
+            self.amount
+        } else {
+            mul_div(
+                self.amount.value(),
+                (now.value() - self.vesting_start.value()) as u128,
+                (self.vesting_end.value() - self.vesting_start.value()) as u128,
+                Rounding::Down,
+            )
+            .into()
+        }
+    }
+
+    /// vested STAKE that has not yet been claimed
+    pub fn claimable(&self, now: BlockTimestamp) -> YoctoStake {
+        self.vested(now) - self.claimed
+    }
+
+    /// STAKE that has not yet vested
+    pub fn locked(&self, now: BlockTimestamp) -> YoctoStake {
+        self.amount - self.vested(now)
+    }
+
+    /// marks the vested STAKE as claimed
+    ///
+    /// Returns the amount that was claimed.
+    pub fn claim(&mut self, now: BlockTimestamp) -> YoctoStake {
+        let amount = self.claimable(now);
+        self.claimed += amount;
+        amount
+    }
+
+    /// the lockup can be deleted once all of the granted STAKE has been claimed
+    pub fn is_fully_claimed(&self) -> bool {
+        self.claimed == self.amount
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lockup() -> Lockup {
+        Lockup::new(
+            "grantor.near".to_string(),
+            1000.into(),
+            100.into(),
+            200.into(),
+            BlockTimeHeight::default(),
+        )
+    }
+
+    #[test]
+    fn vesting_is_linear() {
+        let lockup = lockup();
+        assert_eq!(lockup.vested(50.into()), 0.into());
+        assert_eq!(lockup.vested(100.into()), 0.into());
+        assert_eq!(lockup.vested(125.into()), 250.into());
+        assert_eq!(lockup.locked(125.into()), 750.into());
+        assert_eq!(lockup.vested(200.into()), 1000.into());
+        assert_eq!(lockup.vested(300.into()), 1000.into());
+        assert_eq!(lockup.locked(300.into()), 0.into());
+    }
+
+    #[test]
+    fn claim() {
+        let mut lockup = lockup();
+        assert_eq!(lockup.claim(150.into()), 500.into());
+        assert_eq!(lockup.claimable(150.into()), 0.into());
+        assert_eq!(lockup.claim(150.into()), 0.into());
+        assert!(!lockup.is_fully_claimed());
+
+        assert_eq!(lockup.claim(175.into()), 250.into());
+        assert_eq!(lockup.claim(250.into()), 250.into());
+        assert_eq!(lockup.claimed(), 1000.into());
+        assert!(lockup.is_fully_claimed());
+    }
+}
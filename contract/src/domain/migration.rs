@@ -0,0 +1,160 @@
+use crate::domain::BlockTimeHeight;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use std::ops::Range;
+
+/// Migrations that can be run in chunks via [migrate_chunk](crate::interface::Migrations::migrate_chunk).
+///
+/// Each task walks a persistent collection by a numeric cursor, which enables collections with
+/// tens of thousands of records to be migrated across many transactions without exceeding the gas
+/// limit.
+///
+/// ## How to add a new migration task
+/// 1. append a new variant - variants must only ever be appended because the variant index is
+///    persisted as the migration cursor key
+/// 2. assign the task ID
+/// 3. define the cursor range and the per record migration in [Contract](crate::Contract)
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrationTask {
+    /// rewrites [StakeBatchReceipt](crate::domain::StakeBatchReceipt) records using the latest
+    /// record version, and moves untagged legacy records to the versioned records - see
+    /// [Versioned](crate::domain::Versioned)
+    StakeBatchReceipts,
+    /// rewrites [RedeemStakeBatchReceipt](crate::domain::RedeemStakeBatchReceipt) records using the
+    /// latest record version, and moves untagged legacy records to the versioned records - see
+    /// [Versioned](crate::domain::Versioned)
+    RedeemStakeBatchReceipts,
+}
+
+impl MigrationTask {
+    pub const ALL: [MigrationTask; 2] = [
+        MigrationTask::StakeBatchReceipts,
+        MigrationTask::RedeemStakeBatchReceipts,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            MigrationTask::StakeBatchReceipts => "stake_batch_receipts",
+            MigrationTask::RedeemStakeBatchReceipts => "redeem_stake_batch_receipts",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|task| task.id() == id)
+    }
+}
+
+/// Tracks the progress of a chunked migration.
+///
+/// The cursor range is fixed when the migration is started. Records that are written after the
+/// migration started are already written using the latest layout, thus they do not need to be
+/// migrated.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct MigrationCursor {
+    /// next cursor position to migrate
+    cursor: u64,
+    /// cursor position where the migration completes (exclusive)
+    end: u64,
+    /// number of records that were migrated - cursor positions may not have a record
+    migrated: u64,
+    started: BlockTimeHeight,
+    completed: Option<BlockTimeHeight>,
+}
+
+impl MigrationCursor {
+    pub fn new(start: u64, end: u64, started: BlockTimeHeight) -> Self {
+        Self {
+            cursor: start,
+            end,
+            migrated: 0,
+            started,
+            completed: None,
+        }
+    }
+
+    pub fn cursor(&self) -> u64 {
+        self.cursor
+    }
+
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+
+    pub fn migrated(&self) -> u64 {
+        self.migrated
+    }
+
+    pub fn started(&self) -> BlockTimeHeight {
+        self.started
+    }
+
+    pub fn completed(&self) -> Option<BlockTimeHeight> {
+        self.completed
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed.is_some()
+    }
+
+    /// returns the cursor positions for the next chunk
+    pub fn next_chunk(&self, limit: u32) -> Range<u64> {
+        self.cursor..self.end.min(self.cursor.saturating_add(limit as u64))
+    }
+
+    /// moves the cursor to the end of the chunk that was processed
+    /// - the migration is marked complete once the cursor reaches the end
+    pub fn advance(
+        &mut self,
+        chunk: Range<u64>,
+        migrated: u64,
+        block_time_height: BlockTimeHeight,
+    ) {
+        self.cursor = chunk.end;
+        self.migrated += migrated;
+        if self.cursor >= self.end {
+            self.completed = Some(block_time_height);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn migrate_in_chunks() {
+        let mut migration = MigrationCursor::new(1, 6, BlockTimeHeight::default());
+        let chunk = migration.next_chunk(2);
+        assert_eq!(chunk, 1..3);
+        migration.advance(chunk, 2, BlockTimeHeight::default());
+        assert!(!migration.is_complete());
+
+        let chunk = migration.next_chunk(2);
+        assert_eq!(chunk, 3..5);
+        migration.advance(chunk, 1, BlockTimeHeight::default());
+        assert!(!migration.is_complete());
+
+        let chunk = migration.next_chunk(2);
+        assert_eq!(chunk, 5..6);
+        migration.advance(chunk, 1, BlockTimeHeight::default());
+        assert!(migration.is_complete());
+        assert_eq!(migration.migrated(), 4);
+        assert!(migration.next_chunk(2).is_empty());
+    }
+
+    #[test]
+    fn empty_migration_completes_on_first_chunk() {
+        let mut migration = MigrationCursor::new(1, 1, BlockTimeHeight::default());
+        let chunk = migration.next_chunk(10);
+        assert!(chunk.is_empty());
+        migration.advance(chunk, 0, BlockTimeHeight::default());
+        assert!(migration.is_complete());
+    }
+
+    #[test]
+    fn task_ids() {
+        for task in MigrationTask::ALL.iter() {
+            assert_eq!(MigrationTask::from_id(task.id()), Some(*task));
+        }
+        assert!(MigrationTask::from_id("unknown").is_none());
+    }
+}
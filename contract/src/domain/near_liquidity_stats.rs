@@ -0,0 +1,146 @@
+use crate::domain::YoctoNear;
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+};
+
+/// how NEAR liquidity was added to the liquidity pool
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum NearLiquidityInflow {
+    /// unstaked NEAR that was withdrawn from the staking pool when a stake batch was run
+    StakeBatch,
+    /// NEAR that was added to rebalance the STAKE token value when the staked NEAR balance dropped
+    StakeTokenValueCompensation,
+    /// user account earnings that were distributed when a stake batch was run
+    Earnings,
+}
+
+/// how NEAR liquidity was taken out of the liquidity pool
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum NearLiquidityOutflow {
+    /// claimed by accounts against redeem stake batch receipts that are pending withdrawal
+    Claim,
+    /// used to clear the redeem stake batch that is pending withdrawal from the staking pool
+    PendingWithdrawal,
+    /// drawn on to fulfill account NEAR withdrawals
+    Withdrawal,
+    /// staked back into the staking pool because liquidity was no longer needed
+    Restake,
+    /// paid out to accounts that redeemed STAKE instantly against the liquidity
+    InstantRedeem,
+}
+
+/// Tracks the NEAR liquidity pool inflows and outflows over the lifetime of the contract, which
+/// explains how the liquidity pool balance changed over time.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct NearLiquidityStats {
+    added_from_stake_batches: YoctoNear,
+    added_from_stake_token_value_compensation: YoctoNear,
+    added_from_earnings: YoctoNear,
+    consumed_by_claims: YoctoNear,
+    consumed_by_pending_withdrawals: YoctoNear,
+    consumed_by_withdrawals: YoctoNear,
+    restaked: YoctoNear,
+    consumed_by_instant_redeems: YoctoNear,
+}
+
+impl NearLiquidityStats {
+    pub fn added_from_stake_batches(&self) -> YoctoNear {
+        self.added_from_stake_batches
+    }
+
+    pub fn added_from_stake_token_value_compensation(&self) -> YoctoNear {
+        self.added_from_stake_token_value_compensation
+    }
+
+    pub fn added_from_earnings(&self) -> YoctoNear {
+        self.added_from_earnings
+    }
+
+    pub fn consumed_by_claims(&self) -> YoctoNear {
+        self.consumed_by_claims
+    }
+
+    pub fn consumed_by_pending_withdrawals(&self) -> YoctoNear {
+        self.consumed_by_pending_withdrawals
+    }
+
+    pub fn consumed_by_withdrawals(&self) -> YoctoNear {
+        self.consumed_by_withdrawals
+    }
+
+    pub fn restaked(&self) -> YoctoNear {
+        self.restaked
+    }
+
+    pub fn consumed_by_instant_redeems(&self) -> YoctoNear {
+        self.consumed_by_instant_redeems
+    }
+
+    pub fn total_inflows(&self) -> YoctoNear {
+        self.added_from_stake_batches
+            + self.added_from_stake_token_value_compensation
+            + self.added_from_earnings
+    }
+
+    pub fn total_outflows(&self) -> YoctoNear {
+        self.consumed_by_claims
+            + self.consumed_by_pending_withdrawals
+            + self.consumed_by_withdrawals
+            + self.restaked
+            + self.consumed_by_instant_redeems
+    }
+
+    pub fn record_inflow(&mut self, inflow: NearLiquidityInflow, amount: YoctoNear) {
+        match inflow {
+            NearLiquidityInflow::StakeBatch => self.added_from_stake_batches += amount,
+            NearLiquidityInflow::StakeTokenValueCompensation => {
+                self.added_from_stake_token_value_compensation += amount
+            }
+            NearLiquidityInflow::Earnings => self.added_from_earnings += amount,
+        }
+    }
+
+    pub fn record_outflow(&mut self, outflow: NearLiquidityOutflow, amount: YoctoNear) {
+        match outflow {
+            NearLiquidityOutflow::Claim => self.consumed_by_claims += amount,
+            NearLiquidityOutflow::PendingWithdrawal => {
+                self.consumed_by_pending_withdrawals += amount
+            }
+            NearLiquidityOutflow::Withdrawal => self.consumed_by_withdrawals += amount,
+            NearLiquidityOutflow::Restake => self.restaked += amount,
+            NearLiquidityOutflow::InstantRedeem => self.consumed_by_instant_redeems += amount,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn record_inflows_and_outflows() {
+        let mut stats = NearLiquidityStats::default();
+        stats.record_inflow(NearLiquidityInflow::StakeBatch, 100.into());
+        stats.record_inflow(NearLiquidityInflow::StakeTokenValueCompensation, 10.into());
+        stats.record_inflow(NearLiquidityInflow::Earnings, 5.into());
+        stats.record_inflow(NearLiquidityInflow::StakeBatch, 100.into());
+        assert_eq!(stats.added_from_stake_batches(), 200.into());
+        assert_eq!(stats.total_inflows(), 215.into());
+
+        stats.record_outflow(NearLiquidityOutflow::Claim, 50.into());
+        stats.record_outflow(NearLiquidityOutflow::PendingWithdrawal, 40.into());
+        stats.record_outflow(NearLiquidityOutflow::Withdrawal, 20.into());
+        stats.record_outflow(NearLiquidityOutflow::Restake, 5.into());
+        stats.record_outflow(NearLiquidityOutflow::InstantRedeem, 15.into());
+        assert_eq!(stats.consumed_by_claims(), 50.into());
+        assert_eq!(stats.consumed_by_instant_redeems(), 15.into());
+        assert_eq!(stats.total_outflows(), 130.into());
+    }
+}
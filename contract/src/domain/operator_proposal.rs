@@ -0,0 +1,107 @@
+use crate::domain::BlockTimeHeight;
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// max number of operator accounts in addition to the primary operator account
+pub const MAX_OPERATORS: usize = 10;
+
+/// max number of operator proposals that can be pending at any given time
+pub const MAX_PENDING_OPERATOR_PROPOSALS: usize = 20;
+
+/// sensitive operator actions that require multiple operator confirmations - see
+/// [AccessControl](crate::interface::AccessControl)
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub enum OperatorAction {
+    /// JSON serialized [Config](crate::interface::Config) changes along with their validation hash,
+    /// which must still match the current config when the changes are applied
+    UpdateConfig(String, Vec<u8>),
+    /// JSON serialized [Config](crate::interface::Config) changes that are applied without validation
+    ForceUpdateConfig(String),
+    ChangeStakingPool(AccountId),
+    Pause(String),
+    Resume,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct OperatorProposal {
+    action: OperatorAction,
+    proposer: AccountId,
+    /// operator accounts that confirmed the proposal in the order they confirmed - the proposer
+    /// confirms the proposal when it is proposed
+    confirmations: Vec<AccountId>,
+    proposed: BlockTimeHeight,
+}
+
+impl OperatorProposal {
+    pub fn new(action: OperatorAction, proposer: &str) -> Self {
+        Self {
+            action,
+            proposer: proposer.to_string(),
+            confirmations: vec![proposer.to_string()],
+            proposed: BlockTimeHeight::from_env(),
+        }
+    }
+
+    pub fn action(&self) -> &OperatorAction {
+        &self.action
+    }
+
+    pub fn proposer(&self) -> &str {
+        &self.proposer
+    }
+
+    pub fn confirmations(&self) -> &[AccountId] {
+        &self.confirmations
+    }
+
+    pub fn proposed(&self) -> BlockTimeHeight {
+        self.proposed
+    }
+
+    pub fn confirmed_by(&self, account_id: &str) -> bool {
+        self.confirmations.iter().any(|id| id == account_id)
+    }
+
+    pub fn confirm(&mut self, account_id: &str) {
+        if !self.confirmed_by(account_id) {
+            self.confirmations.push(account_id.to_string());
+        }
+    }
+
+    /// counts the confirmations from accounts that are still operators - confirmations from
+    /// accounts that have since been removed as operators do not count
+    pub fn confirmation_count(&self, is_operator: impl Fn(&str) -> bool) -> usize {
+        self.confirmations
+            .iter()
+            .filter(|account_id| is_operator(account_id))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn confirmations() {
+        testing_env!(new_context("bob.near"));
+
+        let mut proposal = OperatorProposal::new(OperatorAction::Resume, "alice.near");
+        assert!(proposal.confirmed_by("alice.near"));
+        proposal.confirm("bob.near");
+        proposal.confirm("bob.near");
+        assert_eq!(
+            proposal.confirmations().to_vec(),
+            vec!["alice.near", "bob.near"]
+        );
+        assert_eq!(proposal.confirmation_count(|_| true), 2);
+        assert_eq!(
+            proposal.confirmation_count(|account_id| account_id != "alice.near"),
+            1
+        );
+    }
+}
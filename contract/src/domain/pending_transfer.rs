@@ -0,0 +1,58 @@
+use crate::domain::{BlockHeight, BlockTimeHeight, YoctoStake};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// Registered when an `ft_transfer_call` is initiated and removed when the transfer is resolved,
+/// either by the `ft_resolve_transfer_call` callback or as a stalled transfer
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PendingTransfer {
+    sender_id: AccountId,
+    receiver_id: AccountId,
+    amount: YoctoStake,
+    initiated: BlockTimeHeight,
+}
+
+impl PendingTransfer {
+    pub fn new(
+        sender_id: &str,
+        receiver_id: &str,
+        amount: YoctoStake,
+        initiated: BlockTimeHeight,
+    ) -> Self {
+        Self {
+            sender_id: sender_id.to_string(),
+            receiver_id: receiver_id.to_string(),
+            amount,
+            initiated,
+        }
+    }
+
+    pub fn sender_id(&self) -> &str {
+        &self.sender_id
+    }
+
+    pub fn receiver_id(&self) -> &str {
+        &self.receiver_id
+    }
+
+    pub fn amount(&self) -> YoctoStake {
+        self.amount
+    }
+
+    pub fn initiated(&self) -> BlockTimeHeight {
+        self.initiated
+    }
+
+    /// the transfer is considered stalled once the timeout has elapsed since the transfer was
+    /// initiated and the transfer has not yet been resolved
+    pub fn is_stalled(&self, block_height: BlockHeight, timeout_blocks: u64) -> bool {
+        block_height.value()
+            >= self
+                .initiated
+                .block_height()
+                .value()
+                .saturating_add(timeout_blocks)
+    }
+}
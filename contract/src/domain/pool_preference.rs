@@ -0,0 +1,142 @@
+use crate::core::Hash;
+use crate::domain::{EpochHeight, YoctoStake};
+use crate::errors::pool_preferences::MAX_POOL_PREFERENCES_EXCEEDED;
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// max number of distinct staking pools that can be tallied per epoch
+/// - bounds the size of the per epoch tally record
+pub const MAX_POOL_PREFERENCES_PER_EPOCH: usize = 32;
+
+/// staking pool preference that the account has signaled for an epoch
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct PoolPreference {
+    /// the staking pool account ID is hashed to keep the account record fixed size - the account ID
+    /// is resolved from the epoch's [PoolPreferenceTallies]
+    pub pool_id: Hash,
+    pub epoch_height: EpochHeight,
+    /// the account STAKE balance when the preference was signaled
+    pub weight: YoctoStake,
+}
+
+impl PoolPreference {
+    /// used to allocate a fully populated instance to measure account storage usage
+    pub(crate) fn template_to_measure_storage_usage() -> Self {
+        Self {
+            pool_id: Hash::default(),
+            epoch_height: 0.into(),
+            weight: 0.into(),
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PoolPreferenceTally {
+    pub pool_id: AccountId,
+    /// total STAKE weight signaled for the pool
+    pub weight: YoctoStake,
+    /// number of accounts that signaled for the pool
+    pub accounts: u32,
+}
+
+/// aggregated staking pool preference signals for an epoch
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct PoolPreferenceTallies(Vec<PoolPreferenceTally>);
+
+impl PoolPreferenceTallies {
+    pub fn tallies(&self) -> &[PoolPreferenceTally] {
+        &self.0
+    }
+
+    /// resolves the staking pool account ID from its hash
+    pub fn pool_id(&self, pool_id: Hash) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|tally| Hash::from(tally.pool_id.as_str()) == pool_id)
+            .map(|tally| tally.pool_id.as_str())
+    }
+
+    /// ## Panics
+    /// if the pool is not yet tallied and [MAX_POOL_PREFERENCES_PER_EPOCH] pools are already tallied
+    pub fn add(&mut self, pool_id: &str, weight: YoctoStake) {
+        match self.0.iter_mut().find(|tally| tally.pool_id == pool_id) {
+            Some(tally) => {
+                tally.weight += weight;
+                tally.accounts += 1;
+            }
+            None => {
+                assert!(
+                    self.0.len() < MAX_POOL_PREFERENCES_PER_EPOCH,
+                    MAX_POOL_PREFERENCES_EXCEEDED
+                );
+                self.0.push(PoolPreferenceTally {
+                    pool_id: pool_id.to_string(),
+                    weight,
+                    accounts: 1,
+                });
+            }
+        }
+    }
+
+    /// removes the account's prior signal - the pool is dropped from the tallies once no accounts
+    /// signal for it
+    pub fn remove(&mut self, pool_id: Hash, weight: YoctoStake) {
+        if let Some(index) = self
+            .0
+            .iter()
+            .position(|tally| Hash::from(tally.pool_id.as_str()) == pool_id)
+        {
+            let tally = &mut self.0[index];
+            tally.weight = tally.weight.value().saturating_sub(weight.value()).into();
+            tally.accounts = tally.accounts.saturating_sub(1);
+            if tally.accounts == 0 {
+                self.0.remove(index);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn add_and_remove() {
+        testing_env!(new_context("alfio-zappala.near"));
+        let mut tallies = PoolPreferenceTallies::default();
+        tallies.add("pool-1.near", 100.into());
+        tallies.add("pool-1.near", 50.into());
+        tallies.add("pool-2.near", 10.into());
+        assert_eq!(tallies.tallies().len(), 2);
+        assert_eq!(tallies.tallies()[0].weight, 150.into());
+        assert_eq!(tallies.tallies()[0].accounts, 2);
+        assert_eq!(
+            tallies.pool_id(Hash::from("pool-2.near")),
+            Some("pool-2.near")
+        );
+
+        tallies.remove(Hash::from("pool-1.near"), 100.into());
+        assert_eq!(tallies.tallies()[0].weight, 50.into());
+        assert_eq!(tallies.tallies()[0].accounts, 1);
+
+        tallies.remove(Hash::from("pool-2.near"), 10.into());
+        assert_eq!(tallies.tallies().len(), 1);
+        assert!(tallies.pool_id(Hash::from("pool-2.near")).is_none());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "max number of staking pools that can be signaled per epoch has been reached"
+    )]
+    fn add_exceeds_max_pools() {
+        testing_env!(new_context("alfio-zappala.near"));
+        let mut tallies = PoolPreferenceTallies::default();
+        for i in 0..=MAX_POOL_PREFERENCES_PER_EPOCH {
+            tallies.add(&format!("pool-{}.near", i), 1.into());
+        }
+    }
+}
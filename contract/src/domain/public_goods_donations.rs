@@ -0,0 +1,33 @@
+use crate::domain::{BlockTimeHeight, YoctoNear};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Running totals for the share of the user account earnings that is donated to the public goods
+/// beneficiary when earnings are distributed.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub struct PublicGoodsDonations {
+    total: YoctoNear,
+    count: u64,
+    last_donation: Option<(YoctoNear, BlockTimeHeight)>,
+}
+
+impl PublicGoodsDonations {
+    /// lifetime total that has been donated
+    pub fn total(&self) -> YoctoNear {
+        self.total
+    }
+
+    /// number of donations that have been made
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn last_donation(&self) -> Option<(YoctoNear, BlockTimeHeight)> {
+        self.last_donation
+    }
+
+    pub fn record(&mut self, amount: YoctoNear, block_time_height: BlockTimeHeight) {
+        self.total += amount;
+        self.count += 1;
+        self.last_donation = Some((amount, block_time_height));
+    }
+}
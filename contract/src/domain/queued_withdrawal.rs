@@ -0,0 +1,57 @@
+use crate::domain::{EpochHeight, YoctoNear};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// NEAR that was withdrawn from an account, but could not be transferred out of the contract
+/// because the contract level per epoch withdrawal limit was reached.
+///
+/// The funds have already been debited from the account and are reserved for the recipient - they
+/// are released starting with the epoch following [epoch_height](QueuedWithdrawal::epoch_height).
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct QueuedWithdrawal {
+    recipient: AccountId,
+    amount: YoctoNear,
+    /// the epoch in which the withdrawal was last queued
+    epoch_height: EpochHeight,
+}
+
+impl QueuedWithdrawal {
+    pub fn new(recipient: AccountId, amount: YoctoNear, epoch_height: EpochHeight) -> Self {
+        Self {
+            recipient,
+            amount,
+            epoch_height,
+        }
+    }
+
+    pub fn recipient(&self) -> &str {
+        &self.recipient
+    }
+
+    pub fn amount(&self) -> YoctoNear {
+        self.amount
+    }
+
+    pub fn epoch_height(&self) -> EpochHeight {
+        self.epoch_height
+    }
+
+    /// queued withdrawals are released starting with the next epoch
+    pub fn is_releasable(&self, epoch_height: EpochHeight) -> bool {
+        epoch_height > self.epoch_height
+    }
+
+    /// adding to the queued withdrawal pushes out its release to the next epoch
+    pub fn add(&mut self, amount: YoctoNear, epoch_height: EpochHeight) {
+        self.amount += amount;
+        self.epoch_height = epoch_height;
+    }
+
+    /// ## Panics
+    /// if the amount is greater than the queued amount
+    pub fn debit(&mut self, amount: YoctoNear) {
+        self.amount -= amount;
+    }
+}
@@ -0,0 +1,409 @@
+use crate::core::U256;
+use crate::domain::{EpochHeight, YoctoNear, YoctoStake};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Accounts whose average STAKE balance over a rebate round meets the tier minimum receive the
+/// tier's percentage of their proportional share of the round's rebate pool.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct RebateTier {
+    pub min_average_stake: YoctoStake,
+    pub rebate_percentage: u8,
+}
+
+/// Contract level state for the STAKE holder fee rebate program.
+///
+/// The owner funds the rebate pool and periodically closes rebate rounds. When a round is closed,
+/// the pool is allocated to the round and accounts can claim their rebate until the next round is
+/// closed - unclaimed rebates are returned to the pool.
+///
+/// Balances are tracked as "STAKE epochs", i.e., the STAKE balance multiplied by the number of
+/// epochs it was held, which enables the average STAKE balance to be computed per round without
+/// having to snapshot every account each epoch.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct RebateProgram {
+    round_id: u64,
+    round_start_epoch_height: EpochHeight,
+    prev_round_start_epoch_height: Option<EpochHeight>,
+    /// total STAKE supply accumulated within the current round
+    total_stake_epochs: u128,
+    /// the epoch up to which [total_stake_epochs](RebateProgram::total_stake_epochs) has been accumulated
+    epoch_height: EpochHeight,
+    /// NEAR funds that will be allocated to the current round when it is closed
+    pool: YoctoNear,
+    /// sorted by `min_average_stake` in ascending order
+    tiers: Vec<RebateTier>,
+}
+
+impl RebateProgram {
+    pub fn new(epoch_height: EpochHeight) -> Self {
+        Self {
+            round_id: 0,
+            round_start_epoch_height: epoch_height,
+            prev_round_start_epoch_height: None,
+            total_stake_epochs: 0,
+            epoch_height,
+            pool: 0.into(),
+            tiers: vec![],
+        }
+    }
+
+    pub fn round_id(&self) -> u64 {
+        self.round_id
+    }
+
+    pub fn round_start_epoch_height(&self) -> EpochHeight {
+        self.round_start_epoch_height
+    }
+
+    pub fn prev_round_start_epoch_height(&self) -> Option<EpochHeight> {
+        self.prev_round_start_epoch_height
+    }
+
+    pub fn total_stake_epochs(&self) -> u128 {
+        self.total_stake_epochs
+    }
+
+    pub fn pool(&self) -> YoctoNear {
+        self.pool
+    }
+
+    pub fn tiers(&self) -> &[RebateTier] {
+        &self.tiers
+    }
+
+    pub fn fund(&mut self, amount: YoctoNear) {
+        self.pool += amount;
+    }
+
+    /// ## Panics
+    /// - if tiers are not sorted by `min_average_stake` in strictly ascending order
+    /// - if any tier rebate percentage is not within 1-100
+    pub fn set_tiers(&mut self, tiers: Vec<RebateTier>) {
+        assert!(
+            tiers
+                .iter()
+                .all(|tier| tier.rebate_percentage > 0 && tier.rebate_percentage <= 100),
+            "rebate_percentage must be within 1-100"
+        );
+        assert!(
+            tiers
+                .windows(2)
+                .all(|pair| pair[0].min_average_stake < pair[1].min_average_stake),
+            "rebate tiers must be sorted by min_average_stake in ascending order"
+        );
+        self.tiers = tiers;
+    }
+
+    /// accumulates the total STAKE supply up to the specified epoch
+    /// - must be invoked before the total STAKE supply changes
+    pub fn accumulate(&mut self, total_stake: YoctoStake, epoch_height: EpochHeight) {
+        if epoch_height > self.epoch_height {
+            self.total_stake_epochs +=
+                total_stake.value() * (epoch_height.value() - self.epoch_height.value()) as u128;
+            self.epoch_height = epoch_height;
+        }
+    }
+
+    /// closes the current round, allocating the rebate pool to the round, and starts the next round
+    /// - if no STAKE was held during the round, then the pool is carried over to the next round
+    pub fn close_round(
+        &mut self,
+        total_stake: YoctoStake,
+        epoch_height: EpochHeight,
+    ) -> RebateRound {
+        self.accumulate(total_stake, epoch_height);
+        let amount = if self.total_stake_epochs > 0 {
+            self.pool
+        } else {
+            0.into()
+        };
+        let round = RebateRound {
+            id: self.round_id,
+            start_epoch_height: self.round_start_epoch_height,
+            end_epoch_height: epoch_height,
+            total_stake_epochs: self.total_stake_epochs,
+            amount,
+            claimed: 0.into(),
+            tiers: self.tiers.clone(),
+        };
+
+        self.pool -= amount;
+        self.round_id += 1;
+        self.prev_round_start_epoch_height = Some(self.round_start_epoch_height);
+        self.round_start_epoch_height = epoch_height;
+        self.total_stake_epochs = 0;
+        round
+    }
+}
+
+/// Closed rebate round
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct RebateRound {
+    id: u64,
+    start_epoch_height: EpochHeight,
+    end_epoch_height: EpochHeight,
+    total_stake_epochs: u128,
+    /// NEAR funds that were allocated to the round
+    amount: YoctoNear,
+    claimed: YoctoNear,
+    tiers: Vec<RebateTier>,
+}
+
+impl RebateRound {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn start_epoch_height(&self) -> EpochHeight {
+        self.start_epoch_height
+    }
+
+    pub fn end_epoch_height(&self) -> EpochHeight {
+        self.end_epoch_height
+    }
+
+    pub fn total_stake_epochs(&self) -> u128 {
+        self.total_stake_epochs
+    }
+
+    pub fn amount(&self) -> YoctoNear {
+        self.amount
+    }
+
+    pub fn claimed(&self) -> YoctoNear {
+        self.claimed
+    }
+
+    pub fn unclaimed(&self) -> YoctoNear {
+        self.amount - self.claimed
+    }
+
+    pub fn tiers(&self) -> &[RebateTier] {
+        &self.tiers
+    }
+
+    pub fn average_stake(&self, stake_epochs: u128) -> YoctoStake {
+        let epochs = self.end_epoch_height.value() - self.start_epoch_height.value();
+        if epochs == 0 {
+            return 0.into();
+        }
+        (stake_epochs / epochs as u128).into()
+    }
+
+    /// returns the highest tier that the average STAKE balance qualifies for
+    pub fn tier(&self, stake_epochs: u128) -> Option<RebateTier> {
+        let average_stake = self.average_stake(stake_epochs);
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| average_stake >= tier.min_average_stake)
+            .copied()
+    }
+
+    /// computes the account's rebate based on its proportional share of the round's STAKE epochs
+    pub fn compute_rebate(&self, stake_epochs: u128) -> YoctoNear {
+        if self.total_stake_epochs == 0 {
+            return 0.into();
+        }
+        match self.tier(stake_epochs) {
+            None => 0.into(),
+            Some(tier) => {
+                let rebate = (U256::from(self.amount.value()) * U256::from(stake_epochs)
+                    / U256::from(self.total_stake_epochs)
+                    * U256::from(tier.rebate_percentage)
+                    / U256::from(100))
+                .as_u128();
+                rebate.min(self.unclaimed().value()).into()
+            }
+        }
+    }
+
+    pub fn record_claim(&mut self, amount: YoctoNear) {
+        self.claimed += amount;
+    }
+}
+
+/// Account level STAKE balance accumulator for the rebate program
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct RebateAccumulator {
+    round_id: u64,
+    /// the epoch up to which [stake_epochs](RebateAccumulator::stake_epochs) has been accumulated
+    epoch_height: EpochHeight,
+    /// STAKE balance accumulated within the round
+    stake_epochs: u128,
+    /// STAKE balance accumulated within the most recently closed round that has not yet been claimed
+    closed_round: Option<ClosedRoundStakeEpochs>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ClosedRoundStakeEpochs {
+    pub round_id: u64,
+    pub stake_epochs: u128,
+}
+
+impl RebateAccumulator {
+    pub fn new(program: &RebateProgram, epoch_height: EpochHeight) -> Self {
+        Self {
+            round_id: program.round_id,
+            epoch_height,
+            stake_epochs: 0,
+            closed_round: None,
+        }
+    }
+
+    pub fn stake_epochs(&self) -> u128 {
+        self.stake_epochs
+    }
+
+    /// accumulates the account STAKE balance up to the specified epoch
+    /// - the balance is assumed to be unchanged since the accumulator was last updated, i.e., the
+    ///   accumulator must be updated before the account STAKE balance changes
+    /// - if the round that was being tracked has been closed, then the STAKE balance accumulated
+    ///   within the most recently closed round is retained to be claimed
+    pub fn accumulate(
+        &mut self,
+        balance: YoctoStake,
+        program: &RebateProgram,
+        epoch_height: EpochHeight,
+    ) {
+        if self.round_id != program.round_id {
+            let round_start = program.round_start_epoch_height.value();
+            let prev_round_start = program
+                .prev_round_start_epoch_height
+                .map_or(round_start, |epoch_height| epoch_height.value());
+            let stake_epochs = if self.round_id + 1 == program.round_id {
+                self.stake_epochs
+                    + balance.value() * (round_start - self.epoch_height.value()) as u128
+            } else {
+                // the balance was unchanged throughout the most recently closed round
+                balance.value()
+                    * (round_start - prev_round_start.max(self.epoch_height.value())) as u128
+            };
+            self.closed_round = if stake_epochs > 0 {
+                Some(ClosedRoundStakeEpochs {
+                    round_id: program.round_id - 1,
+                    stake_epochs,
+                })
+            } else {
+                None
+            };
+            self.round_id = program.round_id;
+            self.epoch_height = program.round_start_epoch_height;
+            self.stake_epochs = 0;
+        }
+
+        if epoch_height > self.epoch_height {
+            self.stake_epochs +=
+                balance.value() * (epoch_height.value() - self.epoch_height.value()) as u128;
+            self.epoch_height = epoch_height;
+        }
+    }
+
+    /// returns the STAKE balance accumulated within the most recently closed round, if it has not
+    /// yet been claimed
+    pub fn claimable_round(&self, program: &RebateProgram) -> Option<ClosedRoundStakeEpochs> {
+        self.closed_round
+            .filter(|closed_round| closed_round.round_id + 1 == program.round_id)
+    }
+
+    /// returns true if no STAKE balance has been accumulated
+    pub fn is_empty(&self) -> bool {
+        self.stake_epochs == 0 && self.closed_round.is_none()
+    }
+
+    pub fn clear_closed_round(&mut self) {
+        self.closed_round = None;
+    }
+
+    /// used to allocate a fully populated instance to measure account storage usage
+    pub(crate) fn template_to_measure_storage_usage() -> Self {
+        Self {
+            round_id: 0,
+            epoch_height: 0.into(),
+            stake_epochs: 0,
+            closed_round: Some(ClosedRoundStakeEpochs {
+                round_id: 0,
+                stake_epochs: 0,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn account_share_of_closed_round() {
+        let mut program = RebateProgram::new(10.into());
+        program.set_tiers(vec![
+            RebateTier {
+                min_average_stake: 100.into(),
+                rebate_percentage: 50,
+            },
+            RebateTier {
+                min_average_stake: 1000.into(),
+                rebate_percentage: 100,
+            },
+        ]);
+        program.fund(1000.into());
+
+        // account holds 1000 STAKE for the entire round out of a total supply of 4000
+        let mut account = RebateAccumulator::new(&program, 10.into());
+        program.accumulate(4000.into(), 12.into());
+        account.accumulate(1000.into(), &program, 12.into());
+        let round = program.close_round(4000.into(), 14.into());
+        assert_eq!(round.amount(), 1000.into());
+        assert_eq!(round.total_stake_epochs(), 16000);
+        assert_eq!(program.pool(), 0.into());
+        assert_eq!(program.round_id(), 1);
+
+        account.accumulate(1000.into(), &program, 15.into());
+        let closed_round = account.claimable_round(&program).unwrap();
+        assert_eq!(closed_round.round_id, 0);
+        assert_eq!(closed_round.stake_epochs, 4000);
+        assert_eq!(account.stake_epochs(), 1000);
+        assert_eq!(round.average_stake(closed_round.stake_epochs), 1000.into());
+        assert_eq!(round.compute_rebate(closed_round.stake_epochs), 250.into());
+
+        // account with a lower average balance qualifies for the lower tier
+        assert_eq!(round.compute_rebate(2000), 62.into());
+        // below the min tier
+        assert_eq!(round.compute_rebate(100), 0.into());
+    }
+
+    #[test]
+    fn closed_round_expires_when_next_round_is_closed() {
+        let mut program = RebateProgram::new(10.into());
+        let mut account = RebateAccumulator::new(&program, 10.into());
+        program.close_round(1000.into(), 12.into());
+        program.close_round(1000.into(), 15.into());
+
+        // the balance was unchanged throughout the most recently closed round
+        account.accumulate(100.into(), &program, 16.into());
+        let closed_round = account.claimable_round(&program).unwrap();
+        assert_eq!(closed_round.round_id, 1);
+        assert_eq!(closed_round.stake_epochs, 300);
+
+        program.close_round(1000.into(), 17.into());
+        assert!(account.claimable_round(&program).is_none());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "rebate tiers must be sorted by min_average_stake in ascending order"
+    )]
+    fn set_tiers_unsorted() {
+        let mut program = RebateProgram::new(10.into());
+        program.set_tiers(vec![
+            RebateTier {
+                min_average_stake: 1000.into(),
+                rebate_percentage: 50,
+            },
+            RebateTier {
+                min_average_stake: 100.into(),
+                rebate_percentage: 100,
+            },
+        ]);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::domain::YoctoNear;
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId, PublicKey,
+};
+
+/// max number of delegate keys that an account can register
+pub const MAX_DELEGATE_KEYS: usize = 5;
+
+/// max number of receiver contracts that an account can authorize
+pub const MAX_AUTHORIZED_RECEIVERS: usize = 10;
+
+/// Account scoped allowlist of receiver contracts for `ft_transfer_call`.
+///
+/// The allowlist is only enforced for transfers that are signed by one of the account's delegate
+/// keys, i.e., access keys that the account has handed out, e.g., to a custodian or to a session.
+/// Transfers that are signed by any other account key are not restricted.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Default)]
+pub struct ReceiverAuthorizations {
+    delegate_keys: Vec<PublicKey>,
+    receivers: Vec<AccountId>,
+    /// storage fee that the account has paid for the record - refunded as storage is freed
+    storage_escrow: YoctoNear,
+}
+
+impl ReceiverAuthorizations {
+    pub fn delegate_keys(&self) -> &[PublicKey] {
+        &self.delegate_keys
+    }
+
+    pub fn receivers(&self) -> &[AccountId] {
+        &self.receivers
+    }
+
+    pub fn storage_escrow(&self) -> YoctoNear {
+        self.storage_escrow
+    }
+
+    pub fn set_storage_escrow(&mut self, storage_escrow: YoctoNear) {
+        self.storage_escrow = storage_escrow;
+    }
+
+    /// returns true if there are no delegate keys and no authorized receivers
+    pub fn is_empty(&self) -> bool {
+        self.delegate_keys.is_empty() && self.receivers.is_empty()
+    }
+
+    /// returns false if the key is already registered
+    pub fn add_delegate_key(&mut self, public_key: PublicKey) -> bool {
+        if self.is_delegate_key(&public_key) {
+            return false;
+        }
+        self.delegate_keys.push(public_key);
+        true
+    }
+
+    /// returns false if the key was not registered
+    pub fn remove_delegate_key(&mut self, public_key: &[u8]) -> bool {
+        let len = self.delegate_keys.len();
+        self.delegate_keys
+            .retain(|key| key.as_slice() != public_key);
+        self.delegate_keys.len() != len
+    }
+
+    /// returns false if the receiver is already authorized
+    pub fn authorize(&mut self, receiver_id: &str) -> bool {
+        if self.is_authorized(receiver_id) {
+            return false;
+        }
+        self.receivers.push(receiver_id.to_string());
+        true
+    }
+
+    /// returns false if the receiver was not authorized
+    pub fn revoke(&mut self, receiver_id: &str) -> bool {
+        let len = self.receivers.len();
+        self.receivers.retain(|id| id != receiver_id);
+        self.receivers.len() != len
+    }
+
+    pub fn is_delegate_key(&self, public_key: &[u8]) -> bool {
+        self.delegate_keys
+            .iter()
+            .any(|key| key.as_slice() == public_key)
+    }
+
+    pub fn is_authorized(&self, receiver_id: &str) -> bool {
+        self.receivers.iter().any(|id| id == receiver_id)
+    }
+
+    /// returns true if a transfer call to the receiver that is signed with the specified key is
+    /// permitted
+    pub fn permits(&self, signer_public_key: &[u8], receiver_id: &str) -> bool {
+        !self.is_delegate_key(signer_public_key) || self.is_authorized(receiver_id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn permits_only_restricts_delegate_keys() {
+        let mut authorizations = ReceiverAuthorizations::default();
+        let delegate_key = vec![0; 33];
+        let owner_key = vec![1; 33];
+        assert!(authorizations.permits(&delegate_key, "dex.near"));
+
+        assert!(authorizations.add_delegate_key(delegate_key.clone()));
+        assert!(!authorizations.add_delegate_key(delegate_key.clone()));
+        assert!(!authorizations.permits(&delegate_key, "dex.near"));
+        assert!(authorizations.permits(&owner_key, "dex.near"));
+
+        assert!(authorizations.authorize("dex.near"));
+        assert!(!authorizations.authorize("dex.near"));
+        assert!(authorizations.permits(&delegate_key, "dex.near"));
+        assert!(!authorizations.permits(&delegate_key, "farm.near"));
+
+        assert!(authorizations.revoke("dex.near"));
+        assert!(!authorizations.revoke("dex.near"));
+        assert!(!authorizations.permits(&delegate_key, "dex.near"));
+
+        assert!(authorizations.remove_delegate_key(&delegate_key));
+        assert!(!authorizations.remove_delegate_key(&delegate_key));
+        assert!(authorizations.permits(&delegate_key, "dex.near"));
+        assert!(authorizations.is_empty());
+    }
+}
@@ -0,0 +1,177 @@
+use crate::domain::{EpochHeight, YoctoStake};
+use crate::errors::staking_service::REDEEM_LIMIT_EXCEEDED;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Limits how much STAKE an account can redeem per epoch. The limit is set by the account on itself,
+/// e.g., custodial hot wallets can use it to enforce internal risk policies at the contract level.
+///
+/// Lowering the limit takes effect immediately, but raising the limit is delayed - see
+/// [RedeemLimitIncrease]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct RedeemLimit {
+    max_redeem_per_epoch: YoctoStake,
+    /// the epoch in which [redeemed](RedeemLimit::redeemed) was tracked
+    epoch_height: EpochHeight,
+    /// how much STAKE was redeemed within the epoch
+    redeemed: YoctoStake,
+    pending_increase: Option<RedeemLimitIncrease>,
+}
+
+/// raising the redeem limit is time locked
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct RedeemLimitIncrease {
+    /// None means the limit will be removed
+    pub max_redeem_per_epoch: Option<YoctoStake>,
+    /// the limit increase takes effect starting with this epoch
+    pub effective_epoch_height: EpochHeight,
+}
+
+impl RedeemLimit {
+    pub fn new(max_redeem_per_epoch: YoctoStake, epoch_height: EpochHeight) -> Self {
+        Self {
+            max_redeem_per_epoch,
+            epoch_height,
+            redeemed: 0.into(),
+            pending_increase: None,
+        }
+    }
+
+    pub fn max_redeem_per_epoch(&self) -> YoctoStake {
+        self.max_redeem_per_epoch
+    }
+
+    pub fn pending_increase(&self) -> Option<RedeemLimitIncrease> {
+        self.pending_increase
+    }
+
+    /// returns how much STAKE was redeemed within the specified epoch
+    pub fn redeemed(&self, epoch_height: EpochHeight) -> YoctoStake {
+        if self.epoch_height == epoch_height {
+            self.redeemed
+        } else {
+            0.into()
+        }
+    }
+
+    /// returns how much more STAKE can be redeemed within the specified epoch
+    pub fn available(&self, epoch_height: EpochHeight) -> YoctoStake {
+        self.max_redeem_per_epoch
+            .value()
+            .saturating_sub(self.redeemed(epoch_height).value())
+            .into()
+    }
+
+    /// ## Panics
+    /// if the amount would exceed the max redeem amount for the epoch
+    pub fn record_redeem(&mut self, amount: YoctoStake, epoch_height: EpochHeight) {
+        assert!(
+            amount <= self.available(epoch_height),
+            REDEEM_LIMIT_EXCEEDED
+        );
+        self.redeemed = self.redeemed(epoch_height) + amount;
+        self.epoch_height = epoch_height;
+    }
+
+    /// lowering the limit takes effect immediately and cancels any pending increase
+    pub fn lower(&mut self, max_redeem_per_epoch: YoctoStake) {
+        assert!(
+            max_redeem_per_epoch <= self.max_redeem_per_epoch,
+            "redeem limit can only be lowered"
+        );
+        self.max_redeem_per_epoch = max_redeem_per_epoch;
+        self.pending_increase = None;
+    }
+
+    pub fn schedule_increase(&mut self, increase: RedeemLimitIncrease) {
+        self.pending_increase = Some(increase);
+    }
+
+    /// if the pending increase has taken effect, then it is applied.
+    ///
+    /// Returns None if the limit has been removed.
+    pub fn apply_pending_increase(self, epoch_height: EpochHeight) -> Option<Self> {
+        match self.pending_increase {
+            Some(increase) if epoch_height >= increase.effective_epoch_height => increase
+                .max_redeem_per_epoch
+                .map(|max_redeem_per_epoch| Self {
+                    max_redeem_per_epoch,
+                    pending_increase: None,
+                    ..self
+                }),
+            _ => Some(self),
+        }
+    }
+
+    /// used to allocate a fully populated instance to measure account storage usage
+    pub(crate) fn template_to_measure_storage_usage() -> Self {
+        Self {
+            max_redeem_per_epoch: 0.into(),
+            epoch_height: 0.into(),
+            redeemed: 0.into(),
+            pending_increase: Some(RedeemLimitIncrease {
+                max_redeem_per_epoch: Some(0.into()),
+                effective_epoch_height: 0.into(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn record_redeem_resets_per_epoch() {
+        let mut limit = RedeemLimit::new(100.into(), 10.into());
+        limit.record_redeem(60.into(), 10.into());
+        assert_eq!(limit.available(10.into()), 40.into());
+        assert_eq!(limit.available(11.into()), 100.into());
+
+        limit.record_redeem(100.into(), 11.into());
+        assert_eq!(limit.redeemed(11.into()), 100.into());
+        assert_eq!(limit.available(11.into()), 0.into());
+    }
+
+    #[test]
+    #[should_panic(expected = "account redeem limit for the current epoch would be exceeded")]
+    fn record_redeem_exceeds_limit() {
+        let mut limit = RedeemLimit::new(100.into(), 10.into());
+        limit.record_redeem(60.into(), 10.into());
+        limit.record_redeem(41.into(), 10.into());
+    }
+
+    #[test]
+    fn lower_cancels_pending_increase() {
+        let mut limit = RedeemLimit::new(100.into(), 10.into());
+        limit.schedule_increase(RedeemLimitIncrease {
+            max_redeem_per_epoch: Some(200.into()),
+            effective_epoch_height: 14.into(),
+        });
+        limit.lower(50.into());
+        assert_eq!(limit.max_redeem_per_epoch(), 50.into());
+        assert!(limit.pending_increase().is_none());
+    }
+
+    #[test]
+    fn apply_pending_increase() {
+        let mut limit = RedeemLimit::new(100.into(), 10.into());
+        limit.schedule_increase(RedeemLimitIncrease {
+            max_redeem_per_epoch: Some(200.into()),
+            effective_epoch_height: 14.into(),
+        });
+
+        let not_yet_effective = limit.apply_pending_increase(13.into()).unwrap();
+        assert_eq!(not_yet_effective.max_redeem_per_epoch(), 100.into());
+
+        let effective = limit.apply_pending_increase(14.into()).unwrap();
+        assert_eq!(effective.max_redeem_per_epoch(), 200.into());
+        assert!(effective.pending_increase().is_none());
+
+        // remove the limit
+        limit.schedule_increase(RedeemLimitIncrease {
+            max_redeem_per_epoch: None,
+            effective_epoch_height: 14.into(),
+        });
+        assert!(limit.apply_pending_increase(14.into()).is_none());
+    }
+}
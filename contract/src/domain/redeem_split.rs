@@ -0,0 +1,138 @@
+use crate::domain::{BatchId, BlockTimeHeight, YoctoStake};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// max number of redeem splits that can be queued at any point in time
+/// - portions are redeemed when the redeem stake batch run starts, which bounds the gas that is
+///   used by the batch run
+pub const MAX_REDEEM_SPLITS: usize = 20;
+
+/// max number of redeem stake batches that a redemption can be spread across
+pub const MAX_REDEEM_SPLIT_BATCHES: u8 = 10;
+
+/// STAKE redemption that is spread across successive redeem stake batches.
+///
+/// The STAKE is not escrowed - each portion is redeemed from the account STAKE balance when the
+/// redeem stake batch run starts.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct RedeemSplit {
+    account_id: AccountId,
+    /// STAKE that is still scheduled to be redeemed
+    remaining: YoctoStake,
+    /// number of redeem stake batches that the remaining STAKE is spread across
+    remaining_batches: u8,
+    /// the redeem stake batch that the last portion was redeemed into
+    last_batch_id: Option<BatchId>,
+    created: BlockTimeHeight,
+}
+
+impl RedeemSplit {
+    pub fn new(
+        account_id: AccountId,
+        amount: YoctoStake,
+        batches: u8,
+        created: BlockTimeHeight,
+    ) -> Self {
+        Self {
+            account_id,
+            remaining: amount,
+            remaining_batches: batches.max(1),
+            last_batch_id: None,
+            created,
+        }
+    }
+
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn remaining(&self) -> YoctoStake {
+        self.remaining
+    }
+
+    pub fn remaining_batches(&self) -> u8 {
+        self.remaining_batches
+    }
+
+    pub fn last_batch_id(&self) -> Option<BatchId> {
+        self.last_batch_id
+    }
+
+    pub fn created(&self) -> BlockTimeHeight {
+        self.created
+    }
+
+    /// the remaining STAKE is spread evenly across the remaining batches - rounded up, thus the
+    /// last portion absorbs the rounding
+    pub fn next_portion(&self) -> YoctoStake {
+        let remaining = self.remaining.value();
+        let batches = self.remaining_batches as u128;
+        let portion = remaining / batches;
+        if remaining % batches > 0 {
+            (portion + 1).into()
+        } else {
+            portion.into()
+        }
+    }
+
+    /// records that the portion was redeemed into the specified batch
+    /// - if the portion was capped, e.g., by the account redeem limit, then the last batch carries
+    ///   over the STAKE that is still remaining into the following batches
+    pub fn portion_redeemed(&mut self, amount: YoctoStake, batch_id: BatchId) {
+        self.remaining -= amount;
+        if self.remaining_batches > 1 {
+            self.remaining_batches -= 1;
+        }
+        self.last_batch_id = Some(batch_id);
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.remaining.value() == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn portions_are_spread_across_the_batches() {
+        testing_env!(new_context("bob.near"));
+        let mut split = RedeemSplit::new(
+            "bob.near".to_string(),
+            10.into(),
+            3,
+            BlockTimeHeight::from_env(),
+        );
+
+        let mut portions = vec![];
+        let mut batch_id = 1_u128;
+        while !split.is_complete() {
+            let portion = split.next_portion();
+            portions.push(portion.value());
+            split.portion_redeemed(portion, batch_id.into());
+            batch_id += 1;
+        }
+        assert_eq!(portions, vec![4, 3, 3]);
+        assert_eq!(split.last_batch_id(), Some(3.into()));
+    }
+
+    #[test]
+    fn capped_portion_carries_over_to_the_next_batch() {
+        testing_env!(new_context("bob.near"));
+        let mut split = RedeemSplit::new(
+            "bob.near".to_string(),
+            10.into(),
+            1,
+            BlockTimeHeight::from_env(),
+        );
+        split.portion_redeemed(6.into(), 1.into());
+        assert!(!split.is_complete());
+        assert_eq!(split.remaining_batches(), 1);
+        assert_eq!(split.next_portion(), 4.into());
+    }
+}
@@ -1,7 +1,11 @@
 use crate::domain::{
-    BatchId, RedeemStakeBatchReceipt, StakeTokenValue, TimestampedStakeBalance, YoctoStake,
+    BatchId, BatchKind, RedeemStakeBatchReceipt, StakeTokenValue, TimestampedStakeBalance,
+    YoctoStake,
+};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
 };
-use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 
 #[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
 pub struct RedeemStakeBatch {
@@ -27,6 +31,15 @@ impl RedeemStakeBatch {
         self.balance
     }
 
+    /// batches that were persisted before the [BatchKind] was encoded into the batch ID carry
+    /// untagged IDs, which would otherwise be decoded as stake batch IDs
+    pub fn with_tagged_batch_id(self) -> Self {
+        Self {
+            batch_id: self.batch_id.with_kind(BatchKind::Redeem),
+            ..self
+        }
+    }
+
     pub fn add(&mut self, amount: YoctoStake) {
         self.balance.credit(amount)
     }
@@ -36,7 +49,11 @@ impl RedeemStakeBatch {
         self.balance.debit(amount)
     }
 
-    pub fn create_receipt(&self, stake_token_value: StakeTokenValue) -> RedeemStakeBatchReceipt {
-        RedeemStakeBatchReceipt::new(self.balance.amount(), stake_token_value)
+    pub fn create_receipt(
+        &self,
+        stake_token_value: StakeTokenValue,
+        staking_pool_id: AccountId,
+    ) -> RedeemStakeBatchReceipt {
+        RedeemStakeBatchReceipt::new(self.balance.amount(), stake_token_value, staking_pool_id)
     }
 }
@@ -4,20 +4,27 @@ use crate::{
 };
 use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
-    env,
+    env, AccountId,
 };
 
-#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
 pub struct RedeemStakeBatchReceipt {
     redeemed_stake: YoctoStake,
     stake_token_value: StakeTokenValue,
+    /// the staking pool that the batch was unstaked from
+    staking_pool_id: AccountId,
 }
 
 impl RedeemStakeBatchReceipt {
-    pub fn new(redeemed_stake: YoctoStake, stake_token_value: StakeTokenValue) -> Self {
+    pub fn new(
+        redeemed_stake: YoctoStake,
+        stake_token_value: StakeTokenValue,
+        staking_pool_id: AccountId,
+    ) -> Self {
         Self {
             redeemed_stake,
             stake_token_value,
+            staking_pool_id,
         }
     }
 
@@ -33,6 +40,10 @@ impl RedeemStakeBatchReceipt {
         self.stake_token_value
     }
 
+    pub fn staking_pool_id(&self) -> &str {
+        &self.staking_pool_id
+    }
+
     /// returns the epoch within which the unstaked NEAR funds will be available for withdrawal from
     /// the staking pool
     pub fn unstaked_near_withdrawal_availability(&self) -> EpochHeight {
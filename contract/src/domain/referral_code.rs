@@ -0,0 +1,151 @@
+use crate::domain::{BlockTimeHeight, EpochHeight, YoctoNear};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// max number of referral codes that can be registered at any point in time
+/// - the code index is stored in the contract state, which is loaded on every contract call
+pub const MAX_REFERRAL_CODES: usize = 100;
+
+pub const MIN_REFERRAL_CODE_LEN: usize = 3;
+pub const MAX_REFERRAL_CODE_LEN: usize = 32;
+
+/// number of epochs a referral code remains valid after it is registered or renewed
+/// - epochs are ~12 hours, i.e., ~180 days
+pub const REFERRAL_CODE_TTL_EPOCHS: u64 = 360;
+
+/// Short code that maps to a partner account, which lets frontends attribute deposits to the
+/// partner without passing raw account IDs.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ReferralCode {
+    code: String,
+    account_id: AccountId,
+    registered: BlockTimeHeight,
+    expires_at: EpochHeight,
+    /// NEAR that was paid by the partner for the code storage - refunded when the code is
+    /// unregistered or reclaimed after it expires
+    storage_fee: YoctoNear,
+    deposits: u64,
+    referred_near: YoctoNear,
+}
+
+impl ReferralCode {
+    pub fn new(code: String, account_id: &str, registered: BlockTimeHeight) -> Self {
+        Self {
+            code,
+            account_id: account_id.to_string(),
+            registered,
+            expires_at: registered.epoch_height() + REFERRAL_CODE_TTL_EPOCHS,
+            storage_fee: 0.into(),
+            deposits: 0,
+            referred_near: 0.into(),
+        }
+    }
+
+    /// codes must be [MIN_REFERRAL_CODE_LEN]-[MAX_REFERRAL_CODE_LEN] characters long and may only
+    /// contain lowercase ASCII letters, digits, `-`, and `_`
+    pub fn is_valid_code(code: &str) -> bool {
+        code.len() >= MIN_REFERRAL_CODE_LEN
+            && code.len() <= MAX_REFERRAL_CODE_LEN
+            && code
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn registered(&self) -> BlockTimeHeight {
+        self.registered
+    }
+
+    /// the code is valid through the end of the preceding epoch
+    pub fn expires_at(&self) -> EpochHeight {
+        self.expires_at
+    }
+
+    pub fn is_expired(&self, epoch_height: EpochHeight) -> bool {
+        epoch_height >= self.expires_at
+    }
+
+    pub fn storage_fee(&self) -> YoctoNear {
+        self.storage_fee
+    }
+
+    pub fn set_storage_fee(&mut self, storage_fee: YoctoNear) {
+        self.storage_fee = storage_fee;
+    }
+
+    pub fn deposits(&self) -> u64 {
+        self.deposits
+    }
+
+    pub fn referred_near(&self) -> YoctoNear {
+        self.referred_near
+    }
+
+    /// extends the code expiry by [REFERRAL_CODE_TTL_EPOCHS] from the current epoch
+    pub fn renew(&mut self, block_time_height: BlockTimeHeight) {
+        self.expires_at = block_time_height.epoch_height() + REFERRAL_CODE_TTL_EPOCHS;
+    }
+
+    pub fn record_deposit(&mut self, amount: YoctoNear) {
+        self.deposits += 1;
+        self.referred_near += amount;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    fn block_time_height(epoch_height: u64) -> BlockTimeHeight {
+        let mut context = new_context("bob.near");
+        context.epoch_height = epoch_height;
+        testing_env!(context);
+        BlockTimeHeight::from_env()
+    }
+
+    #[test]
+    fn code_validation() {
+        assert!(ReferralCode::is_valid_code("abc"));
+        assert!(ReferralCode::is_valid_code("partner-1_near"));
+        assert!(!ReferralCode::is_valid_code("ab"));
+        assert!(!ReferralCode::is_valid_code("Partner"));
+        assert!(!ReferralCode::is_valid_code("partner.near"));
+        assert!(!ReferralCode::is_valid_code(
+            &"a".repeat(MAX_REFERRAL_CODE_LEN + 1)
+        ));
+    }
+
+    #[test]
+    fn expiry_and_renewal() {
+        let mut code = ReferralCode::new("abc".to_string(), "partner.near", block_time_height(10));
+        assert_eq!(
+            code.expires_at(),
+            EpochHeight(10 + REFERRAL_CODE_TTL_EPOCHS)
+        );
+        assert!(!code.is_expired(EpochHeight(9 + REFERRAL_CODE_TTL_EPOCHS)));
+        assert!(code.is_expired(EpochHeight(10 + REFERRAL_CODE_TTL_EPOCHS)));
+
+        code.renew(block_time_height(20));
+        assert!(!code.is_expired(EpochHeight(10 + REFERRAL_CODE_TTL_EPOCHS)));
+    }
+
+    #[test]
+    fn record_deposit() {
+        let mut code = ReferralCode::new("abc".to_string(), "partner.near", block_time_height(10));
+        code.record_deposit(100.into());
+        code.record_deposit(50.into());
+        assert_eq!(code.deposits(), 2);
+        assert_eq!(code.referred_near(), 150.into());
+    }
+}
@@ -0,0 +1,76 @@
+use crate::core::U256;
+use crate::domain::YoctoNear;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Fraction of the staking rewards that the staking pool keeps as its fee
+/// - mirrors the `RewardFeeFraction` that is returned by the staking pool `get_reward_fee_fraction`
+///   view method
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct RewardFeeFraction {
+    numerator: u32,
+    denominator: u32,
+}
+
+impl RewardFeeFraction {
+    /// ## Panics
+    /// if the denominator is zero or the fraction is greater than 1
+    pub fn new(numerator: u32, denominator: u32) -> Self {
+        assert!(
+            denominator != 0 && numerator <= denominator,
+            "invalid staking pool reward fee fraction: {}/{}",
+            numerator,
+            denominator
+        );
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+
+    pub fn numerator(&self) -> u32 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> u32 {
+        self.denominator
+    }
+
+    /// returns the share of the specified rewards that is kept by the staking pool
+    pub fn fee(&self, rewards: YoctoNear) -> YoctoNear {
+        (U256::from(rewards.value()) * U256::from(self.numerator) / U256::from(self.denominator))
+            .as_u128()
+            .into()
+    }
+
+    /// returns the share of the specified rewards that is passed on to the stakers
+    pub fn net_rewards(&self, rewards: YoctoNear) -> YoctoNear {
+        rewards - self.fee(rewards)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn net_rewards() {
+        let fee = RewardFeeFraction::new(10, 100);
+        assert_eq!(fee.fee(1000.into()), 100.into());
+        assert_eq!(fee.net_rewards(1000.into()), 900.into());
+
+        let no_fee = RewardFeeFraction::new(0, 1);
+        assert_eq!(no_fee.net_rewards(1000.into()), 1000.into());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid staking pool reward fee fraction: 1/0")]
+    fn zero_denominator() {
+        RewardFeeFraction::new(1, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid staking pool reward fee fraction: 2/1")]
+    fn fraction_greater_than_one() {
+        RewardFeeFraction::new(2, 1);
+    }
+}
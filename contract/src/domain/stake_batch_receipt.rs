@@ -8,19 +8,31 @@
 //! STAKE token value needs to be computed.
 
 use crate::domain::{StakeTokenValue, YoctoNear, YoctoStake};
-use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
 
-#[derive(BorshSerialize, BorshDeserialize, Copy, Clone, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
 pub struct StakeBatchReceipt {
     staked_near: YoctoNear,
     stake_token_value: StakeTokenValue,
+    /// the staking pool that the batch was staked with
+    /// - receipts remain attributable to the validator that generated them after a staking pool
+    ///   migration
+    staking_pool_id: AccountId,
 }
 
 impl StakeBatchReceipt {
-    pub fn new(staked_near: YoctoNear, stake_token_value: StakeTokenValue) -> Self {
+    pub fn new(
+        staked_near: YoctoNear,
+        stake_token_value: StakeTokenValue,
+        staking_pool_id: AccountId,
+    ) -> Self {
         Self {
             staked_near,
             stake_token_value,
+            staking_pool_id,
         }
     }
 
@@ -37,6 +49,16 @@ impl StakeBatchReceipt {
         self.stake_token_value
     }
 
+    /// re-prices the unclaimed NEAR on the receipt - used when the batch was minted at a
+    /// provisional STAKE token value and the staking pool balances have since been verified
+    pub fn set_stake_token_value(&mut self, stake_token_value: StakeTokenValue) {
+        self.stake_token_value = stake_token_value;
+    }
+
+    pub fn staking_pool_id(&self) -> &str {
+        &self.staking_pool_id
+    }
+
     /// Used to track when an account has claimed their STAKE tokens for the NEAR they have staked.
     /// When the staked NEAR balance reaches zero, it means all STAKE tokens have been claimed by users.
     pub fn stake_tokens_issued(&mut self, staked_near: YoctoNear) {
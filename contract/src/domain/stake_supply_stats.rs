@@ -0,0 +1,92 @@
+use crate::domain::{EpochHeight, YoctoStake};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Tracks how much STAKE was minted and burned, both within the current epoch and over the
+/// lifetime of the contract.
+///
+/// The per epoch counters are reset lazily, i.e., the first time STAKE is minted or burned within
+/// a new epoch.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct StakeSupplyStats {
+    /// the epoch in which the per epoch counters were tracked
+    epoch_height: EpochHeight,
+    stake_minted_this_epoch: YoctoStake,
+    stake_burned_this_epoch: YoctoStake,
+    total_stake_minted: YoctoStake,
+    total_stake_burned: YoctoStake,
+}
+
+impl StakeSupplyStats {
+    /// returns how much STAKE was minted within the specified epoch
+    pub fn stake_minted(&self, epoch_height: EpochHeight) -> YoctoStake {
+        if self.epoch_height == epoch_height {
+            self.stake_minted_this_epoch
+        } else {
+            0.into()
+        }
+    }
+
+    /// returns how much STAKE was burned within the specified epoch
+    pub fn stake_burned(&self, epoch_height: EpochHeight) -> YoctoStake {
+        if self.epoch_height == epoch_height {
+            self.stake_burned_this_epoch
+        } else {
+            0.into()
+        }
+    }
+
+    pub fn total_stake_minted(&self) -> YoctoStake {
+        self.total_stake_minted
+    }
+
+    pub fn total_stake_burned(&self) -> YoctoStake {
+        self.total_stake_burned
+    }
+
+    pub fn record_mint(&mut self, amount: YoctoStake, epoch_height: EpochHeight) {
+        self.roll_epoch(epoch_height);
+        self.stake_minted_this_epoch += amount;
+        self.total_stake_minted += amount;
+    }
+
+    pub fn record_burn(&mut self, amount: YoctoStake, epoch_height: EpochHeight) {
+        self.roll_epoch(epoch_height);
+        self.stake_burned_this_epoch += amount;
+        self.total_stake_burned += amount;
+    }
+
+    fn roll_epoch(&mut self, epoch_height: EpochHeight) {
+        if self.epoch_height != epoch_height {
+            self.epoch_height = epoch_height;
+            self.stake_minted_this_epoch = 0.into();
+            self.stake_burned_this_epoch = 0.into();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counters_are_reset_per_epoch() {
+        let mut stats = StakeSupplyStats::default();
+        stats.record_mint(100.into(), 1.into());
+        stats.record_mint(50.into(), 1.into());
+        stats.record_burn(30.into(), 1.into());
+        assert_eq!(stats.stake_minted(1.into()), 150.into());
+        assert_eq!(stats.stake_burned(1.into()), 30.into());
+
+        // counters are reported as zero for other epochs
+        assert_eq!(stats.stake_minted(2.into()), 0.into());
+        assert_eq!(stats.stake_burned(2.into()), 0.into());
+
+        stats.record_burn(20.into(), 2.into());
+        assert_eq!(stats.stake_minted(2.into()), 0.into());
+        assert_eq!(stats.stake_burned(2.into()), 20.into());
+
+        // lifetime totals are not reset
+        assert_eq!(stats.total_stake_minted(), 150.into());
+        assert_eq!(stats.total_stake_burned(), 50.into());
+    }
+}
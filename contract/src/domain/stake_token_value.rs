@@ -49,45 +49,84 @@ impl StakeTokenValue {
         self.total_stake_supply
     }
 
-    /// converts NEAR to STAKE rounded down
+    /// returns true if NEAR and STAKE are converted 1:1
+    /// - before any NEAR is staked, or when all STAKE has been redeemed
+    /// - STAKE token value should never be less than 1:1 in terms of NEAR - when NEAR is deposited
+    ///   and staked with the staking pool, a small amount may remain unstaked because of the staking
+    ///   pool share rounding
+    fn is_one_to_one(&self) -> bool {
+        self.total_staked_near_balance.value() == 0
+            || self.total_stake_supply.value() == 0
+            || self.total_staked_near_balance.value() < self.total_stake_supply.value()
+    }
+
+    /// converts NEAR to STAKE rounded down - see [NEAR_TO_STAKE_ROUNDING]
     /// - STAKE appreciates in value over time - if we were to round up, then NEAR would leak out
     pub fn near_to_stake(&self, near: YoctoNear) -> YoctoStake {
-        if self.total_staked_near_balance.value() == 0 {
+        if self.is_one_to_one() {
             return near.value().into();
         }
-
-        let near = U256::from(near);
-        let total_stake_supply = U256::from(self.total_stake_supply);
-        let total_staked_near_balance = U256::from(self.total_staked_near_balance);
-
-        (near * total_stake_supply / total_staked_near_balance)
-            .as_u128()
-            .into()
+        mul_div(
+            near.value(),
+            self.total_stake_supply.value(),
+            self.total_staked_near_balance.value(),
+            NEAR_TO_STAKE_ROUNDING,
+        )
+        .into()
     }
 
-    /// converts STAKE to NEAR rounded up
+    /// converts STAKE to NEAR rounded up - see [STAKE_TO_NEAR_ROUNDING]
     /// - we round up because we never want to short change the payout
     /// - this also helps to compensate for rounding down when we convert NEAR -> STAKE
+    /// - saturates at `u128::MAX`, which is far beyond the total NEAR supply
     pub fn stake_to_near(&self, stake: YoctoStake) -> YoctoNear {
-        if self.total_staked_near_balance.value() == 0
-            // when deposit and staked with staking pool, there is a small amount remaining as unstaked
-            // however, STAKE token value should never be less than 1:1 in terms of NEAR
-            || self.total_staked_near_balance.value() < self.total_stake_supply.value()
-        {
+        if self.is_one_to_one() {
             return stake.value().into();
         }
+        mul_div(
+            stake.value(),
+            self.total_staked_near_balance.value(),
+            self.total_stake_supply.value(),
+            STAKE_TO_NEAR_ROUNDING,
+        )
+        .into()
+    }
+}
+
+/// Rounding direction that is applied when the exact conversion result is fractional
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
 
-        let stake = U256::from(stake);
-        let total_stake_supply = U256::from(self.total_stake_supply);
-        let total_staked_near_balance = U256::from(self.total_staked_near_balance);
+/// Minting STAKE rounds down, i.e., the fractional yoctoSTAKE stays with the protocol.
+pub const NEAR_TO_STAKE_ROUNDING: Rounding = Rounding::Down;
 
-        let near_value = stake * total_staked_near_balance / total_stake_supply;
-        if (stake * total_staked_near_balance) % total_stake_supply == U256::from(0) {
-            near_value.as_u128().into()
-        } else {
-            // round up
-            (near_value.as_u128() + 1).into()
-        }
+/// Redeeming STAKE rounds up by at most 1 yoctoNEAR per conversion. Because minting rounds down,
+/// converting NEAR -> STAKE -> NEAR never yields more NEAR than was converted.
+pub const STAKE_TO_NEAR_ROUNDING: Rounding = Rounding::Up;
+
+/// Staked NEAR compensation, which is added to the NEAR liquidity pool to prevent the STAKE token
+/// value from ticking down, is rounded down.
+pub const STAKE_TOKEN_VALUE_COMPENSATION_ROUNDING: Rounding = Rounding::Down;
+
+/// computes `amount * numerator / denominator` using 256-bit math
+/// - the result saturates at `u128::MAX`
+///
+/// ## Panics
+/// if the denominator is zero
+pub fn mul_div(amount: u128, numerator: u128, denominator: u128, rounding: Rounding) -> u128 {
+    let product = U256::from(amount) * U256::from(numerator);
+    let denominator = U256::from(denominator);
+    let mut quotient = product / denominator;
+    if rounding == Rounding::Up && !(product % denominator).is_zero() {
+        quotient = quotient + U256::from(1);
+    }
+    if quotient > U256::from(u128::MAX) {
+        u128::MAX
+    } else {
+        quotient.as_u128()
     }
 }
 
@@ -108,6 +147,12 @@ mod test {
     use crate::near::YOCTO;
     use crate::test_utils::*;
     use near_sdk::{testing_env, MockedBlockchain};
+    use quickcheck_macros::quickcheck;
+
+    /// total staked NEAR balance is always >= total STAKE supply
+    fn stake_token_value(a: u128, b: u128) -> StakeTokenValue {
+        StakeTokenValue::new(BlockTimeHeight::default(), a.max(b).into(), a.min(b).into())
+    }
 
     #[test]
     fn when_total_stake_supply_is_zero() {
@@ -155,4 +200,114 @@ mod test {
 
         assert_eq!(near_value, YoctoNear(YOCTO));
     }
+
+    #[test]
+    fn conversions_at_u128_bounds() {
+        let max = u128::MAX;
+
+        // STAKE token value is 1:1
+        let value = stake_token_value(max, max);
+        assert_eq!(value.near_to_stake(max.into()), YoctoStake(max));
+        assert_eq!(value.stake_to_near(max.into()), YoctoNear(max));
+
+        // STAKE token value is ~2:1 - NEAR value saturates
+        let value = stake_token_value(max, max / 2);
+        assert_eq!(value.near_to_stake(max.into()), YoctoStake(max / 2));
+        assert_eq!(value.stake_to_near(max.into()), YoctoNear(max));
+
+        // tiny STAKE supply backed by the max NEAR balance
+        let value = stake_token_value(max, 1);
+        assert_eq!(value.near_to_stake((max - 1).into()), YoctoStake(0));
+        assert_eq!(value.near_to_stake(max.into()), YoctoStake(1));
+        assert_eq!(value.stake_to_near(1.into()), YoctoNear(max));
+        assert_eq!(value.stake_to_near(2.into()), YoctoNear(max));
+    }
+
+    #[test]
+    fn tiny_supplies() {
+        let value = stake_token_value(3, 2);
+        // 1 NEAR * 2 / 3 = 0.67 STAKE rounded down
+        assert_eq!(value.near_to_stake(1.into()), YoctoStake(0));
+        // 1 STAKE * 3 / 2 = 1.5 NEAR rounded up
+        assert_eq!(value.stake_to_near(1.into()), YoctoNear(2));
+        assert_eq!(value.stake_to_near(2.into()), YoctoNear(3));
+    }
+
+    #[test]
+    fn zero_stake_supply_converts_one_to_one() {
+        // all STAKE has been redeemed, but some staked NEAR dust remains
+        let value = stake_token_value(1000, 0);
+        assert_eq!(value.near_to_stake(YOCTO.into()), YoctoStake(YOCTO));
+        assert_eq!(value.stake_to_near(YOCTO.into()), YoctoNear(YOCTO));
+    }
+
+    #[test]
+    fn mul_div_rounding() {
+        assert_eq!(mul_div(10, 2, 3, Rounding::Down), 6);
+        assert_eq!(mul_div(10, 2, 3, Rounding::Up), 7);
+        assert_eq!(mul_div(9, 2, 3, Rounding::Up), 6);
+        assert_eq!(mul_div(u128::MAX, u128::MAX, 1, Rounding::Up), u128::MAX);
+        assert_eq!(
+            mul_div(u128::MAX, u128::MAX, u128::MAX, Rounding::Down),
+            u128::MAX
+        );
+    }
+
+    /// NEAR -> STAKE is rounded down: stake * B <= near * S < (stake + 1) * B
+    #[quickcheck]
+    fn near_to_stake_rounds_down(a: u128, b: u128, near: u128) -> bool {
+        let value = stake_token_value(a, b);
+        let stake = value.near_to_stake(near.into()).value();
+        if value.is_one_to_one() {
+            return stake == near;
+        }
+        let total_near = U256::from(value.total_staked_near_balance().value());
+        let total_stake = U256::from(value.total_stake_supply().value());
+        let exact = U256::from(near) * total_stake;
+        U256::from(stake) * total_near <= exact
+            && exact < (U256::from(stake) + U256::from(1)) * total_near
+    }
+
+    /// STAKE -> NEAR is rounded up: (near - 1) * S < stake * B <= near * S, unless saturated
+    #[quickcheck]
+    fn stake_to_near_rounds_up(a: u128, b: u128, stake: u128) -> bool {
+        let value = stake_token_value(a, b);
+        let near = value.stake_to_near(stake.into()).value();
+        if value.is_one_to_one() {
+            return near == stake;
+        }
+        let total_near = U256::from(value.total_staked_near_balance().value());
+        let total_stake = U256::from(value.total_stake_supply().value());
+        let exact = U256::from(stake) * total_near;
+        if near == u128::MAX {
+            // the exact NEAR value may exceed u128::MAX, in which case the NEAR value saturates
+            return U256::from(near - 1) * total_stake < exact;
+        }
+        exact <= U256::from(near) * total_stake
+            && (near == 0 || U256::from(near - 1) * total_stake < exact)
+    }
+
+    /// minting STAKE and then redeeming it never yields more NEAR than was deposited
+    #[quickcheck]
+    fn near_to_stake_to_near_never_gains_near(a: u128, b: u128, near: u128) -> bool {
+        let value = stake_token_value(a, b);
+        let stake = value.near_to_stake(near.into());
+        value.stake_to_near(stake).value() <= near
+    }
+
+    /// the NEAR paid out for STAKE always buys back at least the same STAKE
+    #[quickcheck]
+    fn stake_to_near_to_stake_never_loses_stake(a: u128, b: u128, stake: u128) -> bool {
+        let value = stake_token_value(a, b);
+        let near = value.stake_to_near(stake.into());
+        near.value() == u128::MAX || value.near_to_stake(near).value() >= stake
+    }
+
+    /// STAKE token value never drops below 1:1
+    #[quickcheck]
+    fn stake_is_never_worth_less_than_near(a: u128, b: u128, stake: u128) -> bool {
+        let value = stake_token_value(a, b);
+        value.stake_to_near(stake.into()).value() >= stake
+            && value.near_to_stake(stake.into()).value() <= stake
+    }
 }
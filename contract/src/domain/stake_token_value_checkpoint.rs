@@ -0,0 +1,45 @@
+use crate::domain::{EpochHeight, StakeTokenValue};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// max number of checkpoints that are returned by the
+/// [stake_token_value_history](crate::interface::StakingService::stake_token_value_history) view
+pub const MAX_STAKE_TOKEN_VALUE_HISTORY_LEN: u32 = 100;
+
+/// Records the last [StakeTokenValue] that was computed within an epoch.
+///
+/// Checkpoints are linked backwards in time via [prev_epoch_height](StakeTokenValueCheckpoint::prev_epoch_height),
+/// which enables the time weighted average STAKE token value to be computed without having to scan
+/// every epoch - epochs with no checkpoint simply carry forward the prior checkpoint's value.
+#[derive(BorshSerialize, BorshDeserialize, Copy, Clone, Debug)]
+pub struct StakeTokenValueCheckpoint {
+    stake_token_value: StakeTokenValue,
+    prev_epoch_height: Option<EpochHeight>,
+}
+
+impl StakeTokenValueCheckpoint {
+    pub fn new(stake_token_value: StakeTokenValue, prev_epoch_height: Option<EpochHeight>) -> Self {
+        Self {
+            stake_token_value,
+            prev_epoch_height,
+        }
+    }
+
+    pub fn stake_token_value(&self) -> StakeTokenValue {
+        self.stake_token_value
+    }
+
+    pub fn epoch_height(&self) -> EpochHeight {
+        self.stake_token_value.block_time_height().epoch_height()
+    }
+
+    /// epoch height for the prior checkpoint - None means this is the first checkpoint
+    pub fn prev_epoch_height(&self) -> Option<EpochHeight> {
+        self.prev_epoch_height
+    }
+
+    /// the STAKE token value is updated multiple times within the same epoch - the checkpoint
+    /// keeps the latest value
+    pub fn update(&mut self, stake_token_value: StakeTokenValue) {
+        self.stake_token_value = stake_token_value;
+    }
+}
@@ -0,0 +1,37 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// version of the persisted [Contract](crate::Contract) state layout
+/// - must be bumped whenever the contract state struct layout changes, i.e., when deploying the new
+///   code requires the contract state to be migrated
+/// - records that are stored in persistent collections are versioned independently - see
+///   [Versioned](crate::domain::Versioned)
+#[derive(
+    BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default,
+)]
+pub struct StateVersion(pub u32);
+
+/// the state layout version the contract code was compiled against
+/// - 1: initial layout, which was persisted before the state version was recorded
+/// - 2: adds the operator, governance, liquidity, and workflow state, and changes the config layout
+pub const CURRENT_STATE_VERSION: StateVersion = StateVersion(2);
+
+impl StateVersion {
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+}
+
+/// Migration step that upgrades the persisted contract state layout from the previous state version.
+///
+/// Each [CURRENT_STATE_VERSION] bump must ship a migration step, which is registered with the
+/// contract's state migration registry - the steps are run in version order by the contract's
+/// `migrate` entry point.
+pub trait StateMigration {
+    /// state version that the step upgrades the persisted state to - the step is run against state
+    /// that was persisted using the previous state version layout
+    fn version(&self) -> StateVersion;
+
+    /// reads the persisted contract state using the previous layout and writes it back using the
+    /// new layout
+    fn migrate(&self);
+}
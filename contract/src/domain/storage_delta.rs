@@ -0,0 +1,63 @@
+use crate::domain::{BlockHeight, StorageUsage};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// storage usage diagnostics recorded for the last call to an instrumented contract method
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct StorageDelta {
+    storage_usage_before: StorageUsage,
+    storage_usage_after: StorageUsage,
+    /// number of versioned records, e.g., accounts and batch receipts, that were written or removed
+    records_touched: u32,
+    block_height: BlockHeight,
+}
+
+impl StorageDelta {
+    pub fn new(
+        storage_usage_before: StorageUsage,
+        storage_usage_after: StorageUsage,
+        records_touched: u32,
+        block_height: BlockHeight,
+    ) -> Self {
+        Self {
+            storage_usage_before,
+            storage_usage_after,
+            records_touched,
+            block_height,
+        }
+    }
+
+    pub fn storage_usage_before(&self) -> StorageUsage {
+        self.storage_usage_before
+    }
+
+    pub fn storage_usage_after(&self) -> StorageUsage {
+        self.storage_usage_after
+    }
+
+    pub fn records_touched(&self) -> u32 {
+        self.records_touched
+    }
+
+    pub fn block_height(&self) -> BlockHeight {
+        self.block_height
+    }
+
+    /// negative when storage was freed
+    pub fn delta(&self) -> i64 {
+        self.storage_usage_after.value() as i64 - self.storage_usage_before.value() as i64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn delta() {
+        let delta = StorageDelta::new(100.into(), 150.into(), 2, 10.into());
+        assert_eq!(delta.delta(), 50);
+
+        let delta = StorageDelta::new(150.into(), 100.into(), 1, 10.into());
+        assert_eq!(delta.delta(), -50);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::core::Hash;
+use crate::domain::{EpochHeight, StakeTokenValue, YoctoStake};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Escrowed STAKE that the account is offering to swap for the STAKE of another registered STAKE
+/// contract deployment, e.g., one that is linked to a different validator.
+///
+/// The swap ratio is derived from the STAKE token values of both contracts at the time the intent is
+/// filled, i.e., the NEAR value is preserved across the swap.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct SwapIntent {
+    /// the account ID is hashed to keep the record fixed size - the account ID is resolved from the
+    /// swap deployments that are registered by the operator
+    target_stake_contract: Hash,
+    /// escrowed STAKE that is available to be filled
+    amount: YoctoStake,
+    /// fills that have reserved escrowed STAKE and are awaiting settlement on the target contract
+    pending_fills: u32,
+    posted_epoch_height: EpochHeight,
+}
+
+/// STAKE amounts that are exchanged when a swap intent is filled
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SwapFill {
+    /// escrowed STAKE that is transferred to the filler
+    pub amount: YoctoStake,
+    /// target contract STAKE that is transferred to the intent owner
+    pub target_amount: YoctoStake,
+}
+
+impl SwapIntent {
+    pub fn new(target_stake_contract: Hash, epoch_height: EpochHeight) -> Self {
+        Self {
+            target_stake_contract,
+            amount: 0.into(),
+            pending_fills: 0,
+            posted_epoch_height: epoch_height,
+        }
+    }
+
+    pub fn target_stake_contract(&self) -> Hash {
+        self.target_stake_contract
+    }
+
+    pub fn amount(&self) -> YoctoStake {
+        self.amount
+    }
+
+    pub fn pending_fills(&self) -> u32 {
+        self.pending_fills
+    }
+
+    pub fn posted_epoch_height(&self) -> EpochHeight {
+        self.posted_epoch_height
+    }
+
+    /// the intent can be deleted once all of its escrowed STAKE has been settled
+    pub fn is_closed(&self) -> bool {
+        self.amount.value() == 0 && self.pending_fills == 0
+    }
+
+    pub fn add(&mut self, amount: YoctoStake) {
+        self.amount += amount;
+    }
+
+    /// computes how much escrowed STAKE is exchanged for the target contract STAKE amount
+    /// - the target STAKE is converted to NEAR, which is then converted to STAKE - conversions round
+    ///   in favor of the intent owner
+    /// - if the target STAKE amount exceeds the escrowed STAKE value, then the intent is fully filled
+    ///   and only the target STAKE amount that is needed is used
+    pub fn quote_fill(
+        &self,
+        stake_token_value: StakeTokenValue,
+        target_stake_token_value: StakeTokenValue,
+        target_amount: YoctoStake,
+    ) -> SwapFill {
+        let near = target_stake_token_value.stake_to_near(target_amount);
+        let amount = stake_token_value.near_to_stake(near);
+        if amount <= self.amount {
+            SwapFill {
+                amount,
+                target_amount,
+            }
+        } else {
+            let near = stake_token_value.stake_to_near(self.amount);
+            let needed = target_stake_token_value.near_to_stake(near);
+            SwapFill {
+                amount: self.amount,
+                target_amount: if needed < target_amount {
+                    needed
+                } else {
+                    target_amount
+                },
+            }
+        }
+    }
+
+    /// reserves the escrowed STAKE while the fill is being settled on the target contract
+    ///
+    /// ## Panics
+    /// if the amount exceeds the escrowed STAKE
+    pub fn reserve(&mut self, amount: YoctoStake) {
+        assert!(
+            amount <= self.amount,
+            "swap intent escrow is insufficient to fill"
+        );
+        self.amount -= amount;
+        self.pending_fills += 1;
+    }
+
+    /// the reserved STAKE was transferred to the filler
+    pub fn settle(&mut self) {
+        self.pending_fills -= 1;
+    }
+
+    /// the fill failed to settle - the reserved STAKE is returned to the escrow
+    pub fn release(&mut self, amount: YoctoStake) {
+        self.amount += amount;
+        self.pending_fills -= 1;
+    }
+
+    /// withdraws the escrowed STAKE
+    /// - STAKE that is reserved for pending fills remains escrowed until the fills are settled
+    pub fn withdraw(&mut self) -> YoctoStake {
+        let amount = self.amount;
+        self.amount = 0.into();
+        amount
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::BlockTimeHeight;
+    use crate::near::YOCTO;
+
+    fn stake_token_value(
+        total_staked_near_balance: u128,
+        total_stake_supply: u128,
+    ) -> StakeTokenValue {
+        StakeTokenValue::new(
+            BlockTimeHeight::default(),
+            total_staked_near_balance.into(),
+            total_stake_supply.into(),
+        )
+    }
+
+    #[test]
+    fn quote_partial_fill() {
+        let mut intent = SwapIntent::new(Hash::default(), 10.into());
+        intent.add((100 * YOCTO).into());
+
+        // 1 STAKE = 2 NEAR locally, 1 target STAKE = 1.5 NEAR
+        let fill = intent.quote_fill(
+            stake_token_value(200 * YOCTO, 100 * YOCTO),
+            stake_token_value(150 * YOCTO, 100 * YOCTO),
+            (40 * YOCTO).into(),
+        );
+        assert_eq!(fill.target_amount, (40 * YOCTO).into());
+        assert_eq!(fill.amount, (30 * YOCTO).into());
+    }
+
+    #[test]
+    fn quote_full_fill() {
+        let mut intent = SwapIntent::new(Hash::default(), 10.into());
+        intent.add((30 * YOCTO).into());
+
+        let fill = intent.quote_fill(
+            stake_token_value(200 * YOCTO, 100 * YOCTO),
+            stake_token_value(150 * YOCTO, 100 * YOCTO),
+            (100 * YOCTO).into(),
+        );
+        assert_eq!(fill.amount, (30 * YOCTO).into());
+        assert_eq!(fill.target_amount, (40 * YOCTO).into());
+    }
+
+    #[test]
+    fn reserve_settle_release() {
+        let mut intent = SwapIntent::new(Hash::default(), 10.into());
+        intent.add(100.into());
+
+        intent.reserve(60.into());
+        intent.reserve(40.into());
+        assert_eq!(intent.amount(), 0.into());
+        assert!(!intent.is_closed());
+
+        intent.release(40.into());
+        assert_eq!(intent.amount(), 40.into());
+        intent.settle();
+        assert_eq!(intent.withdraw(), 40.into());
+        assert!(intent.is_closed());
+    }
+
+    #[test]
+    #[should_panic(expected = "swap intent escrow is insufficient to fill")]
+    fn reserve_exceeds_escrow() {
+        let mut intent = SwapIntent::new(Hash::default(), 10.into());
+        intent.add(100.into());
+        intent.reserve(101.into());
+    }
+}
@@ -0,0 +1,346 @@
+//! Records that are persisted independently of the contract state, i.e., stored in persistent
+//! collections, are wrapped in versioned enums. The enum variant tags the record with its layout
+//! version, which enables new fields (e.g., a per batch staking pool ID) to be added by introducing
+//! a new variant - records are upgraded to the latest layout lazily when they are read, instead of
+//! requiring a big-bang state migration of every stored record.
+//!
+//! Structs that are embedded within a versioned record, e.g., [StakeBatch](crate::domain::StakeBatch)
+//! and [StakeTokenValue](crate::domain::StakeTokenValue), are versioned through the enclosing
+//! record.
+//!
+//! ## How to add a new record version
+//! 1. copy the current struct to keep it around as the frozen layout, e.g., [AccountV1]
+//! 2. add the new variant, e.g., `V2(Account)`, and write new records with it
+//! 3. decode the frozen variant into the latest struct, filling in defaults for the new fields
+//!
+//! NOTE: variants must only ever be appended because the variant index is the persisted tag
+//!
+//! ## Untagged legacy records
+//! Records that were persisted before the record types were versioned carry no version tag, and
+//! can not be told apart from tagged records. Versioned records are therefore stored under their
+//! own key prefix, and the untagged records are decoded using their legacy layout:
+//! - accounts are decoded lazily when they are read - see [LegacyVersioned] - legacy redeem stake
+//!   batch IDs are tagged with the batch kind when they are decoded
+//! - batch receipts are moved by the batch receipt migration tasks, because the legacy layout does
+//!   not record the staking pool - see [MigrationTask](crate::domain::MigrationTask)
+//!
+//! ## Hibernated accounts
+//! Dormant accounts can be compressed into a [VersionedAccount::Hibernated] tombstone, which only
+//! retains the account storage escrow. The tombstone is rehydrated into an empty [Account] when it
+//! is read, and is replaced by the full record the next time the account is saved.
+
+use crate::core::Hash;
+use crate::domain::{
+    Account, AccountPreferences, PoolPreference, RebateAccumulator, RedeemLimit, RedeemStakeBatch,
+    RedeemStakeBatchReceipt, StakeBatch, StakeBatchReceipt, StakeTokenValue,
+    TimestampedNearBalance, TimestampedStakeBalance, YoctoNear, YoctoStake,
+};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// domain struct that is persisted as a versioned record
+pub trait Versioned: Sized {
+    type Record: BorshSerialize + BorshDeserialize;
+
+    /// wraps the value using the latest record version
+    fn to_record(&self) -> Self::Record;
+
+    /// decodes any record version into the latest domain struct
+    fn from_record(record: Self::Record) -> Self;
+}
+
+/// versioned record type whose records were persisted without a version tag before the record type
+/// was versioned
+pub trait LegacyVersioned: Versioned {
+    /// untagged record layout
+    type Legacy: BorshSerialize + BorshDeserialize;
+
+    /// decodes the untagged record into the latest domain struct
+    fn from_legacy(legacy: Self::Legacy) -> Self;
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum VersionedAccount {
+    V1(AccountV1),
+    /// tombstone for a hibernated account - retains the account registration and storage escrow
+    Hibernated(YoctoNear),
+    /// adds [idle_near_share](Account::idle_near_share), [preferences](Account::preferences), and
+    /// [nonce](Account::nonce)
+    V2(Account),
+}
+
+impl Versioned for Account {
+    type Record = VersionedAccount;
+
+    fn to_record(&self) -> Self::Record {
+        VersionedAccount::V2(*self)
+    }
+
+    fn from_record(record: Self::Record) -> Self {
+        match record {
+            VersionedAccount::V1(account) => account.into(),
+            VersionedAccount::Hibernated(storage_escrow) => Account::new(storage_escrow),
+            VersionedAccount::V2(account) => account,
+        }
+    }
+}
+
+impl LegacyVersioned for Account {
+    type Legacy = LegacyAccount;
+
+    fn from_legacy(legacy: Self::Legacy) -> Self {
+        Account {
+            storage_escrow: legacy.storage_escrow,
+            near: legacy.near,
+            stake: legacy.stake,
+            stake_batch: legacy.stake_batch,
+            next_stake_batch: legacy.next_stake_batch,
+            redeem_stake_batch: legacy
+                .redeem_stake_batch
+                .map(RedeemStakeBatch::with_tagged_batch_id),
+            next_redeem_stake_batch: legacy
+                .next_redeem_stake_batch
+                .map(RedeemStakeBatch::with_tagged_batch_id),
+            ..Account::new(0.into())
+        }
+    }
+}
+
+/// frozen [VersionedAccount::V1] layout
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub struct AccountV1 {
+    pub storage_escrow: TimestampedNearBalance,
+    pub near: Option<TimestampedNearBalance>,
+    pub stake: Option<TimestampedStakeBalance>,
+    pub stake_batch: Option<StakeBatch>,
+    pub next_stake_batch: Option<StakeBatch>,
+    pub redeem_stake_batch: Option<RedeemStakeBatch>,
+    pub next_redeem_stake_batch: Option<RedeemStakeBatch>,
+    pub redeem_limit: Option<RedeemLimit>,
+    pub rebate_accumulator: Option<RebateAccumulator>,
+    pub pool_preference: Option<PoolPreference>,
+    pub redeem_forwarding: Option<Hash>,
+}
+
+impl From<AccountV1> for Account {
+    fn from(account: AccountV1) -> Self {
+        Account {
+            storage_escrow: account.storage_escrow,
+            near: account.near,
+            stake: account.stake,
+            stake_batch: account.stake_batch,
+            next_stake_batch: account.next_stake_batch,
+            redeem_stake_batch: account.redeem_stake_batch,
+            next_redeem_stake_batch: account.next_redeem_stake_batch,
+            redeem_limit: account.redeem_limit,
+            rebate_accumulator: account.rebate_accumulator,
+            pool_preference: account.pool_preference,
+            redeem_forwarding: account.redeem_forwarding,
+            idle_near_share: None,
+            preferences: AccountPreferences::default(),
+            nonce: 0,
+        }
+    }
+}
+
+/// untagged account layout that was persisted before accounts were versioned
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub struct LegacyAccount {
+    pub storage_escrow: TimestampedNearBalance,
+    pub near: Option<TimestampedNearBalance>,
+    pub stake: Option<TimestampedStakeBalance>,
+    pub stake_batch: Option<StakeBatch>,
+    pub next_stake_batch: Option<StakeBatch>,
+    pub redeem_stake_batch: Option<RedeemStakeBatch>,
+    pub next_redeem_stake_batch: Option<RedeemStakeBatch>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum VersionedStakeBatchReceipt {
+    V1(StakeBatchReceipt),
+}
+
+impl Versioned for StakeBatchReceipt {
+    type Record = VersionedStakeBatchReceipt;
+
+    fn to_record(&self) -> Self::Record {
+        VersionedStakeBatchReceipt::V1(self.clone())
+    }
+
+    fn from_record(record: Self::Record) -> Self {
+        match record {
+            VersionedStakeBatchReceipt::V1(receipt) => receipt,
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum VersionedRedeemStakeBatchReceipt {
+    V1(RedeemStakeBatchReceipt),
+}
+
+impl Versioned for RedeemStakeBatchReceipt {
+    type Record = VersionedRedeemStakeBatchReceipt;
+
+    fn to_record(&self) -> Self::Record {
+        VersionedRedeemStakeBatchReceipt::V1(self.clone())
+    }
+
+    fn from_record(record: Self::Record) -> Self {
+        match record {
+            VersionedRedeemStakeBatchReceipt::V1(receipt) => receipt,
+        }
+    }
+}
+
+/// untagged stake batch receipt layout that was persisted before receipts were versioned
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub struct LegacyStakeBatchReceipt {
+    pub staked_near: YoctoNear,
+    pub stake_token_value: StakeTokenValue,
+}
+
+impl LegacyStakeBatchReceipt {
+    /// the legacy layout predates multiple staking pools, i.e., the batch was staked with the
+    /// staking pool that the contract was configured with
+    pub fn into_receipt(self, staking_pool_id: AccountId) -> StakeBatchReceipt {
+        StakeBatchReceipt::new(self.staked_near, self.stake_token_value, staking_pool_id)
+    }
+}
+
+/// untagged redeem stake batch receipt layout that was persisted before receipts were versioned
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub struct LegacyRedeemStakeBatchReceipt {
+    pub redeemed_stake: YoctoStake,
+    pub stake_token_value: StakeTokenValue,
+}
+
+impl LegacyRedeemStakeBatchReceipt {
+    /// see [LegacyStakeBatchReceipt::into_receipt]
+    pub fn into_receipt(self, staking_pool_id: AccountId) -> RedeemStakeBatchReceipt {
+        RedeemStakeBatchReceipt::new(self.redeemed_stake, self.stake_token_value, staking_pool_id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::{BatchKind, StakeTokenValue};
+    use crate::test_utils::new_context;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn records_are_tagged_with_version() {
+        let account = Account::new(100.into());
+        let bytes = account.to_record().try_to_vec().unwrap();
+        assert_eq!(bytes[0], 2, "V2 is persisted as variant index 2");
+        assert_eq!(&bytes[1..], account.try_to_vec().unwrap().as_slice());
+
+        let account = Account::from_record(VersionedAccount::try_from_slice(&bytes).unwrap());
+        assert_eq!(account.storage_escrow.amount(), 100.into());
+    }
+
+    #[test]
+    fn hibernated_account_is_rehydrated() {
+        testing_env!(new_context("bob.near"));
+        let bytes = VersionedAccount::Hibernated(100.into())
+            .try_to_vec()
+            .unwrap();
+        assert_eq!(bytes.len(), 17);
+
+        let account = Account::from_record(VersionedAccount::try_from_slice(&bytes).unwrap());
+        assert_eq!(account.storage_escrow.amount(), 100.into());
+        assert!(account.can_hibernate());
+        match account.to_record() {
+            VersionedAccount::V2(_) => {}
+            _ => panic!("accounts should be saved using the latest record version"),
+        }
+    }
+
+    #[test]
+    fn v1_account_is_upgraded() {
+        testing_env!(new_context("bob.near"));
+        let mut account = Account::new(100.into());
+        account.stake = Some(TimestampedStakeBalance::new(YoctoStake(200)));
+        account.redeem_forwarding = Some(Hash::default());
+        let v1 = AccountV1 {
+            storage_escrow: account.storage_escrow,
+            near: account.near,
+            stake: account.stake,
+            stake_batch: account.stake_batch,
+            next_stake_batch: account.next_stake_batch,
+            redeem_stake_batch: account.redeem_stake_batch,
+            next_redeem_stake_batch: account.next_redeem_stake_batch,
+            redeem_limit: account.redeem_limit,
+            rebate_accumulator: account.rebate_accumulator,
+            pool_preference: account.pool_preference,
+            redeem_forwarding: account.redeem_forwarding,
+        };
+        let bytes = VersionedAccount::V1(v1).try_to_vec().unwrap();
+        assert_eq!(bytes[0], 0, "V1 is persisted as variant index 0");
+
+        let upgraded = Account::from_record(VersionedAccount::try_from_slice(&bytes).unwrap());
+        assert_eq!(
+            upgraded.stake.unwrap().amount(),
+            account.stake.unwrap().amount()
+        );
+        assert!(upgraded.redeem_forwarding.is_some());
+        assert!(upgraded.idle_near_share.is_none());
+        assert_eq!(upgraded.nonce, 0);
+    }
+
+    #[test]
+    fn legacy_account_is_decoded() {
+        testing_env!(new_context("bob.near"));
+        let legacy = LegacyAccount {
+            storage_escrow: TimestampedNearBalance::new(100.into()),
+            near: Some(TimestampedNearBalance::new(300.into())),
+            stake: Some(TimestampedStakeBalance::new(YoctoStake(200))),
+            stake_batch: Some(StakeBatch::new(1.into(), 400.into())),
+            next_stake_batch: None,
+            redeem_stake_batch: Some(RedeemStakeBatch::new(2.into(), YoctoStake(50))),
+            next_redeem_stake_batch: None,
+        };
+        let bytes = legacy.try_to_vec().unwrap();
+
+        let account = Account::from_legacy(LegacyAccount::try_from_slice(&bytes).unwrap());
+        assert_eq!(account.storage_escrow.amount(), 100.into());
+        assert_eq!(account.near.unwrap().amount(), 300.into());
+        assert_eq!(account.stake.unwrap().amount(), YoctoStake(200));
+        assert_eq!(account.stake_batch.unwrap().balance().amount(), 400.into());
+        // legacy redeem stake batch IDs are tagged with the batch kind
+        let redeem_stake_batch_id = account.redeem_stake_batch.unwrap().id();
+        assert_eq!(redeem_stake_batch_id.kind(), BatchKind::Redeem);
+        assert_eq!(redeem_stake_batch_id.sequence(), 2);
+        assert_eq!(account.stake_batch.unwrap().id().kind(), BatchKind::Stake);
+        assert!(account.redeem_limit.is_none());
+    }
+
+    #[test]
+    fn receipt_round_trip() {
+        let receipt = StakeBatchReceipt::new(
+            100.into(),
+            StakeTokenValue::default(),
+            "pool.near".to_string(),
+        );
+        let bytes = receipt.to_record().try_to_vec().unwrap();
+        let decoded = StakeBatchReceipt::from_record(
+            VersionedStakeBatchReceipt::try_from_slice(&bytes).unwrap(),
+        );
+        assert_eq!(decoded.staked_near(), 100.into());
+        assert_eq!(decoded.staking_pool_id(), "pool.near");
+
+        let receipt = RedeemStakeBatchReceipt::new(
+            100.into(),
+            StakeTokenValue::default(),
+            "pool.near".to_string(),
+        );
+        let bytes = receipt.to_record().try_to_vec().unwrap();
+        let decoded = RedeemStakeBatchReceipt::from_record(
+            VersionedRedeemStakeBatchReceipt::try_from_slice(&bytes).unwrap(),
+        );
+        assert_eq!(decoded.redeemed_stake(), 100.into());
+    }
+}
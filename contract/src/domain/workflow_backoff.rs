@@ -0,0 +1,199 @@
+use crate::domain::BlockHeight;
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+};
+
+/// number of blocks after which a workflow attempt whose outcome was never recorded is counted as
+/// failed
+/// - workflows whose callbacks panic on staking pool failures never record their outcome, thus the
+///   failure is inferred the next time the workflow is started
+pub const WORKFLOW_ATTEMPT_TIMEOUT_BLOCKS: u64 = 10;
+
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Workflow {
+    /// stake batch workflow - see [stake](crate::interface::StakingService::stake)
+    Stake,
+    /// redeem stake batch workflow that unstakes the batch - see [unstake](crate::interface::StakingService::unstake)
+    Unstake,
+    /// redeem stake batch workflow that withdraws the unstaked NEAR once it is available
+    Withdraw,
+    /// see [refresh_stake_token_value](crate::interface::StakingService::refresh_stake_token_value)
+    RefreshStakeTokenValue,
+}
+
+impl Workflow {
+    pub const ALL: [Workflow; 4] = [
+        Workflow::Stake,
+        Workflow::Unstake,
+        Workflow::Withdraw,
+        Workflow::RefreshStakeTokenValue,
+    ];
+}
+
+/// Tracks consecutive failures for a workflow - each consecutive failure doubles the cooldown that
+/// is enforced before the workflow can be retried.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct WorkflowBackoff {
+    consecutive_failures: u32,
+    /// set when the workflow is started and cleared once its outcome is recorded
+    pending_since: Option<BlockHeight>,
+    last_failure: Option<BlockHeight>,
+}
+
+impl WorkflowBackoff {
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    pub fn pending_since(&self) -> Option<BlockHeight> {
+        self.pending_since
+    }
+
+    pub fn last_failure(&self) -> Option<BlockHeight> {
+        self.last_failure
+    }
+
+    /// `base_blocks * 2^(consecutive_failures - 1)`, capped at `max_blocks`
+    /// - 0 if there are no consecutive failures or if `base_blocks` is 0
+    pub fn cooldown_blocks(&self, base_blocks: u64, max_blocks: u64) -> u64 {
+        if self.consecutive_failures == 0 || base_blocks == 0 {
+            return 0;
+        }
+        base_blocks
+            .saturating_mul(2u64.saturating_pow(self.consecutive_failures - 1))
+            .min(max_blocks)
+    }
+
+    /// returns the block height at which the workflow can be retried, if a cooldown applies
+    pub fn retry_at(&self, base_blocks: u64, max_blocks: u64) -> Option<BlockHeight> {
+        let cooldown = self.cooldown_blocks(base_blocks, max_blocks);
+        if cooldown == 0 {
+            return None;
+        }
+        self.last_failure
+            .map(|last_failure| last_failure.value().saturating_add(cooldown).into())
+    }
+
+    pub fn is_cooling_down(&self, now: BlockHeight, base_blocks: u64, max_blocks: u64) -> bool {
+        self.retry_at(base_blocks, max_blocks)
+            .map_or(false, |retry_at| now < retry_at)
+    }
+
+    /// if the pending attempt has timed out, then it is recorded as a failure at the block height
+    /// the attempt was started
+    /// - the failure is backdated in order for the cooldown to be deterministic, i.e., the cooldown
+    ///   does not move if the retry is rejected because the workflow is cooling down
+    ///
+    /// Returns true if a failure was recorded.
+    pub fn expire_pending_attempt(&mut self, now: BlockHeight) -> bool {
+        match self.pending_since {
+            Some(pending_since)
+                if now.value() >= pending_since.value() + WORKFLOW_ATTEMPT_TIMEOUT_BLOCKS =>
+            {
+                self.record_failure(pending_since);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn start(&mut self, now: BlockHeight) {
+        self.pending_since = Some(now);
+    }
+
+    pub fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.pending_since = None;
+    }
+
+    pub fn record_failure(&mut self, now: BlockHeight) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        self.pending_since = None;
+        self.last_failure = Some(now);
+    }
+
+    /// clears the consecutive failures, which lifts the cooldown
+    pub fn reset(&mut self) {
+        self.consecutive_failures = 0;
+        self.pending_since = None;
+    }
+}
+
+/// [WorkflowBackoff] per [Workflow]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct WorkflowBackoffs {
+    stake: WorkflowBackoff,
+    unstake: WorkflowBackoff,
+    withdraw: WorkflowBackoff,
+    refresh_stake_token_value: WorkflowBackoff,
+}
+
+impl WorkflowBackoffs {
+    pub fn get(&self, workflow: Workflow) -> &WorkflowBackoff {
+        match workflow {
+            Workflow::Stake => &self.stake,
+            Workflow::Unstake => &self.unstake,
+            Workflow::Withdraw => &self.withdraw,
+            Workflow::RefreshStakeTokenValue => &self.refresh_stake_token_value,
+        }
+    }
+
+    pub fn get_mut(&mut self, workflow: Workflow) -> &mut WorkflowBackoff {
+        match workflow {
+            Workflow::Stake => &mut self.stake,
+            Workflow::Unstake => &mut self.unstake,
+            Workflow::Withdraw => &mut self.withdraw,
+            Workflow::RefreshStakeTokenValue => &mut self.refresh_stake_token_value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cooldown_doubles_with_each_consecutive_failure() {
+        let mut backoff = WorkflowBackoff::default();
+        assert_eq!(backoff.cooldown_blocks(100, 1000), 0);
+        assert!(backoff.retry_at(100, 1000).is_none());
+
+        backoff.record_failure(BlockHeight(10));
+        assert_eq!(backoff.cooldown_blocks(100, 1000), 100);
+        assert_eq!(backoff.retry_at(100, 1000), Some(BlockHeight(110)));
+        assert!(backoff.is_cooling_down(BlockHeight(109), 100, 1000));
+        assert!(!backoff.is_cooling_down(BlockHeight(110), 100, 1000));
+
+        backoff.record_failure(BlockHeight(110));
+        assert_eq!(backoff.cooldown_blocks(100, 1000), 200);
+        backoff.record_failure(BlockHeight(310));
+        assert_eq!(backoff.cooldown_blocks(100, 1000), 400);
+        backoff.record_failure(BlockHeight(710));
+        backoff.record_failure(BlockHeight(1510));
+        assert_eq!(backoff.cooldown_blocks(100, 1000), 1000);
+        // backoff is disabled
+        assert_eq!(backoff.cooldown_blocks(0, 1000), 0);
+
+        backoff.record_success();
+        assert_eq!(backoff.consecutive_failures(), 0);
+        assert!(!backoff.is_cooling_down(BlockHeight(1510), 100, 1000));
+    }
+
+    #[test]
+    fn pending_attempt_expires_as_failure() {
+        let mut backoff = WorkflowBackoff::default();
+        backoff.start(BlockHeight(10));
+        assert!(!backoff.expire_pending_attempt(BlockHeight(9 + WORKFLOW_ATTEMPT_TIMEOUT_BLOCKS)));
+        assert!(backoff.expire_pending_attempt(BlockHeight(10 + WORKFLOW_ATTEMPT_TIMEOUT_BLOCKS)));
+        assert_eq!(backoff.consecutive_failures(), 1);
+        assert!(backoff.pending_since().is_none());
+        assert_eq!(backoff.last_failure(), Some(BlockHeight(10)));
+
+        backoff.reset();
+        assert_eq!(backoff.consecutive_failures(), 0);
+    }
+}
@@ -0,0 +1,21 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// lifetime counters for the batch workflows, which are used to monitor the contract's health
+/// - see [metric_values](crate::interface::Operator::metric_values)
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct WorkflowCounters {
+    /// stake batches that were staked with the staking pool
+    pub stake_batches_run: u64,
+    /// redeem stake batches that were unstaked with the staking pool
+    pub redeem_stake_batches_run: u64,
+    /// stake batch workflows that failed on the staking pool side and were unlocked by the workflow
+    /// callback
+    pub stake_batch_workflow_failures: u64,
+    /// redeem stake batch workflows that failed on the staking pool side and were unlocked by the
+    /// workflow callback
+    pub redeem_stake_batch_workflow_failures: u64,
+    /// batch locks that were cleared by the operator
+    pub locks_forced: u64,
+    /// batch receipts that were deleted once all of their funds were claimed
+    pub receipts_deleted: u64,
+}
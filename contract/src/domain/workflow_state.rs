@@ -0,0 +1,123 @@
+use crate::domain::{
+    BatchId, RedeemLock, RedeemStakeBatch, RedeemStakeBatchReceipt, StakeBatch, StakeBatchReceipt,
+    StakeLock,
+};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// the minimal mutable workflow state that is needed to resume the batch workflows on another
+/// contract deployment
+/// - exported and imported as Borsh serialized bytes to support disaster recovery drills and
+///   shadow deployments
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct WorkflowState {
+    pub batch_id_sequence: BatchId,
+
+    pub stake_batch: Option<StakeBatch>,
+    pub next_stake_batch: Option<StakeBatch>,
+    pub redeem_stake_batch: Option<RedeemStakeBatch>,
+    pub next_redeem_stake_batch: Option<RedeemStakeBatch>,
+
+    pub stake_batch_lock: Option<StakeLock>,
+    pub redeem_stake_batch_lock: Option<RedeemLock>,
+
+    pub unverified_stake_batches: Vec<BatchId>,
+    /// receipts that the workflows still depend on, i.e., receipts for the open batches and the
+    /// unverified stake batches
+    pub stake_batch_receipts: Vec<(BatchId, StakeBatchReceipt)>,
+    pub redeem_stake_batch_receipts: Vec<(BatchId, RedeemStakeBatchReceipt)>,
+}
+
+impl WorkflowState {
+    /// IDs of the batches whose receipts are pending, i.e., receipts the workflows still depend on
+    pub fn pending_receipt_batch_ids(
+        stake_batch: Option<StakeBatch>,
+        next_stake_batch: Option<StakeBatch>,
+        redeem_stake_batch: Option<RedeemStakeBatch>,
+        next_redeem_stake_batch: Option<RedeemStakeBatch>,
+        unverified_stake_batches: &[BatchId],
+    ) -> Vec<BatchId> {
+        let mut batch_ids: Vec<BatchId> = stake_batch
+            .iter()
+            .chain(next_stake_batch.iter())
+            .map(StakeBatch::id)
+            .chain(
+                redeem_stake_batch
+                    .iter()
+                    .chain(next_redeem_stake_batch.iter())
+                    .map(RedeemStakeBatch::id),
+            )
+            .chain(unverified_stake_batches.iter().copied())
+            .collect();
+        batch_ids.sort_by_key(|batch_id| batch_id.value());
+        batch_ids.dedup();
+        batch_ids
+    }
+
+    /// true if no batches are open and no workflow locks are held
+    pub fn is_idle(&self) -> bool {
+        self.stake_batch.is_none()
+            && self.next_stake_batch.is_none()
+            && self.redeem_stake_batch.is_none()
+            && self.next_redeem_stake_batch.is_none()
+            && self.stake_batch_lock.is_none()
+            && self.redeem_stake_batch_lock.is_none()
+            && self.unverified_stake_batches.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::StakeTokenValue;
+
+    fn workflow_state() -> WorkflowState {
+        WorkflowState {
+            batch_id_sequence: BatchId(3),
+            stake_batch: Some(StakeBatch::new(BatchId(3), 100.into())),
+            next_stake_batch: None,
+            redeem_stake_batch: Some(RedeemStakeBatch::new(BatchId(2), 50.into())),
+            next_redeem_stake_batch: None,
+            stake_batch_lock: None,
+            redeem_stake_batch_lock: Some(RedeemLock::PendingWithdrawal),
+            unverified_stake_batches: vec![BatchId(1)],
+            stake_batch_receipts: vec![(
+                BatchId(1),
+                StakeBatchReceipt::new(
+                    100.into(),
+                    StakeTokenValue::default(),
+                    "staking-pool.near".to_string(),
+                ),
+            )],
+            redeem_stake_batch_receipts: vec![],
+        }
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let state = workflow_state();
+        let bytes = state.try_to_vec().unwrap();
+        let state = WorkflowState::try_from_slice(&bytes).unwrap();
+        assert_eq!(state.batch_id_sequence, BatchId(3));
+        assert_eq!(
+            state.redeem_stake_batch_lock,
+            Some(RedeemLock::PendingWithdrawal)
+        );
+        assert_eq!(state.unverified_stake_batches, vec![BatchId(1)]);
+        assert_eq!(state.stake_batch_receipts.len(), 1);
+        assert_eq!(state.try_to_vec().unwrap(), bytes);
+        assert!(!state.is_idle());
+    }
+
+    #[test]
+    fn pending_receipt_batch_ids_are_sorted_and_unique() {
+        let state = workflow_state();
+        let batch_ids = WorkflowState::pending_receipt_batch_ids(
+            state.stake_batch,
+            state.next_stake_batch,
+            state.redeem_stake_batch,
+            state.next_redeem_stake_batch,
+            &[BatchId(2), BatchId(1)],
+        );
+        assert_eq!(batch_ids, vec![BatchId(1), BatchId(2), BatchId(3)]);
+    }
+}
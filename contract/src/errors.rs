@@ -9,6 +9,11 @@ pub mod asserts {
         "operator account ID must not be the contract account ID";
     pub const PREDECESSOR_MUST_BE_OWNER: &str =
         "contract call is only allowed by the contract owner";
+    pub const PREDECESSOR_MUST_BE_GOVERNANCE: &str =
+        "contract call is only allowed by the governance DAO account";
+    pub const CONTRACT_PAUSED: &str = "contract is paused";
+    pub const PREDECESSOR_MUST_BE_CONTRACT: &str =
+        "contract call is only allowed by the contract account itself";
 }
 
 pub mod staking_pool_failures {
@@ -28,14 +33,23 @@ pub mod staking_errors {
         "action is blocked because STAKE token value is being refreshed";
 
     pub const NO_FUNDS_IN_STAKE_BATCH_TO_WITHDRAW: &str = "there are no funds in stake batch";
+
+    pub const STAKING_POOL_UNAVAILABLE: &str =
+        "staking pool is unavailable - stake batch will be retried after the backoff period";
+
+    pub const STAKE_BATCH_DEPOSIT_COOLDOWN: &str =
+        "stake batch cannot be run until the deposit cooldown period has passed";
+
+    pub const WORKFLOW_BACKOFF_COOLDOWN: &str =
+        "workflow failed and cannot be retried until the backoff cooldown period has passed";
+
+    pub const STAKE_BATCH_PENDING_VERIFICATION: &str =
+        "account has STAKE pending verification - refresh the STAKE token value to verify the stake batch";
 }
 
 pub mod redeeming_stake_errors {
     pub const NO_REDEEM_STAKE_BATCH_TO_RUN: &str = "there is no redeem stake batch";
 
-    pub const UNSTAKING_BLOCKED_BY_PENDING_WITHDRAWAL: &str =
-        "unstaking is blocked until all unstaked NEAR can be withdrawn";
-
     pub const UNSTAKED_FUNDS_NOT_AVAILABLE_FOR_WITHDRAWAL: &str =
         "unstaked NEAR funds are not yet available for withdrawal";
 }
@@ -45,10 +59,43 @@ pub mod staking_service {
 
     pub const ZERO_REDEEM_AMOUNT: &str = "redeem amount must not be zero";
 
+    pub const ZERO_STAKE_TARGET: &str = "target STAKE amount must not be zero";
+
+    pub const STAKE_TARGET_DEPOSIT_INSUFFICIENT: &str =
+        "attached deposit is insufficient to stake for the target STAKE amount";
+
     pub const INSUFFICIENT_STAKE_FOR_REDEEM_REQUEST: &str =
         "account STAKE balance is insufficient to fulfill request";
 
     pub const BATCH_BALANCE_INSUFFICIENT: &str = "batch balance is insufficient to fulfill request";
+
+    pub const REDEEM_LIMIT_EXCEEDED: &str =
+        "account redeem limit for the current epoch would be exceeded";
+
+    pub const INSTANT_LIQUIDITY_UNAVAILABLE: &str =
+        "there is no NEAR liquidity available to instantly redeem STAKE";
+
+    pub const NO_QUEUED_WITHDRAWAL: &str = "account has no queued withdrawal";
+
+    pub const QUEUED_WITHDRAWAL_NOT_RELEASABLE: &str =
+        "queued withdrawal is not releasable until the next epoch";
+
+    pub const QUEUED_WITHDRAWAL_RECIPIENT_MISMATCH: &str =
+        "account has a queued withdrawal for a different recipient";
+
+    pub const NO_PENDING_STAKE_TOKEN_VALUE_JUMP: &str =
+        "there is no pending STAKE token value jump to confirm";
+
+    pub const PENDING_STAKE_TOKEN_VALUE_JUMP_STALE: &str =
+        "pending STAKE token value jump is stale because the STAKE supply has changed";
+
+    pub const REDEEM_FORWARDING_TO_SELF: &str =
+        "redeem proceeds cannot be forwarded to this contract";
+
+    pub const REDEEM_FORWARDING_TARGET_NOT_WHITELISTED: &str =
+        "redeem forwarding target is not whitelisted";
+
+    pub const NO_CLAIMED_NEAR_TO_RESTAKE: &str = "account has no claimed redeemed NEAR to restake";
 }
 
 pub mod illegal_state {
@@ -61,6 +108,44 @@ pub mod illegal_state {
         "ILLEGAL STATE : redeem stake batch receipt should exist";
 
     pub const ILLEGAL_REDEEM_LOCK_STATE: &str = "ILLEGAL STATE : illegal redeem lock state";
+
+    pub const BATCH_ID_SEQUENCE_EXHAUSTED: &str = "ILLEGAL STATE : batch ID sequence is exhausted";
+
+    pub const OPERATOR_PROPOSAL_ACTION_SHOULD_BE_VALID: &str =
+        "ILLEGAL STATE : operator proposal action should be valid";
+}
+
+pub mod access_control {
+    pub const OPERATOR_ALREADY_ADDED: &str = "account is already an operator";
+
+    pub const OPERATOR_NOT_ADDED: &str = "account was not added as an operator";
+
+    pub const MAX_OPERATORS_EXCEEDED: &str = "max number of operators has been reached";
+
+    pub const OPERATOR_MUST_BE_REGISTERED: &str = "operator account must be registered";
+
+    pub const INVALID_REQUIRED_CONFIRMATIONS: &str =
+        "required confirmations must be at least 1 and must not exceed the number of operators";
+
+    pub const OPERATOR_ACTION_REQUIRES_CONFIRMATIONS: &str =
+        "operator action requires multiple operator confirmations - it must be proposed";
+
+    pub const MAX_PENDING_OPERATOR_PROPOSALS_EXCEEDED: &str =
+        "max number of pending operator proposals has been reached";
+
+    pub const CONFIG_MANAGED_BY_GOVERNANCE: &str =
+        "config changes are managed by the governance DAO account";
+
+    pub const OPERATOR_PROPOSAL_NOT_FOUND: &str = "operator proposal was not found";
+
+    pub const OPERATOR_PROPOSAL_ALREADY_CONFIRMED: &str =
+        "operator has already confirmed the proposal";
+
+    pub const OPERATOR_PROPOSAL_NOT_CONFIRMED: &str =
+        "operator proposal does not have the required confirmations";
+
+    pub const OPERATOR_PROPOSAL_CANCEL_ACCESS_DENIED: &str =
+        "operator proposal can only be cancelled by the proposer or the owner";
 }
 
 pub mod account_management {
@@ -72,7 +157,56 @@ pub mod account_management {
     pub const UNREGISTER_REQUIRES_ZERO_BALANCES: &str =
         "all funds must be withdrawn from the account in order to unregister";
 
+    pub const UNREGISTER_REQUIRES_STAKE_BENEFICIARY: &str =
+        "a STAKE beneficiary is required to unregister an account that has a STAKE balance";
+
+    pub const UNREGISTER_STAKE_BENEFICIARY_INVALID: &str =
+        "the STAKE beneficiary must be another registered account";
+
     pub const ACCOUNT_NOT_REGISTERED: &str = "account is not registered";
+
+    pub const ACCOUNT_STORAGE_ESCROW_INSUFFICIENT: &str =
+        "account storage escrow is insufficient and the account NEAR balance is too low to cover the shortfall";
+
+    pub const REGISTRATION_POOL_DEPOSIT_REQUIRED: &str =
+        "deposit is required to fund the registration pool";
+
+    pub const REGISTRATION_POOL_INSUFFICIENT: &str =
+        "registration pool balance is too low to pay for the account storage fee";
+
+    pub const ACCOUNT_HIBERNATION_NOT_ALLOWED: &str =
+        "only accounts with zero balances, no batches, and no account settings can hibernate";
+
+    pub const ACCOUNT_REHYDRATION_FEE_INSUFFICIENT: &str =
+        "attached deposit is not enough to restore the hibernated account storage escrow";
+}
+
+pub mod batch_reservations {
+    pub const BATCH_RESERVATION_ALREADY_EXISTS: &str = "account already has a batch reservation";
+
+    pub const BATCH_RESERVATIONS_FULL: &str = "max number of batch reservations are queued";
+
+    pub const BATCH_RESERVATION_DEPOSIT_INSUFFICIENT: &str =
+        "attached deposit must cover the reserved amount plus the reservation fee";
+
+    pub const NO_BATCH_RESERVATION: &str = "account has no batch reservation";
+}
+
+pub mod batch_totals {
+    pub const BATCH_NOT_CURRENT: &str =
+        "batch totals can only be rebuilt for current batches that have not yet been run";
+
+    pub const BATCH_IS_CURRENT: &str =
+        "batch contributors can only be pruned once the batch is no longer a current batch";
+
+    pub const BATCH_TOTALS_REBUILD_OUT_OF_SEQUENCE: &str =
+        "batch totals rebuild must be resumed from the next contributor index";
+
+    pub const BATCH_CHANGED_DURING_REBUILD: &str =
+        "batch balance changed since the batch totals rebuild was started - restart the rebuild";
+
+    pub const BATCH_NOT_COMPLETED: &str =
+        "claims can only be processed once the batch has been run";
 }
 
 pub mod contract_owner {
@@ -86,3 +220,1564 @@ pub mod contract_owner {
     pub const TRANSFER_TO_NON_REGISTERED_ACCOUNT: &str =
         "contract ownership can only be transferred to a registered account";
 }
+
+pub mod exit_positions {
+    pub const EXIT_POSITION_ALREADY_EXISTS: &str = "account already has an exit position";
+
+    pub const EXIT_POSITION_NOT_FOUND: &str = "account has no exit position";
+
+    pub const NOTHING_TO_EXIT: &str =
+        "account has no STAKE balance and no pending redeem stake batches to exit";
+
+    pub const EXIT_POSITION_STORAGE_FEE_REQUIRED: &str =
+        "attached deposit is required to pay for the exit position storage";
+}
+
+pub mod feature_flags {
+    pub const FEATURE_NAME_INVALID: &str =
+        "feature name must not be blank and must not exceed the max length";
+
+    pub const FEATURE_ROLLOUT_PERCENTAGE_INVALID: &str =
+        "feature rollout percentage must be in the range 0-100";
+
+    pub const MAX_FEATURE_ROLLOUTS_REACHED: &str =
+        "max number of feature rollouts has been reached";
+
+    pub const FEATURE_ALLOWLIST_FULL: &str = "feature allowlist is full";
+
+    pub const FEATURE_NOT_FOUND: &str = "feature rollout does not exist";
+}
+
+pub mod fungible_token {
+    pub const INSUFFICIENT_GAS_FOR_RECEIVER: &str =
+        "prepaid gas is insufficient to cover the gas for receiver and the resolve transfer callback";
+}
+
+pub mod governance {
+    pub const GOVERNANCE_ALREADY_SET: &str = "governance DAO account is already set";
+
+    pub const GOVERNANCE_ID_MUST_NOT_BE_CONTRACT_ID: &str =
+        "governance DAO account ID must not be the contract account ID";
+}
+
+pub mod idle_near_sharing {
+    pub const IDLE_NEAR_LIQUIDITY_BUFFER_EXHAUSTED: &str =
+        "not enough liquid NEAR to fulfill the request while idle NEAR is swept into staking - please try again after the sweep cycle has completed";
+}
+
+pub mod invoices {
+    pub const ZERO_INVOICE_AMOUNT: &str = "invoice amount must not be zero";
+
+    pub const INVOICE_MEMO_TOO_LONG: &str = "invoice memo is too long";
+
+    pub const INVOICE_EXPIRY_MUST_BE_IN_FUTURE: &str = "invoice expiry must be in the future";
+
+    pub const INVOICE_STORAGE_FEE_REQUIRED: &str =
+        "attached deposit is not enough to pay for the invoice storage fee";
+
+    pub const INVOICE_NOT_FOUND: &str = "invoice was not found";
+
+    pub const INVOICE_NOT_PAYABLE: &str = "invoice has already been paid or has expired";
+
+    pub const INVOICE_SELF_PAYMENT: &str = "invoice cannot be paid by the payee";
+
+    pub const INVOICE_PAYEE_ONLY: &str = "invoice can only be closed by the payee";
+}
+
+pub mod liquidity_providers {
+    pub const LIQUIDITY_DEPOSIT_REQUIRED: &str = "attached deposit is required to add liquidity";
+
+    pub const ZERO_LIQUIDITY_AMOUNT: &str = "liquidity amount to remove must not be zero";
+
+    pub const LIQUIDITY_PROVIDER_POOL_BALANCE_INSUFFICIENT: &str =
+        "liquidity provider pool does not have enough available NEAR - NEAR that is pending withdrawal is returned to the pool once the withdrawal completes";
+
+    pub const INSUFFICIENT_LIQUIDITY_SHARES: &str =
+        "account does not own enough liquidity provider pool shares";
+
+    pub const UNREGISTER_REQUIRES_NO_LIQUIDITY_SHARES: &str =
+        "account cannot be unregistered while it owns liquidity provider pool shares";
+
+    pub const ZERO_SWAP_AMOUNT: &str = "STAKE amount to swap must not be zero";
+
+    pub const INSUFFICIENT_STAKE_FOR_SWAP: &str = "account has insufficient STAKE for the swap";
+
+    pub const SWAP_LIQUIDITY_INSUFFICIENT: &str =
+        "liquidity provider pool does not have enough available NEAR to fill the swap";
+
+    pub const SWAP_MIN_NEAR_OUT_NOT_MET: &str = "swap NEAR out would be less than the min NEAR out";
+}
+
+pub mod lockups {
+    pub const ZERO_LOCKUP_AMOUNT: &str = "lockup amount must not be zero";
+
+    pub const INVALID_VESTING_SCHEDULE: &str = "lockup vesting end must be after the vesting start";
+
+    pub const LOCKUP_ALREADY_EXISTS: &str = "beneficiary already has a lockup";
+
+    pub const LOCKUP_STORAGE_FEE_REQUIRED: &str =
+        "attached deposit is not enough to pay for the lockup storage fee";
+
+    pub const LOCKUP_NOT_FOUND: &str = "account has no lockup";
+
+    pub const UNREGISTER_REQUIRES_NO_LOCKUP: &str =
+        "account cannot be unregistered while it has a lockup";
+}
+
+pub mod migration {
+    pub const UNKNOWN_MIGRATION_TASK: &str = "migration task is unknown";
+
+    pub const MIGRATION_ALREADY_COMPLETED: &str =
+        "migration has already completed - reset the migration in order to run it again";
+
+    pub const MIGRATION_IN_PROGRESS: &str =
+        "migration is in progress - it can only be reset once it has completed";
+
+    pub const BATCH_RECEIPTS_NOT_MIGRATED: &str =
+        "batch receipts have not yet been migrated - the batch receipt migrations must be advanced first";
+
+    pub const STATE_VERSION_AHEAD_OF_CODE: &str =
+        "persisted state version is newer than the contract code state version";
+
+    pub const STATE_MIGRATION_NOT_REGISTERED: &str =
+        "state migration step is not registered for the state version";
+
+    pub const CONTRACT_STATE_NOT_INITIALIZED: &str = "contract state is not initialized";
+}
+
+pub mod operator {
+    pub const UNKNOWN_STAKING_POOL_METHOD: &str =
+        "method is not a staking pool method that is invoked by the contract";
+
+    pub const CONFIG_VALIDATION_HASH_MISMATCH: &str =
+        "config validation hash does not match - the config must be validated against the current config";
+
+    pub const INVALID_WORKFLOW_STATE: &str = "workflow state data is invalid";
+
+    pub const WORKFLOW_STATE_IMPORT_REQUIRES_IDLE_WORKFLOWS: &str =
+        "workflow state can only be imported while no batches are open and no workflow locks are held";
+
+    pub const WORKFLOW_STATE_IMPORT_REQUIRES_FRESH_DEPLOYMENT: &str =
+        "workflow state can only be imported into a fresh deployment, i.e., no accounts are registered and no STAKE has been issued";
+
+    pub const WORKFLOW_STATE_BATCH_ID_SEQUENCE_BEHIND: &str =
+        "imported batch ID sequence must not be behind the current batch ID sequence";
+
+    pub const CONTRACT_ALREADY_PAUSED: &str = "contract is already paused";
+
+    pub const CONTRACT_NOT_PAUSED: &str = "contract is not paused";
+
+    pub const PAUSE_REASON_REQUIRED: &str = "pause reason is required";
+
+    pub const PAUSE_REASON_TOO_LONG: &str = "pause reason exceeds the max length";
+
+    pub const NO_STALE_LOCK: &str = "there is no stale workflow lock to release";
+}
+
+pub mod pending_transfers {
+    pub const PENDING_TRANSFER_NOT_FOUND: &str = "pending transfer was not found";
+
+    pub const TRANSFER_NOT_STALLED: &str =
+        "transfer cannot be resolved until the stalled transfer timeout has elapsed";
+
+    pub const PENDING_TRANSFER_ACCESS_DENIED: &str =
+        "stalled transfer can only be resolved by the sender or an operator";
+
+    pub const STALLED_TRANSFER_REFUND_REQUIRES_OPERATOR: &str =
+        "stalled transfer can only be refunded by an operator";
+}
+
+pub mod pool_preferences {
+    pub const MAX_POOL_PREFERENCES_EXCEEDED: &str =
+        "max number of staking pools that can be signaled per epoch has been reached";
+
+    pub const POOL_PREFERENCE_REQUIRES_STAKE: &str =
+        "account must hold STAKE in order to signal a staking pool preference";
+}
+
+pub mod rebates {
+    pub const REBATE_POOL_DEPOSIT_REQUIRED: &str = "deposit is required to fund the rebate pool";
+
+    pub const REBATE_ROUND_TOO_SHORT: &str = "rebate round must span at least 1 epoch";
+}
+
+pub mod receiver_authorization {
+    pub const DELEGATE_KEY_NOT_PERMITTED: &str =
+        "delegate keys cannot change the account receiver authorizations";
+
+    pub const MAX_DELEGATE_KEYS_REACHED: &str = "max number of delegate keys has been reached";
+
+    pub const MAX_AUTHORIZED_RECEIVERS_REACHED: &str =
+        "max number of authorized receivers has been reached";
+
+    pub const RECEIVER_AUTHORIZATION_STORAGE_FEE_REQUIRED: &str =
+        "attached deposit is not enough to pay for the receiver authorization storage fee";
+
+    pub const RECEIVER_NOT_AUTHORIZED: &str =
+        "receiver is not authorized for transfer calls signed with a delegate key";
+}
+
+pub mod redeem_splits {
+    pub const REDEEM_SPLIT_ALREADY_EXISTS: &str = "account already has a redeem split";
+
+    pub const REDEEM_SPLITS_FULL: &str = "max number of redeem splits are queued";
+
+    pub const REDEEM_SPLIT_BATCHES_INVALID: &str =
+        "redeem split max batches must be between 1 and MAX_REDEEM_SPLIT_BATCHES";
+
+    pub const NO_REDEEM_SPLIT: &str = "account has no redeem split";
+}
+
+pub mod referrals {
+    pub const INVALID_REFERRAL_CODE: &str =
+        "referral code must be 3-32 characters and may only contain lowercase ASCII letters, digits, '-', and '_'";
+
+    pub const REFERRAL_CODE_TAKEN: &str =
+        "referral code is registered to another account and has not expired";
+
+    pub const REFERRAL_CODES_FULL: &str = "max number of referral codes are registered";
+
+    pub const REFERRAL_CODE_STORAGE_FEE_REQUIRED: &str =
+        "attached deposit is required to pay for the referral code storage";
+
+    pub const REFERRAL_CODE_NOT_FOUND: &str = "referral code does not exist";
+
+    pub const REFERRAL_CODE_OWNER_ONLY: &str =
+        "referral code can only be unregistered by the account that owns it";
+
+    pub const REFERRAL_CODE_EXPIRED: &str = "referral code has expired";
+
+    pub const SELF_REFERRAL: &str = "account cannot deposit with its own referral code";
+}
+
+pub mod scheduled_actions {
+    pub const NO_SCHEDULED_ACTION: &str =
+        "account has no active scheduled action with the specified ID";
+}
+
+pub mod staking_pools {
+    pub const STAKING_POOL_NOT_WHITELISTED: &str =
+        "staking pool is not whitelisted by the staking pool whitelist contract";
+
+    pub const STAKING_POOL_WHITELIST_UNCONFIRMED: &str =
+        "the staking pool must be confirmed by the staking pool whitelist contract before NEAR is staked with it";
+
+    pub const STAKING_POOL_WHITELIST_CHECK_DISABLED: &str =
+        "the staking pool whitelist check is disabled";
+
+    pub const STAKING_POOL_UNCHANGED: &str = "account is already the staking pool";
+
+    pub const STAKING_POOL_CHANGE_BLOCKED: &str =
+        "the staking pool can only be changed while no STAKE is outstanding and no batches are pending";
+}
+
+pub mod storage_refunds {
+    pub const NO_STORAGE_REFUND: &str = "no storage refund is owed to the account";
+}
+
+pub mod swap_intents {
+    pub const SWAP_DEPLOYMENT_NOT_REGISTERED: &str =
+        "STAKE contract is not a registered swap deployment";
+
+    pub const SWAP_DEPLOYMENT_MUST_NOT_BE_SELF: &str =
+        "this contract cannot be registered as a swap deployment";
+
+    pub const ZERO_SWAP_INTENT_AMOUNT: &str = "swap intent amount must not be zero";
+
+    pub const SWAP_INTENT_TARGET_MISMATCH: &str =
+        "account has an open swap intent for a different target STAKE contract";
+
+    pub const SWAP_INTENT_NOT_FOUND: &str = "swap intent was not found";
+
+    pub const INVALID_FILL_SWAP_INTENT_MESSAGE: &str = "invalid fill swap intent message";
+
+    pub const UNREGISTER_REQUIRES_NO_SWAP_INTENT: &str =
+        "account cannot be unregistered while it has an open swap intent";
+}
+
+pub mod treasury {
+    pub const ZERO_TREASURY_TRANSFER_AMOUNT: &str = "treasury transfer amount must not be zero";
+
+    pub const TREASURY_TRANSFER_TO_TREASURY: &str =
+        "treasury STAKE cannot be transferred to the treasury account";
+
+    pub const TREASURY_BALANCE_INSUFFICIENT: &str =
+        "treasury STAKE balance is too low to fulfill request";
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::contract::StakingPoolAccount;
+    use crate::domain::{self, RedeemLock, RedeemStakeBatch, StakeLock};
+    use crate::interface::{
+        AccessControl, AccountManagement, BatchReservations, BatchTotals, BlockTimestamp,
+        ContractOwner, ExitPositions, FeatureFlags, FeeRebates, FungibleToken, Governance,
+        Invoices, LiquidityProviders, Lockups, Migrations, Operator, PendingTransfers,
+        PoolPreferences, ReceiverAuthorization, RedeemSplits, Referrals, ScheduledActionId,
+        ScheduledActions, StakingPools, StakingService, StorageRefunds, SwapIntents,
+        TransferReceiver, Treasury,
+    };
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use crate::Contract;
+    use near_sdk::{
+        json_types::{Base58PublicKey, ValidAccountId, U128, U64},
+        serde_json, testing_env, MockedBlockchain,
+    };
+    use std::collections::BTreeSet;
+    use std::panic::{self, AssertUnwindSafe};
+
+    /// maps an error constant to the setup that is expected to trigger it
+    struct ErrorCase {
+        name: &'static str,
+        message: &'static str,
+        setup: fn(&mut TestContext<'static>),
+    }
+
+    macro_rules! error_case {
+        ($module:ident::$error:ident, $setup:expr) => {
+            ErrorCase {
+                name: stringify!($error),
+                message: $module::$error,
+                setup: $setup,
+            }
+        };
+    }
+
+    fn set_predecessor(ctx: &mut TestContext, account_id: &str, attached_deposit: u128) {
+        let mut context = ctx.set_predecessor_account_id(account_id);
+        context.attached_deposit = attached_deposit;
+        testing_env!(context);
+    }
+
+    /// callbacks are invoked by the contract itself
+    fn set_predecessor_to_contract(ctx: &mut TestContext) {
+        let contract_id = ctx.context.current_account_id.clone();
+        set_predecessor(ctx, &contract_id, 0);
+    }
+
+    /// workflow state can only be imported while no accounts are registered
+    fn simulate_fresh_deployment(ctx: &mut TestContext) {
+        ctx.accounts_len = 0;
+    }
+
+    fn contract_id(ctx: &TestContext) -> ValidAccountId {
+        to_valid_account_id(&ctx.context.current_account_id)
+    }
+
+    fn credit_near(ctx: &mut TestContext, amount: u128) {
+        let mut account = ctx.registered_account(ctx.account_id);
+        account.apply_near_credit(amount.into());
+        ctx.save_registered_account(&account);
+        ctx.total_near.credit(amount.into());
+    }
+
+    fn delegate_key(index: u8) -> Base58PublicKey {
+        let mut key = vec![0; 33];
+        key[32] = index;
+        Base58PublicKey(key)
+    }
+
+    /// registers a delegate key for the test account and then signs with it
+    fn sign_with_delegate_key(ctx: &mut TestContext) {
+        let account_id = ctx.account_id;
+        set_predecessor(ctx, account_id, YOCTO);
+        ctx.add_delegate_key(delegate_key(0));
+        let mut context = ctx.set_predecessor_account_id(account_id);
+        context.signer_account_pk = delegate_key(0).0;
+        context.attached_deposit = 1;
+        testing_env!(context);
+    }
+
+    fn merge_config(ctx: &mut TestContext, config: &str) {
+        ctx.config.merge(serde_json::from_str(config).unwrap());
+    }
+
+    fn staking_pool_account(unstaked_balance: u128, can_withdraw: bool) -> StakingPoolAccount {
+        StakingPoolAccount {
+            account_id: TEST_STAKING_POOL_ID.to_string(),
+            unstaked_balance: U128(unstaked_balance),
+            staked_balance: U128(0),
+            can_withdraw,
+        }
+    }
+
+    fn limit_withdrawals_per_epoch(ctx: &mut TestContext) {
+        credit_near(ctx, 100 * YOCTO);
+        merge_config(
+            ctx,
+            &format!(r#"{{"max_withdrawal_per_epoch": "{}"}}"#, 10 * YOCTO),
+        );
+    }
+
+    /// registers a pending transfer from the test account and advances the block height past the
+    /// stalled transfer timeout
+    fn stalled_transfer(ctx: &mut TestContext) -> u64 {
+        let account_id = ctx.account_id;
+        let transfer_id = ctx.register_pending_transfer(account_id, "receiver.near", YOCTO.into());
+        let timeout_blocks = ctx.config.stalled_transfer_timeout_blocks();
+        ctx.context.block_index += timeout_blocks;
+        set_predecessor(ctx, account_id, 0);
+        transfer_id
+    }
+
+    fn create_invoice(ctx: &mut TestContext) -> crate::interface::Invoice {
+        let account_id = ctx.account_id;
+        set_predecessor(ctx, account_id, YOCTO);
+        ctx.create_invoice(
+            YOCTO.into(),
+            "order #1".to_string(),
+            BlockTimestamp(1000.into()),
+        )
+    }
+
+    /// adds alice.near as an operator and requires 2 operator confirmations - the predecessor is
+    /// set to the primary operator account
+    fn require_operator_confirmations(ctx: &mut TestContext) {
+        ctx.register_account("alice.near");
+        set_predecessor(ctx, TEST_OWNER_ID, 0);
+        ctx.add_operator(to_valid_account_id("alice.near"));
+        ctx.set_required_confirmations(2);
+        set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+    }
+
+    fn propose_resume(ctx: &mut TestContext) -> U64 {
+        set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+        ctx.propose_operator_action(crate::interface::OperatorAction::Resume)
+            .id
+    }
+
+    fn error_cases() -> Vec<ErrorCase> {
+        vec![
+            // asserts
+            error_case!(asserts::PREDECESSOR_MUST_NE_SELF_OR_OPERATOR, |ctx| {
+                ctx.clear_stake_lock();
+            }),
+            error_case!(asserts::PREDECESSOR_MUST_BE_OPERATOR, |ctx| {
+                ctx.whitelist_auto_registration(to_valid_account_id("bob.near"));
+            }),
+            error_case!(asserts::OPERATOR_ID_MUST_NOT_BE_CONTRACT_ID, |ctx| {
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                let contract_id = contract_id(ctx);
+                ctx.set_operator_id(contract_id);
+            }),
+            error_case!(asserts::PREDECESSOR_MUST_BE_OWNER, |ctx| {
+                ctx.transfer_ownership(to_valid_account_id(TEST_OPERATOR_ID));
+            }),
+            error_case!(asserts::PREDECESSOR_MUST_BE_GOVERNANCE, |ctx| {
+                ctx.relinquish_governance();
+            }),
+            error_case!(asserts::CONTRACT_PAUSED, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.pause("incident".to_string());
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.deposit(None);
+            }),
+            error_case!(asserts::PREDECESSOR_MUST_BE_CONTRACT, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                Contract::migrate();
+            }),
+            // staking_pool_failures
+            error_case!(staking_pool_failures::UNSTAKE_FAILURE, |ctx| {
+                set_predecessor_to_contract(ctx);
+                set_env_with_failed_promise_result(&mut ctx.contract);
+                ctx.on_unstake();
+            }),
+            error_case!(staking_pool_failures::GET_ACCOUNT_FAILURE, |ctx| {
+                set_predecessor_to_contract(ctx);
+                set_env_with_failed_promise_result(&mut ctx.contract);
+                ctx.on_redeeming_stake_pending_withdrawal(staking_pool_account(0, false));
+            }),
+            error_case!(staking_pool_failures::WITHDRAW_ALL_FAILURE, |ctx| {
+                set_predecessor_to_contract(ctx);
+                set_env_with_failed_promise_result(&mut ctx.contract);
+                ctx.on_redeeming_stake_post_withdrawal();
+            }),
+            // staking_errors
+            error_case!(staking_errors::BLOCKED_BY_BATCH_RUNNING, |ctx| {
+                ctx.stake_batch_lock = Some(StakeLock::Staking);
+                ctx.stake();
+            }),
+            error_case!(
+                staking_errors::BLOCKED_BY_STAKE_TOKEN_VALUE_REFRESH,
+                |ctx| {
+                    ctx.stake_batch_lock = Some(StakeLock::RefreshingStakeTokenValue);
+                    ctx.stake();
+                }
+            ),
+            error_case!(staking_errors::NO_FUNDS_IN_STAKE_BATCH_TO_WITHDRAW, |ctx| {
+                ctx.withdraw_from_stake_batch(YOCTO.into());
+            }),
+            error_case!(staking_errors::STAKING_POOL_UNAVAILABLE, |ctx| {
+                ctx.staking_pool_unavailable_until = Some(10.into());
+                ctx.stake();
+            }),
+            error_case!(staking_errors::STAKE_BATCH_DEPOSIT_COOLDOWN, |ctx| {
+                merge_config(ctx, r#"{"deposit_cooldown_blocks": 10}"#);
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.deposit(None);
+                set_predecessor(ctx, account_id, 0);
+                ctx.stake();
+            }),
+            error_case!(staking_errors::STAKE_BATCH_PENDING_VERIFICATION, |ctx| {
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                let batch_id = ctx.deposit(None);
+                let batch_id = domain::BatchId(batch_id.into());
+                ctx.stake_batch = None;
+                ctx.unverified_stake_batches.push(batch_id);
+                ctx.deposit(None);
+            }),
+            error_case!(staking_errors::WORKFLOW_BACKOFF_COOLDOWN, |ctx| {
+                merge_config(ctx, r#"{"workflow_backoff_base_blocks": 10}"#);
+                ctx.workflow_backoffs
+                    .get_mut(domain::Workflow::RefreshStakeTokenValue)
+                    .record_failure(domain::BlockHeight(ctx.context.block_index));
+                ctx.refresh_stake_token_value();
+            }),
+            // redeeming_stake_errors
+            error_case!(
+                redeeming_stake_errors::NO_REDEEM_STAKE_BATCH_TO_RUN,
+                |ctx| {
+                    ctx.unstake();
+                }
+            ),
+            error_case!(
+                redeeming_stake_errors::UNSTAKED_FUNDS_NOT_AVAILABLE_FOR_WITHDRAWAL,
+                |ctx| {
+                    set_predecessor_to_contract(ctx);
+                    set_env_with_success_promise_result(&mut ctx.contract);
+                    ctx.on_redeeming_stake_pending_withdrawal(staking_pool_account(YOCTO, false));
+                }
+            ),
+            // staking_service
+            error_case!(staking_service::DEPOSIT_REQUIRED_FOR_STAKE, |ctx| {
+                ctx.deposit(None);
+            }),
+            error_case!(staking_service::ZERO_REDEEM_AMOUNT, |ctx| {
+                ctx.redeem(0.into());
+            }),
+            error_case!(staking_service::ZERO_STAKE_TARGET, |ctx| {
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.deposit_for_stake_target(0.into());
+            }),
+            error_case!(staking_service::STAKE_TARGET_DEPOSIT_INSUFFICIENT, |ctx| {
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.deposit_for_stake_target((2 * YOCTO).into());
+            }),
+            error_case!(
+                staking_service::INSUFFICIENT_STAKE_FOR_REDEEM_REQUEST,
+                |ctx| {
+                    ctx.redeem(YOCTO.into());
+                }
+            ),
+            error_case!(staking_service::BATCH_BALANCE_INSUFFICIENT, |ctx| {
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                ctx.redeem((5 * YOCTO).into());
+                ctx.remove_from_redeem_stake_batch((6 * YOCTO).into());
+            }),
+            error_case!(staking_service::REDEEM_LIMIT_EXCEEDED, |ctx| {
+                ctx.credit_stake(ctx.account_id, 100 * YOCTO);
+                ctx.set_max_redeem_per_epoch(Some((10 * YOCTO).into()));
+                ctx.redeem((11 * YOCTO).into());
+            }),
+            error_case!(staking_service::INSTANT_LIQUIDITY_UNAVAILABLE, |ctx| {
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                ctx.redeem_for_near_instant(YOCTO.into());
+            }),
+            error_case!(staking_service::NO_QUEUED_WITHDRAWAL, |ctx| {
+                let account_id = to_valid_account_id(ctx.account_id);
+                ctx.process_queued_withdrawal(account_id);
+            }),
+            error_case!(staking_service::QUEUED_WITHDRAWAL_NOT_RELEASABLE, |ctx| {
+                limit_withdrawals_per_epoch(ctx);
+                ctx.withdraw((15 * YOCTO).into());
+                let account_id = to_valid_account_id(ctx.account_id);
+                ctx.process_queued_withdrawal(account_id);
+            }),
+            error_case!(
+                staking_service::QUEUED_WITHDRAWAL_RECIPIENT_MISMATCH,
+                |ctx| {
+                    limit_withdrawals_per_epoch(ctx);
+                    ctx.transfer_near(to_valid_account_id("bob.near"), (15 * YOCTO).into());
+                    ctx.withdraw((5 * YOCTO).into());
+                }
+            ),
+            error_case!(staking_service::NO_PENDING_STAKE_TOKEN_VALUE_JUMP, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.confirm_stake_value_jump();
+            }),
+            error_case!(
+                staking_service::PENDING_STAKE_TOKEN_VALUE_JUMP_STALE,
+                |ctx| {
+                    merge_config(ctx, r#"{"max_stake_token_value_increase_percentage": 5}"#);
+                    ctx.total_stake.credit((100 * YOCTO).into());
+                    ctx.update_stake_token_value((100 * YOCTO).into());
+                    ctx.update_stake_token_value((110 * YOCTO).into());
+                    ctx.total_stake.credit(YOCTO.into());
+                    set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                    ctx.confirm_stake_value_jump();
+                }
+            ),
+            error_case!(staking_service::REDEEM_FORWARDING_TO_SELF, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                let contract_id = contract_id(ctx);
+                ctx.whitelist_redeem_forwarding_target(contract_id);
+            }),
+            error_case!(
+                staking_service::REDEEM_FORWARDING_TARGET_NOT_WHITELISTED,
+                |ctx| {
+                    ctx.set_redeem_forwarding(Some(to_valid_account_id("target.near")));
+                }
+            ),
+            error_case!(staking_service::NO_CLAIMED_NEAR_TO_RESTAKE, |ctx| {
+                ctx.redeem_and_restake_rewards();
+            }),
+            // illegal_state
+            error_case!(illegal_state::STAKE_BATCH_SHOULD_EXIST, |ctx| {
+                ctx.stake();
+            }),
+            error_case!(illegal_state::REDEEM_STAKE_BATCH_SHOULD_EXIST, |ctx| {
+                set_predecessor_to_contract(ctx);
+                ctx.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+                ctx.on_run_redeem_stake_batch(staking_pool_account(0, false));
+            }),
+            error_case!(
+                illegal_state::REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST,
+                |ctx| {
+                    set_predecessor_to_contract(ctx);
+                    set_env_with_success_promise_result(&mut ctx.contract);
+                    *ctx.batch_id_sequence += 1;
+                    let batch_id = ctx.batch_id_sequence;
+                    ctx.redeem_stake_batch =
+                        Some(RedeemStakeBatch::new(batch_id, (100 * YOCTO).into()));
+                    ctx.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+                    ctx.on_redeeming_stake_pending_withdrawal(staking_pool_account(0, false));
+                }
+            ),
+            error_case!(illegal_state::ILLEGAL_REDEEM_LOCK_STATE, |ctx| {
+                set_predecessor_to_contract(ctx);
+                ctx.on_run_redeem_stake_batch(staking_pool_account(0, false));
+            }),
+            error_case!(illegal_state::BATCH_ID_SEQUENCE_EXHAUSTED, |ctx| {
+                ctx.batch_id_sequence = domain::BatchId(domain::MAX_BATCH_ID_SEQUENCE);
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.deposit(None);
+            }),
+            error_case!(
+                illegal_state::OPERATOR_PROPOSAL_ACTION_SHOULD_BE_VALID,
+                |ctx| {
+                    set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                    let proposal = domain::OperatorProposal::new(
+                        domain::OperatorAction::UpdateConfig("{".to_string(), vec![]),
+                        TEST_OPERATOR_ID,
+                    );
+                    ctx.operator_proposals.insert(&1, &proposal);
+                    ctx.operator_proposal(1.into());
+                }
+            ),
+            // access_control
+            error_case!(access_control::OPERATOR_ALREADY_ADDED, |ctx| {
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                ctx.add_operator(to_valid_account_id(TEST_OPERATOR_ID));
+            }),
+            error_case!(access_control::OPERATOR_NOT_ADDED, |ctx| {
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                ctx.remove_operator(to_valid_account_id(TEST_OPERATOR_ID));
+            }),
+            error_case!(access_control::MAX_OPERATORS_EXCEEDED, |ctx| {
+                let operators: Vec<String> = (0..=domain::MAX_OPERATORS)
+                    .map(|i| format!("operator-{}.near", i))
+                    .collect();
+                for account_id in operators.iter() {
+                    ctx.register_account(account_id);
+                }
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                for account_id in operators.iter() {
+                    ctx.add_operator(to_valid_account_id(account_id));
+                }
+            }),
+            error_case!(access_control::OPERATOR_MUST_BE_REGISTERED, |ctx| {
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                ctx.add_operator(to_valid_account_id("alice.near"));
+            }),
+            error_case!(access_control::INVALID_REQUIRED_CONFIRMATIONS, |ctx| {
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                ctx.set_required_confirmations(2);
+            }),
+            error_case!(
+                access_control::OPERATOR_ACTION_REQUIRES_CONFIRMATIONS,
+                |ctx| {
+                    require_operator_confirmations(ctx);
+                    ctx.resume();
+                }
+            ),
+            error_case!(
+                access_control::MAX_PENDING_OPERATOR_PROPOSALS_EXCEEDED,
+                |ctx| {
+                    for _ in 0..=domain::MAX_PENDING_OPERATOR_PROPOSALS {
+                        propose_resume(ctx);
+                    }
+                }
+            ),
+            error_case!(access_control::CONFIG_MANAGED_BY_GOVERNANCE, |ctx| {
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                ctx.set_governance_id(to_valid_account_id("dao.near"));
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.propose_operator_action(crate::interface::OperatorAction::UpdateConfig {
+                    config: crate::interface::Config::default(),
+                    validation_hash: near_sdk::json_types::Base64VecU8(vec![]),
+                });
+            }),
+            error_case!(access_control::OPERATOR_PROPOSAL_NOT_FOUND, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.confirm_operator_action(1.into());
+            }),
+            error_case!(access_control::OPERATOR_PROPOSAL_ALREADY_CONFIRMED, |ctx| {
+                let proposal_id = propose_resume(ctx);
+                ctx.confirm_operator_action(proposal_id);
+            }),
+            error_case!(access_control::OPERATOR_PROPOSAL_NOT_CONFIRMED, |ctx| {
+                require_operator_confirmations(ctx);
+                let proposal_id = propose_resume(ctx);
+                ctx.execute_operator_action(proposal_id);
+            }),
+            error_case!(
+                access_control::OPERATOR_PROPOSAL_CANCEL_ACCESS_DENIED,
+                |ctx| {
+                    let proposal_id = propose_resume(ctx);
+                    set_predecessor(ctx, "bob.near", 0);
+                    ctx.cancel_operator_action(proposal_id);
+                }
+            ),
+            // account_management
+            error_case!(account_management::INSUFFICIENT_STORAGE_FEE, |ctx| {
+                set_predecessor(ctx, "bob.near", 0);
+                ctx.contract.register_account();
+            }),
+            error_case!(account_management::ACCOUNT_ALREADY_REGISTERED, |ctx| {
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.contract.register_account();
+            }),
+            error_case!(
+                account_management::UNREGISTER_REQUIRES_ZERO_BALANCES,
+                |ctx| {
+                    let account_id = ctx.account_id;
+                    set_predecessor(ctx, account_id, YOCTO);
+                    ctx.deposit(None);
+                    set_predecessor(ctx, account_id, 0);
+                    ctx.unregister_account(None);
+                }
+            ),
+            error_case!(
+                account_management::UNREGISTER_REQUIRES_STAKE_BENEFICIARY,
+                |ctx| {
+                    ctx.credit_stake(ctx.account_id, YOCTO);
+                    ctx.unregister_account(None);
+                }
+            ),
+            error_case!(
+                account_management::UNREGISTER_STAKE_BENEFICIARY_INVALID,
+                |ctx| {
+                    ctx.credit_stake(ctx.account_id, YOCTO);
+                    let account_id = to_valid_account_id(ctx.account_id);
+                    ctx.unregister_account(Some(account_id));
+                }
+            ),
+            error_case!(account_management::ACCOUNT_NOT_REGISTERED, |ctx| {
+                set_predecessor(ctx, "bob.near", 0);
+                ctx.unregister_account(None);
+            }),
+            error_case!(
+                account_management::ACCOUNT_STORAGE_ESCROW_INSUFFICIENT,
+                |ctx| {
+                    // the account storage escrow no longer covers the account storage cost
+                    merge_config(ctx, r#"{"account_storage_fee_margin_percentage": 100}"#);
+                    let account_id = ctx.account_id;
+                    set_predecessor(ctx, account_id, YOCTO);
+                    ctx.deposit(None);
+                }
+            ),
+            error_case!(
+                account_management::REGISTRATION_POOL_DEPOSIT_REQUIRED,
+                |ctx| {
+                    set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                    ctx.fund_registration_pool();
+                }
+            ),
+            error_case!(account_management::REGISTRATION_POOL_INSUFFICIENT, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.whitelist_auto_registration(to_valid_account_id("bob.near"));
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, 1);
+                ctx.ft_transfer_call(
+                    to_valid_account_id("bob.near"),
+                    YOCTO.into(),
+                    "".into(),
+                    None,
+                    None,
+                );
+            }),
+            error_case!(account_management::ACCOUNT_HIBERNATION_NOT_ALLOWED, |ctx| {
+                credit_near(ctx, YOCTO);
+                ctx.hibernate_account();
+            }),
+            error_case!(
+                account_management::ACCOUNT_REHYDRATION_FEE_INSUFFICIENT,
+                |ctx| {
+                    ctx.hibernate_account();
+                    let account_id = ctx.account_id;
+                    set_predecessor(ctx, account_id, 1);
+                    ctx.deposit(None);
+                }
+            ),
+            // batch_reservations
+            error_case!(
+                batch_reservations::BATCH_RESERVATION_ALREADY_EXISTS,
+                |ctx| {
+                    let account_id = ctx.account_id;
+                    set_predecessor(ctx, account_id, 100 * YOCTO);
+                    ctx.reserve_batch_slot((10 * YOCTO).into());
+                    ctx.reserve_batch_slot((10 * YOCTO).into());
+                }
+            ),
+            error_case!(batch_reservations::BATCH_RESERVATIONS_FULL, |ctx| {
+                for i in 0..domain::MAX_BATCH_RESERVATIONS {
+                    ctx.batch_reservation_queue.push(crate::core::Hash::from(
+                        format!("account-{}.near", i).as_str(),
+                    ));
+                }
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, 100 * YOCTO);
+                ctx.reserve_batch_slot((10 * YOCTO).into());
+            }),
+            error_case!(
+                batch_reservations::BATCH_RESERVATION_DEPOSIT_INSUFFICIENT,
+                |ctx| {
+                    let account_id = ctx.account_id;
+                    set_predecessor(ctx, account_id, YOCTO);
+                    ctx.reserve_batch_slot((10 * YOCTO).into());
+                }
+            ),
+            error_case!(batch_reservations::NO_BATCH_RESERVATION, |ctx| {
+                ctx.cancel_batch_reservation();
+            }),
+            // batch_totals
+            error_case!(batch_totals::BATCH_NOT_CURRENT, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.rebuild_batch_totals(domain::BatchId(1).into(), 0, 10, true);
+            }),
+            error_case!(batch_totals::BATCH_IS_CURRENT, |ctx| {
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                let batch_id = ctx.deposit(None);
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.prune_batch_contributors(batch_id, 10);
+            }),
+            error_case!(batch_totals::BATCH_TOTALS_REBUILD_OUT_OF_SEQUENCE, |ctx| {
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                let batch_id = ctx.deposit(None);
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.rebuild_batch_totals(batch_id, 1, 10, true);
+            }),
+            error_case!(batch_totals::BATCH_CHANGED_DURING_REBUILD, |ctx| {
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                let batch_id = ctx.deposit(None);
+                set_predecessor(ctx, "bob.near", YOCTO);
+                ctx.contract.register_account();
+                ctx.deposit(None);
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.rebuild_batch_totals(batch_id.clone(), 0, 1, true);
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.deposit(None);
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.rebuild_batch_totals(batch_id, 1, 1, true);
+            }),
+            error_case!(batch_totals::BATCH_NOT_COMPLETED, |ctx| {
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                let batch_id = ctx.deposit(None);
+                ctx.process_claims(batch_id, 0, 10);
+            }),
+            // contract_owner
+            error_case!(
+                contract_owner::INSUFFICIENT_FUNDS_FOR_OWNER_WITHDRAWAL,
+                |ctx| {
+                    set_predecessor(ctx, TEST_OWNER_ID, 0);
+                    ctx.withdraw_owner_balance((1_000_000 * YOCTO).into());
+                }
+            ),
+            error_case!(
+                contract_owner::INSUFFICIENT_FUNDS_FOR_OWNER_STAKING,
+                |ctx| {
+                    set_predecessor(ctx, TEST_OWNER_ID, 0);
+                    ctx.stake_owner_balance((1_000_000 * YOCTO).into());
+                }
+            ),
+            error_case!(contract_owner::TRANSFER_TO_NON_REGISTERED_ACCOUNT, |ctx| {
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                ctx.transfer_ownership(to_valid_account_id("bob.near"));
+            }),
+            // exit_positions
+            error_case!(exit_positions::EXIT_POSITION_ALREADY_EXISTS, |ctx| {
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.exit_position(to_valid_account_id("beneficiary.near"));
+                ctx.exit_position(to_valid_account_id("beneficiary.near"));
+            }),
+            error_case!(exit_positions::EXIT_POSITION_NOT_FOUND, |ctx| {
+                let account_id = ctx.account_id;
+                ctx.process_exit(to_valid_account_id(account_id));
+            }),
+            error_case!(exit_positions::NOTHING_TO_EXIT, |ctx| {
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.exit_position(to_valid_account_id("beneficiary.near"));
+            }),
+            error_case!(exit_positions::EXIT_POSITION_STORAGE_FEE_REQUIRED, |ctx| {
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, 0);
+                ctx.exit_position(to_valid_account_id("beneficiary.near"));
+            }),
+            // feature_flags
+            error_case!(feature_flags::FEATURE_NAME_INVALID, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.set_feature_rollout(" ".to_string(), 10);
+            }),
+            error_case!(feature_flags::FEATURE_ROLLOUT_PERCENTAGE_INVALID, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.set_feature_rollout("instant_redeem".to_string(), 101);
+            }),
+            error_case!(feature_flags::MAX_FEATURE_ROLLOUTS_REACHED, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                for i in 0..=domain::MAX_FEATURE_ROLLOUTS {
+                    ctx.set_feature_rollout(format!("feature-{}", i), 10);
+                }
+            }),
+            error_case!(feature_flags::FEATURE_ALLOWLIST_FULL, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.set_feature_rollout("instant_redeem".to_string(), 0);
+                for i in 0..=domain::MAX_FEATURE_ALLOWLIST_LEN {
+                    ctx.allow_feature(
+                        "instant_redeem".to_string(),
+                        to_valid_account_id(&format!("account-{}.near", i)),
+                    );
+                }
+            }),
+            error_case!(feature_flags::FEATURE_NOT_FOUND, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.allow_feature(
+                    "instant_redeem".to_string(),
+                    to_valid_account_id("bob.near"),
+                );
+            }),
+            // fungible_token
+            error_case!(fungible_token::INSUFFICIENT_GAS_FOR_RECEIVER, |ctx| {
+                ctx.register_account("bob.near");
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, 1);
+                ctx.ft_transfer_call(
+                    to_valid_account_id("bob.near"),
+                    YOCTO.into(),
+                    "".into(),
+                    None,
+                    Some(u64::MAX.into()),
+                );
+            }),
+            // governance
+            error_case!(governance::GOVERNANCE_ALREADY_SET, |ctx| {
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                ctx.set_governance_id(to_valid_account_id("dao.near"));
+                ctx.set_governance_id(to_valid_account_id("dao.near"));
+            }),
+            error_case!(governance::GOVERNANCE_ID_MUST_NOT_BE_CONTRACT_ID, |ctx| {
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                let contract_id = contract_id(ctx);
+                ctx.set_governance_id(contract_id);
+            }),
+            // idle_near_sharing
+            error_case!(
+                idle_near_sharing::IDLE_NEAR_LIQUIDITY_BUFFER_EXHAUSTED,
+                |ctx| {
+                    merge_config(
+                        ctx,
+                        r#"{"idle_near_sweep_percentage": 100, "idle_near_liquidity_buffer_percentage": 50}"#,
+                    );
+                    credit_near(ctx, 100 * YOCTO);
+                    ctx.sweep_idle_near();
+                    ctx.debit_total_near((51 * YOCTO).into());
+                }
+            ),
+            // invoices
+            error_case!(invoices::ZERO_INVOICE_AMOUNT, |ctx| {
+                ctx.create_invoice(0.into(), "".to_string(), BlockTimestamp(1000.into()));
+            }),
+            error_case!(invoices::INVOICE_MEMO_TOO_LONG, |ctx| {
+                ctx.create_invoice(
+                    YOCTO.into(),
+                    "x".repeat(domain::MAX_INVOICE_MEMO_LEN + 1),
+                    BlockTimestamp(1000.into()),
+                );
+            }),
+            error_case!(invoices::INVOICE_EXPIRY_MUST_BE_IN_FUTURE, |ctx| {
+                ctx.create_invoice(YOCTO.into(), "".to_string(), BlockTimestamp(0.into()));
+            }),
+            error_case!(invoices::INVOICE_STORAGE_FEE_REQUIRED, |ctx| {
+                ctx.create_invoice(YOCTO.into(), "".to_string(), BlockTimestamp(1000.into()));
+            }),
+            error_case!(invoices::INVOICE_NOT_FOUND, |ctx| {
+                ctx.pay_invoice(1.into());
+            }),
+            error_case!(invoices::INVOICE_NOT_PAYABLE, |ctx| {
+                let invoice = create_invoice(ctx);
+                let mut context = ctx.set_predecessor_account_id(TEST_OPERATOR_ID);
+                context.attached_deposit = 1;
+                context.block_timestamp = 1000;
+                testing_env!(context);
+                ctx.pay_invoice(invoice.id);
+            }),
+            error_case!(invoices::INVOICE_SELF_PAYMENT, |ctx| {
+                let invoice = create_invoice(ctx);
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, 1);
+                ctx.pay_invoice(invoice.id);
+            }),
+            error_case!(invoices::INVOICE_PAYEE_ONLY, |ctx| {
+                let invoice = create_invoice(ctx);
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.close_invoice(invoice.id);
+            }),
+            // liquidity_providers
+            error_case!(liquidity_providers::LIQUIDITY_DEPOSIT_REQUIRED, |ctx| {
+                ctx.add_liquidity();
+            }),
+            error_case!(liquidity_providers::ZERO_LIQUIDITY_AMOUNT, |ctx| {
+                ctx.remove_liquidity(0.into());
+            }),
+            error_case!(
+                liquidity_providers::LIQUIDITY_PROVIDER_POOL_BALANCE_INSUFFICIENT,
+                |ctx| {
+                    ctx.remove_liquidity(YOCTO.into());
+                }
+            ),
+            error_case!(liquidity_providers::INSUFFICIENT_LIQUIDITY_SHARES, |ctx| {
+                ctx.register_account("liquidity-provider.near");
+                set_predecessor(ctx, "liquidity-provider.near", YOCTO);
+                ctx.add_liquidity();
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, 0);
+                ctx.remove_liquidity(YOCTO.into());
+            }),
+            error_case!(
+                liquidity_providers::UNREGISTER_REQUIRES_NO_LIQUIDITY_SHARES,
+                |ctx| {
+                    let account_id = ctx.account_id;
+                    set_predecessor(ctx, account_id, YOCTO);
+                    ctx.add_liquidity();
+                    set_predecessor(ctx, account_id, 0);
+                    ctx.unregister_account(None);
+                }
+            ),
+            error_case!(liquidity_providers::ZERO_SWAP_AMOUNT, |ctx| {
+                ctx.swap_stake_for_near(0.into(), 0.into());
+            }),
+            error_case!(liquidity_providers::INSUFFICIENT_STAKE_FOR_SWAP, |ctx| {
+                ctx.swap_stake_for_near(YOCTO.into(), 0.into());
+            }),
+            error_case!(liquidity_providers::SWAP_LIQUIDITY_INSUFFICIENT, |ctx| {
+                ctx.credit_stake(ctx.account_id, YOCTO);
+                ctx.swap_stake_for_near(YOCTO.into(), 0.into());
+            }),
+            error_case!(liquidity_providers::SWAP_MIN_NEAR_OUT_NOT_MET, |ctx| {
+                ctx.credit_stake(ctx.account_id, YOCTO);
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, 10 * YOCTO);
+                ctx.add_liquidity();
+                ctx.swap_stake_for_near(YOCTO.into(), YOCTO.into());
+            }),
+            // lockups
+            error_case!(lockups::ZERO_LOCKUP_AMOUNT, |ctx| {
+                let account_id = to_valid_account_id(ctx.account_id);
+                ctx.create_lockup(
+                    account_id,
+                    0.into(),
+                    BlockTimestamp(0.into()),
+                    BlockTimestamp(1000.into()),
+                );
+            }),
+            error_case!(lockups::INVALID_VESTING_SCHEDULE, |ctx| {
+                let account_id = to_valid_account_id(ctx.account_id);
+                ctx.create_lockup(
+                    account_id,
+                    YOCTO.into(),
+                    BlockTimestamp(1000.into()),
+                    BlockTimestamp(1000.into()),
+                );
+            }),
+            error_case!(lockups::LOCKUP_ALREADY_EXISTS, |ctx| {
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                for _ in 0..2 {
+                    ctx.create_lockup(
+                        to_valid_account_id(account_id),
+                        YOCTO.into(),
+                        BlockTimestamp(0.into()),
+                        BlockTimestamp(1000.into()),
+                    );
+                }
+            }),
+            error_case!(lockups::LOCKUP_STORAGE_FEE_REQUIRED, |ctx| {
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                let account_id = to_valid_account_id(ctx.account_id);
+                ctx.create_lockup(
+                    account_id,
+                    YOCTO.into(),
+                    BlockTimestamp(0.into()),
+                    BlockTimestamp(1000.into()),
+                );
+            }),
+            error_case!(lockups::LOCKUP_NOT_FOUND, |ctx| {
+                ctx.claim_vested();
+            }),
+            error_case!(lockups::UNREGISTER_REQUIRES_NO_LOCKUP, |ctx| {
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.create_lockup(
+                    to_valid_account_id(account_id),
+                    (10 * YOCTO).into(),
+                    BlockTimestamp(0.into()),
+                    BlockTimestamp(1000.into()),
+                );
+                set_predecessor(ctx, account_id, 0);
+                ctx.unregister_account(None);
+            }),
+            // migration
+            error_case!(migration::UNKNOWN_MIGRATION_TASK, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.migrate_chunk("accounts".to_string(), 10);
+            }),
+            error_case!(migration::MIGRATION_ALREADY_COMPLETED, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.migrate_chunk("stake_batch_receipts".to_string(), 10);
+                ctx.migrate_chunk("stake_batch_receipts".to_string(), 10);
+            }),
+            error_case!(migration::MIGRATION_IN_PROGRESS, |ctx| {
+                ctx.batch_id_sequence = domain::BatchId(10);
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.migrate_chunk("stake_batch_receipts".to_string(), 2);
+                ctx.reset_migration("stake_batch_receipts".to_string());
+            }),
+            error_case!(migration::BATCH_RECEIPTS_NOT_MIGRATED, |ctx| {
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.deposit(None);
+                // the batch receipt migration has not yet reached the account's stake batch
+                ctx.migrations.insert(
+                    &domain::MigrationTask::StakeBatchReceipts,
+                    &domain::MigrationCursor::new(1, 2, domain::BlockTimeHeight::from_env()),
+                );
+                set_predecessor(ctx, account_id, 0);
+                ctx.claim_receipts();
+            }),
+            error_case!(migration::STATE_VERSION_AHEAD_OF_CODE, |ctx| {
+                let version = domain::CURRENT_STATE_VERSION.value() + 1;
+                Contract::save_state_version(domain::StateVersion(version));
+                set_predecessor_to_contract(ctx);
+                Contract::migrate();
+            }),
+            error_case!(migration::STATE_MIGRATION_NOT_REGISTERED, |ctx| {
+                Contract::save_state_version(domain::StateVersion(0));
+                set_predecessor_to_contract(ctx);
+                Contract::migrate();
+            }),
+            error_case!(migration::CONTRACT_STATE_NOT_INITIALIZED, |ctx| {
+                set_predecessor_to_contract(ctx);
+                Contract::migrate();
+            }),
+            // operator
+            error_case!(operator::UNKNOWN_STAKING_POOL_METHOD, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.record_staking_pool_gas_usage("transfer".to_string(), 1_000_000.into());
+            }),
+            error_case!(operator::CONFIG_VALIDATION_HASH_MISMATCH, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.update_config(
+                    crate::interface::Config::default(),
+                    near_sdk::json_types::Base64VecU8(vec![0; 32]),
+                );
+            }),
+            error_case!(operator::INVALID_WORKFLOW_STATE, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                simulate_fresh_deployment(ctx);
+                ctx.import_workflow_state(near_sdk::json_types::Base64VecU8(vec![1, 2, 3]));
+            }),
+            error_case!(
+                operator::WORKFLOW_STATE_IMPORT_REQUIRES_FRESH_DEPLOYMENT,
+                |ctx| {
+                    set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                    let export = ctx.export_workflow_state();
+                    ctx.import_workflow_state(export.data);
+                }
+            ),
+            error_case!(
+                operator::WORKFLOW_STATE_IMPORT_REQUIRES_IDLE_WORKFLOWS,
+                |ctx| {
+                    set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                    simulate_fresh_deployment(ctx);
+                    let export = ctx.export_workflow_state();
+                    ctx.stake_batch_lock = Some(StakeLock::Staking);
+                    ctx.import_workflow_state(export.data);
+                }
+            ),
+            error_case!(operator::WORKFLOW_STATE_BATCH_ID_SEQUENCE_BEHIND, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                simulate_fresh_deployment(ctx);
+                let export = ctx.export_workflow_state();
+                ctx.batch_id_sequence = domain::BatchId(10);
+                ctx.import_workflow_state(export.data);
+            }),
+            error_case!(operator::CONTRACT_ALREADY_PAUSED, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.pause("incident".to_string());
+                ctx.pause("incident".to_string());
+            }),
+            error_case!(operator::CONTRACT_NOT_PAUSED, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.resume();
+            }),
+            error_case!(operator::PAUSE_REASON_REQUIRED, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.pause("  ".to_string());
+            }),
+            error_case!(operator::PAUSE_REASON_TOO_LONG, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.pause("x".repeat(domain::MAX_PAUSE_REASON_LEN + 1));
+            }),
+            error_case!(operator::NO_STALE_LOCK, |ctx| {
+                ctx.release_stale_lock();
+            }),
+            // pending_transfers
+            error_case!(pending_transfers::PENDING_TRANSFER_NOT_FOUND, |ctx| {
+                ctx.resolve_stalled_transfer(1.into(), false);
+            }),
+            error_case!(pending_transfers::TRANSFER_NOT_STALLED, |ctx| {
+                let account_id = ctx.account_id;
+                let transfer_id =
+                    ctx.register_pending_transfer(account_id, "receiver.near", YOCTO.into());
+                ctx.resolve_stalled_transfer(transfer_id.into(), false);
+            }),
+            error_case!(pending_transfers::PENDING_TRANSFER_ACCESS_DENIED, |ctx| {
+                let transfer_id = stalled_transfer(ctx);
+                set_predecessor(ctx, "receiver.near", 0);
+                ctx.resolve_stalled_transfer(transfer_id.into(), false);
+            }),
+            error_case!(
+                pending_transfers::STALLED_TRANSFER_REFUND_REQUIRES_OPERATOR,
+                |ctx| {
+                    let transfer_id = stalled_transfer(ctx);
+                    ctx.resolve_stalled_transfer(transfer_id.into(), true);
+                }
+            ),
+            // pool_preferences
+            error_case!(pool_preferences::MAX_POOL_PREFERENCES_EXCEEDED, |ctx| {
+                let mut tallies = domain::PoolPreferenceTallies::default();
+                for i in 0..domain::MAX_POOL_PREFERENCES_PER_EPOCH {
+                    tallies.add(&format!("pool-{}.near", i), YOCTO.into());
+                }
+                ctx.pool_preference_tallies.insert(&0.into(), &tallies);
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                ctx.signal_pool_preference(to_valid_account_id(TEST_STAKING_POOL_ID));
+            }),
+            error_case!(pool_preferences::POOL_PREFERENCE_REQUIRES_STAKE, |ctx| {
+                ctx.signal_pool_preference(to_valid_account_id(TEST_STAKING_POOL_ID));
+            }),
+            // rebates
+            error_case!(rebates::REBATE_POOL_DEPOSIT_REQUIRED, |ctx| {
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                ctx.fund_rebate_pool();
+            }),
+            error_case!(rebates::REBATE_ROUND_TOO_SHORT, |ctx| {
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                ctx.close_rebate_round();
+            }),
+            // receiver_authorization
+            error_case!(receiver_authorization::DELEGATE_KEY_NOT_PERMITTED, |ctx| {
+                sign_with_delegate_key(ctx);
+                ctx.remove_delegate_key(delegate_key(0));
+            }),
+            error_case!(receiver_authorization::MAX_DELEGATE_KEYS_REACHED, |ctx| {
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                for i in 0..=domain::MAX_DELEGATE_KEYS {
+                    ctx.add_delegate_key(delegate_key(i as u8));
+                }
+            }),
+            error_case!(
+                receiver_authorization::MAX_AUTHORIZED_RECEIVERS_REACHED,
+                |ctx| {
+                    let account_id = ctx.account_id;
+                    set_predecessor(ctx, account_id, YOCTO);
+                    for i in 0..=domain::MAX_AUTHORIZED_RECEIVERS {
+                        ctx.authorize_receiver(to_valid_account_id(&format!(
+                            "receiver-{}.near",
+                            i
+                        )));
+                    }
+                }
+            ),
+            error_case!(
+                receiver_authorization::RECEIVER_AUTHORIZATION_STORAGE_FEE_REQUIRED,
+                |ctx| {
+                    ctx.authorize_receiver(to_valid_account_id("dex.near"));
+                }
+            ),
+            error_case!(receiver_authorization::RECEIVER_NOT_AUTHORIZED, |ctx| {
+                ctx.credit_stake(ctx.account_id, YOCTO);
+                sign_with_delegate_key(ctx);
+                ctx.ft_transfer_call(
+                    to_valid_account_id("dex.near"),
+                    YOCTO.into(),
+                    "".into(),
+                    None,
+                    None,
+                );
+            }),
+            // redeem_splits
+            error_case!(redeem_splits::REDEEM_SPLIT_ALREADY_EXISTS, |ctx| {
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                ctx.redeem_split((4 * YOCTO).into(), 2);
+                ctx.redeem_split((4 * YOCTO).into(), 2);
+            }),
+            error_case!(redeem_splits::REDEEM_SPLITS_FULL, |ctx| {
+                for i in 0..domain::MAX_REDEEM_SPLITS {
+                    ctx.redeem_split_queue.push(crate::core::Hash::from(
+                        format!("account-{}.near", i).as_str(),
+                    ));
+                }
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                ctx.redeem_split((4 * YOCTO).into(), 2);
+            }),
+            error_case!(redeem_splits::REDEEM_SPLIT_BATCHES_INVALID, |ctx| {
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                ctx.redeem_split((4 * YOCTO).into(), domain::MAX_REDEEM_SPLIT_BATCHES + 1);
+            }),
+            error_case!(redeem_splits::NO_REDEEM_SPLIT, |ctx| {
+                ctx.cancel_redeem_split();
+            }),
+            // referrals
+            error_case!(referrals::INVALID_REFERRAL_CODE, |ctx| {
+                ctx.register_referral_code("Partner".to_string());
+            }),
+            error_case!(referrals::REFERRAL_CODE_TAKEN, |ctx| {
+                ctx.register_account("partner.near");
+                set_predecessor(ctx, "partner.near", YOCTO);
+                ctx.register_referral_code("partner".to_string());
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.register_referral_code("partner".to_string());
+            }),
+            error_case!(referrals::REFERRAL_CODES_FULL, |ctx| {
+                for i in 0..domain::MAX_REFERRAL_CODES {
+                    ctx.referral_code_index
+                        .push(crate::core::Hash::from(format!("code-{}", i).as_str()));
+                }
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.register_referral_code("partner".to_string());
+            }),
+            error_case!(referrals::REFERRAL_CODE_STORAGE_FEE_REQUIRED, |ctx| {
+                ctx.register_referral_code("partner".to_string());
+            }),
+            error_case!(referrals::REFERRAL_CODE_NOT_FOUND, |ctx| {
+                ctx.unregister_referral_code("partner".to_string());
+            }),
+            error_case!(referrals::REFERRAL_CODE_OWNER_ONLY, |ctx| {
+                ctx.register_account("partner.near");
+                set_predecessor(ctx, "partner.near", YOCTO);
+                ctx.register_referral_code("partner".to_string());
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, 0);
+                ctx.unregister_referral_code("partner".to_string());
+            }),
+            error_case!(referrals::REFERRAL_CODE_EXPIRED, |ctx| {
+                ctx.register_account("partner.near");
+                set_predecessor(ctx, "partner.near", YOCTO);
+                ctx.register_referral_code("partner".to_string());
+                ctx.context.epoch_height += domain::REFERRAL_CODE_TTL_EPOCHS;
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.deposit(Some("partner".to_string()));
+            }),
+            error_case!(referrals::SELF_REFERRAL, |ctx| {
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.register_referral_code("partner".to_string());
+                ctx.deposit(Some("partner".to_string()));
+            }),
+            // scheduled_actions
+            error_case!(scheduled_actions::NO_SCHEDULED_ACTION, |ctx| {
+                ctx.cancel_scheduled_action(ScheduledActionId::RedeemForwarding);
+            }),
+            // staking_pools
+            error_case!(staking_pools::STAKING_POOL_NOT_WHITELISTED, |ctx| {
+                set_predecessor_to_contract(ctx);
+                ctx.on_staking_pool_whitelist_check(false, "pool-1.near".to_string());
+            }),
+            error_case!(staking_pools::STAKING_POOL_WHITELIST_UNCONFIRMED, |ctx| {
+                merge_config(ctx, r#"{"staking_pool_whitelist_check": true}"#);
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, YOCTO);
+                ctx.deposit(None);
+                set_predecessor(ctx, account_id, 0);
+                ctx.stake();
+            }),
+            error_case!(
+                staking_pools::STAKING_POOL_WHITELIST_CHECK_DISABLED,
+                |ctx| {
+                    set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                    ctx.check_staking_pool_whitelist();
+                }
+            ),
+            error_case!(staking_pools::STAKING_POOL_UNCHANGED, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.change_staking_pool(to_valid_account_id(TEST_STAKING_POOL_ID));
+            }),
+            error_case!(staking_pools::STAKING_POOL_CHANGE_BLOCKED, |ctx| {
+                ctx.total_stake.credit(YOCTO.into());
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.change_staking_pool(to_valid_account_id("pool-1.near"));
+            }),
+            // storage_refunds
+            error_case!(storage_refunds::NO_STORAGE_REFUND, |ctx| {
+                ctx.withdraw_storage_refund();
+            }),
+            // swap_intents
+            error_case!(swap_intents::SWAP_DEPLOYMENT_NOT_REGISTERED, |ctx| {
+                ctx.post_swap_intent(to_valid_account_id("swap.near"), YOCTO.into());
+            }),
+            error_case!(swap_intents::SWAP_DEPLOYMENT_MUST_NOT_BE_SELF, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                let contract_id = contract_id(ctx);
+                ctx.register_swap_deployment(contract_id);
+            }),
+            error_case!(swap_intents::ZERO_SWAP_INTENT_AMOUNT, |ctx| {
+                ctx.post_swap_intent(to_valid_account_id("swap.near"), 0.into());
+            }),
+            error_case!(swap_intents::SWAP_INTENT_TARGET_MISMATCH, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.register_swap_deployment(to_valid_account_id("swap-1.near"));
+                ctx.register_swap_deployment(to_valid_account_id("swap-2.near"));
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, 0);
+                ctx.post_swap_intent(to_valid_account_id("swap-1.near"), YOCTO.into());
+                ctx.post_swap_intent(to_valid_account_id("swap-2.near"), YOCTO.into());
+            }),
+            error_case!(swap_intents::SWAP_INTENT_NOT_FOUND, |ctx| {
+                ctx.cancel_swap_intent();
+            }),
+            error_case!(swap_intents::INVALID_FILL_SWAP_INTENT_MESSAGE, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.register_swap_deployment(to_valid_account_id("swap.near"));
+                set_predecessor(ctx, "swap.near", 0);
+                let sender_id = to_valid_account_id(ctx.account_id);
+                ctx.ft_on_transfer(sender_id, YOCTO.into(), "invalid".into());
+            }),
+            error_case!(swap_intents::UNREGISTER_REQUIRES_NO_SWAP_INTENT, |ctx| {
+                set_predecessor(ctx, TEST_OPERATOR_ID, 0);
+                ctx.register_swap_deployment(to_valid_account_id("swap.near"));
+                ctx.credit_stake(ctx.account_id, 10 * YOCTO);
+                let account_id = ctx.account_id;
+                set_predecessor(ctx, account_id, 0);
+                ctx.post_swap_intent(to_valid_account_id("swap.near"), (10 * YOCTO).into());
+                ctx.unregister_account(None);
+            }),
+            // treasury
+            error_case!(treasury::ZERO_TREASURY_TRANSFER_AMOUNT, |ctx| {
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                let account_id = to_valid_account_id(ctx.account_id);
+                ctx.treasury_transfer(account_id, 0.into());
+            }),
+            error_case!(treasury::TREASURY_TRANSFER_TO_TREASURY, |ctx| {
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                let contract_id = contract_id(ctx);
+                ctx.treasury_transfer(contract_id, YOCTO.into());
+            }),
+            error_case!(treasury::TREASURY_BALANCE_INSUFFICIENT, |ctx| {
+                set_predecessor(ctx, TEST_OWNER_ID, 0);
+                let account_id = to_valid_account_id(ctx.account_id);
+                ctx.treasury_transfer(account_id, YOCTO.into());
+            }),
+        ]
+    }
+
+    /// parses the error constant names declared in this file
+    fn error_constant_names() -> BTreeSet<&'static str> {
+        include_str!("errors.rs")
+            .split("#[cfg(test)]")
+            .next()
+            .unwrap()
+            .lines()
+            .filter_map(|line| line.trim_start().strip_prefix("pub const "))
+            .map(|line| line.split(':').next().unwrap())
+            .collect()
+    }
+
+    /// runs the case against a freshly initialized contract and returns the panic message
+    fn panic_message(case: &ErrorCase) -> Option<String> {
+        let mut ctx = TestContext::with_registered_account();
+        panic::catch_unwind(AssertUnwindSafe(|| (case.setup)(&mut ctx)))
+            .err()
+            .map(|payload| {
+                payload
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .or_else(|| payload.downcast_ref::<&str>().map(|msg| msg.to_string()))
+                    .unwrap_or_default()
+            })
+    }
+
+    #[test]
+    fn every_error_constant_has_an_error_case() {
+        let cases = error_cases();
+        let case_names: BTreeSet<&str> = cases.iter().map(|case| case.name).collect();
+        assert_eq!(case_names.len(), cases.len(), "duplicate error cases");
+        assert_eq!(case_names, error_constant_names());
+    }
+
+    #[test]
+    fn every_error_case_panics_with_its_error() {
+        let failures: Vec<String> = error_cases()
+            .iter()
+            .filter_map(|case| match panic_message(case) {
+                Some(msg) if msg.contains(case.message) => None,
+                Some(msg) => Some(format!("{} panicked with: {}", case.name, msg)),
+                None => Some(format!("{} did not panic", case.name)),
+            })
+            .collect();
+        assert!(failures.is_empty(), "{:#?}", failures);
+    }
+
+    #[test]
+    fn error_messages_are_unique() {
+        let cases = error_cases();
+        let messages: BTreeSet<&str> = cases.iter().map(|case| case.message).collect();
+        assert_eq!(messages.len(), cases.len());
+        assert!(messages.iter().all(|msg| !msg.is_empty()));
+    }
+}
@@ -1,18 +1,66 @@
 //! defines the interfaces that the contract exposes externally
 
+pub mod access_control;
+pub mod account_activity_logs;
 pub mod account_management;
+pub mod batch_history;
+pub mod batch_reservations;
+pub mod batch_totals;
 pub mod contract_owner;
+pub mod exit_positions;
+pub mod feature_flags;
 pub mod financials;
 pub mod fungible_token;
+pub mod governance;
+pub mod idle_near_sharing;
+pub mod invoices;
+pub mod liquidity_providers;
+pub mod lockups;
 pub mod metadata;
+pub mod migration;
 pub mod model;
 pub mod operator;
+pub mod pending_transfers;
+pub mod pool_preferences;
+pub mod rebates;
+pub mod receiver_authorization;
+pub mod redeem_splits;
+pub mod referrals;
+pub mod scheduled_actions;
+pub mod staking_pools;
 pub mod staking_service;
+pub mod storage_refunds;
+pub mod swap_intents;
+pub mod treasury;
 
+pub use access_control::*;
+pub use account_activity_logs::*;
 pub use account_management::*;
+pub use batch_history::*;
+pub use batch_reservations::*;
+pub use batch_totals::*;
 pub use contract_owner::*;
+pub use exit_positions::*;
+pub use feature_flags::*;
 pub use financials::*;
 pub use fungible_token::*;
+pub use governance::*;
+pub use idle_near_sharing::*;
+pub use invoices::*;
+pub use liquidity_providers::*;
+pub use lockups::*;
+pub use migration::*;
 pub use model::*;
 pub use operator::*;
+pub use pending_transfers::*;
+pub use pool_preferences::*;
+pub use rebates::*;
+pub use receiver_authorization::*;
+pub use redeem_splits::*;
+pub use referrals::*;
+pub use scheduled_actions::*;
+pub use staking_pools::*;
 pub use staking_service::*;
+pub use storage_refunds::*;
+pub use swap_intents::*;
+pub use treasury::*;
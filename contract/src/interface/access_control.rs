@@ -0,0 +1,140 @@
+use crate::interface::{OperatorAction, OperatorProposal};
+use near_sdk::{
+    json_types::{ValidAccountId, U64},
+    AccountId,
+};
+
+/// Role based access control for the operator role.
+///
+/// The owner can grant the operator role to multiple accounts in addition to the primary
+/// [operator account](crate::interface::Operator::operator_id). All operator accounts can perform
+/// the operator actions.
+///
+/// Once the number of [required confirmations](AccessControl::required_confirmations) is more than 1,
+/// sensitive operator actions can no longer be invoked directly. Instead, the action must be
+/// proposed by an operator, confirmed by other operators, and then executed once it has the
+/// required confirmations - see [OperatorAction]. The sensitive operator actions are:
+/// - config changes, including gas config changes and resetting the config
+/// - changing the staking pool
+/// - pausing and resuming the contract
+///
+/// Routine operator actions, e.g., recording staking pool gas usage, which can only auto-tune the
+/// gas within the validated gas bounds, do not require confirmations.
+///
+/// NOTE: while a [governance DAO](crate::interface::Governance) is set, config changes are managed
+/// by the DAO and cannot be proposed.
+pub trait AccessControl {
+    /// returns all operator accounts - the primary operator account is listed first
+    fn operators(&self) -> Vec<AccountId>;
+
+    /// grants the operator role to the account
+    /// - the account must be registered to protect against accounts that do not exist
+    ///
+    /// ## Panics
+    /// - if the predecessor account is not the owner account
+    /// - if the account is already an operator
+    /// - if the account is the contract account
+    /// - if the account is not registered
+    /// - if [MAX_OPERATORS](crate::domain::MAX_OPERATORS) have already been added
+    fn add_operator(&mut self, account_id: ValidAccountId);
+
+    /// revokes the operator role from the account
+    /// - confirmations on pending proposals from the account no longer count
+    /// - the primary operator account is changed via [set_operator_id](crate::interface::ContractOwner::set_operator_id)
+    ///
+    /// ## Panics
+    /// - if the predecessor account is not the owner account
+    /// - if the account was not added as an operator
+    /// - if there would not be enough operators left to meet the required confirmations
+    fn remove_operator(&mut self, account_id: ValidAccountId);
+
+    /// number of operator confirmations that sensitive operator actions require - defaults to 1,
+    /// i.e., sensitive operator actions can be invoked directly by any operator
+    fn required_confirmations(&self) -> u8;
+
+    /// ## Panics
+    /// - if the predecessor account is not the owner account
+    /// - if the count is zero or exceeds the number of operator accounts
+    fn set_required_confirmations(&mut self, count: u8);
+
+    /// proposes a sensitive operator action - the proposer's confirmation is recorded
+    /// - the action is checked when proposed, e.g., config changes are validated against the
+    ///   current config, and is checked again when executed
+    ///
+    /// ## Panics
+    /// - if not invoked by an operator account
+    /// - if [MAX_PENDING_OPERATOR_PROPOSALS](crate::domain::MAX_PENDING_OPERATOR_PROPOSALS) are
+    ///   already pending
+    /// - if the action is a config change while a governance DAO is set
+    /// - if the action is invalid
+    fn propose_operator_action(&mut self, action: OperatorAction) -> OperatorProposal;
+
+    /// ## Panics
+    /// - if not invoked by an operator account
+    /// - if the proposal does not exist
+    /// - if the operator has already confirmed the proposal
+    fn confirm_operator_action(&mut self, proposal_id: U64) -> OperatorProposal;
+
+    /// executes the proposed action once it has the required confirmations - the proposal is
+    /// removed once it is executed
+    ///
+    /// ## Panics
+    /// - if not invoked by an operator account
+    /// - if the proposal does not exist
+    /// - if the proposal does not have the required confirmations from current operator accounts
+    /// - if the action fails, e.g., the config changes are no longer valid
+    fn execute_operator_action(&mut self, proposal_id: U64) -> OperatorProposal;
+
+    /// ## Panics
+    /// - if the proposal does not exist
+    /// - if not invoked by the proposer or the owner account
+    fn cancel_operator_action(&mut self, proposal_id: U64) -> OperatorProposal;
+
+    fn operator_proposal(&self, proposal_id: U64) -> Option<OperatorProposal>;
+
+    /// returns the pending proposals in the order they were proposed
+    fn pending_operator_proposals(&self) -> Vec<OperatorProposal>;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct OperatorAdded<'a> {
+        pub account_id: &'a str,
+    }
+
+    #[derive(Debug)]
+    pub struct OperatorRemoved<'a> {
+        pub account_id: &'a str,
+    }
+
+    #[derive(Debug)]
+    pub struct RequiredConfirmationsChanged {
+        pub from: u8,
+        pub to: u8,
+    }
+
+    #[derive(Debug)]
+    pub struct OperatorActionProposed<'a> {
+        pub proposal_id: u64,
+        pub proposer: &'a str,
+    }
+
+    #[derive(Debug)]
+    pub struct OperatorActionConfirmed<'a> {
+        pub proposal_id: u64,
+        pub operator: &'a str,
+        pub confirmations: usize,
+    }
+
+    #[derive(Debug)]
+    pub struct OperatorActionExecuted<'a> {
+        pub proposal_id: u64,
+        pub executor: &'a str,
+    }
+
+    #[derive(Debug)]
+    pub struct OperatorActionCancelled<'a> {
+        pub proposal_id: u64,
+        pub cancelled_by: &'a str,
+    }
+}
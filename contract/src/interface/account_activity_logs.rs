@@ -0,0 +1,21 @@
+use crate::interface::AccountActivity;
+use near_sdk::json_types::ValidAccountId;
+
+/// Each account retains a log of its most recent activity, i.e., deposits, stakes, redeems, claims,
+/// withdrawals, and STAKE transfers. The number of entries that are retained is
+/// [configurable](crate::config::Config::account_activity_log_len) up to
+/// [MAX_ACCOUNT_ACTIVITY_LOG_LEN](crate::domain::MAX_ACCOUNT_ACTIVITY_LOG_LEN).
+///
+/// The activity log storage is covered by the account storage fee - accounts whose storage escrow
+/// does not cover the activity log, e.g., accounts that were registered before the activity log was
+/// introduced, do not have their activity logged.
+pub trait AccountActivityLogs {
+    /// Returns the account's activity, oldest first, starting at the specified index.
+    /// - returns an empty list if the account is not registered or has no logged activity
+    fn account_activity(
+        &self,
+        account_id: ValidAccountId,
+        from_index: u32,
+        limit: u32,
+    ) -> Vec<AccountActivity>;
+}
@@ -1,5 +1,8 @@
-use crate::interface::{StakeAccount, YoctoNear};
-use near_sdk::json_types::{ValidAccountId, U128};
+use crate::interface::{
+    AccountIdHash, AccountPreferences, BalanceProof, StakeAccount, StakeAccountEntry, YoctoNear,
+    YoctoStake,
+};
+use near_sdk::json_types::{ValidAccountId, U128, U64};
 
 /// Used to manage user accounts. The main use cases supported by this interface are:
 /// 1. Users can register with the contract. Users are required to pay for account storage usage at
@@ -9,6 +12,11 @@ use near_sdk::json_types::{ValidAccountId, U128};
 /// 3. The total number of registered users is tracked.
 /// 4. Users can withdraw unstaked NEAR from STAKE that has been redeemed.
 /// 5. User account info can be looked up.
+/// 6. Dormant accounts can hibernate to reclaim most of their storage fee without unregistering.
+/// 7. Other contracts can gate features on STAKE holdings via balance proofs.
+/// 8. Clients can cheaply detect stale cached account state via account nonces.
+/// 9. Registered accounts can be enumerated, e.g., by indexers and dashboards.
+/// 10. Accounts can opt in to having receipts claimed and redeemed NEAR compounded automatically.
 pub trait AccountManagement {
     /// Creates and registers a new account for the predecessor account ID.
     /// - the account is required to pay for its storage. Storage fees will be escrowed and then refunded
@@ -22,15 +30,27 @@ pub trait AccountManagement {
     /// - if account is already registered
     fn register_account(&mut self);
 
-    /// In order to unregister the account all NEAR must be unstaked and withdrawn from the account.
-    /// The escrowed storage fee will be refunded to the account.
+    /// Sweeps the account funds and unregisters the account:
+    /// 1. unclaimed batch receipt funds are claimed
+    /// 2. the STAKE balance is transferred to the `stake_beneficiary_id` account
+    /// 3. the NEAR balance is withdrawn to the account - if the per epoch withdrawal limit would be
+    ///    exceeded, then the overflow is queued, i.e., the queued withdrawal outlives the account
+    /// 4. the escrowed storage fees, including the storage fee escrowed for the account's
+    ///    [receiver authorizations](crate::interface::ReceiverAuthorization), are refunded to the
+    ///    account
     ///
-    /// Gas Requirements: 8 TGas
+    /// Funds in batches that have not yet been run cannot be swept, i.e., the batches must be run
+    /// before the account can be unregistered.
+    ///
+    /// Gas Requirements: 8 TGas - more if funds are swept
     ///
     /// ## Panics
     /// - if account is not registered
-    /// - if registered account has funds
-    fn unregister_account(&mut self);
+    /// - if the account has funds in batches that have not yet been run
+    /// - if the account has a STAKE balance and no `stake_beneficiary_id` is specified
+    /// - if the STAKE beneficiary is the account itself or is not registered
+    /// - if the account has an open swap intent - see [SwapIntents](crate::interface::SwapIntents)
+    fn unregister_account(&mut self, stake_beneficiary_id: Option<ValidAccountId>);
 
     /// Returns the required deposit amount that is required for account registration.
     ///
@@ -49,4 +69,143 @@ pub trait AccountManagement {
     ///
     /// Gas Requirements: 4 TGas
     fn lookup_account(&self, account_id: ValidAccountId) -> Option<StakeAccount>;
+
+    /// looks up the registered account using the account ID hash, which is the key that accounts
+    /// are stored under - see [AccountIdHash](crate::interface::AccountIdHash)
+    ///
+    /// Gas Requirements: 4 TGas
+    ///
+    /// ## Panics
+    /// if the account ID hash is not 32 bytes
+    fn lookup_account_by_hash(&self, account_id_hash: AccountIdHash) -> Option<StakeAccount>;
+
+    /// Compresses the predecessor account record into a minimal tombstone, which retains the
+    /// account registration, and refunds the storage fee for the storage that is freed.
+    ///
+    /// The account is rehydrated automatically on its next activity. When the account deposits
+    /// NEAR to stake or re-registers, the storage escrow is restored to the
+    /// [account storage fee](AccountManagement::account_storage_fee), which is paid from the
+    /// attached deposit.
+    ///
+    /// Returns the storage fee that was refunded - zero if the account is already hibernated.
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the account has any balances, batches, or account settings, e.g., a redeem limit or
+    ///   redeem forwarding
+    fn hibernate_account(&mut self) -> YoctoNear;
+
+    /// returns true if the account record is a hibernated tombstone
+    fn account_hibernated(&self, account_id: ValidAccountId) -> bool;
+
+    /// Proves whether the account holds at least `min_amount` STAKE at the current block, which
+    /// lets other contracts gate features on STAKE holdings, e.g., membership tiers, via a
+    /// cross-contract call.
+    /// - the balance includes unclaimed stake batch receipts, i.e., newly staked accounts qualify
+    ///   as soon as their stake batch is run
+    /// - unregistered accounts have a zero balance
+    ///
+    /// Gas Requirements: 5 TGas
+    fn prove_min_balance(&self, account_id: ValidAccountId, min_amount: YoctoStake)
+        -> BalanceProof;
+
+    /// Returns the account nonce, which is the [mutation nonce](AccountManagement::mutation_nonce)
+    /// at the time the account record was last mutated, or None if the account is not registered.
+    /// - the nonce strictly increases each time the account record is mutated, i.e., if the nonce
+    ///   has not changed, then the cached account state is current
+    /// - hibernated accounts report a zero nonce until the account is rehydrated
+    ///
+    /// NOTE: unclaimed batch receipts are applied when the account is looked up, thus the
+    ///       [looked up account](AccountManagement::lookup_account) balances may change when a batch
+    ///       is run without the nonce changing
+    fn account_nonce(&self, account_id: ValidAccountId) -> Option<U64>;
+
+    /// global counter that is incremented each time any account record is mutated
+    fn mutation_nonce(&self) -> U64;
+
+    /// Returns registered accounts along with their balances and batches, in index order.
+    /// - when an account unregisters, the last indexed account is moved into its slot, thus paging
+    ///   while accounts are unregistering may skip or repeat accounts
+    fn get_accounts(&self, from_index: U64, limit: U64) -> Vec<StakeAccountEntry>;
+
+    /// returns the number of indexed accounts that can be enumerated via [get_accounts](AccountManagement::get_accounts)
+    /// - accounts that were registered before the account index was introduced are not counted
+    ///   until they are indexed via [index_account](AccountManagement::index_account)
+    fn get_account_count(&self) -> U64;
+
+    /// Adds the registered account to the account index. Accounts are indexed when they register,
+    /// thus this is only needed to backfill accounts that were registered before the account index
+    /// was introduced.
+    ///
+    /// Returns false if the account is already indexed.
+    ///
+    /// NOTE: this is a maintenance call that anyone can invoke
+    ///
+    /// ## Panics
+    /// if the account is not registered
+    fn index_account(&mut self, account_id: ValidAccountId) -> bool;
+
+    /// Sets the predecessor account's preferences, which are applied by the contract whenever the
+    /// account touches the contract:
+    /// - `auto_claim_receipts` - receipts are claimed implicitly by most account transactions - when
+    ///   enabled, receipts are also claimed when the account receives STAKE transfers from other
+    ///   accounts and when the preferences are set
+    /// - `auto_compound` - when redeemed NEAR is claimed, it is moved from the account NEAR balance
+    ///   into the account's stake batch instead, i.e., the NEAR is restaked without a withdraw and
+    ///   deposit round trip. Compounding is skipped if the stake batch balance would be below the
+    ///   [min required deposit](crate::interface::StakingService::min_required_deposit_to_stake).
+    ///
+    /// NOTE: because withdrawals claim receipts first, redeemed NEAR that has not yet been claimed
+    ///       is compounded before it can be withdrawn - disable `auto_compound` to withdraw it
+    ///
+    /// Returns the updated preferences.
+    ///
+    /// ## Panics
+    /// if the account is not registered
+    fn set_account_preferences(&mut self, preferences: AccountPreferences) -> AccountPreferences;
+
+    /// returns None if the account is not registered
+    fn account_preferences(&self, account_id: ValidAccountId) -> Option<AccountPreferences>;
+}
+
+pub mod events {
+    /// logged when the account storage escrow is topped up from the account NEAR balance
+    #[derive(Debug)]
+    pub struct AccountStorageEscrowBilled<'a> {
+        pub account_id: &'a str,
+        pub amount: u128,
+        pub storage_escrow: u128,
+    }
+
+    /// logged when a whitelisted contract is registered with the storage fee paid from the
+    /// registration pool
+    #[derive(Debug)]
+    pub struct AccountAutoRegistered<'a> {
+        pub account_id: &'a str,
+        pub storage_fee: u128,
+        pub registration_pool: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct AccountHibernated<'a> {
+        pub account_id: &'a str,
+        /// storage fee that was refunded
+        pub refund: u128,
+        /// storage fee that remains escrowed for the tombstone
+        pub storage_escrow: u128,
+    }
+
+    /// logged when the hibernated account storage escrow is restored
+    #[derive(Debug)]
+    pub struct AccountRehydrated<'a> {
+        pub account_id: &'a str,
+        pub storage_fee: u128,
+        pub storage_escrow: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct RegistrationPoolFunded {
+        pub amount: u128,
+        pub registration_pool: u128,
+    }
 }
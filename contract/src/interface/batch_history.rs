@@ -0,0 +1,27 @@
+use crate::interface::BatchHistoryEntry;
+
+/// Batch receipts are deleted once all funds have been claimed, which erases the batch history.
+/// Thus, summary records for the stake and redeem batches that are run are retained separately.
+/// - the batch history is disabled by default - the number of entries that are retained per batch
+///   kind is [configurable](crate::config::Config::batch_history_capacity)
+/// - once the history is full, the oldest entries are pruned
+/// - entries are indexed sequentially per batch kind in the order the batches were run
+pub trait BatchHistory {
+    /// returns the stake batch history, oldest first, starting at index `from`
+    /// - returns at most `limit` entries
+    /// - entries that have been pruned are skipped
+    fn stake_batch_history(&self, from: u64, limit: u32) -> Vec<BatchHistoryEntry>;
+
+    /// returns the redeem stake batch history, oldest first, starting at index `from`
+    /// - returns at most `limit` entries
+    /// - entries that have been pruned are skipped
+    fn redeem_batch_history(&self, from: u64, limit: u32) -> Vec<BatchHistoryEntry>;
+
+    /// returns the number of stake batch history entries that have been recorded, including entries
+    /// that have been pruned
+    fn stake_batch_history_len(&self) -> u64;
+
+    /// returns the number of redeem stake batch history entries that have been recorded, including
+    /// entries that have been pruned
+    fn redeem_batch_history_len(&self) -> u64;
+}
@@ -0,0 +1,70 @@
+use crate::interface::{BatchReservation, YoctoNear};
+use near_sdk::json_types::ValidAccountId;
+
+/// Lets large depositors guarantee that their deposit is included in the very next stake batch run.
+///
+/// Deposits that land while the stake batch is locked are pushed into the next stake batch, i.e.,
+/// they miss the batch run they were meant for. A reservation escrows the funds outside of the
+/// stake batches, and the funds are deposited into the stake batch when the next batch run starts.
+///
+/// ## Reservation Fee
+/// A fee is escrowed along with the reserved funds:
+/// - the fee is refunded to the account NEAR balance when the reservation is used
+/// - the fee is forfeited if the reservation expires before a batch is run or if the reservation is
+///   cancelled - forfeited fees are collected as earnings
+pub trait BatchReservations {
+    /// reserves inclusion in the next stake batch run for the specified amount
+    /// - the attached deposit must cover the amount plus the reservation fee - any excess is
+    ///   refunded
+    /// - the reservation expires after [batch_reservation_expiry_blocks](crate::config::Config::batch_reservation_expiry_blocks)
+    ///
+    /// #\[payable\]
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the account already has a reservation
+    /// - if the max number of reservations are queued - see [MAX_BATCH_RESERVATIONS](crate::domain::MAX_BATCH_RESERVATIONS)
+    /// - if the amount is less than the min required NEAR deposit
+    /// - if the attached deposit does not cover the amount plus the reservation fee
+    fn reserve_batch_slot(&mut self, amount: YoctoNear) -> BatchReservation;
+
+    /// cancels the account's unused reservation and refunds the reserved amount
+    /// - the reservation fee is forfeited
+    ///
+    /// Returns the amount that was refunded.
+    ///
+    /// ## Panics
+    /// if the account has no reservation
+    fn cancel_batch_reservation(&mut self) -> YoctoNear;
+
+    fn batch_reservation(&self, account_id: ValidAccountId) -> Option<BatchReservation>;
+
+    /// returns the queued reservations in the order they were made
+    fn batch_reservations(&self) -> Vec<BatchReservation>;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct BatchSlotReserved<'a> {
+        pub account_id: &'a str,
+        pub amount: u128,
+        pub fee: u128,
+        pub expires_at: u64,
+    }
+
+    #[derive(Debug)]
+    pub struct BatchReservationUsed<'a> {
+        pub account_id: &'a str,
+        pub batch_id: u128,
+        pub amount: u128,
+        pub fee_refund: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct BatchReservationCancelled<'a> {
+        pub account_id: &'a str,
+        pub refund: u128,
+        pub forfeited_fee: u128,
+        pub expired: bool,
+    }
+}
@@ -0,0 +1,81 @@
+use crate::interface::{BatchClaims, BatchId, BatchTotalsRebuild};
+
+/// Operator maintenance tools to recover from accounting divergence between the contract level batch
+/// balances and the account level batch balances without redeploying the contract.
+///
+/// Each account that contributes to a batch is recorded in a per batch contributor index. The
+/// contract level batch balance can then be re-derived from the account level batch balances by
+/// walking the index in chunks, which keeps each call within the gas limit regardless of how many
+/// accounts contributed to the batch.
+///
+/// NOTE: batches can only be rebuilt before they are run - once a batch is run, the account level
+/// batch balances are claimed against the batch receipt.
+pub trait BatchTotals {
+    /// Re-derives the contract level batch balance from the per account contributions, processing
+    /// up to `limit` contributors starting from the `from` contributor index.
+    /// - `from=0` starts a new rebuild - any rebuild that is in progress is discarded
+    /// - subsequent chunks must resume from [next_index](crate::interface::BatchTotalsRebuild::next_index)
+    /// - once the last contributor is processed, the rebuild is complete and any drift is repaired,
+    ///   unless `dry_run` is specified, in which case the batch is left as is
+    /// - logs a [BatchTotalsRepaired](events::BatchTotalsRepaired) event when drift is repaired
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if the batch is not a current batch that has not yet been run
+    /// - if the batch is locked because it is being run
+    /// - if the rebuild is not resumed from the next contributor index
+    /// - if the batch balance changed since the rebuild was started - the rebuild must be restarted
+    fn rebuild_batch_totals(
+        &mut self,
+        batch_id: BatchId,
+        from: u64,
+        limit: u32,
+        dry_run: bool,
+    ) -> BatchTotalsRebuild;
+
+    /// Removes up to `limit` entries from the contributor index for a batch that is no longer a
+    /// current batch, i.e., the batch has been run.
+    ///
+    /// Returns the number of contributor index entries that remain for the batch.
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if the batch is a current batch
+    fn prune_batch_contributors(&mut self, batch_id: BatchId, limit: u32) -> u64;
+
+    /// returns the number of accounts in the batch contributor index
+    fn batch_contributors_len(&self, batch_id: BatchId) -> u64;
+
+    /// Proactively claims the batch receipt funds into the accounts that contributed to the batch,
+    /// processing up to `limit` contributors starting from the `from` contributor index. Otherwise,
+    /// account balances, e.g., [ft_balance_of](crate::interface::FungibleToken::ft_balance_of),
+    /// drift from the claimable balances until each account transacts with the contract.
+    /// - claiming is idempotent - contributors that already claimed their funds are skipped
+    /// - contributors whose account ID is not [indexed](crate::interface::AccountManagement::index_account)
+    ///   are skipped because the account ID is required for the NEP-141 `ft_transfer` event
+    /// - the method is permissionless to enable keepers to process claims once a batch completes
+    /// - logs a [BatchClaimsProcessed](events::BatchClaimsProcessed) event
+    ///
+    /// NOTE: the contributor index must not be pruned before claims are processed
+    ///
+    /// ## Panics
+    /// if the batch is a current batch, i.e., the batch has not yet been run
+    fn process_claims(&mut self, batch_id: BatchId, from: u64, limit: u32) -> BatchClaims;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct BatchTotalsRepaired {
+        pub batch_id: u128,
+        pub recorded_balance: u128,
+        pub rebuilt_balance: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct BatchClaimsProcessed {
+        pub batch_id: u128,
+        pub from: u64,
+        pub next_index: u64,
+        pub claimed: u64,
+    }
+}
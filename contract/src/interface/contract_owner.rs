@@ -1,4 +1,4 @@
-use crate::interface::YoctoNear;
+use crate::interface::{BatchId, YoctoNear, YoctoStake};
 use near_sdk::json_types::ValidAccountId;
 use near_sdk::AccountId;
 
@@ -18,6 +18,7 @@ pub trait ContractOwner {
     ///
     /// ## Panics
     /// - if the predecessor account is not the owner account
+    /// - if the operator account is the contract account
     /// - new operator account must be registered
     fn set_operator_id(&mut self, account_id: ValidAccountId);
 
@@ -30,13 +31,29 @@ pub trait ContractOwner {
     /// - if the predecessor account is not the owner account
     fn stake_all_owner_balance(&mut self) -> YoctoNear;
 
-    /// Deposits the owner's balance into the owners STAKE account
+    /// Moves NEAR from the contract owner balance into the current stake batch on behalf of the
+    /// owner's registered account, i.e., the owner's stake is accounted for and batched exactly
+    /// like any other account's deposit.
+    ///
+    /// Returns the batch ID that the NEAR was deposited into.
     ///
     /// ## Panics
     /// - panics if the owner does not have a registered account
     /// - if the owner balance is too low to fulfill the request
     /// - if the predecessor account is not the owner account
-    fn stake_owner_balance(&mut self, amount: YoctoNear);
+    fn stake_owner_balance(&mut self, amount: YoctoNear) -> BatchId;
+
+    /// Mirrors [stake_owner_balance](ContractOwner::stake_owner_balance) - redeems STAKE from the
+    /// owner's registered account via the redeem stake batch. Once the batch is processed, the
+    /// redeemed NEAR is credited to the owner's registered account, like for any other account.
+    ///
+    /// Returns the batch ID that the STAKE was redeemed into.
+    ///
+    /// ## Panics
+    /// - panics if the owner does not have a registered account
+    /// - if the owner's STAKE balance is too low to fulfill the request
+    /// - if the predecessor account is not the owner account
+    fn redeem_owner_stake(&mut self, amount: YoctoStake) -> BatchId;
 
     /// transfers the entire owner balance to the owner's account
     ///
@@ -60,4 +77,18 @@ pub mod events {
         pub from: &'a str,
         pub to: &'a str,
     }
+
+    #[derive(Debug)]
+    pub struct OwnerBalanceStaked {
+        pub batch_id: u128,
+        pub amount: u128,
+        /// remaining contract owner balance
+        pub contract_owner_balance: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct OwnerStakeRedeemed {
+        pub batch_id: u128,
+        pub amount: u128,
+    }
 }
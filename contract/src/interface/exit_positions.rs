@@ -0,0 +1,92 @@
+use crate::interface::{ExitPosition, YoctoNear};
+use near_sdk::json_types::ValidAccountId;
+
+/// Single signature "cash out everything" flow - the account's STAKE is redeemed and the resulting
+/// NEAR is automatically transferred to a beneficiary account once the pending withdrawal
+/// completes.
+///
+/// ## Exit Workflow
+/// 1. the account invokes [exit_position](ExitPositions::exit_position), which redeems all of the
+///    account's STAKE and registers the beneficiary
+/// 2. the redeem stake batch is run through the normal unstake and withdrawal workflows
+/// 3. the next [process_exit](ExitPositions::process_exit) keeper call after the account's redeem
+///    stake batches have completed claims the receipts and transfers the account's available NEAR
+///    balance to the beneficiary
+///
+/// Progress is reported via [events].
+pub trait ExitPositions {
+    /// redeems the account's full STAKE balance and registers the beneficiary that the NEAR is
+    /// transferred to once the account's pending redeem stake batches have completed
+    /// - the attached deposit must cover the exit position storage fee - any excess is refunded
+    /// - NEAR that is pending in the account's stake batches is not part of the exit
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the account already has an exit position
+    /// - if the account has no STAKE balance and no pending redeem stake batches
+    /// - if the attached deposit does not cover the exit position storage fee
+    ///
+    /// #\[payable\]
+    fn exit_position(&mut self, beneficiary: ValidAccountId) -> ExitPosition;
+
+    /// Completes the account's exit once the account's redeem stake batches have completed - the
+    /// account's available NEAR balance plus the exit position storage fee are transferred to the
+    /// beneficiary.
+    /// - the redeem proceeds are paid out to the beneficiary, i.e., they are not forwarded even if
+    ///   the account has [redeem forwarding](crate::interface::StakingService::set_redeem_forwarding)
+    ///   set
+    ///
+    /// NOTE: this is a maintenance call that anyone can invoke, e.g., keepers
+    ///
+    /// Returns the amount that was transferred to the beneficiary, or None if the exit is still
+    /// pending. If the per epoch withdrawal limit would be exceeded, then the overflow is queued -
+    /// see [process_queued_withdrawal](crate::interface::StakingService::process_queued_withdrawal).
+    ///
+    /// ## Panics
+    /// - if the contract is paused
+    /// - if the account has no exit position
+    fn process_exit(&mut self, account_id: ValidAccountId) -> Option<YoctoNear>;
+
+    /// cancels the account's exit position - the redeemed STAKE is not restored, i.e., the NEAR
+    /// remains in the account once the redeem stake batches complete
+    /// - the exit position storage fee is credited to the account's NEAR balance
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the account has no exit position
+    fn cancel_exit_position(&mut self) -> ExitPosition;
+
+    fn exit_position_status(&self, account_id: ValidAccountId) -> Option<ExitPosition>;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct ExitPositionRequested<'a> {
+        pub account_id: &'a str,
+        pub beneficiary: &'a str,
+        pub redeemed: u128,
+        pub batch_id: Option<u128>,
+    }
+
+    /// logged when the exit is processed while the account's redeem stake batches are still
+    /// pending
+    #[derive(Debug)]
+    pub struct ExitPositionPending<'a> {
+        pub account_id: &'a str,
+        pub redeem_stake_batch_id: Option<u128>,
+        pub next_redeem_stake_batch_id: Option<u128>,
+    }
+
+    #[derive(Debug)]
+    pub struct ExitPositionCompleted<'a> {
+        pub account_id: &'a str,
+        pub beneficiary: &'a str,
+        pub amount: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct ExitPositionCancelled<'a> {
+        pub account_id: &'a str,
+        pub beneficiary: &'a str,
+    }
+}
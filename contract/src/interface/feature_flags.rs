@@ -0,0 +1,76 @@
+use crate::interface::FeatureRollout;
+use near_sdk::json_types::ValidAccountId;
+
+/// Lets the operator roll out new contract behaviors gradually on mainnet before they are enabled
+/// globally.
+///
+/// A feature is enabled for an account if either:
+/// - the account is explicitly allowlisted for the feature
+/// - the account falls within the feature rollout percentage cohort - the cohort is stable per
+///   feature, i.e., raising the percentage only adds accounts to the cohort
+///
+/// Features that have no rollout are disabled. Setting the rollout percentage to 100 enables the
+/// feature globally - once the behavior is made permanent via a contract upgrade, the rollout can
+/// be removed.
+pub trait FeatureFlags {
+    /// creates the feature rollout if it does not exist, and sets the rollout percentage
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator
+    /// - if the feature name is blank or exceeds [MAX_FEATURE_NAME_LEN](crate::domain::MAX_FEATURE_NAME_LEN)
+    /// - if the percentage is greater than 100
+    /// - if the feature is new and [MAX_FEATURE_ROLLOUTS](crate::domain::MAX_FEATURE_ROLLOUTS) has
+    ///   been reached
+    fn set_feature_rollout(&mut self, name: String, percentage: u8) -> FeatureRollout;
+
+    /// enables the feature for the account regardless of the rollout percentage
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator
+    /// - if the feature rollout does not exist
+    /// - if the allowlist is full - see [MAX_FEATURE_ALLOWLIST_LEN](crate::domain::MAX_FEATURE_ALLOWLIST_LEN)
+    fn allow_feature(&mut self, name: String, account_id: ValidAccountId) -> FeatureRollout;
+
+    /// removes the account from the feature allowlist
+    /// - the feature remains enabled for the account if the account falls within the rollout
+    ///   percentage cohort
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator
+    /// - if the feature rollout does not exist
+    fn disallow_feature(&mut self, name: String, account_id: ValidAccountId) -> FeatureRollout;
+
+    /// removes the feature rollout, which disables the feature for all accounts
+    ///
+    /// Returns false if the feature rollout does not exist.
+    ///
+    /// ## Panics
+    /// if not invoked by the operator
+    fn remove_feature_rollout(&mut self, name: String) -> bool;
+
+    fn feature_enabled(&self, name: String, account_id: ValidAccountId) -> bool;
+
+    fn feature_rollout(&self, name: String) -> Option<FeatureRollout>;
+
+    fn feature_rollouts(&self) -> Vec<FeatureRollout>;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct FeatureRolloutUpdated<'a> {
+        pub name: &'a str,
+        pub percentage: u8,
+    }
+
+    #[derive(Debug)]
+    pub struct FeatureAllowlistUpdated<'a> {
+        pub name: &'a str,
+        pub account_id: &'a str,
+        pub allowed: bool,
+    }
+
+    #[derive(Debug)]
+    pub struct FeatureRolloutRemoved<'a> {
+        pub name: &'a str,
+    }
+}
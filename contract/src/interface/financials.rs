@@ -1,4 +1,6 @@
-use crate::interface::{ContractBalances, YoctoNear};
+use crate::interface::{
+    ContractBalances, FinancialsSnapshot, LiquidityReport, PublicGoodsDonations, YoctoNear,
+};
 
 pub trait ContractFinancials {
     /// returns consolidated view of contract balances
@@ -13,10 +15,39 @@ pub trait ContractFinancials {
     ///
     /// #\[payable\]
     fn deposit_earnings(&mut self) -> YoctoNear;
+
+    /// returns the [FinancialsSnapshot]s that were recorded when earnings were distributed, starting
+    /// with the snapshot at index `from` (oldest first)
+    /// - returns at most `limit` snapshots
+    /// - snapshots are only recorded when there are earnings to distribute
+    fn financials_history(&self, from: u64, limit: u32) -> Vec<FinancialsSnapshot>;
+
+    /// returns the number of [FinancialsSnapshot]s that have been recorded
+    fn financials_history_len(&self) -> u64;
+
+    /// returns the NEAR liquidity pool balance along with the cumulative inflows and outflows that
+    /// explain how the balance changed
+    /// - each change is also logged as a `NearLiquidityAdded` or `NearLiquidityConsumed` event
+    fn liquidity_report(&self) -> LiquidityReport;
+
+    /// returns the public goods donation accounting
+    /// - donations are disabled by default - deployments opt in by configuring a
+    ///   [public_goods_beneficiary_id](crate::config::Config::public_goods_beneficiary_id) and a
+    ///   [public_goods_percentage](crate::config::Config::public_goods_percentage)
+    /// - each donation is also logged as a `PublicGoodsDonation` event
+    fn public_goods_donations(&self) -> PublicGoodsDonations;
 }
 
 #[derive(Debug)]
 pub struct EarningsDistribution {
+    pub protocol_fee: u128,
     pub contract_owner_earnings: u128,
     pub user_accounts_earnings: u128,
+    pub public_goods_donation: u128,
+}
+
+#[derive(Debug)]
+pub struct PublicGoodsDonation<'a> {
+    pub beneficiary_id: &'a str,
+    pub amount: u128,
 }
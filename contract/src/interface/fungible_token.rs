@@ -1,5 +1,6 @@
+use crate::interface::Gas;
 use near_sdk::{
-    json_types::{ValidAccountId, U128},
+    json_types::{ValidAccountId, U128, U64},
     serde::{Deserialize, Serialize},
     Promise, PromiseOrValue,
 };
@@ -53,6 +54,8 @@ pub trait FungibleToken {
     /// - Sender account is required to attach exactly 1 yoctoNEAR to the function call - see security
     ///   section of the standard.
     ///   - the yoctoNEAR will be credited to the sender account's NEAR balance
+    /// - any unclaimed batch receipts on the sender account are claimed before the sender's STAKE
+    ///   balance is checked, i.e., STAKE can be transferred as soon as the stake batch completes
     ///
     /// Arguments:
     /// - `receiver_id` - the account ID of the receiver.
@@ -79,7 +82,9 @@ pub trait FungibleToken {
     /// the receiver contract, the remaining tokens must be refunded to the `predecessor_account_id`
     /// by the resolve transfer callback.
     ///
-    /// Token contract must pass all the remaining unused gas to [`TransferReceiver::ft_on_transfer`]
+    /// Token contract must pass all the remaining unused gas to [`TransferReceiver::ft_on_transfer`],
+    /// unless the caller specifies `gas_for_receiver` or the operator configured a default - see
+    /// [GasConfig::ft_on_transfer](crate::interface::GasConfig::ft_on_transfer)
     ///
     /// Malicious or invalid behavior by the receiver's contract:
     /// - If the receiver contract promise fails or returns invalid value, the full transfer amount
@@ -89,20 +94,32 @@ pub trait FungibleToken {
     ///
     /// Both accounts must be registered with the contract for transfer to succeed.
     /// Sender must attach exactly 1 yoctoNEAR - see security section of the standard.
+    /// Any unclaimed batch receipts on the sender account are claimed before the sender's STAKE
+    /// balance is checked.
     ///
     /// Arguments:
     /// - `receiver_id` - the account ID of the receiver contract. This contract will be called.
     /// - `amount` - the amount of tokens to transfer - unsigned integer in string representation.
     /// - `msg` - a string message that will be passed to `ft_on_transfer` contract call.
     /// - `memo` - an optional string field in a free form to associate a memo with this transfer.
+    /// - `gas_for_receiver` - optional gas that is forwarded to the receiver's `ft_on_transfer`.
+    ///   Any prepaid gas beyond the receiver gas and the gas reserved for `ft_resolve_transfer_call`
+    ///   is left unused and refunded. If not specified, then the configured default applies.
     ///
     /// Returns a promise to resolve transfer call which will return the used amount - [`ResolveTransferCall`]
     ///
+    /// NOTE: if the receiver is not registered but is [whitelisted for auto-registration](crate::interface::Operator::whitelist_auto_registration),
+    ///       then the receiver is registered with the account storage fee paid from the operator
+    ///       funded registration pool
+    ///
     /// ## Panics
     /// - if the attached deposit is not exactly 1 yoctoNEAR
     /// - if either sender or receiver accounts are not registered
+    /// - if the receiver is auto-registered and the registration pool balance is too low
     /// - if amount is zero
     /// - if the sender account has insufficient funds to fulfill the transfer request
+    /// - if the prepaid gas does not cover the gas for the receiver call plus the gas reserved for
+    ///   `ft_resolve_transfer_call`
     ///
     /// GAS REQUIREMENTS: 40 TGas + gas for receiver call
     /// #\[payable\]
@@ -112,6 +129,7 @@ pub trait FungibleToken {
         amount: TokenAmount,
         msg: TransferCallMessage,
         memo: Option<Memo>,
+        gas_for_receiver: Option<Gas>,
     ) -> Promise;
 
     fn ft_total_supply(&self) -> TokenAmount;
@@ -164,6 +182,9 @@ pub trait ResolveTransferCall {
     /// - `sender_id` - the account ID that initiated the transfer.
     /// - `receiver_id` - the account ID of the receiver contract.
     /// - `amount` - the amount of tokens that were transferred to receiver's account.
+    /// - `transfer_id` - the [PendingTransfers](crate::interface::PendingTransfers) ID that was
+    ///   registered for the transfer call - if the transfer has already been resolved as a stalled
+    ///   transfer, then the callback is a no-op and nothing is refunded
     ///
     /// Promise result data dependency (`unused_amount`):
     /// - the amount of tokens that were unused by receiver's contract.
@@ -183,6 +204,7 @@ pub trait ResolveTransferCall {
         sender_id: ValidAccountId,
         receiver_id: ValidAccountId,
         amount: TokenAmount,
+        transfer_id: Option<U64>,
         // NOTE: #[callback_result] is not supported yet and has to be handled using lower level interface.
         //
         // #[callback_result]
@@ -265,3 +287,33 @@ impl Display for TransferCallMessage {
         self.0.fmt(f)
     }
 }
+
+pub mod events {
+    /// logged when the transfer call refund is burned because the sender account is no longer
+    /// registered
+    #[derive(Debug)]
+    pub struct TransferRefundBurned<'a> {
+        pub sender_id: &'a str,
+        pub amount: u128,
+    }
+
+    /// logged when wNEAR that was received via `ft_transfer_call` was unwrapped and deposited into
+    /// the sender's stake batch
+    #[derive(Debug)]
+    pub struct WrapNearDeposited<'a> {
+        pub account_id: &'a str,
+        pub amount: u128,
+        pub batch_id: u128,
+    }
+
+    /// logged when the wNEAR deposit could not be completed
+    /// - if the wNEAR could not be unwrapped, then the full amount is refunded by the wNEAR contract
+    /// - if the sender unregistered while the wNEAR was being unwrapped, then the unwrapped NEAR is
+    ///   transferred to the sender
+    #[derive(Debug)]
+    pub struct WrapNearDepositFailed<'a> {
+        pub account_id: &'a str,
+        pub amount: u128,
+        pub reason: &'a str,
+    }
+}
@@ -0,0 +1,39 @@
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::AccountId;
+
+/// Enables config governance to be handed over to a DAO contract.
+///
+/// When a governance DAO account is set, config changes are only accepted from the DAO, i.e.,
+/// via its proposal execution calls. Until then, the config is managed by the operator. This enables
+/// communities to progressively decentralize the STAKE deployment.
+pub trait Governance {
+    /// returns the DAO account that governs the contract config
+    /// - None means the config is managed by the operator
+    fn governance_id(&self) -> Option<AccountId>;
+
+    /// hands over config governance to the specified DAO account
+    ///
+    /// ## Panics
+    /// - if the predecessor account is not the owner account
+    /// - if a governance DAO account is already set - only the DAO can relinquish control
+    /// - if the account ID is the contract account ID
+    fn set_governance_id(&mut self, account_id: ValidAccountId);
+
+    /// hands config governance back to the operator
+    ///
+    /// ## Panics
+    /// if the predecessor account is not the governance DAO account
+    fn relinquish_governance(&mut self);
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct GovernanceSet<'a> {
+        pub governance_id: &'a str,
+    }
+
+    #[derive(Debug)]
+    pub struct GovernanceRelinquished<'a> {
+        pub governance_id: &'a str,
+    }
+}
@@ -0,0 +1,64 @@
+use crate::interface::IdleNearSweepStats;
+use near_sdk::json_types::ValidAccountId;
+
+/// Idle NEAR is the NEAR in account balances that is awaiting withdrawal. When enabled via
+/// [idle_near_sweep_percentage](crate::config::Config::idle_near_sweep_percentage), a portion of the
+/// idle NEAR is swept into short staking cycles, and the yield is credited back pro-rata to the
+/// account NEAR balances.
+///
+/// ## Workflow
+/// 1. when the stake batch is run, idle NEAR above the liquidity buffer is swept into the batch
+/// 2. once [idle_near_sweep_cycle_epochs](crate::config::Config::idle_near_sweep_cycle_epochs)
+///    have passed, the swept STAKE is redeemed when the redeem stake batch is unstaked
+/// 3. when the redeemed NEAR is returned, the yield is distributed to the account NEAR balances -
+///    the yield is credited to the account NEAR balance the next time the account is accessed
+///
+/// Account NEAR balances are never debited by the sweep. Withdrawals are fulfilled from the liquid
+/// NEAR, which is protected by the liquidity buffer - see
+/// [idle_near_liquidity_buffer_percentage](crate::config::Config::idle_near_liquidity_buffer_percentage).
+/// Withdrawals that exceed the liquid NEAR are queued until the sweep cycle returns the NEAR, and
+/// queued withdrawals are never swept.
+pub trait IdleNearSharing {
+    /// opts the predecessor account out of, or back into, idle NEAR sharing
+    /// - the NEAR balance of accounts that opted out is excluded from the sweep and earns no yield
+    /// - yield that was earned up to this point is credited before the change takes effect
+    ///
+    /// ## Panics
+    /// if the account is not registered
+    fn set_idle_near_sharing_opt_out(&mut self, opt_out: bool);
+
+    /// returns None if the account is not registered
+    fn idle_near_sharing_opted_out(&self, account_id: ValidAccountId) -> Option<bool>;
+
+    fn idle_near_sweep_stats(&self) -> IdleNearSweepStats;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct IdleNearSwept {
+        pub batch_id: u128,
+        pub amount: u128,
+        /// idle NEAR that remains liquid after the sweep
+        pub liquid: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct IdleNearSweepRedeemed {
+        pub batch_id: u128,
+        pub stake: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct IdleNearSweepSettled {
+        pub swept: u128,
+        pub returned: u128,
+        pub distributed_yield: u128,
+        pub yield_index: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct IdleNearSharingOptOut<'a> {
+        pub account_id: &'a str,
+        pub opt_out: bool,
+    }
+}
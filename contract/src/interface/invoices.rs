@@ -0,0 +1,84 @@
+use crate::interface::{BlockTimestamp, Invoice, YoctoNear, YoctoStake};
+use near_sdk::json_types::U64;
+
+/// Lets merchants accept STAKE natively - the payee creates an invoice, which is settled by the payer
+/// via an internal STAKE transfer. The invoice ID serves as the payment reference, i.e., payments do
+/// not need to be matched with bare transfers using off-chain memos.
+///
+/// ## Workflow
+/// 1. the payee creates the invoice and attaches the NEAR to pay for the invoice storage
+/// 2. the payer pays the invoice before it expires - the STAKE is transferred from the payer's
+///    account to the payee's account
+/// 3. the payee closes the invoice once it has been paid or has expired, which refunds the invoice
+///    storage fee
+pub trait Invoices {
+    /// creates an invoice payable to the predecessor account
+    /// - the attached deposit must cover the invoice storage fee - any excess is refunded
+    /// - `expiry` is the block timestamp in nanoseconds at which the invoice expires
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the amount is zero
+    /// - if the memo exceeds [MAX_INVOICE_MEMO_LEN](crate::domain::MAX_INVOICE_MEMO_LEN) bytes
+    /// - if the expiry is not in the future
+    /// - if the attached deposit does not cover the invoice storage fee
+    fn create_invoice(
+        &mut self,
+        amount: YoctoStake,
+        memo: String,
+        expiry: BlockTimestamp,
+    ) -> Invoice;
+
+    /// transfers the invoice STAKE amount from the predecessor account to the payee account
+    /// - unclaimed batch receipts are claimed before the STAKE balance is debited
+    /// - exactly 1 yoctoNEAR must be attached, which is credited to the payer's account NEAR balance
+    ///
+    /// Returns the paid invoice.
+    ///
+    /// ## Panics
+    /// - if the invoice does not exist
+    /// - if the invoice has already been paid or has expired
+    /// - if the payer is the payee
+    /// - if the payer or payee account is not registered
+    /// - if the payer has insufficient STAKE
+    fn pay_invoice(&mut self, invoice_id: U64) -> Invoice;
+
+    /// deletes the invoice and refunds the invoice storage fee to the payee
+    /// - open invoices are effectively cancelled
+    ///
+    /// Returns the storage fee that was refunded.
+    ///
+    /// ## Panics
+    /// - if the invoice does not exist
+    /// - if not invoked by the payee
+    fn close_invoice(&mut self, invoice_id: U64) -> YoctoNear;
+
+    fn invoice(&self, invoice_id: U64) -> Option<Invoice>;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct InvoiceCreated<'a> {
+        pub invoice_id: u64,
+        pub payee_id: &'a str,
+        pub amount: u128,
+        pub expires_at: u64,
+    }
+
+    #[derive(Debug)]
+    pub struct InvoicePaid<'a> {
+        pub invoice_id: u64,
+        pub payee_id: &'a str,
+        pub payer_id: &'a str,
+        pub amount: u128,
+        pub memo: &'a str,
+    }
+
+    #[derive(Debug)]
+    pub struct InvoiceClosed<'a> {
+        pub invoice_id: u64,
+        pub payee_id: &'a str,
+        pub paid: bool,
+        pub storage_fee_refund: u128,
+    }
+}
@@ -0,0 +1,117 @@
+use crate::interface::{LiquidityPoolBalance, LiquidityShares, StakeSwap, YoctoNear, YoctoStake};
+use near_sdk::json_types::ValidAccountId;
+
+/// Lets third parties provide NEAR liquidity in exchange for liquidity provider pool shares.
+///
+/// The liquidity provider pool is separate from the contract's own NEAR liquidity pool, which is
+/// owned by the STAKE holders. Claims against a redeem stake batch that is pending withdrawal from
+/// the staking pool are served from the contract's NEAR liquidity first. If there is not enough
+/// contract liquidity, then the remainder is served from the liquidity provider pool minus the
+/// [liquidity provider fee](crate::config::Config::liquidity_provider_fee_basis_points):
+/// 1. the pool buys the account's redeemed STAKE and credits the NEAR value minus the fee to the
+///    account's NEAR balance
+/// 2. once the unstaked NEAR is withdrawn from the staking pool, the pool claims the full NEAR value
+///    of the redeemed STAKE - the fee is earned by the pool, which grows the value of the shares
+///
+/// Accounts can also swap STAKE for NEAR against the pool, i.e., instant unstake, for a fee that
+/// increases with the pool utilization - see [swap_stake_for_near](LiquidityProviders::swap_stake_for_near).
+///
+/// Progress is reported via [events].
+pub trait LiquidityProviders {
+    /// adds the attached deposit to the liquidity provider pool and mints pool shares for the
+    /// predecessor account
+    ///
+    /// Returns the account's updated pool shares.
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if no deposit is attached
+    ///
+    /// #\[payable\]
+    fn add_liquidity(&mut self) -> LiquidityShares;
+
+    /// burns the pool shares that are worth the NEAR amount and transfers the NEAR to the
+    /// predecessor account
+    /// - only the NEAR that is available in the pool can be removed, i.e., NEAR that is pending
+    ///   withdrawal from the staking pool is returned to the pool once the withdrawal completes
+    ///
+    /// Returns the account's updated pool shares.
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the amount is zero
+    /// - if the account does not own enough pool shares
+    /// - if the pool does not have enough available NEAR
+    fn remove_liquidity(&mut self, amount: YoctoNear) -> LiquidityShares;
+
+    /// swaps the STAKE for NEAR against the liquidity provider pool, i.e., instant unstake
+    /// - the NEAR value is based on the current STAKE token value, minus the swap fee
+    /// - the swap fee increases linearly with the pool utilization after the swap, from
+    ///   [swap_fee_min_basis_points](crate::config::Config::swap_fee_min_basis_points) to
+    ///   [swap_fee_max_basis_points](crate::config::Config::swap_fee_max_basis_points) - the
+    ///   utilization is the share of the pool value that is tied up in the redeem pipeline
+    /// - the NEAR is credited to the account's NEAR balance, and is available for withdrawal
+    /// - the STAKE is added to the liquidity provider pool's redeem stake batch, i.e., the STAKE is
+    ///   redeemed through the regular redeem workflow and the NEAR is returned to the pool
+    /// - unclaimed batch receipts are claimed before the STAKE balance is debited
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the amount is zero
+    /// - if the account has insufficient STAKE
+    /// - if the pool does not have enough available NEAR to fill the swap
+    /// - if the NEAR out would be less than `min_near_out`
+    fn swap_stake_for_near(&mut self, amount: YoctoStake, min_near_out: YoctoNear) -> StakeSwap;
+
+    /// quotes the STAKE to NEAR swap based on the current STAKE token value and pool utilization
+    /// - wallets should pass the quoted NEAR out, minus their slippage tolerance, as `min_near_out`
+    fn quote_swap_stake_for_near(&self, amount: YoctoStake) -> StakeSwap;
+
+    fn liquidity_pool_balance(&self) -> LiquidityPoolBalance;
+
+    /// returns the account's pool shares - zero shares are returned for accounts that have not
+    /// provided liquidity
+    fn liquidity_shares_of(&self, account_id: ValidAccountId) -> LiquidityShares;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct LiquidityAdded<'a> {
+        pub account_id: &'a str,
+        pub amount: u128,
+        pub shares: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct LiquidityRemoved<'a> {
+        pub account_id: &'a str,
+        pub amount: u128,
+        pub shares: u128,
+    }
+
+    /// a claim pending withdrawal was served from the liquidity provider pool
+    #[derive(Debug)]
+    pub struct LiquidityProviderClaim {
+        pub batch_id: u128,
+        pub redeemed_stake: u128,
+        pub near: u128,
+        pub fee: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct StakeSwapped<'a> {
+        pub account_id: &'a str,
+        pub batch_id: u128,
+        pub stake: u128,
+        pub near_out: u128,
+        pub fee: u128,
+    }
+
+    /// the pool claimed the NEAR for the redeemed STAKE it held once the batch completed
+    #[derive(Debug)]
+    pub struct LiquidityProviderPoolSettled {
+        pub batch_id: u128,
+        pub redeemed_stake: u128,
+        pub near: u128,
+    }
+}
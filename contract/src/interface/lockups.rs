@@ -0,0 +1,82 @@
+use crate::interface::{BlockTimestamp, Lockup, YoctoStake};
+use near_sdk::json_types::ValidAccountId;
+
+/// Lets an account, e.g., the owner or a DAO, grant STAKE to a beneficiary under a linear vesting
+/// schedule.
+///
+/// ## Workflow
+/// 1. the grantor creates the lockup, which escrows the granted STAKE from the grantor's account
+/// 2. the granted STAKE vests linearly between the vesting start and end - while escrowed, the STAKE
+///    remains part of the STAKE supply and accrues staking rewards, but it cannot be transferred or
+///    redeemed
+/// 3. the beneficiary claims the vested STAKE into its account, after which it can be transferred or
+///    redeemed like any other STAKE
+/// 4. once all of the granted STAKE has been claimed, the lockup is deleted and the lockup storage
+///    fee is refunded to the grantor
+///
+/// Progress is reported via [events].
+pub trait Lockups {
+    /// escrows the STAKE amount from the predecessor account into a lockup for the beneficiary
+    /// - `vesting_start` and `vesting_end` are block timestamps in nanoseconds - the vesting start
+    ///   may be in the past
+    /// - the attached deposit must cover the lockup storage fee - any excess is refunded
+    /// - unclaimed batch receipts are claimed before the STAKE balance is debited
+    ///
+    /// ## Panics
+    /// - if the grantor or beneficiary account is not registered
+    /// - if the amount is zero
+    /// - if the vesting end is not after the vesting start
+    /// - if the beneficiary already has a lockup
+    /// - if the grantor has insufficient STAKE
+    /// - if the attached deposit does not cover the lockup storage fee
+    ///
+    /// #\[payable\]
+    fn create_lockup(
+        &mut self,
+        beneficiary_id: ValidAccountId,
+        amount: YoctoStake,
+        vesting_start: BlockTimestamp,
+        vesting_end: BlockTimestamp,
+    ) -> Lockup;
+
+    /// credits the vested STAKE that has not yet been claimed to the predecessor account's STAKE
+    /// balance
+    /// - once all of the granted STAKE has been claimed, the lockup is deleted and the lockup storage
+    ///   fee is refunded to the grantor
+    ///
+    /// Returns the amount of STAKE that was claimed.
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the account has no lockup
+    fn claim_vested(&mut self) -> YoctoStake;
+
+    /// returns the account's lockup, or None if the account has no lockup
+    fn lockup_of(&self, account_id: ValidAccountId) -> Option<Lockup>;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct LockupCreated<'a> {
+        pub grantor_id: &'a str,
+        pub beneficiary_id: &'a str,
+        pub amount: u128,
+        pub vesting_start: u64,
+        pub vesting_end: u64,
+    }
+
+    #[derive(Debug)]
+    pub struct VestedStakeClaimed<'a> {
+        pub beneficiary_id: &'a str,
+        pub amount: u128,
+        /// STAKE that remains in the lockup, i.e., unclaimed
+        pub remaining: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct LockupClosed<'a> {
+        pub grantor_id: &'a str,
+        pub beneficiary_id: &'a str,
+        pub storage_fee_refund: u128,
+    }
+}
@@ -0,0 +1,60 @@
+use crate::interface::MigrationProgress;
+
+/// Runs migrations over large persistent collections in chunks, i.e., across many transactions,
+/// in order to stay within the gas limit on deployments with tens of thousands of records.
+///
+/// Each migration task walks its collection using a cursor, which is persisted between chunks.
+/// The cursor range is fixed when the migration is started.
+pub trait Migrations {
+    /// migrates the next chunk of at most `limit` cursor positions for the migration task
+    /// - the migration is started on the first call - the batch receipt migrations are started by
+    ///   the state migration, and account receipts can not be claimed until the migrations reach
+    ///   the account's batches
+    /// - logs `MigrationCompleted` once the cursor reaches the end
+    /// - can be invoked by any account, which means accounts are not blocked on the operator
+    ///
+    /// ## Panics
+    /// - if the migration task is unknown
+    /// - if the migration has already completed - see [reset_migration](Migrations::reset_migration)
+    fn migrate_chunk(&mut self, task_id: String, limit: u32) -> MigrationProgress;
+
+    /// clears the migration progress, which enables the migration to be run again
+    ///
+    /// Returns false if the migration was never started.
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator
+    /// - if the migration task is unknown
+    /// - if the migration has not completed
+    fn reset_migration(&mut self, task_id: String) -> bool;
+
+    /// returns None if the migration has not been started
+    ///
+    /// ## Panics
+    /// if the migration task is unknown
+    fn migration_progress(&self, task_id: String) -> Option<MigrationProgress>;
+
+    /// returns the IDs of the migration tasks that are supported by the contract
+    fn migration_tasks(&self) -> Vec<String>;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct MigrationStarted<'a> {
+        pub task_id: &'a str,
+        pub cursor: u64,
+        pub end: u64,
+    }
+
+    #[derive(Debug)]
+    pub struct MigrationCompleted<'a> {
+        pub task_id: &'a str,
+        pub migrated: u64,
+    }
+
+    #[derive(Debug)]
+    pub struct StateMigrated {
+        pub from: u32,
+        pub to: u32,
+    }
+}
@@ -1,41 +1,154 @@
+mod account_activity;
+mod account_id_hash;
+mod account_preferences;
+mod account_receipts;
+mod balance_proof;
+mod batch_audit;
+mod batch_claims;
+mod batch_history_entry;
 mod batch_id;
+mod batch_reservation;
+mod batch_result;
+mod batch_run_status;
+mod batch_totals_rebuild;
 mod block_height;
 mod block_time_height;
 mod block_timestamp;
 mod config;
 mod contract_balances;
+mod contract_pause;
 pub mod contract_state;
+mod contract_version;
+mod current_batch_ids;
 mod epoch_height;
+mod exit_position;
+mod feature_rollout;
+mod financials_snapshot;
 mod gas;
+mod gas_usage;
+mod health;
+mod idle_near_sweep_stats;
+mod instant_redeem;
+mod invoice;
+mod liquidity_pool;
+mod liquidity_report;
 mod lock;
+mod lock_history_entry;
+mod lockup;
+mod metric_value;
+mod migration_progress;
+mod operator_proposal;
+mod pending_transfer;
+mod pool_preference;
+mod public_goods_donations;
+mod queued_withdrawal;
+mod rebate;
+mod receiver_authorizations;
+mod redeem_limit;
+mod redeem_participation;
+mod redeem_split;
 mod redeem_stake_batch;
 mod redeem_stake_batch_receipt;
+mod redeem_timeline;
+mod referral_code;
+mod reward_fee_fraction;
+mod rounding_policy;
+mod route_quote;
+mod scheduled_action;
+mod simulation;
 mod stake_account;
 mod stake_batch;
 mod stake_batch_receipt;
+mod stake_conversion;
+mod stake_supply_stats;
 mod stake_token_value;
+mod stake_token_value_twap;
+mod staking_pool_info;
+mod storage_delta;
 mod storage_usage;
+mod swap_intent;
 mod timestamped_near_balance;
 mod timestamped_stake_balance;
+mod treasury_balance;
+mod workflow_backoff_status;
+mod workflow_run;
 mod yocto_near;
 mod yocto_stake;
 
+pub use account_activity::AccountActivity;
+pub use account_id_hash::AccountIdHash;
+pub use account_preferences::AccountPreferences;
+pub use account_receipts::{
+    AccountReceipts, AccountRedeemStakeBatchReceipt, AccountStakeBatchReceipt,
+    RedeemStakeBatchReceiptStatus,
+};
+pub use balance_proof::BalanceProof;
+pub use batch_audit::BatchAudit;
+pub use batch_claims::BatchClaims;
+pub use batch_history_entry::BatchHistoryEntry;
 pub use batch_id::*;
+pub use batch_reservation::BatchReservation;
+pub use batch_result::{StakeBatchResult, UnstakeResult};
+pub use batch_run_status::{BatchRunLock, BatchRunLockStatus, BatchRunStatus, BatchRunStep};
+pub use batch_totals_rebuild::BatchTotalsRebuild;
 pub use block_height::*;
 pub use block_time_height::*;
 pub use block_timestamp::*;
 pub use config::*;
 pub use contract_balances::*;
+pub use contract_pause::ContractPause;
+pub use contract_version::ContractVersion;
+pub use current_batch_ids::CurrentBatchIds;
 pub use epoch_height::*;
+pub use exit_position::ExitPosition;
+pub use feature_rollout::FeatureRollout;
+pub use financials_snapshot::FinancialsSnapshot;
 pub use gas::*;
+pub use gas_usage::{GasUsage, StakingPoolGasUsage};
+pub use health::{AggregateAlarm, Health};
+pub use idle_near_sweep_stats::IdleNearSweepStats;
+pub use instant_redeem::InstantRedeem;
+pub use invoice::{Invoice, InvoiceStatus};
+pub use liquidity_pool::{LiquidityPoolBalance, LiquidityShares, StakeSwap};
+pub use liquidity_report::LiquidityReport;
+pub use lock_history_entry::LockHistoryEntry;
+pub use lockup::Lockup;
+pub use metric_value::MetricValue;
+pub use migration_progress::MigrationProgress;
+pub use operator_proposal::{OperatorAction, OperatorProposal};
+pub use pending_transfer::PendingTransfer;
+pub use pool_preference::{PoolPreference, PoolPreferenceTally};
+pub use public_goods_donations::PublicGoodsDonations;
+pub use queued_withdrawal::QueuedWithdrawal;
+pub use rebate::{AccountRebate, RebateProgram, RebateRound, RebateTier};
+pub use receiver_authorizations::ReceiverAuthorizations;
+pub use redeem_limit::{RedeemLimit, RedeemLimitIncrease};
+pub use redeem_participation::{RedeemParticipation, RedeemParticipationStatus};
+pub use redeem_split::RedeemSplit;
 pub use redeem_stake_batch::RedeemStakeBatch;
 pub use redeem_stake_batch_receipt::RedeemStakeBatchReceipt;
-pub use stake_account::StakeAccount;
+pub use redeem_timeline::{RedeemTimeline, RedeemTimelineStage, RedeemTimelineStep};
+pub use referral_code::ReferralCode;
+pub use reward_fee_fraction::RewardFeeFraction;
+pub use rounding_policy::{Rounding, RoundingPolicy};
+pub use route_quote::RouteQuote;
+pub use scheduled_action::{ScheduledAction, ScheduledActionId};
+pub use simulation::{DepositSimulation, RedeemSimulation};
+pub use stake_account::{StakeAccount, StakeAccountEntry};
 pub use stake_batch::StakeBatch;
 pub use stake_batch_receipt::StakeBatchReceipt;
+pub use stake_conversion::StakeConversion;
+pub use stake_supply_stats::StakeSupplyStats;
 pub use stake_token_value::StakeTokenValue;
+pub use stake_token_value_twap::StakeTokenValueTwap;
+pub use staking_pool_info::StakingPoolInfo;
+pub use storage_delta::StorageDelta;
 pub use storage_usage::*;
+pub use swap_intent::{FillSwapIntentMessage, SwapIntent};
 pub use timestamped_near_balance::TimestampedNearBalance;
 pub use timestamped_stake_balance::TimestampedStakeBalance;
+pub use treasury_balance::TreasuryBalance;
+pub use workflow_backoff_status::WorkflowBackoffStatus;
+pub use workflow_run::WorkflowRun;
 pub use yocto_near::*;
 pub use yocto_stake::*;
@@ -0,0 +1,29 @@
+use crate::{
+    domain::{self, AccountActivityKind},
+    interface::{BatchId, BlockTimeHeight},
+};
+use near_sdk::{
+    json_types::U128,
+    serde::{Deserialize, Serialize},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountActivity {
+    pub kind: AccountActivityKind,
+    /// yoctoNEAR for deposits, claims, and withdrawals - yoctoSTAKE for stakes, redeems, and transfers
+    pub amount: U128,
+    pub batch_id: Option<BatchId>,
+    pub block_time_height: BlockTimeHeight,
+}
+
+impl From<domain::AccountActivity> for AccountActivity {
+    fn from(activity: domain::AccountActivity) -> Self {
+        Self {
+            kind: activity.kind(),
+            amount: activity.amount().into(),
+            batch_id: activity.batch_id().map(Into::into),
+            block_time_height: activity.block_time_height().into(),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::core::Hash;
+use near_sdk::{
+    json_types::Base64VecU8,
+    serde::{Deserialize, Serialize},
+};
+use std::convert::TryInto;
+
+/// Accounts are stored keyed by the SHA-256 hash of the account ID UTF-8 bytes. The hash is
+/// serialized as a base64 encoded string.
+///
+/// Infrastructure that only persists account ID hashes, e.g., for privacy reasons, can use the hash
+/// to lookup accounts without needing to store the raw account IDs. Off chain, the hash can be
+/// computed using any SHA-256 implementation, e.g.,
+/// ```shell
+/// echo -n "alfio-zappala.near" | sha256sum | xxd -r -p | base64
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountIdHash(pub Base64VecU8);
+
+impl AccountIdHash {
+    /// computes the account ID hash that is used as the account storage key
+    /// - NOTE: requires the NEAR runtime environment because the hash is computed via `env::sha256`
+    pub fn from_account_id(account_id: &str) -> Self {
+        Hash::from(account_id).into()
+    }
+}
+
+impl From<Hash> for AccountIdHash {
+    fn from(hash: Hash) -> Self {
+        Self(Base64VecU8(hash.as_bytes().to_vec()))
+    }
+}
+
+impl From<AccountIdHash> for Hash {
+    /// ## Panics
+    /// if the hash is not 32 bytes
+    fn from(hash: AccountIdHash) -> Self {
+        let bytes: [u8; Hash::LENGTH] = (hash.0).0.as_slice().try_into().unwrap_or_else(|_| {
+            panic!(
+                "account ID hash must be {} bytes: {}",
+                Hash::LENGTH,
+                (hash.0).0.len()
+            )
+        });
+        bytes.into()
+    }
+}
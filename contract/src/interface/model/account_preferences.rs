@@ -0,0 +1,32 @@
+use crate::domain;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// see [set_account_preferences](crate::interface::AccountManagement::set_account_preferences)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountPreferences {
+    /// claim the account's receipts whenever the account is touched, including when the account
+    /// receives STAKE from other accounts
+    pub auto_claim_receipts: bool,
+    /// move claimed redeemed NEAR into the account's stake batch instead of the account NEAR
+    /// balance, i.e., compound the redeemed NEAR
+    pub auto_compound: bool,
+}
+
+impl From<domain::AccountPreferences> for AccountPreferences {
+    fn from(value: domain::AccountPreferences) -> Self {
+        Self {
+            auto_claim_receipts: value.auto_claim_receipts,
+            auto_compound: value.auto_compound,
+        }
+    }
+}
+
+impl From<AccountPreferences> for domain::AccountPreferences {
+    fn from(value: AccountPreferences) -> Self {
+        Self {
+            auto_claim_receipts: value.auto_claim_receipts,
+            auto_compound: value.auto_compound,
+        }
+    }
+}
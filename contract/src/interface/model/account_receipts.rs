@@ -0,0 +1,41 @@
+use crate::interface::{BatchId, YoctoNear, YoctoStake};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Batch receipts that currently include unclaimed funds for an account
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountReceipts {
+    pub stake_batch_receipts: Vec<AccountStakeBatchReceipt>,
+    pub redeem_stake_batch_receipts: Vec<AccountRedeemStakeBatchReceipt>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountStakeBatchReceipt {
+    pub batch_id: BatchId,
+    /// NEAR that the account staked in the batch
+    pub staked_near: YoctoNear,
+    /// STAKE that is owed to the account - STAKE can always be claimed once the receipt exists
+    pub stake: YoctoStake,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountRedeemStakeBatchReceipt {
+    pub batch_id: BatchId,
+    /// STAKE that the account redeemed in the batch
+    pub redeemed_stake: YoctoStake,
+    /// NEAR that is owed to the account
+    pub near: YoctoNear,
+    pub status: RedeemStakeBatchReceiptStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RedeemStakeBatchReceiptStatus {
+    /// the NEAR funds can be claimed now
+    Claimable,
+    /// the unstaked NEAR funds are still locked in the staking pool
+    /// - funds may still be claimed against the contract NEAR liquidity, if available
+    PendingWithdrawal,
+}
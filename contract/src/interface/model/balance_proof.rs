@@ -0,0 +1,21 @@
+use crate::interface::{BlockTimeHeight, YoctoStake};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Witness that an account held at least the minimum STAKE balance at the anchored block.
+///
+/// The proof is only trustworthy when it is received as the result of a cross-contract call to
+/// the STAKE token contract - consumers must verify that `contract_id` is the contract they called.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BalanceProof {
+    /// STAKE token contract that produced the proof
+    pub contract_id: String,
+    pub account_id: String,
+    pub min_amount: YoctoStake,
+    /// STAKE balance including unclaimed stake batch receipts
+    pub balance: YoctoStake,
+    /// true if `balance >= min_amount`
+    pub proven: bool,
+    /// the block the proof is anchored to
+    pub block_time_height: BlockTimeHeight,
+}
@@ -0,0 +1,42 @@
+use crate::{
+    domain,
+    interface::{StakeTokenValue, YoctoNear},
+};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// audit record for an executed batch - see [batch_audit](crate::interface::StakingService::batch_audit)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BatchAudit {
+    /// staked balance returned by the staking pool
+    pub staked_balance: YoctoNear,
+    /// unstaked balance returned by the staking pool
+    pub unstaked_balance: YoctoNear,
+    /// NEAR value of the pending withdrawal at the time the batch was run
+    pub pending_withdrawal: YoctoNear,
+    /// NEAR liquidity pool balance at the time the batch was run
+    pub near_liquidity: YoctoNear,
+    /// staked NEAR balance that was derived from the raw staking pool balances
+    pub total_staked_near_balance: YoctoNear,
+    /// STAKE token value before the batch was run
+    pub prior_stake_token_value: StakeTokenValue,
+    /// NEAR that was added to the liquidity pool to prevent the STAKE token value from ticking down
+    pub compensation: YoctoNear,
+    /// STAKE token value after the batch was run
+    pub stake_token_value: StakeTokenValue,
+}
+
+impl From<domain::BatchAudit> for BatchAudit {
+    fn from(audit: domain::BatchAudit) -> Self {
+        Self {
+            staked_balance: audit.staked_balance().into(),
+            unstaked_balance: audit.unstaked_balance().into(),
+            pending_withdrawal: audit.pending_withdrawal().into(),
+            near_liquidity: audit.near_liquidity().into(),
+            total_staked_near_balance: audit.total_staked_near_balance().into(),
+            prior_stake_token_value: audit.prior_stake_token_value().into(),
+            compensation: audit.compensation().into(),
+            stake_token_value: audit.stake_token_value().into(),
+        }
+    }
+}
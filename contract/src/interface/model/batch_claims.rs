@@ -0,0 +1,17 @@
+use crate::interface::BatchId;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// progress of chunked batch claims processing - see [process_claims](crate::interface::BatchTotals::process_claims)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BatchClaims {
+    pub batch_id: BatchId,
+    /// contributor index that the next chunk should start from
+    pub next_index: u64,
+    /// total number of contributors in the batch contributor index
+    pub contributors: u64,
+    /// number of accounts within the chunk whose batch funds were claimed
+    pub claimed: u64,
+    /// true once all contributors have been processed
+    pub complete: bool,
+}
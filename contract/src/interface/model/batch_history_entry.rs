@@ -0,0 +1,41 @@
+use crate::{
+    domain,
+    interface::{BatchId, BlockTimeHeight, BlockTimestamp, StakeTokenValue, YoctoNear, YoctoStake},
+};
+use near_sdk::{
+    json_types::U64,
+    serde::{Deserialize, Serialize},
+};
+
+/// summary record for a batch that was run - see [BatchHistory](crate::interface::BatchHistory)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BatchHistoryEntry {
+    /// history index, which can be used to page through the history
+    pub index: U64,
+    pub batch_id: BatchId,
+    /// NEAR that was staked, or the NEAR value of the STAKE that was redeemed
+    pub near_amount: YoctoNear,
+    /// STAKE that was minted, or the STAKE that was redeemed
+    pub stake_amount: YoctoStake,
+    /// STAKE token value that was used to run the batch
+    pub stake_token_value: StakeTokenValue,
+    /// when funds were last added to the batch
+    pub last_deposit_block_timestamp: BlockTimestamp,
+    /// when the batch was run
+    pub run: BlockTimeHeight,
+}
+
+impl BatchHistoryEntry {
+    pub fn new(index: u64, entry: domain::BatchHistoryEntry) -> Self {
+        Self {
+            index: index.into(),
+            batch_id: entry.batch_id().into(),
+            near_amount: entry.near_amount().into(),
+            stake_amount: entry.stake_amount().into(),
+            stake_token_value: entry.stake_token_value().into(),
+            last_deposit_block_timestamp: entry.last_deposit_block_timestamp().into(),
+            run: entry.run().into(),
+        }
+    }
+}
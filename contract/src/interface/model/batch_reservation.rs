@@ -0,0 +1,34 @@
+use crate::domain;
+use crate::interface::{BlockHeight, BlockTimeHeight, YoctoNear};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BatchReservation {
+    pub account_id: AccountId,
+    /// NEAR that will be deposited into the stake batch when the next batch run starts
+    pub amount: YoctoNear,
+    /// refunded to the account NEAR balance when the reservation is used - forfeited if the
+    /// reservation expires or is cancelled
+    pub fee: YoctoNear,
+    pub created: BlockTimeHeight,
+    pub expires_at: BlockHeight,
+    pub expired: bool,
+}
+
+impl BatchReservation {
+    /// `block_height` is used to determine whether the reservation has expired
+    pub fn from(reservation: domain::BatchReservation, block_height: domain::BlockHeight) -> Self {
+        Self {
+            account_id: reservation.account_id().to_string(),
+            amount: reservation.amount().into(),
+            fee: reservation.fee().into(),
+            created: reservation.created().into(),
+            expires_at: reservation.expires_at().into(),
+            expired: reservation.is_expired(block_height),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    domain,
+    interface::{BatchId, StakeTokenValue, YoctoNear, YoctoStake},
+};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Outcome of a stake batch run, which is returned by the final callback of the stake batch
+/// workflow.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeBatchResult {
+    pub batch_id: BatchId,
+    /// NEAR that was staked from the batch
+    pub staked_near: YoctoNear,
+    /// STAKE that was minted for the batch
+    pub stake_minted: YoctoStake,
+    /// the STAKE token value that the batch was minted at
+    pub stake_token_value: StakeTokenValue,
+    /// false if the staked NEAR balance was derived from the cached STAKE token value because
+    /// the staking pool account could not be fetched - the batch is verified on the next STAKE
+    /// token value refresh
+    pub verified: bool,
+}
+
+impl StakeBatchResult {
+    pub fn new(
+        batch_id: domain::BatchId,
+        receipt: &domain::StakeBatchReceipt,
+        verified: bool,
+    ) -> Self {
+        Self {
+            batch_id: batch_id.into(),
+            staked_near: receipt.staked_near().into(),
+            stake_minted: receipt.near_stake_value().into(),
+            stake_token_value: receipt.stake_token_value().into(),
+            verified,
+        }
+    }
+}
+
+/// Outcome of unstaking a redeem stake batch, which is returned by the final callback of the
+/// unstake workflow.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct UnstakeResult {
+    pub batch_id: BatchId,
+    /// STAKE that was redeemed and burned
+    pub redeemed_stake: YoctoStake,
+    /// NEAR that was unstaked for the redeemed STAKE - available for withdrawal from the staking
+    /// pool after the unstaking period
+    pub unstaked_near: YoctoNear,
+    /// the STAKE token value that the batch was redeemed at
+    pub stake_token_value: StakeTokenValue,
+}
+
+impl UnstakeResult {
+    pub fn new(batch_id: domain::BatchId, receipt: &domain::RedeemStakeBatchReceipt) -> Self {
+        Self {
+            batch_id: batch_id.into(),
+            redeemed_stake: receipt.redeemed_stake().into(),
+            unstaked_near: receipt.stake_near_value().into(),
+            stake_token_value: receipt.stake_token_value().into(),
+        }
+    }
+}
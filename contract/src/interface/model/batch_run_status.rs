@@ -0,0 +1,50 @@
+use crate::interface::model::lock::StakeLock;
+use crate::{
+    domain::RedeemLock,
+    interface::{BatchId, BlockHeight, BlockTimeHeight},
+};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// describes the batch workflows that are currently running - see
+/// [batch_run_status](crate::interface::Operator::batch_run_status)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BatchRunStatus {
+    pub block: BlockTimeHeight,
+    /// None if the [StakeLock](crate::domain::StakeLock) is not held
+    pub stake: Option<BatchRunLockStatus>,
+    /// None if the [RedeemLock](crate::domain::RedeemLock) is not held
+    pub redeem: Option<BatchRunLockStatus>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BatchRunLockStatus {
+    pub lock: BatchRunLock,
+    /// None if the lock was acquired before lock acquisition was tracked
+    /// - locks that are imported via [import_workflow_state](crate::interface::Operator::import_workflow_state)
+    ///   are timed from the import
+    pub acquired_block_height: Option<BlockHeight>,
+    /// batches that the workflow is running
+    pub batch_ids: Vec<BatchId>,
+    pub next_step: BatchRunStep,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum BatchRunLock {
+    Stake(StakeLock),
+    Redeem(RedeemLock),
+}
+
+/// what the locked workflow is waiting on in order to make progress
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum BatchRunStep {
+    /// the workflow is waiting on the contract callback with the specified method name
+    /// - if the lock is held long after the callback should have run, then the callback most
+    ///   likely failed, e.g., exceeded prepaid gas
+    Callback(String),
+    /// the workflow is waiting for the contract method with the specified name to be invoked
+    Invoke(String),
+}
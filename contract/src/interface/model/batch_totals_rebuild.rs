@@ -0,0 +1,25 @@
+use crate::interface::BatchId;
+use near_sdk::{
+    json_types::U128,
+    serde::{Deserialize, Serialize},
+};
+
+/// progress of a chunked batch balance rebuild - see [rebuild_batch_totals](crate::interface::BatchTotals::rebuild_batch_totals)
+/// - balances are in yoctoNEAR for stake batches and in yoctoSTAKE for redeem stake batches
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BatchTotalsRebuild {
+    pub batch_id: BatchId,
+    /// contributor index that the next chunk should start from
+    pub next_index: u64,
+    /// total number of contributors in the batch contributor index
+    pub contributors: u64,
+    /// batch balance re-derived from the contributors that have been processed so far
+    pub rebuilt_balance: U128,
+    /// contract level batch balance
+    pub recorded_balance: U128,
+    /// true once all contributors have been processed
+    pub complete: bool,
+    /// true if the contract level batch balance was repaired
+    pub repaired: bool,
+}
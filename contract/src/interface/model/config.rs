@@ -2,20 +2,143 @@ use crate::{
     config,
     interface::{Gas, YoctoNear},
 };
-use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    json_types::{Base64VecU8, ValidAccountId},
+    serde::{Deserialize, Serialize},
+};
+use std::convert::TryFrom;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Config {
     pub storage_cost_per_byte: Option<YoctoNear>,
     pub gas_config: Option<GasConfig>,
     /// percentage of contract gas rewards that are distributed to the contract owner
     /// - the rest of the contract earnings are staked to boost the staking rewards for user accounts
+    /// - the protocol fee is skimmed before the owner share is applied
     /// - must be a number between 0-100
     pub contract_owner_earnings_percentage: Option<u8>,
+    /// number of epochs to back off running stake batches after the staking pool was detected to
+    /// be unavailable
+    pub staking_pool_unavailable_backoff_epochs: Option<u64>,
+    /// number of epochs before an increase to an account's redeem limit takes effect
+    pub redeem_limit_increase_delay_epochs: Option<u64>,
+    /// max percentage of collected earnings that are distributed per stake batch run
+    /// - large windfalls are spread across multiple batch runs to smooth the STAKE token value curve
+    /// - must be a number between 1-100
+    pub max_earnings_distribution_percentage: Option<u8>,
+    /// max amount of collected earnings that are distributed per stake batch run
+    /// - zero means there is no limit
+    pub max_earnings_distribution_amount: Option<YoctoNear>,
+    /// max amount of NEAR that can leave the contract per epoch via account withdrawals and NEAR
+    /// transfers - any overflow is queued and released in the next epoch
+    /// - zero means there is no limit
+    pub max_withdrawal_per_epoch: Option<YoctoNear>,
+    /// max percentage that the STAKE token value can increase per update - larger jumps are held
+    /// pending until the operator confirms them
+    /// - must be a number between 0-100
+    /// - zero means there is no limit
+    pub max_stake_token_value_increase_percentage: Option<u8>,
+    /// number of blocks that must pass since the stake batch balance last changed before the batch
+    /// can be run - deposits can be withdrawn from the batch penalty free during the cooldown
+    /// - zero means there is no cooldown
+    pub deposit_cooldown_blocks: Option<u64>,
+    /// records the storage usage delta of the last call per instrumented method - should only be
+    /// enabled while profiling
+    pub storage_delta_diagnostics: Option<bool>,
+    /// when enabled, a partial stake batch withdrawal that would leave a batch balance below the
+    /// min required NEAR deposit withdraws the full batch balance instead of failing
+    pub withdraw_full_batch_balance_below_min_deposit: Option<bool>,
+    /// fee that is escrowed with a batch reservation - forfeited if the reservation is not used
+    pub batch_reservation_fee: Option<YoctoNear>,
+    /// number of blocks a batch reservation is valid for
+    /// - must be > 0
+    pub batch_reservation_expiry_blocks: Option<u64>,
+    /// when enabled, the gas attached to staking pool calls is nudged toward the observed gas usage
+    pub staking_pool_gas_auto_tune: Option<bool>,
+    /// headroom that is added on top of the max observed gas burned when auto-tuning
+    /// - must be a number between 0-100
+    pub staking_pool_gas_auto_tune_headroom_percentage: Option<u8>,
+    /// percentage of the sweepable idle NEAR that is swept into a staking cycle - 0 disables sweeping
+    /// - must be a number between 0-100
+    pub idle_near_sweep_percentage: Option<u8>,
+    /// percentage of the idle NEAR that is always kept liquid to fulfill withdrawals
+    /// - must be a number between 1-100
+    pub idle_near_liquidity_buffer_percentage: Option<u8>,
+    /// number of epochs that swept NEAR stays staked before it is redeemed
+    /// - must be > 0
+    pub idle_near_sweep_cycle_epochs: Option<u64>,
+    /// safety margin that is added on top of the protocol storage byte cost when computing the
+    /// account storage fee
+    /// - must be a number between 0-100
+    pub account_storage_fee_margin_percentage: Option<u8>,
+    /// public goods beneficiary that receives a share of the user account earnings when earnings
+    /// are distributed
+    pub public_goods_beneficiary_id: Option<ValidAccountId>,
+    /// percentage of the user account earnings that is donated to the public goods beneficiary
+    /// - 0 disables donations
+    /// - must be a number between 0-100
+    /// - when > 0, then the public goods beneficiary must be set
+    pub public_goods_percentage: Option<u8>,
+    /// max percentage that the STAKE supply, total NEAR, or NEAR liquidity pool can change within a
+    /// single contract call before an alarm is emitted
+    /// - 0 disables the alarm
+    /// - must be a number between 0-100
+    pub aggregate_alarm_txn_threshold_percentage: Option<u8>,
+    /// max percentage that the STAKE supply, total NEAR, or NEAR liquidity pool can change within an
+    /// epoch before an alarm is emitted
+    /// - 0 disables the alarm
+    /// - must be a number between 0-100
+    pub aggregate_alarm_epoch_threshold_percentage: Option<u8>,
+    /// cooldown that is enforced before a workflow can be retried after it failed - the cooldown
+    /// doubles with each consecutive failure
+    /// - 0 disables the backoff
+    /// - must be <= [workflow_backoff_max_blocks](Config::workflow_backoff_max_blocks)
+    pub workflow_backoff_base_blocks: Option<u64>,
+    /// caps the workflow backoff cooldown
+    pub workflow_backoff_max_blocks: Option<u64>,
+    /// number of blocks after which a pending `ft_transfer_call` whose resolve callback never ran
+    /// can be resolved
+    /// - must be > 0
+    pub stalled_transfer_timeout_blocks: Option<u64>,
+    /// wNEAR contract whose `ft_transfer_call` transfers are unwrapped and deposited into the
+    /// sender's stake batch
+    pub wrap_near_contract_id: Option<ValidAccountId>,
+    /// when enabled, the contract only stakes with a staking pool once the staking pool whitelist
+    /// contract reports it as whitelisted
+    /// - should be disabled on networks without a staking pool whitelist contract, e.g., testnets
+    pub staking_pool_whitelist_check: Option<bool>,
+    pub staking_pool_whitelist_contract_id: Option<ValidAccountId>,
+    /// number of activity entries that are retained per account
+    /// - must be <= [MAX_ACCOUNT_ACTIVITY_LOG_LEN](crate::domain::MAX_ACCOUNT_ACTIVITY_LOG_LEN)
+    /// - 0 disables activity logging
+    pub account_activity_log_len: Option<u8>,
+    /// number of batch history entries that are retained per batch kind
+    /// - must be <= [MAX_BATCH_HISTORY_CAPACITY](crate::domain::MAX_BATCH_HISTORY_CAPACITY)
+    /// - 0 disables the batch history
+    pub batch_history_capacity: Option<u32>,
+    /// protocol fee in basis points that is skimmed from the distributed earnings and staked as
+    /// treasury owned STAKE
+    /// - must be <= [MAX_PROTOCOL_FEE_BASIS_POINTS](crate::config::MAX_PROTOCOL_FEE_BASIS_POINTS)
+    /// - 0 disables the protocol fee
+    pub protocol_fee_basis_points: Option<u16>,
+    /// number of epochs that a workflow lock must be held before it can be released as stale -
+    /// see [release_stale_lock](crate::interface::Operator::release_stale_lock)
+    /// - must be > 0
+    pub stale_lock_timeout_epochs: Option<u64>,
+    /// fee in basis points that is earned by the liquidity providers when claims pending withdrawal
+    /// are served from the liquidity provider pool
+    /// - must be <= [MAX_LIQUIDITY_PROVIDER_FEE_BASIS_POINTS](crate::config::MAX_LIQUIDITY_PROVIDER_FEE_BASIS_POINTS)
+    pub liquidity_provider_fee_basis_points: Option<u16>,
+    /// STAKE to NEAR swap fee in basis points when the liquidity provider pool utilization is zero
+    /// - must be <= `swap_fee_max_basis_points`
+    pub swap_fee_min_basis_points: Option<u16>,
+    /// STAKE to NEAR swap fee in basis points when the liquidity provider pool is fully utilized
+    /// - must be <= [MAX_LIQUIDITY_PROVIDER_FEE_BASIS_POINTS](crate::config::MAX_LIQUIDITY_PROVIDER_FEE_BASIS_POINTS)
+    pub swap_fee_max_basis_points: Option<u16>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(crate = "near_sdk::serde")]
 pub struct GasConfig {
     pub staking_pool: Option<StakingPoolGasConfig>,
@@ -23,9 +146,11 @@ pub struct GasConfig {
 
     pub function_call_promise: Option<Gas>,
     pub function_call_promise_data_dependency: Option<Gas>,
+    /// zero means all remaining prepaid gas is forwarded to `ft_on_transfer`
+    pub ft_on_transfer: Option<Gas>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(crate = "near_sdk::serde")]
 pub struct StakingPoolGasConfig {
     pub deposit_and_stake: Option<Gas>,
@@ -34,10 +159,11 @@ pub struct StakingPoolGasConfig {
     pub unstake: Option<Gas>,
     pub withdraw: Option<Gas>,
     pub get_account: Option<Gas>,
+    pub get_reward_fee_fraction: Option<Gas>,
     pub ping: Option<Gas>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(crate = "near_sdk::serde")]
 pub struct CallBacksGasConfig {
     pub on_run_stake_batch: Option<Gas>,
@@ -54,6 +180,75 @@ pub struct CallBacksGasConfig {
     pub resolve_transfer_gas: Option<Gas>,
 
     pub refresh_stake_token_value: Option<Gas>,
+    pub on_refresh_reward_fee_fraction: Option<Gas>,
+
+    /// used by the redeem forwarding workflow
+    pub deposit_redeem_forwarding: Option<Gas>,
+    pub on_redeem_forwarding: Option<Gas>,
+
+    /// used by the swap intent workflow
+    pub swap_stake_token_value: Option<Gas>,
+    pub on_swap_intent_fill: Option<Gas>,
+    pub swap_settlement_transfer: Option<Gas>,
+    pub on_swap_settlement: Option<Gas>,
+
+    /// used by the wNEAR deposit workflow
+    pub wrap_near_withdraw: Option<Gas>,
+    pub on_wrap_near_withdraw: Option<Gas>,
+
+    /// used by the staking pool whitelist check
+    pub staking_pool_whitelist_check: Option<Gas>,
+    pub on_staking_pool_whitelist_check: Option<Gas>,
+
+    /// used by the stale lock recovery
+    pub on_release_stale_lock: Option<Gas>,
+}
+
+/// named config presets that can be used to bootstrap the contract config at deployment time
+/// - see [Contract::new](crate::Contract::new)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum ConfigPreset {
+    /// spreads out earnings distributions and guards against large STAKE token value jumps
+    /// - the staking pool must be whitelisted by the staking pool whitelist contract
+    MainnetConservative,
+    /// minimizes delays and limits to speed up testing
+    TestnetFast,
+}
+
+impl ConfigPreset {
+    pub const ALL: [ConfigPreset; 2] =
+        [ConfigPreset::MainnetConservative, ConfigPreset::TestnetFast];
+}
+
+/// config validation issue that would prevent the config from being updated
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ConfigFinding {
+    /// the config field that the finding applies to, e.g., `callbacks::on_run_stake_batch`
+    pub field: String,
+    pub message: String,
+}
+
+/// result of a config validation dry run - see [validate_config](crate::interface::Operator::validate_config)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ConfigValidation {
+    /// empty if the config is valid
+    pub findings: Vec<ConfigFinding>,
+    /// required by [update_config](crate::interface::Operator::update_config) to apply the config
+    /// - the hash is bound to the proposed config and the current config, thus it is invalidated
+    ///   by any other config change
+    /// - None if the config is invalid
+    pub validation_hash: Option<Base64VecU8>,
+}
+
+/// config preset with the full config that it expands into
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct InitConfigPreset {
+    pub preset: ConfigPreset,
+    pub config: Config,
 }
 
 impl From<config::Config> for Config {
@@ -62,6 +257,65 @@ impl From<config::Config> for Config {
             storage_cost_per_byte: Some(value.storage_cost_per_byte().into()),
             gas_config: Some(value.gas_config().into()),
             contract_owner_earnings_percentage: Some(value.contract_owner_earnings_percentage()),
+            staking_pool_unavailable_backoff_epochs: Some(
+                value.staking_pool_unavailable_backoff_epochs(),
+            ),
+            redeem_limit_increase_delay_epochs: Some(value.redeem_limit_increase_delay_epochs()),
+            max_earnings_distribution_percentage: Some(
+                value.max_earnings_distribution_percentage(),
+            ),
+            max_earnings_distribution_amount: Some(value.max_earnings_distribution_amount().into()),
+            max_withdrawal_per_epoch: Some(value.max_withdrawal_per_epoch().into()),
+            max_stake_token_value_increase_percentage: Some(
+                value.max_stake_token_value_increase_percentage(),
+            ),
+            deposit_cooldown_blocks: Some(value.deposit_cooldown_blocks()),
+            storage_delta_diagnostics: Some(value.storage_delta_diagnostics()),
+            withdraw_full_batch_balance_below_min_deposit: Some(
+                value.withdraw_full_batch_balance_below_min_deposit(),
+            ),
+            batch_reservation_fee: Some(value.batch_reservation_fee().into()),
+            batch_reservation_expiry_blocks: Some(value.batch_reservation_expiry_blocks()),
+            staking_pool_gas_auto_tune: Some(value.staking_pool_gas_auto_tune()),
+            staking_pool_gas_auto_tune_headroom_percentage: Some(
+                value.staking_pool_gas_auto_tune_headroom_percentage(),
+            ),
+            idle_near_sweep_percentage: Some(value.idle_near_sweep_percentage()),
+            idle_near_liquidity_buffer_percentage: Some(
+                value.idle_near_liquidity_buffer_percentage(),
+            ),
+            idle_near_sweep_cycle_epochs: Some(value.idle_near_sweep_cycle_epochs()),
+            account_storage_fee_margin_percentage: Some(
+                value.account_storage_fee_margin_percentage(),
+            ),
+            public_goods_beneficiary_id: value
+                .public_goods_beneficiary_id()
+                .and_then(|account_id| ValidAccountId::try_from(account_id).ok()),
+            public_goods_percentage: Some(value.public_goods_percentage()),
+            aggregate_alarm_txn_threshold_percentage: Some(
+                value.aggregate_alarm_txn_threshold_percentage(),
+            ),
+            aggregate_alarm_epoch_threshold_percentage: Some(
+                value.aggregate_alarm_epoch_threshold_percentage(),
+            ),
+            workflow_backoff_base_blocks: Some(value.workflow_backoff_base_blocks()),
+            workflow_backoff_max_blocks: Some(value.workflow_backoff_max_blocks()),
+            stalled_transfer_timeout_blocks: Some(value.stalled_transfer_timeout_blocks()),
+            wrap_near_contract_id: value
+                .wrap_near_contract_id()
+                .and_then(|account_id| ValidAccountId::try_from(account_id).ok()),
+            staking_pool_whitelist_check: Some(value.staking_pool_whitelist_check()),
+            staking_pool_whitelist_contract_id: ValidAccountId::try_from(
+                value.staking_pool_whitelist_contract_id(),
+            )
+            .ok(),
+            account_activity_log_len: Some(value.account_activity_log_len()),
+            batch_history_capacity: Some(value.batch_history_capacity()),
+            protocol_fee_basis_points: Some(value.protocol_fee_basis_points()),
+            stale_lock_timeout_epochs: Some(value.stale_lock_timeout_epochs()),
+            liquidity_provider_fee_basis_points: Some(value.liquidity_provider_fee_basis_points()),
+            swap_fee_min_basis_points: Some(value.swap_fee_min_basis_points()),
+            swap_fee_max_basis_points: Some(value.swap_fee_max_basis_points()),
         }
     }
 }
@@ -75,6 +329,7 @@ impl From<config::GasConfig> for GasConfig {
             function_call_promise_data_dependency: Some(
                 value.function_call_promise_data_dependency().into(),
             ),
+            ft_on_transfer: Some(value.ft_on_transfer().into()),
         }
     }
 }
@@ -88,6 +343,7 @@ impl From<config::StakingPoolGasConfig> for StakingPoolGasConfig {
             unstake: Some(value.unstake().into()),
             withdraw: Some(value.withdraw().into()),
             get_account: Some(value.get_account().into()),
+            get_reward_fee_fraction: Some(value.get_reward_fee_fraction().into()),
             ping: Some(value.ping().into()),
         }
     }
@@ -109,6 +365,18 @@ impl From<config::CallBacksGasConfig> for CallBacksGasConfig {
             ),
             resolve_transfer_gas: Some(value.resolve_transfer_gas().into()),
             refresh_stake_token_value: Some(value.on_refresh_stake_token_value().into()),
+            on_refresh_reward_fee_fraction: Some(value.on_refresh_reward_fee_fraction().into()),
+            deposit_redeem_forwarding: Some(value.deposit_redeem_forwarding().into()),
+            on_redeem_forwarding: Some(value.on_redeem_forwarding().into()),
+            swap_stake_token_value: Some(value.swap_stake_token_value().into()),
+            on_swap_intent_fill: Some(value.on_swap_intent_fill().into()),
+            swap_settlement_transfer: Some(value.swap_settlement_transfer().into()),
+            on_swap_settlement: Some(value.on_swap_settlement().into()),
+            wrap_near_withdraw: Some(value.wrap_near_withdraw().into()),
+            on_wrap_near_withdraw: Some(value.on_wrap_near_withdraw().into()),
+            staking_pool_whitelist_check: Some(value.staking_pool_whitelist_check().into()),
+            on_staking_pool_whitelist_check: Some(value.on_staking_pool_whitelist_check().into()),
+            on_release_stale_lock: Some(value.on_release_stale_lock().into()),
         }
     }
 }
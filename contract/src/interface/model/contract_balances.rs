@@ -24,6 +24,20 @@ pub struct ContractBalances {
     pub near_liquidity_pool: YoctoNear,
     /// total balance that has been escrowed to pay for user account storage
     pub total_account_storage_escrow: YoctoNear,
+    /// storage fees for freed contract storage that are owed to the accounts whose actions freed it
+    pub total_storage_refunds: YoctoNear,
+    /// NEAR funds reserved for the fee rebate program that have not yet been claimed
+    pub rebate_funds: YoctoNear,
+    /// operator funded NEAR that pays the account storage fee for auto-registered contracts
+    pub registration_pool: YoctoNear,
+    /// NEAR escrowed by batch reservations that will be deposited into the next stake batch run,
+    /// including the escrowed reservation fees
+    pub batch_reservation_escrow: YoctoNear,
+    /// idle NEAR that is swept into staking - accounted for in the account NEAR balances, but is not
+    /// liquid until the sweep cycle completes
+    pub idle_near_swept: YoctoNear,
+    /// idle NEAR yield that has not yet been credited to account NEAR balances
+    pub idle_near_yield_pool: YoctoNear,
 
     pub contract_owner_balance: YoctoNear,
     /// contract earnings that have been accumulated but not yet staked
@@ -31,10 +45,14 @@ pub struct ContractBalances {
     /// NOTE: earnings are distributed when funds are staked, i.e.,
     ///       when [stake()](crate::interface::StakingService::stake) is run.
     pub contract_earnings: YoctoNear,
+    /// basis points of contract_earnings that are skimmed as the protocol fee for the treasury
+    pub protocol_fee_earnings: YoctoNear,
     /// percentage of contract_earnings that are owned by the contract owner
     pub contract_owner_earnings: YoctoNear,
     /// percentage of contract_earnings that are owned by the user accounts
     pub user_accounts_earnings: YoctoNear,
+    /// percentage of contract_earnings that will be donated to the public goods beneficiary
+    pub public_goods_earnings: YoctoNear,
 
     /// funds that have been deposited for boosting staking, but not yet staked
     pub collected_earnings: YoctoNear,
@@ -0,0 +1,19 @@
+use crate::{domain, interface::BlockTimeHeight};
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractPause {
+    pub reason: String,
+    /// when the contract was paused
+    pub paused: BlockTimeHeight,
+}
+
+impl From<domain::ContractPause> for ContractPause {
+    fn from(pause: domain::ContractPause) -> Self {
+        Self {
+            reason: pause.reason().to_string(),
+            paused: pause.paused().into(),
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::interface::model::lock::StakeLock;
-use crate::interface::{BlockHeight, ContractBalances, StorageUsage};
+use crate::interface::{BlockHeight, Config, ContractBalances, ContractPause, StorageUsage};
 use crate::{
     domain::RedeemLock,
     interface::{
@@ -8,11 +8,14 @@ use crate::{
     },
 };
 use near_sdk::{
-    json_types::U128,
+    json_types::{Base64VecU8, U128},
     serde::{Deserialize, Serialize},
     AccountId,
 };
 
+/// aggregate view of the contract state
+/// - kept for backward compatibility - the sectioned views ([StateBalances], [StateBatches],
+///   [StateConfig], [StateLocks]) are cheaper to query and should be preferred
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct ContractState {
@@ -40,6 +43,9 @@ pub struct ContractState {
     pub stake_batch_lock: Option<StakeLock>,
     pub redeem_stake_batch_lock: Option<RedeemLock>,
 
+    /// set while the contract is paused - see [pause](crate::interface::Operator::pause)
+    pub pause: Option<ContractPause>,
+
     pub balances: ContractBalances,
     /// total contract storage usage = [initial_storage_usage](ContractState::initial_storage_usage) + [storage_usage_growth](ContractState::storage_usage_growth)
     pub initial_storage_usage: StorageUsage,
@@ -47,3 +53,107 @@ pub struct ContractState {
     /// - contract storage should be covered by the account storage fees that are escrowed
     pub storage_usage_growth: StorageUsage,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StateBalances {
+    pub block: BlockTimeHeight,
+
+    pub registered_accounts_count: U128,
+
+    pub total_unstaked_near: TimestampedNearBalance,
+    pub total_stake_supply: TimestampedStakeBalance,
+
+    /// STAKE token value snapshot that was last taken when processing a batch
+    pub stake_token_value: StakeTokenValue,
+
+    pub balances: ContractBalances,
+    /// total contract storage usage = [initial_storage_usage](StateBalances::initial_storage_usage) + [storage_usage_growth](StateBalances::storage_usage_growth)
+    pub initial_storage_usage: StorageUsage,
+    /// how much storage usage has grown since the contract was deployed
+    pub storage_usage_growth: StorageUsage,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StateBatches {
+    pub block: BlockTimeHeight,
+
+    pub batch_id_sequence: BatchId,
+
+    pub stake_batch: Option<StakeBatch>,
+    pub next_stake_batch: Option<StakeBatch>,
+
+    pub redeem_stake_batch: Option<RedeemStakeBatch>,
+    pub next_redeem_stake_batch: Option<RedeemStakeBatch>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StateConfig {
+    pub block: BlockTimeHeight,
+    pub config_change_block_height: BlockHeight,
+
+    pub staking_pool_id: AccountId,
+
+    pub config: Config,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StateLocks {
+    pub block: BlockTimeHeight,
+
+    pub stake_batch_lock: Option<StakeLock>,
+    pub redeem_stake_batch_lock: Option<RedeemLock>,
+
+    /// set while the contract is paused - see [pause](crate::interface::Operator::pause)
+    pub pause: Option<ContractPause>,
+}
+
+/// export of the minimal mutable workflow state - see
+/// [export_workflow_state](crate::interface::Operator::export_workflow_state)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WorkflowStateExport {
+    pub batches: StateBatches,
+    pub locks: StateLocks,
+    pub unverified_stake_batches: Vec<BatchId>,
+    /// batch IDs of the pending receipts that are included in the export
+    pub pending_receipts: Vec<BatchId>,
+
+    /// Borsh serialized workflow state that is re-imported via
+    /// [import_workflow_state](crate::interface::Operator::import_workflow_state)
+    pub data: Base64VecU8,
+}
+
+impl ContractState {
+    /// assembles the aggregate view from its sections
+    pub fn from_sections(
+        balances: StateBalances,
+        batches: StateBatches,
+        config: StateConfig,
+        locks: StateLocks,
+    ) -> Self {
+        Self {
+            block: balances.block,
+            config_change_block_height: config.config_change_block_height,
+            staking_pool_id: config.staking_pool_id,
+            registered_accounts_count: balances.registered_accounts_count,
+            total_unstaked_near: balances.total_unstaked_near,
+            total_stake_supply: balances.total_stake_supply,
+            stake_token_value: balances.stake_token_value,
+            batch_id_sequence: batches.batch_id_sequence,
+            stake_batch: batches.stake_batch,
+            next_stake_batch: batches.next_stake_batch,
+            redeem_stake_batch: batches.redeem_stake_batch,
+            next_redeem_stake_batch: batches.next_redeem_stake_batch,
+            stake_batch_lock: locks.stake_batch_lock,
+            redeem_stake_batch_lock: locks.redeem_stake_batch_lock,
+            pause: locks.pause,
+            balances: balances.balances,
+            initial_storage_usage: balances.initial_storage_usage,
+            storage_usage_growth: balances.storage_usage_growth,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// identifies the code and state schema that a contract deployment runs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractVersion {
+    /// contract crate version
+    pub crate_version: String,
+    /// git commit the contract wasm was built from - "unknown" if it could not be determined at
+    /// build time
+    pub git_commit: String,
+    /// hash of the source files that define the persisted state, including the config - changes
+    /// whenever the state schema code changes, which means it may change without the state layout
+    /// changing, but is not changed by comment, formatting, or `#[cfg(test)]` code changes
+    pub state_schema_hash: String,
+    /// state layout version that the contract code was compiled against - see
+    /// [StateVersion](crate::domain::StateVersion)
+    pub state_version: u32,
+    /// state layout version that the contract state is persisted with - lags behind the
+    /// `state_version` until the contract state is migrated
+    pub persisted_state_version: u32,
+}
@@ -0,0 +1,17 @@
+use crate::interface::BatchId;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Stake and redeem batches share the same ID sequence, but the batch kind is encoded into the
+/// batch ID - the most significant bit is set for redeem stake batch IDs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CurrentBatchIds {
+    /// the last batch ID sequence number that was generated
+    pub batch_id_sequence: BatchId,
+
+    pub stake_batch: Option<BatchId>,
+    pub next_stake_batch: Option<BatchId>,
+
+    pub redeem_stake_batch: Option<BatchId>,
+    pub next_redeem_stake_batch: Option<BatchId>,
+}
@@ -0,0 +1,33 @@
+use crate::domain;
+use crate::interface::{BatchId, BlockTimeHeight, YoctoNear, YoctoStake};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExitPosition {
+    /// account that the NEAR is transferred to once the exit completes
+    pub beneficiary: AccountId,
+    /// STAKE that was redeemed when the exit was requested
+    pub redeemed: YoctoStake,
+    /// redeem stake batch that the STAKE was redeemed into
+    pub batch_id: Option<BatchId>,
+    pub requested: BlockTimeHeight,
+    /// NEAR that was paid for the exit position storage - transferred to the beneficiary when the
+    /// exit completes
+    pub storage_fee: YoctoNear,
+}
+
+impl From<domain::ExitPosition> for ExitPosition {
+    fn from(position: domain::ExitPosition) -> Self {
+        Self {
+            beneficiary: position.beneficiary().to_string(),
+            redeemed: position.redeemed().into(),
+            batch_id: position.batch_id().map(Into::into),
+            requested: position.requested().into(),
+            storage_fee: position.storage_fee().into(),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use crate::domain;
+use crate::interface::BlockTimeHeight;
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeatureRollout {
+    pub name: String,
+    /// percentage of accounts, in the range 0-100, that the feature is enabled for
+    pub percentage: u8,
+    /// accounts that the feature is explicitly enabled for, regardless of the rollout percentage
+    pub allowlist: Vec<AccountId>,
+    /// when the rollout was last changed
+    pub updated: BlockTimeHeight,
+}
+
+impl From<domain::FeatureRollout> for FeatureRollout {
+    fn from(rollout: domain::FeatureRollout) -> Self {
+        Self {
+            name: rollout.name().to_string(),
+            percentage: rollout.percentage(),
+            allowlist: rollout.allowlist().to_vec(),
+            updated: rollout.updated().into(),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{
+    domain,
+    interface::{BlockTimeHeight, YoctoNear},
+};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Snapshot of the contract financials that was recorded when earnings were distributed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FinancialsSnapshot {
+    /// when the earnings were distributed
+    pub block_time_height: BlockTimeHeight,
+    /// contract owner balance after the earnings were distributed
+    pub contract_owner_balance: YoctoNear,
+    /// earnings that were distributed to the contract owner
+    pub contract_owner_earnings: YoctoNear,
+    /// earnings that were distributed to user accounts via the liquidity pool
+    pub user_accounts_earnings: YoctoNear,
+    /// collected earnings that were included in the distribution
+    pub collected_earnings: YoctoNear,
+    /// total user accounts balance after the earnings were distributed
+    pub total_user_accounts_balance: YoctoNear,
+}
+
+impl From<domain::FinancialsSnapshot> for FinancialsSnapshot {
+    fn from(value: domain::FinancialsSnapshot) -> Self {
+        Self {
+            block_time_height: value.block_time_height().into(),
+            contract_owner_balance: value.contract_owner_balance().into(),
+            contract_owner_earnings: value.contract_owner_earnings().into(),
+            user_accounts_earnings: value.user_accounts_earnings().into(),
+            collected_earnings: value.collected_earnings().into(),
+            total_user_accounts_balance: value.total_user_accounts_balance().into(),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{
+    domain,
+    interface::{BlockHeight, Gas},
+};
+use near_sdk::{
+    json_types::U64,
+    serde::{Deserialize, Serialize},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakingPoolGasUsage {
+    pub method_name: String,
+    /// gas that is currently attached to the staking pool call or workflow callback
+    pub attached: Gas,
+    /// None if no gas usage has been recorded for the method
+    pub usage: Option<GasUsage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GasUsage {
+    pub samples: U64,
+    pub last_burnt: Gas,
+    /// max gas burned across all samples - used to auto-tune the attached gas
+    pub max_burnt: Gas,
+    /// when the last sample was recorded
+    pub block_height: BlockHeight,
+}
+
+impl From<domain::GasUsage> for GasUsage {
+    fn from(value: domain::GasUsage) -> Self {
+        Self {
+            samples: value.samples().into(),
+            last_burnt: value.last_burnt().into(),
+            max_burnt: value.max_burnt().into(),
+            block_height: value.block_height().into(),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{
+    domain::{self, Aggregate, AlarmWindow},
+    interface::BlockTimeHeight,
+};
+use near_sdk::{
+    json_types::U128,
+    serde::{Deserialize, Serialize},
+};
+
+/// contract health summary that is polled by monitoring systems
+/// - see [health](crate::interface::Operator::health)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Health {
+    pub block: BlockTimeHeight,
+    /// false if an aggregate alarm was raised within the current epoch
+    pub healthy: bool,
+    pub last_aggregate_alarm: Option<AggregateAlarm>,
+    /// number of aggregate alarms that have been raised over the lifetime of the contract
+    pub aggregate_alarms: u64,
+    pub stake_batch_locked: bool,
+    pub redeem_stake_batch_locked: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AggregateAlarm {
+    pub aggregate: Aggregate,
+    pub window: AlarmWindow,
+    /// aggregate value at the start of the window
+    pub before: U128,
+    pub after: U128,
+    /// the configured threshold that was exceeded
+    pub threshold_percentage: u8,
+    pub block: BlockTimeHeight,
+}
+
+impl From<domain::AggregateAlarm> for AggregateAlarm {
+    fn from(alarm: domain::AggregateAlarm) -> Self {
+        Self {
+            aggregate: alarm.aggregate(),
+            window: alarm.window(),
+            before: alarm.before().into(),
+            after: alarm.after().into(),
+            threshold_percentage: alarm.threshold_percentage(),
+            block: alarm.block_time_height().into(),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::interface::{EpochHeight, YoctoNear, YoctoStake};
+use near_sdk::{
+    json_types::U128,
+    serde::{Deserialize, Serialize},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IdleNearSweepStats {
+    /// NEAR in account balances that is awaiting withdrawal
+    pub total_idle_near: YoctoNear,
+    /// idle NEAR held by accounts that opted out - excluded from the sweep and the yield distribution
+    pub total_opted_out: YoctoNear,
+    /// idle NEAR that is currently swept into staking
+    pub swept: YoctoNear,
+    /// when the current sweep cycle started
+    pub swept_epoch: Option<EpochHeight>,
+    /// idle NEAR that is liquid, i.e., available for withdrawal
+    pub liquid: YoctoNear,
+    /// idle NEAR that is always kept liquid to fulfill withdrawals
+    pub liquidity_buffer: YoctoNear,
+    /// STAKE held by the sweep position for the current cycle
+    pub swept_stake: YoctoStake,
+    /// cumulative yield per 1 NEAR of idle balance, scaled by 10^24
+    pub yield_index: U128,
+    /// yield that has been distributed, but not yet credited to account NEAR balances
+    pub yield_pool: YoctoNear,
+    /// lifetime yield that was distributed
+    pub total_yield: YoctoNear,
+}
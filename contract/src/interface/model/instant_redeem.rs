@@ -0,0 +1,16 @@
+use crate::interface::{YoctoNear, YoctoStake};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Result of redeeming STAKE instantly against the NEAR liquidity pool - see
+/// [redeem_for_near_instant](crate::interface::StakingService::redeem_for_near_instant)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct InstantRedeem {
+    /// STAKE that was redeemed and burned
+    pub redeemed: YoctoStake,
+    /// NEAR that was credited to the account's NEAR balance
+    pub near: YoctoNear,
+    /// STAKE that could not be redeemed because the NEAR liquidity was insufficient - the STAKE
+    /// remains in the account
+    pub unfilled: YoctoStake,
+}
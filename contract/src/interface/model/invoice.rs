@@ -0,0 +1,58 @@
+use crate::domain;
+use crate::interface::{BlockTimeHeight, BlockTimestamp, YoctoNear, YoctoStake};
+use near_sdk::{
+    json_types::U64,
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Invoice {
+    /// payment reference
+    pub id: U64,
+    pub payee_id: AccountId,
+    pub amount: YoctoStake,
+    pub memo: String,
+    pub expires_at: BlockTimestamp,
+    /// NEAR that was paid by the payee for the invoice storage - refunded when the invoice is closed
+    pub storage_fee: YoctoNear,
+    pub created: BlockTimeHeight,
+    pub status: InvoiceStatus,
+    pub paid_by: Option<AccountId>,
+    pub paid: Option<BlockTimeHeight>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum InvoiceStatus {
+    Open,
+    Paid,
+    /// the invoice expired before it was paid
+    Expired,
+}
+
+impl Invoice {
+    /// `now` is used to determine whether an unpaid invoice has expired
+    pub fn from(id: u64, invoice: domain::Invoice, now: domain::BlockTimestamp) -> Self {
+        let status = if invoice.payment().is_some() {
+            InvoiceStatus::Paid
+        } else if invoice.is_expired(now) {
+            InvoiceStatus::Expired
+        } else {
+            InvoiceStatus::Open
+        };
+        Self {
+            id: id.into(),
+            payee_id: invoice.payee_id().to_string(),
+            amount: invoice.amount().into(),
+            memo: invoice.memo().to_string(),
+            expires_at: invoice.expires_at().into(),
+            storage_fee: invoice.storage_fee().into(),
+            created: invoice.created().into(),
+            status,
+            paid_by: invoice.payment().map(|payment| payment.payer_id.clone()),
+            paid: invoice.payment().map(|payment| payment.paid.into()),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::interface::{YoctoNear, YoctoStake};
+use near_sdk::{
+    json_types::U128,
+    serde::{Deserialize, Serialize},
+};
+
+/// Liquidity provider pool balances - see [LiquidityProviders](crate::interface::LiquidityProviders)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LiquidityPoolBalance {
+    /// NEAR that is available to serve claims and to be removed by liquidity providers
+    pub available: YoctoNear,
+    /// NEAR value of the redeemed STAKE that the pool holds in the redeem pipeline, i.e., STAKE
+    /// that was bought from claims pending withdrawal or swapped for NEAR - the NEAR is returned to
+    /// the pool once the unstaked NEAR has been withdrawn from the staking pool
+    pub pending_withdrawal: YoctoNear,
+    /// available + pending withdrawal
+    pub total: YoctoNear,
+    pub total_shares: U128,
+    /// total fees that have been earned by the liquidity providers
+    pub fees_earned: YoctoNear,
+    /// fee that is charged for serving claims pending withdrawal
+    pub fee_basis_points: u16,
+}
+
+/// Account's share of the liquidity provider pool
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LiquidityShares {
+    pub shares: U128,
+    /// NEAR value of the shares based on the pool's total NEAR value
+    pub near_value: YoctoNear,
+}
+
+/// STAKE to NEAR swap against the liquidity provider pool - see
+/// [swap_stake_for_near](crate::interface::LiquidityProviders::swap_stake_for_near)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeSwap {
+    pub stake: YoctoStake,
+    /// NEAR value of the STAKE at the current STAKE token value
+    pub near_value: YoctoNear,
+    /// fee that is earned by the liquidity providers
+    pub fee: YoctoNear,
+    pub fee_basis_points: u16,
+    /// NEAR that is credited to the account's NEAR balance, i.e., NEAR value minus the fee
+    pub near_out: YoctoNear,
+}
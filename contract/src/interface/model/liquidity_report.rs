@@ -0,0 +1,60 @@
+use crate::{
+    domain,
+    interface::{BlockHeight, YoctoNear},
+};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Explains how the NEAR liquidity pool balance changed over the lifetime of the contract
+/// - `near_liquidity_pool` = `total_inflows` - `total_outflows`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LiquidityReport {
+    pub near_liquidity_pool: YoctoNear,
+
+    /// unstaked NEAR that was withdrawn from the staking pool when stake batches were run
+    pub added_from_stake_batches: YoctoNear,
+    /// NEAR that was added to rebalance the STAKE token value when the staked NEAR balance dropped
+    pub added_from_stake_token_value_compensation: YoctoNear,
+    /// user account earnings that were distributed when stake batches were run
+    pub added_from_earnings: YoctoNear,
+    pub total_inflows: YoctoNear,
+
+    /// claimed by accounts against redeem stake batch receipts that were pending withdrawal
+    pub consumed_by_claims: YoctoNear,
+    /// used to clear redeem stake batches that were pending withdrawal from the staking pool
+    pub consumed_by_pending_withdrawals: YoctoNear,
+    /// drawn on to fulfill account NEAR withdrawals
+    pub consumed_by_withdrawals: YoctoNear,
+    /// staked back into the staking pool because liquidity was no longer needed
+    pub restaked: YoctoNear,
+    /// paid out to accounts that redeemed STAKE instantly against the liquidity
+    pub consumed_by_instant_redeems: YoctoNear,
+    pub total_outflows: YoctoNear,
+
+    pub block_height: BlockHeight,
+}
+
+impl LiquidityReport {
+    pub fn new(
+        near_liquidity_pool: domain::YoctoNear,
+        stats: domain::NearLiquidityStats,
+        block_height: domain::BlockHeight,
+    ) -> Self {
+        Self {
+            near_liquidity_pool: near_liquidity_pool.into(),
+            added_from_stake_batches: stats.added_from_stake_batches().into(),
+            added_from_stake_token_value_compensation: stats
+                .added_from_stake_token_value_compensation()
+                .into(),
+            added_from_earnings: stats.added_from_earnings().into(),
+            total_inflows: stats.total_inflows().into(),
+            consumed_by_claims: stats.consumed_by_claims().into(),
+            consumed_by_pending_withdrawals: stats.consumed_by_pending_withdrawals().into(),
+            consumed_by_withdrawals: stats.consumed_by_withdrawals().into(),
+            restaked: stats.restaked().into(),
+            consumed_by_instant_redeems: stats.consumed_by_instant_redeems().into(),
+            total_outflows: stats.total_outflows().into(),
+            block_height: block_height.into(),
+        }
+    }
+}
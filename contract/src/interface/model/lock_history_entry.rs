@@ -0,0 +1,29 @@
+use crate::{
+    domain::{self, LockKind},
+    interface::{BatchId, BlockHeight},
+};
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LockHistoryEntry {
+    pub kind: LockKind,
+    pub batch_id: Option<BatchId>,
+    pub acquired_block_height: BlockHeight,
+    /// None means the lock is still held
+    pub released_block_height: Option<BlockHeight>,
+    /// name of the contract method that released the lock
+    pub released_by: Option<String>,
+}
+
+impl From<domain::LockHistoryEntry> for LockHistoryEntry {
+    fn from(entry: domain::LockHistoryEntry) -> Self {
+        Self {
+            kind: entry.kind(),
+            batch_id: entry.batch_id().map(Into::into),
+            acquired_block_height: entry.acquired_block_height().into(),
+            released_block_height: entry.released_block_height().map(Into::into),
+            released_by: entry.released_by().map(str::to_string),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::domain;
+use crate::interface::{BlockTimeHeight, BlockTimestamp, YoctoNear, YoctoStake};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Lockup {
+    pub grantor_id: AccountId,
+    /// total STAKE that was granted
+    pub amount: YoctoStake,
+    /// vested STAKE that has been claimed into the beneficiary's account
+    pub claimed: YoctoStake,
+    /// vested STAKE that is available to be claimed
+    pub claimable: YoctoStake,
+    /// STAKE that has not yet vested
+    pub locked: YoctoStake,
+    /// NEAR value of the STAKE that has not yet been claimed, based on the current cached STAKE
+    /// token value
+    pub unclaimed_near_value: YoctoNear,
+    pub vesting_start: BlockTimestamp,
+    pub vesting_end: BlockTimestamp,
+    pub created: BlockTimeHeight,
+    /// NEAR that was paid by the grantor for the lockup storage - refunded to the grantor once the
+    /// lockup is fully claimed
+    pub storage_fee: YoctoNear,
+}
+
+impl Lockup {
+    pub fn new(
+        lockup: domain::Lockup,
+        now: domain::BlockTimestamp,
+        stake_token_value: domain::StakeTokenValue,
+    ) -> Self {
+        let unclaimed = lockup.amount() - lockup.claimed();
+        Self {
+            grantor_id: lockup.grantor_id().to_string(),
+            amount: lockup.amount().into(),
+            claimed: lockup.claimed().into(),
+            claimable: lockup.claimable(now).into(),
+            locked: lockup.locked(now).into(),
+            unclaimed_near_value: stake_token_value.stake_to_near(unclaimed).into(),
+            vesting_start: lockup.vesting_start().into(),
+            vesting_end: lockup.vesting_end().into(),
+            created: lockup.created().into(),
+            storage_fee: lockup.storage_fee().into(),
+        }
+    }
+}
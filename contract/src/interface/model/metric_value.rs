@@ -0,0 +1,17 @@
+use near_sdk::{
+    json_types::U128,
+    serde::{Deserialize, Serialize},
+};
+
+/// (name, value) pair that is serialized as a JSON array, e.g., `["stake_batches_run_total", "3"]`
+/// - counters are suffixed with `_total` - all other metrics are gauges
+/// - values are serialized as strings because yoctoNEAR amounts do not fit into JSON numbers
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MetricValue(pub String, pub U128);
+
+impl MetricValue {
+    pub fn new<T: Into<u128>>(name: &str, value: T) -> Self {
+        Self(name.to_string(), U128(value.into()))
+    }
+}
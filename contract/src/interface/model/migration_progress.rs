@@ -0,0 +1,31 @@
+use crate::domain;
+use crate::interface::BlockTimeHeight;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// progress of a chunked migration - see [migrate_chunk](crate::interface::Migrations::migrate_chunk)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MigrationProgress {
+    pub task_id: String,
+    /// next cursor position to migrate
+    pub cursor: u64,
+    /// cursor position where the migration completes (exclusive)
+    pub end: u64,
+    /// number of records that were migrated
+    pub migrated: u64,
+    pub started: BlockTimeHeight,
+    pub completed: Option<BlockTimeHeight>,
+}
+
+impl MigrationProgress {
+    pub fn new(task: domain::MigrationTask, cursor: domain::MigrationCursor) -> Self {
+        Self {
+            task_id: task.id().to_string(),
+            cursor: cursor.cursor(),
+            end: cursor.end(),
+            migrated: cursor.migrated(),
+            started: cursor.started().into(),
+            completed: cursor.completed().map(Into::into),
+        }
+    }
+}
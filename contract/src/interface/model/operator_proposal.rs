@@ -0,0 +1,85 @@
+use crate::domain;
+use crate::errors::illegal_state::OPERATOR_PROPOSAL_ACTION_SHOULD_BE_VALID;
+use crate::interface::{BlockTimeHeight, Config};
+use near_sdk::{
+    json_types::{Base64VecU8, ValidAccountId, U64},
+    serde::{Deserialize, Serialize},
+    serde_json, AccountId,
+};
+use std::convert::TryFrom;
+
+/// sensitive operator actions that require multiple operator confirmations - see
+/// [AccessControl](crate::interface::AccessControl)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum OperatorAction {
+    /// see [update_config](crate::interface::Operator::update_config) - the validation hash is
+    /// checked against the current config when proposed and again when executed
+    UpdateConfig {
+        config: Config,
+        validation_hash: Base64VecU8,
+    },
+    /// see [force_update_config](crate::interface::Operator::force_update_config), which is the only
+    /// config change that is not validated
+    ForceUpdateConfig { config: Config },
+    /// see [change_staking_pool](crate::interface::StakingPools::change_staking_pool)
+    ChangeStakingPool { account_id: ValidAccountId },
+    /// see [pause](crate::interface::Operator::pause)
+    Pause { reason: String },
+    /// see [resume](crate::interface::Operator::resume)
+    Resume,
+}
+
+impl From<&domain::OperatorAction> for OperatorAction {
+    fn from(action: &domain::OperatorAction) -> Self {
+        let config = |config: &str| -> Config {
+            serde_json::from_str(config).expect(OPERATOR_PROPOSAL_ACTION_SHOULD_BE_VALID)
+        };
+        let account_id = |account_id: &str| {
+            ValidAccountId::try_from(account_id).expect(OPERATOR_PROPOSAL_ACTION_SHOULD_BE_VALID)
+        };
+        match action {
+            domain::OperatorAction::UpdateConfig(json, validation_hash) => Self::UpdateConfig {
+                config: config(json),
+                validation_hash: Base64VecU8(validation_hash.clone()),
+            },
+            domain::OperatorAction::ForceUpdateConfig(json) => Self::ForceUpdateConfig {
+                config: config(json),
+            },
+            domain::OperatorAction::ChangeStakingPool(id) => Self::ChangeStakingPool {
+                account_id: account_id(id),
+            },
+            domain::OperatorAction::Pause(reason) => Self::Pause {
+                reason: reason.clone(),
+            },
+            domain::OperatorAction::Resume => Self::Resume,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OperatorProposal {
+    pub id: U64,
+    pub action: OperatorAction,
+    pub proposer: AccountId,
+    /// operator accounts that confirmed the proposal - includes the proposer
+    pub confirmations: Vec<AccountId>,
+    /// number of confirmations from current operator accounts that are required to execute the
+    /// proposal
+    pub required_confirmations: u8,
+    pub proposed: BlockTimeHeight,
+}
+
+impl OperatorProposal {
+    pub fn from(id: u64, proposal: &domain::OperatorProposal, required_confirmations: u8) -> Self {
+        Self {
+            id: id.into(),
+            action: proposal.action().into(),
+            proposer: proposal.proposer().to_string(),
+            confirmations: proposal.confirmations().to_vec(),
+            required_confirmations,
+            proposed: proposal.proposed().into(),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::domain;
+use crate::interface::{BlockTimeHeight, TokenAmount};
+use near_sdk::{
+    json_types::U64,
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingTransfer {
+    pub transfer_id: U64,
+    pub sender_id: AccountId,
+    pub receiver_id: AccountId,
+    pub amount: TokenAmount,
+    pub initiated: BlockTimeHeight,
+    /// true if the transfer has not been resolved within the configured
+    /// [stalled transfer timeout](crate::config::Config::stalled_transfer_timeout_blocks)
+    pub stalled: bool,
+}
+
+impl PendingTransfer {
+    pub fn new(transfer_id: u64, transfer: domain::PendingTransfer, stalled: bool) -> Self {
+        Self {
+            transfer_id: transfer_id.into(),
+            sender_id: transfer.sender_id().to_string(),
+            receiver_id: transfer.receiver_id().to_string(),
+            amount: transfer.amount().value().into(),
+            initiated: transfer.initiated().into(),
+            stalled,
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{
+    domain,
+    interface::{EpochHeight, YoctoStake},
+};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+/// staking pool preference that the account has signaled for an epoch
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PoolPreference {
+    pub pool_id: AccountId,
+    pub epoch_height: EpochHeight,
+    /// the account STAKE balance when the preference was signaled
+    pub weight: YoctoStake,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PoolPreferenceTally {
+    pub pool_id: AccountId,
+    /// total STAKE weight signaled for the pool
+    pub weight: YoctoStake,
+    /// number of accounts that signaled for the pool
+    pub accounts: u32,
+}
+
+impl From<&domain::PoolPreferenceTally> for PoolPreferenceTally {
+    fn from(tally: &domain::PoolPreferenceTally) -> Self {
+        Self {
+            pool_id: tally.pool_id.clone(),
+            weight: tally.weight.into(),
+            accounts: tally.accounts,
+        }
+    }
+}
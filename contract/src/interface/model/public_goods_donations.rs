@@ -0,0 +1,25 @@
+use crate::interface::{BlockTimeHeight, YoctoNear};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+/// Public goods donation accounting
+/// - see [public_goods_donations](crate::interface::ContractFinancials::public_goods_donations)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PublicGoodsDonations {
+    pub beneficiary_id: Option<AccountId>,
+    /// percentage of the user account earnings that is donated when earnings are distributed
+    /// - 0 means donations are disabled
+    pub percentage: u8,
+    /// share of the currently accumulated user account earnings that will be donated the next time
+    /// earnings are distributed
+    pub pending_donation: YoctoNear,
+    /// lifetime total that has been donated
+    pub total_donated: YoctoNear,
+    /// number of donations that have been made
+    pub donations: u64,
+    pub last_donation: Option<YoctoNear>,
+    pub last_donation_block_time_height: Option<BlockTimeHeight>,
+}
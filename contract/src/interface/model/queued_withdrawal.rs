@@ -0,0 +1,29 @@
+use crate::{
+    domain,
+    interface::{EpochHeight, YoctoNear},
+};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+/// NEAR withdrawal that overflowed the contract level per epoch withdrawal limit
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QueuedWithdrawal {
+    /// the account that the NEAR funds will be transferred to
+    pub recipient: AccountId,
+    pub amount: YoctoNear,
+    /// the withdrawal can be released starting with the next epoch
+    pub epoch_height: EpochHeight,
+}
+
+impl From<domain::QueuedWithdrawal> for QueuedWithdrawal {
+    fn from(withdrawal: domain::QueuedWithdrawal) -> Self {
+        Self {
+            recipient: withdrawal.recipient().to_string(),
+            amount: withdrawal.amount().into(),
+            epoch_height: withdrawal.epoch_height().into(),
+        }
+    }
+}
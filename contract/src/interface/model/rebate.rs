@@ -0,0 +1,99 @@
+use crate::{
+    domain,
+    interface::{EpochHeight, YoctoNear, YoctoStake},
+};
+use near_sdk::{
+    json_types::U128,
+    serde::{Deserialize, Serialize},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RebateTier {
+    /// min average STAKE balance over the rebate round required to qualify for the tier
+    pub min_average_stake: YoctoStake,
+    /// percentage of the account's proportional share of the round's rebate pool that is rebated
+    pub rebate_percentage: u8,
+}
+
+impl From<domain::RebateTier> for RebateTier {
+    fn from(tier: domain::RebateTier) -> Self {
+        Self {
+            min_average_stake: tier.min_average_stake.into(),
+            rebate_percentage: tier.rebate_percentage,
+        }
+    }
+}
+
+impl From<RebateTier> for domain::RebateTier {
+    fn from(tier: RebateTier) -> Self {
+        Self {
+            min_average_stake: tier.min_average_stake.value().into(),
+            rebate_percentage: tier.rebate_percentage,
+        }
+    }
+}
+
+/// current rebate round
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RebateProgram {
+    pub round_id: u64,
+    pub round_start_epoch_height: EpochHeight,
+    /// NEAR funds that will be allocated to the current round when it is closed
+    pub pool: YoctoNear,
+    /// total STAKE supply multiplied by the number of epochs it was held within the current round
+    pub total_stake_epochs: U128,
+    pub tiers: Vec<RebateTier>,
+}
+
+impl From<&domain::RebateProgram> for RebateProgram {
+    fn from(program: &domain::RebateProgram) -> Self {
+        Self {
+            round_id: program.round_id(),
+            round_start_epoch_height: program.round_start_epoch_height().into(),
+            pool: program.pool().into(),
+            total_stake_epochs: program.total_stake_epochs().into(),
+            tiers: program.tiers().iter().copied().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RebateRound {
+    pub id: u64,
+    pub start_epoch_height: EpochHeight,
+    pub end_epoch_height: EpochHeight,
+    pub total_stake_epochs: U128,
+    /// NEAR funds that were allocated to the round
+    pub amount: YoctoNear,
+    pub claimed: YoctoNear,
+    pub tiers: Vec<RebateTier>,
+}
+
+impl From<domain::RebateRound> for RebateRound {
+    fn from(round: domain::RebateRound) -> Self {
+        Self {
+            id: round.id(),
+            start_epoch_height: round.start_epoch_height().into(),
+            end_epoch_height: round.end_epoch_height().into(),
+            total_stake_epochs: round.total_stake_epochs().into(),
+            amount: round.amount().into(),
+            claimed: round.claimed().into(),
+            tiers: round.tiers().iter().copied().map(Into::into).collect(),
+        }
+    }
+}
+
+/// rebate that the account can claim for the most recently closed round
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountRebate {
+    pub round_id: u64,
+    /// account average STAKE balance over the round
+    pub average_stake: YoctoStake,
+    /// None means the average STAKE balance did not qualify for any tier
+    pub tier: Option<RebateTier>,
+    pub rebate: YoctoNear,
+}
@@ -0,0 +1,32 @@
+use crate::domain;
+use crate::interface::YoctoNear;
+use near_sdk::{
+    json_types::Base58PublicKey,
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+/// see [ReceiverAuthorization](crate::interface::ReceiverAuthorization)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReceiverAuthorizations {
+    /// the receiver allowlist is only enforced while delegate keys are registered
+    pub delegate_keys: Vec<Base58PublicKey>,
+    pub receivers: Vec<AccountId>,
+    /// storage fee that is escrowed for the account's receiver authorizations
+    pub storage_escrow: YoctoNear,
+}
+
+impl From<domain::ReceiverAuthorizations> for ReceiverAuthorizations {
+    fn from(value: domain::ReceiverAuthorizations) -> Self {
+        Self {
+            delegate_keys: value
+                .delegate_keys()
+                .iter()
+                .map(|key| Base58PublicKey(key.clone()))
+                .collect(),
+            receivers: value.receivers().to_vec(),
+            storage_escrow: value.storage_escrow().into(),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{
+    domain,
+    interface::{EpochHeight, YoctoStake},
+};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// View model for the limit that an account sets on itself for how much STAKE can be redeemed
+/// per epoch
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RedeemLimit {
+    pub max_redeem_per_epoch: YoctoStake,
+    /// how much STAKE has been redeemed within the current epoch
+    pub redeemed: YoctoStake,
+    /// how much more STAKE can be redeemed within the current epoch
+    pub available: YoctoStake,
+    /// raising the limit is time locked
+    pub pending_increase: Option<RedeemLimitIncrease>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RedeemLimitIncrease {
+    /// None means the limit will be removed
+    pub max_redeem_per_epoch: Option<YoctoStake>,
+    /// the limit increase takes effect starting with this epoch
+    pub effective_epoch_height: EpochHeight,
+}
+
+impl RedeemLimit {
+    pub fn from(limit: domain::RedeemLimit, epoch_height: domain::EpochHeight) -> Self {
+        Self {
+            max_redeem_per_epoch: limit.max_redeem_per_epoch().into(),
+            redeemed: limit.redeemed(epoch_height).into(),
+            available: limit.available(epoch_height).into(),
+            pending_increase: limit.pending_increase().map(Into::into),
+        }
+    }
+}
+
+impl From<domain::RedeemLimitIncrease> for RedeemLimitIncrease {
+    fn from(value: domain::RedeemLimitIncrease) -> Self {
+        Self {
+            max_redeem_per_epoch: value.max_redeem_per_epoch.map(Into::into),
+            effective_epoch_height: value.effective_epoch_height.into(),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use crate::interface::{BatchId, StakeTokenValue, YoctoNear, YoctoStake};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+/// Proof of an account's participation in a redeem stake batch
+/// - the schema is kept stable for external protocols, e.g., insurance protocols that underwrite
+///   withdrawal delay coverage on top of STAKE
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RedeemParticipation {
+    pub batch_id: BatchId,
+    pub account_id: AccountId,
+    /// STAKE that the account redeemed in the batch
+    pub redeemed_stake: YoctoStake,
+    /// the STAKE token value that the batch was redeemed at
+    /// - None if the batch has not yet been run
+    pub stake_token_value: Option<StakeTokenValue>,
+    /// NEAR that is owed to the account
+    /// - None if the batch has not yet been run
+    pub near: Option<YoctoNear>,
+    pub status: RedeemParticipationStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RedeemParticipationStatus {
+    /// the batch is waiting to be run, i.e., the STAKE has not yet been unstaked
+    Pending,
+    /// the unstaked NEAR funds are still locked in the staking pool
+    /// - funds may still be claimed against the contract NEAR liquidity, if available
+    PendingWithdrawal,
+    /// the NEAR funds can be claimed now
+    Claimable,
+}
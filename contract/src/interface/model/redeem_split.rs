@@ -0,0 +1,34 @@
+use crate::domain;
+use crate::interface::{BatchId, BlockTimeHeight, YoctoStake};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RedeemSplit {
+    pub account_id: AccountId,
+    /// STAKE that is still scheduled to be redeemed
+    pub remaining: YoctoStake,
+    /// number of redeem stake batches that the remaining STAKE is spread across
+    pub remaining_batches: u8,
+    /// STAKE that will be redeemed when the next redeem stake batch run starts
+    pub next_portion: YoctoStake,
+    /// the redeem stake batch that the last portion was redeemed into
+    pub last_batch_id: Option<BatchId>,
+    pub created: BlockTimeHeight,
+}
+
+impl From<domain::RedeemSplit> for RedeemSplit {
+    fn from(split: domain::RedeemSplit) -> Self {
+        Self {
+            account_id: split.account_id().to_string(),
+            remaining: split.remaining().into(),
+            remaining_batches: split.remaining_batches(),
+            next_portion: split.next_portion().into(),
+            last_batch_id: split.last_batch_id().map(Into::into),
+            created: split.created().into(),
+        }
+    }
+}
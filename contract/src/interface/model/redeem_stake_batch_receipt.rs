@@ -2,7 +2,10 @@ use crate::{
     domain,
     interface::{StakeTokenValue, YoctoNear, YoctoStake},
 };
-use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
@@ -17,6 +20,9 @@ pub struct RedeemStakeBatchReceipt {
     pub stake_token_value: StakeTokenValue,
     /// the NEAR value of the redeemed STAKE computed from [stake_token_value](RedeemStakeBatchReceipt::stake_token_value)
     pub redeemed_stake_value: YoctoNear,
+
+    /// the staking pool that the batch was unstaked from
+    pub staking_pool_id: AccountId,
 }
 
 impl From<domain::RedeemStakeBatchReceipt> for RedeemStakeBatchReceipt {
@@ -25,6 +31,7 @@ impl From<domain::RedeemStakeBatchReceipt> for RedeemStakeBatchReceipt {
             redeemed_stake: receipt.redeemed_stake().into(),
             stake_token_value: receipt.stake_token_value().into(),
             redeemed_stake_value: receipt.stake_near_value().into(),
+            staking_pool_id: receipt.staking_pool_id().to_string(),
         }
     }
 }
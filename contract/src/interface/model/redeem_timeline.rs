@@ -0,0 +1,40 @@
+use crate::interface::{BatchId, BlockHeight, BlockTimeHeight, EpochHeight};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// stages that a redeem stake batch goes through until the redeemed NEAR can be claimed
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RedeemTimelineStage {
+    /// the batch STAKE is unstaked from the staking pool
+    Unstake,
+    /// the unstaked NEAR is unlocked by the staking pool and withdrawn
+    Withdrawal,
+    /// the redeemed NEAR can be claimed by the accounts that contributed to the batch
+    Claim,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RedeemTimelineStep {
+    pub stage: RedeemTimelineStage,
+    /// the epoch that the stage was reached in, or is expected to be reached in
+    pub epoch_height: EpochHeight,
+    /// estimated based on the epoch length - except for the unstake stage once the batch has been
+    /// unstaked, which is the actual block height
+    pub block_height: BlockHeight,
+    /// true if the stage has been reached
+    pub reached: bool,
+}
+
+/// Expected chain of events for a redeem stake batch, based on the current epoch and contract
+/// state, which UIs can use to show progress for a pending redemption.
+/// - see [redeem_timeline](crate::interface::StakingService::redeem_timeline)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RedeemTimeline {
+    pub batch_id: BatchId,
+    /// the steps in stage order
+    pub steps: Vec<RedeemTimelineStep>,
+    /// when the timeline was computed
+    pub block_time_height: BlockTimeHeight,
+}
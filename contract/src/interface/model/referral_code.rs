@@ -0,0 +1,43 @@
+use crate::domain;
+use crate::interface::{BlockTimeHeight, EpochHeight, YoctoNear};
+use near_sdk::{
+    json_types::U64,
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReferralCode {
+    pub code: String,
+    /// partner account that deposits made with the code are attributed to
+    pub account_id: AccountId,
+    pub registered: BlockTimeHeight,
+    /// the code can no longer be used for deposits starting with this epoch, and may be reclaimed
+    /// by another account
+    pub expires_at: EpochHeight,
+    pub expired: bool,
+    /// NEAR that was paid by the partner for the code storage - refunded when the code is
+    /// unregistered or reclaimed after it expires
+    pub storage_fee: YoctoNear,
+    /// number of deposits that were attributed to the code
+    pub deposits: U64,
+    /// total NEAR deposited with the code
+    pub referred_near: YoctoNear,
+}
+
+impl ReferralCode {
+    /// `epoch_height` is used to determine whether the code has expired
+    pub fn from(code: domain::ReferralCode, epoch_height: domain::EpochHeight) -> Self {
+        Self {
+            code: code.code().to_string(),
+            account_id: code.account_id().to_string(),
+            registered: code.registered().into(),
+            expires_at: code.expires_at().into(),
+            expired: code.is_expired(epoch_height),
+            storage_fee: code.storage_fee().into(),
+            deposits: code.deposits().into(),
+            referred_near: code.referred_near().into(),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use crate::domain;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Fraction of the staking rewards that the staking pool keeps as its fee
+/// - matches the JSON returned by the staking pool `get_reward_fee_fraction` view method
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RewardFeeFraction {
+    pub numerator: u32,
+    pub denominator: u32,
+}
+
+impl From<domain::RewardFeeFraction> for RewardFeeFraction {
+    fn from(value: domain::RewardFeeFraction) -> Self {
+        Self {
+            numerator: value.numerator(),
+            denominator: value.denominator(),
+        }
+    }
+}
+
+impl From<RewardFeeFraction> for domain::RewardFeeFraction {
+    fn from(value: RewardFeeFraction) -> Self {
+        domain::RewardFeeFraction::new(value.numerator, value.denominator)
+    }
+}
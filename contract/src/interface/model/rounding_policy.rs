@@ -0,0 +1,45 @@
+use crate::domain;
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+impl From<domain::Rounding> for Rounding {
+    fn from(value: domain::Rounding) -> Self {
+        match value {
+            domain::Rounding::Down => Self::Down,
+            domain::Rounding::Up => Self::Up,
+        }
+    }
+}
+
+/// Rounding directions that the contract applies when the exact NEAR / STAKE conversion result
+/// is fractional
+/// - conversions are computed using 256-bit math, thus the only error is the rounding, which is
+///   at most 1 yocto per conversion
+/// - converting NEAR -> STAKE -> NEAR never yields more NEAR than was converted
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoundingPolicy {
+    /// applied when STAKE is minted for NEAR
+    pub near_to_stake: Rounding,
+    /// applied when STAKE is redeemed for NEAR
+    pub stake_to_near: Rounding,
+    /// applied to the staked NEAR compensation that prevents the STAKE token value from ticking
+    /// down
+    pub stake_token_value_compensation: Rounding,
+}
+
+impl RoundingPolicy {
+    pub fn current() -> Self {
+        Self {
+            near_to_stake: domain::NEAR_TO_STAKE_ROUNDING.into(),
+            stake_to_near: domain::STAKE_TO_NEAR_ROUNDING.into(),
+            stake_token_value_compensation: domain::STAKE_TOKEN_VALUE_COMPENSATION_ROUNDING.into(),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use crate::interface::{BlockTimeHeight, RewardFeeFraction, YoctoNear, YoctoStake};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Quote for entering STAKE by minting or exiting STAKE by redeeming through the contract, which
+/// aggregators can compare against secondary market routes
+/// - see [entry_quote](crate::interface::StakingService::entry_quote) and
+///   [exit_quote](crate::interface::StakingService::exit_quote)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RouteQuote {
+    /// NEAR that is paid on entry or received on exit
+    pub near: YoctoNear,
+    /// STAKE that is received on entry or paid on exit
+    pub stake: YoctoStake,
+    /// NEAR value of 1 STAKE that the quote is based on
+    pub rate: YoctoNear,
+    /// when the STAKE token value that was used for the quote was computed
+    /// - if it was not computed within the current epoch, then it is stale
+    pub stake_token_value_block_time_height: BlockTimeHeight,
+    /// fee charged by the contract - minting and redeeming STAKE is free
+    pub fee: YoctoNear,
+    /// staking pool reward fee, which is applied to staking rewards and is thus priced into the
+    /// STAKE token value over time
+    pub staking_pool_reward_fee: Option<RewardFeeFraction>,
+    /// number of epochs before the output can be claimed
+    pub delay_epochs: u64,
+    /// NEAR liquidity that is available to fulfill exits without waiting for the unstaked NEAR to
+    /// be withdrawn from the staking pool
+    pub instant_liquidity: YoctoNear,
+    /// true if the output can be claimed without any epoch delay
+    pub instant: bool,
+    /// false if the route is currently not executable for the amount, e.g., the deposit is below
+    /// the min required deposit
+    pub executable: bool,
+}
@@ -0,0 +1,72 @@
+use crate::interface::{BatchReservation, RedeemSplit, SwapIntent, YoctoNear, YoctoStake};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+/// identifies a behavior that acts on the account's balances automatically
+/// - each account can have at most 1 scheduled action of each kind, thus the kind identifies the
+///   action
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ScheduledActionId {
+    /// redeemed NEAR is forwarded to another STAKE contract deployment when receipts are claimed
+    RedeemForwarding,
+    /// escrowed NEAR is deposited into the next stake batch run
+    BatchReservation,
+    /// escrowed STAKE is swapped when the swap intent is filled
+    SwapIntent,
+    /// STAKE is redeemed in portions across successive redeem stake batch runs
+    RedeemSplit,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScheduledAction {
+    pub id: ScheduledActionId,
+    /// account that the action sends funds to, e.g., the redeem forwarding target or the swap
+    /// intent target STAKE contract
+    pub target: Option<AccountId>,
+    /// NEAR that is held by the action
+    pub near_amount: Option<YoctoNear>,
+    /// STAKE that is held by the action
+    pub stake_amount: Option<YoctoStake>,
+}
+
+impl ScheduledAction {
+    pub fn redeem_forwarding(target: AccountId) -> Self {
+        Self {
+            id: ScheduledActionId::RedeemForwarding,
+            target: Some(target),
+            near_amount: None,
+            stake_amount: None,
+        }
+    }
+
+    pub fn batch_reservation(reservation: BatchReservation) -> Self {
+        Self {
+            id: ScheduledActionId::BatchReservation,
+            target: None,
+            near_amount: Some(reservation.amount),
+            stake_amount: None,
+        }
+    }
+
+    pub fn swap_intent(intent: SwapIntent) -> Self {
+        Self {
+            id: ScheduledActionId::SwapIntent,
+            target: Some(intent.target_stake_contract),
+            near_amount: None,
+            stake_amount: Some(intent.amount),
+        }
+    }
+
+    pub fn redeem_split(split: RedeemSplit) -> Self {
+        Self {
+            id: ScheduledActionId::RedeemSplit,
+            target: None,
+            near_amount: None,
+            stake_amount: Some(split.remaining),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::interface::{BatchId, BlockTimeHeight, EpochHeight, YoctoNear, YoctoStake};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Dry run of depositing NEAR to stake - see [simulate_deposit](crate::interface::StakingService::simulate_deposit)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DepositSimulation {
+    pub near: YoctoNear,
+    /// STAKE that the NEAR would be converted to using the cached STAKE token value
+    /// - the STAKE is actually minted using the STAKE token value computed when the batch is run
+    pub stake: YoctoStake,
+    /// stake batch that the NEAR would be deposited into
+    /// - if the batch does not exist yet, then this is the ID that would be assigned to the new batch
+    pub batch_id: BatchId,
+    /// when the STAKE token value that was used for the simulation was computed
+    pub stake_token_value_block_time_height: BlockTimeHeight,
+    /// error messages for the checks that the deposit would fail - empty if the deposit is valid
+    pub violations: Vec<String>,
+}
+
+/// Dry run of redeeming STAKE - see [simulate_redeem](crate::interface::StakingService::simulate_redeem)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RedeemSimulation {
+    pub stake: YoctoStake,
+    /// NEAR that the STAKE would be converted to using the cached STAKE token value
+    /// - the NEAR is actually computed using the STAKE token value when the batch is run
+    pub near: YoctoNear,
+    /// redeem stake batch that the STAKE would be added to
+    /// - if the batch does not exist yet, then this is the ID that would be assigned to the new batch
+    pub batch_id: BatchId,
+    /// estimated epoch when the unstaked NEAR is available for withdrawal from the staking pool
+    /// - NEAR liquidity may enable the redeemed NEAR to be claimed earlier
+    pub available_epoch_height: EpochHeight,
+    /// when the STAKE token value that was used for the simulation was computed
+    pub stake_token_value_block_time_height: BlockTimeHeight,
+    /// error messages for the checks that the redeem would fail - empty if the redeem is valid
+    pub violations: Vec<String>,
+}
@@ -1,7 +1,10 @@
 use crate::interface::{
     RedeemStakeBatch, StakeBatch, TimestampedNearBalance, TimestampedStakeBalance, YoctoNear,
 };
-use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
 
 /// View model for a registered account with the contract
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -38,3 +41,11 @@ pub struct StakeAccount {
     /// returns None if there is currently no NEAR liquidity to withdraw against
     pub contract_near_liquidity: Option<YoctoNear>,
 }
+
+/// [StakeAccount] along with the account ID - see [get_accounts](crate::interface::AccountManagement::get_accounts)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeAccountEntry {
+    pub account_id: AccountId,
+    pub account: StakeAccount,
+}
@@ -3,7 +3,10 @@ use crate::{
     domain,
     interface::{StakeTokenValue, YoctoNear},
 };
-use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
@@ -18,6 +21,9 @@ pub struct StakeBatchReceipt {
     /// - is used to compute the amount of STAKE tokens to issue to the account based on the amount
     ///   of NEAR that was staked
     pub stake_token_value: StakeTokenValue,
+
+    /// the staking pool that the batch was staked with
+    pub staking_pool_id: AccountId,
 }
 
 impl From<domain::StakeBatchReceipt> for StakeBatchReceipt {
@@ -29,6 +35,7 @@ impl From<domain::StakeBatchReceipt> for StakeBatchReceipt {
                 .stake_token_value()
                 .near_to_stake(receipt.staked_near())
                 .into(),
+            staking_pool_id: receipt.staking_pool_id().to_string(),
         }
     }
 }
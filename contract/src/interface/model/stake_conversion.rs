@@ -0,0 +1,13 @@
+use crate::interface::{BlockTimeHeight, YoctoNear, YoctoStake};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// NEAR to STAKE conversion computed using the contract's cached STAKE token value
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeConversion {
+    pub near: YoctoNear,
+    pub stake: YoctoStake,
+    /// when the STAKE token value that was used for the conversion was computed
+    /// - if it was not computed within the current epoch, then it is stale
+    pub stake_token_value_block_time_height: BlockTimeHeight,
+}
@@ -0,0 +1,20 @@
+use crate::interface::{EpochHeight, YoctoStake};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// STAKE supply changes - used by supply monitors to detect anomalies, e.g., unexpected mint spikes
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeSupplyStats {
+    /// current epoch
+    pub epoch_height: EpochHeight,
+    /// STAKE minted within the current epoch
+    pub stake_minted_this_epoch: YoctoStake,
+    /// STAKE burned within the current epoch
+    pub stake_burned_this_epoch: YoctoStake,
+    /// STAKE minted over the lifetime of the contract
+    pub total_stake_minted: YoctoStake,
+    /// STAKE burned over the lifetime of the contract
+    pub total_stake_burned: YoctoStake,
+    /// total STAKE supply in circulation
+    pub total_stake_supply: YoctoStake,
+}
@@ -3,7 +3,7 @@ use crate::interface::{BlockTimeHeight, YoctoNear, YoctoStake};
 use crate::near::YOCTO;
 use near_sdk::serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(crate = "near_sdk::serde")]
 pub struct StakeTokenValue {
     pub block_time_height: BlockTimeHeight,
@@ -0,0 +1,17 @@
+use crate::interface::{EpochHeight, YoctoNear};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Time weighted average STAKE token value computed from the per epoch STAKE token value checkpoints
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeTokenValueTwap {
+    /// time weighted average value of 1 STAKE token in NEAR
+    pub value: YoctoNear,
+    /// first epoch included in the average
+    pub from_epoch_height: EpochHeight,
+    /// last epoch included in the average, i.e., the current epoch
+    pub to_epoch_height: EpochHeight,
+    /// number of epochs that were averaged
+    /// - may be less than the requested window if checkpoints do not go back far enough
+    pub epochs: u32,
+}
@@ -0,0 +1,25 @@
+use crate::interface::{EpochHeight, RewardFeeFraction};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakingPoolInfo {
+    pub staking_pool_id: AccountId,
+    /// false if the staking pool was detected to be unavailable, e.g., the staking pool is paused
+    /// and is rejecting `deposit_and_stake` requests
+    pub available: bool,
+    /// if the staking pool is unavailable, then stake batches will be retried once this epoch is
+    /// reached
+    pub unavailable_until: Option<EpochHeight>,
+    /// staking pool reward fee that was last cached by [refresh_staking_pool_reward_fee](crate::interface::StakingService::refresh_staking_pool_reward_fee)
+    /// - staking rewards are net of this fee, i.e., when the staking pool changes its fee, the
+    ///   STAKE token yield changes accordingly
+    /// - None if the fee has not yet been retrieved from the staking pool
+    pub reward_fee_fraction: Option<RewardFeeFraction>,
+    /// false while NEAR cannot be staked with the staking pool because the staking pool whitelist
+    /// contract has not confirmed the staking pool - see [StakingPools](crate::interface::StakingPools)
+    pub whitelisted: bool,
+}
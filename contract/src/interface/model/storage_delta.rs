@@ -0,0 +1,29 @@
+use crate::{
+    domain,
+    interface::{BlockHeight, StorageUsage},
+};
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageDelta {
+    pub storage_usage_before: StorageUsage,
+    pub storage_usage_after: StorageUsage,
+    /// storage usage delta in bytes - negative when storage was freed
+    pub delta: i64,
+    /// number of versioned records, e.g., accounts and batch receipts, that were written or removed
+    pub records_touched: u32,
+    pub block_height: BlockHeight,
+}
+
+impl From<domain::StorageDelta> for StorageDelta {
+    fn from(value: domain::StorageDelta) -> Self {
+        Self {
+            storage_usage_before: value.storage_usage_before().into(),
+            storage_usage_after: value.storage_usage_after().into(),
+            delta: value.delta(),
+            records_touched: value.records_touched(),
+            block_height: value.block_height().into(),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use crate::interface::{EpochHeight, YoctoStake};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapIntent {
+    /// STAKE contract deployment whose STAKE the account wants in exchange
+    pub target_stake_contract: AccountId,
+    /// escrowed STAKE that is available to be filled
+    pub amount: YoctoStake,
+    /// fills that are awaiting settlement on the target STAKE contract
+    pub pending_fills: u32,
+    pub posted_epoch_height: EpochHeight,
+}
+
+/// [TransferCallMessage](crate::interface::TransferCallMessage) that is used to fill a swap intent
+/// via `ft_transfer_call` on the target STAKE contract, e.g.
+///
+/// ```json
+/// {"intent_owner_id": "alice.near"}
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FillSwapIntentMessage {
+    pub intent_owner_id: AccountId,
+}
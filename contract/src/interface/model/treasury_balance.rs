@@ -0,0 +1,23 @@
+use crate::interface::{YoctoNear, YoctoStake};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+/// Treasury accounting
+/// - see [treasury_balance](crate::interface::Treasury::treasury_balance)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TreasuryBalance {
+    /// the treasury account, which is the contract account
+    pub account_id: AccountId,
+    /// protocol fee in basis points that is skimmed from the distributed earnings
+    /// - 0 means the protocol fee is disabled
+    pub protocol_fee_basis_points: u16,
+    /// treasury owned STAKE, including unclaimed stake batch receipts
+    pub stake: YoctoStake,
+    /// protocol fees that have been deposited into the treasury stake batches, but not yet staked
+    pub pending_fees: YoctoNear,
+    /// lifetime total protocol fees that have been collected
+    pub total_fees_collected: YoctoNear,
+}
@@ -0,0 +1,45 @@
+use crate::{
+    domain::{self, Workflow},
+    interface::BlockHeight,
+};
+use near_sdk::{
+    json_types::U64,
+    serde::{Deserialize, Serialize},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WorkflowBackoffStatus {
+    pub workflow: Workflow,
+    pub consecutive_failures: u32,
+    /// block height at which the pending workflow attempt was started
+    /// - None means the outcome of the last attempt was recorded
+    pub pending_since: Option<BlockHeight>,
+    pub last_failure: Option<BlockHeight>,
+    /// cooldown that applies after the last failure
+    pub cooldown_blocks: U64,
+    /// None means the workflow is not cooling down
+    pub retry_at: Option<BlockHeight>,
+}
+
+impl WorkflowBackoffStatus {
+    pub fn new(
+        workflow: Workflow,
+        backoff: &domain::WorkflowBackoff,
+        base_blocks: u64,
+        max_blocks: u64,
+        now: domain::BlockHeight,
+    ) -> Self {
+        Self {
+            workflow,
+            consecutive_failures: backoff.consecutive_failures(),
+            pending_since: backoff.pending_since().map(Into::into),
+            last_failure: backoff.last_failure().map(Into::into),
+            cooldown_blocks: backoff.cooldown_blocks(base_blocks, max_blocks).into(),
+            retry_at: backoff
+                .retry_at(base_blocks, max_blocks)
+                .filter(|retry_at| now < *retry_at)
+                .map(Into::into),
+        }
+    }
+}
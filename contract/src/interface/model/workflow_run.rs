@@ -0,0 +1,20 @@
+use crate::interface::BatchId;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// describes the workflow that was kicked off by
+/// [run_pending_workflows](crate::interface::StakingService::run_pending_workflows)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum WorkflowRun {
+    /// the stake batch workflow was run - see [stake](crate::interface::StakingService::stake)
+    /// - if the batch was already staked, then the staked batch was processed
+    Stake(BatchId),
+    /// the redeem stake batch was submitted to the staking pool to be unstaked - see
+    /// [unstake](crate::interface::StakingService::unstake)
+    Unstake(BatchId),
+    /// the unstaked NEAR for the redeem stake batch that is pending withdrawal is being withdrawn
+    /// from the staking pool
+    Withdraw(BatchId),
+    /// no workflow is runnable
+    Idle,
+}
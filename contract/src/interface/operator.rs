@@ -1,5 +1,16 @@
-use crate::interface::{model::contract_state::ContractState, Config};
-use near_sdk::AccountId;
+use crate::domain::Workflow;
+use crate::interface::{
+    model::contract_state::{
+        ContractState, StateBalances, StateBatches, StateConfig, StateLocks, WorkflowStateExport,
+    },
+    BatchRunStatus, Config, ConfigValidation, ContractVersion, Gas, GasConfig, Health,
+    InitConfigPreset, LockHistoryEntry, MetricValue, StakeTokenValue, StakingPoolGasUsage,
+    StorageDelta, WorkflowBackoffStatus, YoctoNear,
+};
+use near_sdk::{
+    json_types::{Base64VecU8, ValidAccountId},
+    AccountId, Promise,
+};
 
 /// provides functions to support DevOps
 pub trait Operator {
@@ -7,36 +18,112 @@ pub trait Operator {
 
     /// returns the contract's state
     /// - useful for monitoring and debugging
+    /// - aggregates all of the state sections - kept for backward compatibility
+    ///
+    /// NOTE: prefer querying the sectioned views, which are cheaper to serve and less likely to
+    ///       time out on congested RPC nodes
     fn contract_state(&self) -> ContractState;
 
+    /// returns the contract's NEAR and STAKE balances, the STAKE token value, and storage usage
+    fn state_balances(&self) -> StateBalances;
+
+    /// returns the contract's stake and redeem stake batches
+    fn state_batches(&self) -> StateBatches;
+
+    /// returns the contract's config along with the staking pool it is linked to
+    fn state_config(&self) -> StateConfig;
+
+    /// returns the contract's stake and redeem stake batch locks along with the pause state
+    fn state_locks(&self) -> StateLocks;
+
     fn config(&self) -> Config;
 
+    /// lists the config presets that the contract can be initialized with along with the full
+    /// config that each preset expands into - see [Contract::new](crate::Contract::new)
+    fn init_config_preset(&self) -> Vec<InitConfigPreset>;
+
     /// resets the config to default settings
+    /// - the default config must have been validated via [validate_config](Operator::validate_config)
+    ///   against the current config
+    /// - while operator confirmations are required, the reset is proposed as an
+    ///   [UpdateConfig](crate::interface::OperatorAction::UpdateConfig) action with the default config
     ///
     /// ## Panics
-    /// if not invoked by the operator account
-    fn reset_config_default(&mut self) -> Config;
+    /// - if not invoked by the operator account
+    /// - if more than 1 operator confirmation is [required](crate::interface::AccessControl::required_confirmations)
+    /// - if the validation hash does not match the default config, or the config was changed since
+    ///   the default config was validated
+    fn reset_config_default(&mut self, validation_hash: Base64VecU8) -> Config;
+
+    /// Dry runs the config changes against the current config without applying them. The findings
+    /// report each field that fails validation, e.g., gas values that are out of bounds, workflow
+    /// gas sums that exceed 300 TGas, and alarm thresholds that are out of order.
+    ///
+    /// If the config is valid, then the returned validation hash must be passed to
+    /// [update_config](Operator::update_config) to apply the config changes.
+    fn validate_config(&self, config: Config) -> ConfigValidation;
 
     /// merges in config changes
-    /// - performs basic validation to prevent mis-configurations
+    /// - the config changes must have been validated via [validate_config](Operator::validate_config)
+    ///   against the current config, which guards against live workflows being bricked by a bad
+    ///   config, e.g., a bad gas schedule
     ///
     /// NOTE: you can [force a config change](Operator::force_update_config) if the validation logic
     ///       is flawed or becomes invalidated because of NEAR platform changes in the future.
     ///
     /// ## Panics
     /// - if not invoked by the operator account
+    /// - if more than 1 operator confirmation is [required](crate::interface::AccessControl::required_confirmations)
+    /// - if the validation hash does not match the config changes, or the config was changed since
+    ///   the config changes were validated
     /// - if config validation fails
-    fn update_config(&mut self, config: Config) -> Config;
+    fn update_config(&mut self, config: Config, validation_hash: Base64VecU8) -> Config;
 
     /// merges in config changes with no validations run
     /// - the purpose to allow config to be updated without validation is in case the assumptions
     ///   made for validation prove to be wrong later on, e.g, gas usage or storage fees may change
     ///   that require config changes that would cause validation to fail
+    /// - this is the one escape hatch - every other config change, including the
+    ///   [operator proposals](crate::interface::OperatorAction::UpdateConfig), requires a validation
+    ///   hash from [validate_config](Operator::validate_config)
     ///
     /// ## Panics
     /// - if not invoked by the operator account
+    /// - if more than 1 operator confirmation is [required](crate::interface::AccessControl::required_confirmations)
     fn force_update_config(&mut self, config: Config) -> Config;
 
+    /// returns the gas that is allocated to the staking pool cross-contract calls and the workflow
+    /// callbacks
+    fn gas_config(&self) -> GasConfig;
+
+    /// Merges in gas config changes, which enables the gas allocations, e.g., for the staking pool
+    /// `deposit_and_stake`, `get_account`, `unstake`, and `withdraw_all` calls and the callbacks,
+    /// to be adjusted when staking pool gas costs change without redeploying the contract.
+    /// - the staking pool `withdraw_all` call is configured via `staking_pool.withdraw`
+    /// - fields that are None are left unchanged, i.e., only the gas values that are specified
+    ///   are updated
+    /// - the changes are validated the same way as [update_config](Operator::update_config), i.e.,
+    ///   each gas value must be within its bounds, and the gas for each batch workflow must fit
+    ///   within the max prepaid gas for a transaction
+    /// - the validation hash is returned by [validate_config](Operator::validate_config) for a
+    ///   [Config] that only sets the `gas_config`
+    /// - while operator confirmations are required, the gas config changes are proposed as an
+    ///   [UpdateConfig](crate::interface::OperatorAction::UpdateConfig) action with the same config
+    ///
+    /// Returns the updated gas config.
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if more than 1 operator confirmation is [required](crate::interface::AccessControl::required_confirmations)
+    /// - if the validation hash does not match the gas config changes, or the config was changed
+    ///   since the gas config changes were validated
+    /// - if gas config validation fails
+    fn update_gas_config(
+        &mut self,
+        gas_config: GasConfig,
+        validation_hash: Base64VecU8,
+    ) -> GasConfig;
+
     /// unlocks the contract if the [StakeLock](crate::domain::StakeLock) state is
     /// [StakeLock::Staking](crate::domain::StakeLock::Staking)
     ///
@@ -49,4 +136,339 @@ pub trait Operator {
     /// ## Panics
     /// if not invoked by self as callback or the operator account
     fn clear_redeem_lock(&mut self);
+
+    /// Releases a workflow lock whose callback never fired, e.g., because the callback ran out of
+    /// gas. A lock is stale once it has been held for at least
+    /// [stale_lock_timeout_epochs](crate::config::Config::stale_lock_timeout_epochs), and only if
+    /// the workflow was blocked before the funds moved, i.e.,
+    /// [StakeLock::Staking](crate::domain::StakeLock::Staking),
+    /// [StakeLock::RefreshingStakeTokenValue](crate::domain::StakeLock::RefreshingStakeTokenValue),
+    /// or [RedeemLock::Unstaking](crate::domain::RedeemLock::Unstaking). The stake lock is checked
+    /// first.
+    ///
+    /// The staking pool account is fetched in order to verify that no funds are in flight before the
+    /// lock is released - if the staking pool balances show that the funds were transferred, then
+    /// the lock is left in place for the operator to resolve and `StaleLockFundsInFlight` is logged.
+    /// The funds are deemed transferred once the staking pool balance moved by more than half of the
+    /// batch amount relative to the balances from before the batch was run, which absorbs the
+    /// staking pool share rounding.
+    ///
+    /// Resolves to true if the lock was released.
+    ///
+    /// NOTE: this is a maintenance call that anyone can invoke, e.g., keepers
+    ///
+    /// ## Panics
+    /// if there is no stale lock
+    fn release_stale_lock(&mut self) -> Promise;
+
+    /// Emergency switch that pauses all methods that move funds, i.e., deposit, stake, redeem,
+    /// unstake, transfers, and withdrawals. View methods remain live, and in-flight workflow
+    /// callbacks are allowed to complete. The pause state and reason are exposed via
+    /// [contract_state](Operator::contract_state).
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if more than 1 operator confirmation is [required](crate::interface::AccessControl::required_confirmations)
+    /// - if the contract is already paused
+    /// - if the reason is blank or exceeds [MAX_PAUSE_REASON_LEN](crate::domain::MAX_PAUSE_REASON_LEN)
+    ///   bytes
+    fn pause(&mut self, reason: String);
+
+    /// lifts the pause
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if more than 1 operator confirmation is [required](crate::interface::AccessControl::required_confirmations)
+    /// - if the contract is not paused
+    fn resume(&mut self);
+
+    /// returns the most recent lock episodes, most recent first
+    /// - at most [LOCK_HISTORY_MAX_LEN](crate::domain::LOCK_HISTORY_MAX_LEN) entries are retained
+    /// - useful for post-incident reconstruction of stuck lock episodes
+    fn lock_history(&self, limit: u32) -> Vec<LockHistoryEntry>;
+
+    /// describes the batch workflows that are currently running, i.e., for each workflow lock that
+    /// is held:
+    /// - when the lock was acquired and the batches involved
+    /// - which callback the workflow is waiting on, or which contract method needs to be invoked
+    ///   for the workflow to make progress
+    ///
+    /// Used to diagnose stuck workflows - see [clear_stake_lock](Operator::clear_stake_lock) and
+    /// [clear_redeem_lock](Operator::clear_redeem_lock)
+    fn batch_run_status(&self) -> BatchRunStatus;
+
+    /// returns the backoff status for each workflow
+    /// - each consecutive workflow failure doubles the cooldown that is enforced before the workflow
+    ///   can be retried, starting from [workflow_backoff_base_blocks](crate::config::Config::workflow_backoff_base_blocks)
+    ///   and capped at [workflow_backoff_max_blocks](crate::config::Config::workflow_backoff_max_blocks)
+    /// - failures are recorded when the workflow callback releases the lock because the staking
+    ///   pool side of the workflow failed - workflow attempts whose outcome is not recorded within
+    ///   [WORKFLOW_ATTEMPT_TIMEOUT_BLOCKS](crate::domain::WORKFLOW_ATTEMPT_TIMEOUT_BLOCKS) are
+    ///   counted as failed the next time the workflow is started
+    fn workflow_backoff_status(&self) -> Vec<WorkflowBackoffStatus>;
+
+    /// operator override that clears the workflow's consecutive failures, which lifts the cooldown
+    /// - used once the underlying issue has been resolved
+    ///
+    /// ## Panics
+    /// if not invoked by the operator account
+    fn reset_workflow_backoff(&mut self, workflow: Workflow) -> WorkflowBackoffStatus;
+
+    /// exports the minimal mutable workflow state, i.e., the workflow locks, open batches, batch ID
+    /// sequence, and the pending receipts index along with the receipts it references
+    /// - used to rehearse disaster recovery procedures and to mirror mainnet workflow state on
+    ///   shadow deployments for testing upgrades against realistic data
+    /// - account balances are not part of the export
+    fn export_workflow_state(&self) -> WorkflowStateExport;
+
+    /// imports workflow state that was exported via [export_workflow_state](Operator::export_workflow_state)
+    /// - the exported receipts are restored, overwriting receipts with the same batch ID
+    /// - the import is restricted to a fresh deployment, i.e., no accounts are registered and no
+    ///   STAKE has been issued, because the imported batches are not backed by the contract balances
+    /// - there is no operator proposal for the import because additional operators must be
+    ///   registered, i.e., the primary operator is the only operator on a fresh deployment
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if the operator action requires confirmations - see [AccessControl](crate::interface::AccessControl)
+    /// - if any accounts are registered or any STAKE has been issued
+    /// - if the data is not valid exported workflow state
+    /// - if any batches are open or workflow locks are held, i.e., the workflows must be idle
+    /// - if the imported batch ID sequence is behind the current batch ID sequence
+    fn import_workflow_state(&mut self, data: Base64VecU8) -> WorkflowStateExport;
+
+    /// emergency override that releases the account's full queued withdrawal immediately, i.e.,
+    /// bypasses the per epoch withdrawal limit and does not wait for the next epoch
+    /// - the funds can only be transferred to the recipient that was specified when the withdrawal
+    ///   was queued
+    ///
+    /// Returns the amount that was transferred.
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if the account has no queued withdrawal
+    fn release_queued_withdrawal(&mut self, account_id: ValidAccountId) -> YoctoNear;
+
+    /// deposits the attached NEAR into the registration pool, which is used to pay the account
+    /// storage fee for whitelisted contracts that are auto-registered
+    ///
+    /// Returns the updated registration pool balance.
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if no deposit is attached
+    fn fund_registration_pool(&mut self) -> YoctoNear;
+
+    fn registration_pool(&self) -> YoctoNear;
+
+    /// Whitelisted contracts, e.g., DEXes and farms, are auto-registered when they are sent STAKE
+    /// via [ft_transfer_call](crate::interface::FungibleToken::ft_transfer_call) and are not yet
+    /// registered. The account storage fee is paid from the registration pool.
+    ///
+    /// ## Panics
+    /// if not invoked by the operator account
+    fn whitelist_auto_registration(&mut self, account_id: ValidAccountId);
+
+    /// ## Panics
+    /// if not invoked by the operator account
+    fn remove_auto_registration(&mut self, account_id: ValidAccountId);
+
+    fn auto_registration_whitelisted(&self, account_id: ValidAccountId) -> bool;
+
+    /// Whitelists another STAKE contract deployment, e.g., linked to a different validator, that
+    /// accounts can forward their redeem proceeds to - see [set_redeem_forwarding](crate::interface::StakingService::set_redeem_forwarding)
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if the target is this contract
+    fn whitelist_redeem_forwarding_target(&mut self, account_id: ValidAccountId);
+
+    /// Accounts that forward to the removed target will no longer have their redeem proceeds
+    /// forwarded, i.e., the NEAR will remain available for withdrawal on the account.
+    ///
+    /// ## Panics
+    /// if not invoked by the operator account
+    fn remove_redeem_forwarding_target(&mut self, account_id: ValidAccountId);
+
+    fn redeem_forwarding_target_whitelisted(&self, account_id: ValidAccountId) -> bool;
+
+    /// Registers another STAKE contract deployment whose STAKE can be swapped for this contract's
+    /// STAKE via swap intents - see [SwapIntents](crate::interface::SwapIntents)
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if the deployment is this contract
+    fn register_swap_deployment(&mut self, account_id: ValidAccountId);
+
+    /// Open swap intents that target the deployment can no longer be filled, but can still be
+    /// cancelled.
+    ///
+    /// ## Panics
+    /// if not invoked by the operator account
+    fn unregister_swap_deployment(&mut self, account_id: ValidAccountId);
+
+    fn swap_deployment_registered(&self, account_id: ValidAccountId) -> bool;
+
+    /// commits the pending STAKE token value update that exceeded the configured
+    /// [max_stake_token_value_increase_percentage](crate::config::Config::max_stake_token_value_increase_percentage)
+    /// - the operator should verify the staking pool balances before confirming the jump
+    ///
+    /// Returns the updated STAKE token value.
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if there is no pending STAKE token value jump
+    /// - if the STAKE supply has changed since the jump was recorded - the STAKE token value needs
+    ///   to be refreshed, which will record a new pending jump
+    fn confirm_stake_value_jump(&mut self) -> StakeTokenValue;
+
+    /// returns the STAKE token value update that is awaiting operator confirmation
+    fn pending_stake_token_value_jump(&self) -> Option<StakeTokenValue>;
+
+    /// returns the storage usage diagnostics recorded for the last call to the specified method
+    /// - diagnostics are only recorded while [storage_delta_diagnostics](crate::config::Config::storage_delta_diagnostics)
+    ///   is enabled
+    /// - instrumented methods: `deposit`, `redeem`, `ft_transfer`, `ft_transfer_call`
+    /// - useful for optimizing the storage footprint of high frequency methods
+    ///
+    /// NOTE: the contract state itself is written after the method returns, thus contract state
+    ///       changes are not included in the delta
+    fn debug_last_storage_delta(&self, method_name: String) -> Option<StorageDelta>;
+
+    /// returns a flat list of numeric (name, value) pairs that can be scraped by monitoring
+    /// systems, e.g., exported as prometheus metrics
+    /// - counters are suffixed with `_total` and only ever increase:
+    ///   - batch workflows run, batch workflow callback failures, locks forcibly cleared by the
+    ///     operator, batch receipts deleted once fully claimed, STAKE minted and burned
+    /// - all other metrics are gauges, e.g., STAKE supply, STAKE token value, liquidity
+    /// - `stake_token_value` is the NEAR value of 1 STAKE in yoctoNEAR
+    /// - lock gauges are 1 if locked, 0 otherwise
+    fn metric_values(&self) -> Vec<MetricValue>;
+
+    /// returns the crate version, git commit, and state schema hash that were embedded into the
+    /// contract wasm at build time, along with the state layout versions
+    /// - used to verify which code and state schema a deployment runs
+    /// - same as [Contract::contract_version](crate::Contract::contract_version), which does not
+    ///   require the contract state to be readable
+    fn version(&self) -> ContractVersion;
+
+    /// records the gas burned by a staking pool cross-contract call
+    /// - the runtime does not expose the gas burned by a receipt to its callback, thus it is reported
+    ///   from the staking pool receipt outcome, e.g., via the `EXPERIMENTAL_tx_status` RPC
+    /// - the staking pool workflow callbacks record their own gas usage - see
+    ///   [WORKFLOW_CALLBACKS](crate::domain::WORKFLOW_CALLBACKS)
+    /// - if [staking_pool_gas_auto_tune](crate::config::Config::staking_pool_gas_auto_tune) is
+    ///   enabled, then the gas attached to the method is nudged toward the max observed gas burned
+    ///   plus headroom - see [GasConfig](crate::config::GasConfig) for the tuning bounds
+    ///
+    /// Returns the updated gas usage for the method.
+    ///
+    /// NOTE: recording gas usage does not require operator confirmations, even though auto-tuning
+    ///       changes the config, because auto-tuning is enabled via a validated config change and
+    ///       the tuned gas is kept within the validated gas bounds
+    ///
+    /// ## Panics
+    /// - if not invoked by an operator account
+    /// - if the method is not a staking pool method that is invoked by the contract - see
+    ///   [STAKING_POOL_METHODS](crate::domain::STAKING_POOL_METHODS)
+    fn record_staking_pool_gas_usage(
+        &mut self,
+        method_name: String,
+        gas_burnt: Gas,
+    ) -> StakingPoolGasUsage;
+
+    /// returns the recorded gas usage for each staking pool method that is invoked by the contract,
+    /// followed by the staking pool workflow callbacks, along with the gas that is currently attached
+    /// - useful for spotting chronic over-attachment of gas
+    fn staking_pool_gas_usage(&self) -> Vec<StakingPoolGasUsage>;
+
+    /// returns a health summary that monitoring systems can poll
+    /// - the STAKE supply, total NEAR, and NEAR liquidity pool are watched for changes that exceed
+    ///   the configured [per call](crate::config::Config::aggregate_alarm_txn_threshold_percentage)
+    ///   and [per epoch](crate::config::Config::aggregate_alarm_epoch_threshold_percentage)
+    ///   thresholds - an [AggregateAlarm](events::AggregateAlarm) event is logged when a threshold
+    ///   is exceeded and the last alarm is recorded in state
+    /// - watched methods: `withdraw`, `withdraw_all`, and the batch workflow steps that mint STAKE,
+    ///   burn STAKE, and move NEAR in or out of the liquidity pool
+    /// - the contract is reported unhealthy while an alarm has been raised within the current epoch
+    fn health(&self) -> Health;
+}
+
+pub mod events {
+    use crate::domain::{self, Aggregate, AlarmWindow, LockKind, Workflow};
+
+    #[derive(Debug)]
+    pub struct ContractPaused<'a> {
+        pub reason: &'a str,
+    }
+
+    #[derive(Debug)]
+    pub struct ContractResumed<'a> {
+        pub reason: &'a str,
+        /// block height when the contract was paused
+        pub paused_block_height: u64,
+    }
+
+    #[derive(Debug)]
+    pub struct StaleLockReleased {
+        pub lock: LockKind,
+        pub acquired_epoch_height: u64,
+    }
+
+    /// the stale lock was not released because the staking pool balances show that the workflow
+    /// funds were transferred
+    #[derive(Debug)]
+    pub struct StaleLockFundsInFlight {
+        pub lock: LockKind,
+    }
+
+    #[derive(Debug)]
+    pub struct WorkflowFailed {
+        pub workflow: Workflow,
+        pub consecutive_failures: u32,
+        pub cooldown_blocks: u64,
+    }
+
+    #[derive(Debug)]
+    pub struct WorkflowBackoffReset {
+        pub workflow: Workflow,
+        pub consecutive_failures: u32,
+    }
+
+    #[derive(Debug)]
+    pub struct WorkflowStateImported {
+        pub batch_id_sequence: u128,
+        pub stake_batch_receipts: usize,
+        pub redeem_stake_batch_receipts: usize,
+    }
+
+    #[derive(Debug)]
+    pub struct StakingPoolGasAutoTuned<'a> {
+        pub method_name: &'a str,
+        pub max_burnt: u64,
+        pub attached: u64,
+    }
+
+    #[derive(Debug)]
+    pub struct AggregateAlarm {
+        pub aggregate: Aggregate,
+        pub window: AlarmWindow,
+        pub before: u128,
+        pub after: u128,
+        pub threshold_percentage: u8,
+        pub epoch_height: u64,
+    }
+
+    impl From<domain::AggregateAlarm> for AggregateAlarm {
+        fn from(alarm: domain::AggregateAlarm) -> Self {
+            Self {
+                aggregate: alarm.aggregate(),
+                window: alarm.window(),
+                before: alarm.before(),
+                after: alarm.after(),
+                threshold_percentage: alarm.threshold_percentage(),
+                epoch_height: alarm.block_time_height().epoch_height().value(),
+            }
+        }
+    }
 }
@@ -0,0 +1,46 @@
+use crate::interface::{PendingTransfer, TokenAmount};
+use near_sdk::json_types::U64;
+
+/// Each [ft_transfer_call](crate::interface::FungibleToken::ft_transfer_call) is registered as a
+/// pending transfer until the `ft_resolve_transfer_call` callback resolves it. If the receiver
+/// contract stalls, or the callback runs out of gas, then the sender's STAKE has been debited while
+/// the transfer is never resolved. Once the configured
+/// [stalled transfer timeout](crate::config::Config::stalled_transfer_timeout_blocks) has elapsed,
+/// the transfer can be resolved explicitly.
+///
+/// Progress is reported via [events].
+pub trait PendingTransfers {
+    /// Returns the pending transfer, or None if the transfer has been resolved or does not exist.
+    fn ft_transfer_call_status(&self, transfer_id: U64) -> Option<PendingTransfer>;
+
+    /// resolves a stalled transfer
+    /// - if `refund` is false, then the transfer is finalized, i.e., the receiver keeps the STAKE
+    /// - if `refund` is true, then the transferred STAKE is refunded from the receiver back to the
+    ///   sender - if the receiver STAKE balance is insufficient, then the receiver's remaining STAKE
+    ///   balance is refunded
+    ///
+    /// Once resolved, the `ft_resolve_transfer_call` callback is a no-op for the transfer, i.e., the
+    /// transfer cannot be resolved twice.
+    ///
+    /// Returns the amount that was refunded to the sender.
+    ///
+    /// ## Panics
+    /// - if the pending transfer does not exist
+    /// - if not invoked by the sender or an operator
+    /// - if the transfer is not stalled
+    /// - if a refund is requested by an account that is not an operator - refunding claws back STAKE
+    ///   from the receiver, which requires operator review
+    fn resolve_stalled_transfer(&mut self, transfer_id: U64, refund: bool) -> TokenAmount;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct StalledTransferResolved<'a> {
+        pub transfer_id: u64,
+        pub sender_id: &'a str,
+        pub receiver_id: &'a str,
+        pub amount: u128,
+        pub refund: u128,
+        pub resolved_by: &'a str,
+    }
+}
@@ -0,0 +1,41 @@
+use crate::interface::{EpochHeight, PoolPreference, PoolPreferenceTally};
+use near_sdk::json_types::ValidAccountId;
+
+/// Lets STAKE holders signal which staking pools (validators) they prefer, without requiring a
+/// governance token. Signals are weighted by the account's STAKE balance and tallied per epoch.
+///
+/// The tallies are informational - they do not change how funds are staked. Once multi-pool
+/// staking is supported, the operator can use the tallies to transparently set pool routing weights.
+pub trait PoolPreferences {
+    /// signals the account's preferred staking pool for the current epoch
+    /// - the signal is weighted by the account's STAKE balance, which includes STAKE from unclaimed
+    ///   batch receipts
+    /// - the weight is captured when the signal is made - signaling again within the same epoch
+    ///   replaces the account's prior signal, which can be used to refresh the weight
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the account does not hold any STAKE
+    /// - if the max number of staking pools that can be tallied per epoch has been reached
+    fn signal_pool_preference(&mut self, pool_id: ValidAccountId) -> PoolPreference;
+
+    /// returns the staking pool preference that the account last signaled
+    fn account_pool_preference(&self, account_id: ValidAccountId) -> Option<PoolPreference>;
+
+    /// returns the staking pool preference tallies for the specified epoch
+    /// - if the epoch is not specified, then the current epoch's tallies are returned
+    fn pool_preference_tallies(
+        &self,
+        epoch_height: Option<EpochHeight>,
+    ) -> Vec<PoolPreferenceTally>;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct PoolPreferenceSignaled<'a> {
+        pub account_id: &'a str,
+        pub pool_id: &'a str,
+        pub epoch_height: u64,
+        pub weight: u128,
+    }
+}
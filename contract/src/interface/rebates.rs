@@ -0,0 +1,73 @@
+use crate::interface::{AccountRebate, RebateProgram, RebateRound, RebateTier, YoctoNear};
+use near_sdk::json_types::ValidAccountId;
+
+/// Loyalty program that rebates NEAR to long term STAKE holders.
+///
+/// The contract owner funds the rebate pool and periodically closes rebate rounds. When a round is
+/// closed, the pool is allocated to the round. Accounts whose average STAKE balance over the round
+/// qualifies for a [RebateTier] can [claim](FeeRebates::claim_rebate) the tier's percentage of their
+/// proportional share of the round's rebate pool. Rebates must be claimed before the next round is
+/// closed - unclaimed rebates are returned to the pool.
+pub trait FeeRebates {
+    /// adds the attached deposit to the rebate pool for the current round
+    /// - returns the updated rebate pool balance
+    ///
+    /// ## Panics
+    /// - if the predecessor account is not the owner account
+    /// - if no deposit is attached
+    fn fund_rebate_pool(&mut self) -> YoctoNear;
+
+    /// sets the rebate tiers that will apply to the current round when it is closed
+    ///
+    /// ## Panics
+    /// - if the predecessor account is not the owner account
+    /// - if tiers are not sorted by `min_average_stake` in strictly ascending order
+    /// - if any tier rebate percentage is not within 1-100
+    fn set_rebate_tiers(&mut self, tiers: Vec<RebateTier>);
+
+    /// closes the current round and allocates the rebate pool to the round
+    /// - unclaimed rebates from the prior round are returned to the rebate pool first
+    ///
+    /// ## Panics
+    /// - if the predecessor account is not the owner account
+    /// - if the round has not spanned at least 1 epoch
+    fn close_rebate_round(&mut self) -> RebateRound;
+
+    /// credits the rebate for the most recently closed round to the account's NEAR balance, which
+    /// is available for withdrawal
+    /// - returns the amount rebated
+    ///
+    /// ## Panics
+    /// if the account is not registered
+    fn claim_rebate(&mut self) -> YoctoNear;
+
+    fn rebate_program(&self) -> RebateProgram;
+
+    fn rebate_round(&self, round_id: u64) -> Option<RebateRound>;
+
+    /// returns the rebate that the account can claim for the most recently closed round
+    fn account_rebate(&self, account_id: ValidAccountId) -> Option<AccountRebate>;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct RebatePoolFunded {
+        pub amount: u128,
+        pub pool: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct RebateRoundClosed {
+        pub round_id: u64,
+        pub amount: u128,
+        /// unclaimed rebates from the prior round that were returned to the pool
+        pub expired: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct RebateClaimed<'a> {
+        pub account_id: &'a str,
+        pub round_id: u64,
+        pub amount: u128,
+    }
+}
@@ -0,0 +1,62 @@
+use crate::interface::ReceiverAuthorizations;
+use near_sdk::json_types::{Base58PublicKey, ValidAccountId};
+
+/// Lets an account restrict which receiver contracts its delegate keys can route STAKE to via
+/// [ft_transfer_call](crate::interface::FungibleToken::ft_transfer_call).
+///
+/// Delegate keys are access keys that the account has handed out, e.g., to a custodian or to a
+/// session. The receiver allowlist is only enforced while the account has delegate keys registered,
+/// and only for transfer calls that are signed with a delegate key.
+///
+/// Delegate keys cannot change the account's receiver authorizations - they must be managed with
+/// the account's other keys.
+///
+/// The attached deposit must cover the storage fee for any storage that the change allocates - any
+/// excess is refunded. Storage fees are refunded as storage is freed.
+pub trait ReceiverAuthorization {
+    /// registers a delegate key for the predecessor account
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if signed with a delegate key
+    /// - if [MAX_DELEGATE_KEYS](crate::domain::MAX_DELEGATE_KEYS) has been reached
+    /// - if the attached deposit does not cover the storage fee
+    fn add_delegate_key(&mut self, public_key: Base58PublicKey) -> ReceiverAuthorizations;
+
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if signed with a delegate key
+    fn remove_delegate_key(&mut self, public_key: Base58PublicKey) -> ReceiverAuthorizations;
+
+    /// authorizes the receiver contract for transfer calls that are signed with a delegate key
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if signed with a delegate key
+    /// - if [MAX_AUTHORIZED_RECEIVERS](crate::domain::MAX_AUTHORIZED_RECEIVERS) has been reached
+    /// - if the attached deposit does not cover the storage fee
+    fn authorize_receiver(&mut self, receiver_id: ValidAccountId) -> ReceiverAuthorizations;
+
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if signed with a delegate key
+    fn revoke_receiver(&mut self, receiver_id: ValidAccountId) -> ReceiverAuthorizations;
+
+    fn receiver_authorizations(&self, account_id: ValidAccountId) -> ReceiverAuthorizations;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct DelegateKeyUpdated<'a> {
+        pub account_id: &'a str,
+        pub public_key: &'a str,
+        pub registered: bool,
+    }
+
+    #[derive(Debug)]
+    pub struct ReceiverAuthorizationUpdated<'a> {
+        pub account_id: &'a str,
+        pub receiver_id: &'a str,
+        pub authorized: bool,
+    }
+}
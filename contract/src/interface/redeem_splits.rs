@@ -0,0 +1,72 @@
+use crate::interface::{RedeemSplit, YoctoStake};
+use near_sdk::json_types::ValidAccountId;
+
+/// Lets large holders spread a redemption across successive redeem stake batches.
+///
+/// Redeeming a large amount in a single batch exposes the whole amount to the STAKE token value
+/// at the time the batch is run, and draws down the NEAR liquidity pool in one go. Splitting the
+/// redemption averages the STAKE token value over the batch runs and smooths the liquidity draw.
+///
+/// The STAKE is not escrowed. Each portion is redeemed from the account STAKE balance when the
+/// redeem stake batch run starts:
+/// - if the account STAKE balance cannot cover the portion, then the remaining balance is redeemed
+///   and the split ends
+/// - if the portion exceeds the account redeem limit, then the portion is capped at the limit and
+///   the balance is carried over into the following batches
+pub trait RedeemSplits {
+    /// Redeems the STAKE amount spread evenly across up to `max_batches` successive redeem stake
+    /// batches.
+    /// - the first portion is redeemed right away
+    /// - each following portion is redeemed when the next redeem stake batch run starts - see
+    ///   [unstake](crate::interface::StakingService::unstake)
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the amount is zero
+    /// - if `max_batches` is zero or more than [MAX_REDEEM_SPLIT_BATCHES](crate::domain::MAX_REDEEM_SPLIT_BATCHES)
+    /// - if the account already has a redeem split
+    /// - if the max number of redeem splits are queued - see [MAX_REDEEM_SPLITS](crate::domain::MAX_REDEEM_SPLITS)
+    /// - if the account STAKE balance is insufficient
+    fn redeem_split(&mut self, amount: YoctoStake, max_batches: u8) -> RedeemSplit;
+
+    /// cancels the portions that have not yet been redeemed - portions that were already redeemed
+    /// remain in their redeem stake batches
+    ///
+    /// Returns the STAKE that will no longer be redeemed.
+    ///
+    /// ## Panics
+    /// if the account has no redeem split
+    fn cancel_redeem_split(&mut self) -> YoctoStake;
+
+    /// returns the portions that are still scheduled for the account
+    fn redeem_split_schedule(&self, account_id: ValidAccountId) -> Option<RedeemSplit>;
+
+    /// returns the queued redeem splits in the order they were made
+    fn redeem_splits(&self) -> Vec<RedeemSplit>;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct RedeemSplitScheduled<'a> {
+        pub account_id: &'a str,
+        pub amount: u128,
+        pub batches: u8,
+    }
+
+    #[derive(Debug)]
+    pub struct RedeemSplitPortionRedeemed<'a> {
+        pub account_id: &'a str,
+        pub batch_id: u128,
+        pub amount: u128,
+        pub remaining: u128,
+    }
+
+    /// logged when the split ends before all of the STAKE was redeemed
+    #[derive(Debug)]
+    pub struct RedeemSplitEnded<'a> {
+        pub account_id: &'a str,
+        /// STAKE that will no longer be redeemed
+        pub remaining: u128,
+        pub cancelled: bool,
+    }
+}
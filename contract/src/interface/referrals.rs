@@ -0,0 +1,72 @@
+use crate::interface::{ReferralCode, YoctoNear};
+
+/// Contract-native referral links - partners register a short code that maps to their account,
+/// which frontends pass to [deposit](crate::interface::StakingService::deposit) in order to
+/// attribute deposits to the partner without passing raw account IDs.
+///
+/// ## Collision rules
+/// - codes are unique - a code that is registered to another account cannot be registered until
+///   it expires
+/// - registering a code that the account already owns renews the code
+/// - expired codes can be reclaimed by any account - the storage fee is refunded to the previous
+///   owner
+///
+/// ## Expiry
+/// Codes expire [REFERRAL_CODE_TTL_EPOCHS](crate::domain::REFERRAL_CODE_TTL_EPOCHS) after they are
+/// registered or renewed. Deposits cannot be attributed to expired codes.
+pub trait Referrals {
+    /// registers the referral code for the predecessor account
+    /// - the attached deposit must cover the code storage fee - any excess is refunded
+    /// - codes must be [MIN_REFERRAL_CODE_LEN](crate::domain::MIN_REFERRAL_CODE_LEN)-[MAX_REFERRAL_CODE_LEN](crate::domain::MAX_REFERRAL_CODE_LEN)
+    ///   characters and may only contain lowercase ASCII letters, digits, `-`, and `_`
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the code is invalid
+    /// - if the code is registered to another account and has not expired
+    /// - if [MAX_REFERRAL_CODES](crate::domain::MAX_REFERRAL_CODES) codes are registered
+    /// - if the attached deposit does not cover the code storage fee
+    ///
+    /// #\[payable\]
+    fn register_referral_code(&mut self, code: String) -> ReferralCode;
+
+    /// deletes the referral code and refunds the code storage fee
+    ///
+    /// Returns the storage fee that was refunded.
+    ///
+    /// ## Panics
+    /// - if the code does not exist
+    /// - if not invoked by the account that owns the code
+    fn unregister_referral_code(&mut self, code: String) -> YoctoNear;
+
+    fn referral_code(&self, code: String) -> Option<ReferralCode>;
+
+    /// enumerates the registered codes, including expired codes that have not been reclaimed
+    fn referral_codes(&self, from_index: u32, limit: u32) -> Vec<ReferralCode>;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct ReferralCodeRegistered<'a> {
+        pub code: &'a str,
+        pub account_id: &'a str,
+        pub expires_at: u64,
+    }
+
+    #[derive(Debug)]
+    pub struct ReferralCodeUnregistered<'a> {
+        pub code: &'a str,
+        pub account_id: &'a str,
+        /// true if the expired code was reclaimed by another account
+        pub reclaimed: bool,
+    }
+
+    #[derive(Debug)]
+    pub struct ReferralDeposit<'a> {
+        pub code: &'a str,
+        pub referrer_id: &'a str,
+        pub account_id: &'a str,
+        pub batch_id: u128,
+        pub amount: u128,
+    }
+}
@@ -0,0 +1,35 @@
+use crate::interface::{ScheduledAction, ScheduledActionId};
+use near_sdk::json_types::ValidAccountId;
+
+/// Unified view over the behaviors that act on the account's balances automatically, i.e.,
+/// without the account having to submit a transaction when the action runs. Lets accounts audit
+/// what is acting on their balances and revoke any of it from a single place.
+pub trait ScheduledActions {
+    /// returns the account's active scheduled actions
+    fn scheduled_actions(&self, account_id: ValidAccountId) -> Vec<ScheduledAction>;
+
+    /// cancels the predecessor account's scheduled action
+    /// - [RedeemForwarding](ScheduledActionId::RedeemForwarding) - redeemed NEAR is no longer
+    ///   forwarded and is credited to the account NEAR balance when receipts are claimed
+    /// - [BatchReservation](ScheduledActionId::BatchReservation) - the reserved NEAR is refunded and
+    ///   the reservation fee is forfeited
+    /// - [SwapIntent](ScheduledActionId::SwapIntent) - the escrowed STAKE that is not pending
+    ///   settlement is credited back to the account STAKE balance
+    /// - [RedeemSplit](ScheduledActionId::RedeemSplit) - the remaining portions are no longer
+    ///   redeemed, portions that were already redeemed are not affected
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the account has no active scheduled action with the specified ID
+    fn cancel_scheduled_action(&mut self, id: ScheduledActionId);
+}
+
+pub mod events {
+    use crate::interface::ScheduledActionId;
+
+    #[derive(Debug)]
+    pub struct ScheduledActionCancelled<'a> {
+        pub account_id: &'a str,
+        pub id: ScheduledActionId,
+    }
+}
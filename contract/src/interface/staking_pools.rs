@@ -0,0 +1,62 @@
+use near_sdk::{json_types::ValidAccountId, AccountId, Promise, PromiseOrValue};
+
+/// Operator managed staking pool that the contract stakes NEAR with.
+///
+/// If the [staking pool whitelist check](crate::config::Config::staking_pool_whitelist_check) is
+/// enabled, then the staking pool must be confirmed by the NEAR Foundation staking pool whitelist
+/// contract before NEAR is staked with it:
+/// - when the contract is initialized, the whitelist check for the staking pool is scheduled
+/// - when the staking pool is changed, the new staking pool only takes effect once the whitelist
+///   contract confirms it
+/// - stake batches are not run until the staking pool is confirmed - see [StakingPoolInfo](crate::interface::StakingPoolInfo)
+pub trait StakingPools {
+    /// changes the staking pool that the contract stakes NEAR with
+    /// - the staking pool can only be changed while no STAKE is outstanding, i.e., while no NEAR is
+    ///   staked with the current staking pool on behalf of STAKE holders
+    /// - if the whitelist check is enabled, then the staking pool is changed once the staking pool
+    ///   whitelist contract confirms that the staking pool is whitelisted
+    /// - while operator confirmations are required, the change is proposed as a
+    ///   [ChangeStakingPool](crate::interface::OperatorAction::ChangeStakingPool) action
+    ///
+    /// Returns the staking pool account ID
+    ///
+    /// ## Panics
+    /// - if not invoked by an operator account
+    /// - if more than 1 operator confirmation is [required](crate::interface::AccessControl::required_confirmations)
+    /// - if the account is already the staking pool
+    /// - if STAKE is outstanding, or batches are pending or running
+    /// - if the staking pool is not whitelisted
+    fn change_staking_pool(&mut self, account_id: ValidAccountId) -> PromiseOrValue<AccountId>;
+
+    /// checks the current staking pool against the staking pool whitelist contract
+    /// - used to confirm the staking pool when the check that was scheduled at deployment failed,
+    ///   or when the whitelist check is enabled after deployment
+    ///
+    /// Returns true if the staking pool is whitelisted
+    ///
+    /// ## Panics
+    /// - if not invoked by an operator account
+    /// - if the whitelist check is disabled
+    /// - if the staking pool is not whitelisted
+    fn check_staking_pool_whitelist(&mut self) -> Promise;
+}
+
+pub mod events {
+    /// logged when the staking pool whitelist check is initiated
+    #[derive(Debug)]
+    pub struct StakingPoolWhitelistCheck<'a> {
+        pub account_id: &'a str,
+        pub whitelist_contract_id: &'a str,
+    }
+
+    #[derive(Debug)]
+    pub struct StakingPoolWhitelisted<'a> {
+        pub account_id: &'a str,
+    }
+
+    #[derive(Debug)]
+    pub struct StakingPoolChanged<'a> {
+        pub from: &'a str,
+        pub to: &'a str,
+    }
+}
@@ -1,5 +1,9 @@
 use crate::interface::{
-    BatchId, RedeemStakeBatchReceipt, StakeBatchReceipt, StakeTokenValue, YoctoNear, YoctoStake,
+    AccountReceipts, BatchAudit, BatchId, CurrentBatchIds, DepositSimulation, InstantRedeem,
+    QueuedWithdrawal, RedeemLimit, RedeemParticipation, RedeemSimulation, RedeemStakeBatchReceipt,
+    RedeemTimeline, RoundingPolicy, RouteQuote, StakeBatchReceipt, StakeConversion,
+    StakeSupplyStats, StakeTokenValue, StakeTokenValueTwap, StakingPoolInfo, WorkflowRun,
+    YoctoNear, YoctoStake,
 };
 use near_sdk::{json_types::ValidAccountId, AccountId, Promise, PromiseOrValue};
 
@@ -57,6 +61,14 @@ pub trait StakingService {
     /// - this is the staking pool that this contract is linked to
     fn staking_pool_id(&self) -> AccountId;
 
+    /// returns the staking pool account ID along with its availability status
+    /// - if the stake batch workflow fails on the staking pool side, e.g., because the staking pool
+    ///   is paused, then the staking pool is marked unavailable and stake batches will not be run
+    ///   until the backoff period expires - see [Config](crate::interface::Config)
+    /// - once the backoff period expires, the next [stake](StakingService::stake) call will retry
+    ///   the stake batch
+    fn staking_pool_info(&self) -> StakingPoolInfo;
+
     /// looks up the receipt for the specified batch ID
     /// - when a batch is successfully processed a receipt is created, meaning the NEAR funds have
     ///   been successfully deposited and staked with the staking pool
@@ -67,6 +79,16 @@ pub trait StakingService {
     ///   have been claimed (for valid batch IDs)
     fn stake_batch_receipt(&self, batch_id: BatchId) -> Option<StakeBatchReceipt>;
 
+    /// returns the stake batches that were staked without verifying the staking pool account
+    /// balances because the prepaid gas did not cover the full workflow
+    /// - the batches were minted at the cached STAKE token value
+    /// - the receipts are verified on the next
+    ///   [refresh_stake_token_value](StakingService::refresh_stake_token_value)
+    /// - the receipts cannot be claimed until they are verified, and accounts with STAKE pending
+    ///   verification cannot deposit or withdraw from stake batches until then
+    /// - the STAKE supply adjustment on verification is logged as a NEP-141 `ft_mint` or `ft_burn`
+    fn unverified_stake_batches(&self) -> Vec<BatchId>;
+
     /// looks up the receipt for the specified batch ID
     /// - when a batch is successfully processed a receipt is created, meaning the unstaked NEAR
     ///   has been withdrawn from the staking pool contract
@@ -78,6 +100,18 @@ pub trait StakingService {
     ///   claimed (for valid batch IDs)
     fn redeem_stake_batch_receipt(&self, batch_id: BatchId) -> Option<RedeemStakeBatchReceipt>;
 
+    /// looks up the audit record for the specified batch ID, which captures the raw staking pool
+    /// account balances and how the STAKE token value was computed when the batch was run
+    /// - the audit record is used to independently verify the liquidity compensation math
+    /// - unlike batch receipts, audit records are retained after all funds have been claimed
+    /// - returns None if the batch has not been run
+    fn batch_audit(&self, batch_id: BatchId) -> Option<BatchAudit>;
+
+    /// returns the IDs for the batches that are currently active
+    /// - stake and redeem batches share the same ID sequence, but the batch kind is encoded into the
+    ///   batch ID, i.e., stake and redeem batch IDs can never collide
+    fn current_batch_ids(&self) -> CurrentBatchIds;
+
     /// Adds the attached deposit to the next [StakeBatch](crate::domain::StakeBatch) scheduled to run.
     /// Returns the [BatchId](crate::domain::BatchId) for the [StakeBatch](crate::domain::StakeBatch)
     /// that the funds are deposited into.
@@ -92,10 +126,15 @@ pub trait StakingService {
     ///     zero yoctoSTAKE tokens if NEAR is deposited and staked
     ///   - in addition because of rounding issues when
     ///
+    /// - the deposit is attributed to the partner that owns the optional `referral_code` - see
+    ///   [Referrals](crate::interface::Referrals)
+    ///
     /// ## Panics
     /// - if account is not registered
     /// - if no deposit is attached
     /// - if less than the minimum required deposit was attached
+    /// - if the referral code does not exist, has expired, or is owned by the account
+    /// - if the account's stake batch is pending verification
     ///
     /// ## Notes
     /// - as a side effect, batch receipts are claimed
@@ -103,7 +142,26 @@ pub trait StakingService {
     /// #\[payable\]
     ///
     /// GAS REQUIREMENTS: 10 TGas
-    fn deposit(&mut self) -> BatchId;
+    fn deposit(&mut self, referral_code: Option<String>) -> BatchId;
+
+    /// Deposits the NEAR that is required to stake for `target_stake_amount` STAKE at the current
+    /// [STAKE token value](StakingService::stake_token_value) into the next [StakeBatch](crate::domain::StakeBatch),
+    /// and refunds the rest of the attached deposit. Lets accounts deposit in STAKE terms, e.g.,
+    /// when exactly 100 STAKE is needed to meet a protocol requirement.
+    /// - the NEAR amount is rounded up, i.e., it converts back to at least the target STAKE amount
+    /// - STAKE is minted at the STAKE token value when the batch is run - if staking rewards are
+    ///   earned in the meantime, then the minted STAKE may fall marginally short of the target
+    /// - use [near_to_stake](StakingService::near_to_stake) to check the current STAKE token value
+    ///
+    /// ## Panics
+    /// - if account is not registered
+    /// - if the target STAKE amount is zero
+    /// - if the attached deposit does not cover the required NEAR amount, including the account
+    ///   rehydration fee for hibernated accounts
+    /// - if the required NEAR amount is less than the minimum required deposit
+    ///
+    /// #\[payable\]
+    fn deposit_for_stake_target(&mut self, target_stake_amount: YoctoStake) -> BatchId;
 
     /// If there is pending unstaked NEAR awaiting to become available for withdrawal, then the the
     /// NEAR deposits stored in the [StakeBatch](crate::domain::StakeBatch) will provide liquidity
@@ -112,6 +170,8 @@ pub trait StakingService {
     /// simply restaked.
     ///
     /// locks the contract to stake the batched NEAR funds and then kicks off the staking workflow
+    /// 0. unexpired [batch reservations](crate::interface::BatchReservations) are deposited into
+    ///    the stake batch
     /// 1. lock the contract
     /// 2. get the account from the staking pool
     /// 3. if there is a pending withdrawal, then add liquidity
@@ -135,14 +195,17 @@ pub trait StakingService {
     ///   - unstaking is in progress
     /// - if there is no stake batch to run
     /// - if the attached deposit is less than the [minimum required deposit](StakingService::min_required_deposit_to_stake)
+    /// - if the staking pool is unavailable - see [staking_pool_info](StakingService::staking_pool_info)
+    /// - if the [deposit cooldown](crate::interface::Config::deposit_cooldown_blocks) has not yet
+    ///   passed since the stake batch balance last changed
     ///
     /// GAS REQUIREMENTS: 200 TGas
     fn stake(&mut self) -> PromiseOrValue<BatchId>;
 
     /// Combines [deposit](StakingService::deposit) and [stake](StakingService::stake) calls together.
     ///
-    /// If the contract is currently locked, then the deposit cannot be be immediately staked. If the
-    /// funds can be staked, then the staking Promise is returned. Otherwise, the funds are simply
+    /// If the contract is currently locked, the staking pool is unavailable, or the deposit cooldown
+    /// is configured, then the deposit cannot be be immediately staked. If the funds can be staked, then the staking Promise is returned. Otherwise, the funds are simply
     /// deposited into the next available batch and the batch ID is returned.
     ///
     /// ## Notes
@@ -157,7 +220,36 @@ pub trait StakingService {
     /// GAS REQUIREMENTS: 225 TGas
     fn deposit_and_stake(&mut self) -> PromiseOrValue<BatchId>;
 
+    /// Deposits the attached NEAR into the specified account's next [StakeBatch](crate::domain::StakeBatch).
+    /// Enables protocols, custodians, and faucets to fund a user's stake directly - the STAKE that
+    /// is minted when the batch is run is credited to `account_id`, not the predecessor.
+    /// - the same deposit rules apply as for [deposit](StakingService::deposit)
+    /// - if the target account is hibernated, then the rehydration fee is paid from the attached deposit
+    ///
+    /// Returns the batch ID that the NEAR was deposited into.
+    ///
+    /// ## Panics
+    /// - if the target account is not registered
+    /// - if no deposit is attached
+    /// - if less than the minimum required deposit was attached
+    ///
+    /// #\[payable\]
+    ///
+    /// GAS REQUIREMENTS: 10 TGas
+    fn deposit_for(&mut self, account_id: ValidAccountId) -> BatchId;
+
+    /// Combines [deposit_for](StakingService::deposit_for) and [stake](StakingService::stake) calls
+    /// together - see [deposit_and_stake](StakingService::deposit_and_stake)
+    ///
+    /// #\[payable\]
+    ///
+    /// GAS REQUIREMENTS: 225 TGas
+    fn deposit_and_stake_for(&mut self, account_id: ValidAccountId) -> PromiseOrValue<BatchId>;
+
     /// withdraws specified amount from uncommitted stake batch and refunds the account
+    /// - if the withdrawal would leave a batch balance below the min required NEAR deposit, then
+    ///   the full batch balance is withdrawn when [withdraw_full_batch_balance_below_min_deposit](crate::config::Config::withdraw_full_batch_balance_below_min_deposit)
+    ///   is enabled
     ///
     /// NOTE: all batch receipts are first claimed
     ///
@@ -165,6 +257,8 @@ pub trait StakingService {
     /// - if the account is not registered
     /// - if there are insufficient funds to fulfill the request
     /// - if the contract is locked
+    /// - if the remaining batch balance would fall below the min required NEAR deposit and
+    ///   withdrawing the full batch balance is not enabled
     fn withdraw_from_stake_batch(&mut self, amount: YoctoNear);
 
     /// withdraws all NEAR from uncommitted stake batch and refunds the account
@@ -196,6 +290,7 @@ pub trait StakingService {
     /// ## Panics
     /// - if account is not registered
     /// - if there is not enough STAKE in the account to fulfill the request
+    /// - if the account [redeem limit](StakingService::redeem_limit) would be exceeded
     fn redeem(&mut self, amount: YoctoStake) -> BatchId;
 
     /// Redeems all available STAKE - see [redeem](StakingService::redeem)
@@ -206,6 +301,27 @@ pub trait StakingService {
     /// - if account is not registered
     fn redeem_all(&mut self) -> Option<BatchId>;
 
+    /// Redeems STAKE for NEAR instantly against the NEAR liquidity pool, i.e., without going
+    /// through the unstaking workflow. The STAKE is converted at the cached
+    /// [STAKE token value](StakingService::stake_token_value) and the NEAR is credited to the
+    /// account's NEAR balance, where it is available for withdrawal.
+    /// - only NEAR liquidity that is not needed to clear the current pending withdrawal is used -
+    ///   see [exit_quote](StakingService::exit_quote)
+    /// - if the liquidity is insufficient to redeem the full amount, then the request is partially
+    ///   filled, and the unfilled STAKE remains in the account
+    /// - the redeemed STAKE counts toward the account [redeem limit](StakingService::redeem_limit)
+    ///
+    /// NOTE: all batch receipts are first claimed
+    ///
+    /// ## Panics
+    /// - if account is not registered
+    /// - if the amount is zero
+    /// - if there is not enough STAKE in the account to fulfill the request
+    /// - if the account [redeem limit](StakingService::redeem_limit) would be exceeded
+    /// - if a batch is running
+    /// - if there is no NEAR liquidity available, i.e., nothing can be redeemed
+    fn redeem_for_near_instant(&mut self, amount: YoctoStake) -> InstantRedeem;
+
     /// Enables the user to remove all STAKE that was redeemed and placed into the uncomitted
     /// [RedeemStakeBatch](crate::domain::RedeemStakeBatch). This effectively unlocks the STAKE
     /// that was specified to be redeemed.
@@ -219,6 +335,25 @@ pub trait StakingService {
     /// Enables the user to remove the specified amount of STAKE from the uncommitted [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
     fn remove_from_redeem_stake_batch(&mut self, amount: YoctoStake);
 
+    /// Sets a limit on how much STAKE the predecessor account can redeem per epoch. This is useful
+    /// for custodial hot wallets to enforce internal risk policies at the contract level.
+    /// - lowering the limit takes effect immediately and cancels any pending increase
+    /// - raising or removing the limit (specified by None) is time locked and takes effect after the
+    ///   configured delay - see [Config](crate::interface::Config)
+    /// - STAKE that is removed from the redeem batch is not credited back to the epoch limit
+    ///
+    /// Returns the updated redeem limit
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    fn set_max_redeem_per_epoch(
+        &mut self,
+        max_redeem_per_epoch: Option<YoctoStake>,
+    ) -> Option<RedeemLimit>;
+
+    /// returns the account's redeem limit, if one is set
+    fn redeem_limit(&self, account_id: ValidAccountId) -> Option<RedeemLimit>;
+
     /// Runs the workflow to process redeem STAKE for NEAR from the staking pool. The workflow consists
     /// of 2 sub-workflows:
     /// 1. NEAR funds are unstaked with the staking pool
@@ -290,25 +425,143 @@ pub trait StakingService {
     /// GAS REQUIREMENTS: 150 TGas
     fn redeem_all_and_unstake(&mut self) -> PromiseOrValue<Option<BatchId>>;
 
+    /// Kicks off whichever batch workflow is currently runnable, which enables keepers to drive the
+    /// contract without needing to know its internal state machine. At most one workflow is run per
+    /// call, checked in the following order:
+    /// 1. [stake](StakingService::stake) - if there is a stake batch that can be run, or a staked
+    ///    batch that needs to be processed
+    /// 2. [unstake](StakingService::unstake) - if there is a redeem stake batch that can be run, or
+    ///    if the unstaked NEAR for the batch pending withdrawal is available to be withdrawn
+    ///
+    /// Workflows that are cooling down because they repeatedly failed are skipped - see
+    /// [workflow_backoff_status](crate::interface::Operator::workflow_backoff_status).
+    ///
+    /// Returns [WorkflowRun::Idle] if no workflow is runnable.
+    ///
+    /// NOTE: this is a maintenance call that anyone can invoke, e.g., keepers
+    ///
+    /// GAS REQUIREMENTS: 150 TGas
+    fn run_pending_workflows(&mut self) -> WorkflowRun;
+
     /// Returns the batch that is awaiting for funds to be available to be withdrawn.
     ///
     /// NOTE: pending withdrawals blocks [RedeemStakeBatch](crate::domain::RedeemStakeBatch) to run
     fn pending_withdrawal(&self) -> Option<RedeemStakeBatchReceipt>;
 
+    /// Returns the stake and redeem batch receipts that currently include unclaimed funds for the
+    /// account, and whether redeemed NEAR funds can be claimed now or are pending withdrawal from
+    /// the staking pool.
+    ///
+    /// Returns None if the account is not registered.
+    fn account_receipts(&self, account_id: ValidAccountId) -> Option<AccountReceipts>;
+
+    /// Returns the account's participation in the redeem stake batch, i.e., how much STAKE the
+    /// account redeemed in the batch, the STAKE token value the batch was redeemed at, and whether
+    /// the NEAR funds can be claimed.
+    ///
+    /// Returns None if the account is not registered or if the account has no unclaimed funds in
+    /// the batch - once the account claims the batch receipt, the participation no longer exists.
+    fn redeem_participation(
+        &self,
+        batch_id: BatchId,
+        account_id: ValidAccountId,
+    ) -> Option<RedeemParticipation>;
+
     /// Enables the user to claim receipts explicitly, which will also claim any available NEAR
     /// liquidity to settle [RedeemStakeBatchReceipts](crate::domain::RedeemStakeBatchReceipt) that
     /// have unstaked NEAR tokens locked in the staking pool and pending withdrawal
     ///
     /// ## Notes
-    /// Receipts will also be claimed implicitly when the user submits any transactions.
+    /// - Receipts will also be claimed implicitly when the user submits any transactions.
+    /// - If the account has [redeem forwarding](StakingService::set_redeem_forwarding) set, then
+    ///   the account's available NEAR balance is forwarded to the target STAKE contract, subject to
+    ///   the contract per epoch withdrawal limit, i.e., including NEAR that was held back when
+    ///   redeem proceeds were claimed before. The transaction must attach enough gas to cover the
+    ///   forwarding workflow, otherwise the NEAR is not forwarded.
     ///
     /// ## Panics
-    /// if account is not registered
+    /// - if the contract is paused
+    /// - if account is not registered
     fn claim_receipts(&mut self);
 
+    /// Claims receipts on behalf of the specified account, which enables anyone, e.g., bots and
+    /// indexers, to finalize other accounts' receipts. Fully claimed receipts are deleted, which frees
+    /// up contract storage sooner.
+    /// - the claimed funds are credited to the account's balances - if the account has
+    ///   [redeem forwarding](StakingService::set_redeem_forwarding) set, then the claimed redeem
+    ///   proceeds are forwarded, but unlike [claim_receipts](StakingService::claim_receipts), the NEAR
+    ///   that the account already held is not
+    /// - the storage that is freed by deleting fully claimed receipts is refunded to the account
+    /// - [events::ReceiptsClaimedFor] is logged if any funds were claimed
+    ///
+    /// ## Panics
+    /// if account is not registered
+    fn claim_receipts_for(&mut self, account_id: ValidAccountId);
+
+    /// Claims the account's receipts and restakes the claimed redeemed NEAR into the stake batch in
+    /// one call, i.e., compounds the NEAR value of the redeemed STAKE without a withdraw and deposit
+    /// round trip.
+    /// - only the NEAR that is claimed by the call is restaked - the NEAR balance that the account
+    ///   held before the call is left untouched
+    /// - if the stake batch is running, then the NEAR is deposited into the next stake batch
+    /// - accounts can opt in to have redeemed NEAR compounded whenever it is claimed via the
+    ///   `auto_compound` [account preference](crate::interface::AccountManagement::set_account_preferences)
+    ///
+    /// Returns the batch ID that the NEAR was deposited into.
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if no redeemed NEAR was claimed
+    /// - if the stake batch balance would be below the minimum required deposit
+    fn redeem_and_restake_rewards(&mut self) -> BatchId;
+
+    /// Opts the account in to have its redeem proceeds staked into a different STAKE contract
+    /// deployment, e.g., one that is linked to a different validator. Whenever the account's redeem
+    /// receipts are claimed, whether [explicitly](StakingService::claim_receipts) or implicitly by
+    /// any other account transaction, the claimed NEAR is forwarded to the target contract via
+    /// [deposit_redeem_forwarding](StakingService::deposit_redeem_forwarding) and deposited into the
+    /// account's stake batch on the target contract.
+    ///
+    /// Specifying None opts the account out.
+    ///
+    /// ## Notes
+    /// - the account must be registered with the target contract
+    /// - if forwarding fails, then the NEAR is credited back to the account
+    /// - the claimed NEAR is not forwarded, i.e., it is credited to the account NEAR balance, if it
+    ///   is [auto-compounded](crate::interface::AccountPreferences), if the contract is paused, if
+    ///   the per epoch withdrawal limit is reached, or if the transaction did not attach enough gas
+    ///   for the forwarding workflow - [claim_receipts](StakingService::claim_receipts) forwards it
+    ///   later
+    ///
+    /// ## Panics
+    /// - if account is not registered
+    /// - if the target contract is not whitelisted by the operator
+    fn set_redeem_forwarding(&mut self, target_stake_contract: Option<ValidAccountId>);
+
+    /// returns the STAKE contract that the account forwards its redeem proceeds to
+    fn redeem_forwarding(&self, account_id: ValidAccountId) -> Option<AccountId>;
+
+    /// Receives redeem proceeds that are forwarded from another STAKE contract deployment. The
+    /// attached deposit is deposited into the specified account's stake batch.
+    ///
+    /// Returns the batch ID that the NEAR was deposited into.
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if no deposit is attached
+    /// - if the stake batch balance would be below the minimum required deposit
+    ///
+    /// #\[payable\]
+    fn deposit_redeem_forwarding(&mut self, account_id: ValidAccountId) -> BatchId;
+
     /// Withdraws the specified amount from the account's available NEAR balance and transfers the
     /// funds to the account.
     ///
+    /// NOTE: if the contract level [max_withdrawal_per_epoch](crate::config::Config::max_withdrawal_per_epoch)
+    /// limit would be exceeded, or the liquid NEAR is not enough while idle NEAR is swept into
+    /// staking, then the overflow is debited from the account and queued - see
+    /// [process_queued_withdrawal](StakingService::process_queued_withdrawal)
+    ///
     /// ## Panics
     /// - if the account is not registered
     /// - if there are not enough available NEAR funds to fulfill the request
@@ -325,6 +578,8 @@ pub trait StakingService {
     /// Transfers the specified amount from the account's available NEAR balance to the specified
     /// recipient account.
     ///
+    /// NOTE: transfers are subject to the same per epoch withdrawal limit as [withdraw](StakingService::withdraw)
+    ///
     /// ## Panics
     /// - if the account is not registered
     /// - if there are not enough available NEAR funds to fulfill the request
@@ -337,6 +592,22 @@ pub trait StakingService {
     /// - if the account is not registered
     fn transfer_all_near(&mut self, recipient: ValidAccountId) -> YoctoNear;
 
+    /// Returns the account's withdrawal that is queued because it overflowed the contract level
+    /// per epoch withdrawal limit or the liquid NEAR
+    fn queued_withdrawal(&self, account_id: ValidAccountId) -> Option<QueuedWithdrawal>;
+
+    /// Transfers the account's queued withdrawal to its recipient, subject to the per epoch
+    /// withdrawal limit and the liquid NEAR - any remainder stays queued. Queued withdrawals are released starting with
+    /// the epoch after they were queued. Anyone can call this function because the funds can only
+    /// be transferred to the recipient that was specified when the withdrawal was queued.
+    ///
+    /// Returns the amount that was transferred.
+    ///
+    /// ## Panics
+    /// - if the account has no queued withdrawal
+    /// - if the queued withdrawal is not yet releasable
+    fn process_queued_withdrawal(&mut self, account_id: ValidAccountId) -> YoctoNear;
+
     /// In order to make sure STAKE tokens are issued when NEAR is staked, the user needs to deposit
     /// a minimum required amount based on the cached STAKE token value to issue ~100 yoctoSTAKE.
     ///
@@ -368,6 +639,16 @@ pub trait StakingService {
     /// - if the contract is locked
     fn refresh_stake_token_value(&mut self) -> Promise;
 
+    /// Retrieves the staking pool's reward fee and caches it on the contract - the cached fee is
+    /// returned by [staking_pool_info](StakingService::staking_pool_info).
+    ///
+    /// Staking rewards are net of the staking pool fee. If the staking pool has changed its fee since
+    /// it was last cached, then a [StakingPoolRewardFeeChanged](events::StakingPoolRewardFeeChanged)
+    /// event is logged, which notifies stakers that their net yield has changed.
+    ///
+    /// NOTE: this is a maintenance call that anyone can invoke
+    fn refresh_staking_pool_reward_fee(&mut self) -> Promise;
+
     /// Returns the latest cached STAKE token value
     ///
     /// ### NOTES
@@ -377,21 +658,234 @@ pub trait StakingService {
     ///   STAKE token value then use [`refresh_stake_token_value`].
     /// - The STAKE token value is refreshed each time the NEAR is staked and when STAKE is redeemed.
     fn stake_token_value(&self) -> StakeTokenValue;
+
+    /// Converts the NEAR amount to STAKE using the latest cached STAKE token value, i.e., applies
+    /// the same math and rounding that the contract applies when minting STAKE.
+    ///
+    /// NOTE: the conversion includes when the STAKE token value was computed - see
+    /// [stake_token_value](StakingService::stake_token_value) regarding stale values
+    fn near_to_stake(&self, amount: YoctoNear) -> StakeConversion;
+
+    /// Converts the STAKE amount to NEAR using the latest cached STAKE token value, i.e., applies
+    /// the same math and rounding that the contract applies when redeeming STAKE.
+    ///
+    /// NOTE: the conversion includes when the STAKE token value was computed - see
+    /// [stake_token_value](StakingService::stake_token_value) regarding stale values
+    fn stake_to_near(&self, amount: YoctoStake) -> StakeConversion;
+
+    /// returns the rounding directions that are applied by [near_to_stake](StakingService::near_to_stake),
+    /// [stake_to_near](StakingService::stake_to_near), and the STAKE token value compensation
+    fn rounding_policy(&self) -> RoundingPolicy;
+
+    /// Quotes the terms for entering STAKE by depositing and staking the NEAR amount, i.e., the STAKE
+    /// that would be minted, the rate, fees, and delay in one response shape that aggregators can
+    /// use to route between minting and secondary markets.
+    /// - STAKE is minted when the stake batch is run - the delay is only non-zero while the staking
+    ///   pool is unavailable
+    /// - the route is not executable if the amount is below the [min required deposit](StakingService::min_required_deposit_to_stake)
+    ///
+    /// NOTE: the quote is based on the cached STAKE token value - see [stake_token_value](StakingService::stake_token_value)
+    fn entry_quote(&self, near_amount: YoctoNear) -> RouteQuote;
+
+    /// Quotes the terms for exiting STAKE by redeeming the STAKE amount, using the same response
+    /// shape as [entry_quote](StakingService::entry_quote).
+    /// - unstaked NEAR is locked by the staking pool for 4 epochs, plus any epochs remaining on the
+    ///   current pending withdrawal, which must clear before the next redeem batch can be run
+    /// - if the NEAR liquidity covers the redeemed NEAR, then the exit is instant, i.e., the NEAR can
+    ///   be claimed as soon as the redeem batch is run
+    ///
+    /// NOTE: the quote is based on the cached STAKE token value - see [stake_token_value](StakingService::stake_token_value)
+    fn exit_quote(&self, stake_amount: YoctoStake) -> RouteQuote;
+
+    /// Dry runs [deposit](StakingService::deposit) for the NEAR amount, i.e., returns the STAKE that
+    /// the NEAR converts to using the cached STAKE token value, the stake batch that the NEAR would be
+    /// deposited into, and the error messages for any checks that the deposit would fail.
+    /// - the min required deposit is checked against the amount on its own - NEAR that the account
+    ///   already has in the batch counts towards the min required deposit when actually depositing
+    ///
+    /// NOTE: the simulation is based on the cached STAKE token value - see [stake_token_value](StakingService::stake_token_value)
+    fn simulate_deposit(&self, amount: YoctoNear) -> DepositSimulation;
+
+    /// Dry runs [redeem](StakingService::redeem) for the STAKE amount, i.e., returns the NEAR that
+    /// the STAKE converts to using the cached STAKE token value, the redeem stake batch that the STAKE
+    /// would be added to, the estimated epoch when the unstaked NEAR is available for withdrawal, and
+    /// the error messages for any checks that the redeem would fail.
+    /// - the account's STAKE balance is not checked
+    ///
+    /// NOTE: the simulation is based on the cached STAKE token value - see [stake_token_value](StakingService::stake_token_value)
+    fn simulate_redeem(&self, amount: YoctoStake) -> RedeemSimulation;
+
+    /// Returns the time weighted average STAKE token value over the specified number of epochs,
+    /// ending with the current epoch. The average is computed from the per epoch STAKE token value
+    /// checkpoints, which are recorded each time the STAKE token value is updated. For epochs where
+    /// no checkpoint was recorded, the prior checkpoint value is carried forward.
+    ///
+    /// The TWAP is intended to be used by other contracts, e.g., lending protocols, as a manipulation
+    /// resistant STAKE price instead of the instantaneous cached STAKE token value.
+    ///
+    /// Returns None if no checkpoints have been recorded yet.
+    ///
+    /// ## Panics
+    /// if `window_epochs` is zero
+    fn stake_token_value_twap(&self, window_epochs: u32) -> Option<StakeTokenValueTwap>;
+
+    /// Returns the per epoch STAKE token value checkpoints, most recent first. Each checkpoint is
+    /// the last STAKE token value that was computed within the epoch, i.e., by a STAKE token value
+    /// refresh or batch run - epochs in which the STAKE token value was not updated are skipped.
+    /// - at most [MAX_STAKE_TOKEN_VALUE_HISTORY_LEN](crate::domain::MAX_STAKE_TOKEN_VALUE_HISTORY_LEN)
+    ///   checkpoints are returned
+    fn stake_token_value_history(&self, limit: u32) -> Vec<StakeTokenValue>;
+
+    /// Returns the expected timeline for the redeem stake batch: when the batch is unstaked, when
+    /// the unstaked NEAR is available for withdrawal, and when the redeemed NEAR can be claimed.
+    /// - the current redeem stake batch is expected to be unstaked in the current epoch
+    /// - the next redeem stake batch can only be unstaked once the current pending withdrawal has
+    ///   cleared
+    /// - unstaked NEAR is locked by the staking pool for 4 epochs
+    /// - block heights for future epochs are estimated based on the mainnet epoch length
+    ///
+    /// NOTE: NEAR liquidity may enable redeemed NEAR to be claimed before the withdrawal
+    ///
+    /// Returns None if the batch is not an active redeem stake batch and has no unclaimed receipt.
+    fn redeem_timeline(&self, batch_id: BatchId) -> Option<RedeemTimeline>;
+
+    /// Returns how much STAKE was minted and burned within the current epoch, as well as the
+    /// lifetime totals.
+    ///
+    /// STAKE is minted when stake batches are processed and burned when redeem stake batches are
+    /// processed, i.e., supply changes outside of these workflows are anomalies.
+    fn stake_supply_stats(&self) -> StakeSupplyStats;
 }
 
+/// Typed events that are logged by the staking service - the events are the public event API that
+/// indexers build on, and are re-exported as [crate::events].
+///
+/// Events are logged using their `Debug` representation - see [log](crate::near::log). The JSON
+/// representation is pinned by golden fixtures, thus any change to an event payload breaks the
+/// tests and requires the [VERSION] to be bumped.
+/// - yoctoNEAR, yoctoSTAKE, and u64 values are encoded as JSON strings because JSON numbers lose
+///   precision beyond 2^53
 pub mod events {
     use crate::domain::{self, BatchId, RedeemStakeBatchReceipt, StakeBatchReceipt};
+    use crate::interface::RewardFeeFraction;
     use crate::near::YOCTO;
+    use near_sdk::serde::{Deserialize, Serialize};
+
+    /// event API version - must be bumped when any event payload changes
+    pub const VERSION: &str = "1.1.0";
+
+    /// envelope used to publish the events to indexers
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct VersionedEvent {
+        pub version: String,
+        pub event: Event,
+    }
+
+    impl From<Event> for VersionedEvent {
+        fn from(event: Event) -> Self {
+            Self {
+                version: VERSION.to_string(),
+                event,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    pub enum Event {
+        StakingPoolRewardFeeChanged(StakingPoolRewardFeeChanged),
+        Unstaked(Unstaked),
+        StakeTokenValueJumpPending(StakeTokenValueJumpPending),
+        StakeTokenValueJumpConfirmed(StakeTokenValueJumpConfirmed),
+        NearLiquidityAdded(NearLiquidityAdded),
+        NearLiquidityConsumed(NearLiquidityConsumed),
+        Staked(Staked),
+        PendingWithdrawalCleared(PendingWithdrawalCleared),
+        StakeBatch(StakeBatch),
+        StakeBatchCancelled(StakeBatchCancelled),
+        RedeemLimitUpdated(RedeemLimitUpdated),
+        StakingPoolUnavailable(StakingPoolUnavailable),
+        RedeemStakeBatch(RedeemStakeBatch),
+        RedeemStakeBatchCancelled(RedeemStakeBatchCancelled),
+        RedeemProceedsForwarded(RedeemProceedsForwarded),
+        RedeemForwardingFailed(RedeemForwardingFailed),
+        WithdrawalQueued(WithdrawalQueued),
+        QueuedWithdrawalReleased(QueuedWithdrawalReleased),
+        StakeBatchVerificationDeferred(StakeBatchVerificationDeferred),
+        StakeBatchVerified(StakeBatchVerified),
+        ReceiptsClaimedFor(ReceiptsClaimedFor),
+    }
 
-    #[derive(Debug)]
+    /// encodes numbers as JSON strings
+    mod string {
+        use near_sdk::serde::{de::Error, Deserialize, Deserializer, Serializer};
+        use std::fmt::Display;
+        use std::str::FromStr;
+
+        pub fn serialize<T: Display, S: Serializer>(
+            value: &T,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&value.to_string())
+        }
+
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+        where
+            T: FromStr,
+            T::Err: Display,
+            D: Deserializer<'de>,
+        {
+            String::deserialize(deserializer)?
+                .parse()
+                .map_err(D::Error::custom)
+        }
+    }
+
+    /// encodes optional numbers as JSON strings
+    mod optional_string {
+        use near_sdk::serde::{de::Error, Deserialize, Deserializer, Serializer};
+        use std::fmt::Display;
+        use std::str::FromStr;
+
+        pub fn serialize<T: Display, S: Serializer>(
+            value: &Option<T>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match value {
+                Some(value) => serializer.serialize_some(&value.to_string()),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+        where
+            T: FromStr,
+            T::Err: Display,
+            D: Deserializer<'de>,
+        {
+            Option::<String>::deserialize(deserializer)?
+                .map(|value| value.parse().map_err(D::Error::custom))
+                .transpose()
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct StakeTokenValue {
+        #[serde(with = "string")]
         pub total_staked_near_balance: u128,
+        #[serde(with = "string")]
         pub total_stake_supply: u128,
         /// the value of 1 STAKE token in NEAR
+        #[serde(with = "string")]
         pub stake_value: u128,
         /// blockchain point in time
+        #[serde(with = "string")]
         pub block_height: u64,
+        #[serde(with = "string")]
         pub block_timestamp: u64,
+        #[serde(with = "string")]
         pub epoch_height: u64,
     }
 
@@ -408,16 +902,29 @@ pub mod events {
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct StakingPoolRewardFeeChanged {
+        pub previous: RewardFeeFraction,
+        pub current: RewardFeeFraction,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct Unstaked {
         /// corresponds to the [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
+        #[serde(with = "string")]
         pub batch_id: u128,
         /// how much STAKE was redeemed in the batch
+        #[serde(with = "string")]
         pub stake: u128,
         /// how much NEAR was unstaked for the redeemed STAKE
+        #[serde(with = "string")]
         pub near: u128,
         /// STAKE token value used to compute amount of NEAR to unstake for redeemed STAKE tokens
         pub stake_token_value: StakeTokenValue,
+        /// the staking pool that the batch was unstaked from
+        pub staking_pool_id: String,
     }
 
     impl Unstaked {
@@ -428,28 +935,72 @@ pub mod events {
                 stake: receipt.redeemed_stake().value(),
                 near: receipt.stake_near_value().value(),
                 stake_token_value: receipt.stake_token_value().into(),
+                staking_pool_id: receipt.staking_pool_id().to_string(),
             }
         }
     }
 
-    #[derive(Debug)]
+    /// logged when the STAKE token value increase exceeds the configured max - the update is held
+    /// pending until the operator confirms it
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct StakeTokenValueJumpPending {
+        /// NEAR value for 1 STAKE based on the current STAKE token value
+        #[serde(with = "string")]
+        pub current_stake_near_value: u128,
+        /// NEAR value for 1 STAKE based on the pending STAKE token value
+        #[serde(with = "string")]
+        pub pending_stake_near_value: u128,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct StakeTokenValueJumpConfirmed {
+        /// NEAR value for 1 STAKE based on the confirmed STAKE token value
+        #[serde(with = "string")]
+        pub stake_near_value: u128,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct NearLiquidityAdded {
+        #[serde(with = "string")]
+        pub amount: u128,
+        /// updated liquidity balance
+        #[serde(with = "string")]
+        pub balance: u128,
         /// how liquidity was added
+        pub inflow: domain::NearLiquidityInflow,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct NearLiquidityConsumed {
+        #[serde(with = "string")]
         pub amount: u128,
         /// updated liquidity balance
+        #[serde(with = "string")]
         pub balance: u128,
+        /// how liquidity was consumed
+        pub outflow: domain::NearLiquidityOutflow,
     }
 
-    #[derive(Debug)]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct Staked {
         /// corresponds to the [StakeBatch](crate::domain::StakeBatch)
+        #[serde(with = "string")]
         pub batch_id: u128,
         /// how much NEAR was staked
+        #[serde(with = "string")]
         pub near: u128,
         /// how much STAKE was minted for the staked NEAR
+        #[serde(with = "string")]
         pub stake: u128,
         /// STAKE token value used to mint new STAKE
         pub stake_token_value: StakeTokenValue,
+        /// the staking pool that the batch was staked with
+        pub staking_pool_id: String,
     }
 
     impl Staked {
@@ -459,20 +1010,27 @@ pub mod events {
                 stake: receipt.near_stake_value().value(),
                 near: receipt.staked_near().value(),
                 stake_token_value: receipt.stake_token_value().into(),
+                staking_pool_id: receipt.staking_pool_id().to_string(),
             }
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct PendingWithdrawalCleared {
         /// corresponds to the [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
+        #[serde(with = "string")]
         pub batch_id: u128,
         /// how much STAKE was redeemed in the batch
+        #[serde(with = "string")]
         pub stake: u128,
         /// how much NEAR was unstaked for the redeemed STAKE
+        #[serde(with = "string")]
         pub near: u128,
         /// STAKE token value used to compute amount of NEAR to unstake for redeemed STAKE tokens
         pub stake_token_value: StakeTokenValue,
+        /// the staking pool that the batch was unstaked from
+        pub staking_pool_id: String,
     }
 
     impl PendingWithdrawalCleared {
@@ -485,15 +1043,19 @@ pub mod events {
                     .stake_to_near(batch.balance().amount())
                     .value(),
                 stake_token_value: receipt.stake_token_value().into(),
+                staking_pool_id: receipt.staking_pool_id().to_string(),
             }
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct StakeBatch {
         /// corresponds to the [StakeBatch](crate::domain::StakeBatch)
+        #[serde(with = "string")]
         pub batch_id: u128,
         /// how much NEAR to staked is in the batch
+        #[serde(with = "string")]
         pub near: u128,
     }
 
@@ -507,16 +1069,43 @@ pub mod events {
     }
 
     /// batch is cancelled if all funds are withdrawn
-    #[derive(Debug)]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct StakeBatchCancelled {
+        #[serde(with = "string")]
         pub batch_id: u128,
     }
 
-    #[derive(Debug)]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct RedeemLimitUpdated {
+        pub account_id: String,
+        /// None means the limit is removed
+        #[serde(with = "optional_string")]
+        pub max_redeem_per_epoch: Option<u128>,
+        /// epoch when the new limit takes effect
+        #[serde(with = "string")]
+        pub effective_epoch_height: u64,
+    }
+
+    /// logged when the stake batch workflow failed on the staking pool side
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct StakingPoolUnavailable {
+        pub staking_pool_id: String,
+        /// stake batches will be retried once this epoch is reached
+        #[serde(with = "string")]
+        pub unavailable_until: u64,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct RedeemStakeBatch {
         /// corresponds to the [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
+        #[serde(with = "string")]
         pub batch_id: u128,
         /// how much STAKE to redeem is in the batch
+        #[serde(with = "string")]
         pub stake: u128,
     }
 
@@ -530,9 +1119,90 @@ pub mod events {
     }
 
     /// batch is cancelled if all funds are withdrawn
-    #[derive(Debug)]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct RedeemStakeBatchCancelled {
+        #[serde(with = "string")]
+        pub batch_id: u128,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct RedeemProceedsForwarded {
+        pub account_id: String,
+        pub target_stake_contract: String,
+        #[serde(with = "string")]
+        pub amount: u128,
+    }
+
+    /// logged when the target STAKE contract rejected the forwarded redeem proceeds - the NEAR is
+    /// credited back to the account
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct RedeemForwardingFailed {
+        pub account_id: String,
+        #[serde(with = "string")]
+        pub amount: u128,
+    }
+
+    /// logged when a withdrawal overflows the contract level per epoch withdrawal limit
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct WithdrawalQueued {
+        pub account_id: String,
+        pub recipient: String,
+        /// amount that was queued
+        #[serde(with = "string")]
+        pub amount: u128,
+        /// total amount that is queued for the account
+        #[serde(with = "string")]
+        pub total_queued: u128,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct QueuedWithdrawalReleased {
+        pub account_id: String,
+        pub recipient: String,
+        #[serde(with = "string")]
+        pub amount: u128,
+        /// amount that is still queued for the account
+        #[serde(with = "string")]
+        pub remaining: u128,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    /// the batch was staked without fetching the staking pool account balances
+    pub struct StakeBatchVerificationDeferred {
+        #[serde(with = "string")]
+        pub batch_id: u128,
+        /// the cached STAKE token value that the batch was provisionally minted at
+        pub stake_token_value: StakeTokenValue,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    /// the batch receipt was re-priced using the verified STAKE token value
+    pub struct StakeBatchVerified {
+        #[serde(with = "string")]
         pub batch_id: u128,
+        pub stake_token_value: StakeTokenValue,
+    }
+
+    /// logged when receipts were claimed on behalf of another account
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct ReceiptsClaimedFor {
+        pub account_id: String,
+        pub claimed_by: String,
+        /// STAKE that was credited to the account
+        #[serde(with = "string")]
+        pub stake: u128,
+        /// NEAR that was credited to the account NEAR balance, i.e., excluding NEAR that was
+        /// compounded or forwarded
+        #[serde(with = "string")]
+        pub near: u128,
     }
 
     #[cfg(test)]
@@ -542,6 +1212,7 @@ pub mod events {
         use crate::domain::{RedeemStakeBatch, StakeTokenValue};
         use crate::near::YOCTO;
         use crate::test_utils::*;
+        use near_sdk::serde_json::{self, Value};
         use near_sdk::{testing_env, MockedBlockchain};
 
         #[test]
@@ -551,9 +1222,180 @@ pub mod events {
             testing_env!(context.clone());
 
             let batch = RedeemStakeBatch::new(1.into(), (10 * YOCTO).into());
-            let receipt = batch.create_receipt(StakeTokenValue::default());
+            let receipt =
+                batch.create_receipt(StakeTokenValue::default(), TEST_STAKING_POOL_ID.to_string());
             let event = Unstaked::new(batch.id(), &receipt);
             println!("{:#?}", event);
         }
+
+        /// golden JSON published to indexers - changing it is a breaking change to the event API
+        const GOLDEN_EVENTS_JSON: &str =
+            include_str!("../../tests/fixtures/staking_service_events.json");
+
+        const STAKING_POOL_ID: &str = "staking-pool.near";
+
+        fn stake_token_value() -> super::StakeTokenValue {
+            super::StakeTokenValue {
+                total_staked_near_balance: 100 * YOCTO,
+                total_stake_supply: 100 * YOCTO,
+                stake_value: YOCTO,
+                block_height: 100,
+                block_timestamp: 1_600_000_000_000_000_000,
+                epoch_height: 10,
+            }
+        }
+
+        /// one sample per event variant
+        fn sample_events() -> Vec<VersionedEvent> {
+            vec![
+                Event::StakingPoolRewardFeeChanged(StakingPoolRewardFeeChanged {
+                    previous: RewardFeeFraction {
+                        numerator: 10,
+                        denominator: 100,
+                    },
+                    current: RewardFeeFraction {
+                        numerator: 20,
+                        denominator: 100,
+                    },
+                }),
+                Event::Unstaked(Unstaked {
+                    batch_id: 2,
+                    stake: 10 * YOCTO,
+                    near: 10 * YOCTO,
+                    stake_token_value: stake_token_value(),
+                    staking_pool_id: STAKING_POOL_ID.to_string(),
+                }),
+                Event::StakeTokenValueJumpPending(StakeTokenValueJumpPending {
+                    current_stake_near_value: YOCTO,
+                    pending_stake_near_value: 12 * YOCTO / 10,
+                }),
+                Event::StakeTokenValueJumpConfirmed(StakeTokenValueJumpConfirmed {
+                    stake_near_value: 12 * YOCTO / 10,
+                }),
+                Event::NearLiquidityAdded(NearLiquidityAdded {
+                    amount: YOCTO,
+                    balance: 5 * YOCTO,
+                    inflow: domain::NearLiquidityInflow::StakeBatch,
+                }),
+                Event::NearLiquidityConsumed(NearLiquidityConsumed {
+                    amount: YOCTO,
+                    balance: 4 * YOCTO,
+                    outflow: domain::NearLiquidityOutflow::Withdrawal,
+                }),
+                Event::Staked(Staked {
+                    batch_id: 1,
+                    near: 100 * YOCTO,
+                    stake: 100 * YOCTO,
+                    stake_token_value: stake_token_value(),
+                    staking_pool_id: STAKING_POOL_ID.to_string(),
+                }),
+                Event::PendingWithdrawalCleared(PendingWithdrawalCleared {
+                    batch_id: 2,
+                    stake: 10 * YOCTO,
+                    near: 10 * YOCTO,
+                    stake_token_value: stake_token_value(),
+                    staking_pool_id: STAKING_POOL_ID.to_string(),
+                }),
+                Event::StakeBatch(super::StakeBatch {
+                    batch_id: 1,
+                    near: 100 * YOCTO,
+                }),
+                Event::StakeBatchCancelled(StakeBatchCancelled { batch_id: 1 }),
+                Event::RedeemLimitUpdated(RedeemLimitUpdated {
+                    account_id: "bob.near".to_string(),
+                    max_redeem_per_epoch: Some(10 * YOCTO),
+                    effective_epoch_height: 12,
+                }),
+                Event::StakingPoolUnavailable(StakingPoolUnavailable {
+                    staking_pool_id: STAKING_POOL_ID.to_string(),
+                    unavailable_until: 11,
+                }),
+                Event::RedeemStakeBatch(super::RedeemStakeBatch {
+                    batch_id: 2,
+                    stake: 10 * YOCTO,
+                }),
+                Event::RedeemStakeBatchCancelled(RedeemStakeBatchCancelled { batch_id: 2 }),
+                Event::RedeemProceedsForwarded(RedeemProceedsForwarded {
+                    account_id: "bob.near".to_string(),
+                    target_stake_contract: "stake.other.near".to_string(),
+                    amount: 10 * YOCTO,
+                }),
+                Event::RedeemForwardingFailed(RedeemForwardingFailed {
+                    account_id: "bob.near".to_string(),
+                    amount: 10 * YOCTO,
+                }),
+                Event::WithdrawalQueued(WithdrawalQueued {
+                    account_id: "bob.near".to_string(),
+                    recipient: "alice.near".to_string(),
+                    amount: 5 * YOCTO,
+                    total_queued: 5 * YOCTO,
+                }),
+                Event::QueuedWithdrawalReleased(QueuedWithdrawalReleased {
+                    account_id: "bob.near".to_string(),
+                    recipient: "alice.near".to_string(),
+                    amount: 5 * YOCTO,
+                    remaining: 0,
+                }),
+                Event::StakeBatchVerificationDeferred(StakeBatchVerificationDeferred {
+                    batch_id: 3,
+                    stake_token_value: stake_token_value(),
+                }),
+                Event::StakeBatchVerified(StakeBatchVerified {
+                    batch_id: 3,
+                    stake_token_value: stake_token_value(),
+                }),
+            ]
+            .into_iter()
+            .map(VersionedEvent::from)
+            .collect()
+        }
+
+        #[test]
+        fn events_serde_round_trip() {
+            for event in sample_events() {
+                let json = serde_json::to_string(&event).unwrap();
+                let deserialized: VersionedEvent = serde_json::from_str(&json).unwrap();
+                assert_eq!(deserialized, event);
+            }
+
+            let event = VersionedEvent::from(Event::RedeemLimitUpdated(RedeemLimitUpdated {
+                account_id: "bob.near".to_string(),
+                max_redeem_per_epoch: None,
+                effective_epoch_height: 12,
+            }));
+            let json = serde_json::to_value(&event).unwrap();
+            assert!(json["event"]["RedeemLimitUpdated"]["max_redeem_per_epoch"].is_null());
+            assert_eq!(
+                serde_json::from_value::<VersionedEvent>(json).unwrap(),
+                event
+            );
+        }
+
+        #[test]
+        fn events_match_golden_json() {
+            let golden: Value = serde_json::from_str(GOLDEN_EVENTS_JSON).unwrap();
+            assert_eq!(serde_json::to_value(sample_events()).unwrap(), golden);
+
+            let events: Vec<VersionedEvent> = serde_json::from_str(GOLDEN_EVENTS_JSON).unwrap();
+            assert_eq!(events, sample_events());
+            assert!(events.iter().all(|event| event.version == VERSION));
+        }
+
+        #[test]
+        fn numbers_are_encoded_as_strings() {
+            let event = VersionedEvent::from(Event::StakeBatch(super::StakeBatch {
+                batch_id: 1,
+                near: u128::MAX,
+            }));
+            let json = serde_json::to_value(&event).unwrap();
+            assert_eq!(
+                json["event"]["StakeBatch"]["near"],
+                Value::String(u128::MAX.to_string())
+            );
+
+            let invalid =
+                r#"{"version":"1.0.0","event":{"StakeBatch":{"batch_id":"1","near":100}}}"#;
+            assert!(serde_json::from_str::<VersionedEvent>(invalid).is_err());
+        }
     }
 }
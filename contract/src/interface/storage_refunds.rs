@@ -0,0 +1,42 @@
+use crate::interface::YoctoNear;
+use near_sdk::json_types::ValidAccountId;
+
+/// Refunds the NEAR storage staking fees for contract storage that is freed by an account's
+/// actions.
+///
+/// Batch receipts are stored until all funds have been claimed from them. The account whose claim
+/// deletes the fully claimed receipt is credited with the storage fee for the freed storage at the
+/// rate that storage is charged, i.e., the protocol storage byte cost plus the account storage fee
+/// margin. Only the receipt storage that the account paid for via the account storage fee is
+/// refunded. The refund is owed to the account until it is withdrawn - it is accounted for as a
+/// user liability.
+///
+/// Storage that is freed when an account or its receiver authorizations are deleted is refunded
+/// from the account's storage escrow.
+pub trait StorageRefunds {
+    /// returns the storage refund that is owed to the account
+    fn storage_refund_balance(&self, account_id: ValidAccountId) -> YoctoNear;
+
+    /// transfers the storage refund that is owed to the predecessor account
+    /// - returns the amount that was transferred
+    ///
+    /// ## Panics
+    /// if no storage refund is owed to the account
+    fn withdraw_storage_refund(&mut self) -> YoctoNear;
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct StorageRefundCredited {
+        pub freed_bytes: u64,
+        pub amount: u128,
+        /// the account storage refund balance after the credit
+        pub balance: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct StorageRefundWithdrawn<'a> {
+        pub account_id: &'a str,
+        pub amount: u128,
+    }
+}
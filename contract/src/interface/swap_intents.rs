@@ -0,0 +1,93 @@
+use crate::interface::{SwapIntent, YoctoStake};
+use near_sdk::json_types::ValidAccountId;
+
+/// Lets STAKE holders swap their STAKE for the STAKE of another STAKE contract deployment, e.g., one
+/// that is linked to a different validator, without exiting through the unstaking workflow, which
+/// takes 4 epochs to unbond.
+///
+/// ## Workflow
+/// 1. the account posts a swap intent, which escrows the STAKE that is offered
+/// 2. a filler holding STAKE on the target contract invokes `ft_transfer_call` on the target contract
+///    with this contract as the receiver and a [FillSwapIntentMessage](crate::interface::FillSwapIntentMessage)
+/// 3. the swap ratio is computed from the STAKE token values of both contracts - both STAKE token
+///    values must have been refreshed within the current epoch, otherwise the fill is rejected
+/// 4. the target STAKE is transferred to the intent owner on the target contract - once the transfer
+///    succeeds, the escrowed STAKE is credited to the filler's account on this contract
+///
+/// NOTE: the intent owner must be registered with the target STAKE contract, and the filler must be
+///       registered with this contract
+pub trait SwapIntents {
+    /// escrows the STAKE amount into the account's swap intent
+    /// - if the account already has an open swap intent for the same target, then the STAKE is added
+    ///   to the intent
+    ///
+    /// Returns the updated swap intent.
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the amount is zero
+    /// - if the target STAKE contract is not a registered swap deployment
+    /// - if the account has insufficient STAKE
+    /// - if the account has an open swap intent for a different target
+    fn post_swap_intent(
+        &mut self,
+        target_stake_contract: ValidAccountId,
+        amount: YoctoStake,
+    ) -> SwapIntent;
+
+    /// returns the escrowed STAKE that has not been filled back to the account
+    /// - STAKE that is reserved for fills that are being settled remains escrowed until the fills
+    ///   complete - fills that fail to settle return the STAKE to the escrow
+    ///
+    /// Returns the amount of STAKE that was returned.
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the account has no open swap intent
+    fn cancel_swap_intent(&mut self) -> YoctoStake;
+
+    fn swap_intent(&self, account_id: ValidAccountId) -> Option<SwapIntent>;
+}
+
+pub mod events {
+    use near_sdk::AccountId;
+
+    #[derive(Debug)]
+    pub struct SwapIntentPosted<'a> {
+        pub account_id: &'a str,
+        pub target_stake_contract: &'a str,
+        pub amount: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct SwapIntentCancelled<'a> {
+        pub account_id: &'a str,
+        pub amount: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct SwapIntentFilled {
+        pub intent_owner_id: AccountId,
+        pub filler_id: AccountId,
+        pub target_stake_contract: AccountId,
+        /// STAKE transferred to the filler
+        pub amount: u128,
+        /// target STAKE transferred to the intent owner
+        pub target_amount: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct SwapIntentFillRejected<'a> {
+        pub intent_owner_id: AccountId,
+        pub filler_id: AccountId,
+        pub reason: &'a str,
+    }
+
+    #[derive(Debug)]
+    pub struct SwapSettlementFailed {
+        pub intent_owner_id: AccountId,
+        pub filler_id: AccountId,
+        pub amount: u128,
+        pub target_amount: u128,
+    }
+}
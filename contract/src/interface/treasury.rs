@@ -0,0 +1,40 @@
+use crate::interface::{TreasuryBalance, YoctoStake};
+use near_sdk::json_types::ValidAccountId;
+
+/// The treasury accrues the protocol fee that is skimmed from the earnings that are distributed when
+/// stake batches are run - see [protocol_fee_basis_points](crate::config::Config::protocol_fee_basis_points).
+///
+/// The protocol fee is deposited into the treasury account's stake batch, i.e., the treasury owns
+/// STAKE exactly like any other account. The treasury account is the contract account.
+pub trait Treasury {
+    /// returns the treasury STAKE balance, which includes unclaimed stake batch receipts, along with
+    /// the protocol fees that are pending to be staked
+    fn treasury_balance(&self) -> TreasuryBalance;
+
+    /// transfers treasury owned STAKE to the recipient's registered account
+    ///
+    /// ## Panics
+    /// - if the predecessor account is not the owner account
+    /// - if the amount is zero
+    /// - if the recipient account is not registered or is the treasury account
+    /// - if the treasury STAKE balance is too low to fulfill the request
+    fn treasury_transfer(&mut self, recipient: ValidAccountId, amount: YoctoStake);
+}
+
+pub mod events {
+    #[derive(Debug)]
+    pub struct ProtocolFeeCollected {
+        /// None if the fee was credited to the treasury NEAR balance because the treasury stake
+        /// batch is pending verification
+        pub batch_id: Option<u128>,
+        pub amount: u128,
+        /// total protocol fees that have been collected
+        pub total: u128,
+    }
+
+    #[derive(Debug)]
+    pub struct TreasuryTransfer<'a> {
+        pub recipient: &'a str,
+        pub amount: u128,
+    }
+}
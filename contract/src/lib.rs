@@ -96,6 +96,8 @@
 //!
 //! See each of the interfaces for details.
 //!
+//! The staking service events are published as a versioned JSON API, see [events].
+//!
 //! Contract **view** and **change** functions follow Rust semantics, i.e., interface methods with an
 //! immutable receiver are **view** functions, and interface methods with a mutable receiver are
 //! **change** functions.
@@ -104,6 +106,7 @@
 //! STAKE token value in NEAR = `total staked NEAR balance / total STAKE token supply`
 //!
 
+pub mod client;
 pub mod config;
 mod contract;
 pub mod core;
@@ -113,6 +116,8 @@ pub mod interface;
 pub mod near;
 
 pub(crate) use contract::*;
+/// versioned staking service events - published as JSON for indexers
+pub use interface::staking_service::events;
 
 #[cfg(test)]
 pub(crate) mod test_utils;
@@ -122,18 +127,45 @@ use crate::{
     config::Config,
     core::Hash,
     domain::{
-        Account, BatchId, BlockHeight, RedeemLock, RedeemStakeBatch, RedeemStakeBatchReceipt,
-        StakeBatch, StakeBatchReceipt, StakeTokenValue, StorageUsage, TimestampedNearBalance,
-        TimestampedStakeBalance, YoctoNear,
+        Account, AccountActivityLog, AggregateWatch, BatchAudit, BatchHistoryEntry,
+        BatchHistoryWindow, BatchId, BatchReservation, BatchTotalsRebuild, BlockHeight,
+        BlockTimeHeight, ContractPause, EpochHeight, EpochWithdrawals, ExitPosition,
+        FeatureRollout, FinancialsSnapshot, GasUsage, IdleNearSweep, Invoice,
+        LiquidityProviderPool, LockHistoryEntry, Lockup, MigrationCursor, MigrationTask,
+        NearLiquidityStats, OperatorProposal, PendingTransfer, PoolPreferenceTallies,
+        PublicGoodsDonations, QueuedWithdrawal, RebateProgram, RebateRound, ReceiverAuthorizations,
+        RedeemLock, RedeemSplit, RedeemStakeBatch, RedeemStakeBatchReceipt, ReferralCode,
+        RewardFeeFraction, StakeBatch, StakeBatchReceipt, StakeSupplyStats, StakeTokenValue,
+        StakeTokenValueCheckpoint, StorageDelta, StorageUsage, SwapIntent, TimestampedNearBalance,
+        TimestampedStakeBalance, WorkflowBackoffs, WorkflowCounters, YoctoNear,
     },
+    interface::ConfigPreset,
     near::storage_keys::{
-        ACCOUNTS_KEY_PREFIX, REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX,
-        STAKE_BATCH_RECEIPTS_KEY_PREFIX,
+        ACCOUNTS_KEY_PREFIX, ACCOUNT_ACTIVITY_LOGS_KEY_PREFIX, ACCOUNT_IDS_KEY_PREFIX,
+        ACCOUNT_ID_INDEX_KEY_PREFIX, AUTO_REGISTRATION_WHITELIST_KEY_PREFIX,
+        BATCH_AUDITS_KEY_PREFIX, BATCH_CONTRIBUTORS_KEY_PREFIX, BATCH_CONTRIBUTORS_LEN_KEY_PREFIX,
+        BATCH_CONTRIBUTOR_SET_KEY_PREFIX, BATCH_RESERVATIONS_KEY_PREFIX, EXIT_POSITIONS_KEY_PREFIX,
+        FEATURE_ROLLOUTS_KEY_PREFIX, FINANCIALS_HISTORY_KEY_PREFIX, INVOICES_KEY_PREFIX,
+        LIQUIDITY_PROVIDER_SHARES_KEY_PREFIX, LOCKUPS_KEY_PREFIX, LOCK_HISTORY_KEY_PREFIX,
+        MIGRATIONS_KEY_PREFIX, OPERATOR_PROPOSALS_KEY_PREFIX, PENDING_TRANSFERS_KEY_PREFIX,
+        POOL_PREFERENCE_TALLIES_KEY_PREFIX, QUEUED_WITHDRAWALS_KEY_PREFIX,
+        REBATE_ROUNDS_KEY_PREFIX, RECEIVER_AUTHORIZATIONS_KEY_PREFIX,
+        REDEEM_BATCH_HISTORY_KEY_PREFIX, REDEEM_FORWARDING_TARGETS_KEY_PREFIX,
+        REDEEM_SPLITS_KEY_PREFIX, REFERRAL_CODES_KEY_PREFIX, STAKE_BATCH_HISTORY_KEY_PREFIX,
+        STAKE_TOKEN_VALUE_CHECKPOINTS_KEY_PREFIX, STAKING_POOL_GAS_USAGE_KEY_PREFIX,
+        STORAGE_DELTAS_KEY_PREFIX, STORAGE_REFUNDS_KEY_PREFIX, SWAP_DEPLOYMENTS_KEY_PREFIX,
+        SWAP_INTENTS_KEY_PREFIX, VERSIONED_ACCOUNTS_KEY_PREFIX,
+        VERSIONED_REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX,
+        VERSIONED_STAKE_BATCH_RECEIPTS_KEY_PREFIX,
+    },
+    near::{
+        versioned_lookup_map::{LegacyVersionedLookupMap, VersionedLookupMap},
+        MAX_ACCOUNT_ID_LEN,
     },
 };
 use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
-    collections::LookupMap,
+    collections::{LookupMap, LookupSet},
     env,
     json_types::ValidAccountId,
     near_bindgen, wee_alloc, AccountId, PanicOnDefault,
@@ -158,24 +190,119 @@ pub struct Contract {
     contract_initial_storage_usage: StorageUsage,
     /// the contract is designed to collect deposits which will be staked to boost STAKE value for user accounts
     collected_earnings: YoctoNear,
+    /// a [FinancialsSnapshot] is recorded each time earnings are distributed
+    /// - snapshots are indexed in the order they are recorded, starting at 0
+    financials_history: LookupMap<u64, FinancialsSnapshot>,
+    financials_history_len: u64,
+    /// fee rebate program for STAKE holders that is funded by the contract owner
+    rebate_program: RebateProgram,
+    /// closed rebate rounds keyed by round ID
+    rebate_rounds: LookupMap<u64, RebateRound>,
+    /// staking pool preferences signaled by STAKE holders, tallied per epoch
+    pool_preference_tallies: LookupMap<EpochHeight, PoolPreferenceTallies>,
 
     /// Operator is allowed to perform operator actions on the contract
     operator_id: AccountId,
+    /// operator accounts in addition to the primary operator account
+    /// - bounded by [MAX_OPERATORS](crate::domain::MAX_OPERATORS)
+    /// - see [AccessControl](crate::interface::AccessControl)
+    operators: Vec<AccountId>,
+    /// number of operator confirmations that sensitive operator actions require
+    operator_required_confirmations: u8,
+    /// pending operator proposals keyed by proposal ID
+    operator_proposals: LookupMap<u64, OperatorProposal>,
+    /// IDs of the pending operator proposals in the order they were proposed
+    /// - bounded by [MAX_PENDING_OPERATOR_PROPOSALS](crate::domain::MAX_PENDING_OPERATOR_PROPOSALS)
+    operator_proposal_ids: Vec<u64>,
+    /// used to generate operator proposal IDs - proposal IDs start at 1
+    operator_proposal_id_sequence: u64,
+    /// when set, config changes are only accepted from the governance DAO account
+    governance_id: Option<AccountId>,
+    /// set while the contract is paused by the operator - see [pause](crate::interface::Operator::pause)
+    pause: Option<ContractPause>,
 
     config: Config,
     /// when the config was last changed
     /// the block info can be looked up via its block index: https://docs.near.org/docs/api/rpc#block
     config_change_block_height: BlockHeight,
+    /// feature rollouts keyed by feature name hash
+    /// - see [FeatureFlags](crate::interface::FeatureFlags)
+    feature_rollouts: LookupMap<Hash, FeatureRollout>,
+    /// bounded by [MAX_FEATURE_ROLLOUTS](crate::domain::MAX_FEATURE_ROLLOUTS)
+    feature_names: Vec<String>,
 
     /// how much storage the account needs to pay for when registering an account
     /// - dynamically computed when the contract is deployed
     account_storage_usage: StorageUsage,
     /// we need to track the storage escrow balance because we can't assume storage staking cost will
     /// remain constant on NEAR
+    /// - includes the invoice storage fees that are escrowed until the invoices are closed
     total_account_storage_escrow: YoctoNear,
-
-    accounts: LookupMap<Hash, Account>,
+    /// operator funded pool that fronts the account storage fee for whitelisted contracts that are
+    /// auto-registered when they are sent STAKE via `ft_transfer_call`
+    registration_pool: YoctoNear,
+    /// contracts, e.g., DEXes and farms, that are auto-registered on first STAKE receipt
+    auto_registration_whitelist: LookupSet<Hash>,
+    /// other STAKE contract deployments that accounts can forward their redeem proceeds to
+    redeem_forwarding_targets: LookupMap<Hash, AccountId>,
+    /// other STAKE contract deployments whose STAKE can be swapped for this contract's STAKE
+    swap_deployments: LookupMap<Hash, AccountId>,
+    /// open swap intents keyed by the account ID hash - each account can have at most 1 open intent
+    swap_intents: LookupMap<Hash, SwapIntent>,
+    /// invoices keyed by invoice ID
+    invoices: LookupMap<u64, Invoice>,
+    /// used to generate invoice IDs - invoice IDs start at 1
+    invoice_id_sequence: u64,
+    /// per account allowlist of receiver contracts for transfer calls signed with delegate keys
+    /// - see [ReceiverAuthorization](crate::interface::ReceiverAuthorization)
+    receiver_authorizations: LookupMap<Hash, ReceiverAuthorizations>,
+    /// batch reservations keyed by the account ID hash - each account can have at most 1 reservation
+    batch_reservations: LookupMap<Hash, BatchReservation>,
+    /// account ID hashes of the queued batch reservations in the order they were made
+    /// - bounded by [MAX_BATCH_RESERVATIONS](crate::domain::MAX_BATCH_RESERVATIONS)
+    batch_reservation_queue: Vec<Hash>,
+    /// total NEAR escrowed by batch reservations, i.e., reserved amounts plus reservation fees
+    total_batch_reservation_escrow: YoctoNear,
+    /// redeem splits keyed by the account ID hash - each account can have at most 1 redeem split
+    redeem_splits: LookupMap<Hash, RedeemSplit>,
+    /// account ID hashes of the queued redeem splits in the order they were made
+    /// - bounded by [MAX_REDEEM_SPLITS](crate::domain::MAX_REDEEM_SPLITS)
+    redeem_split_queue: Vec<Hash>,
+    /// NEAR storage refunds keyed by the account ID hash - credited when the account's actions
+    /// free contract storage, e.g., claiming funds deletes fully claimed batch receipts
+    /// - see [StorageRefunds](crate::interface::StorageRefunds)
+    storage_refunds: LookupMap<Hash, YoctoNear>,
+    /// total NEAR storage refunds that are owed to accounts
+    total_storage_refunds: YoctoNear,
+    /// referral codes keyed by the code hash - see [Referrals](crate::interface::Referrals)
+    referral_codes: LookupMap<Hash, ReferralCode>,
+    /// hashes of the registered referral codes in the order they were registered
+    /// - bounded by [MAX_REFERRAL_CODES](crate::domain::MAX_REFERRAL_CODES)
+    referral_code_index: Vec<Hash>,
+    /// exit positions keyed by the account ID hash - see [ExitPositions](crate::interface::ExitPositions)
+    exit_positions: LookupMap<Hash, ExitPosition>,
+    /// STAKE lockups keyed by the beneficiary account ID hash - each account can have at most 1
+    /// lockup - see [Lockups](crate::interface::Lockups)
+    lockups: LookupMap<Hash, Lockup>,
+    /// in-flight `ft_transfer_call` transfers keyed by transfer ID - see [PendingTransfers](crate::interface::PendingTransfers)
+    pending_transfers: LookupMap<u64, PendingTransfer>,
+    /// used to generate transfer IDs - transfer IDs start at 1
+    pending_transfer_id_sequence: u64,
+    /// recent account activity keyed by the account ID hash - see [AccountActivityLogs](crate::interface::AccountActivityLogs)
+    account_activity_logs: LookupMap<Hash, AccountActivityLog>,
+
+    accounts: LegacyVersionedLookupMap<Hash, Account>,
     accounts_len: u128,
+    /// registered account IDs keyed by index, which enables accounts to be enumerated
+    /// - accounts are stored keyed by the account ID hash, thus the account IDs are indexed separately
+    /// - see [get_accounts](crate::interface::AccountManagement::get_accounts)
+    account_ids: LookupMap<u64, AccountId>,
+    /// account ID hash -> index into `account_ids`, which enables unregistered accounts to be
+    /// removed from the index in constant time
+    account_id_index: LookupMap<Hash, u64>,
+    account_ids_len: u64,
+    /// incremented each time an account record is mutated - see [Account::nonce]
+    mutation_nonce: u64,
 
     /// total NEAR balance across all accounts that is available for withdrawal
     /// - credits are applied when [RedeemStakeBatchReceipt] is created
@@ -185,6 +312,18 @@ pub struct Contract {
     /// - credits are applied when [StakeBatchReceipt] is created
     /// - debits are applied when [RedeemStakeBatchReceipt] is created
     total_stake: TimestampedStakeBalance,
+    /// tracks STAKE minted and burned per epoch and over the lifetime of the contract
+    stake_supply_stats: StakeSupplyStats,
+    /// tracks how much NEAR has been withdrawn within the current epoch
+    /// - used to enforce the [max_withdrawal_per_epoch](crate::config::Config::max_withdrawal_per_epoch) limit
+    epoch_withdrawals: EpochWithdrawals,
+    /// withdrawals that overflowed the per epoch withdrawal limit are queued per account
+    /// - the queued NEAR is still accounted for in [total_near](Contract::total_near) until it is
+    ///   released
+    queued_withdrawals: LookupMap<Hash, QueuedWithdrawal>,
+    /// total NEAR that is queued across all accounts
+    /// - excluded from the idle NEAR that is swept into staking because it is owed to the recipients
+    total_queued_withdrawals: YoctoNear,
 
     /// used to provide liquidity when accounts are redeeming stake
     /// - funds will be drawn from the liquidity pool to fulfill requests to redeem STAKE
@@ -194,10 +333,32 @@ pub struct Contract {
     ///   to the liquidity pool if there are unstaked funds in the staking pool - the unstaked funds
     ///   will simply be restaked
     near_liquidity_pool: YoctoNear,
+    /// cumulative NEAR liquidity pool inflows and outflows
+    near_liquidity_stats: NearLiquidityStats,
+    /// NEAR liquidity provided by third parties - kept separate from the [near_liquidity_pool](Contract::near_liquidity_pool)
+    /// because it is not owned by the STAKE holders - see [LiquidityProviders](crate::interface::LiquidityProviders)
+    liquidity_provider_pool: LiquidityProviderPool,
+    /// liquidity provider pool shares keyed by the account ID hash
+    liquidity_provider_shares: LookupMap<Hash, u128>,
+    /// idle NEAR, i.e., NEAR in account balances awaiting withdrawal, that is swept into staking
+    /// cycles - the yield is shared pro-rata with the account NEAR balances
+    idle_near_sweep: IdleNearSweep,
+    /// share of the user account earnings that has been donated to the public goods beneficiary
+    /// - see [public_goods_percentage](crate::config::Config::public_goods_percentage)
+    public_goods_donations: PublicGoodsDonations,
+    /// lifetime total protocol fees that have been staked on behalf of the treasury
+    /// - see [protocol_fee_basis_points](crate::config::Config::protocol_fee_basis_points)
+    treasury_fees_collected: YoctoNear,
 
     /// cached value - if the epoch has changed, then the STAKE token value is out of date because
     /// stake rewars are issued every epoch.
     stake_token_value: StakeTokenValue,
+    /// STAKE token value update that exceeded the max increase and is awaiting operator confirmation
+    pending_stake_token_value: Option<StakeTokenValue>,
+    /// the latest STAKE token value per epoch - used to compute the STAKE token value TWAP
+    stake_token_value_checkpoints: LookupMap<EpochHeight, StakeTokenValueCheckpoint>,
+    /// epoch height for the most recent checkpoint, which is the head of the checkpoint chain
+    last_stake_token_value_checkpoint: Option<EpochHeight>,
 
     /// used to generate new batch IDs
     /// - the sequence is incremented to generate a new batch ID
@@ -224,14 +385,71 @@ pub struct Contract {
     ///      from storage
     ///    - if batches completed successfully, then accounts claim STAKE tokens
     ///    - if the batches failed. then receipt is never created - the batch can be retried
-    stake_batch_receipts: LookupMap<BatchId, StakeBatchReceipt>,
+    stake_batch_receipts: VersionedLookupMap<BatchId, StakeBatchReceipt>,
     /// - if batches completed successfully, then accounts claim NEAR tokens
     /// - if the batches failed. then the receipt is never created - the batch can be retried
-    redeem_stake_batch_receipts: LookupMap<BatchId, RedeemStakeBatchReceipt>,
+    redeem_stake_batch_receipts: VersionedLookupMap<BatchId, RedeemStakeBatchReceipt>,
+    /// audit trail of the staking pool balances and STAKE token value computation per executed batch
+    /// - unlike receipts, audit records are retained after the batch funds are claimed
+    batch_audits: LookupMap<BatchId, BatchAudit>,
+    /// summary records of the stake batches that were run keyed by history index - see [BatchHistory](crate::interface::BatchHistory)
+    stake_batch_history: LookupMap<u64, BatchHistoryEntry>,
+    stake_batch_history_window: BatchHistoryWindow,
+    /// summary records of the redeem stake batches that were run keyed by history index
+    redeem_batch_history: LookupMap<u64, BatchHistoryEntry>,
+    redeem_batch_history_window: BatchHistoryWindow,
+    /// per batch index of the accounts that contributed to the batch, keyed by (batch ID, index)
+    /// - used to rebuild the contract level batch balance from the account level batch balances
+    /// - see [rebuild_batch_totals](crate::interface::BatchTotals::rebuild_batch_totals)
+    batch_contributors: LookupMap<(BatchId, u64), Hash>,
+    batch_contributors_len: LookupMap<BatchId, u64>,
+    /// guards against indexing the same account more than once per batch
+    batch_contributor_set: LookupSet<(BatchId, Hash)>,
+    /// batch balance rebuild that is in progress
+    batch_totals_rebuild: Option<BatchTotalsRebuild>,
+    /// chunked migration progress per migration task
+    /// - see [migrate_chunk](crate::interface::Migrations::migrate_chunk)
+    migrations: LookupMap<MigrationTask, MigrationCursor>,
 
     staking_pool_id: AccountId,
+    /// set when the stake batch workflow fails on the staking pool side, e.g., the staking pool is
+    /// paused and is rejecting `deposit_and_stake` requests
+    /// - stake batches will not be run until this epoch is reached
+    staking_pool_unavailable_until: Option<EpochHeight>,
+    /// cached staking pool reward fee - refreshed via [refresh_staking_pool_reward_fee](crate::interface::StakingService::refresh_staking_pool_reward_fee)
+    staking_pool_reward_fee_fraction: Option<RewardFeeFraction>,
+    /// set once the staking pool whitelist contract confirms the staking pool - see [StakingPools](crate::interface::StakingPools)
+    staking_pool_whitelisted: bool,
     stake_batch_lock: Option<StakeLock>,
+    /// when the [StakeLock] that is currently held was acquired - used to detect stale locks
+    stake_batch_lock_acquired: Option<BlockTimeHeight>,
+    /// stake batches that were staked without fetching the staking pool account balances because
+    /// there was not enough gas for the full workflow
+    /// - the receipts are verified on the next [refresh_stake_token_value](crate::interface::StakingService::refresh_stake_token_value)
+    unverified_stake_batches: Vec<BatchId>,
     redeem_stake_batch_lock: Option<RedeemLock>,
+    /// when the [RedeemLock] that is currently held was acquired - used to detect stale locks
+    redeem_stake_batch_lock_acquired: Option<BlockTimeHeight>,
+    /// lock episodes are recorded in a ring buffer for forensic analysis, e.g., to reconstruct stuck
+    /// lock episodes
+    /// - entries are keyed by `index % LOCK_HISTORY_MAX_LEN`
+    lock_history: LookupMap<u64, LockHistoryEntry>,
+    /// total number of lock episodes that have been recorded
+    lock_history_len: u64,
+    /// lifetime batch workflow counters that are exposed as metrics
+    workflow_counters: WorkflowCounters,
+    /// consecutive failures per workflow, which are used to enforce an increasing cooldown before
+    /// a failing workflow is retried
+    workflow_backoffs: WorkflowBackoffs,
+    /// storage usage diagnostics for the last call per instrumented method name
+    /// - only recorded while [storage_delta_diagnostics](crate::config::Config::storage_delta_diagnostics)
+    ///   is enabled
+    storage_deltas: LookupMap<String, StorageDelta>,
+    /// gas burned per staking pool method name and staking pool workflow callback
+    staking_pool_gas_usage: LookupMap<String, GasUsage>,
+    /// watches the STAKE supply, total NEAR, and NEAR liquidity pool for abrupt changes
+    /// - see [health](crate::interface::Operator::health)
+    aggregate_watch: AggregateWatch,
 
     #[cfg(test)]
     #[borsh_skip]
@@ -243,52 +461,46 @@ impl Contract {
     /// ## Notes
     /// - when the contract is deployed it will measure account storage usage
     /// - owner account ID defaults to the operator account ID
+    /// - the config is bootstrapped from the `config_preset`, if specified, otherwise from the default
+    ///   config - `config` overrides are then applied on top, which are validated the same way as
+    ///   [Operator::update_config](crate::interface::Operator::update_config)
+    ///   - use [Operator::init_config_preset](crate::interface::Operator::init_config_preset) to
+    ///     lookup the presets
+    /// - if the staking pool whitelist check is enabled, then the staking pool is checked against the
+    ///   staking pool whitelist contract - see [StakingPools](crate::interface::StakingPools)
     #[init]
     pub fn new(
         staking_pool_id: ValidAccountId,
         owner_id: ValidAccountId,
         operator_id: ValidAccountId,
+        config_preset: Option<ConfigPreset>,
+        config: Option<interface::Config>,
     ) -> Self {
         assert!(!env::state_exists(), "contract is already initialized");
         assert_ne!(env::current_account_id().as_str(), owner_id.as_ref());
-        assert_ne!(env::current_account_id().as_str(), operator_id.as_ref());
-
-        let mut contract = Self {
-            owner_id: owner_id.into(),
-            contract_owner_balance: env::account_balance().into(),
-
-            operator_id: operator_id.into(),
-
-            config: Config::default(),
-            config_change_block_height: env::block_index().into(),
+        assert_ne!(
+            env::current_account_id().as_str(),
+            operator_id.as_ref(),
+            "{}",
+            errors::asserts::OPERATOR_ID_MUST_NOT_BE_CONTRACT_ID
+        );
 
-            accounts: LookupMap::new(ACCOUNTS_KEY_PREFIX.to_vec()),
-            accounts_len: 0,
-            total_near: TimestampedNearBalance::new(0.into()),
-            total_stake: TimestampedStakeBalance::new(0.into()),
-            near_liquidity_pool: 0.into(),
-            stake_token_value: StakeTokenValue::default(),
-            batch_id_sequence: BatchId::default(),
-            stake_batch: None,
-            redeem_stake_batch: None,
-            next_stake_batch: None,
-            next_redeem_stake_batch: None,
-            stake_batch_receipts: LookupMap::new(STAKE_BATCH_RECEIPTS_KEY_PREFIX.to_vec()),
-            redeem_stake_batch_receipts: LookupMap::new(
-                REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX.to_vec(),
-            ),
-            account_storage_usage: Default::default(),
-            staking_pool_id: staking_pool_id.into(),
-            stake_batch_lock: None,
-            redeem_stake_batch_lock: None,
+        let config = {
+            let mut preset_config = config_preset.map_or_else(Config::default, Config::preset);
+            if let Some(config) = config {
+                preset_config.merge(config);
+            }
+            preset_config
+        };
 
-            total_account_storage_escrow: 0.into(),
-            contract_initial_storage_usage: 0.into(), // computed after contract is created - see below
-            collected_earnings: 0.into(),
+        let mut contract = Self::initial_state(
+            staking_pool_id.into(),
+            owner_id.into(),
+            operator_id.into(),
+            config,
+        );
 
-            #[cfg(test)]
-            env: near_env::Env::default(),
-        };
+        Self::save_state_version(domain::CURRENT_STATE_VERSION);
 
         // compute initial_contract_storage_usage
         // the contract state is not yet saved to storage - measure it's storage usage manually by
@@ -300,14 +512,11 @@ impl Contract {
             + state_storage_overhead)
             .into();
 
-        // compute account storage usage
-        {
-            let initial_storage_usage = env::storage_usage();
-            contract.allocate_account_template_to_measure_storage_usage();
-            contract.account_storage_usage =
-                StorageUsage(env::storage_usage() - initial_storage_usage);
-            contract.deallocate_account_template_to_measure_storage_usage();
-            assert_eq!(initial_storage_usage, env::storage_usage());
+        contract.measure_account_storage_usage();
+
+        // NEAR is not staked with the staking pool until the whitelist contract confirms it
+        if contract.config.staking_pool_whitelist_check() {
+            contract.staking_pool_whitelist_check(contract.staking_pool_id.clone());
         }
 
         // for testing purposes, inject a successful PromiseResult
@@ -325,6 +534,151 @@ impl Contract {
 }
 
 impl Contract {
+    /// contract state with all collections empty and all balances zeroed
+    /// - used to initialize new contracts and by state migrations, which carry over the persisted
+    ///   state on top
+    fn initial_state(
+        staking_pool_id: AccountId,
+        owner_id: AccountId,
+        operator_id: AccountId,
+        config: Config,
+    ) -> Self {
+        Self {
+            owner_id,
+            contract_owner_balance: env::account_balance().into(),
+
+            operator_id,
+            operators: vec![],
+            operator_required_confirmations: 1,
+            operator_proposals: LookupMap::new(OPERATOR_PROPOSALS_KEY_PREFIX.to_vec()),
+            operator_proposal_ids: vec![],
+            operator_proposal_id_sequence: 0,
+            governance_id: None,
+            pause: None,
+
+            config,
+            config_change_block_height: env::block_index().into(),
+            feature_rollouts: LookupMap::new(FEATURE_ROLLOUTS_KEY_PREFIX.to_vec()),
+            feature_names: vec![],
+
+            accounts: LegacyVersionedLookupMap::new(
+                VERSIONED_ACCOUNTS_KEY_PREFIX.to_vec(),
+                ACCOUNTS_KEY_PREFIX.to_vec(),
+            ),
+            accounts_len: 0,
+            account_ids: LookupMap::new(ACCOUNT_IDS_KEY_PREFIX.to_vec()),
+            account_id_index: LookupMap::new(ACCOUNT_ID_INDEX_KEY_PREFIX.to_vec()),
+            account_ids_len: 0,
+            mutation_nonce: 0,
+            total_near: TimestampedNearBalance::new(0.into()),
+            total_stake: TimestampedStakeBalance::new(0.into()),
+            stake_supply_stats: StakeSupplyStats::default(),
+            epoch_withdrawals: EpochWithdrawals::default(),
+            queued_withdrawals: LookupMap::new(QUEUED_WITHDRAWALS_KEY_PREFIX.to_vec()),
+            total_queued_withdrawals: 0.into(),
+            near_liquidity_pool: 0.into(),
+            near_liquidity_stats: NearLiquidityStats::default(),
+            liquidity_provider_pool: LiquidityProviderPool::default(),
+            liquidity_provider_shares: LookupMap::new(
+                LIQUIDITY_PROVIDER_SHARES_KEY_PREFIX.to_vec(),
+            ),
+            idle_near_sweep: IdleNearSweep::default(),
+            public_goods_donations: PublicGoodsDonations::default(),
+            treasury_fees_collected: 0.into(),
+            stake_token_value: StakeTokenValue::default(),
+            pending_stake_token_value: None,
+            stake_token_value_checkpoints: LookupMap::new(
+                STAKE_TOKEN_VALUE_CHECKPOINTS_KEY_PREFIX.to_vec(),
+            ),
+            last_stake_token_value_checkpoint: None,
+            batch_id_sequence: BatchId::default(),
+            stake_batch: None,
+            redeem_stake_batch: None,
+            next_stake_batch: None,
+            next_redeem_stake_batch: None,
+            stake_batch_receipts: VersionedLookupMap::new(
+                VERSIONED_STAKE_BATCH_RECEIPTS_KEY_PREFIX.to_vec(),
+            ),
+            redeem_stake_batch_receipts: VersionedLookupMap::new(
+                VERSIONED_REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX.to_vec(),
+            ),
+            batch_audits: LookupMap::new(BATCH_AUDITS_KEY_PREFIX.to_vec()),
+            stake_batch_history: LookupMap::new(STAKE_BATCH_HISTORY_KEY_PREFIX.to_vec()),
+            stake_batch_history_window: BatchHistoryWindow::default(),
+            redeem_batch_history: LookupMap::new(REDEEM_BATCH_HISTORY_KEY_PREFIX.to_vec()),
+            redeem_batch_history_window: BatchHistoryWindow::default(),
+            batch_contributors: LookupMap::new(BATCH_CONTRIBUTORS_KEY_PREFIX.to_vec()),
+            batch_contributors_len: LookupMap::new(BATCH_CONTRIBUTORS_LEN_KEY_PREFIX.to_vec()),
+            batch_contributor_set: LookupSet::new(BATCH_CONTRIBUTOR_SET_KEY_PREFIX.to_vec()),
+            batch_totals_rebuild: None,
+            migrations: LookupMap::new(MIGRATIONS_KEY_PREFIX.to_vec()),
+            account_storage_usage: Default::default(),
+            staking_pool_id,
+            staking_pool_unavailable_until: None,
+            staking_pool_reward_fee_fraction: None,
+            staking_pool_whitelisted: false,
+            stake_batch_lock: None,
+            stake_batch_lock_acquired: None,
+            unverified_stake_batches: vec![],
+            redeem_stake_batch_lock: None,
+            redeem_stake_batch_lock_acquired: None,
+            lock_history: LookupMap::new(LOCK_HISTORY_KEY_PREFIX.to_vec()),
+            lock_history_len: 0,
+            workflow_counters: WorkflowCounters::default(),
+            workflow_backoffs: WorkflowBackoffs::default(),
+            storage_deltas: LookupMap::new(STORAGE_DELTAS_KEY_PREFIX.to_vec()),
+            staking_pool_gas_usage: LookupMap::new(STAKING_POOL_GAS_USAGE_KEY_PREFIX.to_vec()),
+            aggregate_watch: AggregateWatch::default(),
+
+            total_account_storage_escrow: 0.into(),
+            registration_pool: 0.into(),
+            auto_registration_whitelist: LookupSet::new(
+                AUTO_REGISTRATION_WHITELIST_KEY_PREFIX.to_vec(),
+            ),
+            redeem_forwarding_targets: LookupMap::new(
+                REDEEM_FORWARDING_TARGETS_KEY_PREFIX.to_vec(),
+            ),
+            swap_deployments: LookupMap::new(SWAP_DEPLOYMENTS_KEY_PREFIX.to_vec()),
+            swap_intents: LookupMap::new(SWAP_INTENTS_KEY_PREFIX.to_vec()),
+            invoices: LookupMap::new(INVOICES_KEY_PREFIX.to_vec()),
+            invoice_id_sequence: 0,
+            receiver_authorizations: LookupMap::new(RECEIVER_AUTHORIZATIONS_KEY_PREFIX.to_vec()),
+            batch_reservations: LookupMap::new(BATCH_RESERVATIONS_KEY_PREFIX.to_vec()),
+            batch_reservation_queue: Vec::new(),
+            total_batch_reservation_escrow: 0.into(),
+            redeem_splits: LookupMap::new(REDEEM_SPLITS_KEY_PREFIX.to_vec()),
+            redeem_split_queue: Vec::new(),
+            storage_refunds: LookupMap::new(STORAGE_REFUNDS_KEY_PREFIX.to_vec()),
+            total_storage_refunds: 0.into(),
+            referral_codes: LookupMap::new(REFERRAL_CODES_KEY_PREFIX.to_vec()),
+            referral_code_index: Vec::new(),
+            exit_positions: LookupMap::new(EXIT_POSITIONS_KEY_PREFIX.to_vec()),
+            lockups: LookupMap::new(LOCKUPS_KEY_PREFIX.to_vec()),
+            pending_transfers: LookupMap::new(PENDING_TRANSFERS_KEY_PREFIX.to_vec()),
+            pending_transfer_id_sequence: 0,
+            account_activity_logs: LookupMap::new(ACCOUNT_ACTIVITY_LOGS_KEY_PREFIX.to_vec()),
+            contract_initial_storage_usage: 0.into(), // computed after contract is created - see below
+            collected_earnings: 0.into(),
+            financials_history: LookupMap::new(FINANCIALS_HISTORY_KEY_PREFIX.to_vec()),
+            financials_history_len: 0,
+            rebate_program: RebateProgram::new(env::epoch_height().into()),
+            rebate_rounds: LookupMap::new(REBATE_ROUNDS_KEY_PREFIX.to_vec()),
+            pool_preference_tallies: LookupMap::new(POOL_PREFERENCE_TALLIES_KEY_PREFIX.to_vec()),
+
+            #[cfg(test)]
+            env: near_env::Env::default(),
+        }
+    }
+
+    /// computes the account storage usage, which is used to charge accounts for registration
+    fn measure_account_storage_usage(&mut self) {
+        let initial_storage_usage = env::storage_usage();
+        self.allocate_account_template_to_measure_storage_usage();
+        self.account_storage_usage = StorageUsage(env::storage_usage() - initial_storage_usage);
+        self.deallocate_account_template_to_measure_storage_usage();
+        assert_eq!(initial_storage_usage, env::storage_usage());
+    }
+
     /// this is used to compute the storage usage fees to charge for account registration
     /// - the account is responsible to pay for its storage fees - account storage is allocated, measured,
     ///   and then freed
@@ -332,21 +686,40 @@ impl Contract {
         let hash = Hash::from([0u8; 32]);
         let account_template = Account::account_template_to_measure_storage_usage();
         self.accounts.insert(&hash, &account_template);
+        // the account ID index entry is measured using the max length account ID
+        self.account_ids
+            .insert(&u64::MAX, &"a".repeat(MAX_ACCOUNT_ID_LEN));
+        self.account_id_index.insert(&hash, &u64::MAX);
+        self.account_activity_logs.insert(
+            &hash,
+            &AccountActivityLog::template_to_measure_storage_usage(),
+        );
 
         let batch_id = BatchId(0);
         self.stake_batch_receipts.insert(
             &batch_id,
-            &StakeBatchReceipt::new(0.into(), StakeTokenValue::default()),
+            &StakeBatchReceipt::new(
+                0.into(),
+                StakeTokenValue::default(),
+                self.staking_pool_id.clone(),
+            ),
         );
         self.redeem_stake_batch_receipts.insert(
             &batch_id,
-            &RedeemStakeBatchReceipt::new(0.into(), StakeTokenValue::default()),
+            &RedeemStakeBatchReceipt::new(
+                0.into(),
+                StakeTokenValue::default(),
+                self.staking_pool_id.clone(),
+            ),
         );
     }
 
     fn deallocate_account_template_to_measure_storage_usage(&mut self) {
         let hash = Hash::from([0u8; 32]);
         self.accounts.remove(&hash);
+        self.account_ids.remove(&u64::MAX);
+        self.account_id_index.remove(&hash);
+        self.account_activity_logs.remove(&hash);
 
         let batch_id = BatchId(0);
         self.stake_batch_receipts.remove(&batch_id);
@@ -358,7 +731,7 @@ impl Contract {
 mod test {
     use super::*;
     use crate::{interface::AccountManagement, test_utils::*};
-    use near_sdk::{testing_env, MockedBlockchain};
+    use near_sdk::{serde_json, testing_env, MockedBlockchain};
 
     #[test]
     #[should_panic(expected = "The contract is not initialized")]
@@ -389,15 +762,14 @@ mod test {
         let test_ctx = TestContext::new();
 
         // Assert
-        pub const EXPECTED_ACCOUNT_STORAGE_USAGE: u64 = 681;
+        pub const EXPECTED_ACCOUNT_STORAGE_USAGE: u64 = 1536;
         assert_eq!(
             test_ctx.account_storage_usage.value(),
             EXPECTED_ACCOUNT_STORAGE_USAGE
         );
         assert_eq!(
             test_ctx.account_storage_fee().value(),
-            EXPECTED_ACCOUNT_STORAGE_USAGE as u128
-                * test_ctx.config.storage_cost_per_byte().value()
+            EXPECTED_ACCOUNT_STORAGE_USAGE as u128 * env::storage_byte_cost() * 110 / 100
         );
 
         assert_eq!(
@@ -441,4 +813,44 @@ mod test {
             test_ctx.contract_initial_storage_usage
         );
     }
+
+    #[test]
+    fn contract_init_with_config_preset_and_overrides() {
+        let context = new_context("bob.near");
+        testing_env!(context);
+
+        let contract = Contract::new(
+            to_valid_account_id(TEST_STAKING_POOL_ID),
+            to_valid_account_id(TEST_OWNER_ID),
+            to_valid_account_id(TEST_OPERATOR_ID),
+            Some(ConfigPreset::MainnetConservative),
+            Some(serde_json::from_str(r#"{"max_earnings_distribution_percentage": 50}"#).unwrap()),
+        );
+        let preset = Config::preset(ConfigPreset::MainnetConservative);
+        // overrides are applied on top of the preset
+        assert_eq!(contract.config.max_earnings_distribution_percentage(), 50);
+        assert_eq!(
+            contract.config.max_stake_token_value_increase_percentage(),
+            preset.max_stake_token_value_increase_percentage()
+        );
+        assert_eq!(
+            contract.config.redeem_limit_increase_delay_epochs(),
+            preset.redeem_limit_increase_delay_epochs()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "max_earnings_distribution_percentage must be within 1 - 100")]
+    fn contract_init_with_invalid_config_override() {
+        let context = new_context("bob.near");
+        testing_env!(context);
+
+        Contract::new(
+            to_valid_account_id(TEST_STAKING_POOL_ID),
+            to_valid_account_id(TEST_OWNER_ID),
+            to_valid_account_id(TEST_OPERATOR_ID),
+            Some(ConfigPreset::TestnetFast),
+            Some(serde_json::from_str(r#"{"max_earnings_distribution_percentage": 0}"#).unwrap()),
+        );
+    }
 }
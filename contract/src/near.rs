@@ -1,6 +1,8 @@
 //! NEAR specific constants and logging support
 
+pub mod events;
 pub mod storage_keys;
+pub mod versioned_lookup_map;
 
 use crate::domain::{EpochHeight, YoctoNear};
 use near_sdk::env;
@@ -22,6 +24,16 @@ pub const NO_DEPOSIT: YoctoNear = YoctoNear(0);
 ///  - `const NUM_EPOCHS_TO_UNLOCK: EpochHeight = 4;`
 pub const UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK: EpochHeight = EpochHeight(4);
 
+/// number of blocks per epoch on mainnet - used to estimate the block height for future epochs
+pub const EPOCH_LENGTH: u64 = 43_200;
+
+/// storage overhead that NEAR charges per TRIE data record, i.e., per key-value pair
+/// - defined by the NEAR protocol runtime storage usage config as `num_extra_bytes_record`
+pub const STORAGE_RECORD_OVERHEAD: u64 = 40;
+
+/// max NEAR account ID length - defined by the NEAR protocol
+pub const MAX_ACCOUNT_ID_LEN: usize = 64;
+
 /// wrapper around `near_sdk::env::log()` which supports structured logging
 pub fn log<T: Debug>(event: T) {
     env::log(format!("{:#?}", event).as_bytes());
@@ -0,0 +1,256 @@
+//! [NEP-297](https://nomicon.io/Standards/EventsFormat) events, which are logged as
+//! `EVENT_JSON:{"standard": ..., "version": ..., "event": ..., "data": [...]}`
+//!
+//! - fungible token events follow the [NEP-141 events](https://nomicon.io/Standards/Tokens/FungibleToken/Event)
+//!   standard, which enables indexers to track STAKE activity without custom parsing
+//!   - STAKE is minted and burned when batches are run - minted STAKE is held by the contract on
+//!     behalf of the batch participants until it is claimed, thus the contract account is used as
+//!     the owner for mint and burn events
+//!   - STAKE that the contract holds on behalf of accounts is logged as an `ft_transfer` to or from
+//!     the contract account, i.e., when stake batch receipts are claimed, when STAKE is added to
+//!     or removed from redeem stake batches, and for lockups, swap intents, and STAKE swapped for
+//!     NEAR - thus the sum of the NEP-141 events per account always matches the account balance
+//! - batch workflow and liquidity events are published under the [STAKE_STANDARD] standard
+//!
+//! NOTE: the custom event structs that are logged via [log](crate::near::log) continue to be logged
+//!       alongside the NEP-297 events
+
+use crate::domain::{BatchId, NearLiquidityInflow, StakeTokenValue, YoctoNear, YoctoStake};
+use crate::near::YOCTO;
+use near_sdk::{
+    env,
+    json_types::{U128, U64},
+    serde::Serialize,
+    serde_json,
+};
+
+pub const EVENT_JSON_PREFIX: &str = "EVENT_JSON:";
+
+pub const FT_STANDARD: &str = "nep141";
+pub const FT_VERSION: &str = "1.0.0";
+
+pub const STAKE_STANDARD: &str = "oysterpack-stake";
+pub const STAKE_VERSION: &str = "1.0.0";
+
+/// 1 STAKE - the STAKE token value is published as the NEAR value of 1 STAKE
+const YOCTO_STAKE: YoctoStake = YoctoStake(YOCTO);
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+struct NearEvent<'a, T: Serialize> {
+    standard: &'a str,
+    version: &'a str,
+    event: &'a str,
+    data: &'a [T],
+}
+
+fn emit<T: Serialize>(standard: &str, version: &str, event: &str, data: &[T]) {
+    let event = NearEvent {
+        standard,
+        version,
+        event,
+        data,
+    };
+    let json = serde_json::to_string(&event).expect("event failed to serialize to JSON");
+    env::log(format!("{}{}", EVENT_JSON_PREFIX, json).as_bytes());
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FtMint<'a> {
+    pub owner_id: &'a str,
+    pub amount: U128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FtTransfer<'a> {
+    pub old_owner_id: &'a str,
+    pub new_owner_id: &'a str,
+    pub amount: U128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FtBurn<'a> {
+    pub owner_id: &'a str,
+    pub amount: U128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeBatchRun {
+    pub batch_id: U128,
+    /// NEAR that was staked for the batch
+    pub near_amount: U128,
+    /// STAKE that was minted for the batch
+    pub stake_amount: U128,
+    pub stake_token_value: U128,
+    pub epoch_height: U64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RedeemBatchRun {
+    pub batch_id: U128,
+    /// STAKE that was redeemed and burned for the batch
+    pub stake_amount: U128,
+    /// NEAR value of the redeemed STAKE that was unstaked
+    pub near_amount: U128,
+    pub stake_token_value: U128,
+    pub epoch_height: U64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LiquidityAdded {
+    pub amount: U128,
+    /// NEAR liquidity pool balance after the liquidity was added
+    pub balance: U128,
+    pub inflow: NearLiquidityInflow,
+}
+
+pub fn ft_mint(owner_id: &str, amount: YoctoStake, memo: Option<&str>) {
+    emit(
+        FT_STANDARD,
+        FT_VERSION,
+        "ft_mint",
+        &[FtMint {
+            owner_id,
+            amount: amount.value().into(),
+            memo,
+        }],
+    );
+}
+
+pub fn ft_transfer(old_owner_id: &str, new_owner_id: &str, amount: YoctoStake, memo: Option<&str>) {
+    emit(
+        FT_STANDARD,
+        FT_VERSION,
+        "ft_transfer",
+        &[FtTransfer {
+            old_owner_id,
+            new_owner_id,
+            amount: amount.value().into(),
+            memo,
+        }],
+    );
+}
+
+pub fn ft_burn(owner_id: &str, amount: YoctoStake, memo: Option<&str>) {
+    emit(
+        FT_STANDARD,
+        FT_VERSION,
+        "ft_burn",
+        &[FtBurn {
+            owner_id,
+            amount: amount.value().into(),
+            memo,
+        }],
+    );
+}
+
+pub fn stake_batch_run(
+    batch_id: BatchId,
+    near_amount: YoctoNear,
+    stake_amount: YoctoStake,
+    stake_token_value: StakeTokenValue,
+) {
+    emit(
+        STAKE_STANDARD,
+        STAKE_VERSION,
+        "stake_batch_run",
+        &[StakeBatchRun {
+            batch_id: batch_id.value().into(),
+            near_amount: near_amount.value().into(),
+            stake_amount: stake_amount.value().into(),
+            stake_token_value: stake_token_value.stake_to_near(YOCTO_STAKE).value().into(),
+            epoch_height: env::epoch_height().into(),
+        }],
+    );
+}
+
+pub fn redeem_batch_run(
+    batch_id: BatchId,
+    stake_amount: YoctoStake,
+    near_amount: YoctoNear,
+    stake_token_value: StakeTokenValue,
+) {
+    emit(
+        STAKE_STANDARD,
+        STAKE_VERSION,
+        "redeem_batch_run",
+        &[RedeemBatchRun {
+            batch_id: batch_id.value().into(),
+            stake_amount: stake_amount.value().into(),
+            near_amount: near_amount.value().into(),
+            stake_token_value: stake_token_value.stake_to_near(YOCTO_STAKE).value().into(),
+            epoch_height: env::epoch_height().into(),
+        }],
+    );
+}
+
+pub fn liquidity_added(amount: YoctoNear, balance: YoctoNear, inflow: NearLiquidityInflow) {
+    emit(
+        STAKE_STANDARD,
+        STAKE_VERSION,
+        "liquidity_added",
+        &[LiquidityAdded {
+            amount: amount.value().into(),
+            balance: balance.value().into(),
+            inflow,
+        }],
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::{serde_json::Value, test_utils::get_logs, testing_env, MockedBlockchain};
+
+    fn event_json(log: &str) -> Value {
+        assert!(log.starts_with(EVENT_JSON_PREFIX));
+        serde_json::from_str(&log[EVENT_JSON_PREFIX.len()..]).unwrap()
+    }
+
+    #[test]
+    fn ft_transfer_event_format() {
+        testing_env!(new_context("bob.near"));
+        ft_transfer("alice.near", "bob.near", 100.into(), None);
+        ft_burn("alice.near", 10.into(), Some("refund"));
+
+        let logs = get_logs();
+        assert_eq!(
+            event_json(&logs[0]),
+            serde_json::json!({
+                "standard": "nep141",
+                "version": "1.0.0",
+                "event": "ft_transfer",
+                "data": [{"old_owner_id": "alice.near", "new_owner_id": "bob.near", "amount": "100"}]
+            })
+        );
+        assert_eq!(
+            event_json(&logs[1])["data"][0],
+            serde_json::json!({"owner_id": "alice.near", "amount": "10", "memo": "refund"})
+        );
+    }
+
+    #[test]
+    fn liquidity_added_event_format() {
+        testing_env!(new_context("bob.near"));
+        liquidity_added(10.into(), 30.into(), NearLiquidityInflow::StakeBatch);
+
+        let event = event_json(&get_logs()[0]);
+        assert_eq!(event["standard"], STAKE_STANDARD);
+        assert_eq!(event["event"], "liquidity_added");
+        assert_eq!(event["data"][0]["amount"], "10");
+        assert_eq!(event["data"][0]["balance"], "30");
+    }
+}
@@ -7,3 +7,52 @@
 pub const ACCOUNTS_KEY_PREFIX: [u8; 1] = [0];
 pub const STAKE_BATCH_RECEIPTS_KEY_PREFIX: [u8; 1] = [1];
 pub const REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX: [u8; 1] = [2];
+pub const STAKE_TOKEN_VALUE_CHECKPOINTS_KEY_PREFIX: [u8; 1] = [3];
+pub const FINANCIALS_HISTORY_KEY_PREFIX: [u8; 1] = [4];
+pub const LOCK_HISTORY_KEY_PREFIX: [u8; 1] = [5];
+pub const QUEUED_WITHDRAWALS_KEY_PREFIX: [u8; 1] = [6];
+pub const REBATE_ROUNDS_KEY_PREFIX: [u8; 1] = [7];
+pub const AUTO_REGISTRATION_WHITELIST_KEY_PREFIX: [u8; 1] = [8];
+pub const POOL_PREFERENCE_TALLIES_KEY_PREFIX: [u8; 1] = [9];
+pub const REDEEM_FORWARDING_TARGETS_KEY_PREFIX: [u8; 1] = [10];
+pub const STORAGE_DELTAS_KEY_PREFIX: [u8; 1] = [11];
+pub const SWAP_DEPLOYMENTS_KEY_PREFIX: [u8; 1] = [12];
+pub const SWAP_INTENTS_KEY_PREFIX: [u8; 1] = [13];
+pub const INVOICES_KEY_PREFIX: [u8; 1] = [14];
+pub const BATCH_RESERVATIONS_KEY_PREFIX: [u8; 1] = [15];
+pub const STAKING_POOL_GAS_USAGE_KEY_PREFIX: [u8; 1] = [16];
+pub const BATCH_AUDITS_KEY_PREFIX: [u8; 1] = [17];
+pub const BATCH_CONTRIBUTORS_KEY_PREFIX: [u8; 1] = [18];
+pub const BATCH_CONTRIBUTORS_LEN_KEY_PREFIX: [u8; 1] = [19];
+pub const BATCH_CONTRIBUTOR_SET_KEY_PREFIX: [u8; 1] = [20];
+pub const FEATURE_ROLLOUTS_KEY_PREFIX: [u8; 1] = [21];
+pub const MIGRATIONS_KEY_PREFIX: [u8; 1] = [22];
+pub const RECEIVER_AUTHORIZATIONS_KEY_PREFIX: [u8; 1] = [23];
+pub const REDEEM_SPLITS_KEY_PREFIX: [u8; 1] = [24];
+pub const STORAGE_REFUNDS_KEY_PREFIX: [u8; 1] = [25];
+pub const REFERRAL_CODES_KEY_PREFIX: [u8; 1] = [26];
+pub const EXIT_POSITIONS_KEY_PREFIX: [u8; 1] = [27];
+pub const ACCOUNT_IDS_KEY_PREFIX: [u8; 1] = [28];
+pub const ACCOUNT_ID_INDEX_KEY_PREFIX: [u8; 1] = [29];
+pub const PENDING_TRANSFERS_KEY_PREFIX: [u8; 1] = [30];
+pub const ACCOUNT_ACTIVITY_LOGS_KEY_PREFIX: [u8; 1] = [31];
+pub const STAKE_BATCH_HISTORY_KEY_PREFIX: [u8; 1] = [32];
+pub const REDEEM_BATCH_HISTORY_KEY_PREFIX: [u8; 1] = [33];
+pub const OPERATOR_PROPOSALS_KEY_PREFIX: [u8; 1] = [34];
+
+/// singleton key for the persisted [StateVersion](crate::domain::StateVersion) - it is stored
+/// separately from the contract state in order for it to be read before the contract state is
+/// deserialized, i.e., when the contract state layout is out of date
+pub const STATE_VERSION_KEY: [u8; 1] = [35];
+
+pub const LOCKUPS_KEY_PREFIX: [u8; 1] = [36];
+
+pub const LIQUIDITY_PROVIDER_SHARES_KEY_PREFIX: [u8; 1] = [37];
+
+/// versioned records are stored separately from the untagged records that were persisted before the
+/// record types were versioned, because untagged records can not be told apart from tagged records
+/// - the untagged records remain under the original key prefixes, i.e., [ACCOUNTS_KEY_PREFIX],
+///   [STAKE_BATCH_RECEIPTS_KEY_PREFIX], and [REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX]
+pub const VERSIONED_ACCOUNTS_KEY_PREFIX: [u8; 1] = [38];
+pub const VERSIONED_STAKE_BATCH_RECEIPTS_KEY_PREFIX: [u8; 1] = [39];
+pub const VERSIONED_REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX: [u8; 1] = [40];
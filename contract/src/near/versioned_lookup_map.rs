@@ -0,0 +1,215 @@
+use crate::domain::{LegacyVersioned, Versioned};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    collections::LookupMap,
+};
+use std::cell::Cell;
+
+thread_local! {
+    static RECORDS_TOUCHED: Cell<u32> = Cell::new(0);
+}
+
+/// returns the number of records that have been written or removed across all [VersionedLookupMap]
+/// instances within the current contract call
+/// - used to collect storage diagnostics - see [StorageDelta](crate::domain::StorageDelta)
+pub fn records_touched() -> u32 {
+    RECORDS_TOUCHED.with(|count| count.get())
+}
+
+fn record_touched() {
+    RECORDS_TOUCHED.with(|count| count.set(count.get().saturating_add(1)));
+}
+
+/// [LookupMap] that persists values as versioned records - see [Versioned]
+/// - values are upgraded to the latest version when they are read
+/// - values are always written using the latest version
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct VersionedLookupMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize,
+    V: Versioned,
+{
+    map: LookupMap<K, V::Record>,
+}
+
+impl<K, V> VersionedLookupMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize,
+    V: Versioned,
+{
+    pub fn new(key_prefix: Vec<u8>) -> Self {
+        Self {
+            map: LookupMap::new(key_prefix),
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.map.get(key).map(V::from_record)
+    }
+
+    /// returns the record as persisted, i.e., without upgrading it to the latest version
+    pub fn get_record(&self, key: &K) -> Option<V::Record> {
+        self.map.get(key)
+    }
+
+    /// returns the previous value
+    pub fn insert(&mut self, key: &K, value: &V) -> Option<V> {
+        record_touched();
+        self.map.insert(key, &value.to_record()).map(V::from_record)
+    }
+
+    /// writes the record as is - used to persist records that are not written using the latest
+    /// version, e.g., tombstones
+    /// - returns the previous value
+    pub fn insert_record(&mut self, key: &K, record: &V::Record) -> Option<V> {
+        record_touched();
+        self.map.insert(key, record).map(V::from_record)
+    }
+
+    /// returns the removed value
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        record_touched();
+        self.map.remove(key).map(V::from_record)
+    }
+
+    /// rewrites the record using the latest record version
+    /// - returns false if there is no record for the key
+    pub fn upgrade(&mut self, key: &K) -> bool {
+        match self.get(key) {
+            Some(value) => {
+                self.insert(key, &value);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// [VersionedLookupMap] for records that were persisted without a version tag before the record type
+/// was versioned - see [LegacyVersioned]
+/// - versioned records are stored under their own key prefix, because untagged records can not be
+///   told apart from tagged records
+/// - untagged records are decoded using the legacy layout when they are read, and are moved to the
+///   versioned key prefix the first time they are written, i.e., a key is only ever stored under
+///   one of the key prefixes
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct LegacyVersionedLookupMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize,
+    V: LegacyVersioned,
+{
+    versioned: VersionedLookupMap<K, V>,
+    legacy: LookupMap<K, V::Legacy>,
+}
+
+impl<K, V> LegacyVersionedLookupMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize,
+    V: LegacyVersioned,
+{
+    pub fn new(key_prefix: Vec<u8>, legacy_key_prefix: Vec<u8>) -> Self {
+        Self {
+            versioned: VersionedLookupMap::new(key_prefix),
+            legacy: LookupMap::new(legacy_key_prefix),
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.versioned.contains_key(key) || self.legacy.contains_key(key)
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.versioned
+            .get(key)
+            .or_else(|| self.legacy.get(key).map(V::from_legacy))
+    }
+
+    /// returns the versioned record as persisted
+    /// - returns None for untagged legacy records
+    pub fn get_record(&self, key: &K) -> Option<V::Record> {
+        self.versioned.get_record(key)
+    }
+
+    /// returns the previous value
+    pub fn insert(&mut self, key: &K, value: &V) -> Option<V> {
+        let previous = self.versioned.insert(key, value);
+        previous.or_else(|| self.remove_legacy(key))
+    }
+
+    /// see [VersionedLookupMap::insert_record]
+    pub fn insert_record(&mut self, key: &K, record: &V::Record) -> Option<V> {
+        let previous = self.versioned.insert_record(key, record);
+        previous.or_else(|| self.remove_legacy(key))
+    }
+
+    /// returns the removed value
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let removed = self.versioned.remove(key);
+        removed.or_else(|| self.remove_legacy(key))
+    }
+
+    /// rewrites the record using the latest record version - legacy records are moved to the
+    /// versioned key prefix
+    /// - returns false if there is no record for the key
+    pub fn upgrade(&mut self, key: &K) -> bool {
+        match self.get(key) {
+            Some(value) => {
+                self.insert(key, &value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn remove_legacy(&mut self, key: &K) -> Option<V> {
+        self.legacy.remove(key).map(V::from_legacy)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::Hash;
+    use crate::domain::{Account, LegacyAccount, TimestampedNearBalance};
+    use crate::test_utils::new_context;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn legacy_records_are_moved_when_written() {
+        testing_env!(new_context("bob.near"));
+        let key = Hash::from("alice.near");
+        let mut legacy = LookupMap::<Hash, LegacyAccount>::new(b"L".to_vec());
+        legacy.insert(
+            &key,
+            &LegacyAccount {
+                storage_escrow: TimestampedNearBalance::new(100.into()),
+                near: None,
+                stake: None,
+                stake_batch: None,
+                next_stake_batch: None,
+                redeem_stake_batch: None,
+                next_redeem_stake_batch: None,
+            },
+        );
+
+        let mut map = LegacyVersionedLookupMap::<Hash, Account>::new(b"V".to_vec(), b"L".to_vec());
+        assert!(map.contains_key(&key));
+        assert!(map.get_record(&key).is_none());
+        let mut account = map.get(&key).unwrap();
+        assert_eq!(account.storage_escrow.amount(), 100.into());
+
+        account.nonce = 1;
+        let previous = map.insert(&key, &account).unwrap();
+        assert_eq!(previous.nonce, 0);
+        assert!(!legacy.contains_key(&key));
+        assert!(map.get_record(&key).is_some());
+        assert_eq!(map.get(&key).unwrap().nonce, 1);
+
+        assert!(map.remove(&key).is_some());
+        assert!(!map.contains_key(&key));
+    }
+}
@@ -1,5 +1,7 @@
 #![allow(dead_code)]
 
+pub mod replay;
+
 use crate::interface::AccountManagement;
 use crate::near_env::Env;
 use crate::{near::*, Contract};
@@ -25,7 +27,7 @@ pub fn to_valid_account_id(account_id: &str) -> ValidAccountId {
 }
 
 const TEST_ACCOUNT_ID: &str = "oysterpack.near";
-const TEST_STAKING_POOL_ID: &str = "staking-pool.near";
+pub const TEST_STAKING_POOL_ID: &str = "staking-pool.near";
 pub const TEST_OWNER_ID: &str = "owner.stake.oysterpack.near";
 pub const TEST_OPERATOR_ID: &str = "operator.stake.oysterpack.near";
 
@@ -39,6 +41,8 @@ impl<'a> TestContext<'a> {
             to_valid_account_id(TEST_STAKING_POOL_ID),
             to_valid_account_id(TEST_OWNER_ID),
             to_valid_account_id(TEST_OPERATOR_ID),
+            None,
+            None,
         );
 
         Self {
@@ -62,6 +66,8 @@ impl<'a> TestContext<'a> {
             to_valid_account_id(TEST_STAKING_POOL_ID),
             to_valid_account_id(TEST_OWNER_ID),
             to_valid_account_id(TEST_OPERATOR_ID),
+            None,
+            None,
         );
 
         context.attached_deposit = YOCTO;
@@ -103,6 +109,14 @@ impl<'a> TestContext<'a> {
         context.predecessor_account_id = account_id.to_string();
         context
     }
+
+    /// credits STAKE to the registered account, and keeps the total STAKE supply in sync
+    pub fn credit_stake(&mut self, account_id: &str, amount: u128) {
+        let mut account = self.contract.registered_account(account_id);
+        account.apply_stake_credit(amount.into());
+        self.contract.save_registered_account(&account);
+        self.contract.total_stake.credit(amount.into());
+    }
 }
 
 impl<'a> Deref for TestContext<'a> {
@@ -0,0 +1,246 @@
+//! Deterministic replay of the contract event logs.
+//!
+//! The contract logs structured events using [log](crate::near::log), which formats the event
+//! using its pretty printed `Debug` representation, e.g.,
+//! ```text
+//! StakeBatch {
+//!     batch_id: 1,
+//!     near: 100,
+//! }
+//! ```
+//!
+//! [EventReplay] reconstructs the contract aggregates from the event stream alone, i.e., the same
+//! way an indexer would. Asserting the replayed state against the actual contract state proves the
+//! event stream is complete, and catches any state mutation that forgets to emit an event.
+//!
+//! NOTE: the logs are reset each time `testing_env!` is invoked - thus [EventReplay::collect] must
+//! be invoked after each contract call and before the VM context is reset.
+
+use crate::Contract;
+use near_sdk::test_utils::get_logs;
+use std::collections::{BTreeMap, HashMap};
+
+/// event parsed from the log - only top level scalar fields are captured
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub name: String,
+    pub fields: HashMap<String, String>,
+}
+
+impl Event {
+    /// returns None if the log is not a structured event
+    pub fn parse(log: &str) -> Option<Self> {
+        let mut lines = log.lines();
+        let name = lines.next()?.strip_suffix(" {")?;
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        let fields = lines
+            .filter(|line| line.starts_with("    ") && !line.starts_with("     "))
+            .filter_map(|line| {
+                let line = line.trim().trim_end_matches(',');
+                let mut field = line.splitn(2, ": ");
+                let key = field.next()?;
+                let value = field.next()?;
+                // nested values are not captured
+                if value.ends_with('{') || value.ends_with('[') || value.ends_with('(') {
+                    return None;
+                }
+                Some((key.to_string(), value.trim_matches('"').to_string()))
+            })
+            .collect();
+        Some(Self {
+            name: name.to_string(),
+            fields,
+        })
+    }
+
+    /// ## Panics
+    /// if the field does not exist or is not a number
+    pub fn u128(&self, field: &str) -> u128 {
+        self.fields
+            .get(field)
+            .unwrap_or_else(|| panic!("{} event has no `{}` field", self.name, field))
+            .parse()
+            .unwrap_or_else(|_| panic!("{}.{} is not a number", self.name, field))
+    }
+}
+
+/// contract aggregates reconstructed from the event stream
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayedState {
+    pub stake_supply: u128,
+    pub near_liquidity_pool: u128,
+    /// open stake batches, i.e., the current and next stake batches: batch ID -> NEAR balance
+    pub stake_batches: BTreeMap<u128, u128>,
+}
+
+impl ReplayedState {
+    /// extracts the replayed aggregates from the actual contract state
+    pub fn from_contract(contract: &Contract) -> Self {
+        Self {
+            stake_supply: contract.total_stake.amount().value(),
+            near_liquidity_pool: contract.near_liquidity_pool.value(),
+            stake_batches: contract
+                .stake_batch
+                .iter()
+                .chain(contract.next_stake_batch.iter())
+                .map(|batch| (batch.id().value(), batch.balance().amount().value()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct EventReplay {
+    events: Vec<Event>,
+    state: ReplayedState,
+}
+
+impl EventReplay {
+    /// collects the events that were logged since the VM context was last reset and applies them
+    pub fn collect(&mut self) {
+        for log in get_logs() {
+            if let Some(event) = Event::parse(&log) {
+                self.apply(event);
+            }
+        }
+    }
+
+    pub fn apply(&mut self, event: Event) {
+        let state = &mut self.state;
+        match event.name.as_str() {
+            "StakeBatch" => {
+                state
+                    .stake_batches
+                    .insert(event.u128("batch_id"), event.u128("near"));
+            }
+            "StakeBatchCancelled" => {
+                state.stake_batches.remove(&event.u128("batch_id"));
+            }
+            "Staked" => {
+                state.stake_batches.remove(&event.u128("batch_id"));
+                state.stake_supply += event.u128("stake");
+            }
+            "Unstaked" | "TransferRefundBurned" => {
+                let amount = if event.name == "Unstaked" {
+                    event.u128("stake")
+                } else {
+                    event.u128("amount")
+                };
+                state.stake_supply = state
+                    .stake_supply
+                    .checked_sub(amount)
+                    .unwrap_or_else(|| panic!("replayed STAKE supply underflow: {:?}", event));
+            }
+            "NearLiquidityAdded" | "NearLiquidityConsumed" => {
+                state.near_liquidity_pool = event.u128("balance");
+            }
+            _ => {}
+        }
+        self.events.push(event);
+    }
+
+    /// events that have been applied in the order they were logged
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn state(&self) -> &ReplayedState {
+        &self.state
+    }
+
+    /// ## Panics
+    /// if the replayed state does not match the actual contract state
+    pub fn assert_matches(&self, contract: &Contract) {
+        assert_eq!(
+            self.state,
+            ReplayedState::from_contract(contract),
+            "replayed state does not match the contract state - events: {:#?}",
+            self.events
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::StakeLock;
+    use crate::interface::{StakingService, YoctoNear};
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain, VMContext};
+
+    fn deposit(ctx: &mut TestContext, amount: u128) {
+        let mut context = ctx.context.clone();
+        context.attached_deposit = amount;
+        testing_env!(context);
+        ctx.deposit(None);
+    }
+
+    fn self_invoked(ctx: &TestContext) -> VMContext {
+        let mut context = ctx.context.clone();
+        context.predecessor_account_id = context.current_account_id.clone();
+        context
+    }
+
+    #[test]
+    fn parse_event() {
+        let event = Event::parse("Staked {\n    batch_id: 1,\n    near: 100,\n    stake_token_value: StakeTokenValue {\n        total_stake_supply: 5,\n    },\n    staking_pool_id: \"staking-pool.near\",\n}").unwrap();
+        assert_eq!(event.name, "Staked");
+        assert_eq!(event.u128("batch_id"), 1);
+        assert_eq!(event.u128("near"), 100);
+        assert_eq!(event.fields["staking_pool_id"], "staking-pool.near");
+        assert!(!event.fields.contains_key("stake_token_value"));
+        assert!(!event.fields.contains_key("total_stake_supply"));
+
+        assert!(Event::parse("unused amount: 100").is_none());
+    }
+
+    #[test]
+    fn replay_stake_batch_workflow() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut replay = EventReplay::default();
+
+        deposit(&mut ctx, 100 * YOCTO);
+        replay.collect();
+        replay.assert_matches(&ctx);
+
+        // while the batch is being staked, deposits are added to the next batch
+        ctx.stake_batch_lock = Some(StakeLock::Staked {
+            near_liquidity: None,
+            staked_balance: (100 * YOCTO).into(),
+            unstaked_balance: 0.into(),
+        });
+        deposit(&mut ctx, 10 * YOCTO);
+        replay.collect();
+        replay.assert_matches(&ctx);
+        assert_eq!(replay.state().stake_batches.len(), 2);
+
+        testing_env!(self_invoked(&ctx));
+        ctx.process_staked_batch();
+        replay.collect();
+        replay.assert_matches(&ctx);
+        assert_eq!(replay.state().stake_supply, 100 * YOCTO);
+        assert_eq!(replay.state().stake_batches.len(), 1);
+
+        // withdrawing all funds from the batch cancels the batch
+        testing_env!(ctx.context.clone());
+        assert_eq!(ctx.withdraw_all_from_stake_batch(), YoctoNear(10 * YOCTO));
+        replay.collect();
+        replay.assert_matches(&ctx);
+        assert!(replay.state().stake_batches.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "replayed state does not match the contract state")]
+    fn unlogged_mutation_is_detected() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut replay = EventReplay::default();
+
+        deposit(&mut ctx, 100 * YOCTO);
+        replay.collect();
+        ctx.near_liquidity_pool = YOCTO.into();
+        replay.assert_matches(&ctx);
+    }
+}
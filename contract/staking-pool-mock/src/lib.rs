@@ -21,6 +21,8 @@ static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct StakingPool {
     accounts: LookupMap<String, StakingPoolAccount>,
+    /// when paused, requests to stake are rejected
+    paused: bool,
 }
 
 /// staking pool interface that STAKE token contract depends on
@@ -30,6 +32,7 @@ impl StakingPool {
     pub fn new() -> Self {
         Self {
             accounts: LookupMap::new(vec![1]),
+            paused: false,
         }
     }
 
@@ -50,6 +53,7 @@ impl StakingPool {
 
     pub fn stake(&mut self, amount: U128) {
         log!("StakingPool::stake()");
+        assert!(!self.paused, "staking is paused");
         let mut account = self.get_account(env::predecessor_account_id());
         account.unstaked_balance = (account.unstaked_balance.0 - amount.0).into();
         account.staked_balance = (account.staked_balance.0 + amount.0).into();
@@ -99,6 +103,18 @@ impl StakingPool {
     pub fn update_account(&mut self, account: StakingPoolAccount) {
         self.save_account(&account);
     }
+
+    pub fn pause_staking(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume_staking(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_staking_paused(&self) -> bool {
+        self.paused
+    }
 }
 
 impl StakingPool {
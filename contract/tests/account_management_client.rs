@@ -1,11 +1,13 @@
 #![allow(dead_code)]
 
 use near_sdk::json_types::U128;
-use near_sdk::{serde_json::json, AccountId, PendingContractTx};
+use near_sdk::{AccountId, PendingContractTx};
 use near_sdk_sim::*;
+use oysterpack_near_stake_token::client;
 use oysterpack_near_stake_token::domain::{Gas, YoctoNear};
 use oysterpack_near_stake_token::interface::StakeAccount;
 use oysterpack_near_stake_token::near::NO_DEPOSIT;
+use std::convert::TryInto;
 
 pub struct AccountManagementClient {
     contract_account_id: AccountId,
@@ -28,7 +30,7 @@ impl AccountManagementClient {
             PendingContractTx::new(
                 &self.contract_account_id,
                 "register_account",
-                json!({}),
+                client::no_args(),
                 false,
             ),
             deposit.value(),
@@ -43,7 +45,7 @@ impl AccountManagementClient {
             PendingContractTx::new(
                 &self.contract_account_id,
                 "unregister_account",
-                json!({}),
+                client::no_args(),
                 false,
             ),
             NO_DEPOSIT.value(),
@@ -57,7 +59,7 @@ impl AccountManagementClient {
         let result = user.view(PendingContractTx::new(
             &self.contract_account_id,
             "account_storage_fee",
-            json!({}),
+            client::no_args(),
             true,
         ));
 
@@ -69,7 +71,7 @@ impl AccountManagementClient {
         let result = user.view(PendingContractTx::new(
             &self.contract_account_id,
             "account_registered",
-            json!({ "account_id": account_id }),
+            client::account_management::account_registered(account_id.try_into().unwrap()),
             true,
         ));
 
@@ -80,7 +82,7 @@ impl AccountManagementClient {
         let result = user.view(PendingContractTx::new(
             &self.contract_account_id,
             "total_registered_accounts",
-            json!({}),
+            client::no_args(),
             true,
         ));
 
@@ -92,7 +94,7 @@ impl AccountManagementClient {
         let result = user.view(PendingContractTx::new(
             &self.contract_account_id,
             "lookup_account",
-            json!({ "account_id": account_id }),
+            client::account_management::lookup_account(account_id.try_into().unwrap()),
             true,
         ));
 
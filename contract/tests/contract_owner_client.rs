@@ -1,6 +1,7 @@
+use near_sdk::PendingContractTx;
 use near_sdk::{json_types::U128, AccountId};
-use near_sdk::{serde_json::json, PendingContractTx};
 use near_sdk_sim::*;
+use oysterpack_near_stake_token::client;
 use oysterpack_near_stake_token::domain::{Gas, YoctoNear};
 use oysterpack_near_stake_token::interface::StakeAccount;
 use oysterpack_near_stake_token::near::NO_DEPOSIT;
@@ -9,7 +10,7 @@ pub fn owner_id(contract_account_id: &str, user: &UserAccount) -> AccountId {
     let result = user.view(PendingContractTx::new(
         contract_account_id,
         "owner_id",
-        json!({}),
+        client::no_args(),
         true,
     ));
 
@@ -8,18 +8,23 @@
 //!
 //! cd staking-pool-mock
 //! ./build.sh
+//!
+//! cd ../ft-transfer-receiver-example
+//! ./build.sh
 //! ```
 
 extern crate oysterpack_near_stake_token;
 
 mod account_management_client;
 mod financials_client;
+mod fungible_token_client;
 mod operator_client;
 mod staking_pool_client;
 mod staking_service_client;
 mod test_utils;
 
 use near_sdk::{
+    json_types::U128,
     serde_json::{self, json},
     PendingContractTx,
 };
@@ -37,6 +42,7 @@ use oysterpack_near_stake_token::{
 
 use account_management_client::*;
 use financials_client::*;
+use oysterpack_near_stake_token::client::{self, fungible_token::TransferCallArgs};
 use oysterpack_near_stake_token::core::U256;
 use staking_service_client::*;
 
@@ -80,6 +86,164 @@ fn sim_test() {
     // check_user_accounts_after_redeem_stake_batch_completed(&ctx);
 }
 
+#[test]
+fn staking_pool_paused_sim_test() {
+    let ctx = test_utils::create_context();
+
+    register_user_accounts(&ctx);
+    deposit_funds_for_each_user_account(&ctx);
+
+    // when the staking pool rejects the deposit, the stake batch workflow fails
+    ctx.staking_pool.pause_staking(&ctx.master_account);
+    let result = ctx.staking_service.stake(&ctx.contract_operator);
+    ctx.process_all_transactions();
+    println!("stake receipt results: {:#?}", result.get_receipt_results());
+
+    // then the staking pool is marked unavailable and the stake batch is retained
+    let staking_pool_info = ctx.staking_service.staking_pool_info(&ctx.master_account);
+    assert!(!staking_pool_info.available);
+    assert!(staking_pool_info.unavailable_until.is_some());
+    let contract_state = ctx.operator.contract_state(&ctx.master_account);
+    assert!(contract_state.stake_batch.is_some());
+    assert!(contract_state.stake_batch_lock.is_none());
+
+    // while the backoff period is in effect, stake batches are not run
+    let result = ctx.staking_service.stake(&ctx.contract_operator);
+    match result.status() {
+        ExecutionStatus::Failure(err) => {
+            assert!(err.to_string().contains("staking pool is unavailable"))
+        }
+        _ => panic!("expected stake to fail while the staking pool is unavailable"),
+    }
+
+    // once the backoff period expires, the stake batch is retried
+    ctx.staking_pool.resume_staking(&ctx.master_account);
+    let epoch_height: u64 = staking_pool_info.unavailable_until.unwrap().0.into();
+    while ctx.runtime.borrow().current_block().epoch_height < epoch_height {
+        ctx.runtime.borrow_mut().produce_block().unwrap();
+    }
+    stake(&ctx);
+    let staking_pool_info = ctx.staking_service.staking_pool_info(&ctx.master_account);
+    assert!(staking_pool_info.available);
+    assert!(staking_pool_info.unavailable_until.is_none());
+}
+
+/// exercises the `ft_resolve_transfer_call` edge cases against the reference receiver contract
+#[test]
+fn ft_transfer_call_sim_test() {
+    let ctx = test_utils::create_context();
+
+    register_user_accounts(&ctx);
+    deposit_funds_for_each_user_account(&ctx);
+    stake(&ctx);
+
+    let vault = deploy_stake_vault(&ctx, YOCTO);
+    let user = ctx.users.get("user-3").unwrap();
+    let vault_id = to_valid_account_id(&vault.account_id());
+    let stake_balance = |account_id: &str| {
+        ctx.fungible_token
+            .ft_balance_of(&ctx.master_account, account_id)
+            .value()
+    };
+    let initial_user_balance = stake_balance(&user.account_id());
+    assert!(initial_user_balance > 2 * YOCTO);
+
+    // partial use - the vault only accepts STAKE up to its capacity and returns the unused amount
+    let result = ctx.fungible_token.ft_transfer_call(
+        user,
+        TransferCallArgs::new(vault_id.clone(), (2 * YOCTO).into(), "\"Deposit\"".into()),
+    );
+    result.assert_success();
+    ctx.process_all_transactions();
+    assert_eq!(stake_balance(&vault.account_id()), YOCTO);
+    assert_eq!(
+        stake_balance(&user.account_id()),
+        initial_user_balance - YOCTO
+    );
+    let deposit: U128 = vault
+        .view(PendingContractTx::new(
+            &vault.account_id(),
+            "deposit_of",
+            json!({ "account_id": user.account_id() }),
+            true,
+        ))
+        .unwrap_json();
+    assert_eq!(deposit.0, YOCTO);
+
+    // over-return - the refund is capped at the transfer amount, i.e., the receiver's balance that
+    // was accepted from prior transfers is not drained
+    let result = ctx.fungible_token.ft_transfer_call(
+        user,
+        TransferCallArgs::new(
+            vault_id.clone(),
+            (YOCTO / 2).into(),
+            r#"{"OverReturn":{"extra":"1000000000000000000000000"}}"#.into(),
+        ),
+    );
+    result.assert_success();
+    ctx.process_all_transactions();
+    assert_eq!(stake_balance(&vault.account_id()), YOCTO);
+    assert_eq!(
+        stake_balance(&user.account_id()),
+        initial_user_balance - YOCTO
+    );
+
+    // panic - the full transfer amount is refunded
+    let result = ctx.fungible_token.ft_transfer_call(
+        user,
+        TransferCallArgs::new(vault_id, (YOCTO / 2).into(), "\"Panic\"".into()),
+    );
+    result.assert_success();
+    ctx.process_all_transactions();
+    assert_eq!(stake_balance(&vault.account_id()), YOCTO);
+    assert_eq!(
+        stake_balance(&user.account_id()),
+        initial_user_balance - YOCTO
+    );
+}
+
+/// deploys the reference `ft_on_transfer` receiver and registers it with the STAKE contract
+fn deploy_stake_vault(ctx: &TestContext, capacity: u128) -> UserAccount {
+    let vault = ctx.master_account.deploy(
+        &FT_TRANSFER_RECEIVER_EXAMPLE_WASM_BYTES,
+        "stake-vault".to_string(),
+        100 * YOCTO,
+    );
+    vault
+        .call(
+            PendingContractTx::new(
+                &vault.account_id(),
+                "new",
+                json!({
+                    "token_contract": ctx.contract_account_id(),
+                    "capacity": U128(capacity),
+                }),
+                false,
+            ),
+            NO_DEPOSIT.value(),
+            TGAS.value() * 10,
+        )
+        .assert_success();
+
+    let account_storage_fee = ctx
+        .account_management
+        .account_storage_fee(ctx.master_account());
+    vault
+        .call(
+            PendingContractTx::new(
+                &vault.account_id(),
+                "register_with_token_contract",
+                client::no_args(),
+                false,
+            ),
+            account_storage_fee.value(),
+            TGAS.value() * 20,
+        )
+        .assert_success();
+    ctx.process_all_transactions();
+    vault
+}
+
 fn check_user_accounts_after_redeem_stake_batch_completed(ctx: &TestContext) {
     println!("###############################################################");
     println!("### check_user_accounts_after_redeem_stake_batch_completed ####");
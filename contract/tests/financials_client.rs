@@ -1,10 +1,11 @@
 #![allow(dead_code)]
 
-use near_sdk::{serde_json::json, AccountId, PendingContractTx};
+use near_sdk::{AccountId, PendingContractTx};
 use near_sdk_sim::*;
+use oysterpack_near_stake_token::client;
 use oysterpack_near_stake_token::{
     domain::{Gas, YoctoNear},
-    interface::{Config, ContractBalances},
+    interface::{Config, ContractBalances, FinancialsSnapshot},
 };
 
 pub struct FinancialsClient {
@@ -22,7 +23,7 @@ impl FinancialsClient {
         let result = user.view(PendingContractTx::new(
             &self.contract_account_id,
             "balances",
-            json!({}),
+            client::no_args(),
             true,
         ));
 
@@ -39,7 +40,7 @@ impl FinancialsClient {
             PendingContractTx::new(
                 &self.contract_account_id,
                 "deposit_earnings",
-                json!({}),
+                client::no_args(),
                 false,
             ),
             deposit.value(),
@@ -48,4 +49,20 @@ impl FinancialsClient {
 
         result.unwrap_json()
     }
+
+    pub fn financials_history(
+        &self,
+        user: &UserAccount,
+        from: u64,
+        limit: u32,
+    ) -> Vec<FinancialsSnapshot> {
+        let result = user.view(PendingContractTx::new(
+            &self.contract_account_id,
+            "financials_history",
+            client::financials::financials_history(from, limit),
+            true,
+        ));
+
+        result.unwrap_json()
+    }
 }
@@ -0,0 +1,46 @@
+#![allow(dead_code)]
+
+use near_sdk::{AccountId, PendingContractTx};
+use near_sdk_sim::*;
+use oysterpack_near_stake_token::client;
+use oysterpack_near_stake_token::client::fungible_token::TransferCallArgs;
+use oysterpack_near_stake_token::domain::TGAS;
+use oysterpack_near_stake_token::interface::TokenAmount;
+use std::convert::TryInto;
+
+pub struct FungibleTokenClient {
+    contract_account_id: AccountId,
+}
+
+impl FungibleTokenClient {
+    pub fn new(contract_account_id: &str) -> Self {
+        Self {
+            contract_account_id: contract_account_id.to_string(),
+        }
+    }
+
+    pub fn ft_balance_of(&self, user: &UserAccount, account_id: &str) -> TokenAmount {
+        let result = user.view(PendingContractTx::new(
+            &self.contract_account_id,
+            "ft_balance_of",
+            client::fungible_token::ft_balance_of(account_id.try_into().unwrap()),
+            true,
+        ));
+        result.unwrap_json()
+    }
+
+    pub fn ft_transfer_call(&self, user: &UserAccount, args: TransferCallArgs) -> ExecutionResult {
+        let result = user.call(
+            PendingContractTx::new(
+                &self.contract_account_id,
+                "ft_transfer_call",
+                args.to_json(),
+                false,
+            ),
+            1,
+            TGAS.value() * 100,
+        );
+        println!("ft_transfer_call: {:#?}", result);
+        result
+    }
+}
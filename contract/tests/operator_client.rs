@@ -1,6 +1,9 @@
-use near_sdk::{serde_json::json, AccountId, PendingContractTx};
+use near_sdk::{AccountId, PendingContractTx};
 use near_sdk_sim::*;
-use oysterpack_near_stake_token::interface::contract_state::ContractState;
+use oysterpack_near_stake_token::client;
+use oysterpack_near_stake_token::interface::contract_state::{
+    ContractState, StateBalances, StateBatches, StateConfig, StateLocks,
+};
 use oysterpack_near_stake_token::interface::Config;
 
 pub struct OperatorClient {
@@ -18,7 +21,7 @@ impl OperatorClient {
         let result = user.view(PendingContractTx::new(
             &self.contract_account_id,
             "contract_state",
-            json!({}),
+            client::no_args(),
             true,
         ));
 
@@ -34,7 +37,51 @@ impl OperatorClient {
         let result = user.view(PendingContractTx::new(
             &self.contract_account_id,
             "config",
-            json!({}),
+            client::no_args(),
+            true,
+        ));
+
+        result.unwrap_json()
+    }
+
+    pub fn state_balances(&self, user: &UserAccount) -> StateBalances {
+        let result = user.view(PendingContractTx::new(
+            &self.contract_account_id,
+            "state_balances",
+            client::no_args(),
+            true,
+        ));
+
+        result.unwrap_json()
+    }
+
+    pub fn state_batches(&self, user: &UserAccount) -> StateBatches {
+        let result = user.view(PendingContractTx::new(
+            &self.contract_account_id,
+            "state_batches",
+            client::no_args(),
+            true,
+        ));
+
+        result.unwrap_json()
+    }
+
+    pub fn state_config(&self, user: &UserAccount) -> StateConfig {
+        let result = user.view(PendingContractTx::new(
+            &self.contract_account_id,
+            "state_config",
+            client::no_args(),
+            true,
+        ));
+
+        result.unwrap_json()
+    }
+
+    pub fn state_locks(&self, user: &UserAccount) -> StateLocks {
+        let result = user.view(PendingContractTx::new(
+            &self.contract_account_id,
+            "state_locks",
+            client::no_args(),
             true,
         ));
 
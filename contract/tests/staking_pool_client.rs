@@ -46,6 +46,24 @@ impl StakingPoolClient {
         );
         result.assert_success();
     }
+
+    pub fn pause_staking(&self, user: &UserAccount) {
+        let result = user.call(
+            PendingContractTx::new(&self.staking_pool_id, "pause_staking", json!({}), false),
+            0,
+            TGAS.value() * 10,
+        );
+        result.assert_success();
+    }
+
+    pub fn resume_staking(&self, user: &UserAccount) {
+        let result = user.call(
+            PendingContractTx::new(&self.staking_pool_id, "resume_staking", json!({}), false),
+            0,
+            TGAS.value() * 10,
+        );
+        result.assert_success();
+    }
 }
 
 type Balance = near_sdk::json_types::U128;
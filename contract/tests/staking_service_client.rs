@@ -1,13 +1,18 @@
 #![allow(dead_code)]
 
-use near_sdk::{serde_json::json, AccountId, PendingContractTx};
+use near_sdk::{AccountId, PendingContractTx};
 use near_sdk_sim::*;
-use oysterpack_near_stake_token::interface::{BatchId, StakeBatchReceipt};
+use oysterpack_near_stake_token::client;
+use oysterpack_near_stake_token::interface::{
+    AccountReceipts, BatchId, QueuedWithdrawal, StakeBatchReceipt, StakeSupplyStats,
+    StakingPoolInfo,
+};
 use oysterpack_near_stake_token::near::NO_DEPOSIT;
 use oysterpack_near_stake_token::{
     domain::{YoctoNear, TGAS},
     interface,
 };
+use std::convert::TryInto;
 
 pub struct StakingServiceClient {
     pub contract_account_id: AccountId,
@@ -24,7 +29,55 @@ impl StakingServiceClient {
         let result = user.view(PendingContractTx::new(
             &self.contract_account_id,
             "staking_pool_id",
-            json!({}),
+            client::no_args(),
+            true,
+        ));
+        result.unwrap_json()
+    }
+
+    pub fn staking_pool_info(&self, user: &UserAccount) -> StakingPoolInfo {
+        let result = user.view(PendingContractTx::new(
+            &self.contract_account_id,
+            "staking_pool_info",
+            client::no_args(),
+            true,
+        ));
+        result.unwrap_json()
+    }
+
+    pub fn account_receipts(
+        &self,
+        user: &UserAccount,
+        account_id: &str,
+    ) -> Option<AccountReceipts> {
+        let result = user.view(PendingContractTx::new(
+            &self.contract_account_id,
+            "account_receipts",
+            client::staking_service::account_receipts(account_id.try_into().unwrap()),
+            true,
+        ));
+        result.unwrap_json()
+    }
+
+    pub fn stake_supply_stats(&self, user: &UserAccount) -> StakeSupplyStats {
+        let result = user.view(PendingContractTx::new(
+            &self.contract_account_id,
+            "stake_supply_stats",
+            client::no_args(),
+            true,
+        ));
+        result.unwrap_json()
+    }
+
+    pub fn queued_withdrawal(
+        &self,
+        user: &UserAccount,
+        account_id: &str,
+    ) -> Option<QueuedWithdrawal> {
+        let result = user.view(PendingContractTx::new(
+            &self.contract_account_id,
+            "queued_withdrawal",
+            client::staking_service::queued_withdrawal(account_id.try_into().unwrap()),
             true,
         ));
         result.unwrap_json()
@@ -38,7 +91,7 @@ impl StakingServiceClient {
         let result = user.view(PendingContractTx::new(
             &self.contract_account_id,
             "stake_batch_receipt",
-            json!({ "batch_id": batch_id }),
+            client::staking_service::stake_batch_receipt(batch_id),
             true,
         ));
         result.unwrap_json()
@@ -46,7 +99,12 @@ impl StakingServiceClient {
 
     pub fn deposit(&self, user: &UserAccount, amount: interface::YoctoNear) -> interface::BatchId {
         let result = user.call(
-            PendingContractTx::new(&self.contract_account_id, "deposit", json!({}), false),
+            PendingContractTx::new(
+                &self.contract_account_id,
+                "deposit",
+                client::no_args(),
+                false,
+            ),
             amount.value(),
             TGAS.value() * 10,
         );
@@ -56,7 +114,7 @@ impl StakingServiceClient {
 
     pub fn stake(&self, user: &UserAccount) -> ExecutionResult {
         let result = user.call(
-            PendingContractTx::new(&self.contract_account_id, "stake", json!({}), false),
+            PendingContractTx::new(&self.contract_account_id, "stake", client::no_args(), false),
             NO_DEPOSIT.value(),
             TGAS.value() * 200,
         );
@@ -69,7 +127,7 @@ impl StakingServiceClient {
             PendingContractTx::new(
                 &self.contract_account_id,
                 "claim_receipts",
-                json!({}),
+                client::no_args(),
                 false,
             ),
             NO_DEPOSIT.value(),
@@ -81,7 +139,12 @@ impl StakingServiceClient {
 
     pub fn redeem_all(&self, user: &UserAccount) -> Option<BatchId> {
         let result = user.call(
-            PendingContractTx::new(&self.contract_account_id, "redeem_all", json!({}), false),
+            PendingContractTx::new(
+                &self.contract_account_id,
+                "redeem_all",
+                client::no_args(),
+                false,
+            ),
             NO_DEPOSIT.value(),
             TGAS.value() * 10,
         );
@@ -91,7 +154,12 @@ impl StakingServiceClient {
 
     pub fn unstake(&self, user: &UserAccount) -> ExecutionResult {
         let result = user.call(
-            PendingContractTx::new(&self.contract_account_id, "unstake", json!({}), false),
+            PendingContractTx::new(
+                &self.contract_account_id,
+                "unstake",
+                client::no_args(),
+                false,
+            ),
             NO_DEPOSIT.value(),
             TGAS.value() * 150,
         );
@@ -7,6 +7,7 @@ use near_sdk_sim::*;
 
 use crate::account_management_client::AccountManagementClient;
 use crate::financials_client::FinancialsClient;
+use crate::fungible_token_client::FungibleTokenClient;
 use crate::operator_client::OperatorClient;
 use crate::staking_pool_client::StakingPoolClient;
 use crate::staking_service_client::StakingServiceClient;
@@ -28,6 +29,8 @@ lazy_static! {
         include_bytes!("../res/oysterpack_near_stake_token.wasm").as_ref();
     static ref STAKING_POOL_WASM_BYTES: &'static [u8] =
         include_bytes!("../res/staking_pool_mock.wasm").as_ref();
+    pub static ref FT_TRANSFER_RECEIVER_EXAMPLE_WASM_BYTES: &'static [u8] =
+        include_bytes!("../res/ft_transfer_receiver_example.wasm").as_ref();
 }
 
 pub struct TestContext {
@@ -45,6 +48,7 @@ pub struct TestContext {
     pub account_management: AccountManagementClient,
     pub operator: OperatorClient,
     pub financials: FinancialsClient,
+    pub fungible_token: FungibleTokenClient,
 }
 
 impl TestContext {
@@ -113,7 +117,7 @@ pub fn create_context() -> TestContext {
         // User deploying the contract,
         signer_account: master_account,
         // init method
-        init_method: new(staking_pool_id, contract_owner_id, contract_operator_id)
+        init_method: new(staking_pool_id, contract_owner_id, contract_operator_id, None, None)
     );
     let contract_account_id = contract.user_account.account_id();
 
@@ -144,6 +148,7 @@ pub fn create_context() -> TestContext {
     let account_management = AccountManagementClient::new(&contract_account_id);
     let operator = OperatorClient::new(&contract_account_id);
     let financials = FinancialsClient::new(&contract_account_id);
+    let fungible_token = FungibleTokenClient::new(&contract_account_id);
 
     TestContext {
         runtime,
@@ -160,6 +165,7 @@ pub fn create_context() -> TestContext {
         account_management,
         operator,
         financials,
+        fungible_token,
     }
 }
 